@@ -26,6 +26,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         connections: 10,
         priority: 0,
         pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
     };
 
     // Build configuration
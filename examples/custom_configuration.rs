@@ -16,10 +16,11 @@ use usenet_dl::api::start_api_server;
 use usenet_dl::config::{
     ApiConfig, AutomationConfig, CleanupConfig, Config, DeobfuscationConfig, DiskSpaceConfig,
     DownloadConfig, DuplicateAction, DuplicateConfig, DuplicateMethod, ExtractionConfig,
-    FileCollisionAction, NotificationConfig, PersistenceConfig, PostProcess, ProcessingConfig,
-    RetryConfig, RssFeedConfig, ScheduleAction, ScheduleRule, ScriptConfig, ScriptEvent,
-    ServerConfig, ServerIntegrationConfig, ToolsConfig, WatchFolderAction, WatchFolderConfig,
-    WebhookConfig, WebhookEvent, Weekday,
+    FileCollisionAction, JunkFilterConfig, NotificationConfig, PermissionsConfig,
+    PersistenceConfig, PostProcess, ProcessingConfig, RetryConfig, RssFeedAuth, RssFeedConfig,
+    ScheduleAction, ScheduleRule, SchedulerConfig, ScriptConfig, ScriptEvent, ServerConfig,
+    ServerIntegrationConfig, SidecarFormat, SubtitleConfig, ToolsConfig, WatchFolderAction,
+    WatchFolderConfig, WatchMode, WebhookConfig, WebhookEvent, Weekday,
 };
 use usenet_dl::{Priority, UsenetDownloader};
 
@@ -39,6 +40,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         connections: 20,
         priority: 0, // Try first
         pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
     };
 
     let backup_server = ServerConfig {
@@ -50,6 +55,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         connections: 10,
         priority: 1, // Try if primary fails
         pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
     };
 
     // Retry configuration with exponential backoff
@@ -71,6 +80,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "tar".to_string(),
             "gz".to_string(),
         ],
+        prefer_cli: false,           // unrar/7z binaries are only a fallback
+        flatten_single_folder: true, // Release.Name/episode.mkv lands as episode.mkv
     };
 
     // Disk space checking
@@ -91,6 +102,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let deobfuscation_config = DeobfuscationConfig {
         enabled: true,
         min_length: 12,
+        ..Default::default()
     };
 
     // Watch folder for movies
@@ -98,7 +110,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         path: PathBuf::from("/path/to/nzb/movies"),
         after_import: WatchFolderAction::MoveToProcessed,
         category: Some("movies".to_string()),
+        mode: WatchMode::Auto,
         scan_interval: Duration::from_secs(5),
+        processed_dir: None,
+        error_dir: None,
     };
 
     // RSS feed for TV shows
@@ -110,6 +125,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         auto_download: true,
         priority: Priority::Normal,
         enabled: true,
+        auth: RssFeedAuth {
+            api_key: Some("your-indexer-api-key".to_string()),
+            ..Default::default()
+        },
+        preference: 0,
     };
 
     // Schedule: unlimited speed at night
@@ -120,6 +140,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         end_time: "06:00".to_string(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        start_date: None,
+        end_date: None,
     };
 
     // Schedule: limited during work hours
@@ -138,6 +160,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             limit_bps: 1_000_000, // 1 MB/s
         },
         enabled: true,
+        start_date: None,
+        end_date: None,
     };
 
     // Webhook notification
@@ -146,6 +170,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         events: vec![WebhookEvent::OnComplete, WebhookEvent::OnFailed],
         auth_header: Some("Bearer secret-token".to_string()),
         timeout: Duration::from_secs(30),
+        digest_interval: None,
     };
 
     // Post-processing script
@@ -183,6 +208,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         notifications: NotificationConfig {
             webhooks: vec![webhook],
             scripts: vec![script],
+            mqtt: None,
         },
 
         // Persistence (database and schedules)
@@ -200,6 +226,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             disk_space: disk_space_config,
             cleanup: CleanupConfig::default(),
             direct_unpack: Default::default(),
+            // Make extracted files readable by a media server running as another user
+            permissions: PermissionsConfig {
+                file_mode: Some(0o644),
+                dir_mode: Some(0o755),
+                ..Default::default()
+            },
+            path_sanitization: Default::default(),
+            keep_intermediate_on_failure: true,
+            // Leave a JSON record of each job next to its files for library tools
+            completion_sidecar: SidecarFormat::Json,
+            // One extraction at a time, so the disk isn't thrashed by a burst of completions
+            max_parallel_jobs: 1,
+            scan: Default::default(),
+            // Drop shortcut/ad files and bundled executables before they reach the library
+            junk_filter: JunkFilterConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            // Put Subs/2_eng.srt beside the movie as Movie.eng.srt
+            subtitles: SubtitleConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            // Verify downloads that carry PAR2 files (categories can opt out)
+            verify_policy: Default::default(),
         },
 
         // Automation (watch folders, RSS, deobfuscation)
@@ -207,6 +258,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             watch_folders: vec![movies_watch],
             rss_feeds: vec![tv_rss],
             deobfuscation: deobfuscation_config,
+            rss_dedup: Default::default(),
         },
 
         // Server integration (API)
@@ -218,6 +270,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 ..Default::default()
             },
         },
+
+        // Diagnostics (NNTP command tracing is off by default)
+        logging: Default::default(),
+
+        // Evaluate the schedule rules above in this time zone, not the host's
+        scheduler: SchedulerConfig {
+            timezone: Some("Europe/Amsterdam".to_string()),
+        },
+
+        // Standby mode (off: this instance runs the queue)
+        read_only: false,
     };
 
     println!("Configuration:");
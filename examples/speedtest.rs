@@ -52,6 +52,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             connections,
             priority: 0,
             pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
         }],
         persistence: PersistenceConfig {
             database_path: temp_dir.path().join("test.db"),
@@ -285,7 +289,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Ok(other) => {
                     println!("  [EVENT] {other:?}");
                 }
-                Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                Err(usenet_dl::event_bus::RecvError::Lagged(n)) => {
                     eprintln!("  [WARNING] Event receiver lagged, missed {n} events!");
                 }
                 Err(_) => break,
@@ -6,6 +6,7 @@
 use crate::{Config, Result, UsenetDownloader};
 use axum::{
     Router,
+    extract::DefaultBodyLimit,
     http::HeaderValue,
     middleware,
     routing::{delete, get, patch, post, put},
@@ -34,33 +35,59 @@ pub use state::AppState;
 /// ## Queue Management
 /// - `GET /downloads` - List all downloads
 /// - `GET /downloads/:id` - Get single download
+/// - `GET /downloads/:id/artifacts` - List files left in the temp folder
 /// - `POST /downloads` - Add NZB from file upload
 /// - `POST /downloads/url` - Add NZB from URL
+/// - `POST /downloads/urls` - Add NZBs from a list of URLs
+/// - `POST /downloads/validate` - Preview adding an NZB without queueing it
 /// - `POST /downloads/:id/pause` - Pause download
 /// - `POST /downloads/:id/resume` - Resume download
+/// - `POST /downloads/:id/duplicate/accept` - Queue a held duplicate
+/// - `POST /downloads/:id/duplicate/discard` - Remove a held duplicate
+/// - `POST /downloads/:id/password` - Retry extraction with a submitted password
 /// - `DELETE /downloads/:id` - Cancel/remove download
 /// - `PATCH /downloads/:id/priority` - Set priority
-/// - `POST /downloads/:id/reprocess` - Re-run post-processing
+/// - `PUT /downloads/:id/speed-limit` - Set per-download speed limit
+/// - `PUT /downloads/:id/name` - Rename download
+/// - `POST /downloads/:id/reprocess` - Re-run post-processing (optionally `?stage=`)
 /// - `POST /downloads/:id/reextract` - Re-run extraction only
 ///
 /// ## Queue-Wide Operations
 /// - `POST /queue/pause` - Pause all downloads
 /// - `POST /queue/resume` - Resume all downloads
 /// - `GET /queue/stats` - Get queue statistics
+/// - `GET /queue/export` - Download the pending queue as an archive
+/// - `POST /queue/import` - Add the downloads from a queue archive
+///
+/// ## Batches
+/// - `POST /batches` - Create a batch (add downloads with `options.batch_id`)
+/// - `GET /batches/:id` - Get batch with aggregated progress
 ///
 /// ## History
 /// - `GET /history` - Get download history (with pagination)
 /// - `DELETE /history` - Clear history
+/// - `POST /history/:id/retry` - Re-queue a history entry's download
 ///
 /// ## Server Management
+/// - `GET /servers` - List configured servers
+/// - `POST /servers` - Add a server
+/// - `PUT /servers/:id` - Update a server
+/// - `DELETE /servers/:id` - Remove a server
+/// - `GET /servers/status` - Whether each server is in rotation
+/// - `POST /servers/:id/disable` - Take a server out of rotation, optionally for a while
+/// - `POST /servers/:id/enable` - Put a disabled server back into rotation
+/// - `POST /servers/:id/reconnect` - Close a server's connections and open fresh ones
 /// - `POST /servers/test` - Test server connection
 /// - `GET /servers/test` - Test all configured servers
+/// - `GET /servers/:id/trace` - Recent NNTP commands for a server (when tracing is enabled)
+/// - `PUT /servers/:id/speed-limit` - Set per-server speed limit
+/// - `POST /servers/:id/speedtest` - Measure throughput per connection count
 ///
 /// ## Configuration
 /// - `GET /config` - Get current config (sensitive fields redacted)
 /// - `PATCH /config` - Update config
 /// - `GET /config/speed-limit` - Get speed limit
-/// - `PUT /config/speed-limit` - Set speed limit
+/// - `PUT /config/speed-limit` - Set global speed limit (and burst size)
 ///
 /// ## Categories
 /// - `GET /categories` - List categories
@@ -68,11 +95,18 @@ pub use state::AppState;
 /// - `DELETE /categories/:name` - Delete category
 ///
 /// ## System
-/// - `GET /capabilities` - Query system capabilities
+/// - `GET /system/capabilities` - Query system capabilities (also at `/capabilities`)
 /// - `GET /health` - Health check
+/// - `GET /health/live` - Liveness probe
+/// - `GET /health/ready` - Readiness probe (database, servers, temp dir)
 /// - `GET /openapi.json` - OpenAPI specification
 /// - `GET /swagger-ui` - Interactive Swagger UI documentation (if enabled)
 /// - `GET /events` - Server-sent events stream
+/// - `GET /system/log/stream` - Server-sent stream of log events (`?level=`)
+/// - `POST /system/drain` - Stop accepting work and drain active jobs
+/// - `DELETE /system/drain` - Leave maintenance mode
+/// - `GET /system/backup` - Download a backup archive
+/// - `POST /system/backup` - Restore a backup archive
 /// - `POST /shutdown` - Graceful shutdown
 ///
 /// ## RSS Feeds
@@ -87,6 +121,9 @@ pub use state::AppState;
 /// - `POST /scheduler` - Add schedule rule
 /// - `PUT /scheduler/:id` - Update schedule rule
 /// - `DELETE /scheduler/:id` - Delete schedule rule
+///
+/// ## Statistics
+/// - `GET /stats` - Usage per server, category and day (`?period=day|week|month`)
 pub fn create_router(downloader: Arc<UsenetDownloader>, config: Arc<Config>) -> Router {
     let state = AppState::new(downloader, config.clone());
 
@@ -97,40 +134,100 @@ pub fn create_router(downloader: Arc<UsenetDownloader>, config: Arc<Config>) ->
         .route("/downloads", post(routes::add_download))
         .route("/downloads/:id", get(routes::get_download))
         .route("/downloads/:id", delete(routes::delete_download))
+        .route(
+            "/downloads/:id/artifacts",
+            get(routes::get_download_artifacts),
+        )
         .route("/downloads/:id/pause", post(routes::pause_download))
         .route("/downloads/:id/resume", post(routes::resume_download))
+        .route(
+            "/downloads/:id/duplicate/accept",
+            post(routes::accept_duplicate),
+        )
+        .route(
+            "/downloads/:id/duplicate/discard",
+            post(routes::discard_duplicate),
+        )
+        .route("/downloads/:id/password", post(routes::submit_password))
         .route(
             "/downloads/:id/priority",
             patch(routes::set_download_priority),
         )
+        .route(
+            "/downloads/:id/speed-limit",
+            put(routes::set_download_speed_limit),
+        )
+        .route("/downloads/:id/name", put(routes::rename_download))
         .route("/downloads/:id/reprocess", post(routes::reprocess_download))
         .route("/downloads/:id/reextract", post(routes::reextract_download))
+        .route(
+            "/downloads/:id/check",
+            post(routes::check_download_completion),
+        )
         // URL-based NZB adding
         .route("/downloads/url", post(routes::add_download_url))
+        .route("/downloads/urls", post(routes::add_download_urls))
+        .route("/downloads/validate", post(routes::validate_download))
         // Queue-Wide Operations
         .route("/queue/pause", post(routes::pause_queue))
         .route("/queue/resume", post(routes::resume_queue))
         .route("/queue/stats", get(routes::queue_stats))
+        .route("/queue/export", get(routes::export_queue))
+        .route(
+            "/queue/import",
+            post(routes::import_queue).layer(DefaultBodyLimit::disable()),
+        )
+        // Batches
+        .route("/batches", post(routes::create_batch))
+        .route("/batches/:id", get(routes::get_batch))
         // History
         .route("/history", get(routes::get_history))
         .route("/history", delete(routes::clear_history))
+        .route("/history/:id/retry", post(routes::retry_history))
         // Server Management
+        .route("/servers", get(routes::list_servers))
+        .route("/servers", post(routes::add_server))
+        .route("/servers/:id", put(routes::update_server))
+        .route("/servers/:id", delete(routes::delete_server))
+        .route("/servers/status", get(routes::list_server_statuses))
+        .route("/servers/:id/disable", post(routes::disable_server))
+        .route("/servers/:id/enable", post(routes::enable_server))
+        .route("/servers/:id/reconnect", post(routes::reconnect_server))
         .route("/servers/test", post(routes::test_server))
         .route("/servers/test", get(routes::test_all_servers))
+        .route("/servers/:id/trace", get(routes::get_server_trace))
+        .route(
+            "/servers/:id/speed-limit",
+            put(routes::set_server_speed_limit),
+        )
+        .route("/servers/:id/speedtest", post(routes::speed_test_server))
         // Configuration
         .route("/config", get(routes::get_config))
         .route("/config", patch(routes::update_config))
         .route("/config/speed-limit", get(routes::get_speed_limit))
         .route("/config/speed-limit", put(routes::set_speed_limit))
+        .route("/config/bandwidth-mode", get(routes::get_bandwidth_mode))
+        .route("/config/bandwidth-mode", put(routes::set_bandwidth_mode))
         // Categories
         .route("/categories", get(routes::list_categories))
         .route("/categories/:name", put(routes::create_or_update_category))
         .route("/categories/:name", delete(routes::delete_category))
         // System
         .route("/capabilities", get(routes::get_capabilities))
+        .route("/system/capabilities", get(routes::get_capabilities))
         .route("/health", get(routes::health_check))
+        .route("/health/live", get(routes::health_live))
+        .route("/health/ready", get(routes::health_ready))
         .route("/openapi.json", get(routes::openapi_spec))
         .route("/events", get(routes::event_stream))
+        .route("/system/log/stream", get(routes::log_stream))
+        .route("/system/drain", post(routes::start_drain))
+        .route("/system/drain", delete(routes::cancel_drain))
+        .route("/system/backup", get(routes::export_backup))
+        .route(
+            "/system/backup",
+            post(routes::import_backup).layer(DefaultBodyLimit::disable()),
+        )
         .route("/shutdown", post(routes::shutdown))
         // RSS Feeds
         .route("/rss", get(routes::list_rss_feeds))
@@ -142,7 +239,9 @@ pub fn create_router(downloader: Arc<UsenetDownloader>, config: Arc<Config>) ->
         .route("/scheduler", get(routes::list_schedule_rules))
         .route("/scheduler", post(routes::add_schedule_rule))
         .route("/scheduler/:id", put(routes::update_schedule_rule))
-        .route("/scheduler/:id", delete(routes::delete_schedule_rule));
+        .route("/scheduler/:id", delete(routes::delete_schedule_rule))
+        // Statistics
+        .route("/stats", get(routes::get_stats));
 
     // Merge Swagger UI routes if enabled in config (before applying state)
     // Note: SwaggerUi will use the existing /openapi.json endpoint we already defined
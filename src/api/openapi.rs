@@ -34,33 +34,60 @@ use utoipa::OpenApi;
         // Queue Management - Downloads
         crate::api::routes::list_downloads,
         crate::api::routes::get_download,
+        crate::api::routes::get_download_artifacts,
         crate::api::routes::add_download,
         crate::api::routes::add_download_url,
+        crate::api::routes::add_download_urls,
+        crate::api::routes::validate_download,
         crate::api::routes::pause_download,
         crate::api::routes::resume_download,
+        crate::api::routes::accept_duplicate,
+        crate::api::routes::discard_duplicate,
+        crate::api::routes::submit_password,
         crate::api::routes::delete_download,
         crate::api::routes::set_download_priority,
+        crate::api::routes::set_download_speed_limit,
+        crate::api::routes::rename_download,
         crate::api::routes::reprocess_download,
         crate::api::routes::reextract_download,
+        crate::api::routes::check_download_completion,
 
         // Queue-Wide Operations
         crate::api::routes::pause_queue,
         crate::api::routes::resume_queue,
         crate::api::routes::queue_stats,
+        crate::api::routes::export_queue,
+        crate::api::routes::import_queue,
 
         // History
+        crate::api::routes::create_batch,
+        crate::api::routes::get_batch,
         crate::api::routes::get_history,
         crate::api::routes::clear_history,
+        crate::api::routes::retry_history,
 
         // Server Management
+        crate::api::routes::list_servers,
+        crate::api::routes::add_server,
+        crate::api::routes::update_server,
+        crate::api::routes::delete_server,
+        crate::api::routes::list_server_statuses,
+        crate::api::routes::disable_server,
+        crate::api::routes::enable_server,
+        crate::api::routes::reconnect_server,
         crate::api::routes::test_server,
         crate::api::routes::test_all_servers,
+        crate::api::routes::get_server_trace,
+        crate::api::routes::set_server_speed_limit,
+        crate::api::routes::speed_test_server,
 
         // Configuration
         crate::api::routes::get_config,
         crate::api::routes::update_config,
         crate::api::routes::get_speed_limit,
         crate::api::routes::set_speed_limit,
+        crate::api::routes::get_bandwidth_mode,
+        crate::api::routes::set_bandwidth_mode,
 
         // Categories
         crate::api::routes::list_categories,
@@ -70,8 +97,15 @@ use utoipa::OpenApi;
         // System
         crate::api::routes::get_capabilities,
         crate::api::routes::health_check,
+        crate::api::routes::health_live,
+        crate::api::routes::health_ready,
         crate::api::routes::openapi_spec,
         crate::api::routes::event_stream,
+        crate::api::routes::start_drain,
+        crate::api::routes::cancel_drain,
+        crate::api::routes::log_stream,
+        crate::api::routes::export_backup,
+        crate::api::routes::import_backup,
         crate::api::routes::shutdown,
 
         // RSS Feeds
@@ -86,20 +120,48 @@ use utoipa::OpenApi;
         crate::api::routes::add_schedule_rule,
         crate::api::routes::update_schedule_rule,
         crate::api::routes::delete_schedule_rule,
+
+        // Statistics
+        crate::api::routes::get_stats,
     ),
     components(schemas(
         // Core types from types.rs
         crate::types::Status,
         crate::types::Priority,
+        crate::types::PauseScope,
+        crate::types::BandwidthMode,
         crate::types::Stage,
         crate::types::ArchiveType,
         crate::types::DownloadInfo,
+        crate::types::DuplicateInfo,
+        crate::types::DownloadArtifacts,
+        crate::types::DownloadArtifact,
+        crate::types::NzbValidation,
+        crate::types::NzbFilePreview,
         crate::types::DownloadOptions,
         crate::types::HistoryEntry,
+        crate::types::HistoryPage,
         crate::types::QueueStats,
+        crate::types::BatchInfo,
         crate::types::Capabilities,
         crate::types::ParityCapabilitiesInfo,
         crate::types::ServerCapabilities,
+        crate::types::ServerStatus,
+        crate::types::NntpTraceEntry,
+        crate::types::LogEntry,
+        crate::types::SpeedTestOptions,
+        crate::types::SpeedTestRun,
+        crate::types::SpeedTestResult,
+        crate::types::StatsPeriod,
+        crate::types::UsageStats,
+        crate::types::ServerUsage,
+        crate::types::CategoryUsage,
+        crate::types::DailyUsage,
+        crate::types::CompletionCheck,
+        crate::types::ServerCompletion,
+        crate::types::HealthCheck,
+        crate::types::HealthReport,
+        crate::types::EventEnvelope,
 
         // Config types from config.rs
         crate::config::Config,
@@ -107,6 +169,12 @@ use utoipa::OpenApi;
         crate::config::ServerConfig,
         crate::config::RetryConfig,
         crate::config::PostProcess,
+        crate::config::MoveChecksum,
+        crate::config::RejectAction,
+        crate::config::ServerRouting,
+        crate::config::ScanAction,
+        crate::config::SidecarFormat,
+        crate::config::VerifyPolicy,
         crate::config::ExtractionConfig,
         crate::config::FileCollisionAction,
         crate::config::DeobfuscationConfig,
@@ -114,27 +182,41 @@ use utoipa::OpenApi;
         crate::config::DuplicateAction,
         crate::config::DuplicateMethod,
         crate::config::DiskSpaceConfig,
+        crate::config::CompletionCheckConfig,
         crate::config::CleanupConfig,
+        crate::config::ProcessPriorityConfig,
+        crate::config::IoClass,
         crate::config::ApiConfig,
         crate::config::RateLimitConfig,
+        crate::config::HealthConfig,
+        crate::config::LogLevel,
         crate::config::ScheduleRule,
         crate::config::ScheduleAction,
         crate::config::Weekday,
         crate::config::WatchFolderConfig,
         crate::config::WatchFolderAction,
+        crate::config::WatchMode,
         crate::config::WebhookConfig,
         crate::config::WebhookEvent,
         crate::config::ScriptConfig,
         crate::config::ScriptEvent,
+        crate::config::MqttConfig,
+        crate::config::MqttEvent,
         crate::config::CategoryConfig,
         crate::config::RssFeedConfig,
+        crate::config::RssFeedAuth,
         crate::config::RssFilter,
+        crate::config::RssFilterAction,
 
         // API request/response types from routes.rs
         crate::api::routes::AddRssFeedRequest,
         crate::api::routes::RssFeedResponse,
         crate::api::routes::CheckRssFeedResponse,
         crate::api::routes::ScheduleRuleResponse,
+        crate::api::routes::CreateBatchRequest,
+        crate::api::routes::RenameDownloadRequest,
+        crate::api::routes::SubmitPasswordRequest,
+        crate::api::routes::SetBandwidthModeRequest,
 
         // Error types from error.rs
         crate::error::ApiError,
@@ -142,14 +224,16 @@ use utoipa::OpenApi;
     )),
     tags(
         (name = "downloads", description = "Download queue management - Add, pause, resume, and monitor downloads"),
-        (name = "queue", description = "Queue-wide operations - Pause/resume all downloads, get statistics"),
+        (name = "queue", description = "Queue-wide operations - Pause/resume all downloads, get statistics, export/import the queue"),
+        (name = "batches", description = "Batches - Group downloads and track their combined progress"),
         (name = "history", description = "Download history - View completed and failed downloads"),
-        (name = "servers", description = "Server management - Test NNTP server connections and configuration"),
+        (name = "servers", description = "Server management - Add, update, remove, disable and test NNTP servers"),
         (name = "config", description = "Configuration - Get and update runtime configuration settings"),
         (name = "categories", description = "Categories - Manage download categories and their settings"),
-        (name = "system", description = "System endpoints - Health checks, OpenAPI spec, events, shutdown"),
+        (name = "system", description = "System endpoints - Health checks, OpenAPI spec, events, backups, shutdown"),
         (name = "rss", description = "RSS feeds - Manage RSS feed subscriptions and automatic downloads"),
         (name = "scheduler", description = "Scheduler - Time-based rules for speed limits and pause/resume"),
+        (name = "stats", description = "Statistics - Bytes downloaded per server, category and day"),
     ),
     modifiers(&SecurityAddon)
 )]
@@ -0,0 +1,74 @@
+//! Batch (download group) handlers.
+
+use super::CreateBatchRequest;
+use crate::api::AppState;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+/// POST /batches - Create a batch
+#[utoipa::path(
+    post,
+    path = "/api/v1/batches",
+    tag = "batches",
+    request_body = CreateBatchRequest,
+    responses(
+        (status = 201, description = "Batch created", body = i64),
+        (status = 400, description = "Invalid batch name"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_batch(
+    State(state): State<AppState>,
+    Json(request): Json<CreateBatchRequest>,
+) -> Response {
+    if request.name.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": {"code": "invalid_input", "message": "Batch name cannot be empty"}})),
+        )
+            .into_response();
+    }
+
+    match state.downloader.create_batch(&request.name).await {
+        Ok(id) => (StatusCode::CREATED, Json(json!({"id": id}))).into_response(),
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to create batch: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "database_error", "message": format!("Failed to create batch: {}", e)}}))).into_response()
+        }
+    }
+}
+
+/// GET /batches/:id - Get batch with aggregated progress
+#[utoipa::path(
+    get,
+    path = "/api/v1/batches/{id}",
+    tag = "batches",
+    params(
+        ("id" = i64, Path, description = "Batch ID")
+    ),
+    responses(
+        (status = 200, description = "Batch information", body = crate::types::BatchInfo),
+        (status = 404, description = "Batch not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_batch(State(state): State<AppState>, Path(id): Path<i64>) -> Response {
+    match state.downloader.get_batch(id).await {
+        Ok(Some(batch)) => (StatusCode::OK, Json(batch)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": {"code": "not_found", "message": format!("Batch {} not found", id)}})),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to get batch {}: {}", id, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "database_error", "message": format!("Failed to get batch: {}", e)}}))).into_response()
+        }
+    }
+}
@@ -1,8 +1,14 @@
 //! Configuration handlers.
 
-use super::SetSpeedLimitRequest;
+use super::{SetBandwidthModeRequest, SetSpeedLimitRequest};
 use crate::api::AppState;
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use crate::config::{Config, REDACTED};
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
 use serde_json::json;
 
 /// GET /config - Get current config (sensitive fields redacted)
@@ -11,57 +17,80 @@ use serde_json::json;
     path = "/api/v1/config",
     tag = "config",
     responses(
-        (status = 200, description = "Current configuration", body = crate::config::Config),
+        (status = 200, description = "Configuration in effect, including runtime changes", body = crate::config::Config),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn get_config(State(state): State<AppState>) -> impl IntoResponse {
-    let config = state.downloader.get_config();
+    let config = state.downloader.effective_config().await;
+    (StatusCode::OK, Json(redact_config(config)))
+}
 
-    let mut redacted_config = (*config).clone();
+/// PATCH /config - Update config
+#[utoipa::path(
+    patch,
+    path = "/api/v1/config",
+    tag = "config",
+    request_body(content = Object, description = "Partial configuration merged into the current one; only runtime-changeable settings may differ"),
+    responses(
+        (status = 200, description = "Configuration in effect after the update", body = crate::config::Config),
+        (status = 400, description = "Invalid or restart-only setting; `error.details.path` names the field"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn update_config(
+    State(state): State<AppState>,
+    Json(patch): Json<serde_json::Value>,
+) -> Response {
+    match state.downloader.patch_config(&patch).await {
+        Ok(config) => (StatusCode::OK, Json(redact_config(config))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
 
+/// Replace secrets with a placeholder before returning a configuration
+fn redact_config(mut config: Config) -> Config {
     // Redact server passwords
-    for server in &mut redacted_config.servers {
+    for server in &mut config.servers {
         if server.password.is_some() {
-            server.password = Some("***REDACTED***".to_string());
+            server.password = Some(REDACTED.to_string());
         }
     }
 
     // Redact API key
-    if redacted_config.server.api.api_key.is_some() {
-        redacted_config.server.api.api_key = Some("***REDACTED***".to_string());
+    if config.server.api.api_key.is_some() {
+        config.server.api.api_key = Some(REDACTED.to_string());
     }
 
     // Redact webhook auth headers
-    for webhook in &mut redacted_config.notifications.webhooks {
+    for webhook in &mut config.notifications.webhooks {
         if webhook.auth_header.is_some() {
-            webhook.auth_header = Some("***REDACTED***".to_string());
+            webhook.auth_header = Some(REDACTED.to_string());
         }
     }
 
-    (StatusCode::OK, Json(redacted_config))
-}
+    // Redact MQTT broker password
+    if let Some(mqtt) = &mut config.notifications.mqtt
+        && mqtt.password.is_some()
+    {
+        mqtt.password = Some(REDACTED.to_string());
+    }
 
-/// PATCH /config - Update config
-#[utoipa::path(
-    patch,
-    path = "/api/v1/config",
-    tag = "config",
-    request_body(content = crate::config::ConfigUpdate, description = "Configuration updates (runtime-changeable fields only)"),
-    responses(
-        (status = 200, description = "Configuration updated", body = crate::config::Config),
-        (status = 400, description = "Invalid configuration"),
-        (status = 500, description = "Internal server error")
-    )
-)]
-pub async fn update_config(
-    State(state): State<AppState>,
-    Json(updates): Json<crate::config::ConfigUpdate>,
-) -> impl IntoResponse {
-    state.downloader.update_config(updates).await;
+    // Redact RSS feed credentials (header and cookie names are kept)
+    for feed in &mut config.automation.rss_feeds {
+        let auth = &mut feed.auth;
+        for secret in [&mut auth.password, &mut auth.api_key]
+            .into_iter()
+            .flatten()
+        {
+            *secret = REDACTED.to_string();
+        }
+        for value in auth.headers.values_mut().chain(auth.cookies.values_mut()) {
+            *value = REDACTED.to_string();
+        }
+    }
 
-    // Return the updated config (with redaction)
-    get_config(State(state)).await
+    config
 }
 
 /// GET /config/speed-limit - Get speed limit
@@ -70,13 +99,14 @@ pub async fn update_config(
     path = "/api/v1/config/speed-limit",
     tag = "config",
     responses(
-        (status = 200, description = "Current speed limit in bytes per second", body = inline(Object)),
+        (status = 200, description = "Current speed limit and burst size in bytes", body = inline(Object)),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn get_speed_limit(State(state): State<AppState>) -> impl IntoResponse {
     let limit_bps = state.downloader.get_speed_limit();
-    Json(json!({"limit_bps": limit_bps}))
+    let burst_bytes = state.downloader.get_speed_limit_burst();
+    Json(json!({"limit_bps": limit_bps, "burst_bytes": burst_bytes}))
 }
 
 /// PUT /config/speed-limit - Set speed limit
@@ -95,6 +125,45 @@ pub async fn set_speed_limit(
     State(state): State<AppState>,
     Json(request): Json<SetSpeedLimitRequest>,
 ) -> impl IntoResponse {
+    if let Some(burst_bytes) = request.burst_bytes {
+        state
+            .downloader
+            .set_speed_limit_burst((burst_bytes > 0).then_some(burst_bytes));
+    }
     state.downloader.set_speed_limit(request.limit_bps).await;
     StatusCode::NO_CONTENT
 }
+
+/// GET /config/bandwidth-mode - Get bandwidth mode
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/bandwidth-mode",
+    tag = "config",
+    responses(
+        (status = 200, description = "Current bandwidth mode", body = inline(Object)),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_bandwidth_mode(State(state): State<AppState>) -> impl IntoResponse {
+    Json(json!({"mode": state.downloader.get_bandwidth_mode()}))
+}
+
+/// PUT /config/bandwidth-mode - Set bandwidth mode
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/bandwidth-mode",
+    tag = "config",
+    request_body = SetBandwidthModeRequest,
+    responses(
+        (status = 204, description = "Bandwidth mode updated successfully"),
+        (status = 400, description = "Invalid mode"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn set_bandwidth_mode(
+    State(state): State<AppState>,
+    Json(request): Json<SetBandwidthModeRequest>,
+) -> impl IntoResponse {
+    state.downloader.set_bandwidth_mode(request.mode).await;
+    StatusCode::NO_CONTENT
+}
@@ -1,6 +1,9 @@
 //! Download management handlers.
 
-use super::DeleteDownloadQuery;
+use super::{
+    CompletionCheckQuery, DeleteDownloadQuery, PauseQuery, RenameDownloadRequest, ReprocessQuery,
+    SetSpeedLimitRequest, SubmitPasswordRequest,
+};
 use crate::api::AppState;
 use axum::{
     Json,
@@ -21,44 +24,8 @@ use serde_json::json;
     )
 )]
 pub async fn list_downloads(State(state): State<AppState>) -> impl IntoResponse {
-    match state.downloader.db.list_downloads().await {
-        Ok(downloads) => {
-            let download_infos: Vec<crate::types::DownloadInfo> = downloads
-                .into_iter()
-                .map(|d| {
-                    let eta_seconds = if d.speed_bps > 0 && d.status == 1 {
-                        let remaining = d.size_bytes.saturating_sub(d.downloaded_bytes);
-                        if remaining > 0 {
-                            Some((remaining as u64) / (d.speed_bps as u64))
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    };
-
-                    crate::types::DownloadInfo {
-                        id: crate::types::DownloadId(d.id),
-                        name: d.name,
-                        category: d.category,
-                        status: crate::types::Status::from_i32(d.status),
-                        progress: d.progress,
-                        speed_bps: d.speed_bps as u64,
-                        size_bytes: d.size_bytes as u64,
-                        downloaded_bytes: d.downloaded_bytes as u64,
-                        eta_seconds,
-                        priority: crate::types::Priority::from_i32(d.priority),
-                        created_at: chrono::DateTime::from_timestamp(d.created_at, 0)
-                            .unwrap_or_else(chrono::Utc::now),
-                        started_at: d
-                            .started_at
-                            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0)),
-                    }
-                })
-                .collect();
-
-            (StatusCode::OK, Json(download_infos))
-        }
+    match state.downloader.list_downloads().await {
+        Ok(downloads) => (StatusCode::OK, Json(downloads)),
         Err(e) => {
             tracing::error!("Failed to list downloads: {}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]))
@@ -83,42 +50,10 @@ pub async fn list_downloads(State(state): State<AppState>) -> impl IntoResponse
 pub async fn get_download(State(state): State<AppState>, Path(id): Path<i64>) -> Response {
     match state
         .downloader
-        .db
         .get_download(crate::types::DownloadId(id))
         .await
     {
-        Ok(Some(d)) => {
-            let eta_seconds = if d.speed_bps > 0 && d.status == 1 {
-                let remaining = d.size_bytes.saturating_sub(d.downloaded_bytes);
-                if remaining > 0 {
-                    Some((remaining as u64) / (d.speed_bps as u64))
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-
-            let download_info = crate::types::DownloadInfo {
-                id: crate::types::DownloadId(d.id),
-                name: d.name,
-                category: d.category,
-                status: crate::types::Status::from_i32(d.status),
-                progress: d.progress,
-                speed_bps: d.speed_bps as u64,
-                size_bytes: d.size_bytes as u64,
-                downloaded_bytes: d.downloaded_bytes as u64,
-                eta_seconds,
-                priority: crate::types::Priority::from_i32(d.priority),
-                created_at: chrono::DateTime::from_timestamp(d.created_at, 0)
-                    .unwrap_or_else(chrono::Utc::now),
-                started_at: d
-                    .started_at
-                    .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0)),
-            };
-
-            (StatusCode::OK, Json(download_info)).into_response()
-        }
+        Ok(Some(download_info)) => (StatusCode::OK, Json(download_info)).into_response(),
         Ok(None) => (
             StatusCode::NOT_FOUND,
             Json(json!({"error": "download not found"})),
@@ -136,39 +71,134 @@ pub async fn get_download(State(state): State<AppState>, Path(id): Path<i64>) ->
 }
 
 /// POST /downloads - Add NZB from file upload
+///
+/// A `password` form field overrides `options.password`. Several passwords can be
+/// given separated by semicolons; the NZB's own password is tried after them.
+/// `category` and `priority` form fields likewise override the options, and a truthy
+/// `paused` field adds the downloads paused.
+///
+/// Several `file` fields may be sent at once; each NZB becomes its own download and the
+/// response lists an ID or error per file instead of a single `id`. A truthy
+/// `split_media_files` field adds an NZB of several media files (e.g. a season pack) as
+/// a download per file, listed like several uploaded files.
 #[utoipa::path(
     post,
     path = "/api/v1/downloads",
     tag = "downloads",
-    request_body(content = Vec<u8>, description = "NZB file upload (multipart/form-data)", content_type = "multipart/form-data"),
+    request_body(content = Vec<u8>, description = "NZB file upload (multipart/form-data): one or more `file` fields plus optional `options`, `category`, `priority`, `password`, `paused` and `split_media_files` fields", content_type = "multipart/form-data"),
     responses(
         (status = 201, description = "Download added successfully", body = i64),
-        (status = 400, description = "Invalid NZB file"),
+        (status = 400, description = "Invalid NZB file or form field"),
         (status = 409, description = "Duplicate download detected"),
         (status = 422, description = "Unprocessable entity"),
         (status = 500, description = "Internal server error")
     )
 )]
-pub async fn add_download(State(state): State<AppState>, mut multipart: Multipart) -> Response {
-    let mut nzb_content: Option<Vec<u8>> = None;
-    let mut nzb_filename: Option<String> = None;
+pub async fn add_download(State(state): State<AppState>, multipart: Multipart) -> Response {
+    let UploadForm {
+        files,
+        options,
+        paused,
+        split_media_files,
+    } = match read_upload_form(multipart).await {
+        Ok(form) => form,
+        Err(response) => return response,
+    };
+
+    // A single file keeps the plain `{"id": ...}` response
+    if files.len() == 1 && !split_media_files {
+        let (name, content) = &files[0];
+        let result = add_uploaded_nzb(&state, content, name, options, paused, false)
+            .await
+            .remove(0);
+        return match result {
+            Ok(download_id) => {
+                (StatusCode::CREATED, Json(json!({"id": download_id}))).into_response()
+            }
+            Err(e @ crate::Error::ReadOnly) => e.into_response(),
+            Err(e) => {
+                let (status, error) = upload_error(e);
+                (status, Json(json!({"error": error}))).into_response()
+            }
+        };
+    }
+
+    let mut ids = Vec::new();
+    let mut results = Vec::new();
+    let mut first_failure = None;
+    for (name, content) in &files {
+        let added = add_uploaded_nzb(
+            &state,
+            content,
+            name,
+            options.clone(),
+            paused,
+            split_media_files,
+        )
+        .await;
+        for result in added {
+            match result {
+                Ok(download_id) => {
+                    ids.push(download_id);
+                    results.push(json!({"file": name, "id": download_id}));
+                }
+                Err(e @ crate::Error::ReadOnly) => return e.into_response(),
+                Err(e) => {
+                    let (status, error) = upload_error(e);
+                    results.push(json!({"file": name, "error": error}));
+                    first_failure.get_or_insert((status, error));
+                }
+            }
+        }
+    }
+
+    match first_failure {
+        Some((status, error)) if ids.is_empty() => {
+            (status, Json(json!({"error": error, "results": results}))).into_response()
+        }
+        _ => (
+            StatusCode::CREATED,
+            Json(json!({"ids": ids, "results": results})),
+        )
+            .into_response(),
+    }
+}
+
+/// NZB upload form shared by `POST /downloads` and `POST /downloads/validate`
+struct UploadForm {
+    /// Uploaded files as (filename, content)
+    files: Vec<(String, Vec<u8>)>,
+    /// `options` JSON with the `password`, `category` and `priority` fields applied
+    options: crate::types::DownloadOptions,
+    /// Whether a truthy `paused` field was sent
+    paused: bool,
+    /// Whether a truthy `split_media_files` field was sent
+    split_media_files: bool,
+}
+
+/// Read an NZB upload form, or the 400 response for a malformed one
+async fn read_upload_form(mut multipart: Multipart) -> Result<UploadForm, Response> {
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
     let mut options_json: Option<String> = None;
+    let mut password: Option<String> = None;
+    let mut category: Option<String> = None;
+    let mut priority: Option<crate::types::Priority> = None;
+    let mut paused = false;
+    let mut split_media_files = false;
 
     while let Ok(Some(field)) = multipart.next_field().await {
         let name = field.name().unwrap_or("").to_string();
 
         match name.as_str() {
             "file" => {
-                if let Some(filename) = field.file_name() {
-                    nzb_filename = Some(filename.to_string());
-                }
+                let filename = field.file_name().unwrap_or("upload.nzb").to_string();
                 match field.bytes().await {
-                    Ok(bytes) => nzb_content = Some(bytes.to_vec()),
+                    Ok(bytes) => files.push((filename, bytes.to_vec())),
                     Err(e) => {
-                        return (
+                        return Err((
                             StatusCode::BAD_REQUEST,
                             Json(json!({"error": {"code": "invalid_file", "message": format!("Failed to read file: {}", e)}}))
-                        ).into_response();
+                        ).into_response());
                     }
                 }
             }
@@ -179,49 +209,226 @@ pub async fn add_download(State(state): State<AppState>, mut multipart: Multipar
                     options_json = Some(s);
                 }
             }
+            "password" => {
+                if let Ok(bytes) = field.bytes().await
+                    && let Ok(s) = String::from_utf8(bytes.to_vec())
+                    && !s.is_empty()
+                {
+                    password = Some(s);
+                }
+            }
+            "category" => {
+                if let Ok(text) = field.text().await
+                    && !text.trim().is_empty()
+                {
+                    category = Some(text.trim().to_string());
+                }
+            }
+            "priority" => {
+                let text = field.text().await.unwrap_or_default();
+                match parse_priority(text.trim()) {
+                    Some(p) => priority = Some(p),
+                    None => {
+                        return Err((
+                            StatusCode::BAD_REQUEST,
+                            Json(json!({"error": {"code": "invalid_priority", "message": format!("Invalid priority '{}': expected low, normal, high, force or -1 to 2", text.trim())}}))
+                        ).into_response());
+                    }
+                }
+            }
+            "paused" => {
+                let text = field.text().await.unwrap_or_default();
+                match parse_flag(text.trim()) {
+                    Some(flag) => paused = flag,
+                    None => {
+                        return Err((
+                            StatusCode::BAD_REQUEST,
+                            Json(json!({"error": {"code": "invalid_paused", "message": format!("Invalid paused value '{}': expected true or false", text.trim())}}))
+                        ).into_response());
+                    }
+                }
+            }
+            "split_media_files" => {
+                let text = field.text().await.unwrap_or_default();
+                match parse_flag(text.trim()) {
+                    Some(flag) => split_media_files = flag,
+                    None => {
+                        return Err((
+                            StatusCode::BAD_REQUEST,
+                            Json(json!({"error": {"code": "invalid_split_media_files", "message": format!("Invalid split_media_files value '{}': expected true or false", text.trim())}}))
+                        ).into_response());
+                    }
+                }
+            }
             _ => {}
         }
     }
 
-    let nzb_bytes = match nzb_content {
-        Some(bytes) => bytes,
-        None => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": {"code": "missing_file", "message": "No NZB file provided in 'file' field"}}))
-            ).into_response();
-        }
-    };
+    if files.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": {"code": "missing_file", "message": "No NZB file provided in 'file' field"}}))
+        ).into_response());
+    }
 
-    let options: crate::types::DownloadOptions = match options_json {
+    let mut options: crate::types::DownloadOptions = match options_json {
         Some(json_str) => match serde_json::from_str(&json_str) {
             Ok(opts) => opts,
             Err(e) => {
-                return (
+                return Err((
                         StatusCode::BAD_REQUEST,
                         Json(json!({"error": {"code": "invalid_options", "message": format!("Invalid options JSON: {}", e)}}))
-                    ).into_response();
+                    ).into_response());
             }
         },
         None => crate::types::DownloadOptions::default(),
     };
+    if password.is_some() {
+        options.password = password;
+    }
+    if category.is_some() {
+        options.category = category;
+    }
+    if let Some(priority) = priority {
+        options.priority = priority;
+    }
 
-    let name = nzb_filename.unwrap_or_else(|| "upload.nzb".to_string());
+    Ok(UploadForm {
+        files,
+        options,
+        paused,
+        split_media_files,
+    })
+}
 
-    match state.downloader.add_nzb_content(&nzb_bytes, &name, options).await {
-        Ok(download_id) => {
-            (StatusCode::CREATED, Json(json!({"id": download_id}))).into_response()
-        }
-        Err(crate::Error::Duplicate(msg)) => {
-            (StatusCode::CONFLICT, Json(json!({"error": {"code": "duplicate", "message": msg}}))).into_response()
+/// Add one uploaded NZB, split into a download per media file if requested, pausing the
+/// downloads right away if requested
+async fn add_uploaded_nzb(
+    state: &AppState,
+    content: &[u8],
+    name: &str,
+    options: crate::types::DownloadOptions,
+    paused: bool,
+    split_media_files: bool,
+) -> Vec<crate::Result<crate::types::DownloadId>> {
+    let results = if split_media_files {
+        state
+            .downloader
+            .add_nzb_content_split(content, name, options)
+            .await
+    } else {
+        vec![
+            state
+                .downloader
+                .add_nzb_content(content, name, options)
+                .await,
+        ]
+    };
+    if paused {
+        for &download_id in results.iter().flatten() {
+            if let Err(e) = state.downloader.pause(download_id).await {
+                tracing::warn!("Failed to pause uploaded download {}: {}", download_id, e);
+            }
         }
-        Err(e) => {
-            (StatusCode::UNPROCESSABLE_ENTITY, Json(json!({"error": {"code": "nzb_processing_failed", "message": format!("Failed to process NZB: {}", e)}}))).into_response()
+    }
+    results
+}
+
+/// Status and error body for an NZB upload that couldn't be added
+fn upload_error(e: crate::Error) -> (StatusCode, serde_json::Value) {
+    match e {
+        crate::Error::Duplicate(msg) => (
+            StatusCode::CONFLICT,
+            json!({"code": "duplicate", "message": msg}),
+        ),
+        e => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            json!({"code": "nzb_processing_failed", "message": format!("Failed to process NZB: {}", e)}),
+        ),
+    }
+}
+
+/// POST /downloads/validate - Preview adding an NZB without queueing it
+///
+/// Takes the same form as `POST /downloads` and reports the parsed files and size,
+/// a duplicate and what would happen to it, disk space problems and the destination
+/// and post-processing mode after applying the category. Several `file` fields give
+/// a `results` list with a report or error per file.
+#[utoipa::path(
+    post,
+    path = "/api/v1/downloads/validate",
+    tag = "downloads",
+    request_body(content = Vec<u8>, description = "NZB file upload (multipart/form-data), as for POST /api/v1/downloads", content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Validation report; nothing was queued", body = crate::types::NzbValidation),
+        (status = 400, description = "Missing file or invalid form field"),
+        (status = 422, description = "Invalid NZB file")
+    )
+)]
+pub async fn validate_download(State(state): State<AppState>, multipart: Multipart) -> Response {
+    let UploadForm { files, options, .. } = match read_upload_form(multipart).await {
+        Ok(form) => form,
+        Err(response) => return response,
+    };
+
+    if files.len() == 1 {
+        let (name, content) = &files[0];
+        return match state
+            .downloader
+            .validate_nzb_content(content, name, &options)
+            .await
+        {
+            Ok(report) => Json(report).into_response(),
+            Err(e) => {
+                let (status, error) = upload_error(e);
+                (status, Json(json!({"error": error}))).into_response()
+            }
+        };
+    }
+
+    let mut results = Vec::new();
+    for (name, content) in &files {
+        match state
+            .downloader
+            .validate_nzb_content(content, name, &options)
+            .await
+        {
+            Ok(report) => results.push(json!({"file": name, "report": report})),
+            Err(e) => {
+                let (_, error) = upload_error(e);
+                results.push(json!({"file": name, "error": error}));
+            }
         }
     }
+    Json(json!({"results": results})).into_response()
+}
+
+/// Parse a priority form field given by name (`high`) or number (`1`)
+fn parse_priority(value: &str) -> Option<crate::types::Priority> {
+    use crate::types::Priority;
+    match value.to_ascii_lowercase().as_str() {
+        "low" | "-1" => Some(Priority::Low),
+        "normal" | "0" => Some(Priority::Normal),
+        "high" | "1" => Some(Priority::High),
+        "force" | "2" => Some(Priority::Force),
+        _ => None,
+    }
+}
+
+/// Parse a boolean form field the way HTML forms and HTTP clients send them
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "on" | "yes" => Some(true),
+        "false" | "0" | "off" | "no" | "" => Some(false),
+        _ => None,
+    }
 }
 
 /// POST /downloads/url - Add NZB from URL
+///
+/// A top-level `password` overrides `options.password`, as for `POST /downloads`.
+/// With `"defer": true` the NZB is fetched only when the download starts; `name` then
+/// names the download until it is.
 #[utoipa::path(
     post,
     path = "/api/v1/downloads/url",
@@ -249,7 +456,9 @@ pub async fn add_download_url(
         }
     };
 
-    let options = if let Some(options_value) = payload.get("options") {
+    let mut options: crate::types::DownloadOptions = if let Some(options_value) =
+        payload.get("options")
+    {
         match serde_json::from_value(options_value.clone()) {
             Ok(opts) => opts,
             Err(e) => {
@@ -262,49 +471,238 @@ pub async fn add_download_url(
     } else {
         crate::types::DownloadOptions::default()
     };
+    if let Some(password) = payload.get("password").and_then(|v| v.as_str()) {
+        options.password = Some(password.to_string());
+    }
+
+    let result = if payload.get("defer").and_then(|v| v.as_bool()) == Some(true) {
+        let name = payload.get("name").and_then(|v| v.as_str());
+        state
+            .downloader
+            .add_nzb_url_deferred(url, name, options)
+            .await
+    } else {
+        state.downloader.add_nzb_url(url, options).await
+    };
 
-    match state.downloader.add_nzb_url(url, options).await {
+    match result {
         Ok(id) => (StatusCode::CREATED, Json(json!({"id": id}))).into_response(),
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
         Err(e) => {
-            let (status, code, message) = match e {
-                crate::error::Error::Duplicate(msg) => (StatusCode::CONFLICT, "duplicate", msg),
-                crate::error::Error::Io(ref e) => (
-                    StatusCode::BAD_REQUEST,
-                    "io_error",
-                    format!("I/O error: {}", e),
-                ),
-                crate::error::Error::Network(ref e) => (
+            let (status, error) = url_error(e);
+            (status, Json(json!({"error": error}))).into_response()
+        }
+    }
+}
+
+/// Status and error body for an NZB URL that couldn't be added
+fn url_error(e: crate::Error) -> (StatusCode, serde_json::Value) {
+    let (status, code, message) = match e {
+        crate::error::Error::Duplicate(msg) => (StatusCode::CONFLICT, "duplicate", msg),
+        crate::error::Error::Io(ref e) => (
+            StatusCode::BAD_REQUEST,
+            "io_error",
+            format!("I/O error: {}", e),
+        ),
+        crate::error::Error::Network(ref e) => (
+            StatusCode::BAD_REQUEST,
+            "network_error",
+            format!("Network error: {}", e),
+        ),
+        crate::error::Error::InvalidNzb(ref e) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_nzb",
+            format!("Invalid NZB: {}", e),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "add_failed",
+            format!("Failed to add NZB from URL: {}", e),
+        ),
+    };
+    (status, json!({"code": code, "message": message}))
+}
+
+/// URLs in a pasted list: one per line, skipping blank lines, `#` comments and repeats
+fn parse_url_list(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || urls.iter().any(|u| u == line) {
+            continue;
+        }
+        urls.push(line.to_string());
+    }
+    urls
+}
+
+/// POST /downloads/urls - Add an NZB from each of several URLs
+///
+/// Takes either a JSON body with `urls` (an array, or a string of newline-separated
+/// links) plus the shared `options` and `password` of `POST /downloads/url`, or a
+/// `text/plain` body of newline-separated links added with default options. Blank
+/// lines, `#` comments and repeated URLs are skipped. Each URL becomes its own download
+/// and the response lists an ID or error per URL, in the order given. `"defer": true`
+/// in a JSON body defers fetching each NZB until its download starts.
+#[utoipa::path(
+    post,
+    path = "/api/v1/downloads/urls",
+    tag = "downloads",
+    request_body(content = String, description = "JSON with `urls`, `options` and `password`, or a text/plain list of URLs, one per line"),
+    responses(
+        (status = 201, description = "At least one URL was added; `ids` and per-URL `results`"),
+        (status = 400, description = "No URLs given or invalid options"),
+        (status = 409, description = "Every URL was a duplicate"),
+        (status = 422, description = "No URL could be added")
+    )
+)]
+pub async fn add_download_urls(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    let is_text = headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/plain"));
+
+    let (urls, options, defer) = if is_text {
+        (
+            parse_url_list(&String::from_utf8_lossy(&body)),
+            crate::types::DownloadOptions::default(),
+            false,
+        )
+    } else {
+        let payload: serde_json::Value = match serde_json::from_slice(&body) {
+            Ok(payload) => payload,
+            Err(e) => {
+                return (
                     StatusCode::BAD_REQUEST,
-                    "network_error",
-                    format!("Network error: {}", e),
-                ),
-                crate::error::Error::InvalidNzb(ref e) => (
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    "invalid_nzb",
-                    format!("Invalid NZB: {}", e),
-                ),
-                _ => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "add_failed",
-                    format!("Failed to add NZB from URL: {}", e),
-                ),
-            };
+                    Json(json!({"error": {"code": "invalid_body", "message": format!("Invalid JSON body: {}", e)}}))
+                ).into_response();
+            }
+        };
 
-            (
-                status,
-                Json(json!({"error": {"code": code, "message": message}})),
-            )
-                .into_response()
+        let urls = match payload.get("urls") {
+            Some(serde_json::Value::String(text)) => parse_url_list(text),
+            Some(serde_json::Value::Array(items)) => {
+                let lines: Vec<&str> = items.iter().filter_map(|v| v.as_str()).collect();
+                parse_url_list(&lines.join("\n"))
+            }
+            _ => Vec::new(),
+        };
+
+        let mut options: crate::types::DownloadOptions = match payload.get("options") {
+            Some(options_value) => match serde_json::from_value(options_value.clone()) {
+                Ok(opts) => opts,
+                Err(e) => {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({"error": {"code": "invalid_options", "message": format!("Invalid download options: {}", e)}}))
+                    ).into_response();
+                }
+            },
+            None => crate::types::DownloadOptions::default(),
+        };
+        if let Some(password) = payload.get("password").and_then(|v| v.as_str()) {
+            options.password = Some(password.to_string());
         }
+        let defer = payload.get("defer").and_then(|v| v.as_bool()) == Some(true);
+        (urls, options, defer)
+    };
+
+    if urls.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(
+                json!({"error": {"code": "missing_urls", "message": "No URLs provided in 'urls'"}}),
+            ),
+        )
+            .into_response();
+    }
+
+    let mut ids = Vec::new();
+    let mut results = Vec::new();
+    let mut first_failure = None;
+    let added = if defer {
+        let mut added = Vec::with_capacity(urls.len());
+        for url in &urls {
+            added.push(
+                state
+                    .downloader
+                    .add_nzb_url_deferred(url, None, options.clone())
+                    .await,
+            );
+        }
+        added
+    } else {
+        state.downloader.add_nzb_urls(&urls, options).await
+    };
+    for (url, result) in urls.iter().zip(added) {
+        match result {
+            Ok(download_id) => {
+                ids.push(download_id);
+                results.push(json!({"url": url, "id": download_id}));
+            }
+            Err(e @ crate::Error::ReadOnly) => return e.into_response(),
+            Err(e) => {
+                let (status, error) = url_error(e);
+                results.push(json!({"url": url, "error": error}));
+                first_failure.get_or_insert((status, error));
+            }
+        }
+    }
+
+    match first_failure {
+        Some((status, error)) if ids.is_empty() => {
+            (status, Json(json!({"error": error, "results": results}))).into_response()
+        }
+        _ => (
+            StatusCode::CREATED,
+            Json(json!({"ids": ids, "results": results})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /downloads/:id/artifacts - List files left in the download's temp folder
+#[utoipa::path(
+    get,
+    path = "/api/v1/downloads/{id}/artifacts",
+    tag = "downloads",
+    params(("id" = i64, Path, description = "Download ID")),
+    responses(
+        (status = 200, description = "Files left on disk", body = crate::types::DownloadArtifacts),
+        (status = 404, description = "Download not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_download_artifacts(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Response {
+    match state
+        .downloader
+        .download_artifacts(crate::types::DownloadId(id))
+        .await
+    {
+        Ok(artifacts) => (StatusCode::OK, Json(artifacts)).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
 /// POST /downloads/:id/pause - Pause download
+///
+/// `scope=download` only stops downloading, so a job that's already downloaded still
+/// post-processes; `scope=post_processing` lets it keep downloading but holds it before
+/// post-processing.
 #[utoipa::path(
     post,
     path = "/api/v1/downloads/{id}/pause",
     tag = "downloads",
-    params(("id" = i64, Path, description = "Download ID")),
+    params(
+        ("id" = i64, Path, description = "Download ID"),
+        ("scope" = Option<crate::types::PauseScope>, Query, description = "What to pause: download, post_processing or all (default)")
+    ),
     responses(
         (status = 204, description = "Download paused successfully"),
         (status = 404, description = "Download not found"),
@@ -315,9 +713,15 @@ pub async fn add_download_url(
 pub async fn pause_download(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Query(params): Query<PauseQuery>,
 ) -> impl IntoResponse {
-    match state.downloader.pause(crate::types::DownloadId(id)).await {
+    match state
+        .downloader
+        .pause_scoped(crate::types::DownloadId(id), params.scope)
+        .await
+    {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
         Err(e) => {
             let error_msg = e.to_string();
             if error_msg.contains("not found") {
@@ -343,12 +747,98 @@ pub async fn pause_download(
     }
 }
 
+/// POST /downloads/:id/duplicate/accept - Queue a download held as a duplicate
+#[utoipa::path(
+    post,
+    path = "/api/v1/downloads/{id}/duplicate/accept",
+    tag = "downloads",
+    params(("id" = i64, Path, description = "Download ID")),
+    responses(
+        (status = 204, description = "Held duplicate queued"),
+        (status = 404, description = "Download not found"),
+        (status = 409, description = "Download isn't held as a duplicate"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn accept_duplicate(State(state): State<AppState>, Path(id): Path<i64>) -> Response {
+    match state
+        .downloader
+        .accept_duplicate(crate::types::DownloadId(id))
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// POST /downloads/:id/duplicate/discard - Remove a download held as a duplicate
+#[utoipa::path(
+    post,
+    path = "/api/v1/downloads/{id}/duplicate/discard",
+    tag = "downloads",
+    params(("id" = i64, Path, description = "Download ID")),
+    responses(
+        (status = 204, description = "Held duplicate removed"),
+        (status = 404, description = "Download not found"),
+        (status = 409, description = "Download isn't held as a duplicate"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn discard_duplicate(State(state): State<AppState>, Path(id): Path<i64>) -> Response {
+    match state
+        .downloader
+        .discard_duplicate(crate::types::DownloadId(id))
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// POST /downloads/:id/password - Retry extraction of a download waiting for a password
+#[utoipa::path(
+    post,
+    path = "/api/v1/downloads/{id}/password",
+    tag = "downloads",
+    params(("id" = i64, Path, description = "Download ID")),
+    request_body = SubmitPasswordRequest,
+    responses(
+        (status = 204, description = "Extraction restarted with the password"),
+        (status = 400, description = "Empty password"),
+        (status = 404, description = "Download not found"),
+        (status = 409, description = "Download isn't waiting for a password"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn submit_password(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(request): Json<SubmitPasswordRequest>,
+) -> Response {
+    match state
+        .downloader
+        .submit_password(crate::types::DownloadId(id), &request.password)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(crate::Error::Config { message, .. }) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": {"code": "validation_error", "message": message}})),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
 /// POST /downloads/:id/resume - Resume download
 #[utoipa::path(
     post,
     path = "/api/v1/downloads/{id}/resume",
     tag = "downloads",
-    params(("id" = i64, Path, description = "Download ID")),
+    params(
+        ("id" = i64, Path, description = "Download ID"),
+        ("scope" = Option<crate::types::PauseScope>, Query, description = "What to resume: download, post_processing or all (default)")
+    ),
     responses(
         (status = 204, description = "Download resumed successfully"),
         (status = 404, description = "Download not found"),
@@ -359,9 +849,15 @@ pub async fn pause_download(
 pub async fn resume_download(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Query(params): Query<PauseQuery>,
 ) -> impl IntoResponse {
-    match state.downloader.resume(crate::types::DownloadId(id)).await {
+    match state
+        .downloader
+        .resume_scoped(crate::types::DownloadId(id), params.scope)
+        .await
+    {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
         Err(e) => {
             let error_msg = e.to_string();
             if error_msg.contains("not found") {
@@ -447,6 +943,7 @@ pub async fn delete_download(
             }
             StatusCode::NO_CONTENT.into_response()
         }
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
         Err(e) => {
             if e.to_string().contains("not found") {
                 (StatusCode::NOT_FOUND, Json(json!({"error": {"code": "not_found", "message": format!("Download {} not found", id)}}))).into_response()
@@ -500,6 +997,7 @@ pub async fn set_download_priority(
         .await
     {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
         Err(e) => {
             let error_msg = e.to_string();
             if error_msg.contains("not found") {
@@ -519,29 +1017,118 @@ pub async fn set_download_priority(
     }
 }
 
-/// POST /downloads/:id/reprocess - Re-run post-processing
+/// PUT /downloads/:id/speed-limit - Set a per-download speed limit
 #[utoipa::path(
-    post,
-    path = "/api/v1/downloads/{id}/reprocess",
+    put,
+    path = "/api/v1/downloads/{id}/speed-limit",
     tag = "downloads",
     params(("id" = i64, Path, description = "Download ID")),
+    request_body = SetSpeedLimitRequest,
     responses(
-        (status = 204, description = "Reprocessing started successfully"),
+        (status = 204, description = "Download speed limit updated successfully"),
         (status = 404, description = "Download not found"),
-        (status = 400, description = "Download files not found"),
         (status = 500, description = "Internal server error")
     )
 )]
-pub async fn reprocess_download(
+pub async fn set_download_speed_limit(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    Json(request): Json<SetSpeedLimitRequest>,
 ) -> impl IntoResponse {
     match state
         .downloader
-        .reprocess(crate::types::DownloadId(id))
+        .set_download_speed_limit(crate::types::DownloadId(id), request.limit_bps)
         .await
     {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(crate::Error::Download(crate::error::DownloadError::NotFound { .. })) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": {"code": "not_found", "message": format!("Download {} not found", id)}})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": {"code": "internal_error", "message": e.to_string()}})),
+        )
+            .into_response(),
+    }
+}
+
+/// PUT /downloads/:id/name - Rename a download
+#[utoipa::path(
+    put,
+    path = "/api/v1/downloads/{id}/name",
+    tag = "downloads",
+    params(("id" = i64, Path, description = "Download ID")),
+    request_body = RenameDownloadRequest,
+    responses(
+        (status = 204, description = "Download renamed successfully"),
+        (status = 400, description = "Invalid name"),
+        (status = 404, description = "Download not found"),
+        (status = 409, description = "Download is post-processing or complete"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn rename_download(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(request): Json<RenameDownloadRequest>,
+) -> Response {
+    match state
+        .downloader
+        .rename_download(crate::types::DownloadId(id), &request.name)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(crate::Error::Config { message, .. }) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": {"code": "validation_error", "message": message}})),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// POST /downloads/:id/reprocess - Re-run post-processing
+#[utoipa::path(
+    post,
+    path = "/api/v1/downloads/{id}/reprocess",
+    tag = "downloads",
+    params(
+        ("id" = i64, Path, description = "Download ID"),
+        ("stage" = Option<String>, Query, description = "Stage to restart from: verify, repair, extract, move or cleanup (default: run the download's normal pipeline)")
+    ),
+    responses(
+        (status = 204, description = "Reprocessing started successfully"),
+        (status = 404, description = "Download or download files not found"),
+        (status = 400, description = "Stage cannot be re-run"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn reprocess_download(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(params): Query<ReprocessQuery>,
+) -> impl IntoResponse {
+    let download_id = crate::types::DownloadId(id);
+    let result = match params.stage {
+        Some(stage) => {
+            state
+                .downloader
+                .reprocess_from_stage(download_id, stage)
+                .await
+        }
+        None => state.downloader.reprocess(download_id).await,
+    };
+
+    match result {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
+        Err(crate::Error::NotSupported(msg)) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": {"code": "invalid_stage", "message": msg}})),
+        )
+            .into_response(),
         Err(crate::Error::NotFound(msg)) => {
             let error_code = if msg.contains("Download files not found") {
                 "files_not_found"
@@ -584,6 +1171,7 @@ pub async fn reextract_download(
         .await
     {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
         Err(crate::Error::NotFound(msg)) => {
             let error_code = if msg.contains("Download files not found") {
                 "files_not_found"
@@ -602,3 +1190,33 @@ pub async fn reextract_download(
         }
     }
 }
+
+/// POST /downloads/:id/check - Estimate availability on the servers before downloading
+#[utoipa::path(
+    post,
+    path = "/api/v1/downloads/{id}/check",
+    tag = "downloads",
+    params(
+        ("id" = i64, Path, description = "Download ID"),
+        ("sample_size" = Option<usize>, Query, description = "Number of articles to sample (default: completion_check.sample_size)")
+    ),
+    responses(
+        (status = 200, description = "Estimated completion of the download", body = crate::types::CompletionCheck),
+        (status = 404, description = "Download not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn check_download_completion(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(params): Query<CompletionCheckQuery>,
+) -> Response {
+    match state
+        .downloader
+        .check_completion(crate::types::DownloadId(id), params.sample_size)
+        .await
+    {
+        Ok(check) => (StatusCode::OK, Json(check)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -4,9 +4,10 @@ use super::{ClearHistoryQuery, HistoryQuery};
 use crate::api::AppState;
 use axum::{
     Json,
-    extract::{Query, State},
+    body::Bytes,
+    extract::{Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
 use serde_json::json;
 
@@ -18,10 +19,11 @@ use serde_json::json;
     params(
         ("limit" = Option<i64>, Query, description = "Maximum number of items to return"),
         ("offset" = Option<i64>, Query, description = "Number of items to skip"),
-        ("status" = Option<String>, Query, description = "Filter by status (complete/failed)")
+        ("status" = Option<String>, Query, description = "Filter by status (complete/failed)"),
+        ("search" = Option<String>, Query, description = "Only entries whose name or category has words starting with each of these, best matches first")
     ),
     responses(
-        (status = 200, description = "Download history", body = Vec<crate::types::HistoryEntry>),
+        (status = 200, description = "Download history", body = crate::types::HistoryPage),
         (status = 400, description = "Invalid query parameters"),
         (status = 500, description = "Internal server error")
     )
@@ -33,42 +35,28 @@ pub async fn get_history(
     let limit = query.limit.unwrap_or(50).clamp(1, 1000) as usize;
     let offset = query.offset.unwrap_or(0).max(0) as usize;
 
-    let status_filter = if let Some(status_str) = query.status {
-        match status_str.to_lowercase().as_str() {
-            "complete" => Some(4),
-            "failed" => Some(5),
-            _ => {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(json!({"error": {"code": "invalid_status", "message": "Invalid status filter. Must be 'complete' or 'failed'"}})),
-                ).into_response();
-            }
-        }
-    } else {
-        None
+    let status_filter = match query.status.as_deref().map(parse_history_status) {
+        Some(Some(status)) => Some(status),
+        Some(None) => return invalid_status_response(),
+        None => None,
     };
 
-    match state
-        .downloader
-        .db
-        .query_history(status_filter, limit, offset)
-        .await
-    {
-        Ok(entries) => match state.downloader.db.count_history(status_filter).await {
-            Ok(total) => {
-                let response = json!({
-                    "items": entries,
-                    "total": total,
-                    "limit": limit,
-                    "offset": offset
-                });
-                (StatusCode::OK, Json(response)).into_response()
-            }
-            Err(e) => {
-                tracing::error!("Failed to count history: {}", e);
-                (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "database_error", "message": "Failed to count history entries"}}))).into_response()
-            }
-        },
+    let page = match query.search.as_deref().map(str::trim) {
+        Some(search) if !search.is_empty() => {
+            state
+                .downloader
+                .search_history(search, status_filter, limit, offset)
+                .await
+        }
+        _ => {
+            state
+                .downloader
+                .get_history(status_filter, limit, offset)
+                .await
+        }
+    };
+    match page {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
         Err(e) => {
             tracing::error!("Failed to query history: {}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "database_error", "message": "Failed to retrieve history"}}))).into_response()
@@ -76,6 +64,24 @@ pub async fn get_history(
     }
 }
 
+/// Parse a history status filter: "complete", "failed" or "failed_unrepairable"
+fn parse_history_status(status: &str) -> Option<crate::types::Status> {
+    match status.to_lowercase().as_str() {
+        "complete" => Some(crate::types::Status::Complete),
+        "failed" => Some(crate::types::Status::Failed),
+        "failed_unrepairable" => Some(crate::types::Status::FailedUnrepairable),
+        _ => None,
+    }
+}
+
+fn invalid_status_response() -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": {"code": "invalid_status", "message": "Invalid status filter. Must be 'complete', 'failed' or 'failed_unrepairable'"}})),
+    )
+        .into_response()
+}
+
 /// DELETE /history - Clear history
 #[utoipa::path(
     delete,
@@ -95,33 +101,68 @@ pub async fn clear_history(
     State(state): State<AppState>,
     Query(query): Query<ClearHistoryQuery>,
 ) -> impl IntoResponse {
-    let status_filter = if let Some(status_str) = query.status {
-        match status_str.to_lowercase().as_str() {
-            "complete" => Some(4),
-            "failed" => Some(5),
-            _ => {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(json!({"error": {"code": "invalid_status", "message": "Invalid status filter. Must be 'complete' or 'failed'"}})),
-                ).into_response();
-            }
-        }
-    } else {
-        None
+    let status_filter = match query.status.as_deref().map(parse_history_status) {
+        Some(Some(status)) => Some(status),
+        Some(None) => return invalid_status_response(),
+        None => None,
     };
 
     match state
         .downloader
-        .db
-        .delete_history_filtered(query.before, status_filter)
+        .clear_history(query.before, status_filter)
         .await
     {
         Ok(deleted_count) => {
             (StatusCode::OK, Json(json!({"deleted": deleted_count}))).into_response()
         }
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
         Err(e) => {
             tracing::error!(error = %e, "Failed to clear history");
             (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "clear_failed", "message": format!("Failed to clear history: {}", e)}}))).into_response()
         }
     }
 }
+
+/// POST /history/:id/retry - Queue a new attempt of a history entry's download
+#[utoipa::path(
+    post,
+    path = "/api/v1/history/{id}/retry",
+    tag = "history",
+    params(("id" = i64, Path, description = "History entry ID")),
+    request_body(content = Option<crate::types::DownloadOptions>, description = "Options overriding the original download's (optional)"),
+    responses(
+        (status = 201, description = "Download re-queued", body = i64),
+        (status = 400, description = "Invalid download options"),
+        (status = 404, description = "History entry not found, or its download was removed"),
+        (status = 409, description = "Original download is still in progress"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn retry_history(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    body: Bytes,
+) -> Response {
+    let options = if body.is_empty() {
+        crate::types::DownloadOptions::default()
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(options) => options,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({"error": {"code": "invalid_options", "message": format!("Invalid download options: {}", e)}})),
+                )
+                    .into_response();
+            }
+        }
+    };
+
+    match state.downloader.retry_history(id, options).await {
+        Ok(download_id) => (StatusCode::CREATED, Json(json!({"id": download_id}))).into_response(),
+        Err(e) => {
+            tracing::error!(history_id = id, error = %e, "Failed to retry history entry");
+            e.into_response()
+        }
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Handlers are organized by domain:
 //! - [`downloads`] — Individual download management
+//! - [`batches`] — Download groups
 //! - [`queue`] — Queue-wide operations
 //! - [`history`] — Download history
 //! - [`servers`] — Server management
@@ -10,9 +11,11 @@
 //! - [`system`] — Health, events, OpenAPI, shutdown
 //! - [`rss`] — RSS feed management
 //! - [`scheduler`] — Schedule rule management
+//! - [`stats`] — Usage statistics
 
 use serde::{Deserialize, Serialize};
 
+mod batches;
 mod categories;
 mod config;
 mod downloads;
@@ -21,9 +24,11 @@ mod queue;
 mod rss;
 mod scheduler;
 mod servers;
+mod stats;
 mod system;
 
 // Re-export all handlers so `routes::function_name` continues to work
+pub use batches::*;
 pub use categories::*;
 pub use config::*;
 pub use downloads::*;
@@ -32,6 +37,7 @@ pub use queue::*;
 pub use rss::*;
 pub use scheduler::*;
 pub use servers::*;
+pub use stats::*;
 pub use system::*;
 
 // ============================================================================
@@ -46,6 +52,59 @@ pub struct DeleteDownloadQuery {
     pub delete_files: bool,
 }
 
+/// Query parameters for POST /downloads/:id/reprocess
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct ReprocessQuery {
+    /// Stage to restart from: verify, repair, extract, move or cleanup (default: full pipeline)
+    pub stage: Option<crate::types::Stage>,
+}
+
+/// Query parameters for POST /downloads/:id/check
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct CompletionCheckQuery {
+    /// Number of articles to sample (default: `completion_check.sample_size`)
+    pub sample_size: Option<usize>,
+}
+
+/// Query parameters for the download and queue pause and resume endpoints
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct PauseQuery {
+    /// What to pause or resume: `download`, `post_processing` or `all` (default)
+    #[serde(default)]
+    pub scope: crate::types::PauseScope,
+}
+
+/// Query parameters for POST /servers/:id/disable
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct DisableServerQuery {
+    /// Seconds until the server is enabled again (default: until re-enabled)
+    pub duration: Option<u64>,
+}
+
+/// Query parameters for GET /events
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct EventStreamQuery {
+    /// Comma-separated event types to send (default: all)
+    pub types: Option<String>,
+    /// Replay journaled events after this sequence number before live ones
+    pub since_seq: Option<u64>,
+}
+
+/// Query parameters for GET /system/log/stream
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct LogStreamQuery {
+    /// Most verbose level to send (default: `logging.log_stream_level`)
+    pub level: Option<crate::config::LogLevel>,
+}
+
+/// Query parameters for GET /stats
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct StatsQuery {
+    /// Reporting window: day, week or month (default: day)
+    #[serde(default)]
+    pub period: crate::types::StatsPeriod,
+}
+
 /// Query parameters for GET /history
 #[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
 pub struct HistoryQuery {
@@ -53,8 +112,10 @@ pub struct HistoryQuery {
     pub limit: Option<i64>,
     /// Number of items to skip (default: 0)
     pub offset: Option<i64>,
-    /// Filter by status: "complete" or "failed"
+    /// Filter by status: "complete", "failed" or "failed_unrepairable"
     pub status: Option<String>,
+    /// Only entries whose name or category matches these words, best matches first
+    pub search: Option<String>,
 }
 
 /// Query parameters for DELETE /history
@@ -62,15 +123,47 @@ pub struct HistoryQuery {
 pub struct ClearHistoryQuery {
     /// Clear entries before this timestamp
     pub before: Option<i64>,
-    /// Clear only entries with this status: "complete" or "failed"
+    /// Clear only entries with this status: "complete", "failed" or "failed_unrepairable"
     pub status: Option<String>,
 }
 
-/// Request body for PUT /config/speed-limit
+/// Request body for PUT /config/speed-limit, /servers/:id/speed-limit and /downloads/:id/speed-limit
 #[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
 pub struct SetSpeedLimitRequest {
     /// Speed limit in bytes per second. Use null for unlimited.
     pub limit_bps: Option<u64>,
+    /// Token bucket size in bytes (global limit only). Omit to keep the current burst;
+    /// 0 resets it to one second of the speed limit.
+    #[serde(default)]
+    pub burst_bytes: Option<u64>,
+}
+
+/// Request body for PUT /config/bandwidth-mode
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct SetBandwidthModeRequest {
+    /// Mode to switch to
+    pub mode: crate::types::BandwidthMode,
+}
+
+/// Request body for PUT /downloads/:id/name
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct RenameDownloadRequest {
+    /// New name for the download
+    pub name: String,
+}
+
+/// Request body for POST /downloads/:id/password
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct SubmitPasswordRequest {
+    /// Password to retry extraction with
+    pub password: String,
+}
+
+/// Request body for POST /batches
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct CreateBatchRequest {
+    /// Human-readable name for the batch
+    pub name: String,
 }
 
 /// Request body for POST /rss and PUT /rss/:id
@@ -1,27 +1,39 @@
 //! Queue-wide operation handlers.
 
+use super::PauseQuery;
 use crate::api::AppState;
 use axum::{
     Json,
-    extract::State,
-    http::StatusCode,
+    body::Bytes,
+    extract::{Query, State},
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
 use serde_json::json;
 
 /// POST /queue/pause - Pause all downloads
+///
+/// `scope=download` stops downloading but lets downloaded jobs post-process;
+/// `scope=post_processing` keeps downloading but holds every job before post-processing.
 #[utoipa::path(
     post,
     path = "/api/v1/queue/pause",
     tag = "queue",
+    params(
+        ("scope" = Option<crate::types::PauseScope>, Query, description = "What to pause: download, post_processing or all (default)")
+    ),
     responses(
         (status = 204, description = "Queue paused successfully"),
         (status = 500, description = "Internal server error")
     )
 )]
-pub async fn pause_queue(State(state): State<AppState>) -> impl IntoResponse {
-    match state.downloader.pause_all().await {
+pub async fn pause_queue(
+    State(state): State<AppState>,
+    Query(params): Query<PauseQuery>,
+) -> impl IntoResponse {
+    match state.downloader.pause_queue(params.scope).await {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
         Err(e) => {
             tracing::error!(error = %e, "Failed to pause queue");
             (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "pause_failed", "message": format!("Failed to pause queue: {}", e)}}))).into_response()
@@ -34,14 +46,21 @@ pub async fn pause_queue(State(state): State<AppState>) -> impl IntoResponse {
     post,
     path = "/api/v1/queue/resume",
     tag = "queue",
+    params(
+        ("scope" = Option<crate::types::PauseScope>, Query, description = "What to resume: download, post_processing or all (default)")
+    ),
     responses(
         (status = 204, description = "Queue resumed successfully"),
         (status = 500, description = "Internal server error")
     )
 )]
-pub async fn resume_queue(State(state): State<AppState>) -> impl IntoResponse {
-    match state.downloader.resume_all().await {
+pub async fn resume_queue(
+    State(state): State<AppState>,
+    Query(params): Query<PauseQuery>,
+) -> impl IntoResponse {
+    match state.downloader.resume_queue(params.scope).await {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
         Err(e) => {
             tracing::error!(error = %e, "Failed to resume queue");
             (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "resume_failed", "message": format!("Failed to resume queue: {}", e)}}))).into_response()
@@ -60,65 +79,94 @@ pub async fn resume_queue(State(state): State<AppState>) -> impl IntoResponse {
     )
 )]
 pub async fn queue_stats(State(state): State<AppState>) -> Response {
-    match state.downloader.db.get_all_downloads().await {
-        Ok(downloads) => {
-            let mut queued = 0;
-            let mut downloading = 0;
-            let mut paused = 0;
-            let mut processing = 0;
-            let mut total_speed_bps = 0u64;
-            let mut total_size_bytes = 0u64;
-            let mut downloaded_bytes = 0u64;
-
-            for download in &downloads {
-                let status = crate::types::Status::from_i32(download.status);
-                match status {
-                    crate::types::Status::Queued => queued += 1,
-                    crate::types::Status::Downloading => downloading += 1,
-                    crate::types::Status::Paused => paused += 1,
-                    crate::types::Status::Processing => processing += 1,
-                    _ => {}
-                }
-                if status == crate::types::Status::Downloading {
-                    total_speed_bps += download.speed_bps as u64;
-                }
-                total_size_bytes += download.size_bytes as u64;
-                downloaded_bytes += download.downloaded_bytes as u64;
-            }
+    match state.downloader.queue_stats().await {
+        Ok(stats) => (StatusCode::OK, Json(stats)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to get queue statistics");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "stats_failed", "message": format!("Failed to get queue statistics: {}", e)}}))).into_response()
+        }
+    }
+}
 
-            let total = downloads.len();
-            let overall_progress = if total_size_bytes > 0 {
-                (downloaded_bytes as f32 / total_size_bytes as f32) * 100.0
-            } else {
-                0.0
-            };
+/// GET /queue/export - Download the pending queue as a portable archive
+#[utoipa::path(
+    get,
+    path = "/api/v1/queue/export",
+    tag = "queue",
+    responses(
+        (status = 200, description = "Zip archive with the queued, downloading and paused downloads, their options and progress", content_type = "application/zip"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn export_queue(State(state): State<AppState>) -> Response {
+    let result = async {
+        let scratch = state.downloader.backup_scratch_dir().await?;
+        let archive = scratch.join("queue.zip");
+        let result = match state.downloader.export_queue(&archive).await {
+            Ok(_) => tokio::fs::read(&archive).await.map_err(crate::Error::from),
+            Err(e) => Err(e),
+        };
+        let _ = tokio::fs::remove_dir_all(&scratch).await;
+        result
+    }
+    .await;
 
-            let speed_limit_bps = state.downloader.speed_limiter.get_limit();
-            let accepting_new = state
-                .downloader
-                .queue_state
-                .accepting_new
-                .load(std::sync::atomic::Ordering::SeqCst);
+    match result {
+        Ok(bytes) => {
+            let filename = format!(
+                "usenet-dl-queue-{}.zip",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S")
+            );
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "application/zip".to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"{}\"", filename),
+                    ),
+                ],
+                bytes,
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to export queue");
+            e.into_response()
+        }
+    }
+}
 
-            let stats = crate::types::QueueStats {
-                total,
-                queued,
-                downloading,
-                paused,
-                processing,
-                total_speed_bps,
-                total_size_bytes,
-                downloaded_bytes,
-                overall_progress,
-                speed_limit_bps,
-                accepting_new,
-            };
+/// POST /queue/import - Add the downloads from a queue export archive
+#[utoipa::path(
+    post,
+    path = "/api/v1/queue/import",
+    tag = "queue",
+    request_body(content = Vec<u8>, description = "Zip archive from GET /queue/export", content_type = "application/zip"),
+    responses(
+        (status = 201, description = "Downloads added; body is `{\"ids\": [...]}`"),
+        (status = 422, description = "Not a valid queue export archive"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn import_queue(State(state): State<AppState>, body: Bytes) -> Response {
+    let result = async {
+        let scratch = state.downloader.backup_scratch_dir().await?;
+        let archive = scratch.join("queue.zip");
+        let result = match tokio::fs::write(&archive, &body).await {
+            Ok(()) => state.downloader.import_queue(&archive).await,
+            Err(e) => Err(e.into()),
+        };
+        let _ = tokio::fs::remove_dir_all(&scratch).await;
+        result
+    }
+    .await;
 
-            (StatusCode::OK, Json(stats)).into_response()
-        }
+    match result {
+        Ok(ids) => (StatusCode::CREATED, Json(json!({"ids": ids}))).into_response(),
         Err(e) => {
-            tracing::error!(error = %e, "Failed to get queue statistics");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "stats_failed", "message": format!("Failed to get queue statistics: {}", e)}}))).into_response()
+            tracing::error!(error = %e, "Failed to import queue");
+            e.into_response()
         }
     }
 }
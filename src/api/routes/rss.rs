@@ -21,62 +21,19 @@ use serde_json::json;
     )
 )]
 pub async fn list_rss_feeds(State(state): State<AppState>) -> impl IntoResponse {
-    let feeds = match state.downloader.db.get_all_rss_feeds().await {
-        Ok(f) => f,
+    match state.downloader.list_rss_feeds().await {
+        Ok(feeds) => {
+            let responses: Vec<RssFeedResponse> = feeds
+                .into_iter()
+                .map(|(id, name, config)| RssFeedResponse { id, name, config })
+                .collect();
+            (StatusCode::OK, Json(responses)).into_response()
+        }
         Err(e) => {
             tracing::error!("Failed to get RSS feeds: {}", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "database_error", "message": format!("Failed to get RSS feeds: {}", e)}}))).into_response();
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "database_error", "message": format!("Failed to get RSS feeds: {}", e)}}))).into_response()
         }
-    };
-
-    let mut responses = Vec::new();
-
-    for feed in feeds {
-        let filter_rows = match state.downloader.db.get_rss_filters(feed.id).await {
-            Ok(f) => f,
-            Err(e) => {
-                tracing::error!("Failed to get filters for feed {}: {}", feed.id, e);
-                continue;
-            }
-        };
-
-        let filters = filter_rows
-            .into_iter()
-            .map(|row| {
-                use std::time::Duration;
-                crate::config::RssFilter {
-                    name: row.name,
-                    include: row
-                        .include_patterns
-                        .and_then(|s| serde_json::from_str(&s).ok())
-                        .unwrap_or_default(),
-                    exclude: row
-                        .exclude_patterns
-                        .and_then(|s| serde_json::from_str(&s).ok())
-                        .unwrap_or_default(),
-                    min_size: row.min_size.map(|s| s as u64),
-                    max_size: row.max_size.map(|s| s as u64),
-                    max_age: row.max_age_secs.map(|s| Duration::from_secs(s as u64)),
-                }
-            })
-            .collect();
-
-        responses.push(RssFeedResponse {
-            id: feed.id,
-            name: feed.name,
-            config: crate::config::RssFeedConfig {
-                url: feed.url,
-                check_interval: std::time::Duration::from_secs(feed.check_interval_secs as u64),
-                category: feed.category,
-                filters,
-                auto_download: feed.auto_download != 0,
-                priority: crate::types::Priority::from_i32(feed.priority),
-                enabled: feed.enabled != 0,
-            },
-        });
     }
-
-    (StatusCode::OK, Json(responses)).into_response()
 }
 
 /// POST /rss - Add RSS feed
@@ -279,6 +236,7 @@ pub async fn check_rss_feed(
 ) -> impl IntoResponse {
     match state.downloader.check_rss_feed_now(id).await {
         Ok(queued) => (StatusCode::OK, Json(CheckRssFeedResponse { queued })).into_response(),
+        Err(e @ crate::Error::ReadOnly) => e.into_response(),
         Err(crate::Error::NotFound(_)) => (
             StatusCode::NOT_FOUND,
             Json(json!({"error": {"code": "not_found", "message": "RSS feed not found"}})),
@@ -57,6 +57,9 @@ pub async fn add_schedule_rule(
     if chrono::NaiveTime::parse_from_str(&rule.end_time, "%H:%M").is_err() {
         return (StatusCode::BAD_REQUEST, Json(json!({"error": {"code": "invalid_input", "message": format!("Invalid end_time format: '{}'. Expected HH:MM", rule.end_time)}}))).into_response();
     }
+    if let Err((field, message)) = rule.date_range() {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": {"code": "invalid_input", "message": format!("{}: {}", field, message)}}))).into_response();
+    }
 
     let id = state.downloader.add_schedule_rule(rule).await;
     (StatusCode::CREATED, Json(json!({"id": id}))).into_response()
@@ -88,6 +91,9 @@ pub async fn update_schedule_rule(
     if chrono::NaiveTime::parse_from_str(&rule.end_time, "%H:%M").is_err() {
         return (StatusCode::BAD_REQUEST, Json(json!({"error": {"code": "invalid_input", "message": format!("Invalid end_time format: '{}'. Expected HH:MM", rule.end_time)}}))).into_response();
     }
+    if let Err((field, message)) = rule.date_range() {
+        return (StatusCode::BAD_REQUEST, Json(json!({"error": {"code": "invalid_input", "message": format!("{}: {}", field, message)}}))).into_response();
+    }
 
     match state.downloader.update_schedule_rule(id, rule).await {
         true => StatusCode::NO_CONTENT.into_response(),
@@ -1,8 +1,181 @@
 //! Server management handlers.
 
+use super::{DisableServerQuery, SetSpeedLimitRequest};
 use crate::api::AppState;
-use crate::config::ServerConfig;
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use crate::config::{REDACTED, ServerConfig};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+/// GET /servers - List configured servers (passwords redacted)
+#[utoipa::path(
+    get,
+    path = "/api/v1/servers",
+    tag = "servers",
+    responses(
+        (status = 200, description = "Servers in the order they're tried; the index is the server ID", body = Vec<crate::config::ServerConfig>)
+    )
+)]
+pub async fn list_servers(State(state): State<AppState>) -> impl IntoResponse {
+    let mut servers = state.downloader.get_servers();
+    for server in &mut servers {
+        if server.password.is_some() {
+            server.password = Some(REDACTED.to_string());
+        }
+    }
+    (StatusCode::OK, Json(servers))
+}
+
+/// POST /servers - Add a server
+#[utoipa::path(
+    post,
+    path = "/api/v1/servers",
+    tag = "servers",
+    request_body(content = crate::config::ServerConfig, description = "Server to add, tried after the existing ones"),
+    responses(
+        (status = 201, description = "Server added; returns its ID"),
+        (status = 400, description = "Invalid server configuration; `error.details.path` names the field"),
+        (status = 500, description = "Connection pool could not be created")
+    )
+)]
+pub async fn add_server(
+    State(state): State<AppState>,
+    Json(server): Json<ServerConfig>,
+) -> Response {
+    match state.downloader.add_server(server).await {
+        Ok(id) => (StatusCode::CREATED, Json(json!({"id": id}))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// PUT /servers/:id - Replace a server's configuration
+#[utoipa::path(
+    put,
+    path = "/api/v1/servers/{id}",
+    tag = "servers",
+    params(
+        ("id" = usize, Path, description = "Server index in the configured server list")
+    ),
+    request_body(content = crate::config::ServerConfig, description = "New server configuration; a redacted password keeps the current one"),
+    responses(
+        (status = 204, description = "Server updated"),
+        (status = 400, description = "Invalid server configuration; `error.details.path` names the field"),
+        (status = 404, description = "Server not found")
+    )
+)]
+pub async fn update_server(
+    State(state): State<AppState>,
+    Path(id): Path<usize>,
+    Json(server): Json<ServerConfig>,
+) -> Response {
+    match state.downloader.update_server(id, server).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// DELETE /servers/:id - Remove a server
+#[utoipa::path(
+    delete,
+    path = "/api/v1/servers/{id}",
+    tag = "servers",
+    params(
+        ("id" = usize, Path, description = "Server index in the configured server list; later servers move up one index")
+    ),
+    responses(
+        (status = 204, description = "Server removed"),
+        (status = 404, description = "Server not found")
+    )
+)]
+pub async fn delete_server(State(state): State<AppState>, Path(id): Path<usize>) -> Response {
+    match state.downloader.remove_server(id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// GET /servers/status - Whether each server is in rotation
+#[utoipa::path(
+    get,
+    path = "/api/v1/servers/status",
+    tag = "servers",
+    responses(
+        (status = 200, description = "Rotation status of each server, in server order", body = Vec<crate::types::ServerStatus>)
+    )
+)]
+pub async fn list_server_statuses(State(state): State<AppState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(state.downloader.server_statuses()))
+}
+
+/// POST /servers/:id/disable - Take a server out of rotation
+#[utoipa::path(
+    post,
+    path = "/api/v1/servers/{id}/disable",
+    tag = "servers",
+    params(
+        ("id" = usize, Path, description = "Server index in the configured server list"),
+        ("duration" = Option<u64>, Query, description = "Seconds until the server is enabled again (default: until re-enabled)")
+    ),
+    responses(
+        (status = 200, description = "Server disabled", body = crate::types::ServerStatus),
+        (status = 404, description = "Server not found"),
+    )
+)]
+pub async fn disable_server(
+    State(state): State<AppState>,
+    Path(id): Path<usize>,
+    Query(params): Query<DisableServerQuery>,
+) -> Response {
+    let duration = params.duration.map(std::time::Duration::from_secs);
+    match state.downloader.disable_server(id, duration).await {
+        Ok(status) => (StatusCode::OK, Json(status)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// POST /servers/:id/enable - Put a disabled server back into rotation
+#[utoipa::path(
+    post,
+    path = "/api/v1/servers/{id}/enable",
+    tag = "servers",
+    params(
+        ("id" = usize, Path, description = "Server index in the configured server list")
+    ),
+    responses(
+        (status = 200, description = "Server enabled", body = crate::types::ServerStatus),
+        (status = 404, description = "Server not found"),
+    )
+)]
+pub async fn enable_server(State(state): State<AppState>, Path(id): Path<usize>) -> Response {
+    match state.downloader.enable_server(id).await {
+        Ok(status) => (StatusCode::OK, Json(status)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// POST /servers/:id/reconnect - Close a server's connections and start over with fresh ones
+#[utoipa::path(
+    post,
+    path = "/api/v1/servers/{id}/reconnect",
+    tag = "servers",
+    params(
+        ("id" = usize, Path, description = "Server index in the configured server list")
+    ),
+    responses(
+        (status = 200, description = "Connections closed; new ones are opened as needed", body = crate::types::ServerStatus),
+        (status = 404, description = "Server not found"),
+    )
+)]
+pub async fn reconnect_server(State(state): State<AppState>, Path(id): Path<usize>) -> Response {
+    match state.downloader.reconnect_server(id).await {
+        Ok(status) => (StatusCode::OK, Json(status)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
 
 /// POST /servers/test - Test server connection
 #[utoipa::path(
@@ -38,3 +211,89 @@ pub async fn test_all_servers(State(state): State<AppState>) -> impl IntoRespons
     let results = state.downloader.test_all_servers().await;
     (StatusCode::OK, Json(results))
 }
+
+/// GET /servers/:id/trace - Recent NNTP commands and responses for a server
+#[utoipa::path(
+    get,
+    path = "/api/v1/servers/{id}/trace",
+    tag = "servers",
+    params(
+        ("id" = usize, Path, description = "Server index in the configured server list")
+    ),
+    responses(
+        (status = 200, description = "Trace entries, oldest first (empty unless logging.nntp_trace is enabled)", body = Vec<crate::types::NntpTraceEntry>),
+        (status = 404, description = "Server not found")
+    )
+)]
+pub async fn get_server_trace(State(state): State<AppState>, Path(id): Path<usize>) -> Response {
+    match state.downloader.nntp_trace(id) {
+        Some(entries) => (StatusCode::OK, Json(entries)).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": {"code": "not_found", "message": format!("Server {} not found", id)}})),
+        )
+            .into_response(),
+    }
+}
+
+/// PUT /servers/:id/speed-limit - Set a per-server speed limit
+#[utoipa::path(
+    put,
+    path = "/api/v1/servers/{id}/speed-limit",
+    tag = "servers",
+    params(
+        ("id" = usize, Path, description = "Server index in the configured server list")
+    ),
+    request_body = SetSpeedLimitRequest,
+    responses(
+        (status = 204, description = "Server speed limit updated successfully"),
+        (status = 404, description = "Server not found")
+    )
+)]
+pub async fn set_server_speed_limit(
+    State(state): State<AppState>,
+    Path(id): Path<usize>,
+    Json(request): Json<SetSpeedLimitRequest>,
+) -> Response {
+    if state
+        .downloader
+        .set_server_speed_limit(id, request.limit_bps)
+    {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": {"code": "not_found", "message": format!("Server {} not found", id)}})),
+        )
+            .into_response()
+    }
+}
+
+/// POST /servers/:id/speedtest - Measure download throughput per connection count
+#[utoipa::path(
+    post,
+    path = "/api/v1/servers/{id}/speedtest",
+    tag = "servers",
+    params(
+        ("id" = usize, Path, description = "Server index in the configured server list")
+    ),
+    request_body(content = crate::types::SpeedTestOptions, description = "Speed test options (send {} for defaults)"),
+    responses(
+        (status = 200, description = "Throughput for each connection count", body = crate::types::SpeedTestResult),
+        (status = 404, description = "Server not found")
+    )
+)]
+pub async fn speed_test_server(
+    State(state): State<AppState>,
+    Path(id): Path<usize>,
+    Json(options): Json<crate::types::SpeedTestOptions>,
+) -> Response {
+    match state.downloader.speed_test(id, options).await {
+        Some(result) => (StatusCode::OK, Json(result)).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": {"code": "not_found", "message": format!("Server {} not found", id)}})),
+        )
+            .into_response(),
+    }
+}
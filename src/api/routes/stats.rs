@@ -0,0 +1,35 @@
+//! Usage statistics handlers.
+
+use super::StatsQuery;
+use crate::api::AppState;
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+/// GET /stats - Bytes downloaded per server, category and day
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats",
+    tag = "stats",
+    params(
+        ("period" = Option<String>, Query, description = "Reporting window: day, week or month (default: day)")
+    ),
+    responses(
+        (status = 200, description = "Usage statistics for the period", body = crate::types::UsageStats),
+        (status = 400, description = "Invalid period"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_stats(State(state): State<AppState>, Query(query): Query<StatsQuery>) -> Response {
+    match state.downloader.usage_stats(query.period).await {
+        Ok(stats) => (StatusCode::OK, Json(stats)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to get usage statistics");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": {"code": "database_error", "message": format!("Failed to get usage statistics: {}", e)}}))).into_response()
+        }
+    }
+}
@@ -1,12 +1,14 @@
-//! System handlers: health, capabilities, OpenAPI, events, shutdown.
+//! System handlers: health, capabilities, OpenAPI, events, logs, backups, shutdown.
 
 use crate::api::AppState;
+use crate::api::routes::{EventStreamQuery, LogStreamQuery};
 use axum::{
     Json,
-    extract::State,
-    http::StatusCode,
+    body::Bytes,
+    extract::{Query, State},
+    http::{StatusCode, header},
     response::{
-        IntoResponse,
+        IntoResponse, Response,
         sse::{Event as SseEvent, KeepAlive, Sse},
     },
 };
@@ -31,10 +33,50 @@ pub async fn health_check() -> impl IntoResponse {
     }))
 }
 
-/// GET /capabilities - Query system capabilities
+/// GET /health/live - Liveness probe (process is up)
 #[utoipa::path(
     get,
-    path = "/api/v1/capabilities",
+    path = "/api/v1/health/live",
+    tag = "system",
+    responses(
+        (status = 200, description = "Process is alive", body = crate::types::HealthReport),
+        (status = 503, description = "A liveness check failed", body = crate::types::HealthReport)
+    )
+)]
+pub async fn health_live(State(state): State<AppState>) -> impl IntoResponse {
+    health_response(state.downloader.liveness().await)
+}
+
+/// GET /health/ready - Readiness probe (safe to route traffic here)
+#[utoipa::path(
+    get,
+    path = "/api/v1/health/ready",
+    tag = "system",
+    responses(
+        (status = 200, description = "Instance is ready to serve traffic", body = crate::types::HealthReport),
+        (status = 503, description = "A readiness check failed", body = crate::types::HealthReport)
+    )
+)]
+pub async fn health_ready(State(state): State<AppState>) -> impl IntoResponse {
+    health_response(state.downloader.readiness().await)
+}
+
+/// Map a health report to 200 (ok) or 503 (any check failed)
+fn health_response(report: crate::types::HealthReport) -> impl IntoResponse {
+    let status = if report.ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+/// GET /system/capabilities - Query system capabilities
+///
+/// Also served at `/capabilities`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/capabilities",
     tag = "system",
     responses(
         (status = 200, description = "Current system capabilities", body = crate::types::Capabilities),
@@ -67,72 +109,273 @@ pub async fn openapi_spec() -> impl IntoResponse {
     get,
     path = "/api/v1/events",
     tag = "system",
+    params(
+        ("types" = Option<String>, Query, description = "Comma-separated event types to send, e.g. `queued,complete,failed` (default: all)"),
+        ("since_seq" = Option<u64>, Query, description = "Replay journaled events after this sequence number before live ones; the `Last-Event-ID` header is used when absent")
+    ),
     responses(
-        (status = 200, description = "Server-sent events stream (text/event-stream)", content_type = "text/event-stream"),
+        (status = 200, description = "Server-sent events stream (text/event-stream); each event's data is a JSON EventEnvelope and its id the envelope's sequence number", content_type = "text/event-stream"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn event_stream(
     State(state): State<AppState>,
-) -> Sse<impl tokio_stream::Stream<Item = Result<SseEvent, Infallible>>> {
-    let receiver = state.downloader.subscribe();
-    let stream = BroadcastStream::new(receiver);
-
-    let sse_stream = stream.filter_map(|result| match result {
-        Ok(event) => match serde_json::to_string(&event) {
-            Ok(json_data) => {
-                let event_type = match &event {
-                    crate::types::Event::Queued { .. } => "queued",
-                    crate::types::Event::Removed { .. } => "removed",
-                    crate::types::Event::Downloading { .. } => "downloading",
-                    crate::types::Event::DownloadComplete { .. } => "download_complete",
-                    crate::types::Event::DownloadFailed { .. } => "download_failed",
-                    crate::types::Event::Verifying { .. } => "verifying",
-                    crate::types::Event::VerifyComplete { .. } => "verify_complete",
-                    crate::types::Event::Repairing { .. } => "repairing",
-                    crate::types::Event::RepairComplete { .. } => "repair_complete",
-                    crate::types::Event::RepairSkipped { .. } => "repair_skipped",
-                    crate::types::Event::Extracting { .. } => "extracting",
-                    crate::types::Event::ExtractComplete { .. } => "extract_complete",
-                    crate::types::Event::Moving { .. } => "moving",
-                    crate::types::Event::Cleaning { .. } => "cleaning",
-                    crate::types::Event::Complete { .. } => "complete",
-                    crate::types::Event::Failed { .. } => "failed",
-                    crate::types::Event::SpeedLimitChanged { .. } => "speed_limit_changed",
-                    crate::types::Event::QueuePaused => "queue_paused",
-                    crate::types::Event::QueueResumed => "queue_resumed",
-                    crate::types::Event::WebhookFailed { .. } => "webhook_failed",
-                    crate::types::Event::ScriptFailed { .. } => "script_failed",
-                    crate::types::Event::DuplicateDetected { .. } => "duplicate_detected",
-                    crate::types::Event::DirectUnpackStarted { .. } => "direct_unpack_started",
-                    crate::types::Event::FileCompleted { .. } => "file_completed",
-                    crate::types::Event::DirectUnpackExtracting { .. } => {
-                        "direct_unpack_extracting"
-                    }
-                    crate::types::Event::DirectUnpackExtracted { .. } => "direct_unpack_extracted",
-                    crate::types::Event::DirectUnpackCancelled { .. } => "direct_unpack_cancelled",
-                    crate::types::Event::DirectUnpackComplete { .. } => "direct_unpack_complete",
-                    crate::types::Event::DirectRenamed { .. } => "direct_renamed",
-                    crate::types::Event::Shutdown => "shutdown",
-                };
-
-                Some(Ok(SseEvent::default().event(event_type).data(json_data)))
-            }
+    Query(params): Query<EventStreamQuery>,
+    headers: axum::http::HeaderMap,
+) -> Response {
+    use crate::event_bus::RecvError;
+
+    let types: Option<std::collections::HashSet<String>> = params.types.map(|types| {
+        types
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect()
+    });
+
+    // EventSource clients resend the last id they saw when reconnecting
+    let since_seq = params.since_seq.or_else(|| {
+        headers
+            .get("last-event-id")
+            .and_then(|id| id.to_str().ok())
+            .and_then(|id| id.trim().parse().ok())
+    });
+
+    type EnvelopeStream = std::pin::Pin<
+        Box<dyn tokio_stream::Stream<Item = Result<crate::types::EventEnvelope, RecvError>> + Send>,
+    >;
+    let stream: EnvelopeStream = match since_seq {
+        Some(since_seq) => match state.downloader.subscribe_from(since_seq).await {
+            Ok(subscription) => Box::pin(futures::stream::unfold(
+                subscription,
+                |mut subscription| async {
+                    let result = subscription.recv().await;
+                    Some((result, subscription))
+                },
+            )),
             Err(e) => {
-                tracing::warn!("Failed to serialize event to JSON: {}", e);
-                None
+                tracing::error!(error = %e, "Failed to replay journaled events");
+                return e.into_response();
             }
         },
-        Err(tokio_stream::wrappers::errors::BroadcastStreamRecvError::Lagged(skipped)) => {
-            tracing::warn!("SSE client lagged, skipped {} events", skipped);
-            Some(Ok(SseEvent::default().event("error").data(format!(
-                r#"{{"error":"lagged","skipped":{}}}"#,
-                skipped
-            ))))
-        }
+        None => Box::pin(futures::stream::unfold(
+            state.downloader.subscribe_json(),
+            |mut receiver| async {
+                let result = receiver.recv().await;
+                Some((result, receiver))
+            },
+        )),
+    };
+
+    let sse_stream = stream
+        .take_while(|result| !matches!(result, Err(RecvError::Closed)))
+        .filter_map(move |result| match result {
+            Ok(envelope) => {
+                if types
+                    .as_ref()
+                    .is_some_and(|types| !types.contains(&envelope.event_type))
+                {
+                    return None;
+                }
+
+                match SseEvent::default()
+                    .event(&envelope.event_type)
+                    .id(envelope.seq.to_string())
+                    .json_data(&envelope)
+                {
+                    Ok(sse_event) => Some(Ok::<_, Infallible>(sse_event)),
+                    Err(e) => {
+                        tracing::warn!("Failed to serialize event to JSON: {}", e);
+                        None
+                    }
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("SSE client lagged, skipped {} events", skipped);
+                Some(Ok(SseEvent::default().event("error").data(format!(
+                    r#"{{"error":"lagged","skipped":{}}}"#,
+                    skipped
+                ))))
+            }
+            Err(RecvError::Closed) => None,
+        });
+
+    Sse::new(sse_stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// GET /system/log/stream - Server-sent stream of log events
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/log/stream",
+    tag = "system",
+    params(
+        ("level" = Option<String>, Query, description = "Most verbose level to send: error, warn, info, debug or trace (default: logging.log_stream_level)")
+    ),
+    responses(
+        (status = 200, description = "Recent log events followed by live ones, each a `log` event with a JSON LogEntry (text/event-stream)", content_type = "text/event-stream"),
+        (status = 503, description = "The application's tracing subscriber doesn't include the log stream layer")
+    )
+)]
+pub async fn log_stream(
+    State(state): State<AppState>,
+    Query(params): Query<LogStreamQuery>,
+) -> Response {
+    use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+
+    if !crate::log_stream::is_installed() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"error": {"code": "log_stream_unavailable", "message": "Log streaming needs usenet_dl::log_stream::layer() in the application's tracing subscriber"}})),
+        )
+            .into_response();
+    }
+
+    let level = params
+        .level
+        .unwrap_or(state.downloader.config.logging.log_stream_level);
+    let (recent, receiver) = crate::log_stream::subscribe(level);
+
+    let log_event = |entry: &crate::types::LogEntry| {
+        SseEvent::default()
+            .event("log")
+            .json_data(entry)
+            .ok()
+            .map(Ok::<_, Infallible>)
+    };
+    let live = BroadcastStream::new(receiver).filter_map(move |result| match result {
+        Ok(entry) if entry.level <= level => log_event(&entry),
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => Some(Ok(SseEvent::default()
+            .event("error")
+            .data(format!(r#"{{"error":"lagged","skipped":{}}}"#, skipped)))),
     });
+    let stream = tokio_stream::iter(recent)
+        .filter_map(move |entry| log_event(&entry))
+        .chain(live);
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// POST /system/drain - Enter maintenance mode and drain the queue
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/drain",
+    tag = "system",
+    responses(
+        (status = 202, description = "Drain started, or already under way; a `drained` event is emitted once idle")
+    )
+)]
+pub async fn start_drain(State(state): State<AppState>) -> impl IntoResponse {
+    // Flip into maintenance mode before responding so stats reflect it immediately;
+    // only the request that started the drain waits for it
+    if state.downloader.begin_drain() {
+        let downloader = state.downloader.clone();
+        tokio::spawn(async move { downloader.finish_drain().await });
+    }
 
-    Sse::new(sse_stream).keep_alive(KeepAlive::default())
+    (StatusCode::ACCEPTED, Json(json!({"status": "draining"})))
+}
+
+/// DELETE /system/drain - Leave maintenance mode
+#[utoipa::path(
+    delete,
+    path = "/api/v1/system/drain",
+    tag = "system",
+    responses(
+        (status = 204, description = "Drain cancelled, downloads are accepted again")
+    )
+)]
+pub async fn cancel_drain(State(state): State<AppState>) -> impl IntoResponse {
+    state.downloader.cancel_drain();
+    StatusCode::NO_CONTENT
+}
+
+/// GET /system/backup - Download a backup archive of configuration and database
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/backup",
+    tag = "system",
+    responses(
+        (status = 200, description = "Zip archive with manifest, configuration and database snapshot", content_type = "application/zip"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn export_backup(State(state): State<AppState>) -> Response {
+    let result = async {
+        let scratch = state.downloader.backup_scratch_dir().await?;
+        let archive = scratch.join("backup.zip");
+        let result = match state.downloader.export_backup(&archive).await {
+            Ok(()) => tokio::fs::read(&archive).await.map_err(crate::Error::from),
+            Err(e) => Err(e),
+        };
+        let _ = tokio::fs::remove_dir_all(&scratch).await;
+        result
+    }
+    .await;
+
+    match result {
+        Ok(bytes) => {
+            let filename = format!(
+                "usenet-dl-backup-{}.zip",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S")
+            );
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "application/zip".to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"{}\"", filename),
+                    ),
+                ],
+                bytes,
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to export backup");
+            e.into_response()
+        }
+    }
+}
+
+/// POST /system/backup - Restore a backup archive
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/backup",
+    tag = "system",
+    request_body(content = Vec<u8>, description = "Zip archive from GET /system/backup", content_type = "application/zip"),
+    responses(
+        (status = 204, description = "Database, categories and schedule rules restored"),
+        (status = 409, description = "Downloads or post-processing are still running"),
+        (status = 422, description = "Not a valid backup archive"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn import_backup(State(state): State<AppState>, body: Bytes) -> Response {
+    let result = async {
+        let scratch = state.downloader.backup_scratch_dir().await?;
+        let archive = scratch.join("backup.zip");
+        let result = match tokio::fs::write(&archive, &body).await {
+            Ok(()) => state.downloader.import_backup(&archive).await,
+            Err(e) => Err(e.into()),
+        };
+        let _ = tokio::fs::remove_dir_all(&scratch).await;
+        result
+    }
+    .await;
+
+    match result {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to restore backup");
+            e.into_response()
+        }
+    }
 }
 
 /// POST /shutdown - Graceful shutdown
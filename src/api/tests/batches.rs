@@ -0,0 +1,104 @@
+use super::*;
+use crate::downloader::test_helpers::SAMPLE_NZB;
+
+#[tokio::test]
+async fn test_create_and_get_batch() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/batches")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"name":"Season 2"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let batch_id = json["id"].as_i64().unwrap();
+
+    for name in ["S02E01", "S02E02"] {
+        downloader
+            .add_nzb_content(
+                SAMPLE_NZB.as_bytes(),
+                name,
+                crate::types::DownloadOptions {
+                    batch_id: Some(batch_id),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/batches/{}", batch_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["id"], batch_id);
+    assert_eq!(json["name"], "Season 2");
+    assert_eq!(json["total"], 2);
+    assert_eq!(json["downloads"].as_array().unwrap().len(), 2);
+    assert_eq!(json["downloads"][0]["name"], "S02E01");
+    assert!(json["completed_at"].is_null());
+}
+
+#[tokio::test]
+async fn test_get_unknown_batch_returns_404() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader, config);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/batches/12345")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_create_batch_rejects_empty_name() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader, config);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/batches")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"name":"  "}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
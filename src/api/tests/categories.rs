@@ -59,6 +59,9 @@ async fn test_create_or_update_category() {
         destination: PathBuf::from("/downloads/movies"),
         post_process: Some(PostProcess::UnpackAndCleanup),
         scripts: vec![],
+        flatten_single_folder: None,
+        junk_filter: None,
+        verify_policy: None,
     };
 
     let response = app
@@ -113,6 +116,9 @@ async fn test_create_or_update_category() {
         destination: PathBuf::from("/downloads/movies-updated"),
         post_process: Some(PostProcess::Unpack),
         scripts: vec![],
+        flatten_single_folder: None,
+        junk_filter: None,
+        verify_policy: None,
     };
 
     let response = app
@@ -211,6 +217,9 @@ async fn test_delete_category() {
         destination: PathBuf::from("/downloads/movies"),
         post_process: Some(PostProcess::UnpackAndCleanup),
         scripts: vec![],
+        flatten_single_folder: None,
+        junk_filter: None,
+        verify_policy: None,
     };
 
     let response = app
@@ -1,5 +1,6 @@
 use super::*;
-use crate::types::DownloadId;
+use crate::downloader::test_helpers::SAMPLE_NZB;
+use crate::types::{DownloadId, DownloadOptions};
 
 #[tokio::test]
 async fn test_list_downloads_endpoint() {
@@ -162,6 +163,15 @@ async fn test_get_download_endpoint() {
     assert_eq!(download_info.status, crate::types::Status::Queued);
     assert_eq!(download_info.priority, crate::types::Priority::Normal);
     assert_eq!(download_info.size_bytes, 1024 * 1024 * 100);
+    assert_eq!(
+        download_info.incomplete_path,
+        downloader
+            .config
+            .download
+            .temp_dir
+            .join(format!("download_{}", download_id)),
+        "incomplete_path should default to the configured temp_dir"
+    );
 
     println!("✅ get_download endpoint test (existing download) passed!");
     println!("   - Download ID: {}", download_info.id);
@@ -1267,6 +1277,72 @@ async fn test_reprocess_download_endpoint() {
     println!("   - Returns 404 with 'not_found' for non-existent downloads");
 }
 
+#[tokio::test]
+async fn test_reprocess_download_with_stage() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt; // for oneshot()
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let download_id = downloader
+        .add_nzb_content(
+            crate::downloader::test_helpers::SAMPLE_NZB.as_bytes(),
+            "stage.nzb",
+            crate::types::DownloadOptions {
+                post_process: Some(crate::config::PostProcess::None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let download_path = downloader
+        .config
+        .temp_dir()
+        .join(format!("download_{}", download_id));
+    std::fs::create_dir_all(&download_path).unwrap();
+
+    // A re-runnable stage starts reprocessing
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!(
+            "/downloads/{}/reprocess?stage=cleanup",
+            download_id
+        ))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    // The download stage can't be re-run from the temp folder
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!(
+            "/downloads/{}/reprocess?stage=download",
+            download_id
+        ))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(response_json["error"]["code"], "invalid_stage");
+
+    // Unknown stage names are rejected by the query extractor
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/downloads/{}/reprocess?stage=bogus", download_id))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_reextract_download_endpoint() {
     use axum::body::Body;
@@ -1415,3 +1491,874 @@ async fn test_reextract_download_endpoint() {
     println!("   - Returns 404 with 'files_not_found' when download files are missing");
     println!("   - Returns 404 with 'not_found' for non-existent downloads");
 }
+
+#[tokio::test]
+async fn test_set_download_speed_limit_endpoint() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt; // for oneshot()
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let download_id = downloader
+        .add_nzb_content(
+            crate::downloader::test_helpers::SAMPLE_NZB.as_bytes(),
+            "limited.nzb",
+            crate::types::DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let request = Request::builder()
+        .method("PUT")
+        .uri(format!("/downloads/{}/speed-limit", download_id))
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"limit_bps": 1048576}"#))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        downloader.download_speed_limiter(download_id).get_limit(),
+        Some(1_048_576)
+    );
+
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/downloads/99999/speed-limit")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"limit_bps": 1048576}"#))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(response_json["error"]["code"], "not_found");
+}
+
+#[tokio::test]
+async fn test_check_download_completion_endpoint() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "check-api",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let config = downloader.config.clone();
+    let app = create_router(downloader.clone(), config);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/downloads/{}/check?sample_size=1", id.0))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["download_id"], id.0);
+    assert_eq!(json["sampled_articles"], 1);
+    assert!(json["servers"].as_array().unwrap().is_empty());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/downloads/999999/check")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_rename_download_endpoint() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let download_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "obfuscated",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let rename = |uri: String, body: &'static str| {
+        Request::builder()
+            .method("PUT")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(rename(
+            format!("/downloads/{}/name", download_id),
+            r#"{"name": "Movie.Name.2024"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(download.name, "Movie.Name.2024");
+
+    let response = app
+        .clone()
+        .oneshot(rename(
+            format!("/downloads/{}/name", download_id),
+            r#"{"name": "  "}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(response_json["error"]["code"], "validation_error");
+
+    let response = app
+        .oneshot(rename(
+            "/downloads/99999/name".to_string(),
+            r#"{"name": "Movie.Name.2024"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_add_download_password_field_keeps_nzb_password() {
+    use axum::http::header;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    // SAMPLE_NZB carries <meta type="password">testpass123</meta>
+    let boundary = "----PasswordBoundary";
+    let body = format!(
+        "--{boundary}\r\n\
+         Content-Disposition: form-data; name=\"file\"; filename=\"test.nzb\"\r\n\
+         Content-Type: application/x-nzb\r\n\
+         \r\n\
+         {SAMPLE_NZB}\r\n\
+         --{boundary}\r\n\
+         Content-Disposition: form-data; name=\"options\"\r\n\
+         \r\n\
+         {{\"password\":\"ignored\"}}\r\n\
+         --{boundary}\r\n\
+         Content-Disposition: form-data; name=\"password\"\r\n\
+         \r\n\
+         first;second\r\n\
+         --{boundary}--\r\n"
+    );
+    let request = Request::builder()
+        .method("POST")
+        .uri("/downloads")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let download_id = DownloadId(response_json["id"].as_i64().unwrap());
+
+    let passwords = downloader
+        .db
+        .get_download_passwords(download_id)
+        .await
+        .unwrap();
+    assert_eq!(passwords.password.as_deref(), Some("first;second"));
+    assert_eq!(passwords.nzb_password.as_deref(), Some("testpass123"));
+}
+
+/// Build a multipart body from `(field, filename, value)` parts
+fn multipart_body(boundary: &str, parts: &[(&str, Option<&str>, &str)]) -> String {
+    let mut body = String::new();
+    for (field, filename, value) in parts {
+        body.push_str(&format!("--{boundary}\r\n"));
+        match filename {
+            Some(filename) => body.push_str(&format!(
+                "Content-Disposition: form-data; name=\"{field}\"; filename=\"{filename}\"\r\n\
+                 Content-Type: application/x-nzb\r\n"
+            )),
+            None => body.push_str(&format!(
+                "Content-Disposition: form-data; name=\"{field}\"\r\n"
+            )),
+        }
+        body.push_str(&format!("\r\n{value}\r\n"));
+    }
+    body.push_str(&format!("--{boundary}--\r\n"));
+    body
+}
+
+async fn post_multipart(
+    app: axum::Router,
+    parts: &[(&str, Option<&str>, &str)],
+) -> (StatusCode, serde_json::Value) {
+    use axum::http::header;
+
+    let boundary = "----UploadBoundary";
+    let request = Request::builder()
+        .method("POST")
+        .uri("/downloads")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(multipart_body(boundary, parts)))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_add_download_multiple_files_with_form_fields() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let (status, json) = post_multipart(
+        app,
+        &[
+            ("file", Some("first.nzb"), SAMPLE_NZB),
+            ("file", Some("second.nzb"), SAMPLE_NZB),
+            ("options", None, r#"{"category":"tv","priority":"low"}"#),
+            ("category", None, "movies"),
+            ("priority", None, "high"),
+            ("paused", None, "true"),
+        ],
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::CREATED, "{json}");
+    let ids: Vec<i64> = json["ids"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| id.as_i64().unwrap())
+        .collect();
+    assert_eq!(ids.len(), 2);
+    assert_eq!(json["results"][0]["file"], "first.nzb");
+    assert_eq!(json["results"][1]["id"], ids[1]);
+
+    for id in ids {
+        let download = downloader
+            .db
+            .get_download(DownloadId(id))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(download.category.as_deref(), Some("movies"));
+        assert_eq!(download.priority, 1);
+        assert_eq!(download.status, crate::types::Status::Paused.to_i32());
+    }
+}
+
+#[tokio::test]
+async fn test_add_download_multiple_files_reports_each_failure() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+
+    let (status, json) = post_multipart(
+        create_router(downloader.clone(), config.clone()),
+        &[
+            ("file", Some("good.nzb"), SAMPLE_NZB),
+            ("file", Some("bad.nzb"), "not an nzb"),
+        ],
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{json}");
+    assert_eq!(json["ids"].as_array().unwrap().len(), 1);
+    assert!(json["results"][0]["id"].is_i64());
+    assert_eq!(json["results"][1]["file"], "bad.nzb");
+    assert_eq!(json["results"][1]["error"]["code"], "nzb_processing_failed");
+
+    // With nothing added the first failure decides the status
+    let (status, json) = post_multipart(
+        create_router(downloader.clone(), config),
+        &[
+            ("file", Some("bad1.nzb"), "not an nzb"),
+            ("file", Some("bad2.nzb"), "still not an nzb"),
+        ],
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(json["error"]["code"], "nzb_processing_failed");
+    assert_eq!(json["results"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_add_download_splits_media_files() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let files: String = ["Show.S01E01.mkv", "Show.S01E02.mkv"]
+        .iter()
+        .map(|filename| {
+            format!(
+                r#"<file poster="p" date="1" subject="&quot;{filename}&quot; yEnc (1/1)"><groups><group>a.b.test</group></groups><segments><segment bytes="1000" number="1">{filename}@example.com</segment></segments></file>"#
+            )
+        })
+        .collect();
+    let nzb = format!(r#"<nzb xmlns="http://www.newzbin.com/DTD/2003/nzb">{files}</nzb>"#);
+
+    let (status, json) = post_multipart(
+        create_router(downloader.clone(), config),
+        &[
+            ("file", Some("Show.S01.nzb"), &nzb),
+            ("split_media_files", None, "true"),
+        ],
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::CREATED, "{json}");
+    let results = json["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    for (result, name) in results.iter().zip(["Show.S01E01", "Show.S01E02"]) {
+        assert_eq!(result["file"], "Show.S01.nzb");
+        let download = downloader
+            .db
+            .get_download(DownloadId(result["id"].as_i64().unwrap()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(download.name, name);
+    }
+}
+
+#[tokio::test]
+async fn test_add_download_rejects_invalid_form_fields() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+
+    for (field, value, code) in [
+        ("priority", "urgent", "invalid_priority"),
+        ("paused", "maybe", "invalid_paused"),
+        ("split_media_files", "maybe", "invalid_split_media_files"),
+    ] {
+        let (status, json) = post_multipart(
+            create_router(downloader.clone(), config.clone()),
+            &[("file", Some("test.nzb"), SAMPLE_NZB), (field, None, value)],
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["error"]["code"], code);
+    }
+
+    // Numeric priorities and unpaused uploads are accepted
+    let (status, json) = post_multipart(
+        create_router(downloader.clone(), config),
+        &[
+            ("file", Some("test.nzb"), SAMPLE_NZB),
+            ("priority", None, "2"),
+            ("paused", None, "false"),
+        ],
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{json}");
+    let download = downloader
+        .db
+        .get_download(DownloadId(json["id"].as_i64().unwrap()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(download.priority, 2);
+    assert_ne!(download.status, crate::types::Status::Paused.to_i32());
+}
+
+#[tokio::test]
+async fn test_held_duplicate_accept_and_discard() {
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+
+    let existing = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "original",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    // Hold two copies the way DuplicateAction::Ask does
+    let mut held = Vec::new();
+    for name in ["copy-1", "copy-2"] {
+        let id = downloader
+            .add_nzb_content(SAMPLE_NZB.as_bytes(), name, DownloadOptions::default())
+            .await
+            .unwrap();
+        downloader.remove_from_queue(id).await;
+        downloader
+            .db
+            .update_status(id, crate::types::Status::HeldDuplicate.to_i32())
+            .await
+            .unwrap();
+        downloader
+            .db
+            .set_held_duplicate(&crate::db::HeldDuplicate {
+                download_id: id.0,
+                method: crate::config::DuplicateMethod::NzbHash.to_i32(),
+                existing_id: existing.0,
+                existing_name: "original".to_string(),
+            })
+            .await
+            .unwrap();
+        held.push(id);
+    }
+
+    let post = |uri: String| {
+        let app = create_router(downloader.clone(), config.clone());
+        async move {
+            app.oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+        }
+    };
+
+    // The payload says what the held download duplicates
+    let response = create_router(downloader.clone(), config.clone())
+        .oneshot(
+            Request::builder()
+                .uri(format!("/downloads/{}", held[0]))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "held_duplicate");
+    assert_eq!(json["duplicate"]["method"], "nzb_hash");
+    assert_eq!(json["duplicate"]["existing_id"], existing.0);
+    assert_eq!(json["duplicate"]["existing_name"], "original");
+
+    assert_eq!(
+        post(format!("/downloads/{}/duplicate/accept", held[0])).await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        post(format!("/downloads/{}/duplicate/discard", held[1])).await,
+        StatusCode::NO_CONTENT
+    );
+
+    let accepted = downloader.db.get_download(held[0]).await.unwrap().unwrap();
+    assert_eq!(accepted.status, crate::types::Status::Queued.to_i32());
+    assert!(downloader.db.get_download(held[1]).await.unwrap().is_none());
+
+    // Neither is held any more
+    assert_eq!(
+        post(format!("/downloads/{}/duplicate/accept", held[0])).await,
+        StatusCode::CONFLICT
+    );
+    assert_eq!(
+        post(format!("/downloads/{}/duplicate/discard", held[1])).await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_submit_password() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+
+    let queued = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "queued", DownloadOptions::default())
+        .await
+        .unwrap();
+    let waiting = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "locked", DownloadOptions::default())
+        .await
+        .unwrap();
+    downloader.remove_from_queue(waiting).await;
+    downloader
+        .db
+        .update_status(waiting, crate::types::Status::WaitingForPassword.to_i32())
+        .await
+        .unwrap();
+    let download = downloader.db.get_download(waiting).await.unwrap().unwrap();
+    std::fs::create_dir_all(download.incomplete_path(&config.download.temp_dir)).unwrap();
+
+    let submit = |id: DownloadId, password: &str| {
+        let app = create_router(downloader.clone(), config.clone());
+        let body = serde_json::json!({ "password": password }).to_string();
+        async move {
+            app.oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/downloads/{}/password", id))
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+        }
+    };
+
+    assert_eq!(submit(waiting, "").await, StatusCode::BAD_REQUEST);
+    assert_eq!(submit(queued, "secret").await, StatusCode::CONFLICT);
+    assert_eq!(
+        submit(DownloadId(9999), "secret").await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(submit(waiting, "secret").await, StatusCode::NO_CONTENT);
+
+    let passwords = downloader.db.get_download_passwords(waiting).await.unwrap();
+    assert_eq!(passwords.password.as_deref(), Some("secret"));
+    // The NZB's password is kept
+    assert_eq!(passwords.nzb_password.as_deref(), Some("testpass123"));
+}
+
+#[tokio::test]
+async fn test_get_download_artifacts() {
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+
+    let id = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+    let download_path = downloader
+        .config
+        .download
+        .temp_dir
+        .join(format!("download_{}", id.0));
+    std::fs::create_dir_all(&download_path).unwrap();
+    std::fs::write(download_path.join("movie.mkv"), b"12345").unwrap();
+
+    let get = |uri: String| {
+        let app = create_router(downloader.clone(), config.clone());
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            (status, json)
+        }
+    };
+
+    let (status, json) = get(format!("/downloads/{}/artifacts", id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["id"], id.0);
+    assert_eq!(json["files"][0]["path"], "movie.mkv");
+    assert_eq!(json["files"][0]["size_bytes"], 5);
+    assert_eq!(json["total_bytes"], 5);
+
+    let (status, _) = get("/downloads/999/artifacts".to_string()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_validate_download_reports_without_queueing() {
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode, header};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let existing = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "existing.nzb",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let boundary = "----validate";
+    let body = format!(
+        "--{boundary}\r\n\
+         Content-Disposition: form-data; name=\"file\"; filename=\"test.nzb\"\r\n\
+         \r\n\
+         {SAMPLE_NZB}\r\n\
+         --{boundary}\r\n\
+         Content-Disposition: form-data; name=\"category\"\r\n\
+         \r\n\
+         unknown\r\n\
+         --{boundary}--\r\n"
+    );
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/downloads/validate")
+                .header(
+                    header::CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={boundary}"),
+                )
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["name"], "test.nzb");
+    assert_eq!(report["job_name"], "Test Download");
+    assert_eq!(report["has_password"], true);
+    assert_eq!(report["size_bytes"], 768000 + 512000);
+    assert_eq!(report["segment_count"], 2);
+    assert_eq!(report["files"][0]["segments"], 2);
+    assert_eq!(report["duplicate"]["existing_id"], existing.0);
+    assert_eq!(report["category"], "unknown");
+    assert_eq!(report["category_configured"], false);
+    assert_eq!(report["warnings"].as_array().unwrap().len(), 1);
+
+    // Only the download added up front exists
+    assert_eq!(downloader.db.list_downloads().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_validate_download_rejects_invalid_nzb() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode, header};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader, config);
+
+    let boundary = "----validate";
+    let body = format!(
+        "--{boundary}\r\n\
+         Content-Disposition: form-data; name=\"file\"; filename=\"broken.nzb\"\r\n\
+         \r\n\
+         not an nzb\r\n\
+         --{boundary}--\r\n"
+    );
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/downloads/validate")
+                .header(
+                    header::CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={boundary}"),
+                )
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+async fn post_urls(
+    app: axum::Router,
+    content_type: &str,
+    body: String,
+) -> (StatusCode, serde_json::Value) {
+    use axum::http::header;
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/downloads/urls")
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_add_download_urls_reports_each_url() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+
+    let mock_server = MockServer::start().await;
+    for nzb in ["/first.nzb", "/second.nzb"] {
+        Mock::given(method("GET"))
+            .and(path(nzb))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(SAMPLE_NZB))
+            .mount(&mock_server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/missing.nzb"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+
+    let first = format!("{}/first.nzb", mock_server.uri());
+    let missing = format!("{}/missing.nzb", mock_server.uri());
+    let second = format!("{}/second.nzb", mock_server.uri());
+    let (status, json) = post_urls(
+        create_router(downloader.clone(), config.clone()),
+        "application/json",
+        serde_json::json!({
+            "urls": [first, missing, second],
+            "options": {"category": "movies"}
+        })
+        .to_string(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::CREATED, "{json}");
+    assert_eq!(json["ids"].as_array().unwrap().len(), 2);
+    assert_eq!(json["results"][0]["url"], first);
+    assert_eq!(json["results"][1]["url"], missing);
+    assert!(json["results"][1]["error"]["code"].is_string());
+    assert_eq!(json["results"][2]["url"], second);
+
+    let id = DownloadId(json["results"][2]["id"].as_i64().unwrap());
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.category.as_deref(), Some("movies"));
+
+    // With nothing added the first failure decides the status
+    let (status, json) = post_urls(
+        create_router(downloader.clone(), config),
+        "application/json",
+        serde_json::json!({"urls": missing}).to_string(),
+    )
+    .await;
+    assert_ne!(status, StatusCode::CREATED);
+    assert!(json["error"]["code"].is_string());
+    assert_eq!(json["results"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_add_download_urls_accepts_text_list() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/one.nzb"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(SAMPLE_NZB))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Blank lines, comments and repeats are skipped
+    let url = format!("{}/one.nzb", mock_server.uri());
+    let (status, json) = post_urls(
+        create_router(downloader.clone(), config.clone()),
+        "text/plain; charset=utf-8",
+        format!("# from the forum\n\n  {url}  \r\n{url}\n"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{json}");
+    assert_eq!(json["results"].as_array().unwrap().len(), 1);
+    assert_eq!(json["results"][0]["url"], url);
+
+    let (status, json) = post_urls(
+        create_router(downloader, config),
+        "text/plain",
+        "\n# nothing here\n".to_string(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["error"]["code"], "missing_urls");
+}
+
+#[tokio::test]
+async fn test_add_download_url_deferred() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    // Nothing is fetched, so an unreachable URL is still added
+    let url = "http://invalid-nonexistent-domain-12345.com/getnzb?id=abc";
+    let request = Request::builder()
+        .method("POST")
+        .uri("/downloads/url")
+        .header(axum::http::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            serde_json::json!({"url": url, "defer": true, "name": "Later"}).to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    let request = Request::builder()
+        .uri(format!("/downloads/{}", json["id"]))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let info: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(info["name"], "Later");
+    assert_eq!(info["deferred_url"], url);
+    assert_eq!(info["size_bytes"], 0);
+}
@@ -1,4 +1,6 @@
 use super::*;
+use crate::downloader::test_helpers::SAMPLE_NZB;
+use crate::types::{DownloadId, DownloadOptions, Priority, Status};
 
 #[tokio::test]
 async fn test_get_history_endpoint() {
@@ -271,6 +273,66 @@ async fn test_get_history_endpoint() {
     println!("   - Limit boundary values handled correctly");
 }
 
+#[tokio::test]
+async fn test_get_history_search() {
+    use crate::db::NewHistoryEntry;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let now = chrono::Utc::now().timestamp();
+    for (name, status) in [
+        ("Show.S01E01.720p", 4),
+        ("Show.S01E02.720p", 5),
+        ("Some.Movie.2024", 4),
+    ] {
+        downloader
+            .db
+            .insert_history(&NewHistoryEntry {
+                name: name.to_string(),
+                category: None,
+                destination: None,
+                status,
+                size_bytes: 1024,
+                download_time_secs: 60,
+                completed_at: now,
+            })
+            .await
+            .unwrap();
+    }
+
+    let get = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        }
+    };
+
+    let json = get("/history?search=show%20s01e0").await;
+    assert_eq!(json["total"], 2);
+    assert_eq!(json["items"].as_array().unwrap().len(), 2);
+
+    let json = get("/history?search=show&status=failed").await;
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["items"][0]["name"], "Show.S01E02.720p");
+
+    // A blank search lists everything
+    let json = get("/history?search=%20").await;
+    assert_eq!(json["total"], 3);
+}
+
 #[tokio::test]
 async fn test_clear_history_endpoint() {
     use crate::db::NewHistoryEntry;
@@ -614,3 +676,66 @@ async fn test_clear_history_endpoint() {
     println!("   - Combines both filters (before + status) correctly");
     println!("   - Returns 400 for invalid status filter");
 }
+
+#[tokio::test]
+async fn test_retry_history_endpoint() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let original = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "retry-api",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+    downloader
+        .db
+        .update_status(original, Status::Failed.to_i32())
+        .await
+        .unwrap();
+    let history_id = downloader
+        .db
+        .record_download_history(original)
+        .await
+        .unwrap();
+
+    let config = downloader.config.clone();
+    let app = create_router(downloader.clone(), config);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/history/{}/retry", history_id))
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"priority": "high"}"#))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let retried = DownloadId(json["id"].as_i64().unwrap());
+    let download = downloader.db.get_download(retried).await.unwrap().unwrap();
+    assert_eq!(download.priority, Priority::High as i32);
+
+    // No body keeps the original options
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/history/{}/retry", history_id))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/history/9999/retry")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -10,12 +10,14 @@ use std::time::Duration;
 use tempfile::tempdir;
 use tower::ServiceExt;
 
+mod batches;
 mod categories;
 mod config;
 mod downloads;
 mod history;
 mod queue;
 mod servers;
+mod stats;
 mod system;
 
 /// Helper to create a test UsenetDownloader instance wrapped in Arc
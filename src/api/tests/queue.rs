@@ -411,3 +411,161 @@ async fn test_queue_stats_endpoint() {
     println!("   - Speed limit is reflected in response");
     println!("   - Total size and progress are calculated correctly");
 }
+
+#[tokio::test]
+async fn test_queue_changes_refused_in_read_only_mode() {
+    let (writer, _temp_dir) = create_test_downloader().await;
+
+    let mut config = (*writer.config).clone();
+    config.read_only = true;
+    let standby = Arc::new(UsenetDownloader::new(config.clone()).await.unwrap());
+    let app = create_router(standby, Arc::new(config));
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/queue/pause")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["error"]["code"], "read_only");
+
+    // Reads are still served
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/downloads")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_queue_export_and_import_endpoints() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (source, _source_dir) = create_test_downloader().await;
+    source
+        .add_nzb_content(
+            crate::downloader::test_helpers::SAMPLE_NZB.as_bytes(),
+            "moved",
+            crate::types::DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+    let config = Arc::new((*source.config).clone());
+    let app = create_router(source.clone(), config);
+
+    let request = Request::builder()
+        .uri("/queue/export")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/zip"
+    );
+    let archive = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    let (target, _target_dir) = create_test_downloader().await;
+    let config = Arc::new((*target.config).clone());
+    let app = create_router(target.clone(), config);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/queue/import")
+        .header("content-type", "application/zip")
+        .body(Body::from(archive))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let ids = json["ids"].as_array().unwrap();
+    assert_eq!(ids.len(), 1);
+    let download = target
+        .db
+        .get_download(crate::types::DownloadId(ids[0].as_i64().unwrap()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(download.name, "moved");
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/queue/import")
+        .header("content-type", "application/zip")
+        .body(Body::from("not a zip"))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_pause_queue_post_processing_scope() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let id = downloader
+        .add_nzb_content(
+            crate::downloader::test_helpers::SAMPLE_NZB.as_bytes(),
+            "test.nzb",
+            crate::types::DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/queue/pause?scope=post_processing")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    // Downloading goes on
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.status, crate::types::Status::Queued.to_i32());
+    assert!(downloader.db.is_post_processing_paused().await.unwrap());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/queue/resume?scope=post_processing")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(!downloader.db.is_post_processing_paused().await.unwrap());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/queue/pause?scope=everything")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
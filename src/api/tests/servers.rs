@@ -27,6 +27,10 @@ async fn test_post_servers_test_endpoint() {
         connections: 10,
         priority: 0,
         pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
     };
 
     let request = Request::builder()
@@ -92,6 +96,10 @@ async fn test_get_servers_test_endpoint() {
         connections: 10,
         priority: 0,
         pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
     });
     config.servers.push(crate::config::ServerConfig {
         host: "news2.example.com".to_string(),
@@ -102,6 +110,10 @@ async fn test_get_servers_test_endpoint() {
         connections: 5,
         priority: 1,
         pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
     });
 
     // Create a new downloader with the modified config
@@ -171,3 +183,351 @@ async fn test_get_servers_test_endpoint() {
     println!("   - Tests all configured servers");
     println!("   - Each result includes server name and test result");
 }
+
+#[tokio::test]
+async fn test_get_server_trace_endpoint() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt; // for oneshot()
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut config = (*downloader.get_config()).clone();
+    config.servers.push(crate::config::ServerConfig {
+        host: "news1.example.com".to_string(),
+        port: 563,
+        tls: true,
+        username: None,
+        password: None,
+        connections: 1,
+        priority: 0,
+        pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
+    });
+    config.logging.nntp_trace = true;
+
+    let downloader = Arc::new(crate::UsenetDownloader::new(config.clone()).await.unwrap());
+    let app = create_router(downloader, Arc::new(config));
+
+    // Configured server: empty trace until commands are issued
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/servers/0/trace")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let entries: Vec<crate::types::NntpTraceEntry> = serde_json::from_slice(&body).unwrap();
+    assert!(entries.is_empty());
+
+    // Unknown server index
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/servers/5/trace")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["error"]["code"], "not_found");
+}
+
+#[tokio::test]
+async fn test_set_server_speed_limit_endpoint() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt; // for oneshot()
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut config = (*downloader.get_config()).clone();
+    config.servers.push(crate::config::ServerConfig {
+        host: "news1.example.com".to_string(),
+        port: 563,
+        tls: true,
+        username: None,
+        password: None,
+        connections: 1,
+        priority: 0,
+        pipeline_depth: 10,
+        speed_limit_bps: Some(5_000_000),
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
+    });
+
+    let downloader = Arc::new(crate::UsenetDownloader::new(config.clone()).await.unwrap());
+    let app = create_router(downloader.clone(), Arc::new(config));
+
+    assert_eq!(
+        downloader.servers.current().speed_limiters[0].get_limit(),
+        Some(5_000_000)
+    );
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri("/servers/0/speed-limit")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"limit_bps": 1000000}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        downloader.servers.current().speed_limiters[0].get_limit(),
+        Some(1_000_000)
+    );
+
+    // Unknown server index
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri("/servers/5/speed-limit")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"limit_bps": null}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_speed_test_unknown_server_returns_404() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt; // for oneshot()
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = downloader.get_config();
+    let app = create_router(downloader, config);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/servers/0/speedtest")
+                .header("content-type", "application/json")
+                .body(Body::from("{}"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["error"]["code"], "not_found");
+}
+
+#[tokio::test]
+async fn test_server_crud_endpoints() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt; // for oneshot()
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let json_request = |method: &str, uri: &str, body: serde_json::Value| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let server = serde_json::json!({
+        "host": "news.example.com",
+        "port": 563,
+        "tls": true,
+        "username": "user",
+        "password": "secret",
+        "connections": 8
+    });
+
+    let response = app
+        .clone()
+        .oneshot(json_request("POST", "/servers", server.clone()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["id"], 0);
+
+    // Invalid configuration names the field
+    let mut invalid = server.clone();
+    invalid["connections"] = serde_json::json!(0);
+    let response = app
+        .clone()
+        .oneshot(json_request("POST", "/servers", invalid))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Listing redacts the password, and the redacted value keeps it on update
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/servers")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let mut listed: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_ne!(listed[0]["password"], "secret");
+
+    listed[0]["connections"] = serde_json::json!(20);
+    let response = app
+        .clone()
+        .oneshot(json_request("PUT", "/servers/0", listed[0].clone()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let servers = downloader.get_servers();
+    assert_eq!(servers[0].connections, 20);
+    assert_eq!(servers[0].password.as_deref(), Some("secret"));
+
+    let response = app
+        .clone()
+        .oneshot(json_request("PUT", "/servers/3", server))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri("/servers/0")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(downloader.get_servers().is_empty());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri("/servers/0")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_disable_and_enable_server_endpoints() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt; // for oneshot()
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader
+        .add_server(crate::config::ServerConfig {
+            host: "news.example.com".to_string(),
+            port: 563,
+            tls: true,
+            username: None,
+            password: None,
+            connections: 8,
+            priority: 0,
+            pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
+        })
+        .await
+        .unwrap();
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let request = |method: &str, uri: &str| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap()
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/servers/0/disable?duration=3600"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json(response).await;
+    assert_eq!(json["enabled"], false);
+    assert!(json["disabled_until"].is_string());
+
+    let response = app
+        .clone()
+        .oneshot(request("GET", "/servers/status"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json(response).await;
+    assert_eq!(json[0]["host"], "news.example.com");
+    assert_eq!(json[0]["enabled"], false);
+
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/servers/0/enable"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = read_json(response).await;
+    assert_eq!(json["enabled"], true);
+    assert!(json.get("disabled_until").is_none());
+
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/servers/4/disable"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,74 @@
+use super::*;
+
+#[tokio::test]
+async fn test_get_stats_endpoint() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt; // for oneshot()
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader
+        .db
+        .add_usage(
+            &chrono::Utc::now().format("%Y-%m-%d").to_string(),
+            "news.example.com",
+            "movies",
+            4096,
+        )
+        .await
+        .unwrap();
+
+    let config = downloader.get_config();
+    let app = create_router(downloader, config);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/stats?period=month")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let stats: crate::types::UsageStats = serde_json::from_slice(&body).unwrap();
+    assert_eq!(stats.period, crate::types::StatsPeriod::Month);
+    assert_eq!(stats.total_bytes, 4096);
+    assert_eq!(stats.daily.len(), 30);
+    assert_eq!(stats.servers[0].server, "news.example.com");
+    assert_eq!(stats.categories[0].category.as_deref(), Some("movies"));
+
+    // Defaults to today
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/stats")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["period"], "day");
+    assert_eq!(json["daily"].as_array().unwrap().len(), 1);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/stats?period=decade")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
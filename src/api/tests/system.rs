@@ -124,6 +124,8 @@ async fn test_scheduler_endpoints() {
         end_time: "06:00".to_string(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        start_date: None,
+        end_date: None,
     };
 
     let request = Request::builder()
@@ -166,6 +168,8 @@ async fn test_scheduler_endpoints() {
             limit_bps: 1_000_000,
         },
         enabled: true,
+        start_date: None,
+        end_date: None,
     };
 
     let request = Request::builder()
@@ -232,6 +236,8 @@ async fn test_scheduler_endpoints() {
         end_time: "08:00".to_string(), // Changed to 8 AM
         action: ScheduleAction::Unlimited,
         enabled: false, // Disabled
+        start_date: None,
+        end_date: None,
     };
 
     let request = Request::builder()
@@ -296,6 +302,8 @@ async fn test_scheduler_endpoints() {
         end_time: "06:00".to_string(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        start_date: None,
+        end_date: None,
     };
 
     let request = Request::builder()
@@ -416,6 +424,7 @@ async fn test_duplicate_detection_via_api() {
                 database_path: temp_dir.path().join("test.db"),
                 schedule_rules: vec![],
                 categories: std::collections::HashMap::new(),
+                ..Default::default()
             },
             download: crate::config::DownloadConfig {
                 download_dir: temp_dir.path().join("downloads"),
@@ -515,6 +524,7 @@ async fn test_duplicate_detection_via_api() {
                 database_path: temp_dir.path().join("test.db"),
                 schedule_rules: vec![],
                 categories: std::collections::HashMap::new(),
+                ..Default::default()
             },
             download: crate::config::DownloadConfig {
                 download_dir: temp_dir.path().join("downloads"),
@@ -659,6 +669,7 @@ async fn test_duplicate_detection_via_api() {
                 database_path: temp_dir.path().join("test.db"),
                 schedule_rules: vec![],
                 categories: std::collections::HashMap::new(),
+                ..Default::default()
             },
             download: crate::config::DownloadConfig {
                 download_dir: temp_dir.path().join("downloads"),
@@ -746,6 +757,7 @@ async fn test_duplicate_detection_via_api() {
                 database_path: temp_dir.path().join("test.db"),
                 schedule_rules: vec![],
                 categories: std::collections::HashMap::new(),
+                ..Default::default()
             },
             download: crate::config::DownloadConfig {
                 download_dir: temp_dir.path().join("downloads"),
@@ -1033,6 +1045,46 @@ async fn test_capabilities_with_no_servers_reflects_noop_parity() {
     );
 }
 
+#[tokio::test]
+async fn test_system_capabilities_reports_noop_parity_details() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let temp_dir = tempdir().unwrap();
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.servers = vec![];
+    config.tools.par2_path = None;
+    config.tools.search_path = false;
+
+    let downloader = crate::UsenetDownloader::new(config.clone()).await.unwrap();
+    let app = create_router(Arc::new(downloader), Arc::new(config));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/system/capabilities")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    let parity = &json["parity"];
+    assert_eq!(parity["handler"], "noop");
+    assert_eq!(parity["can_create"], false, "NoOp handler cannot create");
+    assert_eq!(parity["multithreaded"], false);
+    assert!(parity["version"].is_null(), "NoOp handler has no version");
+}
+
 #[tokio::test]
 async fn test_health_endpoint_not_affected_by_authentication() {
     use axum::body::Body;
@@ -1322,3 +1374,514 @@ async fn test_shutdown_with_wrong_method_returns_405() {
         "GET /shutdown should return 405 Method Not Allowed"
     );
 }
+
+#[tokio::test]
+async fn test_drain_endpoint_starts_drain_and_delete_cancels_it() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = downloader.config.clone();
+    let app = create_router(downloader.clone(), config);
+    let mut events = downloader.subscribe();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/system/drain")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert!(downloader.is_draining());
+
+    let drained = tokio::time::timeout(Duration::from_secs(2), async {
+        loop {
+            if let Ok(crate::types::Event::Drained) = events.recv().await {
+                return;
+            }
+        }
+    })
+    .await;
+    assert!(drained.is_ok(), "idle downloader should emit Drained");
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri("/system/drain")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(!downloader.is_draining());
+}
+
+#[tokio::test]
+async fn test_repeated_drain_requests_emit_drained_once() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = downloader.config.clone();
+    let app = create_router(downloader.clone(), config);
+    let mut events = downloader.subscribe();
+
+    // Held so the first drain is still waiting when the second request arrives
+    let guard = downloader.post_processing_guard();
+    for _ in 0..2 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/system/drain")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+    }
+    drop(guard);
+
+    let mut drained = 0;
+    while let Ok(Ok(event)) = tokio::time::timeout(Duration::from_millis(500), events.recv()).await
+    {
+        if matches!(event, crate::types::Event::Drained) {
+            drained += 1;
+        }
+    }
+    assert_eq!(drained, 1, "one drain must emit Drained exactly once");
+}
+
+/// Build a router whose downloader uses `configure` to adjust its config before wrapping
+async fn create_health_test_app(
+    configure: impl FnOnce(&mut Config),
+) -> (axum::Router, tempfile::TempDir) {
+    let (mut downloader, temp_dir) =
+        crate::downloader::test_helpers::create_test_downloader().await;
+    let mut config = (*downloader.config).clone();
+    configure(&mut config);
+    for server in &config.servers {
+        downloader.add_server(server.clone()).await.unwrap();
+    }
+    downloader.config = Arc::new(config);
+    let config = downloader.config.clone();
+    (create_router(Arc::new(downloader), config), temp_dir)
+}
+
+async fn get_health_json(app: axum::Router, uri: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+fn failed_check_names(json: &serde_json::Value) -> Vec<String> {
+    json["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|c| c["ok"] == false)
+        .map(|c| c["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_health_live_returns_ok_without_checks_by_default() {
+    let (app, _temp_dir) = create_health_test_app(|_| {}).await;
+
+    let (status, json) = get_health_json(app, "/health/live").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["ok"], true);
+    assert!(json["checks"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_health_live_can_check_database() {
+    let (app, _temp_dir) = create_health_test_app(|config| {
+        config.server.api.health.live_check_database = true;
+    })
+    .await;
+
+    let (status, json) = get_health_json(app, "/health/live").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["checks"][0]["name"], "database");
+    assert_eq!(json["checks"][0]["ok"], true);
+}
+
+#[tokio::test]
+async fn test_health_ready_fails_without_servers() {
+    let (app, _temp_dir) = create_health_test_app(|_| {}).await;
+
+    let (status, json) = get_health_json(app, "/health/ready").await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(json["ok"], false);
+    assert_eq!(
+        failed_check_names(&json),
+        vec!["servers"],
+        "only the server check should fail when no servers are configured"
+    );
+}
+
+#[tokio::test]
+async fn test_health_ready_passes_with_configured_server() {
+    let (app, _temp_dir) = create_health_test_app(|config| {
+        config.servers = vec![crate::config::ServerConfig {
+            host: "news.example.com".to_string(),
+            port: 563,
+            tls: true,
+            username: None,
+            password: None,
+            connections: 1,
+            priority: 0,
+            pipeline_depth: 1,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
+        }];
+    })
+    .await;
+
+    let (status, json) = get_health_json(app, "/health/ready").await;
+
+    assert_eq!(status, StatusCode::OK, "body: {json}");
+    let names: Vec<&str> = json["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["database", "servers", "temp_dir"]);
+}
+
+#[tokio::test]
+async fn test_health_ready_reports_unwritable_temp_dir() {
+    let (app, temp_dir) = create_health_test_app(|config| {
+        config.server.api.health.ready_check_servers = false;
+    })
+    .await;
+    // A temp_dir that is a regular file can never be written into, even as root
+    let temp_path = temp_dir.path().join("temp");
+    std::fs::remove_dir_all(&temp_path).unwrap();
+    std::fs::write(&temp_path, b"not a directory").unwrap();
+
+    let (status, json) = get_health_json(app, "/health/ready").await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(failed_check_names(&json), vec!["temp_dir"]);
+}
+
+#[tokio::test]
+async fn test_health_ready_with_all_checks_disabled_is_ok() {
+    let (app, _temp_dir) = create_health_test_app(|config| {
+        config.server.api.health.ready_check_database = false;
+        config.server.api.health.ready_check_servers = false;
+        config.server.api.health.ready_check_temp_dir = false;
+    })
+    .await;
+
+    let (status, json) = get_health_json(app, "/health/ready").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(json["checks"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_backup_export_and_restore_endpoints() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let request = Request::builder()
+        .uri("/system/backup")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/zip"
+    );
+    let archive = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(archive.starts_with(b"PK"), "response is a zip archive");
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/system/backup")
+        .header("content-type", "application/zip")
+        .body(Body::from(archive))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/system/backup")
+        .header("content-type", "application/zip")
+        .body(Body::from("not a zip"))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["error"]["code"], "invalid_backup");
+}
+
+#[tokio::test]
+async fn test_log_stream_replays_recent_events() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use futures::StreamExt;
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let subscriber = tracing_subscriber::registry().with(crate::log_stream::layer());
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(download_id = 3, "log stream endpoint test event");
+    });
+
+    let request = Request::builder()
+        .uri("/system/log/stream?level=warn")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .contains("text/event-stream")
+    );
+
+    let mut body = response.into_body().into_data_stream();
+    let mut received = String::new();
+    while !received.contains("log stream endpoint test event") {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+            .await
+            .expect("replayed event within 5s")
+            .unwrap()
+            .unwrap();
+        received.push_str(&String::from_utf8_lossy(&chunk));
+    }
+    assert!(received.contains("event: log"));
+    assert!(received.contains(r#""level":"warn""#));
+    assert!(received.contains(r#""download_id":3"#));
+
+    let request = Request::builder()
+        .uri("/system/log/stream?level=loud")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_event_stream_filters_by_type() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use futures::StreamExt;
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let request = Request::builder()
+        .uri("/events?types=queue_paused,%20queue_resumed")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    downloader.emit_event(crate::types::Event::Queued {
+        id: crate::types::DownloadId(1),
+        name: "filtered out".to_string(),
+    });
+    downloader.emit_event(crate::types::Event::QueuePaused);
+
+    let mut body = response.into_body().into_data_stream();
+    let mut received = String::new();
+    while !received.contains("event: queue_paused") {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+            .await
+            .expect("queue_paused event within 5s")
+            .unwrap()
+            .unwrap();
+        received.push_str(&String::from_utf8_lossy(&chunk));
+    }
+    assert!(!received.contains("event: queued"));
+    assert!(!received.contains("filtered out"));
+}
+
+#[tokio::test]
+async fn test_event_stream_sends_envelopes_with_sequence_ids() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use futures::StreamExt;
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let request = Request::builder()
+        .uri("/events?types=queue_paused")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    downloader.emit_event(crate::types::Event::QueuePaused);
+
+    let mut body = response.into_body().into_data_stream();
+    let mut received = String::new();
+    while !received.contains("\n\n") {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+            .await
+            .expect("queue_paused event within 5s")
+            .unwrap()
+            .unwrap();
+        received.push_str(&String::from_utf8_lossy(&chunk));
+    }
+
+    let data = received
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .expect("data line");
+    let envelope: serde_json::Value = serde_json::from_str(data).unwrap();
+    assert_eq!(envelope["version"], 1);
+    assert_eq!(envelope["type"], "queue_paused");
+    assert_eq!(envelope["payload"], serde_json::json!({}));
+    assert!(envelope["timestamp"].is_string());
+    let seq = envelope["seq"].as_u64().unwrap();
+    assert!(received.contains(&format!("id: {}\n", seq)));
+}
+
+#[tokio::test]
+async fn test_event_stream_replays_events_since_sequence_number() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use futures::StreamExt;
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    // Emitted before the client connects
+    let mut envelopes = downloader.subscribe_json();
+    downloader.emit_event(crate::types::Event::QueuePaused);
+    let missed = tokio::time::timeout(std::time::Duration::from_secs(5), envelopes.recv())
+        .await
+        .expect("envelope within 5s")
+        .unwrap();
+
+    let request = Request::builder()
+        .uri(format!("/events?since_seq={}", missed.seq - 1))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut body = response.into_body().into_data_stream();
+    let mut received = String::new();
+    while !received.contains("event: queue_paused") {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+            .await
+            .expect("replayed event within 5s")
+            .unwrap()
+            .unwrap();
+        received.push_str(&String::from_utf8_lossy(&chunk));
+    }
+    assert!(received.contains(&format!("id: {}\n", missed.seq)));
+}
+
+#[tokio::test]
+async fn test_event_stream_coalesces_progress_for_slow_client() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use futures::StreamExt;
+    use tower::ServiceExt;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let config = Arc::new((*downloader.config).clone());
+    let app = create_router(downloader.clone(), config);
+
+    let request = Request::builder()
+        .uri("/events")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The client doesn't read while more progress is sent than its queue holds
+    let mut envelopes = downloader.subscribe_json();
+    for percent in 0..1500 {
+        downloader.emit_event(crate::types::Event::Downloading {
+            id: crate::types::DownloadId(1),
+            percent: percent as f32,
+            speed_bps: 0,
+            failed_articles: None,
+            total_articles: None,
+            health_percent: None,
+        });
+    }
+    downloader.emit_event(crate::types::Event::QueuePaused);
+    loop {
+        let envelope = tokio::time::timeout(std::time::Duration::from_secs(5), envelopes.recv())
+            .await
+            .expect("envelope within 5s")
+            .unwrap();
+        if envelope.event_type == "queue_paused" {
+            break;
+        }
+    }
+
+    let mut body = response.into_body().into_data_stream();
+    let mut received = String::new();
+    while !received.contains("event: queue_paused") {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+            .await
+            .expect("event within 5s")
+            .unwrap()
+            .unwrap();
+        received.push_str(&String::from_utf8_lossy(&chunk));
+    }
+    assert!(
+        !received.contains("event: error"),
+        "the slow client must not lag: {received}"
+    );
+    assert!(received.matches("event: downloading").count() < 1500);
+    assert!(received.contains(r#""percent":1499.0"#));
+}
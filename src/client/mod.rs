@@ -0,0 +1,356 @@
+//! Typed async client for the REST API.
+//!
+//! [`Client`] wraps the endpoints companion tools (CLIs, TUIs, tray apps) need most,
+//! taking and returning the same types the server uses, so requests and responses
+//! can't drift from what the API serves. Its routes are checked against the utoipa
+//! definitions behind `GET /openapi.json` by the crate's tests.
+//!
+//! Available with the `client` feature.
+//!
+//! ```no_run
+//! use usenet_dl::client::Client;
+//! use usenet_dl::{DownloadOptions, Priority};
+//!
+//! # async fn example() -> Result<(), usenet_dl::client::ClientError> {
+//! let client = Client::new("http://localhost:6789")?.with_api_key("secret");
+//!
+//! let nzb = std::fs::read("movie.nzb").expect("NZB file");
+//! let id = client
+//!     .add_nzb(nzb, "movie.nzb", &DownloadOptions::default())
+//!     .await?;
+//! client.set_priority(id, Priority::High).await?;
+//!
+//! for download in client.list_downloads().await? {
+//!     println!("{}: {:.1}%", download.name, download.progress);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::routes::{
+    DeleteDownloadQuery, HistoryQuery, SetBandwidthModeRequest, SetSpeedLimitRequest, StatsQuery,
+};
+use crate::config::Config;
+use crate::types::{
+    BandwidthMode, DownloadId, DownloadInfo, DownloadOptions, HistoryPage, Priority, QueueStats,
+    StatsPeriod, UsageStats,
+};
+use reqwest::{Method, RequestBuilder, Response};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+/// Errors returned by [`Client`]
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// The base URL (or a URL built from it) is invalid
+    #[error("invalid URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+
+    /// The request couldn't be sent or its response couldn't be read
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The API answered with an error status
+    #[error("API error (HTTP {status}): {message}")]
+    Api {
+        /// HTTP status code
+        status: u16,
+        /// Machine-readable error code, e.g. `not_found`, when the API sent one
+        code: Option<String>,
+        /// Human-readable error message
+        message: String,
+    },
+}
+
+/// Result type for [`Client`] calls
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+/// Global speed limit, as returned by [`Client::get_speed_limit`]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct SpeedLimit {
+    /// Speed limit in bytes per second (None = unlimited)
+    pub limit_bps: Option<u64>,
+    /// Token bucket size in bytes (None = one second of the limit)
+    pub burst_bytes: Option<u64>,
+}
+
+/// An API endpoint: method and path template relative to the base URL
+#[derive(Clone, Debug)]
+struct Route {
+    method: Method,
+    path: &'static str,
+}
+
+impl Route {
+    const fn new(method: Method, path: &'static str) -> Self {
+        Self { method, path }
+    }
+}
+
+const LIST_DOWNLOADS: Route = Route::new(Method::GET, "downloads");
+const ADD_DOWNLOAD: Route = Route::new(Method::POST, "downloads");
+const GET_DOWNLOAD: Route = Route::new(Method::GET, "downloads/{id}");
+const DELETE_DOWNLOAD: Route = Route::new(Method::DELETE, "downloads/{id}");
+const ADD_DOWNLOAD_URL: Route = Route::new(Method::POST, "downloads/url");
+const PAUSE_DOWNLOAD: Route = Route::new(Method::POST, "downloads/{id}/pause");
+const RESUME_DOWNLOAD: Route = Route::new(Method::POST, "downloads/{id}/resume");
+const SET_DOWNLOAD_PRIORITY: Route = Route::new(Method::PATCH, "downloads/{id}/priority");
+const PAUSE_QUEUE: Route = Route::new(Method::POST, "queue/pause");
+const RESUME_QUEUE: Route = Route::new(Method::POST, "queue/resume");
+const QUEUE_STATS: Route = Route::new(Method::GET, "queue/stats");
+const GET_HISTORY: Route = Route::new(Method::GET, "history");
+const GET_CONFIG: Route = Route::new(Method::GET, "config");
+const GET_SPEED_LIMIT: Route = Route::new(Method::GET, "config/speed-limit");
+const SET_SPEED_LIMIT: Route = Route::new(Method::PUT, "config/speed-limit");
+const GET_BANDWIDTH_MODE: Route = Route::new(Method::GET, "config/bandwidth-mode");
+const SET_BANDWIDTH_MODE: Route = Route::new(Method::PUT, "config/bandwidth-mode");
+const GET_STATS: Route = Route::new(Method::GET, "stats");
+
+/// Every route the client calls, checked against the OpenAPI spec in tests
+#[cfg(test)]
+const ROUTES: &[Route] = &[
+    LIST_DOWNLOADS,
+    ADD_DOWNLOAD,
+    GET_DOWNLOAD,
+    DELETE_DOWNLOAD,
+    ADD_DOWNLOAD_URL,
+    PAUSE_DOWNLOAD,
+    RESUME_DOWNLOAD,
+    SET_DOWNLOAD_PRIORITY,
+    PAUSE_QUEUE,
+    RESUME_QUEUE,
+    QUEUE_STATS,
+    GET_HISTORY,
+    GET_CONFIG,
+    GET_SPEED_LIMIT,
+    SET_SPEED_LIMIT,
+    GET_BANDWIDTH_MODE,
+    SET_BANDWIDTH_MODE,
+    GET_STATS,
+];
+
+/// Client for a usenet-dl REST API
+///
+/// Cheap to clone; clones share the connection pool.
+#[derive(Clone, Debug)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: url::Url,
+    api_key: Option<String>,
+}
+
+impl Client {
+    /// Client for the API served at `base_url`, e.g. `http://localhost:6789`
+    ///
+    /// Endpoints are resolved relative to it, so an API behind a reverse proxy can be
+    /// reached with a base URL like `https://example.com/usenet/`.
+    pub fn new(base_url: &str) -> Result<Self> {
+        let mut base_url = url::Url::parse(base_url)?;
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base_url,
+            api_key: None,
+        })
+    }
+
+    /// Send `api_key` in the `X-Api-Key` header, for servers with `api.api_key` set
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// List every download in the queue and history
+    pub async fn list_downloads(&self) -> Result<Vec<DownloadInfo>> {
+        json_response(self.request(&LIST_DOWNLOADS, None)?).await
+    }
+
+    /// Get a single download
+    pub async fn get_download(&self, id: DownloadId) -> Result<DownloadInfo> {
+        json_response(self.request(&GET_DOWNLOAD, Some(id))?).await
+    }
+
+    /// Upload an NZB, named after `filename`
+    pub async fn add_nzb(
+        &self,
+        content: Vec<u8>,
+        filename: &str,
+        options: &DownloadOptions,
+    ) -> Result<DownloadId> {
+        let form = reqwest::multipart::Form::new()
+            .part(
+                "file",
+                reqwest::multipart::Part::bytes(content).file_name(filename.to_string()),
+            )
+            .text(
+                "options",
+                serde_json::to_string(options).unwrap_or_default(),
+            );
+        let added: Added =
+            json_response(self.request(&ADD_DOWNLOAD, None)?.multipart(form)).await?;
+        Ok(added.id)
+    }
+
+    /// Add an NZB the server fetches from `url`
+    pub async fn add_nzb_url(&self, url: &str, options: &DownloadOptions) -> Result<DownloadId> {
+        let body = json!({"url": url, "options": options});
+        let added: Added =
+            json_response(self.request(&ADD_DOWNLOAD_URL, None)?.json(&body)).await?;
+        Ok(added.id)
+    }
+
+    /// Pause a download
+    pub async fn pause_download(&self, id: DownloadId) -> Result<()> {
+        empty_response(self.request(&PAUSE_DOWNLOAD, Some(id))?).await
+    }
+
+    /// Resume a paused download
+    pub async fn resume_download(&self, id: DownloadId) -> Result<()> {
+        empty_response(self.request(&RESUME_DOWNLOAD, Some(id))?).await
+    }
+
+    /// Remove a download, also deleting its files from the destination if `delete_files`
+    pub async fn delete_download(&self, id: DownloadId, delete_files: bool) -> Result<()> {
+        let query = DeleteDownloadQuery { delete_files };
+        empty_response(self.request(&DELETE_DOWNLOAD, Some(id))?.query(&query)).await
+    }
+
+    /// Change a download's priority
+    pub async fn set_priority(&self, id: DownloadId, priority: Priority) -> Result<()> {
+        let body = json!({ "priority": priority });
+        empty_response(self.request(&SET_DOWNLOAD_PRIORITY, Some(id))?.json(&body)).await
+    }
+
+    /// Pause the whole queue
+    pub async fn pause_queue(&self) -> Result<()> {
+        empty_response(self.request(&PAUSE_QUEUE, None)?).await
+    }
+
+    /// Resume the whole queue
+    pub async fn resume_queue(&self) -> Result<()> {
+        empty_response(self.request(&RESUME_QUEUE, None)?).await
+    }
+
+    /// Queue-wide statistics
+    pub async fn queue_stats(&self) -> Result<QueueStats> {
+        json_response(self.request(&QUEUE_STATS, None)?).await
+    }
+
+    /// A page of history, filtered and paged by `query`
+    pub async fn history(&self, query: &HistoryQuery) -> Result<HistoryPage> {
+        json_response(self.request(&GET_HISTORY, None)?.query(query)).await
+    }
+
+    /// Configuration in effect on the server, including runtime changes
+    pub async fn get_config(&self) -> Result<Config> {
+        json_response(self.request(&GET_CONFIG, None)?).await
+    }
+
+    /// Global speed limit
+    pub async fn get_speed_limit(&self) -> Result<SpeedLimit> {
+        json_response(self.request(&GET_SPEED_LIMIT, None)?).await
+    }
+
+    /// Set the global speed limit in bytes per second (None = unlimited)
+    pub async fn set_speed_limit(&self, limit_bps: Option<u64>) -> Result<()> {
+        let body = SetSpeedLimitRequest {
+            limit_bps,
+            burst_bytes: None,
+        };
+        empty_response(self.request(&SET_SPEED_LIMIT, None)?.json(&body)).await
+    }
+
+    /// Current bandwidth mode
+    pub async fn get_bandwidth_mode(&self) -> Result<BandwidthMode> {
+        #[derive(Deserialize)]
+        struct Mode {
+            mode: BandwidthMode,
+        }
+        let mode: Mode = json_response(self.request(&GET_BANDWIDTH_MODE, None)?).await?;
+        Ok(mode.mode)
+    }
+
+    /// Switch the bandwidth mode
+    pub async fn set_bandwidth_mode(&self, mode: BandwidthMode) -> Result<()> {
+        let body = SetBandwidthModeRequest { mode };
+        empty_response(self.request(&SET_BANDWIDTH_MODE, None)?.json(&body)).await
+    }
+
+    /// Usage statistics for `period`
+    pub async fn stats(&self, period: StatsPeriod) -> Result<UsageStats> {
+        json_response(
+            self.request(&GET_STATS, None)?
+                .query(&StatsQuery { period }),
+        )
+        .await
+    }
+
+    /// Request for `route`, with `{id}` filled in and the API key attached
+    fn request(&self, route: &Route, id: Option<DownloadId>) -> Result<RequestBuilder> {
+        let path = match id {
+            Some(id) => route.path.replace("{id}", &id.0.to_string()),
+            None => route.path.to_string(),
+        };
+        let url = self.base_url.join(&path)?;
+        let request = self.http.request(route.method.clone(), url);
+        Ok(match &self.api_key {
+            Some(api_key) => request.header("X-Api-Key", api_key),
+            None => request,
+        })
+    }
+}
+
+/// Response body of the endpoints adding a download
+#[derive(Deserialize)]
+struct Added {
+    id: DownloadId,
+}
+
+/// Send `request`, turning an error status into [`ClientError::Api`]
+async fn send(request: RequestBuilder) -> Result<Response> {
+    let response = request.send().await?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    // Errors are `{"error": {"code", "message"}}`, or `{"error": "message"}` from a few
+    // older endpoints
+    let body = response.text().await.unwrap_or_default();
+    let error = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|value| value.get("error").cloned());
+    let (code, message) = match error {
+        Some(serde_json::Value::String(message)) => (None, message),
+        Some(error) => (
+            error["code"].as_str().map(String::from),
+            error["message"].as_str().unwrap_or(&body).to_string(),
+        ),
+        None if body.is_empty() => (None, status.to_string()),
+        None => (None, body),
+    };
+    Err(ClientError::Api {
+        status: status.as_u16(),
+        code,
+        message,
+    })
+}
+
+/// Send `request` and parse its JSON response
+async fn json_response<T: DeserializeOwned>(request: RequestBuilder) -> Result<T> {
+    Ok(send(request).await?.json().await?)
+}
+
+/// Send `request`, which has no response body
+async fn empty_response(request: RequestBuilder) -> Result<()> {
+    send(request).await.map(drop)
+}
+
+// unwrap/expect are acceptable in tests for concise failure-on-error assertions
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,146 @@
+use super::*;
+use crate::UsenetDownloader;
+use crate::downloader::test_helpers::{SAMPLE_NZB, create_test_downloader};
+use crate::types::Status;
+use std::sync::Arc;
+use utoipa::OpenApi;
+use utoipa::openapi::PathItemType;
+
+/// Serve the API for a test downloader on a free local port, returning its base URL
+async fn start_api(api_key: Option<&str>) -> (String, Arc<UsenetDownloader>, tempfile::TempDir) {
+    let (downloader, temp_dir) = create_test_downloader().await;
+    let downloader = Arc::new(downloader);
+    let mut config = (*downloader.config).clone();
+    config.server.api.api_key = api_key.map(String::from);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = crate::api::create_router(downloader.clone(), Arc::new(config));
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    (format!("http://{addr}"), downloader, temp_dir)
+}
+
+#[test]
+fn routes_are_in_the_openapi_spec() {
+    let spec = crate::api::openapi::ApiDoc::openapi();
+    for route in ROUTES {
+        let path = format!("/api/v1/{}", route.path);
+        let item = spec
+            .paths
+            .paths
+            .get(&path)
+            .unwrap_or_else(|| panic!("{path} isn't in the OpenAPI spec"));
+        let method = match route.method {
+            Method::GET => PathItemType::Get,
+            Method::POST => PathItemType::Post,
+            Method::PUT => PathItemType::Put,
+            Method::PATCH => PathItemType::Patch,
+            Method::DELETE => PathItemType::Delete,
+            ref other => panic!("unexpected method {other}"),
+        };
+        assert!(
+            item.operations.contains_key(&method),
+            "{} {path} isn't in the OpenAPI spec",
+            route.method
+        );
+    }
+}
+
+#[tokio::test]
+async fn manages_downloads_through_the_api() {
+    let (base_url, downloader, _temp_dir) = start_api(None).await;
+    let client = Client::new(&base_url).unwrap();
+
+    let options = DownloadOptions {
+        category: Some("movies".to_string()),
+        ..Default::default()
+    };
+    let id = client
+        .add_nzb(SAMPLE_NZB.as_bytes().to_vec(), "movie.nzb", &options)
+        .await
+        .unwrap();
+
+    let download = client.get_download(id).await.unwrap();
+    assert_eq!(download.name, "movie.nzb");
+    assert_eq!(download.category.as_deref(), Some("movies"));
+
+    client.set_priority(id, Priority::High).await.unwrap();
+    client.pause_download(id).await.unwrap();
+    let download = client.get_download(id).await.unwrap();
+    assert_eq!(download.priority, Priority::High);
+    assert_eq!(download.status, Status::Paused);
+
+    let listed = client.list_downloads().await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, id);
+    assert_eq!(client.queue_stats().await.unwrap().total, 1);
+
+    client.delete_download(id, false).await.unwrap();
+    assert!(downloader.db.get_download(id).await.unwrap().is_none());
+    match client.get_download(id).await {
+        Err(ClientError::Api {
+            status, message, ..
+        }) => {
+            assert_eq!(status, 404);
+            assert_eq!(message, "download not found");
+        }
+        other => panic!("expected a 404, got {other:?}"),
+    }
+
+    let history = client
+        .history(&HistoryQuery {
+            limit: Some(10),
+            offset: None,
+            status: None,
+            search: None,
+        })
+        .await
+        .unwrap();
+    assert!(history.items.is_empty());
+}
+
+#[tokio::test]
+async fn reads_and_changes_settings() {
+    let (base_url, downloader, _temp_dir) = start_api(None).await;
+    // A trailing slash makes no difference
+    let client = Client::new(&format!("{base_url}/")).unwrap();
+
+    client.set_speed_limit(Some(5_000_000)).await.unwrap();
+    assert_eq!(
+        client.get_speed_limit().await.unwrap().limit_bps,
+        Some(5_000_000)
+    );
+
+    client
+        .set_bandwidth_mode(BandwidthMode::Metered)
+        .await
+        .unwrap();
+    assert_eq!(
+        client.get_bandwidth_mode().await.unwrap(),
+        BandwidthMode::Metered
+    );
+
+    let config = client.get_config().await.unwrap();
+    assert_eq!(
+        config.download.max_concurrent_downloads,
+        downloader.config.download.max_concurrent_downloads
+    );
+    client.stats(StatsPeriod::Week).await.unwrap();
+}
+
+#[tokio::test]
+async fn sends_the_api_key() {
+    let (base_url, _downloader, _temp_dir) = start_api(Some("secret")).await;
+
+    match Client::new(&base_url).unwrap().queue_stats().await {
+        Err(ClientError::Api { status, .. }) => assert_eq!(status, 401),
+        other => panic!("expected a 401, got {other:?}"),
+    }
+
+    let client = Client::new(&base_url).unwrap().with_api_key("secret");
+    client.pause_queue().await.unwrap();
+    client.resume_queue().await.unwrap();
+}
@@ -19,6 +19,11 @@ pub struct DownloadConfig {
     #[serde(default = "default_temp_dir")]
     pub temp_dir: PathBuf,
 
+    /// How in-progress folders are laid out under the temp directory
+    /// (default: one `download_{id}` folder per download)
+    #[serde(default)]
+    pub incomplete_layout: IncompleteLayout,
+
     /// Maximum concurrent downloads (default: 3)
     #[serde(default = "default_max_concurrent")]
     pub max_concurrent_downloads: usize,
@@ -27,6 +32,10 @@ pub struct DownloadConfig {
     #[serde(default)]
     pub speed_limit_bps: Option<u64>,
 
+    /// Token bucket size for the global speed limit in bytes (None = one second of `speed_limit_bps`)
+    #[serde(default)]
+    pub speed_limit_burst_bytes: Option<u64>,
+
     /// Default post-processing mode
     #[serde(default)]
     pub default_post_process: PostProcess,
@@ -39,6 +48,21 @@ pub struct DownloadConfig {
     #[serde(default)]
     pub file_collision: FileCollisionAction,
 
+    /// How completed files are moved to the destination (default: rename with copy fallback)
+    #[serde(default)]
+    pub move_mode: MoveMode,
+
+    /// Checksum used to verify files copied by the move stage (default: sha256)
+    #[serde(default)]
+    pub move_checksum: MoveChecksum,
+
+    /// How long `link` mode keeps the originals in the temp directory (seconds, default: 86400)
+    ///
+    /// The download's temp folder is deleted once this has passed, including after a
+    /// restart.
+    #[serde(default = "default_link_retention", with = "duration_serde")]
+    pub link_retention: Duration,
+
     /// Maximum article failure ratio before considering a download failed (default: 0.5 = 50%)
     ///
     /// When the ratio of failed articles to total articles exceeds this threshold,
@@ -58,6 +82,80 @@ pub struct DownloadConfig {
     /// Number of articles to sample before evaluating the fast-fail heuristic (default: 10)
     #[serde(default = "default_fast_fail_sample_size")]
     pub fast_fail_sample_size: usize,
+
+    /// Abort downloads once missing data exceeds the PAR2 recovery capacity in the NZB (default: true)
+    ///
+    /// Such downloads are marked `failed_unrepairable` instead of downloading the rest.
+    #[serde(default = "default_true")]
+    pub abort_unrepairable: bool,
+
+    /// Fetch articles that several queued downloads reference only once (default: true)
+    ///
+    /// Cross-posted releases queued as separate NZBs often share message-IDs. The first
+    /// download to fetch a shared article keeps it in `temp_dir` until the others have
+    /// taken it.
+    #[serde(default = "default_true")]
+    pub deduplicate_articles: bool,
+
+    /// Hold back PAR2 recovery volumes until repair needs them (default: false)
+    ///
+    /// Only the PAR2 index file is fetched up front. If repair is short of recovery
+    /// blocks, the held volumes are fetched and repair runs again.
+    #[serde(default)]
+    pub hold_par2_volumes: bool,
+
+    /// How article fetches pick a server (default: in server order)
+    #[serde(default)]
+    pub server_routing: ServerRouting,
+
+    /// Extensions of files that are never downloaded, e.g. `[".exe", ".scr", ".lnk"]` (default: none)
+    ///
+    /// Matched case-insensitively against the end of each NZB file's name; the leading
+    /// dot is optional.
+    #[serde(default)]
+    pub reject_extensions: Vec<String>,
+
+    /// What happens to a download that contains a rejected file (default: skip the file)
+    #[serde(default)]
+    pub reject_action: RejectAction,
+
+    /// Completion check (article availability sampling) settings
+    #[serde(default)]
+    pub completion_check: CompletionCheckConfig,
+
+    /// URL to try when a deferred NZB's URL returns 404 or 410 (default: none)
+    ///
+    /// Indexer links often expire, while a search by release name still finds the NZB.
+    /// `{name}` in the URL is replaced by the URL-encoded release name, e.g.
+    /// `"https://indexer.example/api?t=get&name={name}&apikey=..."`.
+    #[serde(default)]
+    pub nzb_fallback_url: Option<String>,
+
+    /// Close idle server connections after this long (seconds, default: None, 5 minutes)
+    ///
+    /// Some providers count idle connections against the connection limit. A server's
+    /// own `idle_timeout` takes precedence. Connections are closed after 5 minutes idle
+    /// whatever the setting.
+    #[serde(default, with = "optional_duration_serde")]
+    pub connection_idle_timeout: Option<Duration>,
+
+    /// Stall detection settings (see [`UsenetDownloader::start_stall_watchdog`])
+    ///
+    /// [`UsenetDownloader::start_stall_watchdog`]: crate::UsenetDownloader::start_stall_watchdog
+    #[serde(default)]
+    pub stall: StallConfig,
+
+    /// Simulated downloads, for working on a UI without network access
+    #[serde(default)]
+    pub simulate: SimulateConfig,
+
+    /// Limits applied in [`BandwidthMode::Metered`](crate::types::BandwidthMode::Metered)
+    #[serde(default)]
+    pub metered: MeteredConfig,
+
+    /// Fast lane letting small downloads start while the download slots are taken
+    #[serde(default)]
+    pub fast_lane: FastLaneConfig,
 }
 
 impl Default for DownloadConfig {
@@ -65,18 +163,223 @@ impl Default for DownloadConfig {
         Self {
             download_dir: default_download_dir(),
             temp_dir: default_temp_dir(),
+            incomplete_layout: IncompleteLayout::default(),
             max_concurrent_downloads: default_max_concurrent(),
             speed_limit_bps: None,
+            speed_limit_burst_bytes: None,
             default_post_process: PostProcess::default(),
             delete_samples: true,
             file_collision: FileCollisionAction::default(),
+            move_mode: MoveMode::default(),
+            move_checksum: MoveChecksum::default(),
+            link_retention: default_link_retention(),
             max_failure_ratio: default_max_failure_ratio(),
             fast_fail_threshold: default_fast_fail_threshold(),
             fast_fail_sample_size: default_fast_fail_sample_size(),
+            abort_unrepairable: true,
+            deduplicate_articles: true,
+            hold_par2_volumes: false,
+            server_routing: ServerRouting::default(),
+            reject_extensions: vec![],
+            reject_action: RejectAction::default(),
+            completion_check: CompletionCheckConfig::default(),
+            nzb_fallback_url: None,
+            connection_idle_timeout: None,
+            stall: StallConfig::default(),
+            simulate: SimulateConfig::default(),
+            metered: MeteredConfig::default(),
+            fast_lane: FastLaneConfig::default(),
         }
     }
 }
 
+impl DownloadConfig {
+    /// Whether `filename` has one of the `reject_extensions`
+    pub fn is_rejected(&self, filename: &str) -> bool {
+        let filename = filename.to_lowercase();
+        self.reject_extensions.iter().any(|extension| {
+            let extension = extension.trim().trim_start_matches('.').to_lowercase();
+            !extension.is_empty() && filename.ends_with(&format!(".{}", extension))
+        })
+    }
+}
+
+/// Completion check configuration
+///
+/// A completion check asks the servers whether a random sample of a download's
+/// articles exists, estimating how much of it is available before downloading.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct CompletionCheckConfig {
+    /// Check every download when it is added (default: false)
+    #[serde(default)]
+    pub check_on_add: bool,
+
+    /// Number of articles to sample per check (default: 100)
+    #[serde(default = "default_completion_sample_size")]
+    pub sample_size: usize,
+
+    /// Minimum estimated completion for downloads checked on add (default: 0.9 = 90%)
+    ///
+    /// Downloads estimated below this fraction are marked failed instead of queued.
+    #[serde(default = "default_min_completion")]
+    pub min_completion: f64,
+}
+
+impl Default for CompletionCheckConfig {
+    fn default() -> Self {
+        Self {
+            check_on_add: false,
+            sample_size: default_completion_sample_size(),
+            min_completion: default_min_completion(),
+        }
+    }
+}
+
+/// Stall detection configuration
+///
+/// A download is stalled when it has received no data for `no_progress_timeout` while
+/// at least one server is in rotation, or when a post-processing stage has run longer
+/// than `stage_timeout`. Stalled downloads get `action`; once a download has stalled
+/// `max_attempts` times it's failed whatever the action.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct StallConfig {
+    /// Watch for stalled downloads (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How long a download may receive no data before it's stalled (default: 10 minutes)
+    #[serde(default = "default_stall_no_progress_timeout", with = "duration_serde")]
+    pub no_progress_timeout: Duration,
+
+    /// How long a single post-processing stage may run before it's stalled (default: 2 hours)
+    #[serde(default = "default_stall_stage_timeout", with = "duration_serde")]
+    pub stage_timeout: Duration,
+
+    /// What happens to a stalled download (default: retry)
+    #[serde(default)]
+    pub action: StallAction,
+
+    /// Number of stalls after which a download is failed whatever the action (default: 3)
+    #[serde(default = "default_stall_max_attempts")]
+    pub max_attempts: u32,
+}
+
+impl Default for StallConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            no_progress_timeout: default_stall_no_progress_timeout(),
+            stage_timeout: default_stall_stage_timeout(),
+            action: StallAction::default(),
+            max_attempts: default_stall_max_attempts(),
+        }
+    }
+}
+
+/// Simulated download configuration
+///
+/// With `enabled`, no server is contacted: each article is made up on the spot as
+/// yEnc data for its part of its file and delivered at `speed_bps`. Everything after
+/// the fetch (events, database, post-processing) runs as usual. Files found by name in
+/// `seed_dir` are served as they are, so post-processing has real archives to work on;
+/// other files are filled with zeros.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SimulateConfig {
+    /// Fake article fetches instead of contacting the servers (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Speed simulated fetches are delivered at, in bytes per second (default: 10 MB/s;
+    /// 0 = as fast as possible)
+    #[serde(default = "default_simulate_speed_bps")]
+    pub speed_bps: u64,
+
+    /// Directory of files to serve instead of zeros, matched by filename (default: none)
+    #[serde(default)]
+    pub seed_dir: Option<PathBuf>,
+}
+
+impl Default for SimulateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            speed_bps: default_simulate_speed_bps(),
+            seed_dir: None,
+        }
+    }
+}
+
+/// Metered connection configuration
+///
+/// Applies while the host application has switched the downloader to
+/// [`BandwidthMode::Metered`](crate::types::BandwidthMode::Metered), e.g. on a mobile
+/// hotspot. Downloads already running carry on, at the metered speed limit.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct MeteredConfig {
+    /// Speed limit while metered, in bytes per second (default: 1 MB/s; None = keep the
+    /// normal limit)
+    #[serde(default = "default_metered_speed_limit_bps")]
+    pub speed_limit_bps: Option<u64>,
+
+    /// Downloads larger than this stay queued while metered, unless forced
+    /// (default: 1 GB; None = start downloads of any size)
+    #[serde(default = "default_metered_max_job_bytes")]
+    pub max_job_bytes: Option<u64>,
+}
+
+impl Default for MeteredConfig {
+    fn default() -> Self {
+        Self {
+            speed_limit_bps: default_metered_speed_limit_bps(),
+            max_job_bytes: default_metered_max_job_bytes(),
+        }
+    }
+}
+
+/// Small job fast lane configuration
+///
+/// While every `max_concurrent_downloads` slot is taken, the highest priority queued
+/// download of at most `max_job_bytes` starts anyway in one extra slot, so a small NZB
+/// doesn't wait hours behind a large one. `connections` are held back from every other
+/// download, and are all the fast lane download uses.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct FastLaneConfig {
+    /// Enable the fast lane (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Largest download that may take the fast lane, in bytes (default: 500 MB)
+    #[serde(default = "default_fast_lane_max_job_bytes")]
+    pub max_job_bytes: u64,
+
+    /// Connections reserved for the fast lane (default: 4)
+    #[serde(default = "default_fast_lane_connections")]
+    pub connections: usize,
+}
+
+impl Default for FastLaneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_job_bytes: default_fast_lane_max_job_bytes(),
+            connections: default_fast_lane_connections(),
+        }
+    }
+}
+
+/// What happens to a stalled download
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StallAction {
+    /// Restart the download, or the stalled post-processing stage (default)
+    #[default]
+    Retry,
+    /// Like `retry`, but a download goes back in the queue one priority lower
+    Requeue,
+    /// Fail the download
+    Fail,
+}
+
 /// External tool paths (unrar, 7z, par2) and password configuration
 ///
 /// Groups settings for external binaries and password handling.
@@ -122,6 +425,32 @@ pub struct ToolsConfig {
     #[serde(skip)]
     #[schema(ignore)]
     pub parity_handler: Option<std::sync::Arc<dyn crate::parity::ParityHandler>>,
+
+    /// HTTP endpoint to look up archive passwords by release name (default: None)
+    ///
+    /// `{name}` in the URL is replaced by the URL-encoded release name; without it a
+    /// `name` query parameter is added. See
+    /// [`HttpPasswordProvider`](crate::extraction::HttpPasswordProvider) for the
+    /// accepted response formats.
+    #[serde(default)]
+    pub password_lookup_url: Option<String>,
+
+    /// Timeout for password lookup requests (default: 10 seconds)
+    #[serde(default = "default_password_lookup_timeout", with = "duration_serde")]
+    pub password_lookup_timeout: Duration,
+
+    /// Optional pre-built password provider to use instead of `password_lookup_url`
+    ///
+    /// When `Some`, it is consulted for every extraction; when `None` (the default), an
+    /// [`HttpPasswordProvider`](crate::extraction::HttpPasswordProvider) is created
+    /// if `password_lookup_url` is set.
+    #[serde(skip)]
+    #[schema(ignore)]
+    pub password_provider: Option<std::sync::Arc<dyn crate::extraction::PasswordProvider>>,
+
+    /// CPU and I/O priority for `par2`, `unrar`, `7z` and the virus scanner (default: inherited)
+    #[serde(default)]
+    pub process_priority: ProcessPriorityConfig,
 }
 
 impl std::fmt::Debug for ToolsConfig {
@@ -135,11 +464,12 @@ impl std::fmt::Debug for ToolsConfig {
             .field("search_path", &self.search_path)
             .field(
                 "parity_handler",
-                &self
-                    .parity_handler
-                    .as_ref()
-                    .map(|h| h.name()),
+                &self.parity_handler.as_ref().map(|h| h.name()),
             )
+            .field("password_lookup_url", &self.password_lookup_url)
+            .field("password_lookup_timeout", &self.password_lookup_timeout)
+            .field("password_provider", &self.password_provider.is_some())
+            .field("process_priority", &self.process_priority)
             .finish()
     }
 }
@@ -154,11 +484,57 @@ impl Default for ToolsConfig {
             par2_path: None,
             search_path: true,
             parity_handler: None,
+            password_lookup_url: None,
+            password_lookup_timeout: default_password_lookup_timeout(),
+            password_provider: None,
+            process_priority: ProcessPriorityConfig::default(),
         }
     }
 }
 
-/// Notification configuration (webhooks and scripts)
+/// Scheduling applied to external repair and extraction tools (Unix only)
+///
+/// Keeps `par2`, `unrar` and `7z` from starving other programs on the same machine,
+/// such as a media server. All fields default to unset, which leaves the tools at the
+/// downloader's own priority. Settings the system refuses (e.g. a negative `nice`
+/// without privileges) are skipped. `io_class` and `cpu_affinity` are Linux only.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ProcessPriorityConfig {
+    /// Niceness to run the tools at, from -20 (highest priority) to 19 (lowest)
+    #[serde(default)]
+    pub nice: Option<i32>,
+
+    /// I/O scheduling class
+    #[serde(default)]
+    pub io_class: Option<IoClass>,
+
+    /// Priority within the `best_effort` I/O class, from 0 (highest) to 7 (lowest; default: 4)
+    #[serde(default)]
+    pub io_priority: Option<u8>,
+
+    /// CPUs the tools may run on, by index (empty: any)
+    #[serde(default)]
+    pub cpu_affinity: Vec<usize>,
+}
+
+impl ProcessPriorityConfig {
+    /// Whether every setting is left at the inherited value
+    pub fn is_inherited(&self) -> bool {
+        self.nice.is_none() && self.io_class.is_none() && self.cpu_affinity.is_empty()
+    }
+}
+
+/// I/O scheduling class for external tools
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IoClass {
+    /// Normal disk access, ordered by `io_priority`
+    BestEffort,
+    /// Disk access only when no other program needs the disk
+    Idle,
+}
+
+/// Notification configuration (webhooks, scripts and MQTT)
 ///
 /// Groups settings for external notifications triggered by download events.
 /// Used as a nested sub-config within [`Config`].
@@ -171,6 +547,10 @@ pub struct NotificationConfig {
     /// Script configurations
     #[serde(default)]
     pub scripts: Vec<ScriptConfig>,
+
+    /// MQTT broker to publish download events to (default: None)
+    #[serde(default)]
+    pub mqtt: Option<MqttConfig>,
 }
 
 /// Main configuration for UsenetDownloader
@@ -178,7 +558,7 @@ pub struct NotificationConfig {
 /// Fields are organized into logical sub-configs for maintainability:
 /// - [`download`](DownloadConfig) — directories, concurrency, post-processing
 /// - [`tools`](ToolsConfig) — external binary paths, password handling
-/// - [`notifications`](NotificationConfig) — webhooks and scripts
+/// - [`notifications`](NotificationConfig) — webhooks, scripts and MQTT
 ///
 /// All sub-config fields are flattened for backward-compatible serialization,
 /// meaning the JSON/TOML format remains unchanged (no nesting).
@@ -197,7 +577,7 @@ pub struct Config {
     #[serde(flatten)]
     pub tools: ToolsConfig,
 
-    /// Notification settings (webhooks and scripts)
+    /// Notification settings (webhooks, scripts and MQTT)
     #[serde(flatten)]
     pub notifications: NotificationConfig,
 
@@ -208,6 +588,24 @@ pub struct Config {
     /// Data storage and state management
     pub persistence: PersistenceConfig,
 
+    /// Diagnostic logging options
+    #[serde(default)]
+    pub logging: LoggingConfig,
+
+    /// Schedule rule evaluation settings
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+
+    /// Serve the queue, history and state without changing them (default: false)
+    ///
+    /// For standby replicas pointed at a shared database: the database is opened without
+    /// migrating it, queued downloads are neither restored nor started, folder watching
+    /// and RSS auto-download stay off, and queue changes fail with [`Error::ReadOnly`].
+    ///
+    /// [`Error::ReadOnly`]: crate::Error::ReadOnly
+    #[serde(default)]
+    pub read_only: bool,
+
     /// Automated content discovery and ingestion
     #[serde(flatten)]
     pub automation: AutomationConfig,
@@ -231,6 +629,276 @@ impl Config {
     }
 }
 
+/// Placeholder shown instead of secrets when the configuration is returned by the API
+///
+/// Sending it back in a config patch keeps the current value.
+pub(crate) const REDACTED: &str = "***REDACTED***";
+
+impl Config {
+    /// Parse a configuration from a JSON value
+    ///
+    /// On failure the error's `key` is the path of the offending field, e.g.
+    /// `servers[0].connections` or `retry.max_delay`.
+    pub fn from_json_value(value: &serde_json::Value) -> crate::Result<Self> {
+        match Config::deserialize(value) {
+            Ok(config) => Ok(config),
+            Err(e) => Err(
+                Self::locate_parse_error(value).unwrap_or(crate::Error::Config {
+                    message: e.to_string(),
+                    key: None,
+                }),
+            ),
+        }
+    }
+
+    /// Find which field failed to parse
+    ///
+    /// Flattened sections are buffered by serde, which loses the field path, so each
+    /// section is parsed on its own before the top-level struct.
+    fn locate_parse_error(value: &serde_json::Value) -> Option<crate::Error> {
+        fn probe<'de, T: Deserialize<'de>>(value: &'de serde_json::Value) -> Option<crate::Error> {
+            let error = serde_path_to_error::deserialize::<_, T>(value).err()?;
+            let path = error.path().to_string();
+            Some(crate::Error::Config {
+                message: error.into_inner().to_string(),
+                key: (path != ".").then_some(path),
+            })
+        }
+
+        probe::<DownloadConfig>(value)
+            .or_else(|| probe::<ToolsConfig>(value))
+            .or_else(|| probe::<NotificationConfig>(value))
+            .or_else(|| probe::<ProcessingConfig>(value))
+            .or_else(|| probe::<AutomationConfig>(value))
+            .or_else(|| probe::<ServerIntegrationConfig>(value))
+            .or_else(|| probe::<Config>(value))
+    }
+
+    /// Check values that parse but can't work
+    ///
+    /// Returns the first problem found, with the field path as the error's `key`.
+    pub fn validate(&self) -> crate::Result<()> {
+        let invalid = |key: String, message: &str| {
+            Err(crate::Error::Config {
+                message: format!("{}: {}", key, message),
+                key: Some(key),
+            })
+        };
+
+        for (i, server) in self.servers.iter().enumerate() {
+            server.validate(i)?;
+        }
+
+        if self.download.max_concurrent_downloads == 0 {
+            return invalid("max_concurrent_downloads".to_string(), "must be at least 1");
+        }
+
+        let priority = &self.tools.process_priority;
+        if priority
+            .nice
+            .is_some_and(|nice| !(-20..=19).contains(&nice))
+        {
+            return invalid(
+                "process_priority.nice".to_string(),
+                "must be between -20 and 19",
+            );
+        }
+        if priority.io_priority.is_some_and(|level| level > 7) {
+            return invalid(
+                "process_priority.io_priority".to_string(),
+                "must be between 0 and 7",
+            );
+        }
+
+        let fast_lane = &self.download.fast_lane;
+        if fast_lane.enabled && fast_lane.connections == 0 {
+            return invalid("fast_lane.connections".to_string(), "must be at least 1");
+        }
+
+        if self.processing.max_parallel_jobs == 0 {
+            return invalid("max_parallel_jobs".to_string(), "must be at least 1");
+        }
+
+        if self.persistence.progress_flush_interval_ms == 0 {
+            return invalid(
+                "progress_flush_interval_ms".to_string(),
+                "must be greater than 0",
+            );
+        }
+
+        let scan = &self.processing.scan;
+        if scan.enabled && scan.command.as_os_str().is_empty() {
+            return invalid("scan.command".to_string(), "must not be empty");
+        }
+        if scan.enabled && scan.timeout.is_zero() {
+            return invalid("scan.timeout".to_string(), "must be greater than 0");
+        }
+
+        let retry = &self.processing.retry;
+        if retry.backoff_multiplier.is_nan() || retry.backoff_multiplier < 1.0 {
+            return invalid(
+                "retry.backoff_multiplier".to_string(),
+                "must be at least 1.0",
+            );
+        }
+        if retry.initial_delay > retry.max_delay {
+            return invalid(
+                "retry.initial_delay".to_string(),
+                "must not exceed retry.max_delay",
+            );
+        }
+
+        if let Some(url) = &self.tools.password_lookup_url
+            && !reqwest::Url::parse(&url.replace("{name}", "name"))
+                .is_ok_and(|u| matches!(u.scheme(), "http" | "https"))
+        {
+            return invalid(
+                "password_lookup_url".to_string(),
+                "must be an http or https URL",
+            );
+        }
+
+        if let Some(url) = &self.download.nzb_fallback_url {
+            if !url.contains("{name}") {
+                return invalid(
+                    "nzb_fallback_url".to_string(),
+                    "must contain the {name} placeholder",
+                );
+            }
+            if !reqwest::Url::parse(&url.replace("{name}", "name"))
+                .is_ok_and(|u| matches!(u.scheme(), "http" | "https"))
+            {
+                return invalid(
+                    "nzb_fallback_url".to_string(),
+                    "must be an http or https URL",
+                );
+            }
+        }
+
+        let completion_check = &self.download.completion_check;
+        if completion_check.sample_size == 0 {
+            return invalid(
+                "completion_check.sample_size".to_string(),
+                "must be at least 1",
+            );
+        }
+        if !(0.0..=1.0).contains(&completion_check.min_completion) {
+            return invalid(
+                "completion_check.min_completion".to_string(),
+                "must be between 0.0 and 1.0",
+            );
+        }
+
+        if self
+            .download
+            .connection_idle_timeout
+            .is_some_and(|timeout| timeout.is_zero())
+        {
+            return invalid(
+                "connection_idle_timeout".to_string(),
+                "must be greater than 0",
+            );
+        }
+
+        let stall = &self.download.stall;
+        if stall.no_progress_timeout.is_zero() {
+            return invalid(
+                "stall.no_progress_timeout".to_string(),
+                "must be greater than 0",
+            );
+        }
+        if stall.stage_timeout.is_zero() {
+            return invalid("stall.stage_timeout".to_string(), "must be greater than 0");
+        }
+        if stall.max_attempts == 0 {
+            return invalid("stall.max_attempts".to_string(), "must be at least 1");
+        }
+
+        let size_multiplier = self.processing.disk_space.size_multiplier;
+        if size_multiplier.is_nan() || size_multiplier <= 0.0 {
+            return invalid(
+                "disk_space.size_multiplier".to_string(),
+                "must be greater than 0",
+            );
+        }
+
+        let size_tolerance = self.automation.rss_dedup.size_tolerance_percent;
+        if !(0.0..=100.0).contains(&size_tolerance) {
+            return invalid(
+                "rss_dedup.size_tolerance_percent".to_string(),
+                "must be between 0 and 100",
+            );
+        }
+
+        for (i, webhook) in self.notifications.webhooks.iter().enumerate() {
+            if webhook
+                .digest_interval
+                .is_some_and(|interval| interval.is_zero())
+            {
+                return invalid(
+                    format!("webhooks[{}].digest_interval", i),
+                    "must be greater than 0",
+                );
+            }
+        }
+
+        if let Some(mqtt) = &self.notifications.mqtt {
+            if mqtt.host.is_empty() {
+                return invalid("mqtt.host".to_string(), "must not be empty");
+            }
+            if mqtt.client_id.is_empty() {
+                return invalid("mqtt.client_id".to_string(), "must not be empty");
+            }
+            if mqtt.password.is_some() && mqtt.username.is_none() {
+                return invalid("mqtt.password".to_string(), "requires mqtt.username");
+            }
+            if mqtt.topic_prefix.is_empty() || mqtt.topic_prefix.contains(['+', '#']) {
+                return invalid(
+                    "mqtt.topic_prefix".to_string(),
+                    "must be non-empty and contain no wildcards",
+                );
+            }
+            if mqtt.keep_alive.is_zero() || mqtt.keep_alive.as_secs() > u16::MAX as u64 {
+                return invalid(
+                    "mqtt.keep_alive".to_string(),
+                    "must be between 1 and 65535 seconds",
+                );
+            }
+        }
+
+        if let Some(timezone) = &self.scheduler.timezone
+            && timezone.parse::<chrono_tz::Tz>().is_err()
+        {
+            return invalid(
+                "scheduler.timezone".to_string(),
+                "must be an IANA time zone name, e.g. \"Europe/Amsterdam\"",
+            );
+        }
+
+        for (i, rule) in self.persistence.schedule_rules.iter().enumerate() {
+            for (field, time) in [
+                ("start_time", &rule.start_time),
+                ("end_time", &rule.end_time),
+            ] {
+                if chrono::NaiveTime::parse_from_str(time, "%H:%M").is_err() {
+                    return invalid(
+                        format!("persistence.schedule_rules[{}].{}", i, field),
+                        "must be a time in HH:MM format",
+                    );
+                }
+            }
+            if let Err((field, message)) = rule.date_range() {
+                return invalid(
+                    format!("persistence.schedule_rules[{}].{}", i, field),
+                    message,
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// NNTP server configuration
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct ServerConfig {
@@ -249,7 +917,10 @@ pub struct ServerConfig {
     /// Password for authentication
     pub password: Option<String>,
 
-    /// Number of connections to maintain
+    /// Maximum number of connections
+    ///
+    /// Connections are opened as downloads need them rather than up front, so an empty
+    /// queue holds none open once idle ones have been closed (see `idle_timeout`).
     #[serde(default = "default_connections")]
     pub connections: usize,
 
@@ -266,6 +937,70 @@ pub struct ServerConfig {
     /// Recommended values: 5-20 depending on network latency and server capabilities.
     #[serde(default = "default_pipeline_depth")]
     pub pipeline_depth: usize,
+
+    /// Speed limit for this server in bytes per second (None = only the global limit applies)
+    ///
+    /// Applied on top of the global limit, e.g. for a block account that should only be
+    /// used slowly. Can be changed at runtime via `PUT /servers/{id}/speed-limit`.
+    #[serde(default)]
+    pub speed_limit_bps: Option<u64>,
+
+    /// Close this server's connections once they've been idle this long (seconds,
+    /// default: `connection_idle_timeout`)
+    #[serde(default, with = "optional_duration_serde")]
+    pub idle_timeout: Option<Duration>,
+
+    /// Relative cost of fetching from this server, e.g. its price per GB (default: 0)
+    ///
+    /// With `server_routing = "least_cost"`, servers are tried cheapest first.
+    #[serde(default)]
+    pub cost: f64,
+
+    /// Bytes this server may serve in any 30 days (None = unlimited)
+    ///
+    /// With `server_routing = "least_cost"`, a server that has used up its quota is
+    /// skipped until older usage falls out of the window.
+    #[serde(default)]
+    pub quota_bytes: Option<u64>,
+}
+
+impl ServerConfig {
+    /// Check values that parse but can't work, for the server at `index` in `servers`
+    ///
+    /// Returns the first problem found, with the field path (e.g. `servers[0].port`) as
+    /// the error's `key`.
+    pub fn validate(&self, index: usize) -> crate::Result<()> {
+        let invalid = |field: &str, message: &str| {
+            let key = format!("servers[{}].{}", index, field);
+            Err(crate::Error::Config {
+                message: format!("{}: {}", key, message),
+                key: Some(key),
+            })
+        };
+
+        if self.host.trim().is_empty() {
+            return invalid("host", "must not be empty");
+        }
+        if self.port == 0 {
+            return invalid("port", "must be between 1 and 65535");
+        }
+        if self.connections == 0 {
+            return invalid("connections", "must be at least 1");
+        }
+        if self.pipeline_depth == 0 {
+            return invalid("pipeline_depth", "must be at least 1");
+        }
+        if self.idle_timeout.is_some_and(|timeout| timeout.is_zero()) {
+            return invalid("idle_timeout", "must be greater than 0");
+        }
+        if !self.cost.is_finite() || self.cost < 0.0 {
+            return invalid("cost", "must be 0 or greater");
+        }
+        if self.quota_bytes == Some(0) {
+            return invalid("quota_bytes", "must be greater than 0");
+        }
+        Ok(())
+    }
 }
 
 /// Retry configuration for transient failures
@@ -356,6 +1091,20 @@ pub struct ExtractionConfig {
     /// File extensions to treat as archives
     #[serde(default = "default_archive_extensions")]
     pub archive_extensions: Vec<String>,
+
+    /// Extract RAR archives with the external `unrar`/`7z` binary first (default: false)
+    ///
+    /// Either way, when a binary is available it's used as a fallback for archives
+    /// the other extractor fails on.
+    #[serde(default)]
+    pub prefer_cli: bool,
+
+    /// Move the contents of a lone top-level folder rather than the folder itself (default: false)
+    ///
+    /// An archive holding `Release.Name/…` otherwise ends up as
+    /// `destination/Release.Name/…`. Categories can override this.
+    #[serde(default)]
+    pub flatten_single_folder: bool,
 }
 
 impl Default for ExtractionConfig {
@@ -363,6 +1112,8 @@ impl Default for ExtractionConfig {
         Self {
             max_recursion_depth: 2,
             archive_extensions: default_archive_extensions(),
+            prefer_cli: false,
+            flatten_single_folder: false,
         }
     }
 }
@@ -380,6 +1131,74 @@ pub enum FileCollisionAction {
     Skip,
 }
 
+/// How in-progress folders are named under the temp directory
+///
+/// Named layouts get a ` (2)`, ` (3)`, ... suffix when another download already uses
+/// the folder. The layout is picked when a download is added, so changing it only
+/// affects new downloads.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IncompleteLayout {
+    /// `download_{id}` per download (default)
+    #[default]
+    Id,
+    /// One folder per job, named after it
+    Job,
+    /// Job folders grouped by the day they were added (`2024-05-01/<job>`)
+    Date,
+    /// Job folders grouped by category (`<category>/<job>`, `uncategorized` without one)
+    Category,
+}
+
+/// What happens to a download that contains a file with a rejected extension
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectAction {
+    /// Download the other files and skip the rejected ones (default)
+    #[default]
+    Skip,
+    /// Fail the whole download when it's added
+    Fail,
+}
+
+/// How article fetches pick among the enabled servers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerRouting {
+    /// Try servers in the order they're configured, moving on when one can't be
+    /// reached (default)
+    #[default]
+    Priority,
+    /// Try the cheapest server (by `cost`) with quota left first, then the next
+    /// cheapest, skipping servers that have used up their `quota_bytes`
+    LeastCost,
+}
+
+/// How the move stage transfers files to the destination
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveMode {
+    /// Rename files, falling back to copy + verify + delete across filesystems (default)
+    #[default]
+    Rename,
+    /// Always copy + verify + delete (for network/FUSE mounts where rename is unreliable)
+    Copy,
+    /// Hard-link (or reflink) files into place and keep the originals for
+    /// `link_retention`, falling back to copy + verify across filesystems
+    Link,
+}
+
+/// Checksum computed while the move stage copies a file and checked against the copy
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveChecksum {
+    /// SHA-256 (default)
+    #[default]
+    Sha256,
+    /// BLAKE3, several times faster than SHA-256 on large files
+    Blake3,
+}
+
 /// Obfuscated filename detection and renaming configuration
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct DeobfuscationConfig {
@@ -390,6 +1209,38 @@ pub struct DeobfuscationConfig {
     /// Minimum filename length to consider for deobfuscation (default: 12)
     #[serde(default = "default_min_length")]
     pub min_length: usize,
+
+    /// Restore the file names recorded in the download's PAR2 files (default: true)
+    #[serde(default = "default_true")]
+    pub par2_names: bool,
+
+    /// Rename obfuscated RAR volumes so the set can be extracted (default: true)
+    #[serde(default = "default_true")]
+    pub rar_volumes: bool,
+
+    /// Rename the largest obfuscated file after the NZB file name (default: true)
+    #[serde(default = "default_true")]
+    pub hash_names: bool,
+
+    /// Rename the largest obfuscated file after the NZB meta title (default: true)
+    #[serde(default = "default_true")]
+    pub nzb_meta: bool,
+}
+
+impl DeobfuscationConfig {
+    /// Whether the strategy called `name` should run
+    ///
+    /// Built-in strategies follow their flag; strategies registered by embedders
+    /// always run.
+    pub fn strategy_enabled(&self, name: &str) -> bool {
+        match name {
+            "par2_names" => self.par2_names,
+            "rar_volumes" => self.rar_volumes,
+            "hash_names" => self.hash_names,
+            "nzb_meta" => self.nzb_meta,
+            _ => true,
+        }
+    }
 }
 
 impl Default for DeobfuscationConfig {
@@ -397,6 +1248,10 @@ impl Default for DeobfuscationConfig {
         Self {
             enabled: true,
             min_length: 12,
+            par2_names: true,
+            rar_volumes: true,
+            hash_names: true,
+            nzb_meta: true,
         }
     }
 }
@@ -438,6 +1293,8 @@ pub enum DuplicateAction {
     Warn,
     /// Allow silently
     Allow,
+    /// Hold the download until it's accepted or discarded
+    Ask,
 }
 
 /// Duplicate detection method
@@ -452,6 +1309,26 @@ pub enum DuplicateMethod {
     JobName,
 }
 
+impl DuplicateMethod {
+    /// Convert DuplicateMethod enum to integer for database storage
+    pub fn to_i32(&self) -> i32 {
+        match self {
+            DuplicateMethod::NzbHash => 0,
+            DuplicateMethod::NzbName => 1,
+            DuplicateMethod::JobName => 2,
+        }
+    }
+
+    /// Convert integer from database to DuplicateMethod enum
+    pub fn from_i32(value: i32) -> Self {
+        match value {
+            1 => DuplicateMethod::NzbName,
+            2 => DuplicateMethod::JobName,
+            _ => DuplicateMethod::NzbHash, // Default
+        }
+    }
+}
+
 /// Disk space checking configuration
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct DiskSpaceConfig {
@@ -526,34 +1403,240 @@ pub struct DirectUnpackConfig {
     #[serde(default)]
     pub enabled: bool,
 
-    /// Enable DirectRename — use PAR2 metadata to fix obfuscated filenames (default: false)
-    ///
-    /// Requires PAR2 files to be downloaded early. When enabled, PAR2 file articles
-    /// are prioritized in the download queue.
+    /// Enable DirectRename — use PAR2 metadata to fix obfuscated filenames (default: false)
+    ///
+    /// Requires PAR2 files to be downloaded early. When enabled, PAR2 file articles
+    /// are prioritized in the download queue.
+    #[serde(default)]
+    pub direct_rename: bool,
+
+    /// How often to poll for newly completed files, in milliseconds (default: 200)
+    #[serde(default = "default_direct_unpack_poll_interval")]
+    pub poll_interval_ms: u64,
+}
+
+impl Default for DirectUnpackConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            direct_rename: false,
+            poll_interval_ms: default_direct_unpack_poll_interval(),
+        }
+    }
+}
+
+/// Permissions and ownership normalization for moved files (Unix only)
+///
+/// Applied by the move stage to every file and directory it places in the destination,
+/// so extracted files (often mode 0600) are readable by e.g. a media server user.
+/// Modes are octal strings such as `"0644"`. All fields default to `None`, which leaves
+/// permissions and ownership untouched. Ignored on non-Unix platforms.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct PermissionsConfig {
+    /// Mode for files (e.g. "0644"); takes precedence over `umask` for files
+    #[serde(default, with = "optional_octal_mode_serde")]
+    #[schema(value_type = Option<String>)]
+    pub file_mode: Option<u32>,
+
+    /// Mode for directories (e.g. "0755"); takes precedence over `umask` for directories
+    #[serde(default, with = "optional_octal_mode_serde")]
+    #[schema(value_type = Option<String>)]
+    pub dir_mode: Option<u32>,
+
+    /// Bits to clear from existing modes when no explicit mode is set (e.g. "0022")
+    #[serde(default, with = "optional_octal_mode_serde")]
+    #[schema(value_type = Option<String>)]
+    pub umask: Option<u32>,
+
+    /// Owner user ID to chown to (requires sufficient privileges)
+    #[serde(default)]
+    pub uid: Option<u32>,
+
+    /// Owner group ID to chown to (requires sufficient privileges or group membership)
+    #[serde(default)]
+    pub gid: Option<u32>,
+}
+
+/// File and folder name sanitization applied by the move stage
+///
+/// With `windows_compatible` enabled, names that Windows can't create are rewritten:
+/// reserved device names (`CON`, `NUL`, `COM1`, ...) get a `_` suffix, trailing dots and
+/// spaces are trimmed, and the characters `<>:"\|?*` plus control characters are replaced.
+/// Long paths are always handled on Windows via the `\\?\` prefix.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct PathSanitizationConfig {
+    /// Apply Windows naming rules (default: true on Windows, false elsewhere).
+    /// Enable on other platforms when the destination is shared with Windows clients (SMB).
+    #[serde(default = "default_windows_compatible")]
+    pub windows_compatible: bool,
+
+    /// Replace illegal characters with full-width look-alikes (`:` becomes `：`) instead
+    /// of `_`, keeping names readable (default: false)
+    #[serde(default)]
+    pub transliterate_illegal_chars: bool,
+}
+
+impl Default for PathSanitizationConfig {
+    fn default() -> Self {
+        Self {
+            windows_compatible: default_windows_compatible(),
+            transliterate_illegal_chars: false,
+        }
+    }
+}
+
+/// Junk files removed from a download right before the move stage
+///
+/// Extensions are matched case-insensitively; the leading dot is optional. Junk is
+/// deleted from the temp folder, so it never reaches the destination.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct JunkFilterConfig {
+    /// Enable the junk filter (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Extensions of files that are always junk (default: `url`, `lnk`, `txt`)
+    #[serde(default = "default_junk_extensions")]
+    pub extensions: Vec<String>,
+
+    /// Extensions of files that are junk in a release containing a video file
+    /// (default: `exe`, `scr`, `bat`, `cmd`)
+    #[serde(default = "default_junk_video_release_extensions")]
+    pub video_release_extensions: Vec<String>,
+
+    /// Extensions that make a release a video release
+    /// (default: `mkv`, `mp4`, `avi`, `m4v`, `mov`, `wmv`, `ts`)
+    #[serde(default = "default_video_extensions")]
+    pub video_extensions: Vec<String>,
+
+    /// Remove zero-byte files (default: true)
+    #[serde(default = "default_true")]
+    pub empty_files: bool,
+}
+
+impl Default for JunkFilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            extensions: default_junk_extensions(),
+            video_release_extensions: default_junk_video_release_extensions(),
+            video_extensions: default_video_extensions(),
+            empty_files: true,
+        }
+    }
+}
+
+/// Subtitle organizer run right before the move stage
+///
+/// Archives inside subtitle folders are extracted, and the subtitles found there are
+/// renamed after the video they belong to (`Subs/2_eng.srt` becomes `Movie.eng.srt`)
+/// and placed beside it.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SubtitleConfig {
+    /// Enable the subtitle organizer (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Names of subtitle folders, matched case-insensitively (default: `Subs`, `Subtitles`)
+    #[serde(default = "default_subtitle_folder_names")]
+    pub folder_names: Vec<String>,
+
+    /// Extensions of subtitle files
+    /// (default: `srt`, `sub`, `idx`, `ass`, `ssa`, `vtt`, `sup`)
+    #[serde(default = "default_subtitle_extensions")]
+    pub extensions: Vec<String>,
+
+    /// Extensions of the video files subtitles are paired with
+    /// (default: `mkv`, `mp4`, `avi`, `m4v`, `mov`, `wmv`, `ts`)
+    #[serde(default = "default_video_extensions")]
+    pub video_extensions: Vec<String>,
+}
+
+impl Default for SubtitleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            folder_names: default_subtitle_folder_names(),
+            extensions: default_subtitle_extensions(),
+            video_extensions: default_video_extensions(),
+        }
+    }
+}
+
+/// Virus scan run on each download after extraction, before the move stage
+///
+/// The scanner is run once per download as `command args... <path>`, where `<path>` is
+/// the folder about to be moved. An exit code listed in `infected_exit_codes` means
+/// something was found; each non-empty line the scanner prints is reported as a
+/// detection. Any other non-zero exit code, or running past `timeout`, fails the download.
+/// The defaults suit ClamAV's `clamscan`.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScanConfig {
+    /// Enable the scan stage (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Scanner executable (default: "clamscan", looked up in PATH)
+    #[serde(default = "default_scan_command")]
+    pub command: PathBuf,
+
+    /// Arguments passed before the path (default: `--infected --recursive --no-summary`)
+    #[serde(default = "default_scan_args")]
+    pub args: Vec<String>,
+
+    /// Exit codes that mean infected files were found (default: `[1]`)
+    #[serde(default = "default_scan_infected_exit_codes")]
+    pub infected_exit_codes: Vec<i32>,
+
+    /// How long the scanner may run before the download fails (default: 10 minutes)
+    #[serde(default = "default_scan_timeout", with = "duration_serde")]
+    pub timeout: Duration,
+
+    /// Skip the scan for downloads larger than this many bytes (default: no limit)
     #[serde(default)]
-    pub direct_rename: bool,
+    pub max_size: Option<u64>,
 
-    /// How often to poll for newly completed files, in milliseconds (default: 200)
-    #[serde(default = "default_direct_unpack_poll_interval")]
-    pub poll_interval_ms: u64,
+    /// What happens to a download with infected files (default: fail)
+    #[serde(default)]
+    pub action: ScanAction,
+
+    /// Where quarantined downloads are moved (default: `quarantine` in `download.temp_dir`)
+    #[serde(default)]
+    pub quarantine_dir: Option<PathBuf>,
 }
 
-impl Default for DirectUnpackConfig {
+impl Default for ScanConfig {
     fn default() -> Self {
         Self {
             enabled: false,
-            direct_rename: false,
-            poll_interval_ms: default_direct_unpack_poll_interval(),
+            command: default_scan_command(),
+            args: default_scan_args(),
+            infected_exit_codes: default_scan_infected_exit_codes(),
+            timeout: default_scan_timeout(),
+            max_size: None,
+            action: ScanAction::default(),
+            quarantine_dir: None,
         }
     }
 }
 
+/// What happens to a download whose virus scan found infected files
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanAction {
+    /// Fail the download, leaving its files in the temp folder (default)
+    #[default]
+    Fail,
+    /// Fail the download and move its temp folder to `quarantine_dir`
+    Quarantine,
+}
+
 /// Content pipeline processing configuration
 ///
 /// Groups settings related to post-download file processing, validation,
 /// and cleanup. All settings in this config are used together during the
 /// post-processing pipeline.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct ProcessingConfig {
     /// Archive extraction configuration
     #[serde(default)]
@@ -578,6 +1661,104 @@ pub struct ProcessingConfig {
     /// DirectUnpack — extract archives while download is still in progress
     #[serde(default)]
     pub direct_unpack: DirectUnpackConfig,
+
+    /// Permissions and ownership applied to files placed in the destination (Unix only)
+    #[serde(default)]
+    pub permissions: PermissionsConfig,
+
+    /// File and folder name sanitization for the destination
+    #[serde(default)]
+    pub path_sanitization: PathSanitizationConfig,
+
+    /// Keep a download's temp folder when repair or extraction fails (default: true)
+    ///
+    /// The files left behind are listed by `GET /downloads/{id}/artifacts`, so content
+    /// can be salvaged by hand. When false the temp folder is deleted on failure.
+    #[serde(default = "default_true")]
+    pub keep_intermediate_on_failure: bool,
+
+    /// Metadata file written into the destination of each completed download (default: none)
+    ///
+    /// Named `{job_name}.usenet-dl.json` or `.usenet-dl.nfo`, it records the NZB name,
+    /// category, a sample of message-IDs, timestamps and what repair and extraction did.
+    #[serde(default)]
+    pub completion_sidecar: SidecarFormat,
+
+    /// Maximum number of downloads post-processed at the same time (default: 2)
+    ///
+    /// Further downloads that finish downloading wait in the post-processing queue, so a
+    /// burst of completions doesn't start many repairs and extractions at once.
+    #[serde(default = "default_max_parallel_jobs")]
+    pub max_parallel_jobs: usize,
+
+    /// Virus scan of extracted files before they're moved (default: disabled)
+    #[serde(default)]
+    pub scan: ScanConfig,
+
+    /// Removal of junk files before they're moved (default: disabled)
+    #[serde(default)]
+    pub junk_filter: JunkFilterConfig,
+
+    /// Pairing of subtitles with their video before they're moved (default: disabled)
+    #[serde(default)]
+    pub subtitles: SubtitleConfig,
+
+    /// When PAR2 verification runs (default: when_pars_present)
+    ///
+    /// Applies whatever the download's post-processing mode, except `none`, which skips
+    /// every stage. Categories can override it, e.g. to skip verification for trusted
+    /// sources on a slow CPU.
+    #[serde(default)]
+    pub verify_policy: VerifyPolicy,
+}
+
+impl Default for ProcessingConfig {
+    fn default() -> Self {
+        Self {
+            extraction: ExtractionConfig::default(),
+            duplicate: DuplicateConfig::default(),
+            disk_space: DiskSpaceConfig::default(),
+            retry: RetryConfig::default(),
+            cleanup: CleanupConfig::default(),
+            direct_unpack: DirectUnpackConfig::default(),
+            permissions: PermissionsConfig::default(),
+            path_sanitization: PathSanitizationConfig::default(),
+            keep_intermediate_on_failure: true,
+            completion_sidecar: SidecarFormat::default(),
+            max_parallel_jobs: default_max_parallel_jobs(),
+            scan: ScanConfig::default(),
+            junk_filter: JunkFilterConfig::default(),
+            subtitles: SubtitleConfig::default(),
+            verify_policy: VerifyPolicy::default(),
+        }
+    }
+}
+
+/// When the verify stage runs PAR2 verification
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyPolicy {
+    /// Verify every download; one without PAR2 files, or without a PAR2 tool able to
+    /// verify, fails
+    Always,
+    /// Verify downloads that have PAR2 files (default)
+    #[default]
+    WhenParsPresent,
+    /// Skip verification and repair
+    Never,
+}
+
+/// Format of the metadata sidecar written for completed downloads
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SidecarFormat {
+    /// Don't write a sidecar
+    #[default]
+    None,
+    /// JSON document
+    Json,
+    /// Plain-text `key: value` lines
+    Nfo,
 }
 
 /// Automated content discovery and ingestion configuration
@@ -597,6 +1778,143 @@ pub struct AutomationConfig {
     /// Filename deobfuscation configuration
     #[serde(default)]
     pub deobfuscation: DeobfuscationConfig,
+
+    /// Skipping releases already grabbed from another RSS feed
+    #[serde(default)]
+    pub rss_dedup: RssDedupConfig,
+}
+
+/// Cross-feed RSS deduplication
+///
+/// The same release often shows up on several indexer feeds. Releases are compared
+/// by normalized title (case and punctuation are ignored) and size.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct RssDedupConfig {
+    /// Skip releases already grabbed from any feed (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Sizes within this percentage of each other count as the same release (default: 5)
+    ///
+    /// When either size is unknown the title alone decides.
+    #[serde(default = "default_rss_dedup_size_tolerance")]
+    pub size_tolerance_percent: f64,
+
+    /// How long a grab is remembered (default: 7 days)
+    #[serde(default = "default_rss_dedup_window", with = "duration_serde")]
+    pub window: Duration,
+}
+
+impl Default for RssDedupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            size_tolerance_percent: default_rss_dedup_size_tolerance(),
+            window: default_rss_dedup_window(),
+        }
+    }
+}
+
+/// Diagnostic logging configuration
+///
+/// Nested under `logging` in config files.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct LoggingConfig {
+    /// Record NNTP commands and responses per server for debugging (default: false)
+    ///
+    /// Credentials are redacted. Retrieve the trace via `GET /api/v1/servers/{id}/trace`.
+    #[serde(default)]
+    pub nntp_trace: bool,
+
+    /// Number of trace entries kept per server; oldest entries are dropped (default: 500)
+    #[serde(default = "default_nntp_trace_capacity")]
+    pub nntp_trace_capacity: usize,
+
+    /// Most verbose level captured for `GET /api/v1/system/log/stream` (default: info)
+    ///
+    /// Only takes effect when [`crate::log_stream::layer`] is part of the application's
+    /// tracing subscriber.
+    #[serde(default)]
+    pub log_stream_level: LogLevel,
+
+    /// Number of recent log events replayed to new log stream clients (default: 1000)
+    #[serde(default = "default_log_stream_capacity")]
+    pub log_stream_capacity: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            nntp_trace: false,
+            nntp_trace_capacity: default_nntp_trace_capacity(),
+            log_stream_level: LogLevel::default(),
+            log_stream_capacity: default_log_stream_capacity(),
+        }
+    }
+}
+
+/// Log verbosity, from least to most verbose
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// Errors only
+    Error,
+    /// Warnings and errors
+    Warn,
+    /// Informational messages and above
+    #[default]
+    Info,
+    /// Debug messages and above
+    Debug,
+    /// Everything
+    Trace,
+}
+
+impl LogLevel {
+    /// The matching tracing level
+    pub fn to_tracing(self) -> tracing::Level {
+        match self {
+            LogLevel::Error => tracing::Level::ERROR,
+            LogLevel::Warn => tracing::Level::WARN,
+            LogLevel::Info => tracing::Level::INFO,
+            LogLevel::Debug => tracing::Level::DEBUG,
+            LogLevel::Trace => tracing::Level::TRACE,
+        }
+    }
+
+    /// The matching log level for a tracing level
+    pub fn from_tracing(level: tracing::Level) -> Self {
+        match level {
+            tracing::Level::ERROR => LogLevel::Error,
+            tracing::Level::WARN => LogLevel::Warn,
+            tracing::Level::INFO => LogLevel::Info,
+            tracing::Level::DEBUG => LogLevel::Debug,
+            tracing::Level::TRACE => LogLevel::Trace,
+        }
+    }
+}
+
+/// Schedule rule evaluation configuration
+///
+/// Nested under `scheduler` in config files.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct SchedulerConfig {
+    /// IANA time zone schedule rules are evaluated in, e.g. `"Europe/Amsterdam"`
+    /// (default: the host's local time zone)
+    ///
+    /// Containers usually run in UTC, so set this to keep "22:00" meaning 22:00 where
+    /// the user is.
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+impl SchedulerConfig {
+    /// The configured time zone, or `None` to use the host's local time zone
+    pub fn tz(&self) -> Option<chrono_tz::Tz> {
+        self.timezone.as_deref()?.parse().ok()
+    }
 }
 
 /// Data storage and state management configuration
@@ -616,6 +1934,34 @@ pub struct PersistenceConfig {
     /// Category configurations
     #[serde(default)]
     pub categories: HashMap<String, CategoryConfig>,
+
+    /// Directory that keeps a copy of every added NZB (default: None, disabled)
+    ///
+    /// Files are named `{download_id}-{job_name}.nzb`.
+    #[serde(default)]
+    pub nzb_backup_dir: Option<PathBuf>,
+
+    /// Delete NZB backups older than this (seconds, default: None, keep forever)
+    #[serde(default, with = "optional_duration_serde")]
+    pub nzb_backup_max_age: Option<Duration>,
+
+    /// Keep at most this many NZB backups, deleting the oldest (default: None, unlimited)
+    #[serde(default)]
+    pub nzb_backup_max_count: Option<usize>,
+
+    /// How often queued download progress is written to the database (milliseconds, default: 1000)
+    ///
+    /// Progress and article results are buffered in memory and written together in one
+    /// transaction per interval.
+    #[serde(default = "default_progress_flush_interval_ms")]
+    pub progress_flush_interval_ms: u64,
+
+    /// Number of recent events kept in the database for replay (default: 10000, 0 disables)
+    ///
+    /// Subscribers that missed events can replay them by sequence number, e.g. with
+    /// `GET /events?since_seq=N`. Progress events aren't journaled.
+    #[serde(default = "default_event_journal_size")]
+    pub event_journal_size: usize,
 }
 
 impl Default for PersistenceConfig {
@@ -624,6 +1970,11 @@ impl Default for PersistenceConfig {
             database_path: default_database_path(),
             schedule_rules: vec![],
             categories: HashMap::new(),
+            nzb_backup_dir: None,
+            nzb_backup_max_age: None,
+            nzb_backup_max_count: None,
+            progress_flush_interval_ms: default_progress_flush_interval_ms(),
+            event_journal_size: default_event_journal_size(),
         }
     }
 }
@@ -664,6 +2015,10 @@ pub struct ApiConfig {
     /// Rate limiting configuration
     #[serde(default)]
     pub rate_limit: RateLimitConfig,
+
+    /// Liveness and readiness probe configuration
+    #[serde(default)]
+    pub health: HealthConfig,
 }
 
 impl Default for ApiConfig {
@@ -675,6 +2030,7 @@ impl Default for ApiConfig {
             cors_origins: default_cors_origins(),
             swagger_ui: true,
             rate_limit: RateLimitConfig::default(),
+            health: HealthConfig::default(),
         }
     }
 }
@@ -715,13 +2071,51 @@ impl Default for RateLimitConfig {
     }
 }
 
+/// Health probe configuration
+///
+/// Controls which checks run for `/health/live` and `/health/ready`.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct HealthConfig {
+    /// Also require the database to answer queries for liveness (default: false)
+    #[serde(default)]
+    pub live_check_database: bool,
+
+    /// Require all database migrations to be applied for readiness (default: true)
+    #[serde(default = "default_true")]
+    pub ready_check_database: bool,
+
+    /// Require at least one NNTP server for readiness (default: true)
+    #[serde(default = "default_true")]
+    pub ready_check_servers: bool,
+
+    /// Connect to the servers instead of only checking that one is configured (default: false)
+    #[serde(default)]
+    pub ready_probe_servers: bool,
+
+    /// Require the temp directory to be writable for readiness (default: true)
+    #[serde(default = "default_true")]
+    pub ready_check_temp_dir: bool,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            live_check_database: false,
+            ready_check_database: true,
+            ready_check_servers: true,
+            ready_probe_servers: false,
+            ready_check_temp_dir: true,
+        }
+    }
+}
+
 /// Schedule rule for time-based actions
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScheduleRule {
     /// Human-readable name
     pub name: String,
 
-    /// Days this rule applies (empty = all days)
+    /// Days this rule applies (empty = all days; ignored for one-off rules)
     #[serde(default)]
     pub days: Vec<Weekday>,
 
@@ -737,6 +2131,50 @@ pub struct ScheduleRule {
     /// Whether rule is active
     #[serde(default = "default_true")]
     pub enabled: bool,
+
+    /// First day of a one-off rule (YYYY-MM-DD), which starts at `start_time` that day
+    #[serde(default)]
+    pub start_date: Option<String>,
+
+    /// Last day of a one-off rule (YYYY-MM-DD), which ends at `end_time` that day
+    #[serde(default)]
+    pub end_date: Option<String>,
+}
+
+impl ScheduleRule {
+    /// Dates of a one-off rule, or `None` for a rule that repeats weekly
+    ///
+    /// On error, returns the offending field and what is wrong with it: a malformed
+    /// date, only one of `start_date` and `end_date` set, or a rule that ends before
+    /// it starts.
+    pub fn date_range(
+        &self,
+    ) -> std::result::Result<
+        Option<(chrono::NaiveDate, chrono::NaiveDate)>,
+        (&'static str, &'static str),
+    > {
+        let parse = |field, date: &str| {
+            chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| (field, "must be a date in YYYY-MM-DD format"))
+        };
+        let (start, end) = match (&self.start_date, &self.end_date) {
+            (None, None) => return Ok(None),
+            (Some(start), Some(end)) => (parse("start_date", start)?, parse("end_date", end)?),
+            (Some(_), None) => return Err(("end_date", "must be set with start_date")),
+            (None, Some(_)) => return Err(("start_date", "must be set with end_date")),
+        };
+
+        let times = (
+            chrono::NaiveTime::parse_from_str(&self.start_time, "%H:%M"),
+            chrono::NaiveTime::parse_from_str(&self.end_time, "%H:%M"),
+        );
+        if let (Ok(start_time), Ok(end_time)) = times
+            && start.and_time(start_time) >= end.and_time(end_time)
+        {
+            return Err(("end_date", "must be after the start date and time"));
+        }
+        Ok(Some((start, end)))
+    }
 }
 
 /// Day of week for schedule rules
@@ -787,9 +2225,55 @@ pub struct WatchFolderConfig {
     #[serde(default)]
     pub category: Option<String>,
 
-    /// Scan interval (default: 5 seconds)
+    /// How changes in the folder are detected (default: `auto`)
+    #[serde(default)]
+    pub mode: WatchMode,
+
+    /// Scan interval when the folder is polled (default: 5 seconds)
     #[serde(default = "default_scan_interval", with = "duration_serde")]
     pub scan_interval: Duration,
+
+    /// Where `move_to_processed` moves imported files (default: `processed`)
+    ///
+    /// A relative path is resolved against the watch folder.
+    #[serde(default)]
+    pub processed_dir: Option<PathBuf>,
+
+    /// Where files that can't be imported are moved, each with a `<file>.error.txt`
+    /// giving the reason (default: `error`)
+    ///
+    /// A relative path is resolved against the watch folder.
+    #[serde(default)]
+    pub error_dir: Option<PathBuf>,
+}
+
+impl WatchFolderConfig {
+    /// Folder `move_to_processed` moves imported files into
+    pub fn processed_path(&self) -> PathBuf {
+        let dir = self.processed_dir.as_deref();
+        self.path
+            .join(dir.unwrap_or(std::path::Path::new("processed")))
+    }
+
+    /// Folder files that can't be imported are moved into
+    pub fn error_path(&self) -> PathBuf {
+        let dir = self.error_dir.as_deref();
+        self.path.join(dir.unwrap_or(std::path::Path::new("error")))
+    }
+}
+
+/// How a watch folder detects new files
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchMode {
+    /// Use filesystem events, falling back to polling on network filesystems or when
+    /// events don't arrive (default)
+    #[default]
+    Auto,
+    /// Always use filesystem events
+    Events,
+    /// Always scan the folder every `scan_interval`
+    Poll,
 }
 
 /// Action to take with NZB file after import
@@ -798,11 +2282,13 @@ pub struct WatchFolderConfig {
 pub enum WatchFolderAction {
     /// Delete NZB file
     Delete,
-    /// Move to a 'processed' subfolder (default)
+    /// Move to the processed folder (default)
     #[default]
     MoveToProcessed,
     /// Keep in place
     Keep,
+    /// Rename in place with an `.imported` suffix
+    RenameImported,
 }
 
 /// Webhook configuration
@@ -821,6 +2307,17 @@ pub struct WebhookConfig {
     /// Timeout for webhook requests (default: 30 seconds)
     #[serde(default = "default_webhook_timeout", with = "duration_serde")]
     pub timeout: Duration,
+
+    /// Send one summary per this interval instead of a request per download
+    /// (seconds, default: None)
+    ///
+    /// The summary is a [`WebhookDigest`](crate::types::WebhookDigest) listing the
+    /// payloads of the period; a period with nothing to report sends nothing. Digests
+    /// are sent once [`start_webhook_digests`] is called, and what's left on shutdown.
+    ///
+    /// [`start_webhook_digests`]: crate::UsenetDownloader::start_webhook_digests
+    #[serde(default, with = "optional_duration_serde")]
+    pub digest_interval: Option<Duration>,
 }
 
 /// Webhook trigger event
@@ -857,6 +2354,62 @@ pub enum ScriptEvent {
     OnFailed,
     /// Triggered when post-processing completes
     OnPostProcessComplete,
+    /// Triggered once when every download in a batch has finished (succeeded or failed)
+    OnBatchComplete,
+}
+
+/// MQTT publisher configuration
+///
+/// Events go to `{topic_prefix}/{event}`, e.g. `usenet-dl/completed`. Two retained
+/// topics carry state for home-automation setups: `{topic_prefix}/status` is `online`
+/// while connected and `offline` (the last will) otherwise, and `{topic_prefix}/queue`
+/// is `paused` or `running`. See [`MqttPublisher`](crate::notifications::mqtt::MqttPublisher).
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct MqttConfig {
+    /// Broker hostname
+    pub host: String,
+
+    /// Broker port (default: 1883)
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+
+    /// Client identifier sent to the broker (default: "usenet-dl")
+    #[serde(default = "default_mqtt_client_id")]
+    pub client_id: String,
+
+    /// Username for authentication
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Password for authentication
+    #[serde(default)]
+    pub password: Option<String>,
+
+    /// Prefix of every published topic (default: "usenet-dl")
+    #[serde(default = "default_mqtt_topic_prefix")]
+    pub topic_prefix: String,
+
+    /// Events to publish (default: all)
+    #[serde(default = "default_mqtt_events")]
+    pub events: Vec<MqttEvent>,
+
+    /// Interval between keep-alive pings (default: 60 seconds)
+    #[serde(default = "default_mqtt_keep_alive", with = "duration_serde")]
+    pub keep_alive: Duration,
+}
+
+/// Event published over MQTT
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MqttEvent {
+    /// A download was added to the queue (topic `added`)
+    Added,
+    /// A download finished post-processing (topic `completed`)
+    Completed,
+    /// A download failed while downloading or post-processing (topic `failed`)
+    Failed,
+    /// The queue was paused or resumed (retained topic `queue`)
+    QueuePaused,
 }
 
 /// RSS feed configuration
@@ -888,6 +2441,61 @@ pub struct RssFeedConfig {
     /// Whether feed is active
     #[serde(default = "default_true")]
     pub enabled: bool,
+
+    /// Authentication sent when fetching the feed
+    #[serde(default)]
+    pub auth: RssFeedAuth,
+
+    /// Rank among feeds carrying the same releases (lower is preferred, default: 0)
+    ///
+    /// Feeds due for a check at the same time are checked in this order, so with
+    /// [`RssDedupConfig`] a release is grabbed from the most preferred of them.
+    #[serde(default)]
+    pub preference: i32,
+}
+
+/// Authentication for fetching an RSS feed
+///
+/// Indexer feeds usually need credentials; these are added to each feed request
+/// instead of being embedded in the URL.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RssFeedAuth {
+    /// Extra HTTP headers (e.g. `{"X-Api-Key": "..."}`)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// Username for HTTP basic auth
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Password for HTTP basic auth (ignored without `username`)
+    #[serde(default)]
+    pub password: Option<String>,
+
+    /// Cookies sent in the `Cookie` header (name to value)
+    #[serde(default)]
+    pub cookies: HashMap<String, String>,
+
+    /// API key appended to the feed URL as the `api_key_param` query parameter
+    #[serde(default)]
+    pub api_key: Option<String>,
+
+    /// Query parameter carrying `api_key` (default: "apikey", as used by Newznab indexers)
+    #[serde(default = "default_rss_api_key_param")]
+    pub api_key_param: String,
+}
+
+impl Default for RssFeedAuth {
+    fn default() -> Self {
+        Self {
+            headers: HashMap::new(),
+            username: None,
+            password: None,
+            cookies: HashMap::new(),
+            api_key: None,
+            api_key_param: default_rss_api_key_param(),
+        }
+    }
 }
 
 /// RSS feed filter
@@ -906,15 +2514,56 @@ pub struct RssFilter {
 
     /// Minimum size (bytes)
     #[serde(default)]
-    pub min_size: Option<u64>,
+    pub min_size: Option<u64>,
+
+    /// Maximum size (bytes)
+    #[serde(default)]
+    pub max_size: Option<u64>,
+
+    /// Maximum age from publish date (seconds)
+    #[serde(default, with = "optional_duration_serde")]
+    pub max_age: Option<Duration>,
+
+    /// What to do with items this filter matches
+    #[serde(default)]
+    pub action: RssFilterAction,
+
+    /// Category for matched items (overrides the feed's category)
+    #[serde(default)]
+    pub category: Option<String>,
 
-    /// Maximum size (bytes)
+    /// Priority for matched items (overrides the feed's priority)
     #[serde(default)]
-    pub max_size: Option<u64>,
+    pub priority: Option<crate::types::Priority>,
+}
 
-    /// Maximum age from publish date (seconds)
-    #[serde(default, with = "optional_duration_serde")]
-    pub max_age: Option<Duration>,
+/// What an RSS filter does with the items it matches
+///
+/// A matching `reject` filter always wins; otherwise the first matching filter decides.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RssFilterAction {
+    /// Download the item (if the feed auto-downloads)
+    #[default]
+    Download = 0,
+    /// Add the item to the queue paused
+    DownloadPaused = 1,
+    /// Emit an `rss_item_matched` event without downloading
+    Notify = 2,
+    /// Drop the item even if another filter matches
+    Reject = 3,
+}
+
+impl RssFilterAction {
+    /// Convert integer action code to RssFilterAction enum
+    pub fn from_i32(action: i32) -> Self {
+        match action {
+            1 => RssFilterAction::DownloadPaused,
+            2 => RssFilterAction::Notify,
+            3 => RssFilterAction::Reject,
+            _ => RssFilterAction::Download,
+        }
+    }
 }
 
 /// Category configuration
@@ -930,6 +2579,18 @@ pub struct CategoryConfig {
     /// Category-specific scripts
     #[serde(default)]
     pub scripts: Vec<ScriptConfig>,
+
+    /// Override `processing.extraction.flatten_single_folder` for this category
+    #[serde(default)]
+    pub flatten_single_folder: Option<bool>,
+
+    /// Override `processing.junk_filter` for this category
+    #[serde(default)]
+    pub junk_filter: Option<JunkFilterConfig>,
+
+    /// Override `processing.verify_policy` for this category
+    #[serde(default)]
+    pub verify_policy: Option<VerifyPolicy>,
 }
 
 // Default value functions
@@ -945,6 +2606,18 @@ fn default_max_concurrent() -> usize {
     3
 }
 
+fn default_progress_flush_interval_ms() -> u64 {
+    1000
+}
+
+fn default_event_journal_size() -> usize {
+    10_000
+}
+
+fn default_max_parallel_jobs() -> usize {
+    2
+}
+
 fn default_database_path() -> PathBuf {
     PathBuf::from("usenet-dl.db")
 }
@@ -961,6 +2634,60 @@ fn default_true() -> bool {
     true
 }
 
+fn default_stall_no_progress_timeout() -> Duration {
+    Duration::from_secs(600)
+}
+
+fn default_stall_stage_timeout() -> Duration {
+    Duration::from_secs(2 * 60 * 60)
+}
+
+fn default_metered_speed_limit_bps() -> Option<u64> {
+    Some(1024 * 1024)
+}
+
+fn default_metered_max_job_bytes() -> Option<u64> {
+    Some(1024 * 1024 * 1024)
+}
+
+fn default_fast_lane_max_job_bytes() -> u64 {
+    500 * 1024 * 1024
+}
+
+fn default_fast_lane_connections() -> usize {
+    4
+}
+
+fn default_simulate_speed_bps() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_stall_max_attempts() -> u32 {
+    3
+}
+
+fn default_scan_command() -> PathBuf {
+    PathBuf::from("clamscan")
+}
+
+fn default_scan_args() -> Vec<String> {
+    ["--infected", "--recursive", "--no-summary"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_scan_infected_exit_codes() -> Vec<i32> {
+    vec![1]
+}
+
+fn default_scan_timeout() -> Duration {
+    Duration::from_secs(600)
+}
+
+fn default_password_lookup_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
 fn default_max_failure_ratio() -> f64 {
     0.5
 }
@@ -973,6 +2700,14 @@ fn default_fast_fail_sample_size() -> usize {
     10
 }
 
+fn default_completion_sample_size() -> usize {
+    100
+}
+
+fn default_min_completion() -> f64 {
+    0.9
+}
+
 fn default_max_attempts() -> u32 {
     5
 }
@@ -1001,6 +2736,7 @@ fn default_archive_extensions() -> Vec<String> {
         "tar".into(),
         "gz".into(),
         "bz2".into(),
+        "zst".into(),
     ]
 }
 
@@ -1059,6 +2795,31 @@ fn default_webhook_timeout() -> Duration {
     Duration::from_secs(30)
 }
 
+fn default_mqtt_port() -> u16 {
+    1883
+}
+
+fn default_mqtt_client_id() -> String {
+    "usenet-dl".to_string()
+}
+
+fn default_mqtt_topic_prefix() -> String {
+    "usenet-dl".to_string()
+}
+
+fn default_mqtt_events() -> Vec<MqttEvent> {
+    vec![
+        MqttEvent::Added,
+        MqttEvent::Completed,
+        MqttEvent::Failed,
+        MqttEvent::QueuePaused,
+    ]
+}
+
+fn default_mqtt_keep_alive() -> Duration {
+    Duration::from_secs(60)
+}
+
 fn default_script_timeout() -> Duration {
     Duration::from_secs(300) // 5 minutes
 }
@@ -1078,6 +2839,48 @@ fn default_cleanup_extensions() -> Vec<String> {
     ]
 }
 
+fn default_nntp_trace_capacity() -> usize {
+    500
+}
+
+fn default_log_stream_capacity() -> usize {
+    1000
+}
+
+fn default_link_retention() -> Duration {
+    Duration::from_secs(24 * 60 * 60)
+}
+
+fn default_junk_extensions() -> Vec<String> {
+    vec!["url".into(), "lnk".into(), "txt".into()]
+}
+
+fn default_junk_video_release_extensions() -> Vec<String> {
+    vec!["exe".into(), "scr".into(), "bat".into(), "cmd".into()]
+}
+
+fn default_video_extensions() -> Vec<String> {
+    ["mkv", "mp4", "avi", "m4v", "mov", "wmv", "ts"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_subtitle_folder_names() -> Vec<String> {
+    vec!["Subs".into(), "Subtitles".into()]
+}
+
+fn default_subtitle_extensions() -> Vec<String> {
+    ["srt", "sub", "idx", "ass", "ssa", "vtt", "sup"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_windows_compatible() -> bool {
+    cfg!(windows)
+}
+
 fn default_sample_folder_names() -> Vec<String> {
     vec![
         "sample".into(),
@@ -1093,6 +2896,18 @@ fn default_rss_check_interval() -> Duration {
     Duration::from_secs(15 * 60) // 15 minutes
 }
 
+fn default_rss_dedup_size_tolerance() -> f64 {
+    5.0
+}
+
+fn default_rss_dedup_window() -> Duration {
+    Duration::from_secs(7 * 24 * 60 * 60) // 7 days
+}
+
+fn default_rss_api_key_param() -> String {
+    "apikey".to_string()
+}
+
 fn default_direct_unpack_poll_interval() -> u64 {
     200
 }
@@ -1142,14 +2957,54 @@ mod optional_duration_serde {
     }
 }
 
+// Optional octal file mode serialization helper ("0644" <-> 0o644)
+mod optional_octal_mode_serde {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(mode: &Option<u32>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match mode {
+            Some(m) => serializer.serialize_some(&format!("{:04o}", m)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Some(s) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let digits = s.trim().trim_start_matches("0o");
+        let mode = u32::from_str_radix(digits, 8)
+            .map_err(|_| serde::de::Error::custom(format!("invalid octal mode: {:?}", s)))?;
+        if mode > 0o7777 {
+            return Err(serde::de::Error::custom(format!(
+                "mode out of range: {:?}",
+                s
+            )));
+        }
+        Ok(Some(mode))
+    }
+}
+
 // Conversion from our ServerConfig to nntp-rs's ServerConfig
 impl From<ServerConfig> for nntp_rs::ServerConfig {
     fn from(config: ServerConfig) -> Self {
+        // Certificates are always verified, except the self-signed one of a test server
+        #[cfg(any(test, feature = "testing"))]
+        let allow_insecure_tls = crate::testing::is_mock_server(&config.host, config.port);
+        #[cfg(not(any(test, feature = "testing")))]
+        let allow_insecure_tls = false;
+
         nntp_rs::ServerConfig {
             host: config.host,
             port: config.port,
             tls: config.tls,
-            allow_insecure_tls: false,
+            allow_insecure_tls,
             username: config.username.unwrap_or_default(),
             password: config.password.unwrap_or_default(),
         }
@@ -1184,6 +3039,8 @@ mod tests {
             auto_download: true,
             priority: Priority::Normal,
             enabled: true,
+            auth: Default::default(),
+            preference: 0,
         };
 
         let json = serde_json::to_string(&feed).expect("serialize failed");
@@ -1250,6 +3107,10 @@ mod tests {
             connections: 10,
             priority: 0,
             pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
         };
 
         let nntp: nntp_rs::ServerConfig = our.into();
@@ -1276,6 +3137,10 @@ mod tests {
             connections: 5,
             priority: 1,
             pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
         };
 
         let nntp: nntp_rs::ServerConfig = our.into();
@@ -1388,6 +3253,9 @@ mod tests {
             min_size: None,
             max_size: None,
             max_age: Some(Duration::from_secs(3600)),
+            action: Default::default(),
+            category: None,
+            priority: None,
         };
 
         let json = serde_json::to_value(&filter).expect("serialize failed");
@@ -1409,6 +3277,9 @@ mod tests {
             min_size: None,
             max_size: None,
             max_age: None,
+            action: Default::default(),
+            category: None,
+            priority: None,
         };
 
         let json = serde_json::to_value(&filter).expect("serialize failed");
@@ -1557,4 +3428,344 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn permissions_config_parses_octal_modes() {
+        let json = r#"{"file_mode": "0644", "dir_mode": "0o755", "umask": "022", "uid": 1000}"#;
+        let permissions: PermissionsConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(permissions.file_mode, Some(0o644));
+        assert_eq!(permissions.dir_mode, Some(0o755));
+        assert_eq!(permissions.umask, Some(0o022));
+        assert_eq!(permissions.uid, Some(1000));
+        assert_eq!(permissions.gid, None);
+
+        // Modes serialize back to octal strings
+        let value = serde_json::to_value(&permissions).unwrap();
+        assert_eq!(value["file_mode"], "0644");
+        assert_eq!(value["dir_mode"], "0755");
+    }
+
+    #[test]
+    fn permissions_config_rejects_invalid_modes() {
+        for bad in [r#"{"file_mode": "0698"}"#, r#"{"dir_mode": "17777"}"#] {
+            assert!(
+                serde_json::from_str::<PermissionsConfig>(bad).is_err(),
+                "{bad} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn path_sanitization_defaults_follow_platform() {
+        let sanitization: PathSanitizationConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(sanitization.windows_compatible, cfg!(windows));
+        assert!(!sanitization.transliterate_illegal_chars);
+
+        let sanitization: PathSanitizationConfig =
+            serde_json::from_str(r#"{"windows_compatible": true}"#).unwrap();
+        assert!(sanitization.windows_compatible);
+    }
+
+    // --- Field paths in parse and validation errors ---
+
+    fn error_key(result: crate::Result<impl std::fmt::Debug>) -> Option<String> {
+        match result {
+            Err(crate::Error::Config { key, .. }) => key,
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_json_value_reports_path_inside_flattened_section() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value["retry"]["max_attempts"] = serde_json::json!("five");
+        assert_eq!(
+            error_key(Config::from_json_value(&value)).as_deref(),
+            Some("retry.max_attempts")
+        );
+    }
+
+    #[test]
+    fn from_json_value_reports_path_inside_servers() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value["servers"] = serde_json::json!([
+            {"host": "news.example.com", "port": 563, "tls": true, "connections": -1}
+        ]);
+        assert_eq!(
+            error_key(Config::from_json_value(&value)).as_deref(),
+            Some("servers[0].connections")
+        );
+    }
+
+    #[test]
+    fn validate_reports_first_invalid_field() {
+        let mut config = Config::default();
+        assert!(config.validate().is_ok());
+
+        config.servers.push(ServerConfig {
+            host: "news.example.com".to_string(),
+            port: 563,
+            tls: true,
+            username: None,
+            password: None,
+            connections: 0,
+            priority: 0,
+            pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
+        });
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("servers[0].connections")
+        );
+
+        config.servers[0].connections = 10;
+        config.persistence.schedule_rules.push(ScheduleRule {
+            name: "night".to_string(),
+            days: vec![],
+            start_time: "25:00".to_string(),
+            end_time: "06:00".to_string(),
+            action: ScheduleAction::Unlimited,
+            enabled: true,
+            start_date: None,
+            end_date: None,
+        });
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("persistence.schedule_rules[0].start_time")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_unknown_scheduler_timezone() {
+        let mut config = Config::default();
+        assert_eq!(config.scheduler.tz(), None);
+
+        config.scheduler.timezone = Some("Europe/Amsterdam".to_string());
+        assert!(config.validate().is_ok());
+        assert_eq!(config.scheduler.tz(), Some(chrono_tz::Europe::Amsterdam));
+
+        config.scheduler.timezone = Some("Mars/Olympus_Mons".to_string());
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("scheduler.timezone")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_bad_one_off_schedule_dates() {
+        let mut config = Config::default();
+        config.persistence.schedule_rules.push(ScheduleRule {
+            name: "holidays".to_string(),
+            days: vec![],
+            start_time: "18:00".to_string(),
+            end_time: "08:00".to_string(),
+            action: ScheduleAction::Pause,
+            enabled: true,
+            start_date: Some("2024-12-24".to_string()),
+            end_date: Some("2024-12-26".to_string()),
+        });
+        assert!(config.validate().is_ok());
+
+        for (start_date, end_date, key) in [
+            (Some("2024-12-24"), None, "end_date"),
+            (None, Some("2024-12-26"), "start_date"),
+            (Some("24/12/2024"), Some("2024-12-26"), "start_date"),
+            (Some("2024-12-24"), Some("2024-12-24"), "end_date"),
+        ] {
+            let rule = &mut config.persistence.schedule_rules[0];
+            rule.start_date = start_date.map(str::to_string);
+            rule.end_date = end_date.map(str::to_string);
+            assert_eq!(
+                error_key(config.validate()),
+                Some(format!("persistence.schedule_rules[0].{}", key)),
+                "{:?} to {:?}",
+                start_date,
+                end_date
+            );
+        }
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_min_completion() {
+        let mut config = Config::default();
+        config.download.completion_check.min_completion = 1.5;
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("completion_check.min_completion")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_zero_stall_max_attempts() {
+        let mut config = Config::default();
+        config.download.stall.max_attempts = 0;
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("stall.max_attempts")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_zero_idle_timeouts() {
+        let mut config = Config::default();
+        config.download.connection_idle_timeout = Some(Duration::ZERO);
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("connection_idle_timeout")
+        );
+
+        config.download.connection_idle_timeout = None;
+        config.servers.push(ServerConfig {
+            host: "news.example.com".to_string(),
+            port: 563,
+            tls: true,
+            username: None,
+            password: None,
+            connections: 10,
+            priority: 0,
+            pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: Some(Duration::ZERO),
+            cost: 0.0,
+            quota_bytes: None,
+        });
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("servers[0].idle_timeout")
+        );
+
+        config.servers[0].idle_timeout = None;
+        config.servers[0].cost = -1.0;
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("servers[0].cost")
+        );
+
+        config.servers[0].cost = 0.5;
+        config.servers[0].quota_bytes = Some(0);
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("servers[0].quota_bytes")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_process_priority() {
+        let mut config = Config::default();
+        config.tools.process_priority.nice = Some(20);
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("process_priority.nice")
+        );
+
+        config.tools.process_priority.nice = Some(10);
+        config.tools.process_priority.io_priority = Some(8);
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("process_priority.io_priority")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_zero_post_processing_slots() {
+        let mut config = Config::default();
+        config.processing.max_parallel_jobs = 0;
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("max_parallel_jobs")
+        );
+    }
+
+    #[test]
+    fn reject_extensions_match_case_insensitively_with_or_without_dot() {
+        let config = DownloadConfig {
+            reject_extensions: vec![".exe".to_string(), "LNK".to_string(), " ".to_string()],
+            ..Default::default()
+        };
+        assert!(config.is_rejected("Setup.EXE"));
+        assert!(config.is_rejected("shortcut.lnk"));
+        assert!(!config.is_rejected("release.mkv"));
+        assert!(!config.is_rejected("notanexe"));
+    }
+
+    #[test]
+    fn validate_rejects_zero_progress_flush_interval() {
+        let mut config = Config::default();
+        config.persistence.progress_flush_interval_ms = 0;
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("progress_flush_interval_ms")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_zero_scan_timeout_only_when_enabled() {
+        let mut config = Config::default();
+        config.processing.scan.timeout = Duration::ZERO;
+        assert!(config.validate().is_ok());
+
+        config.processing.scan.enabled = true;
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("scan.timeout")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_nzb_fallback_url_without_placeholder() {
+        let mut config = Config::default();
+        config.download.nzb_fallback_url =
+            Some("https://indexer.local/get?name={name}".to_string());
+        assert!(config.validate().is_ok());
+
+        for url in ["https://indexer.local/get", "ftp://indexer.local/{name}"] {
+            config.download.nzb_fallback_url = Some(url.to_string());
+            assert_eq!(
+                error_key(config.validate()).as_deref(),
+                Some("nzb_fallback_url"),
+                "{url}"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_rejects_non_http_password_lookup_url() {
+        let mut config = Config::default();
+        config.tools.password_lookup_url = Some("http://passwords.local/{name}".to_string());
+        assert!(config.validate().is_ok());
+
+        config.tools.password_lookup_url = Some("ftp://passwords.local/".to_string());
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("password_lookup_url")
+        );
+    }
+
+    #[test]
+    fn mqtt_config_defaults_and_password_requires_username() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value["mqtt"] = serde_json::json!({ "host": "broker.local", "password": "secret" });
+        let mut config = Config::from_json_value(&value).unwrap();
+        let mqtt = config.notifications.mqtt.as_ref().unwrap();
+        assert_eq!(mqtt.port, 1883);
+        assert_eq!(mqtt.topic_prefix, "usenet-dl");
+        assert_eq!(mqtt.events.len(), 4);
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("mqtt.password")
+        );
+
+        if let Some(mqtt) = &mut config.notifications.mqtt {
+            mqtt.username = Some("usenet-dl".to_string());
+            mqtt.topic_prefix = "home/#".to_string();
+        }
+        assert_eq!(
+            error_key(config.validate()).as_deref(),
+            Some("mqtt.topic_prefix")
+        );
+    }
 }
@@ -21,7 +21,7 @@ impl Database {
         .bind(article.segment_number)
         .bind(article.file_index)
         .bind(article.size_bytes)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -61,7 +61,7 @@ impl Database {
             });
 
             let query = query_builder.build();
-            query.execute(&self.pool).await.map_err(|e| {
+            query.execute(&self.writer).await.map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
                     "Failed to insert articles batch: {}",
                     e
@@ -90,7 +90,7 @@ impl Database {
             None
         })
         .bind(article_id)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -126,7 +126,7 @@ impl Database {
         })
         .bind(download_id)
         .bind(message_id)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -167,53 +167,12 @@ impl Database {
             return Ok(());
         }
 
-        // Each update uses up to 4 bind variables (id in status CASE, status, id in downloaded_at CASE,
-        // optional timestamp, id in WHERE IN). Conservative limit of 100 per batch.
-        const MAX_UPDATES_PER_BATCH: usize = 100;
-
         let now = chrono::Utc::now().timestamp();
 
         for chunk in updates.chunks(MAX_UPDATES_PER_BATCH) {
-            let mut query_builder =
-                sqlx::QueryBuilder::new("UPDATE download_articles SET status = CASE ");
-
-            // Build status CASE clause
-            for (article_id, status) in chunk {
-                query_builder.push("WHEN id = ");
-                query_builder.push_bind(*article_id);
-                query_builder.push(" THEN ");
-                query_builder.push_bind(*status);
-                query_builder.push(" ");
-            }
-            query_builder.push("END, downloaded_at = CASE ");
-
-            // Build downloaded_at CASE clause (only set timestamp for DOWNLOADED status)
-            for (article_id, status) in chunk {
-                query_builder.push("WHEN id = ");
-                query_builder.push_bind(*article_id);
-                if *status == article_status::DOWNLOADED {
-                    query_builder.push(" THEN ");
-                    query_builder.push_bind(now);
-                } else {
-                    query_builder.push(" THEN downloaded_at"); // Keep existing value
-                }
-                query_builder.push(" ");
-            }
-            query_builder.push("END WHERE id IN (");
-
-            // Build WHERE IN clause
-            let mut first = true;
-            for (article_id, _) in chunk {
-                if !first {
-                    query_builder.push(", ");
-                }
-                query_builder.push_bind(*article_id);
-                first = false;
-            }
-            query_builder.push(")");
-
+            let mut query_builder = article_status_batch_query(chunk, now);
             let query = query_builder.build();
-            query.execute(&self.pool).await.map_err(|e| {
+            query.execute(&self.writer).await.map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
                     "Failed to update articles status batch: {}",
                     e
@@ -247,7 +206,36 @@ impl Database {
         Ok(rows)
     }
 
-    /// Get pending articles for a download, excluding paused files.
+    /// Get the message-IDs of a download's first `limit` articles
+    pub async fn sample_message_ids(
+        &self,
+        download_id: DownloadId,
+        limit: i64,
+    ) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT message_id
+            FROM download_articles
+            WHERE download_id = ?
+            ORDER BY file_index ASC, segment_number ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(download_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to sample message IDs: {}",
+                e
+            )))
+        })?;
+
+        Ok(rows.into_iter().map(|(message_id,)| message_id).collect())
+    }
+
+    /// Get pending articles for a download, excluding paused and held files.
     pub async fn get_pending_articles(&self, download_id: DownloadId) -> Result<Vec<Article>> {
         let rows = sqlx::query_as::<_, Article>(
             r#"
@@ -259,6 +247,7 @@ impl Database {
             WHERE da.download_id = ?
               AND da.status = 0
               AND COALESCE(df.paused, 0) = 0
+              AND COALESCE(df.held, 0) = 0
             ORDER BY da.file_index ASC, da.segment_number ASC
             "#,
         )
@@ -275,6 +264,42 @@ impl Database {
         Ok(rows)
     }
 
+    /// Count, for each of a download's pending articles, the other unfinished downloads
+    /// that still need the same message-ID
+    ///
+    /// Only message-IDs shared with at least one other queued, downloading or paused
+    /// download are returned.
+    pub async fn shared_article_readers(
+        &self,
+        download_id: DownloadId,
+    ) -> Result<Vec<(String, i64)>> {
+        sqlx::query_as(
+            r#"
+            SELECT a.message_id, COUNT(DISTINCT b.download_id)
+            FROM download_articles a
+            JOIN download_articles b
+              ON b.message_id = a.message_id
+             AND b.download_id != a.download_id
+             AND b.status = 0
+            JOIN downloads d
+              ON d.id = b.download_id
+             AND d.status IN (0, 1, 2)
+            WHERE a.download_id = ?
+              AND a.status = 0
+            GROUP BY a.message_id
+            "#,
+        )
+        .bind(download_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to find shared articles: {}",
+                e
+            )))
+        })
+    }
+
     /// Get article by message_id
     pub async fn get_article_by_message_id(
         &self,
@@ -346,7 +371,7 @@ impl Database {
     pub async fn delete_articles(&self, download_id: DownloadId) -> Result<()> {
         sqlx::query("DELETE FROM download_articles WHERE download_id = ?")
             .bind(download_id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -381,7 +406,7 @@ impl Database {
             });
 
             let query = query_builder.build();
-            query.execute(&self.pool).await.map_err(|e| {
+            query.execute(&self.writer).await.map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
                     "Failed to insert files batch: {}",
                     e
@@ -399,7 +424,7 @@ impl Database {
     ) -> Result<Vec<super::DownloadFile>> {
         let rows = sqlx::query_as::<_, super::DownloadFile>(
             r#"
-            SELECT id, download_id, file_index, filename, subject, total_segments, paused, completed, original_filename
+            SELECT id, download_id, file_index, filename, subject, total_segments, paused, held, completed, original_filename
             FROM download_files
             WHERE download_id = ?
             ORDER BY file_index ASC
@@ -465,7 +490,7 @@ impl Database {
         )
         .bind(download_id)
         .bind(file_index)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -486,7 +511,7 @@ impl Database {
         sqlx::query("UPDATE downloads SET direct_unpack_state = ? WHERE id = ?")
             .bind(state)
             .bind(download_id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -533,7 +558,7 @@ impl Database {
         .bind(new_filename)
         .bind(download_id)
         .bind(file_index)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -554,7 +579,7 @@ impl Database {
         sqlx::query("UPDATE downloads SET direct_unpack_extracted_count = ? WHERE id = ?")
             .bind(count)
             .bind(download_id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -596,7 +621,7 @@ impl Database {
         .bind(if paused { 1 } else { 0 })
         .bind(download_id)
         .bind(file_index)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -608,6 +633,49 @@ impl Database {
         Ok(())
     }
 
+    /// Hold files until repair needs them, so their articles aren't fetched
+    pub async fn hold_files(&self, download_id: DownloadId, file_indices: &[i32]) -> Result<()> {
+        for file_index in file_indices {
+            sqlx::query(
+                "UPDATE download_files SET held = 1 WHERE download_id = ? AND file_index = ?",
+            )
+            .bind(download_id)
+            .bind(file_index)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to hold file: {}",
+                    e
+                )))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Release a download's held files so their articles are fetched, returning them
+    pub async fn release_held_files(&self, download_id: DownloadId) -> Result<Vec<DownloadFile>> {
+        let files = sqlx::query_as::<_, DownloadFile>(
+            r#"
+            UPDATE download_files SET held = 0
+            WHERE download_id = ? AND held = 1
+            RETURNING id, download_id, file_index, filename, subject, total_segments, paused, held, completed, original_filename
+            "#,
+        )
+        .bind(download_id)
+        .fetch_all(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to release held files: {}",
+                e
+            )))
+        })?;
+
+        Ok(files)
+    }
+
     /// Get a single download file by download and file index.
     pub async fn get_download_file(
         &self,
@@ -616,7 +684,7 @@ impl Database {
     ) -> Result<Option<DownloadFile>> {
         let row = sqlx::query_as::<_, DownloadFile>(
             r#"
-            SELECT id, download_id, file_index, filename, subject, total_segments, paused, completed, original_filename
+            SELECT id, download_id, file_index, filename, subject, total_segments, paused, held, completed, original_filename
             FROM download_files
             WHERE download_id = ? AND file_index = ?
             "#,
@@ -647,6 +715,7 @@ impl Database {
             WHERE da.download_id = ?
               AND da.status = 0
               AND COALESCE(df.paused, 0) = 0
+              AND COALESCE(df.held, 0) = 0
             "#,
         )
         .bind(download_id)
@@ -663,9 +732,20 @@ impl Database {
     }
 
     /// Return true when a download still has any pending articles, including paused files.
+    ///
+    /// Held files don't count: they're only fetched once repair asks for them.
     pub async fn has_any_pending_articles(&self, download_id: DownloadId) -> Result<bool> {
         let count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM download_articles WHERE download_id = ? AND status = 0",
+            r#"
+            SELECT COUNT(*)
+            FROM download_articles da
+            LEFT JOIN download_files df
+              ON df.download_id = da.download_id
+             AND df.file_index = da.file_index
+            WHERE da.download_id = ?
+              AND da.status = 0
+              AND COALESCE(df.held, 0) = 0
+            "#,
         )
         .bind(download_id)
         .fetch_one(&self.pool)
@@ -698,3 +778,56 @@ impl Database {
         Ok(count)
     }
 }
+
+/// Most article status updates written by one statement, to stay within SQLite's bind
+/// variable limit
+pub(super) const MAX_UPDATES_PER_BATCH: usize = 100;
+
+/// Build a single UPDATE that sets each article in `chunk` to its status
+///
+/// Each update uses up to 4 bind variables (id in status CASE, status, id in
+/// downloaded_at CASE, optional timestamp, id in WHERE IN), so `chunk` should hold at
+/// most [`MAX_UPDATES_PER_BATCH`] updates.
+pub(super) fn article_status_batch_query(
+    chunk: &[(i64, i32)],
+    now: i64,
+) -> sqlx::QueryBuilder<'static, sqlx::Sqlite> {
+    let mut query_builder = sqlx::QueryBuilder::new("UPDATE download_articles SET status = CASE ");
+
+    // Build status CASE clause
+    for (article_id, status) in chunk {
+        query_builder.push("WHEN id = ");
+        query_builder.push_bind(*article_id);
+        query_builder.push(" THEN ");
+        query_builder.push_bind(*status);
+        query_builder.push(" ");
+    }
+    query_builder.push("END, downloaded_at = CASE ");
+
+    // Build downloaded_at CASE clause (only set timestamp for DOWNLOADED status)
+    for (article_id, status) in chunk {
+        query_builder.push("WHEN id = ");
+        query_builder.push_bind(*article_id);
+        if *status == article_status::DOWNLOADED {
+            query_builder.push(" THEN ");
+            query_builder.push_bind(now);
+        } else {
+            query_builder.push(" THEN downloaded_at"); // Keep existing value
+        }
+        query_builder.push(" ");
+    }
+    query_builder.push("END WHERE id IN (");
+
+    // Build WHERE IN clause
+    let mut first = true;
+    for (article_id, _) in chunk {
+        if !first {
+            query_builder.push(", ");
+        }
+        query_builder.push_bind(*article_id);
+        first = false;
+    }
+    query_builder.push(")");
+
+    query_builder
+}
@@ -0,0 +1,124 @@
+//! Database snapshots for backup and restore.
+
+use crate::error::DatabaseError;
+use crate::{Error, Result};
+use std::path::Path;
+
+use super::Database;
+
+impl Database {
+    /// Write a consistent copy of the whole database to `path`
+    ///
+    /// Uses `VACUUM INTO`, so the snapshot includes pending WAL contents and is safe
+    /// to take while the database is in use. `path` must not already exist.
+    pub async fn snapshot_to(&self, path: &Path) -> Result<()> {
+        sqlx::query("VACUUM INTO ?")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to snapshot database: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// Replace the contents of every table with those of the database at `path`
+    ///
+    /// The source must already be migrated to the current schema (open it with
+    /// [`Database::new`] first). Runs in a single transaction: on failure nothing changes.
+    pub async fn restore_from(&self, path: &Path) -> Result<()> {
+        let mut conn = self.writer.acquire().await.map_err(|e| {
+            Error::Database(DatabaseError::ConnectionFailed(format!(
+                "Failed to acquire connection: {}",
+                e
+            )))
+        })?;
+
+        sqlx::query("ATTACH DATABASE ? AS restore_source")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to attach backup database: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            sqlx::query("BEGIN IMMEDIATE")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::QueryFailed(format!(
+                        "Failed to begin restore: {}",
+                        e
+                    )))
+                })?;
+
+            let copied = async {
+                // Rows are swapped table by table, so check references only at commit
+                sqlx::query("PRAGMA defer_foreign_keys = ON")
+                    .execute(&mut *conn)
+                    .await?;
+
+                // schema_version describes the live schema (which the source already
+                // matches) and runtime_state holds this instance's shutdown marker
+                let tables: Vec<String> = sqlx::query_scalar(
+                    "SELECT name FROM main.sqlite_master WHERE type = 'table' \
+                     AND name NOT LIKE 'sqlite_%' \
+                     AND name NOT IN ('schema_version', 'runtime_state') ORDER BY name",
+                )
+                .fetch_all(&mut *conn)
+                .await?;
+
+                for table in &tables {
+                    let columns: Vec<String> =
+                        sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+                            .bind(table)
+                            .fetch_all(&mut *conn)
+                            .await?;
+                    let columns = columns
+                        .iter()
+                        .map(|c| format!("\"{}\"", c))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    sqlx::query(&format!("DELETE FROM main.\"{}\"", table))
+                        .execute(&mut *conn)
+                        .await?;
+                    sqlx::query(&format!(
+                        "INSERT INTO main.\"{table}\" ({columns}) \
+                         SELECT {columns} FROM restore_source.\"{table}\""
+                    ))
+                    .execute(&mut *conn)
+                    .await?;
+                }
+
+                sqlx::query("COMMIT").execute(&mut *conn).await?;
+                Ok::<(), sqlx::Error>(())
+            }
+            .await;
+
+            if let Err(e) = copied {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to restore database: {}",
+                    e
+                ))));
+            }
+            Ok(())
+        }
+        .await;
+
+        let _ = sqlx::query("DETACH DATABASE restore_source")
+            .execute(&mut *conn)
+            .await;
+
+        result
+    }
+}
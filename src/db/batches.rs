@@ -0,0 +1,174 @@
+//! Batch (download group) tracking.
+
+use crate::error::DatabaseError;
+use crate::types::DownloadId;
+use crate::{Error, Result};
+
+use super::{Batch, Database, Download};
+
+impl Database {
+    /// Insert a new batch
+    pub async fn insert_batch(&self, name: &str) -> Result<i64> {
+        let now = chrono::Utc::now().timestamp();
+
+        let result = sqlx::query("INSERT INTO batches (name, created_at) VALUES (?, ?)")
+            .bind(name)
+            .bind(now)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to insert batch: {}",
+                    e
+                )))
+            })?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Get a batch by ID
+    pub async fn get_batch(&self, id: i64) -> Result<Option<Batch>> {
+        let batch = sqlx::query_as::<_, Batch>(
+            "SELECT id, name, created_at, completed_at FROM batches WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to get batch: {}",
+                e
+            )))
+        })?;
+
+        Ok(batch)
+    }
+
+    /// Assign a download to a batch
+    pub async fn set_download_batch(&self, id: DownloadId, batch_id: i64) -> Result<()> {
+        sqlx::query("UPDATE downloads SET batch_id = ? WHERE id = ?")
+            .bind(batch_id)
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to set download batch: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// Get the batch a download belongs to (None if it isn't part of a batch)
+    pub async fn get_download_batch_id(&self, id: DownloadId) -> Result<Option<i64>> {
+        let batch_id: Option<Option<i64>> =
+            sqlx::query_scalar("SELECT batch_id FROM downloads WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::QueryFailed(format!(
+                        "Failed to get download batch: {}",
+                        e
+                    )))
+                })?;
+
+        Ok(batch_id.flatten())
+    }
+
+    /// Get all downloads belonging to a batch
+    pub async fn list_batch_downloads(&self, batch_id: i64) -> Result<Vec<Download>> {
+        let rows = sqlx::query_as::<_, Download>(
+            r#"
+            SELECT
+                id, name, nzb_path, nzb_meta_name, nzb_hash, job_name,
+                category, destination, post_process, priority, status,
+                progress, speed_bps, size_bytes, downloaded_bytes,
+                error_message, error_code, created_at, started_at, completed_at,
+                direct_unpack_state, direct_unpack_extracted_count, temp_dir, incomplete_dir,
+                post_processing_paused
+            FROM downloads
+            WHERE batch_id = ?
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to list batch downloads: {}",
+                e
+            )))
+        })?;
+
+        Ok(rows)
+    }
+
+    /// Get the IDs of batch members that have reached their final state
+    pub async fn list_batch_done_ids(&self, batch_id: i64) -> Result<Vec<i64>> {
+        let ids =
+            sqlx::query_scalar("SELECT id FROM downloads WHERE batch_id = ? AND batch_done = 1")
+                .bind(batch_id)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::QueryFailed(format!(
+                        "Failed to list finished batch members: {}",
+                        e
+                    )))
+                })?;
+
+        Ok(ids)
+    }
+
+    /// Mark a download as having reached its final state within its batch
+    pub async fn mark_batch_member_done(&self, id: DownloadId) -> Result<()> {
+        sqlx::query("UPDATE downloads SET batch_done = 1 WHERE id = ? AND batch_id IS NOT NULL")
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to mark batch member done: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// Mark a batch complete if it has members and all of them are done
+    ///
+    /// Returns true only for the call that actually completed the batch, so callers
+    /// racing on the last members fire the completion hook exactly once.
+    pub async fn try_complete_batch(&self, batch_id: i64) -> Result<bool> {
+        let now = chrono::Utc::now().timestamp();
+
+        let result = sqlx::query(
+            r#"
+            UPDATE batches SET completed_at = ?
+            WHERE id = ?
+              AND completed_at IS NULL
+              AND EXISTS (SELECT 1 FROM downloads WHERE batch_id = ?)
+              AND NOT EXISTS (SELECT 1 FROM downloads WHERE batch_id = ? AND batch_done = 0)
+            "#,
+        )
+        .bind(now)
+        .bind(batch_id)
+        .bind(batch_id)
+        .bind(batch_id)
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to complete batch: {}",
+                e
+            )))
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,93 @@
+//! Post-processing stage checkpoints.
+
+use crate::error::DatabaseError;
+use crate::types::{DownloadId, Stage};
+use crate::{Error, Result};
+
+use super::Database;
+
+/// Name a stage is stored under (`"extract"`, `"move"`, ...)
+pub(super) fn stage_name(stage: Stage) -> Option<String> {
+    serde_json::to_value(stage)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+}
+
+/// Stage stored under `name`, if it is one
+pub(super) fn parse_stage(name: String) -> Option<Stage> {
+    serde_json::from_value(serde_json::Value::String(name)).ok()
+}
+
+impl Database {
+    /// Record that a download completed a post-processing stage
+    pub async fn add_stage_checkpoint(&self, id: DownloadId, stage: Stage) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO stage_checkpoints (download_id, stage, completed_at) VALUES (?, ?, ?)",
+        )
+        .bind(id)
+        .bind(stage_name(stage))
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to record stage checkpoint: {}",
+                e
+            )))
+        })?;
+
+        Ok(())
+    }
+
+    /// Post-processing stages a download has completed
+    pub async fn get_stage_checkpoints(&self, id: DownloadId) -> Result<Vec<Stage>> {
+        let names: Vec<String> =
+            sqlx::query_scalar("SELECT stage FROM stage_checkpoints WHERE download_id = ?")
+                .bind(id)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::QueryFailed(format!(
+                        "Failed to get stage checkpoints: {}",
+                        e
+                    )))
+                })?;
+
+        Ok(names.into_iter().filter_map(parse_stage).collect())
+    }
+
+    /// Forget that a download completed any of `stages`
+    pub async fn clear_stage_checkpoints(&self, id: DownloadId, stages: &[Stage]) -> Result<()> {
+        for stage in stages {
+            sqlx::query("DELETE FROM stage_checkpoints WHERE download_id = ? AND stage = ?")
+                .bind(id)
+                .bind(stage_name(*stage))
+                .execute(&self.writer)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::QueryFailed(format!(
+                        "Failed to clear stage checkpoint: {}",
+                        e
+                    )))
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Forget every post-processing stage a download completed
+    pub async fn clear_all_stage_checkpoints(&self, id: DownloadId) -> Result<()> {
+        sqlx::query("DELETE FROM stage_checkpoints WHERE download_id = ?")
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to clear stage checkpoints: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+}
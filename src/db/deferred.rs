@@ -0,0 +1,106 @@
+//! Deferred NZB queries.
+//!
+//! A download added with a deferred NZB has a row but no files or articles until its
+//! NZB is fetched, which happens when it reaches the front of the queue.
+
+use crate::error::DatabaseError;
+use crate::types::DownloadId;
+use crate::{Error, Result};
+
+use super::{Database, FetchedNzb};
+
+impl Database {
+    /// Record the URL a download's NZB will be fetched from
+    pub async fn set_deferred_nzb(&self, download_id: DownloadId, url: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO deferred_nzbs (download_id, url) VALUES (?, ?)
+            ON CONFLICT(download_id) DO UPDATE SET url = excluded.url
+            "#,
+        )
+        .bind(download_id)
+        .bind(url)
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to set deferred NZB: {}",
+                e
+            )))
+        })?;
+
+        Ok(())
+    }
+
+    /// Get the URL a download's NZB is still to be fetched from
+    ///
+    /// Returns None if the download's NZB isn't deferred.
+    pub async fn get_deferred_nzb(&self, download_id: DownloadId) -> Result<Option<String>> {
+        sqlx::query_scalar("SELECT url FROM deferred_nzbs WHERE download_id = ?")
+            .bind(download_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to get deferred NZB: {}",
+                    e
+                )))
+            })
+    }
+
+    /// List every deferred NZB as `(download_id, url)`
+    pub async fn list_deferred_nzbs(&self) -> Result<Vec<(i64, String)>> {
+        sqlx::query_as("SELECT download_id, url FROM deferred_nzbs")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to list deferred NZBs: {}",
+                    e
+                )))
+            })
+    }
+
+    /// Store what was learned from a fetched NZB and stop treating it as deferred
+    pub async fn complete_deferred_nzb(
+        &self,
+        download_id: DownloadId,
+        fetched: &FetchedNzb,
+    ) -> Result<()> {
+        let query_failed = |e: sqlx::Error| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to complete deferred NZB: {}",
+                e
+            )))
+        };
+
+        let mut tx = self.writer.begin().await.map_err(query_failed)?;
+
+        sqlx::query(
+            r#"
+            UPDATE downloads
+            SET name = ?, nzb_meta_name = ?, nzb_hash = ?, job_name = ?, size_bytes = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&fetched.name)
+        .bind(&fetched.nzb_meta_name)
+        .bind(&fetched.nzb_hash)
+        .bind(&fetched.job_name)
+        .bind(fetched.size_bytes)
+        .bind(download_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(query_failed)?;
+
+        sqlx::query("DELETE FROM deferred_nzbs WHERE download_id = ?")
+            .bind(download_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(query_failed)?;
+
+        tx.commit().await.map_err(query_failed)?;
+
+        Ok(())
+    }
+}
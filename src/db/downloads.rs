@@ -1,9 +1,10 @@
 //! Download queue CRUD operations.
 
 use crate::error::DatabaseError;
-use crate::types::DownloadId;
+use crate::types::{DownloadId, Stage};
 use crate::{Error, Result};
 
+use super::checkpoints::{parse_stage, stage_name};
 use super::{Database, Download, NewDownload};
 
 impl Database {
@@ -36,7 +37,7 @@ impl Database {
         .bind(download.size_bytes)
         .bind(0i64) // downloaded_bytes
         .bind(now)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -56,8 +57,9 @@ impl Database {
                 id, name, nzb_path, nzb_meta_name, nzb_hash, job_name,
                 category, destination, post_process, priority, status,
                 progress, speed_bps, size_bytes, downloaded_bytes,
-                error_message, created_at, started_at, completed_at,
-                direct_unpack_state, direct_unpack_extracted_count
+                error_message, error_code, created_at, started_at, completed_at,
+                direct_unpack_state, direct_unpack_extracted_count, temp_dir, incomplete_dir,
+                post_processing_paused
             FROM downloads
             WHERE id = ?
             "#,
@@ -83,8 +85,9 @@ impl Database {
                 id, name, nzb_path, nzb_meta_name, nzb_hash, job_name,
                 category, destination, post_process, priority, status,
                 progress, speed_bps, size_bytes, downloaded_bytes,
-                error_message, created_at, started_at, completed_at,
-                direct_unpack_state, direct_unpack_extracted_count
+                error_message, error_code, created_at, started_at, completed_at,
+                direct_unpack_state, direct_unpack_extracted_count, temp_dir, incomplete_dir,
+                post_processing_paused
             FROM downloads
             ORDER BY priority DESC, created_at ASC
             "#,
@@ -109,8 +112,9 @@ impl Database {
                 id, name, nzb_path, nzb_meta_name, nzb_hash, job_name,
                 category, destination, post_process, priority, status,
                 progress, speed_bps, size_bytes, downloaded_bytes,
-                error_message, created_at, started_at, completed_at,
-                direct_unpack_state, direct_unpack_extracted_count
+                error_message, error_code, created_at, started_at, completed_at,
+                direct_unpack_state, direct_unpack_extracted_count, temp_dir, incomplete_dir,
+                post_processing_paused
             FROM downloads
             WHERE status = ?
             ORDER BY priority DESC, created_at ASC
@@ -134,7 +138,7 @@ impl Database {
         sqlx::query("UPDATE downloads SET status = ? WHERE id = ?")
             .bind(status)
             .bind(id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -147,6 +151,8 @@ impl Database {
     }
 
     /// Update download progress
+    ///
+    /// Replaces any progress queued with [`queue_progress`](Self::queue_progress).
     pub async fn update_progress(
         &self,
         id: DownloadId,
@@ -154,6 +160,7 @@ impl Database {
         speed_bps: u64,
         downloaded_bytes: u64,
     ) -> Result<()> {
+        self.discard_queued_progress(id);
         sqlx::query(
             "UPDATE downloads SET progress = ?, speed_bps = ?, downloaded_bytes = ? WHERE id = ?",
         )
@@ -161,7 +168,7 @@ impl Database {
         .bind(speed_bps as i64)
         .bind(downloaded_bytes as i64)
         .bind(id)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -178,7 +185,7 @@ impl Database {
         sqlx::query("UPDATE downloads SET priority = ? WHERE id = ?")
             .bind(priority)
             .bind(id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -190,12 +197,64 @@ impl Database {
         Ok(())
     }
 
-    /// Set download error message
+    /// Move a download to another category, with the destination and post-processing
+    /// mode that come with it
+    pub async fn update_category(
+        &self,
+        id: DownloadId,
+        category: Option<&str>,
+        destination: &str,
+        post_process: i32,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE downloads SET category = ?, destination = ?, post_process = ? WHERE id = ?",
+        )
+        .bind(category)
+        .bind(destination)
+        .bind(post_process)
+        .bind(id)
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to update category: {}",
+                e
+            )))
+        })?;
+
+        Ok(())
+    }
+
+    /// Rename a download, updating its name, job name and destination in one statement
+    pub async fn rename_download(
+        &self,
+        id: DownloadId,
+        name: &str,
+        destination: &str,
+    ) -> Result<()> {
+        sqlx::query("UPDATE downloads SET name = ?, job_name = ?, destination = ? WHERE id = ?")
+            .bind(name)
+            .bind(name)
+            .bind(destination)
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to rename download: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// Set download error message, clearing its failure code
     pub async fn set_error(&self, id: DownloadId, error: &str) -> Result<()> {
-        sqlx::query("UPDATE downloads SET error_message = ? WHERE id = ?")
+        sqlx::query("UPDATE downloads SET error_message = ?, error_code = NULL WHERE id = ?")
             .bind(error)
             .bind(id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -207,13 +266,155 @@ impl Database {
         Ok(())
     }
 
+    /// Record why a download failed: a machine-readable `code` and the error message
+    pub async fn set_failure(&self, id: DownloadId, code: &str, error: &str) -> Result<()> {
+        sqlx::query("UPDATE downloads SET error_message = ?, error_code = ? WHERE id = ?")
+            .bind(error)
+            .bind(code)
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to set failure: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// Set the per-download temp directory override
+    pub async fn set_temp_dir(&self, id: DownloadId, temp_dir: &str) -> Result<()> {
+        sqlx::query("UPDATE downloads SET temp_dir = ? WHERE id = ?")
+            .bind(temp_dir)
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to set temp dir: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// Set the download's in-progress folder, relative to its temp directory
+    pub async fn set_incomplete_dir(&self, id: DownloadId, incomplete_dir: &str) -> Result<()> {
+        sqlx::query("UPDATE downloads SET incomplete_dir = ? WHERE id = ?")
+            .bind(incomplete_dir)
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to set incomplete dir: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// Whether an unfinished download already uses an in-progress folder
+    ///
+    /// Completed downloads are ignored; their folder is gone (or checked on disk).
+    pub async fn incomplete_dir_in_use(&self, incomplete_dir: &str) -> Result<bool> {
+        let row: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM downloads WHERE incomplete_dir = ? AND status != ?",
+        )
+        .bind(incomplete_dir)
+        .bind(crate::types::Status::Complete.to_i32())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to check incomplete dir: {}",
+                e
+            )))
+        })?;
+
+        Ok(row.0 > 0)
+    }
+
+    /// Pause or resume a download's post-processing
+    pub async fn set_post_processing_paused(&self, id: DownloadId, paused: bool) -> Result<()> {
+        sqlx::query("UPDATE downloads SET post_processing_paused = ? WHERE id = ?")
+            .bind(paused)
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to set post-processing pause: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// Resume post-processing for every download whose own post-processing is paused
+    pub async fn clear_post_processing_paused(&self) -> Result<()> {
+        sqlx::query(
+            "UPDATE downloads SET post_processing_paused = 0 WHERE post_processing_paused != 0",
+        )
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to clear post-processing pauses: {}",
+                e
+            )))
+        })?;
+
+        Ok(())
+    }
+
+    /// Record the post-processing stage a download is in, or clear it with `None`
+    pub async fn set_post_process_stage(&self, id: DownloadId, stage: Option<Stage>) -> Result<()> {
+        sqlx::query("UPDATE downloads SET post_process_stage = ? WHERE id = ?")
+            .bind(stage.and_then(stage_name))
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to record post-processing stage: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// The post-processing stage recorded for a download, if any
+    pub async fn get_post_process_stage(&self, id: DownloadId) -> Result<Option<Stage>> {
+        let name: Option<String> =
+            sqlx::query_scalar("SELECT post_process_stage FROM downloads WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::QueryFailed(format!(
+                        "Failed to get post-processing stage: {}",
+                        e
+                    )))
+                })?
+                .flatten();
+
+        Ok(name.and_then(parse_stage))
+    }
+
     /// Set download started timestamp
     pub async fn set_started(&self, id: DownloadId) -> Result<()> {
         let now = chrono::Utc::now().timestamp();
         sqlx::query("UPDATE downloads SET started_at = ? WHERE id = ?")
             .bind(now)
             .bind(id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -231,7 +432,7 @@ impl Database {
         sqlx::query("UPDATE downloads SET completed_at = ? WHERE id = ?")
             .bind(now)
             .bind(id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -247,7 +448,7 @@ impl Database {
     pub async fn delete_download(&self, id: DownloadId) -> Result<()> {
         sqlx::query("DELETE FROM downloads WHERE id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -267,8 +468,9 @@ impl Database {
                 id, name, nzb_path, nzb_meta_name, nzb_hash, job_name,
                 category, destination, post_process, priority, status,
                 progress, speed_bps, size_bytes, downloaded_bytes,
-                error_message, created_at, started_at, completed_at,
-                direct_unpack_state, direct_unpack_extracted_count
+                error_message, error_code, created_at, started_at, completed_at,
+                direct_unpack_state, direct_unpack_extracted_count, temp_dir, incomplete_dir,
+                post_processing_paused
             FROM downloads
             WHERE status IN (0, 1, 3)
             ORDER BY priority DESC, created_at ASC
@@ -294,8 +496,9 @@ impl Database {
                 id, name, nzb_path, nzb_meta_name, nzb_hash, job_name,
                 category, destination, post_process, priority, status,
                 progress, speed_bps, size_bytes, downloaded_bytes,
-                error_message, created_at, started_at, completed_at,
-                direct_unpack_state, direct_unpack_extracted_count
+                error_message, error_code, created_at, started_at, completed_at,
+                direct_unpack_state, direct_unpack_extracted_count, temp_dir, incomplete_dir,
+                post_processing_paused
             FROM downloads
             ORDER BY created_at ASC
             "#,
@@ -1,9 +1,10 @@
 //! Duplicate detection queries.
 
 use crate::error::DatabaseError;
+use crate::types::DownloadId;
 use crate::{Error, Result};
 
-use super::{Database, Download};
+use super::{Database, Download, HeldDuplicate};
 
 impl Database {
     /// Find a download by NZB hash
@@ -17,8 +18,9 @@ impl Database {
                 id, name, nzb_path, nzb_meta_name, nzb_hash, job_name,
                 category, destination, post_process, priority, status,
                 progress, speed_bps, size_bytes, downloaded_bytes,
-                error_message, created_at, started_at, completed_at,
-                direct_unpack_state, direct_unpack_extracted_count
+                error_message, error_code, created_at, started_at, completed_at,
+                direct_unpack_state, direct_unpack_extracted_count, temp_dir, incomplete_dir,
+                post_processing_paused
             FROM downloads
             WHERE nzb_hash = ?
             LIMIT 1
@@ -48,8 +50,9 @@ impl Database {
                 id, name, nzb_path, nzb_meta_name, nzb_hash, job_name,
                 category, destination, post_process, priority, status,
                 progress, speed_bps, size_bytes, downloaded_bytes,
-                error_message, created_at, started_at, completed_at,
-                direct_unpack_state, direct_unpack_extracted_count
+                error_message, error_code, created_at, started_at, completed_at,
+                direct_unpack_state, direct_unpack_extracted_count, temp_dir, incomplete_dir,
+                post_processing_paused
             FROM downloads
             WHERE name = ?
             LIMIT 1
@@ -79,8 +82,9 @@ impl Database {
                 id, name, nzb_path, nzb_meta_name, nzb_hash, job_name,
                 category, destination, post_process, priority, status,
                 progress, speed_bps, size_bytes, downloaded_bytes,
-                error_message, created_at, started_at, completed_at,
-                direct_unpack_state, direct_unpack_extracted_count
+                error_message, error_code, created_at, started_at, completed_at,
+                direct_unpack_state, direct_unpack_extracted_count, temp_dir, incomplete_dir,
+                post_processing_paused
             FROM downloads
             WHERE job_name = ?
             LIMIT 1
@@ -98,4 +102,84 @@ impl Database {
 
         Ok(row)
     }
+
+    /// Record why a download is held as a duplicate
+    pub async fn set_held_duplicate(&self, held: &HeldDuplicate) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO held_duplicates (download_id, method, existing_id, existing_name)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(download_id) DO UPDATE SET
+                method = excluded.method,
+                existing_id = excluded.existing_id,
+                existing_name = excluded.existing_name
+            "#,
+        )
+        .bind(held.download_id)
+        .bind(held.method)
+        .bind(held.existing_id)
+        .bind(&held.existing_name)
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to set held duplicate: {}",
+                e
+            )))
+        })?;
+
+        Ok(())
+    }
+
+    /// Get why a download is held as a duplicate
+    ///
+    /// Returns None if the download isn't held.
+    pub async fn get_held_duplicate(
+        &self,
+        download_id: DownloadId,
+    ) -> Result<Option<HeldDuplicate>> {
+        sqlx::query_as(
+            "SELECT download_id, method, existing_id, existing_name FROM held_duplicates WHERE download_id = ?",
+        )
+        .bind(download_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to get held duplicate: {}",
+                e
+            )))
+        })
+    }
+
+    /// List every download held as a duplicate
+    pub async fn list_held_duplicates(&self) -> Result<Vec<HeldDuplicate>> {
+        sqlx::query_as(
+            "SELECT download_id, method, existing_id, existing_name FROM held_duplicates",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to list held duplicates: {}",
+                e
+            )))
+        })
+    }
+
+    /// Forget why a download was held, once it's been accepted
+    pub async fn delete_held_duplicate(&self, download_id: DownloadId) -> Result<()> {
+        sqlx::query("DELETE FROM held_duplicates WHERE download_id = ?")
+            .bind(download_id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to delete held duplicate: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
 }
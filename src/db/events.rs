@@ -0,0 +1,56 @@
+//! Journal of recent events.
+//!
+//! Event envelopes other than progress updates are queued for the journal and written
+//! with the next flush (see [`write_behind`](super::write_behind)). Only the newest
+//! entries are kept, so subscribers that missed events can replay them by sequence number.
+
+use crate::error::DatabaseError;
+use crate::types::EventEnvelope;
+use crate::{Error, Result};
+
+use super::Database;
+
+impl Database {
+    /// Sequence number of the newest journaled event (0 if the journal is empty)
+    pub async fn latest_event_seq(&self) -> Result<u64> {
+        let seq: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(seq), 0) FROM event_journal")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to get latest event sequence number: {}",
+                    e
+                )))
+            })?;
+
+        Ok(seq as u64)
+    }
+
+    /// Journaled events with a sequence number above `since_seq`, oldest first
+    ///
+    /// Events still queued are only included once flushed.
+    pub async fn list_events_since(&self, since_seq: u64) -> Result<Vec<EventEnvelope>> {
+        let rows: Vec<String> =
+            sqlx::query_scalar("SELECT envelope FROM event_journal WHERE seq > ? ORDER BY seq")
+                .bind(since_seq as i64)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::QueryFailed(format!(
+                        "Failed to list journaled events: {}",
+                        e
+                    )))
+                })?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|envelope| match serde_json::from_str(envelope) {
+                Ok(envelope) => Some(envelope),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Skipping unreadable journaled event");
+                    None
+                }
+            })
+            .collect())
+    }
+}
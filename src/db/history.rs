@@ -1,6 +1,6 @@
 //! History management operations.
 
-use crate::types::HistoryEntry;
+use crate::types::{DownloadId, HistoryEntry};
 use crate::{Error, Result};
 
 use super::{Database, HistoryRow, NewHistoryEntry};
@@ -32,7 +32,7 @@ impl Database {
         .bind(entry.size_bytes as i64)
         .bind(entry.download_time_secs)
         .bind(entry.completed_at)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(Error::Sqlx)?;
 
@@ -53,7 +53,8 @@ impl Database {
             sqlx::query_as::<_, HistoryRow>(
                 r#"
                 SELECT id, name, category, destination, status, size_bytes,
-                       download_time_secs, completed_at
+                       download_time_secs, completed_at, download_id, retried_as,
+                       fetched_bytes, server_bytes, repair_blocks_used, extraction_time_ms
                 FROM history
                 WHERE status = ?
                 ORDER BY completed_at DESC
@@ -67,7 +68,8 @@ impl Database {
             sqlx::query_as::<_, HistoryRow>(
                 r#"
                 SELECT id, name, category, destination, status, size_bytes,
-                       download_time_secs, completed_at
+                       download_time_secs, completed_at, download_id, retried_as,
+                       fetched_bytes, server_bytes, repair_blocks_used, extraction_time_ms
                 FROM history
                 ORDER BY completed_at DESC
                 LIMIT ? OFFSET ?
@@ -102,6 +104,74 @@ impl Database {
         Ok(count)
     }
 
+    /// Search history by name and category, best matches first
+    ///
+    /// Every word of `search` has to start a word of the entry's name or category, so
+    /// `show s01` finds `Show.S01E02.720p`. Entries that match equally well are ordered
+    /// by completion time (most recent first).
+    pub async fn search_history(
+        &self,
+        search: &str,
+        status_filter: Option<i32>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<HistoryEntry>> {
+        let Some(query) = fts_query(search) else {
+            return Ok(Vec::new());
+        };
+
+        let rows = sqlx::query_as::<_, HistoryRow>(
+            r#"
+            SELECT h.id, h.name, h.category, h.destination, h.status, h.size_bytes,
+                   h.download_time_secs, h.completed_at, h.download_id, h.retried_as,
+                   h.fetched_bytes, h.server_bytes, h.repair_blocks_used, h.extraction_time_ms
+            FROM history_fts
+            JOIN history h ON h.id = history_fts.rowid
+            WHERE history_fts MATCH ?
+              AND (? IS NULL OR h.status = ?)
+            ORDER BY history_fts.rank, h.completed_at DESC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(&query)
+        .bind(status_filter)
+        .bind(status_filter)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Sqlx)?;
+
+        Ok(rows.into_iter().map(HistoryEntry::from).collect())
+    }
+
+    /// Count the history entries [`Database::search_history`] finds
+    pub async fn count_history_search(
+        &self,
+        search: &str,
+        status_filter: Option<i32>,
+    ) -> Result<i64> {
+        let Some(query) = fts_query(search) else {
+            return Ok(0);
+        };
+
+        sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM history_fts
+            JOIN history h ON h.id = history_fts.rowid
+            WHERE history_fts MATCH ?
+              AND (? IS NULL OR h.status = ?)
+            "#,
+        )
+        .bind(&query)
+        .bind(status_filter)
+        .bind(status_filter)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Error::Sqlx)
+    }
+
     /// Delete history entries older than the specified timestamp
     ///
     /// Returns the number of records deleted.
@@ -109,7 +179,7 @@ impl Database {
     pub async fn delete_history_before(&self, before_timestamp: i64) -> Result<u64> {
         let result = sqlx::query("DELETE FROM history WHERE completed_at < ?")
             .bind(before_timestamp)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(Error::Sqlx)?;
 
@@ -123,7 +193,7 @@ impl Database {
     pub async fn delete_history_by_status(&self, status: i32) -> Result<u64> {
         let result = sqlx::query("DELETE FROM history WHERE status = ?")
             .bind(status)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(Error::Sqlx)?;
 
@@ -136,7 +206,7 @@ impl Database {
     /// This is a destructive operation - use with caution.
     pub async fn clear_history(&self) -> Result<u64> {
         let result = sqlx::query("DELETE FROM history")
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(Error::Sqlx)?;
 
@@ -175,7 +245,7 @@ impl Database {
                     sqlx::query("DELETE FROM history WHERE completed_at < ? AND status = ?")
                         .bind(before)
                         .bind(status_val)
-                        .execute(&self.pool)
+                        .execute(&self.writer)
                         .await
                         .map_err(Error::Sqlx)?;
 
@@ -189,7 +259,8 @@ impl Database {
         let row = sqlx::query_as::<_, HistoryRow>(
             r#"
             SELECT id, name, category, destination, status, size_bytes,
-                   download_time_secs, completed_at
+                   download_time_secs, completed_at, download_id, retried_as,
+                   fetched_bytes, server_bytes, repair_blocks_used, extraction_time_ms
             FROM history
             WHERE id = ?
             "#,
@@ -201,4 +272,64 @@ impl Database {
 
         Ok(row.map(HistoryEntry::from))
     }
+
+    /// Record a download that just completed or failed in history
+    ///
+    /// Copies the download's current name, category, destination, status and statistics,
+    /// and links the entry to the download so it can be retried later. Returns the
+    /// entry's ID.
+    pub async fn record_download_history(&self, id: DownloadId) -> Result<i64> {
+        let now = chrono::Utc::now().timestamp();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO history (
+                name, category, destination, status, size_bytes,
+                download_time_secs, completed_at, download_id,
+                fetched_bytes, server_bytes, repair_blocks_used, extraction_time_ms
+            )
+            SELECT name, category, destination, status, size_bytes,
+                   MAX(COALESCE(completed_at, ?) - COALESCE(started_at, created_at), 0),
+                   ?, id,
+                   fetched_bytes, server_bytes, repair_blocks_used, extraction_time_ms
+            FROM downloads
+            WHERE id = ?
+            "#,
+        )
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&self.writer)
+        .await
+        .map_err(Error::Sqlx)?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound(format!("download {} not found", id)));
+        }
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Link a history entry to the download created by retrying it
+    pub async fn set_history_retried_as(&self, id: i64, download_id: DownloadId) -> Result<()> {
+        sqlx::query("UPDATE history SET retried_as = ? WHERE id = ?")
+            .bind(download_id)
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(Error::Sqlx)?;
+
+        Ok(())
+    }
+}
+
+/// FTS5 query matching entries with a word starting with each word of `search`
+///
+/// Words are quoted, so FTS5 syntax in `search` is matched literally. None if `search`
+/// has no words.
+fn fts_query(search: &str) -> Option<String> {
+    let terms: Vec<String> = search
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
 }
@@ -1,18 +1,131 @@
 //! Database lifecycle and schema migrations.
+//!
+//! The schema is versioned: migration `vN` takes the schema from version `N - 1` to `N`,
+//! and every migration can be reverted. [`Database::new`] rolls a database forward to
+//! [`LATEST_SCHEMA_VERSION`]; embedders that control upgrades open it with
+//! [`Database::open`] instead, inspect [`Database::plan_migration`] and move to a
+//! chosen version with [`Database::migrate_to`]. A snapshot of the database is written
+//! next to it before any existing schema is changed; only the newest
+//! [`MIGRATION_BACKUPS_KEPT`] are kept.
 
 use crate::error::DatabaseError;
 use crate::{Error, Result};
 use sqlx::SqliteConnection;
 use sqlx::sqlite::SqlitePool;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use super::Database;
 
+/// How long a statement waits on a lock held by another connection before failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Number of pre-migration snapshots kept next to the database; older ones are deleted
+pub const MIGRATION_BACKUPS_KEPT: usize = 3;
+
+/// Schema version produced by the newest migration
+pub const LATEST_SCHEMA_VERSION: i64 = 28;
+
+/// What each migration does, by version (`MIGRATIONS[0]` is v1)
+const MIGRATIONS: [&str; LATEST_SCHEMA_VERSION as usize] = [
+    "Create initial schema",
+    "Add runtime state table for shutdown tracking",
+    "Add RSS feed tables",
+    "Add download_files table and file_index column to download_articles",
+    "Add DirectUnpack support columns",
+    "Add direct_unpack_extracted_count column",
+    "Persist per-file paused state",
+    "Add batches (groups of downloads added together)",
+    "Add per-download temp_dir override",
+    "Add daily usage statistics",
+    "Link history entries to their download and its retry",
+    "Store per-download and NZB metadata passwords",
+    "Add RSS feed authentication",
+    "Add RSS feed preference and cross-feed grab tracking",
+    "Add RSS filter actions and overrides",
+    "Add held duplicates awaiting a decision",
+    "Persist disabled servers",
+    "Index articles by message-ID for cross-download deduplication",
+    "Add per-download in-progress folder names",
+    "Add per-download post-processing pause",
+    "Add event journal for replaying missed events",
+    "Add per-download statistics to downloads and history",
+    "Add deferred NZB URLs fetched when a download starts",
+    "Add failure codes to downloads",
+    "Hold PAR2 recovery volumes until repair needs them",
+    "Add full-text index for searching history",
+    "Journal the running post-processing stage",
+    "Add post-processing stage checkpoints",
+];
+
+/// Direction of a migration step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationDirection {
+    /// Apply the migration
+    Up,
+    /// Revert the migration
+    Down,
+}
+
+/// One migration applied or reverted by a [`MigrationPlan`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStep {
+    /// Version the migration produces when applied
+    pub version: i64,
+    /// Whether the migration is applied or reverted
+    pub direction: MigrationDirection,
+    /// What the migration changes
+    pub description: &'static str,
+}
+
+/// Migrations that take the schema from one version to another, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationPlan {
+    /// Current schema version
+    pub from_version: i64,
+    /// Requested schema version
+    pub to_version: i64,
+    /// Steps to run, in order (empty if the schema is already at `to_version`)
+    pub steps: Vec<MigrationStep>,
+}
+
+impl MigrationPlan {
+    /// Whether the schema is already at the requested version
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Result of [`Database::migrate_to`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationOutcome {
+    /// The steps that were run
+    pub plan: MigrationPlan,
+    /// Snapshot written before migrating (`None` if nothing ran or the database was empty)
+    pub backup_path: Option<PathBuf>,
+}
+
 impl Database {
     /// Create a new database connection
     ///
-    /// Creates the database file if it doesn't exist and runs migrations.
+    /// Creates the database file if it doesn't exist and rolls its schema forward to
+    /// [`LATEST_SCHEMA_VERSION`], backing it up first if it already had a schema.
+    /// A database with a newer schema is opened as is.
     pub async fn new(path: &Path) -> Result<Self> {
+        let db = Self::open(path).await?;
+
+        if db.current_version().await? < LATEST_SCHEMA_VERSION {
+            db.migrate_to(LATEST_SCHEMA_VERSION).await?;
+        }
+
+        Ok(db)
+    }
+
+    /// Open a database without migrating it
+    ///
+    /// Creates the database file if it doesn't exist. Use [`Database::migrate_to`] to
+    /// bring the schema to the version the caller expects.
+    pub async fn open(path: &Path) -> Result<Self> {
         // Create parent directory if it doesn't exist
         if let Some(parent) = path.parent() {
             tokio::fs::create_dir_all(parent).await.map_err(|e| {
@@ -23,8 +136,11 @@ impl Database {
             })?;
         }
 
-        // Connect to database with foreign key enforcement and WAL mode
-        use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+        // Connect to database with foreign key enforcement and WAL mode, so readers
+        // never block the writer; NORMAL sync is durable in WAL mode except on power loss
+        use sqlx::sqlite::{
+            SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous,
+        };
         use std::str::FromStr;
 
         let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", path.display()))
@@ -36,31 +152,33 @@ impl Database {
             })?
             .create_if_missing(true)
             .foreign_keys(true)
-            .journal_mode(SqliteJournalMode::Wal);
-
-        let pool = SqlitePool::connect_with(options).await.map_err(|e| {
-            Error::Database(DatabaseError::ConnectionFailed(format!(
-                "Failed to connect to database: {}",
-                e
-            )))
-        })?;
-
-        let db = Self { pool };
-
-        // Run migrations
-        db.run_migrations().await?;
-
-        Ok(db)
-    }
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(BUSY_TIMEOUT);
+
+        // Every write goes through a single connection, so concurrent writers queue for
+        // it instead of racing for the file lock and failing with `database is locked`
+        let writer = SqlitePoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(BUSY_TIMEOUT)
+            .connect_with(options.clone())
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::ConnectionFailed(format!(
+                    "Failed to connect to database: {}",
+                    e
+                )))
+            })?;
 
-    /// Run database migrations
-    async fn run_migrations(&self) -> Result<()> {
-        let mut conn = self.pool.acquire().await.map_err(|e| {
-            Error::Database(DatabaseError::ConnectionFailed(format!(
-                "Failed to acquire connection: {}",
-                e
-            )))
-        })?;
+        let pool = SqlitePoolOptions::new()
+            .connect_with(options)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::ConnectionFailed(format!(
+                    "Failed to connect to database: {}",
+                    e
+                )))
+            })?;
 
         // Create schema version table
         sqlx::query(
@@ -71,7 +189,7 @@ impl Database {
             )
             "#,
         )
-        .execute(&mut *conn)
+        .execute(&writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::MigrationFailed(format!(
@@ -80,43 +198,305 @@ impl Database {
             )))
         })?;
 
-        // Check current version
-        let current_version: Option<i64> =
-            sqlx::query_scalar("SELECT MAX(version) FROM schema_version")
-                .fetch_optional(&mut *conn)
-                .await
-                .map_err(|e| {
-                    Error::Database(DatabaseError::QueryFailed(format!(
-                        "Failed to query schema version: {}",
-                        e
-                    )))
-                })?;
+        Ok(Self {
+            pool,
+            writer,
+            pending_writes: std::sync::Mutex::new(Default::default()),
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Get the highest applied schema version (0 if no migrations have run)
+    pub async fn current_version(&self) -> Result<i64> {
+        let version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM schema_version")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to query schema version: {}",
+                    e
+                )))
+            })?;
+
+        Ok(version.unwrap_or(0))
+    }
+
+    /// Work out the migrations [`Database::migrate_to`] would run, without running them
+    ///
+    /// Fails if `version` is not between 0 and [`LATEST_SCHEMA_VERSION`], or if the
+    /// database has a schema newer than this version of the library knows.
+    pub async fn plan_migration(&self, version: i64) -> Result<MigrationPlan> {
+        if !(0..=LATEST_SCHEMA_VERSION).contains(&version) {
+            return Err(Error::Database(DatabaseError::MigrationFailed(format!(
+                "Unknown schema version {} (latest is {})",
+                version, LATEST_SCHEMA_VERSION
+            ))));
+        }
+
+        let current = self.current_version().await?;
+        if current > LATEST_SCHEMA_VERSION {
+            return Err(Error::Database(DatabaseError::MigrationFailed(format!(
+                "Database schema version {} is newer than the latest known version {}",
+                current, LATEST_SCHEMA_VERSION
+            ))));
+        }
 
-        let current_version = current_version.unwrap_or(0);
+        let step = |version: i64, direction| MigrationStep {
+            version,
+            direction,
+            description: MIGRATIONS[(version - 1) as usize],
+        };
+        let steps = if version >= current {
+            (current + 1..=version)
+                .map(|v| step(v, MigrationDirection::Up))
+                .collect()
+        } else {
+            (version + 1..=current)
+                .rev()
+                .map(|v| step(v, MigrationDirection::Down))
+                .collect()
+        };
+
+        Ok(MigrationPlan {
+            from_version: current,
+            to_version: version,
+            steps,
+        })
+    }
 
-        // Apply migrations
-        if current_version < 1 {
-            Self::migrate_v1(&mut conn).await?;
+    /// Migrate the schema forward or back to `version`
+    ///
+    /// Each step runs in its own transaction, so a failure leaves the schema at the
+    /// last version that was fully reached. Unless the database has no schema yet, a
+    /// snapshot is written next to the database file first
+    /// (`<file>.v<from>-<timestamp>.bak`), and all but the newest
+    /// [`MIGRATION_BACKUPS_KEPT`] snapshots are deleted. Reverting a migration drops the tables and
+    /// columns it added along with their data.
+    pub async fn migrate_to(&self, version: i64) -> Result<MigrationOutcome> {
+        let plan = self.plan_migration(version).await?;
+        if plan.is_empty() {
+            return Ok(MigrationOutcome {
+                plan,
+                backup_path: None,
+            });
         }
-        if current_version < 2 {
-            Self::migrate_v2(&mut conn).await?;
+
+        let backup_path = if plan.from_version > 0 {
+            Some(self.backup_before_migration(plan.from_version).await?)
+        } else {
+            None
+        };
+
+        let mut conn = self.writer.acquire().await.map_err(|e| {
+            Error::Database(DatabaseError::ConnectionFailed(format!(
+                "Failed to acquire connection: {}",
+                e
+            )))
+        })?;
+
+        for step in &plan.steps {
+            match step.direction {
+                MigrationDirection::Up => Self::apply_migration(&mut conn, step.version).await?,
+                MigrationDirection::Down => Self::revert_migration(&mut conn, step.version).await?,
+            }
         }
-        if current_version < 3 {
-            Self::migrate_v3(&mut conn).await?;
+
+        Ok(MigrationOutcome { plan, backup_path })
+    }
+
+    /// Snapshot the database before migrating it away from schema version `from`
+    async fn backup_before_migration(&self, from: i64) -> Result<PathBuf> {
+        let file_name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "usenet-dl.db".to_string());
+        let backup_path = self.path.with_file_name(format!(
+            "{}.v{}-{}.bak",
+            file_name,
+            from,
+            chrono::Utc::now().format("%Y%m%d%H%M%S")
+        ));
+
+        if tokio::fs::try_exists(&backup_path).await.unwrap_or(false) {
+            tokio::fs::remove_file(&backup_path).await?;
         }
-        if current_version < 4 {
-            Self::migrate_v4(&mut conn).await?;
+        self.snapshot_to(&backup_path).await?;
+
+        tracing::info!(
+            from_version = from,
+            backup = %backup_path.display(),
+            "Backed up database before migration"
+        );
+
+        if let Err(e) = prune_migration_backups(&backup_path, &file_name).await {
+            tracing::warn!(error = %e, "Failed to delete old migration backups");
         }
-        if current_version < 5 {
-            Self::migrate_v5(&mut conn).await?;
+        Ok(backup_path)
+    }
+
+    /// Apply the migration producing schema `version`
+    async fn apply_migration(conn: &mut SqliteConnection, version: i64) -> Result<()> {
+        match version {
+            1 => Self::migrate_v1(conn).await,
+            2 => Self::migrate_v2(conn).await,
+            3 => Self::migrate_v3(conn).await,
+            4 => Self::migrate_v4(conn).await,
+            5 => Self::migrate_v5(conn).await,
+            6 => Self::migrate_v6(conn).await,
+            7 => Self::migrate_v7(conn).await,
+            8 => Self::migrate_v8(conn).await,
+            9 => Self::migrate_v9(conn).await,
+            10 => Self::migrate_v10(conn).await,
+            11 => Self::migrate_v11(conn).await,
+            12 => Self::migrate_v12(conn).await,
+            13 => Self::migrate_v13(conn).await,
+            14 => Self::migrate_v14(conn).await,
+            15 => Self::migrate_v15(conn).await,
+            16 => Self::migrate_v16(conn).await,
+            17 => Self::migrate_v17(conn).await,
+            18 => Self::migrate_v18(conn).await,
+            19 => Self::migrate_v19(conn).await,
+            20 => Self::migrate_v20(conn).await,
+            21 => Self::migrate_v21(conn).await,
+            22 => Self::migrate_v22(conn).await,
+            23 => Self::migrate_v23(conn).await,
+            24 => Self::migrate_v24(conn).await,
+            25 => Self::migrate_v25(conn).await,
+            26 => Self::migrate_v26(conn).await,
+            27 => Self::migrate_v27(conn).await,
+            28 => Self::migrate_v28(conn).await,
+            _ => Err(Error::Database(DatabaseError::MigrationFailed(format!(
+                "Unknown migration v{}",
+                version
+            )))),
         }
-        if current_version < 6 {
-            Self::migrate_v6(&mut conn).await?;
+    }
+
+    /// Revert the migration producing schema `version`, back to `version - 1`
+    async fn revert_migration(conn: &mut SqliteConnection, version: i64) -> Result<()> {
+        // Indexes on a dropped column must go first; tables are dropped children first
+        let statements: &[&str] = match version {
+            1 => &[
+                "DROP TABLE history",
+                "DROP TABLE processed_nzbs",
+                "DROP TABLE passwords",
+                "DROP TABLE download_articles",
+                "DROP TABLE downloads",
+            ],
+            2 => &["DROP TABLE runtime_state"],
+            3 => &[
+                "DROP TABLE rss_seen",
+                "DROP TABLE rss_filters",
+                "DROP TABLE rss_feeds",
+            ],
+            4 => &[
+                "ALTER TABLE download_articles DROP COLUMN file_index",
+                "DROP TABLE download_files",
+            ],
+            5 => &[
+                "DROP INDEX idx_download_files_completed",
+                "ALTER TABLE download_files DROP COLUMN original_filename",
+                "ALTER TABLE download_files DROP COLUMN completed",
+                "ALTER TABLE downloads DROP COLUMN direct_unpack_state",
+            ],
+            6 => &["ALTER TABLE downloads DROP COLUMN direct_unpack_extracted_count"],
+            7 => &[
+                "DROP INDEX idx_download_files_paused",
+                "ALTER TABLE download_files DROP COLUMN paused",
+            ],
+            8 => &[
+                "DROP INDEX idx_downloads_batch",
+                "ALTER TABLE downloads DROP COLUMN batch_done",
+                "ALTER TABLE downloads DROP COLUMN batch_id",
+                "DROP TABLE batches",
+            ],
+            9 => &["ALTER TABLE downloads DROP COLUMN temp_dir"],
+            10 => &["DROP TABLE usage_stats"],
+            11 => &[
+                "ALTER TABLE history DROP COLUMN retried_as",
+                "ALTER TABLE history DROP COLUMN download_id",
+            ],
+            12 => &["DROP TABLE download_passwords"],
+            13 => &["ALTER TABLE rss_feeds DROP COLUMN auth"],
+            14 => &[
+                "DROP TABLE rss_grabs",
+                "ALTER TABLE rss_feeds DROP COLUMN preference",
+            ],
+            15 => &[
+                "ALTER TABLE rss_filters DROP COLUMN priority",
+                "ALTER TABLE rss_filters DROP COLUMN category",
+                "ALTER TABLE rss_filters DROP COLUMN action",
+            ],
+            16 => &["DROP TABLE held_duplicates"],
+            17 => &["DROP TABLE disabled_servers"],
+            18 => &["DROP INDEX idx_articles_message_id"],
+            19 => &["ALTER TABLE downloads DROP COLUMN incomplete_dir"],
+            20 => &["ALTER TABLE downloads DROP COLUMN post_processing_paused"],
+            21 => &["DROP TABLE event_journal"],
+            22 => &[
+                "ALTER TABLE history DROP COLUMN extraction_time_ms",
+                "ALTER TABLE history DROP COLUMN repair_blocks_used",
+                "ALTER TABLE history DROP COLUMN server_bytes",
+                "ALTER TABLE history DROP COLUMN fetched_bytes",
+                "ALTER TABLE downloads DROP COLUMN extraction_time_ms",
+                "ALTER TABLE downloads DROP COLUMN repair_blocks_used",
+                "ALTER TABLE downloads DROP COLUMN server_bytes",
+                "ALTER TABLE downloads DROP COLUMN fetched_bytes",
+            ],
+            23 => &["DROP TABLE deferred_nzbs"],
+            24 => &["ALTER TABLE downloads DROP COLUMN error_code"],
+            25 => &["ALTER TABLE download_files DROP COLUMN held"],
+            26 => &[
+                "DROP TRIGGER history_fts_update",
+                "DROP TRIGGER history_fts_delete",
+                "DROP TRIGGER history_fts_insert",
+                "DROP TABLE history_fts",
+            ],
+            27 => &["ALTER TABLE downloads DROP COLUMN post_process_stage"],
+            28 => &["DROP TABLE stage_checkpoints"],
+            _ => {
+                return Err(Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Unknown migration v{}",
+                    version
+                ))));
+            }
+        };
+
+        tracing::info!("Reverting database migration v{}", version);
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            for statement in statements {
+                sqlx::query(statement).execute(&mut *conn).await?;
+            }
+            sqlx::query("DELETE FROM schema_version WHERE version = ?")
+                .bind(version)
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("COMMIT").execute(&mut *conn).await?;
+            Ok::<(), sqlx::Error>(())
         }
-        if current_version < 7 {
-            Self::migrate_v7(&mut conn).await?;
+        .await;
+
+        if let Err(e) = result {
+            let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+            return Err(Error::Database(DatabaseError::MigrationFailed(format!(
+                "Failed to revert migration v{}: {}",
+                version, e
+            ))));
         }
 
+        tracing::info!("Database migration v{} reverted", version);
         Ok(())
     }
 
@@ -894,13 +1274,1358 @@ impl Database {
         Ok(())
     }
 
-    /// Close the database connection
-    pub async fn close(self) {
-        self.pool.close().await;
+    /// Migration v8: Add batches (groups of downloads added together).
+    async fn migrate_v8(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v8");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            sqlx::query(
+                r#"
+                CREATE TABLE batches (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    completed_at INTEGER
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to create batches table: {}",
+                    e
+                )))
+            })?;
+
+            sqlx::query(
+                "ALTER TABLE downloads ADD COLUMN batch_id INTEGER REFERENCES batches(id) ON DELETE SET NULL",
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to add batch_id column: {}",
+                    e
+                )))
+            })?;
+
+            // Set once a member reaches its final state (post-processing done or failed),
+            // which the status column alone can't express: downloads are briefly Complete
+            // before post-processing starts.
+            sqlx::query(
+                "ALTER TABLE downloads ADD COLUMN batch_done INTEGER NOT NULL DEFAULT 0",
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to add batch_done column: {}",
+                    e
+                )))
+            })?;
+
+            sqlx::query("CREATE INDEX idx_downloads_batch ON downloads(batch_id)")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::MigrationFailed(format!(
+                        "Failed to create batch index: {}",
+                        e
+                    )))
+                })?;
+
+            Self::record_migration(conn, 8).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v8: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v8 complete");
+        Ok(())
     }
 
-    /// Get the underlying connection pool
-    pub fn pool(&self) -> &SqlitePool {
-        &self.pool
+    /// Migration v9: Add per-download temp_dir override
+    async fn migrate_v9(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v9");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // NULL means the download uses the global download.temp_dir
+            sqlx::query("ALTER TABLE downloads ADD COLUMN temp_dir TEXT")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::MigrationFailed(format!(
+                        "Failed to add temp_dir column: {}",
+                        e
+                    )))
+                })?;
+
+            Self::record_migration(conn, 9).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v9: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v9 complete");
+        Ok(())
     }
+
+    /// Migration v10: Add daily usage statistics
+    async fn migrate_v10(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v10");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // One row per UTC day, server and category; weekly and monthly figures are
+            // summed from these at query time. Uncategorized downloads use ''.
+            sqlx::query(
+                r#"
+                CREATE TABLE usage_stats (
+                    day TEXT NOT NULL,
+                    server TEXT NOT NULL,
+                    category TEXT NOT NULL DEFAULT '',
+                    bytes INTEGER NOT NULL DEFAULT 0,
+                    PRIMARY KEY (day, server, category)
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to create usage_stats table: {}",
+                    e
+                )))
+            })?;
+
+            Self::record_migration(conn, 10).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v10: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v10 complete");
+        Ok(())
+    }
+
+    /// Migration v11: Link history entries to their download and its retry
+    async fn migrate_v11(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v11");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // The download a history entry records (its files and articles are what a
+            // retry re-queues), and the download created by retrying it. Neither is a
+            // foreign key: history outlives the downloads it refers to.
+            for statement in [
+                "ALTER TABLE history ADD COLUMN download_id INTEGER",
+                "ALTER TABLE history ADD COLUMN retried_as INTEGER",
+            ] {
+                sqlx::query(statement)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to add history column: {}",
+                            e
+                        )))
+                    })?;
+            }
+
+            Self::record_migration(conn, 11).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v11: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v11 complete");
+        Ok(())
+    }
+
+    /// Migration v12: Store per-download and NZB metadata passwords
+    async fn migrate_v12(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v12");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Passwords a download was added with, kept apart from the password cache
+            // (which only holds one known to be correct). Either may hold several
+            // passwords separated by semicolons.
+            sqlx::query(
+                r#"
+                CREATE TABLE download_passwords (
+                    download_id INTEGER PRIMARY KEY REFERENCES downloads(id) ON DELETE CASCADE,
+                    password TEXT,
+                    nzb_password TEXT
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to create download_passwords table: {}",
+                    e
+                )))
+            })?;
+
+            Self::record_migration(conn, 12).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v12: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v12 complete");
+        Ok(())
+    }
+
+    /// Migration v13: Add RSS feed authentication
+    async fn migrate_v13(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v13");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Headers, basic auth, cookies and API key for fetching the feed, as JSON
+            sqlx::query("ALTER TABLE rss_feeds ADD COLUMN auth TEXT")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::MigrationFailed(format!(
+                        "Failed to add auth column to rss_feeds: {}",
+                        e
+                    )))
+                })?;
+
+            Self::record_migration(conn, 13).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v13: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v13 complete");
+        Ok(())
+    }
+
+    /// Migration v14: Add RSS feed preference and cross-feed grab tracking
+    async fn migrate_v14(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v14");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            sqlx::query("ALTER TABLE rss_feeds ADD COLUMN preference INTEGER NOT NULL DEFAULT 0")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::MigrationFailed(format!(
+                        "Failed to add preference column to rss_feeds: {}",
+                        e
+                    )))
+                })?;
+
+            // Releases auto-downloaded from RSS, by normalized title. feed_id isn't a
+            // foreign key: a grab still counts after its feed is deleted.
+            for statement in [
+                r#"
+                CREATE TABLE rss_grabs (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    feed_id INTEGER NOT NULL,
+                    title_key TEXT NOT NULL,
+                    size INTEGER,
+                    grabbed_at INTEGER NOT NULL
+                )
+                "#,
+                "CREATE INDEX idx_rss_grabs_title_key ON rss_grabs(title_key)",
+            ] {
+                sqlx::query(statement)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to create rss_grabs table: {}",
+                            e
+                        )))
+                    })?;
+            }
+
+            Self::record_migration(conn, 14).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v14: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v14 complete");
+        Ok(())
+    }
+
+    /// Migration v15: Add RSS filter actions and overrides
+    async fn migrate_v15(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v15");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            for statement in [
+                "ALTER TABLE rss_filters ADD COLUMN action INTEGER NOT NULL DEFAULT 0",
+                "ALTER TABLE rss_filters ADD COLUMN category TEXT",
+                "ALTER TABLE rss_filters ADD COLUMN priority INTEGER",
+            ] {
+                sqlx::query(statement)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to add action columns to rss_filters: {}",
+                            e
+                        )))
+                    })?;
+            }
+
+            Self::record_migration(conn, 15).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v15: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v15 complete");
+        Ok(())
+    }
+
+    async fn migrate_v16(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v16");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Why a download was held, until it's accepted or discarded
+            sqlx::query(
+                r#"
+                CREATE TABLE held_duplicates (
+                    download_id INTEGER PRIMARY KEY REFERENCES downloads(id) ON DELETE CASCADE,
+                    method INTEGER NOT NULL,
+                    existing_id INTEGER NOT NULL,
+                    existing_name TEXT NOT NULL
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to create held_duplicates table: {}",
+                    e
+                )))
+            })?;
+
+            Self::record_migration(conn, 16).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v16: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v16 complete");
+        Ok(())
+    }
+
+    async fn migrate_v17(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v17");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Servers taken out of rotation, by server key; NULL until re-enabled by hand
+            sqlx::query(
+                r#"
+                CREATE TABLE disabled_servers (
+                    server TEXT PRIMARY KEY,
+                    disabled_until INTEGER
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to create disabled_servers table: {}",
+                    e
+                )))
+            })?;
+
+            Self::record_migration(conn, 17).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v17: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v17 complete");
+        Ok(())
+    }
+
+    async fn migrate_v18(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v18");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Lets a download find articles other queued downloads also need
+            sqlx::query("CREATE INDEX idx_articles_message_id ON download_articles(message_id)")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::MigrationFailed(format!(
+                        "Failed to create message_id index: {}",
+                        e
+                    )))
+                })?;
+
+            Self::record_migration(conn, 18).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v18: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v18 complete");
+        Ok(())
+    }
+
+    /// Migration v19: Add per-download in-progress folder names
+    async fn migrate_v19(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v19");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // NULL means the download uses the `download_{id}` folder
+            sqlx::query("ALTER TABLE downloads ADD COLUMN incomplete_dir TEXT")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::MigrationFailed(format!(
+                        "Failed to add incomplete_dir column: {}",
+                        e
+                    )))
+                })?;
+
+            Self::record_migration(conn, 19).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v19: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v19 complete");
+        Ok(())
+    }
+
+    /// Migration v20: Add per-download post-processing pause
+    async fn migrate_v20(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v20");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Post-processing waits while set, even once downloading is done
+            sqlx::query(
+                "ALTER TABLE downloads ADD COLUMN post_processing_paused INTEGER NOT NULL DEFAULT 0",
+            )
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::MigrationFailed(format!(
+                        "Failed to add post_processing_paused column: {}",
+                        e
+                    )))
+                })?;
+
+            Self::record_migration(conn, 20).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v20: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v20 complete");
+        Ok(())
+    }
+
+    /// Migration v21: Add event journal for replaying missed events
+    async fn migrate_v21(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v21");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Recent event envelopes, oldest trimmed as new ones arrive
+            sqlx::query(
+                r#"
+                CREATE TABLE event_journal (
+                    seq INTEGER PRIMARY KEY,
+                    event_type TEXT NOT NULL,
+                    envelope TEXT NOT NULL,
+                    created_at INTEGER NOT NULL
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to create event_journal table: {}",
+                    e
+                )))
+            })?;
+
+            Self::record_migration(conn, 21).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v21: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v21 complete");
+        Ok(())
+    }
+
+    /// Migration v22: Add per-download statistics to downloads and history
+    async fn migrate_v22(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v22");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Collected on the download while it runs and copied into its history entry.
+            // server_bytes is a JSON object of bytes fetched per server hostname.
+            for table in ["downloads", "history"] {
+                for column in [
+                    "fetched_bytes INTEGER NOT NULL DEFAULT 0",
+                    "server_bytes TEXT",
+                    "repair_blocks_used INTEGER NOT NULL DEFAULT 0",
+                    "extraction_time_ms INTEGER NOT NULL DEFAULT 0",
+                ] {
+                    sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {}", table, column))
+                        .execute(&mut *conn)
+                        .await
+                        .map_err(|e| {
+                            Error::Database(DatabaseError::MigrationFailed(format!(
+                                "Failed to add {} statistics column: {}",
+                                table, e
+                            )))
+                        })?;
+                }
+            }
+
+            Self::record_migration(conn, 22).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v22: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v22 complete");
+        Ok(())
+    }
+
+    async fn migrate_v23(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v23");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // NZB of a download added without fetching it, until it starts
+            sqlx::query(
+                r#"
+                CREATE TABLE deferred_nzbs (
+                    download_id INTEGER PRIMARY KEY REFERENCES downloads(id) ON DELETE CASCADE,
+                    url TEXT NOT NULL
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to create deferred_nzbs table: {}",
+                    e
+                )))
+            })?;
+
+            Self::record_migration(conn, 23).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v23: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v23 complete");
+        Ok(())
+    }
+
+    /// Migration v24: Add failure codes to downloads
+    async fn migrate_v24(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v24");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Machine-readable reason a download failed, next to error_message
+            sqlx::query("ALTER TABLE downloads ADD COLUMN error_code TEXT")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::MigrationFailed(format!(
+                        "Failed to add error_code column: {}",
+                        e
+                    )))
+                })?;
+
+            Self::record_migration(conn, 24).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v24: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v24 complete");
+        Ok(())
+    }
+
+    /// Migration v25: Hold PAR2 recovery volumes until repair needs them
+    async fn migrate_v25(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v25");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Files whose articles are only fetched once repair asks for them
+            sqlx::query("ALTER TABLE download_files ADD COLUMN held INTEGER NOT NULL DEFAULT 0")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::MigrationFailed(format!(
+                        "Failed to add held column: {}",
+                        e
+                    )))
+                })?;
+
+            Self::record_migration(conn, 25).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v25: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v25 complete");
+        Ok(())
+    }
+
+    /// Migration v26: Add full-text index for searching history
+    async fn migrate_v26(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v26");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // External-content index over history names and categories, kept in step
+            // by triggers and filled from the existing rows
+            for statement in [
+                r#"
+                CREATE VIRTUAL TABLE history_fts USING fts5(
+                    name, category, content='history', content_rowid='id'
+                )
+                "#,
+                r#"
+                CREATE TRIGGER history_fts_insert AFTER INSERT ON history BEGIN
+                    INSERT INTO history_fts(rowid, name, category)
+                    VALUES (new.id, new.name, new.category);
+                END
+                "#,
+                r#"
+                CREATE TRIGGER history_fts_delete AFTER DELETE ON history BEGIN
+                    INSERT INTO history_fts(history_fts, rowid, name, category)
+                    VALUES ('delete', old.id, old.name, old.category);
+                END
+                "#,
+                r#"
+                CREATE TRIGGER history_fts_update AFTER UPDATE OF name, category ON history BEGIN
+                    INSERT INTO history_fts(history_fts, rowid, name, category)
+                    VALUES ('delete', old.id, old.name, old.category);
+                    INSERT INTO history_fts(rowid, name, category)
+                    VALUES (new.id, new.name, new.category);
+                END
+                "#,
+                "INSERT INTO history_fts(history_fts) VALUES ('rebuild')",
+            ] {
+                sqlx::query(statement)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to create history search index: {}",
+                            e
+                        )))
+                    })?;
+            }
+
+            Self::record_migration(conn, 26).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v26: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v26 complete");
+        Ok(())
+    }
+
+    /// Migration v27: Journal the running post-processing stage
+    async fn migrate_v27(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v27");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Stage a post-processing run was in, so one cut short by a crash can resume
+            sqlx::query("ALTER TABLE downloads ADD COLUMN post_process_stage TEXT")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    Error::Database(DatabaseError::MigrationFailed(format!(
+                        "Failed to add post_process_stage column: {}",
+                        e
+                    )))
+                })?;
+
+            Self::record_migration(conn, 27).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v27: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v27 complete");
+        Ok(())
+    }
+
+    /// Migration v28: Add post-processing stage checkpoints
+    async fn migrate_v28(conn: &mut SqliteConnection) -> Result<()> {
+        tracing::info!("Applying database migration v28");
+
+        sqlx::query("BEGIN")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to begin transaction: {}",
+                    e
+                )))
+            })?;
+
+        let result = async {
+            // Post-processing stages a download has completed, skipped when it's run again
+            sqlx::query(
+                r#"
+                CREATE TABLE stage_checkpoints (
+                    download_id INTEGER NOT NULL REFERENCES downloads(id) ON DELETE CASCADE,
+                    stage TEXT NOT NULL,
+                    completed_at INTEGER NOT NULL,
+                    PRIMARY KEY (download_id, stage)
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::MigrationFailed(format!(
+                    "Failed to create stage_checkpoints table: {}",
+                    e
+                )))
+            })?;
+
+            Self::record_migration(conn, 28).await?;
+            Ok::<(), Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                sqlx::query("COMMIT")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| {
+                        Error::Database(DatabaseError::MigrationFailed(format!(
+                            "Failed to commit migration v28: {}",
+                            e
+                        )))
+                    })?;
+            }
+            Err(e) => {
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                return Err(e);
+            }
+        }
+
+        tracing::info!("Database migration v28 complete");
+        Ok(())
+    }
+
+    /// Close the database connection
+    ///
+    /// Writes any queued progress first.
+    pub async fn close(self) {
+        if let Err(e) = self.flush_pending_writes().await {
+            tracing::warn!(error = %e, "Failed to flush queued progress while closing database");
+        }
+        self.pool.close().await;
+        self.writer.close().await;
+    }
+
+    /// Get the underlying connection pool
+    ///
+    /// Used for reads; the database's own writes go through a separate single-connection
+    /// pool, so writes made here may wait up to the busy timeout for it.
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+}
+
+/// Delete all but the newest [`MIGRATION_BACKUPS_KEPT`] snapshots of `file_name`
+/// in the directory of `latest`
+async fn prune_migration_backups(latest: &Path, file_name: &str) -> std::io::Result<()> {
+    let dir = match latest.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut backups = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if is_migration_backup(&name, file_name) {
+            let modified = entry.metadata().await?.modified()?;
+            backups.push((modified, entry.path()));
+        }
+    }
+
+    // Newest first
+    backups.sort_by(|a, b| b.cmp(a));
+    for (_, path) in backups.into_iter().skip(MIGRATION_BACKUPS_KEPT) {
+        if path != latest {
+            tokio::fs::remove_file(&path).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether `name` is a `{file_name}.v<version>-<timestamp>.bak` snapshot written by
+/// [`Database::migrate_to`], so backups the user made by hand are never pruned
+fn is_migration_backup(name: &str, file_name: &str) -> bool {
+    let Some((version, timestamp)) = name
+        .strip_prefix(file_name)
+        .and_then(|rest| rest.strip_prefix(".v"))
+        .and_then(|rest| rest.strip_suffix(".bak"))
+        .and_then(|rest| rest.split_once('-'))
+    else {
+        return false;
+    };
+    let all_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    all_digits(version) && timestamp.len() == 14 && all_digits(timestamp)
 }
@@ -5,27 +5,44 @@
 //! ## Submodules
 //!
 //! Methods on [`Database`] are organized by domain:
-//! - [`migrations`] — Database lifecycle, schema migrations
+//! - [`migrations`] — Database lifecycle, versioned schema migrations with dry-run plans
 //! - [`downloads`] — Download queue CRUD
 //! - [`articles`] — Article-level tracking for resume support
-//! - [`passwords`] — Password cache for archive extraction
+//! - [`passwords`] — Password cache and per-download passwords for archive extraction
 //! - [`duplicates`] — Duplicate detection queries
 //! - [`history`] — History management
 //! - [`state`] — Runtime state (shutdown tracking, NZB processing, RSS seen)
 //! - [`rss`] — RSS feed CRUD
+//! - [`batches`] — Batch (download group) tracking
+//! - [`stats`] — Daily usage statistics
+//! - [`backup`] — Snapshots for backup and restore
+//! - [`servers`] — Servers taken out of rotation
+//! - [`write_behind`] — Progress writes queued and flushed in batches
+//! - [`events`] — Journal of recent events for replay
+//! - [`deferred`] — NZBs fetched when their download starts
 
-use crate::types::{HistoryEntry, Status};
+use crate::types::{DuplicateInfo, HistoryEntry, Status};
 use sqlx::{FromRow, sqlite::SqlitePool};
 use std::path::PathBuf;
 
 mod articles;
+mod backup;
+mod batches;
+mod checkpoints;
+mod deferred;
 mod downloads;
 mod duplicates;
+mod events;
 mod history;
-mod migrations;
+pub mod migrations;
 mod passwords;
 mod rss;
+mod servers;
 mod state;
+mod stats;
+mod write_behind;
+
+pub use migrations::{LATEST_SCHEMA_VERSION, MIGRATION_BACKUPS_KEPT};
 
 /// New download to be inserted into the database
 #[derive(Debug, Clone)]
@@ -89,6 +106,8 @@ pub struct Download {
     pub downloaded_bytes: i64,
     /// Error message if download failed
     pub error_message: Option<String>,
+    /// Machine-readable reason the download failed (see [`crate::error::failure_code`])
+    pub error_code: Option<String>,
     /// Unix timestamp when download was created
     pub created_at: i64,
     /// Unix timestamp when download started
@@ -99,6 +118,52 @@ pub struct Download {
     pub direct_unpack_state: i32,
     /// Number of files extracted by DirectUnpack (0 means vacuous completion)
     pub direct_unpack_extracted_count: i32,
+    /// Per-download temp directory override (None uses the configured temp_dir)
+    pub temp_dir: Option<String>,
+    /// In-progress folder relative to the temp directory (None uses `download_{id}`)
+    pub incomplete_dir: Option<String>,
+    /// Whether this download's post-processing is paused
+    pub post_processing_paused: bool,
+}
+
+impl Download {
+    /// Why this download failed, if it has
+    pub fn failure(&self) -> Option<crate::error::ErrorDetail> {
+        let failed = matches!(
+            crate::types::Status::from_i32(self.status),
+            crate::types::Status::Failed | crate::types::Status::FailedUnrepairable
+        );
+        let message = self.error_message.as_deref().filter(|m| !m.is_empty());
+        match (failed, message) {
+            (true, Some(message)) => Some(crate::error::ErrorDetail::download_failure(
+                self.error_code.as_deref(),
+                message,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Directory holding this download's in-progress data
+    ///
+    /// The folder picked by `download.incomplete_layout` when the download was added
+    /// (`download_{id}` if none was), under the per-download temp_dir override if set,
+    /// otherwise under `default_temp_dir` (normally `config.download.temp_dir`).
+    pub fn incomplete_path(&self, default_temp_dir: &std::path::Path) -> std::path::PathBuf {
+        let base = self
+            .temp_dir
+            .as_deref()
+            .map(std::path::Path::new)
+            .unwrap_or(default_temp_dir);
+        match &self.incomplete_dir {
+            Some(dir) => base.join(dir),
+            None => base.join(format!("download_{}", self.id)),
+        }
+    }
+
+    /// Release name used for lookups: the job name (NZB title) if known, else the name
+    pub fn release_name(&self) -> &str {
+        self.job_name.as_deref().unwrap_or(&self.name)
+    }
 }
 
 /// New article to be inserted into the database
@@ -169,6 +234,8 @@ pub struct DownloadFile {
     pub total_segments: i32,
     /// Whether this file is paused (0=no, 1=yes)
     pub paused: i32,
+    /// Whether this file is held until repair needs it (0=no, 1=yes)
+    pub held: i32,
     /// Whether all segments of this file have been downloaded (0=no, 1=yes)
     pub completed: i32,
     /// Original filename before DirectRename (None if not renamed)
@@ -223,6 +290,31 @@ pub struct HistoryRow {
     pub download_time_secs: i64,
     /// Unix timestamp when download completed
     pub completed_at: i64,
+    /// Download this entry records (None for entries inserted directly)
+    pub download_id: Option<i64>,
+    /// Download created by retrying this entry
+    pub retried_as: Option<i64>,
+    /// Bytes fetched from servers, including refetched articles
+    pub fetched_bytes: i64,
+    /// JSON object of bytes fetched per server hostname
+    pub server_bytes: Option<String>,
+    /// PAR2 recovery blocks used by repair
+    pub repair_blocks_used: i64,
+    /// Time spent extracting archives, in milliseconds
+    pub extraction_time_ms: i64,
+}
+
+/// Parse the `server_bytes` column into per-server totals, largest first
+pub(crate) fn parse_server_bytes(json: Option<&str>) -> Vec<crate::types::ServerUsage> {
+    let totals: std::collections::HashMap<String, u64> = json
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default();
+    let mut servers: Vec<_> = totals
+        .into_iter()
+        .map(|(server, bytes)| crate::types::ServerUsage { server, bytes })
+        .collect();
+    servers.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.server.cmp(&b.server)));
+    servers
 }
 
 impl From<HistoryRow> for HistoryEntry {
@@ -238,14 +330,34 @@ impl From<HistoryRow> for HistoryEntry {
             status: Status::from_i32(row.status),
             size_bytes: row.size_bytes as u64,
             download_time: Duration::from_secs(row.download_time_secs as u64),
+            fetched_bytes: row.fetched_bytes as u64,
+            average_speed_bps: row.fetched_bytes as u64 / row.download_time_secs.max(1) as u64,
+            server_bytes: parse_server_bytes(row.server_bytes.as_deref()),
+            repair_blocks_used: row.repair_blocks_used as u32,
+            extraction_time: Duration::from_millis(row.extraction_time_ms as u64),
             completed_at: Utc
                 .timestamp_opt(row.completed_at, 0)
                 .single()
                 .unwrap_or_else(Utc::now),
+            download_id: row.download_id.map(crate::types::DownloadId),
+            retried_as: row.retried_as.map(crate::types::DownloadId),
         }
     }
 }
 
+/// Batch record from database
+#[derive(Debug, Clone, FromRow)]
+pub struct Batch {
+    /// Unique database ID
+    pub id: i64,
+    /// Batch display name
+    pub name: String,
+    /// Unix timestamp when the batch was created
+    pub created_at: i64,
+    /// Unix timestamp when every member reached a final state
+    pub completed_at: Option<i64>,
+}
+
 /// RSS feed record from database
 #[derive(Debug, Clone, FromRow)]
 pub struct RssFeed {
@@ -271,6 +383,10 @@ pub struct RssFeed {
     pub last_error: Option<String>,
     /// Unix timestamp when feed was created
     pub created_at: i64,
+    /// Feed authentication as JSON (see [`crate::config::RssFeedAuth`])
+    pub auth: Option<String>,
+    /// Rank among feeds carrying the same releases (lower is preferred)
+    pub preference: i32,
 }
 
 /// RSS filter record from database
@@ -292,6 +408,12 @@ pub struct RssFilterRow {
     pub max_size: Option<i64>,
     /// Maximum age of items in seconds
     pub max_age_secs: Option<i64>,
+    /// Action code for matched items (see `RssFilterAction`)
+    pub action: i32,
+    /// Category override for matched items
+    pub category: Option<String>,
+    /// Priority override for matched items
+    pub priority: Option<i32>,
 }
 
 /// Parameters for inserting a new RSS feed
@@ -310,6 +432,10 @@ pub struct InsertRssFeedParams<'a> {
     pub priority: i32,
     /// Whether the feed is enabled
     pub enabled: bool,
+    /// Feed authentication as JSON
+    pub auth: Option<&'a str>,
+    /// Rank among feeds carrying the same releases (lower is preferred)
+    pub preference: i32,
 }
 
 /// Parameters for updating an existing RSS feed
@@ -330,6 +456,10 @@ pub struct UpdateRssFeedParams<'a> {
     pub priority: i32,
     /// Whether the feed is enabled
     pub enabled: bool,
+    /// Feed authentication as JSON
+    pub auth: Option<&'a str>,
+    /// Rank among feeds carrying the same releases (lower is preferred)
+    pub preference: i32,
 }
 
 /// Parameters for inserting a new RSS filter
@@ -348,11 +478,106 @@ pub struct InsertRssFilterParams<'a> {
     pub max_size: Option<i64>,
     /// Maximum age in seconds
     pub max_age_secs: Option<i64>,
+    /// Action code for matched items
+    pub action: i32,
+    /// Category override for matched items
+    pub category: Option<&'a str>,
+    /// Priority override for matched items
+    pub priority: Option<i32>,
+}
+
+/// Release auto-downloaded from an RSS feed, from database
+#[derive(Debug, Clone, FromRow)]
+pub struct RssGrabRow {
+    /// Feed the release was grabbed from
+    pub feed_id: i64,
+    /// Release size in bytes, if the feed reported it
+    pub size: Option<i64>,
+    /// Unix timestamp of the grab
+    pub grabbed_at: i64,
+}
+
+/// Daily usage record from database
+#[derive(Debug, Clone, FromRow)]
+pub struct UsageRow {
+    /// UTC date (YYYY-MM-DD)
+    pub day: String,
+    /// Server hostname
+    pub server: String,
+    /// Category name ('' for uncategorized downloads)
+    pub category: String,
+    /// Bytes downloaded
+    pub bytes: i64,
+}
+
+/// Server taken out of rotation, from database
+#[derive(Debug, Clone, FromRow)]
+pub struct DisabledServerRow {
+    /// Server key, `host:port` prefixed with `username@` if the server has one
+    pub server: String,
+    /// Unix timestamp at which the server is enabled again (`None` = until re-enabled)
+    pub disabled_until: Option<i64>,
+}
+
+/// Passwords a download was added with, from database
+///
+/// Each may hold several passwords separated by semicolons.
+#[derive(Debug, Clone, Default, PartialEq, Eq, FromRow)]
+pub struct DownloadPasswords {
+    /// Password given when adding the download
+    pub password: Option<String>,
+    /// Password from the NZB's `<meta type="password">`
+    pub nzb_password: Option<String>,
+}
+
+/// Details of a deferred NZB once it has been fetched
+#[derive(Debug, Clone)]
+pub struct FetchedNzb {
+    /// Display name for this download
+    pub name: String,
+    /// Original name from NZB metadata
+    pub nzb_meta_name: Option<String>,
+    /// SHA-256 hash of the NZB file
+    pub nzb_hash: String,
+    /// Job name for post-processing scripts
+    pub job_name: String,
+    /// Total size in bytes
+    pub size_bytes: i64,
+}
+
+/// Why a download is held as a duplicate, from database
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct HeldDuplicate {
+    /// The held download
+    pub download_id: i64,
+    /// Detection method that matched (see `DuplicateMethod::to_i32`)
+    pub method: i32,
+    /// ID of the download it duplicates
+    pub existing_id: i64,
+    /// Name of the download it duplicates
+    pub existing_name: String,
+}
+
+impl From<HeldDuplicate> for DuplicateInfo {
+    fn from(row: HeldDuplicate) -> Self {
+        DuplicateInfo {
+            method: crate::config::DuplicateMethod::from_i32(row.method),
+            existing_id: crate::types::DownloadId(row.existing_id),
+            existing_name: row.existing_name,
+        }
+    }
 }
 
 /// Database handle for usenet-dl
 pub struct Database {
+    /// Connections for reads
     pool: SqlitePool,
+    /// The one connection every write goes through, serializing writers
+    writer: SqlitePool,
+    /// Progress updates waiting for the next flush
+    pending_writes: std::sync::Mutex<write_behind::PendingWrites>,
+    /// Path of the database file, used for pre-migration backups
+    path: PathBuf,
 }
 
 // unwrap/expect are acceptable in tests for concise failure-on-error assertions
@@ -4,7 +4,7 @@ use crate::error::DatabaseError;
 use crate::types::DownloadId;
 use crate::{Error, Result};
 
-use super::Database;
+use super::{Database, DownloadPasswords};
 
 impl Database {
     /// Cache a correct password for a download
@@ -25,7 +25,7 @@ impl Database {
         )
         .bind(download_id)
         .bind(password)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -62,7 +62,7 @@ impl Database {
     pub async fn delete_cached_password(&self, download_id: DownloadId) -> Result<()> {
         sqlx::query("DELETE FROM passwords WHERE download_id = ?")
             .bind(download_id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -73,4 +73,61 @@ impl Database {
 
         Ok(())
     }
+
+    /// Store the passwords a download was added with
+    ///
+    /// Unlike [`Database::set_correct_password`], these are only candidates: they are
+    /// tried after the cached correct password during extraction.
+    pub async fn set_download_passwords(
+        &self,
+        download_id: DownloadId,
+        password: Option<&str>,
+        nzb_password: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO download_passwords (download_id, password, nzb_password)
+            VALUES (?, ?, ?)
+            ON CONFLICT(download_id) DO UPDATE SET
+                password = excluded.password,
+                nzb_password = excluded.nzb_password
+            "#,
+        )
+        .bind(download_id)
+        .bind(password)
+        .bind(nzb_password)
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to set download passwords: {}",
+                e
+            )))
+        })?;
+
+        Ok(())
+    }
+
+    /// Get the passwords a download was added with
+    ///
+    /// Returns empty passwords if none were stored for this download.
+    pub async fn get_download_passwords(
+        &self,
+        download_id: DownloadId,
+    ) -> Result<DownloadPasswords> {
+        let passwords: Option<DownloadPasswords> = sqlx::query_as(
+            "SELECT password, nzb_password FROM download_passwords WHERE download_id = ?",
+        )
+        .bind(download_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to get download passwords: {}",
+                e
+            )))
+        })?;
+
+        Ok(passwords.unwrap_or_default())
+    }
 }
@@ -4,7 +4,7 @@ use crate::error::DatabaseError;
 use crate::{Error, Result};
 
 use super::{
-    Database, InsertRssFeedParams, InsertRssFilterParams, RssFeed, RssFilterRow,
+    Database, InsertRssFeedParams, InsertRssFilterParams, RssFeed, RssFilterRow, RssGrabRow,
     UpdateRssFeedParams,
 };
 
@@ -14,7 +14,8 @@ impl Database {
         let feeds = sqlx::query_as::<_, RssFeed>(
             r#"
             SELECT id, name, url, check_interval_secs, category, auto_download,
-                   priority, enabled, last_check, last_error, created_at
+                   priority, enabled, last_check, last_error, created_at, auth,
+                   preference
             FROM rss_feeds
             ORDER BY id ASC
             "#,
@@ -36,7 +37,8 @@ impl Database {
         let feed = sqlx::query_as::<_, RssFeed>(
             r#"
             SELECT id, name, url, check_interval_secs, category, auto_download,
-                   priority, enabled, last_check, last_error, created_at
+                   priority, enabled, last_check, last_error, created_at, auth,
+                   preference
             FROM rss_feeds
             WHERE id = ?
             "#,
@@ -64,14 +66,16 @@ impl Database {
             auto_download,
             priority,
             enabled,
+            auth,
+            preference,
         } = params;
         let now = chrono::Utc::now().timestamp();
 
         let result = sqlx::query(
             r#"
             INSERT INTO rss_feeds (name, url, check_interval_secs, category, auto_download,
-                                  priority, enabled, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                                  priority, enabled, created_at, auth, preference)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(name)
@@ -82,7 +86,9 @@ impl Database {
         .bind(priority)
         .bind(enabled as i32)
         .bind(now)
-        .execute(&self.pool)
+        .bind(auth)
+        .bind(preference)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -105,12 +111,15 @@ impl Database {
             auto_download,
             priority,
             enabled,
+            auth,
+            preference,
         } = params;
         let result = sqlx::query(
             r#"
             UPDATE rss_feeds
             SET name = ?, url = ?, check_interval_secs = ?, category = ?,
-                auto_download = ?, priority = ?, enabled = ?
+                auto_download = ?, priority = ?, enabled = ?, auth = ?,
+                preference = ?
             WHERE id = ?
             "#,
         )
@@ -121,8 +130,10 @@ impl Database {
         .bind(auto_download as i32)
         .bind(priority)
         .bind(enabled as i32)
+        .bind(auth)
+        .bind(preference)
         .bind(id)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -138,7 +149,7 @@ impl Database {
     pub async fn delete_rss_feed(&self, id: i64) -> Result<bool> {
         let result = sqlx::query("DELETE FROM rss_feeds WHERE id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -155,7 +166,7 @@ impl Database {
         let filters = sqlx::query_as::<_, RssFilterRow>(
             r#"
             SELECT id, feed_id, name, include_patterns, exclude_patterns,
-                   min_size, max_size, max_age_secs
+                   min_size, max_size, max_age_secs, action, category, priority
             FROM rss_filters
             WHERE feed_id = ?
             ORDER BY id ASC
@@ -184,12 +195,16 @@ impl Database {
             min_size,
             max_size,
             max_age_secs,
+            action,
+            category,
+            priority,
         } = params;
         let result = sqlx::query(
             r#"
             INSERT INTO rss_filters (feed_id, name, include_patterns, exclude_patterns,
-                                    min_size, max_size, max_age_secs)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+                                    min_size, max_size, max_age_secs, action, category,
+                                    priority)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(feed_id)
@@ -199,7 +214,10 @@ impl Database {
         .bind(min_size)
         .bind(max_size)
         .bind(max_age_secs)
-        .execute(&self.pool)
+        .bind(action)
+        .bind(category)
+        .bind(priority)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -215,7 +233,7 @@ impl Database {
     pub async fn delete_rss_filters(&self, feed_id: i64) -> Result<()> {
         sqlx::query("DELETE FROM rss_filters WHERE feed_id = ?")
             .bind(feed_id)
-            .execute(&self.pool)
+            .execute(&self.writer)
             .await
             .map_err(|e| {
                 Error::Database(DatabaseError::QueryFailed(format!(
@@ -245,7 +263,7 @@ impl Database {
         .bind(now)
         .bind(last_error)
         .bind(id)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -256,4 +274,74 @@ impl Database {
 
         Ok(())
     }
+
+    /// Record a release auto-downloaded from an RSS feed
+    pub async fn record_rss_grab(
+        &self,
+        feed_id: i64,
+        title_key: &str,
+        size: Option<i64>,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query(
+            "INSERT INTO rss_grabs (feed_id, title_key, size, grabbed_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(feed_id)
+        .bind(title_key)
+        .bind(size)
+        .bind(now)
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to record RSS grab: {}",
+                e
+            )))
+        })?;
+
+        Ok(())
+    }
+
+    /// Get grabs of a release (by normalized title) made at or after `since`
+    pub async fn find_rss_grabs(&self, title_key: &str, since: i64) -> Result<Vec<RssGrabRow>> {
+        let grabs = sqlx::query_as::<_, RssGrabRow>(
+            r#"
+            SELECT feed_id, size, grabbed_at
+            FROM rss_grabs
+            WHERE title_key = ? AND grabbed_at >= ?
+            ORDER BY grabbed_at ASC
+            "#,
+        )
+        .bind(title_key)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to find RSS grabs: {}",
+                e
+            )))
+        })?;
+
+        Ok(grabs)
+    }
+
+    /// Forget grabs made before `before` (Unix timestamp)
+    ///
+    /// Returns the number of grabs deleted.
+    pub async fn delete_rss_grabs_before(&self, before: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM rss_grabs WHERE grabbed_at < ?")
+            .bind(before)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to delete old RSS grabs: {}",
+                    e
+                )))
+            })?;
+
+        Ok(result.rows_affected())
+    }
 }
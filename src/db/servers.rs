@@ -0,0 +1,83 @@
+//! Servers taken out of rotation.
+//!
+//! Servers are identified by a key built from their connection settings rather than
+//! their index, so a disabled server stays disabled when others are added or removed.
+
+use crate::error::DatabaseError;
+use crate::{Error, Result};
+
+use super::{Database, DisabledServerRow};
+
+impl Database {
+    /// Mark a server as disabled until `disabled_until` (unix seconds), or until it's
+    /// re-enabled if `None`
+    pub async fn set_server_disabled(
+        &self,
+        server: &str,
+        disabled_until: Option<i64>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO disabled_servers (server, disabled_until)
+            VALUES (?, ?)
+            ON CONFLICT(server) DO UPDATE SET disabled_until = excluded.disabled_until
+            "#,
+        )
+        .bind(server)
+        .bind(disabled_until)
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to disable server: {}",
+                e
+            )))
+        })?;
+
+        Ok(())
+    }
+
+    /// Forget that a server was disabled
+    pub async fn clear_server_disabled(&self, server: &str) -> Result<()> {
+        sqlx::query("DELETE FROM disabled_servers WHERE server = ?")
+            .bind(server)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to enable server: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// Servers that are disabled at `now` (unix seconds)
+    ///
+    /// Entries that expired before `now` are removed.
+    pub async fn list_disabled_servers(&self, now: i64) -> Result<Vec<DisabledServerRow>> {
+        sqlx::query("DELETE FROM disabled_servers WHERE disabled_until <= ?")
+            .bind(now)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to remove expired server disables: {}",
+                    e
+                )))
+            })?;
+
+        sqlx::query_as::<_, DisabledServerRow>(
+            "SELECT server, disabled_until FROM disabled_servers ORDER BY server",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to list disabled servers: {}",
+                e
+            )))
+        })
+    }
+}
@@ -47,7 +47,7 @@ impl Database {
         )
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -75,7 +75,7 @@ impl Database {
         )
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -87,6 +87,50 @@ impl Database {
         Ok(())
     }
 
+    /// Whether post-processing was paused queue-wide
+    pub async fn is_post_processing_paused(&self) -> Result<bool> {
+        let value: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT value FROM runtime_state WHERE key = 'post_processing_paused'
+            "#,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to check post-processing pause: {}",
+                e
+            )))
+        })?;
+
+        Ok(value.is_some_and(|v| v == "true"))
+    }
+
+    /// Pause or resume post-processing queue-wide, surviving restarts
+    pub async fn set_post_processing_paused_all(&self, paused: bool) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let value = if paused { "true" } else { "false" };
+        sqlx::query(
+            r#"
+            INSERT INTO runtime_state (key, value, updated_at)
+            VALUES ('post_processing_paused', ?, ?)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(value)
+        .bind(now)
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to set post-processing pause: {}",
+                e
+            )))
+        })?;
+
+        Ok(())
+    }
+
     /// Mark an NZB file as processed
     ///
     /// This is used by the folder watcher with WatchFolderAction::Keep to track
@@ -105,7 +149,7 @@ impl Database {
         .bind(&path_str)
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -175,7 +219,7 @@ impl Database {
         .bind(guid)
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .execute(&self.writer)
         .await
         .map_err(|e| {
             Error::Database(DatabaseError::QueryFailed(format!(
@@ -0,0 +1,144 @@
+//! Daily usage statistics and per-download statistics.
+
+use crate::error::DatabaseError;
+use crate::types::DownloadId;
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::{Database, UsageRow};
+
+impl Database {
+    /// Add downloaded bytes to a day's total for a server and category
+    ///
+    /// `day` is a UTC date (YYYY-MM-DD); use '' as the category for uncategorized downloads.
+    pub async fn add_usage(
+        &self,
+        day: &str,
+        server: &str,
+        category: &str,
+        bytes: u64,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO usage_stats (day, server, category, bytes)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(day, server, category) DO UPDATE SET bytes = bytes + excluded.bytes
+            "#,
+        )
+        .bind(day)
+        .bind(server)
+        .bind(category)
+        .bind(bytes as i64)
+        .execute(&self.writer)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to record usage: {}",
+                e
+            )))
+        })?;
+
+        Ok(())
+    }
+
+    /// Get usage rows for every day from `since_day` (inclusive, YYYY-MM-DD) onwards
+    pub async fn get_usage_since(&self, since_day: &str) -> Result<Vec<UsageRow>> {
+        let rows = sqlx::query_as::<_, UsageRow>(
+            "SELECT day, server, category, bytes FROM usage_stats WHERE day >= ? ORDER BY day ASC",
+        )
+        .bind(since_day)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to get usage statistics: {}",
+                e
+            )))
+        })?;
+
+        Ok(rows)
+    }
+
+    /// Add bytes fetched for a download, by server hostname, to its totals
+    pub async fn add_download_traffic(
+        &self,
+        id: DownloadId,
+        server_bytes: &HashMap<String, u64>,
+    ) -> Result<()> {
+        let query_failed = |e: sqlx::Error| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to record download traffic: {}",
+                e
+            )))
+        };
+
+        let mut tx = self.writer.begin().await.map_err(query_failed)?;
+
+        let existing: Option<Option<String>> =
+            sqlx::query_scalar("SELECT server_bytes FROM downloads WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(query_failed)?;
+        let Some(existing) = existing else {
+            return Ok(());
+        };
+
+        let mut totals: HashMap<String, u64> = existing
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        for (server, bytes) in server_bytes {
+            *totals.entry(server.clone()).or_default() += bytes;
+        }
+        let json = serde_json::to_string(&totals)?;
+
+        sqlx::query(
+            "UPDATE downloads SET fetched_bytes = fetched_bytes + ?, server_bytes = ? WHERE id = ?",
+        )
+        .bind(server_bytes.values().sum::<u64>() as i64)
+        .bind(json)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(query_failed)?;
+
+        tx.commit().await.map_err(query_failed)?;
+
+        Ok(())
+    }
+
+    /// Record how many PAR2 recovery blocks repairing a download used
+    pub async fn set_repair_blocks_used(&self, id: DownloadId, blocks: u32) -> Result<()> {
+        sqlx::query("UPDATE downloads SET repair_blocks_used = ? WHERE id = ?")
+            .bind(blocks as i64)
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to record repair blocks: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+
+    /// Record how long extracting a download's archives took
+    pub async fn set_extraction_time(&self, id: DownloadId, elapsed: Duration) -> Result<()> {
+        sqlx::query("UPDATE downloads SET extraction_time_ms = ? WHERE id = ?")
+            .bind(elapsed.as_millis() as i64)
+            .bind(id)
+            .execute(&self.writer)
+            .await
+            .map_err(|e| {
+                Error::Database(DatabaseError::QueryFailed(format!(
+                    "Failed to record extraction time: {}",
+                    e
+                )))
+            })?;
+
+        Ok(())
+    }
+}
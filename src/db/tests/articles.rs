@@ -717,3 +717,84 @@ async fn test_all_remaining_pending_articles_can_be_paused() {
 
     db.close().await;
 }
+
+#[tokio::test]
+async fn test_held_files_wait_until_released() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+    let download_id = create_download_with_files(&db).await;
+
+    db.hold_files(download_id, &[1]).await.unwrap();
+
+    let pending = db.get_pending_articles(download_id).await.unwrap();
+    assert!(pending.iter().all(|article| article.file_index == 0));
+    // Unlike paused files, held files don't keep a download from completing
+    db.hold_files(download_id, &[0]).await.unwrap();
+    assert!(!db.has_active_pending_articles(download_id).await.unwrap());
+    assert!(!db.has_any_pending_articles(download_id).await.unwrap());
+
+    let released = db.release_held_files(download_id).await.unwrap();
+    assert_eq!(released.len(), 2);
+    assert!(released.iter().all(|file| file.held == 0));
+    assert_eq!(db.get_pending_articles(download_id).await.unwrap().len(), 4);
+    assert!(db.release_held_files(download_id).await.unwrap().is_empty());
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn test_shared_article_readers_counts_unfinished_downloads_still_needing_article() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    // Three downloads of the same cross-posted release
+    let first = create_download_with_files(&db).await;
+    let second = create_download_with_files(&db).await;
+    let finished = create_download_with_files(&db).await;
+    db.update_status(finished, crate::types::Status::Complete.to_i32())
+        .await
+        .unwrap();
+
+    // The second download already has one article; the first has another
+    let article_id = |download_id, message_id: &'static str| {
+        let db = &db;
+        async move {
+            db.get_article_by_message_id(download_id, message_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .id
+        }
+    };
+    let done = article_id(second, "<f0-1@example.com>").await;
+    db.update_article_status(done, article_status::DOWNLOADED)
+        .await
+        .unwrap();
+    let done = article_id(first, "<f1-2@example.com>").await;
+    db.update_article_status(done, article_status::DOWNLOADED)
+        .await
+        .unwrap();
+
+    let mut readers = db.shared_article_readers(first).await.unwrap();
+    readers.sort();
+    assert_eq!(
+        readers,
+        [
+            ("<f0-2@example.com>".to_string(), 1),
+            ("<f1-1@example.com>".to_string(), 1),
+        ]
+    );
+
+    // Articles the other download already has aren't shared
+    let mut readers = db.shared_article_readers(second).await.unwrap();
+    readers.sort();
+    assert_eq!(
+        readers,
+        [
+            ("<f0-2@example.com>".to_string(), 1),
+            ("<f1-1@example.com>".to_string(), 1),
+        ]
+    );
+
+    db.close().await;
+}
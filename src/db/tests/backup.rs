@@ -0,0 +1,84 @@
+use crate::db::*;
+use tempfile::{NamedTempFile, tempdir};
+
+fn new_download(name: &str) -> NewDownload {
+    NewDownload {
+        name: name.to_string(),
+        nzb_path: format!("/path/to/{}.nzb", name),
+        nzb_meta_name: None,
+        nzb_hash: None,
+        job_name: Some(name.to_string()),
+        category: None,
+        destination: "/downloads".to_string(),
+        post_process: 4,
+        priority: 0,
+        status: 0,
+        size_bytes: 1024,
+    }
+}
+
+#[tokio::test]
+async fn test_snapshot_and_restore_replace_table_contents() {
+    let source_file = NamedTempFile::new().unwrap();
+    let source = Database::new(source_file.path()).await.unwrap();
+    source
+        .insert_download(&new_download("from-backup"))
+        .await
+        .unwrap();
+    source
+        .add_usage("2024-01-15", "news.example.com", "tv", 100)
+        .await
+        .unwrap();
+
+    let snapshot_dir = tempdir().unwrap();
+    let snapshot = snapshot_dir.path().join("snapshot.db");
+    source.snapshot_to(&snapshot).await.unwrap();
+    source.close().await;
+
+    let target_file = NamedTempFile::new().unwrap();
+    let target = Database::new(target_file.path()).await.unwrap();
+    target
+        .insert_download(&new_download("local-1"))
+        .await
+        .unwrap();
+    target
+        .insert_download(&new_download("local-2"))
+        .await
+        .unwrap();
+
+    target.restore_from(&snapshot).await.unwrap();
+
+    let downloads = target.list_downloads().await.unwrap();
+    assert_eq!(downloads.len(), 1, "local rows are replaced");
+    assert_eq!(downloads[0].name, "from-backup");
+
+    let usage = target.get_usage_since("2024-01-01").await.unwrap();
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].bytes, 100);
+
+    // The attached source is released again
+    target.restore_from(&snapshot).await.unwrap();
+
+    target.close().await;
+}
+
+#[tokio::test]
+async fn test_restore_from_missing_tables_leaves_database_unchanged() {
+    let dir = tempdir().unwrap();
+    let empty = dir.path().join("empty.db");
+    std::fs::File::create(&empty).unwrap();
+
+    let target_file = NamedTempFile::new().unwrap();
+    let target = Database::new(target_file.path()).await.unwrap();
+    target
+        .insert_download(&new_download("local"))
+        .await
+        .unwrap();
+
+    assert!(target.restore_from(&empty).await.is_err());
+
+    let downloads = target.list_downloads().await.unwrap();
+    assert_eq!(downloads.len(), 1, "failed restore rolls back");
+
+    target.close().await;
+}
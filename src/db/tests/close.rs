@@ -63,7 +63,9 @@ async fn test_insert_download_after_pool_close_returns_error() {
     let temp_file = NamedTempFile::new().unwrap();
     let db = Database::new(temp_file.path()).await.unwrap();
 
+    // Writes go through their own connection
     db.pool().close().await;
+    db.writer.close().await;
 
     let new_download = NewDownload {
         name: "After Close".to_string(),
@@ -93,7 +95,9 @@ async fn test_update_status_after_pool_close_returns_error() {
     let temp_file = NamedTempFile::new().unwrap();
     let db = Database::new(temp_file.path()).await.unwrap();
 
+    // Writes go through their own connection
     db.pool().close().await;
+    db.writer.close().await;
 
     let result = db.update_status(DownloadId(1), 1).await;
     assert!(
@@ -206,3 +206,127 @@ async fn test_get_incomplete_downloads() {
 
     db.close().await;
 }
+
+#[tokio::test]
+async fn test_concurrent_writes_and_reads_do_not_fail_with_locked() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = std::sync::Arc::new(Database::new(temp_file.path()).await.unwrap());
+
+    let mut ids = Vec::new();
+    for i in 0..4 {
+        let new_download = NewDownload {
+            name: format!("Download {}", i),
+            nzb_path: format!("/path/to/test{}.nzb", i),
+            nzb_meta_name: None,
+            nzb_hash: None,
+            job_name: None,
+            category: None,
+            destination: "/downloads".to_string(),
+            post_process: 4,
+            priority: 0,
+            status: 1,
+            size_bytes: 1000,
+        };
+        ids.push(db.insert_download(&new_download).await.unwrap());
+    }
+
+    // Progress updates from every download racing the API's reads
+    let mut tasks = Vec::new();
+    for id in ids.clone() {
+        let db = std::sync::Arc::clone(&db);
+        tasks.push(tokio::spawn(async move {
+            for step in 1..=50u64 {
+                db.update_progress(id, step as f32 * 2.0, 1000, step * 20)
+                    .await?;
+                db.list_downloads().await?;
+            }
+            Ok::<(), crate::Error>(())
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    for id in ids {
+        let download = db.get_download(id).await.unwrap().unwrap();
+        assert_eq!(download.downloaded_bytes, 1000);
+    }
+}
+
+#[tokio::test]
+async fn test_queued_progress_is_coalesced_until_flushed() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    let new_download = NewDownload {
+        name: "Queued".to_string(),
+        nzb_path: "/path/to/queued.nzb".to_string(),
+        nzb_meta_name: None,
+        nzb_hash: None,
+        job_name: None,
+        category: None,
+        destination: "/downloads".to_string(),
+        post_process: 4,
+        priority: 0,
+        status: 1,
+        size_bytes: 1000,
+    };
+    let id = db.insert_download(&new_download).await.unwrap();
+
+    db.queue_progress(id, 10.0, 100, 100);
+    db.queue_progress(id, 50.0, 500, 500);
+
+    let download = db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(
+        download.downloaded_bytes, 0,
+        "nothing written before a flush"
+    );
+
+    db.flush_pending_writes().await.unwrap();
+    let download = db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.progress, 50.0);
+    assert_eq!(download.downloaded_bytes, 500);
+
+    // A direct write supersedes anything still queued
+    db.queue_progress(id, 60.0, 600, 600);
+    db.update_progress(id, 100.0, 0, 1000).await.unwrap();
+    db.flush_pending_writes().await.unwrap();
+    let download = db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.downloaded_bytes, 1000);
+}
+
+#[tokio::test]
+async fn test_post_process_stage_round_trips() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    let id = db
+        .insert_download(&NewDownload {
+            name: "Journaled".to_string(),
+            nzb_path: "/path/to/journaled.nzb".to_string(),
+            nzb_meta_name: None,
+            nzb_hash: None,
+            job_name: None,
+            category: None,
+            destination: "/downloads".to_string(),
+            post_process: 4,
+            priority: 0,
+            status: 3,
+            size_bytes: 1024,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(db.get_post_process_stage(id).await.unwrap(), None);
+    db.set_post_process_stage(id, Some(crate::types::Stage::Extract))
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_post_process_stage(id).await.unwrap(),
+        Some(crate::types::Stage::Extract)
+    );
+    db.set_post_process_stage(id, None).await.unwrap();
+    assert_eq!(db.get_post_process_stage(id).await.unwrap(), None);
+
+    db.close().await;
+}
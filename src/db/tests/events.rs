@@ -0,0 +1,55 @@
+use crate::db::*;
+use crate::types::{Event, EventEnvelope};
+use tempfile::NamedTempFile;
+
+#[tokio::test]
+async fn test_event_journal_keeps_newest_events() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+    assert_eq!(db.latest_event_seq().await.unwrap(), 0);
+
+    for seq in 1..=5 {
+        db.queue_journal_event(&EventEnvelope::new(seq, &Event::QueuePaused), 3);
+    }
+    assert!(
+        db.list_events_since(0).await.unwrap().is_empty(),
+        "nothing journaled before a flush"
+    );
+
+    db.flush_pending_writes().await.unwrap();
+    assert_eq!(db.latest_event_seq().await.unwrap(), 5);
+
+    let seqs: Vec<u64> = db
+        .list_events_since(0)
+        .await
+        .unwrap()
+        .iter()
+        .map(|envelope| envelope.seq)
+        .collect();
+    assert_eq!(seqs, vec![3, 4, 5]);
+
+    let since_four = db.list_events_since(4).await.unwrap();
+    assert_eq!(since_four.len(), 1);
+    assert_eq!(since_four[0].event_type, "queue_paused");
+    assert_eq!(since_four[0].payload, serde_json::json!({}));
+}
+
+#[tokio::test]
+async fn test_event_journal_keeps_newest_events_across_gaps() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    for seq in [1, 4, 5, 9] {
+        db.queue_journal_event(&EventEnvelope::new(seq, &Event::QueuePaused), 3);
+    }
+    db.flush_pending_writes().await.unwrap();
+
+    let seqs: Vec<u64> = db
+        .list_events_since(0)
+        .await
+        .unwrap()
+        .iter()
+        .map(|envelope| envelope.seq)
+        .collect();
+    assert_eq!(seqs, vec![4, 5, 9]);
+}
@@ -80,6 +80,78 @@ async fn test_query_history_pagination() {
     db.close().await;
 }
 
+#[tokio::test]
+async fn test_search_history_ranks_matches() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    let now = chrono::Utc::now().timestamp();
+    for (i, (name, category, status)) in [
+        ("Show.S01E01.720p", Some("tv"), 4),
+        ("Show.S01E02.1080p", Some("tv"), 5),
+        ("Other.Show.Show.Show.S02E01", Some("tv"), 4),
+        ("Some.Movie.2024", Some("movies"), 4),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        db.insert_history(&NewHistoryEntry {
+            name: name.to_string(),
+            category: category.map(str::to_string),
+            destination: None,
+            status,
+            size_bytes: 1024,
+            download_time_secs: 60,
+            completed_at: now - i as i64,
+        })
+        .await
+        .unwrap();
+    }
+
+    // Word prefixes, in any case
+    let found = db.search_history("show s01", None, 10, 0).await.unwrap();
+    let names: Vec<_> = found.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["Show.S01E01.720p", "Show.S01E02.1080p"]);
+    assert_eq!(db.count_history_search("show s01", None).await.unwrap(), 2);
+
+    // The entry naming the word most often ranks first
+    let found = db.search_history("show", None, 10, 0).await.unwrap();
+    assert_eq!(found.len(), 3);
+    assert_eq!(found[0].name, "Other.Show.Show.Show.S02E01");
+
+    // Categories match too, and the status filter applies
+    let found = db.search_history("movies", None, 10, 0).await.unwrap();
+    assert_eq!(found[0].name, "Some.Movie.2024");
+    let failed = db.search_history("show", Some(5), 10, 0).await.unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].name, "Show.S01E02.1080p");
+    assert_eq!(db.count_history_search("show", Some(5)).await.unwrap(), 1);
+
+    // Paging
+    let second = db.search_history("show", None, 2, 2).await.unwrap();
+    assert_eq!(second.len(), 1);
+
+    // FTS5 syntax is matched literally, and punctuation alone finds nothing
+    assert!(db.search_history("show OR \"", None, 10, 0).await.is_ok());
+    assert!(
+        db.search_history("...", None, 10, 0)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // Deleted entries leave the index
+    db.clear_history().await.unwrap();
+    assert!(
+        db.search_history("show", None, 10, 0)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    db.close().await;
+}
+
 #[tokio::test]
 async fn test_query_history_status_filter() {
     let temp_file = NamedTempFile::new().unwrap();
@@ -274,3 +346,117 @@ async fn test_history_ordering() {
 
     db.close().await;
 }
+
+#[tokio::test]
+async fn test_record_download_history_links_download_and_retry() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    let download = NewDownload {
+        name: "Recorded.Show".to_string(),
+        nzb_path: "memory:Recorded.Show".to_string(),
+        nzb_meta_name: None,
+        nzb_hash: None,
+        job_name: None,
+        category: Some("tv".to_string()),
+        destination: "/downloads/tv".to_string(),
+        post_process: 4,
+        priority: 0,
+        status: Status::Failed.to_i32(),
+        size_bytes: 2048,
+    };
+    let original = db.insert_download(&download).await.unwrap();
+    let retry = db.insert_download(&download).await.unwrap();
+
+    let id = db.record_download_history(original).await.unwrap();
+    let entry = db.get_history_entry(id).await.unwrap().unwrap();
+    assert_eq!(entry.name, "Recorded.Show");
+    assert_eq!(entry.category.as_deref(), Some("tv"));
+    assert_eq!(entry.destination, Some(PathBuf::from("/downloads/tv")));
+    assert_eq!(entry.status, Status::Failed);
+    assert_eq!(entry.size_bytes, 2048);
+    assert_eq!(entry.download_id, Some(original));
+    assert!(entry.retried_as.is_none());
+
+    db.set_history_retried_as(id, retry).await.unwrap();
+    let entry = db.get_history_entry(id).await.unwrap().unwrap();
+    assert_eq!(entry.retried_as, Some(retry));
+
+    assert!(
+        db.record_download_history(crate::types::DownloadId(9999))
+            .await
+            .is_err(),
+        "unknown downloads are not recorded"
+    );
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn test_record_download_history_copies_statistics() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    let id = db
+        .insert_download(&NewDownload {
+            name: "Stats.Show".to_string(),
+            nzb_path: "memory:Stats.Show".to_string(),
+            nzb_meta_name: None,
+            nzb_hash: None,
+            job_name: None,
+            category: None,
+            destination: "/downloads".to_string(),
+            post_process: 4,
+            priority: 0,
+            status: Status::Complete.to_i32(),
+            size_bytes: 2048,
+        })
+        .await
+        .unwrap();
+
+    let traffic = |pairs: &[(&str, u64)]| {
+        pairs
+            .iter()
+            .map(|(server, bytes)| (server.to_string(), *bytes))
+            .collect::<std::collections::HashMap<_, _>>()
+    };
+    db.add_download_traffic(
+        id,
+        &traffic(&[("news.example.com", 1500), ("backup.example.com", 300)]),
+    )
+    .await
+    .unwrap();
+    // A retried article fetched again from the backup server
+    db.add_download_traffic(id, &traffic(&[("backup.example.com", 400)]))
+        .await
+        .unwrap();
+    db.set_repair_blocks_used(id, 12).await.unwrap();
+    db.set_extraction_time(id, std::time::Duration::from_millis(2500))
+        .await
+        .unwrap();
+
+    let history_id = db.record_download_history(id).await.unwrap();
+    let entry = db.get_history_entry(history_id).await.unwrap().unwrap();
+    assert_eq!(entry.fetched_bytes, 2200);
+    let servers: Vec<_> = entry
+        .server_bytes
+        .iter()
+        .map(|s| (s.server.as_str(), s.bytes))
+        .collect();
+    assert_eq!(
+        servers,
+        vec![("news.example.com", 1500), ("backup.example.com", 700)]
+    );
+    assert_eq!(entry.repair_blocks_used, 12);
+    assert_eq!(
+        entry.extraction_time,
+        std::time::Duration::from_millis(2500)
+    );
+
+    // Listing includes the statistics too
+    let page = db.query_history(None, 10, 0).await.unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].fetched_bytes, 2200);
+
+    db.close().await;
+}
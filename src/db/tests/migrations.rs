@@ -38,3 +38,195 @@ async fn test_database_creation() {
 
     db.close().await;
 }
+
+async fn table_exists(db: &Database, table: &str) -> bool {
+    let name: Option<String> =
+        sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type='table' AND name = ?")
+            .bind(table)
+            .fetch_optional(&db.pool)
+            .await
+            .unwrap();
+    name.is_some()
+}
+
+#[tokio::test]
+async fn test_open_does_not_migrate() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.db"))
+        .await
+        .unwrap();
+
+    assert_eq!(db.current_version().await.unwrap(), 0);
+    assert!(!table_exists(&db, "downloads").await);
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn test_plan_migration_is_dry_run() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    let plan = db.plan_migration(9).await.unwrap();
+    assert_eq!(plan.from_version, LATEST_SCHEMA_VERSION);
+    assert_eq!(plan.to_version, 9);
+    let versions: Vec<i64> = plan.steps.iter().map(|s| s.version).collect();
+    assert_eq!(
+        versions,
+        vec![
+            28, 27, 26, 25, 24, 23, 22, 21, 20, 19, 18, 17, 16, 15, 14, 13, 12, 11, 10
+        ]
+    );
+    assert!(
+        plan.steps
+            .iter()
+            .all(|s| s.direction == migrations::MigrationDirection::Down)
+    );
+
+    // Nothing was changed
+    assert_eq!(db.current_version().await.unwrap(), LATEST_SCHEMA_VERSION);
+    assert!(table_exists(&db, "usage_stats").await);
+    assert!(
+        db.plan_migration(LATEST_SCHEMA_VERSION)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn test_plan_migration_rejects_unknown_version() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    assert!(db.plan_migration(LATEST_SCHEMA_VERSION + 1).await.is_err());
+    assert!(db.plan_migration(-1).await.is_err());
+    assert!(db.migrate_to(LATEST_SCHEMA_VERSION + 1).await.is_err());
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn test_migrate_down_and_up_preserves_data() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = Database::new(&db_path).await.unwrap();
+
+    let id = db
+        .insert_download(&NewDownload {
+            name: "Kept".to_string(),
+            nzb_path: "/tmp/kept.nzb".to_string(),
+            nzb_meta_name: None,
+            nzb_hash: None,
+            job_name: None,
+            category: None,
+            destination: "/downloads".to_string(),
+            post_process: 0,
+            priority: 0,
+            status: 0,
+            size_bytes: 1024,
+        })
+        .await
+        .unwrap();
+
+    let outcome = db.migrate_to(7).await.unwrap();
+    assert_eq!(
+        outcome.plan.steps.len(),
+        (LATEST_SCHEMA_VERSION - 7) as usize
+    );
+    assert_eq!(db.current_version().await.unwrap(), 7);
+    assert!(!table_exists(&db, "batches").await);
+    assert!(!table_exists(&db, "usage_stats").await);
+
+    // A snapshot of the pre-migration database was written next to it
+    let backup = outcome.backup_path.unwrap();
+    assert_eq!(backup.parent(), Some(temp_dir.path()));
+    let snapshot = Database::open(&backup).await.unwrap();
+    assert_eq!(
+        snapshot.current_version().await.unwrap(),
+        LATEST_SCHEMA_VERSION
+    );
+    snapshot.close().await;
+
+    let outcome = db.migrate_to(LATEST_SCHEMA_VERSION).await.unwrap();
+    assert_eq!(
+        outcome.plan.steps.len(),
+        (LATEST_SCHEMA_VERSION - 7) as usize
+    );
+    assert_eq!(db.current_version().await.unwrap(), LATEST_SCHEMA_VERSION);
+    assert!(table_exists(&db, "batches").await);
+    assert_eq!(db.get_download(id).await.unwrap().unwrap().name, "Kept");
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn test_migrate_to_zero_and_back() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::new(&temp_dir.path().join("test.db"))
+        .await
+        .unwrap();
+
+    db.migrate_to(0).await.unwrap();
+    assert_eq!(db.current_version().await.unwrap(), 0);
+    for table in ["downloads", "history", "rss_feeds", "usage_stats"] {
+        assert!(!table_exists(&db, table).await, "{} still exists", table);
+    }
+
+    // Empty schema: nothing to back up
+    let outcome = db.migrate_to(LATEST_SCHEMA_VERSION).await.unwrap();
+    assert!(outcome.backup_path.is_none());
+    assert_eq!(db.current_version().await.unwrap(), LATEST_SCHEMA_VERSION);
+    assert!(table_exists(&db, "downloads").await);
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn test_migrate_to_keeps_only_newest_backups() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::new(&temp_dir.path().join("test.db"))
+        .await
+        .unwrap();
+
+    let mut latest = None;
+    for version in (LATEST_SCHEMA_VERSION - 4..LATEST_SCHEMA_VERSION).rev() {
+        latest = db.migrate_to(version).await.unwrap().backup_path;
+    }
+
+    let backups: Vec<_> = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "bak"))
+        .collect();
+    assert_eq!(backups.len(), MIGRATION_BACKUPS_KEPT);
+    assert!(backups.contains(&latest.unwrap()));
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn test_migrate_to_leaves_other_backups_alone() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::new(&temp_dir.path().join("test.db"))
+        .await
+        .unwrap();
+    let own_backup = temp_dir.path().join("test.db.vbefore-upgrade.bak");
+    std::fs::write(&own_backup, b"user backup").unwrap();
+
+    for version in (LATEST_SCHEMA_VERSION - 4..LATEST_SCHEMA_VERSION).rev() {
+        db.migrate_to(version).await.unwrap();
+    }
+
+    assert!(own_backup.exists(), "hand-made backup must not be pruned");
+    let snapshots = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "bak") && *path != own_backup)
+        .count();
+    assert_eq!(snapshots, MIGRATION_BACKUPS_KEPT);
+
+    db.close().await;
+}
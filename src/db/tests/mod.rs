@@ -1,9 +1,13 @@
 mod articles;
+mod backup;
 mod close;
 mod downloads;
 mod duplicates;
+mod events;
 mod history;
 mod migrations;
 mod passwords;
 mod rss;
+mod servers;
 mod state;
+mod stats;
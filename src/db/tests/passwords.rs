@@ -191,3 +191,54 @@ async fn test_empty_password() {
 
     db.close().await;
 }
+
+#[tokio::test]
+async fn test_download_passwords() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db_path = temp_file.path();
+    let db = Database::new(db_path).await.unwrap();
+
+    // Create a download
+    let new_download = NewDownload {
+        name: "Test".to_string(),
+        nzb_path: "/test.nzb".to_string(),
+        nzb_meta_name: None,
+        nzb_hash: None,
+        job_name: None,
+        category: None,
+        destination: "/downloads".to_string(),
+        post_process: 4,
+        priority: 0,
+        status: 0,
+        size_bytes: 1024,
+    };
+    let download_id = db.insert_download(&new_download).await.unwrap();
+
+    // Nothing stored yet
+    let passwords = db.get_download_passwords(download_id).await.unwrap();
+    assert_eq!(passwords, DownloadPasswords::default());
+
+    // Both passwords are kept, separate from the correct password cache
+    db.set_download_passwords(download_id, Some("a;b"), Some("meta"))
+        .await
+        .unwrap();
+    let passwords = db.get_download_passwords(download_id).await.unwrap();
+    assert_eq!(passwords.password.as_deref(), Some("a;b"));
+    assert_eq!(passwords.nzb_password.as_deref(), Some("meta"));
+    assert!(db.get_cached_password(download_id).await.unwrap().is_none());
+
+    // Setting again replaces both
+    db.set_download_passwords(download_id, None, Some("meta2"))
+        .await
+        .unwrap();
+    let passwords = db.get_download_passwords(download_id).await.unwrap();
+    assert_eq!(passwords.password, None);
+    assert_eq!(passwords.nzb_password.as_deref(), Some("meta2"));
+
+    // Deleting the download deletes its passwords
+    db.delete_download(download_id).await.unwrap();
+    let passwords = db.get_download_passwords(download_id).await.unwrap();
+    assert_eq!(passwords, DownloadPasswords::default());
+
+    db.close().await;
+}
@@ -18,6 +18,8 @@ async fn insert_test_feed(db: &Database, name: &str, url: &str) -> i64 {
         auto_download: true,
         priority: 1,
         enabled: true,
+        auth: None,
+        preference: 0,
     })
     .await
     .unwrap()
@@ -436,6 +438,8 @@ async fn insert_and_get_feed_preserves_all_fields() {
             auto_download: false,
             priority: 2,
             enabled: false,
+            auth: None,
+            preference: 0,
         })
         .await
         .unwrap();
@@ -479,6 +483,8 @@ async fn insert_feed_with_null_category_stores_none() {
             auto_download: true,
             priority: 0,
             enabled: true,
+            auth: None,
+            preference: 0,
         })
         .await
         .unwrap();
@@ -547,6 +553,8 @@ async fn update_feed_persists_changed_fields() {
             auto_download: false,
             priority: -1,
             enabled: false,
+            auth: None,
+            preference: 0,
         })
         .await
         .unwrap();
@@ -579,6 +587,8 @@ async fn update_nonexistent_feed_returns_false() {
             auto_download: false,
             priority: 0,
             enabled: false,
+            auth: None,
+            preference: 0,
         })
         .await
         .unwrap();
@@ -635,6 +645,9 @@ async fn delete_feed_cascades_to_filters() {
         min_size: None,
         max_size: None,
         max_age_secs: None,
+        action: 0,
+        category: None,
+        priority: None,
     })
     .await
     .unwrap();
@@ -647,6 +660,9 @@ async fn delete_feed_cascades_to_filters() {
         min_size: None,
         max_size: None,
         max_age_secs: None,
+        action: 0,
+        category: None,
+        priority: None,
     })
     .await
     .unwrap();
@@ -697,6 +713,9 @@ async fn insert_and_get_filter_preserves_all_fields() {
             min_size: Some(500_000_000),
             max_size: Some(50_000_000_000),
             max_age_secs: Some(86400),
+            action: 0,
+            category: None,
+            priority: None,
         })
         .await
         .unwrap();
@@ -731,6 +750,9 @@ async fn insert_filter_with_all_optional_fields_null() {
         min_size: None,
         max_size: None,
         max_age_secs: None,
+        action: 0,
+        category: None,
+        priority: None,
     })
     .await
     .unwrap();
@@ -763,6 +785,9 @@ async fn get_filters_returns_only_filters_for_requested_feed() {
         min_size: None,
         max_size: None,
         max_age_secs: None,
+        action: 0,
+        category: None,
+        priority: None,
     })
     .await
     .unwrap();
@@ -775,6 +800,9 @@ async fn get_filters_returns_only_filters_for_requested_feed() {
         min_size: None,
         max_size: None,
         max_age_secs: None,
+        action: 0,
+        category: None,
+        priority: None,
     })
     .await
     .unwrap();
@@ -821,6 +849,9 @@ async fn delete_filters_removes_all_filters_for_feed() {
             min_size: None,
             max_size: None,
             max_age_secs: None,
+            action: 0,
+            category: None,
+            priority: None,
         })
         .await
         .unwrap();
@@ -856,6 +887,9 @@ async fn delete_filters_does_not_affect_other_feeds_filters() {
         min_size: None,
         max_size: None,
         max_age_secs: None,
+        action: 0,
+        category: None,
+        priority: None,
     })
     .await
     .unwrap();
@@ -868,6 +902,9 @@ async fn delete_filters_does_not_affect_other_feeds_filters() {
         min_size: None,
         max_size: None,
         max_age_secs: None,
+        action: 0,
+        category: None,
+        priority: None,
     })
     .await
     .unwrap();
@@ -998,6 +1035,9 @@ async fn get_filters_returns_in_id_order() {
             min_size: None,
             max_size: None,
             max_age_secs: None,
+            action: 0,
+            category: None,
+            priority: None,
         })
         .await
         .unwrap();
@@ -1011,6 +1051,9 @@ async fn get_filters_returns_in_id_order() {
             min_size: None,
             max_size: None,
             max_age_secs: None,
+            action: 0,
+            category: None,
+            priority: None,
         })
         .await
         .unwrap();
@@ -1024,3 +1067,35 @@ async fn get_filters_returns_in_id_order() {
 
     db.close().await;
 }
+
+#[tokio::test]
+async fn rss_grabs_record_find_and_prune() {
+    let (db, _tmp) = setup_db().await;
+
+    let feed_id = insert_test_feed(&db, "Grabs", "https://grabs.com/rss").await;
+    db.record_rss_grab(feed_id, "show s01e01", Some(1000))
+        .await
+        .unwrap();
+    db.record_rss_grab(feed_id, "other show", None)
+        .await
+        .unwrap();
+
+    let grabs = db.find_rss_grabs("show s01e01", 0).await.unwrap();
+    assert_eq!(grabs.len(), 1);
+    assert_eq!(grabs[0].feed_id, feed_id);
+    assert_eq!(grabs[0].size, Some(1000));
+
+    let future = chrono::Utc::now().timestamp() + 60;
+    assert!(
+        db.find_rss_grabs("show s01e01", future)
+            .await
+            .unwrap()
+            .is_empty(),
+        "grabs older than `since` should not be returned"
+    );
+
+    assert_eq!(db.delete_rss_grabs_before(future).await.unwrap(), 2);
+    assert!(db.find_rss_grabs("other show", 0).await.unwrap().is_empty());
+
+    db.close().await;
+}
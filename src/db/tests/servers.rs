@@ -0,0 +1,37 @@
+use crate::db::*;
+use tempfile::NamedTempFile;
+
+#[tokio::test]
+async fn test_disabled_servers_expire_and_clear() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    db.set_server_disabled("user@primary:563", Some(200))
+        .await
+        .unwrap();
+    db.set_server_disabled("backup:119", None).await.unwrap();
+
+    let disabled = db.list_disabled_servers(100).await.unwrap();
+    let servers: Vec<_> = disabled
+        .iter()
+        .map(|row| (row.server.as_str(), row.disabled_until))
+        .collect();
+    assert_eq!(
+        servers,
+        [("backup:119", None), ("user@primary:563", Some(200))]
+    );
+
+    // Disabling again replaces the TTL
+    db.set_server_disabled("backup:119", Some(300))
+        .await
+        .unwrap();
+    let disabled = db.list_disabled_servers(250).await.unwrap();
+    assert_eq!(disabled.len(), 1, "expired entries are removed");
+    assert_eq!(disabled[0].server, "backup:119");
+    assert_eq!(disabled[0].disabled_until, Some(300));
+
+    db.clear_server_disabled("backup:119").await.unwrap();
+    assert!(db.list_disabled_servers(250).await.unwrap().is_empty());
+
+    db.close().await;
+}
@@ -78,3 +78,22 @@ async fn test_shutdown_state_unclean_detection() {
         db.close().await;
     }
 }
+
+#[tokio::test]
+async fn test_post_processing_paused_persists() {
+    let temp_file = NamedTempFile::new().unwrap();
+
+    {
+        let db = Database::new(temp_file.path()).await.unwrap();
+        assert!(!db.is_post_processing_paused().await.unwrap());
+        db.set_post_processing_paused_all(true).await.unwrap();
+        db.close().await;
+    }
+
+    let db = Database::new(temp_file.path()).await.unwrap();
+    assert!(db.is_post_processing_paused().await.unwrap());
+    db.set_post_processing_paused_all(false).await.unwrap();
+    assert!(!db.is_post_processing_paused().await.unwrap());
+
+    db.close().await;
+}
@@ -0,0 +1,41 @@
+use crate::db::*;
+use tempfile::NamedTempFile;
+
+#[tokio::test]
+async fn test_add_usage_accumulates_per_day_server_and_category() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_file.path()).await.unwrap();
+
+    db.add_usage("2024-01-14", "news.example.com", "tv", 100)
+        .await
+        .unwrap();
+    db.add_usage("2024-01-15", "news.example.com", "tv", 200)
+        .await
+        .unwrap();
+    db.add_usage("2024-01-15", "news.example.com", "tv", 50)
+        .await
+        .unwrap();
+    db.add_usage("2024-01-15", "backup.example.com", "", 25)
+        .await
+        .unwrap();
+
+    let rows = db.get_usage_since("2024-01-15").await.unwrap();
+    assert_eq!(rows.len(), 2, "rows before since_day are excluded");
+
+    let tv = rows
+        .iter()
+        .find(|r| r.server == "news.example.com")
+        .unwrap();
+    assert_eq!(tv.day, "2024-01-15");
+    assert_eq!(tv.category, "tv");
+    assert_eq!(tv.bytes, 250);
+
+    let uncategorized = rows
+        .iter()
+        .find(|r| r.server == "backup.example.com")
+        .unwrap();
+    assert_eq!(uncategorized.category, "");
+    assert_eq!(uncategorized.bytes, 25);
+
+    db.close().await;
+}
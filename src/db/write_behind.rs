@@ -0,0 +1,208 @@
+//! Write-behind buffer for download progress.
+//!
+//! Downloads report their progress and article results many times a second. Instead of
+//! a write for each report, they are queued here and written together in one
+//! transaction by [`Database::flush_pending_writes`]. A download's newest progress
+//! replaces any still queued, as does an article's newest status. Events for the
+//! journal are queued here too, and all of them are kept.
+
+use crate::error::DatabaseError;
+use crate::types::{DownloadId, EventEnvelope};
+use crate::{Error, Result};
+use std::collections::HashMap;
+
+use super::Database;
+use super::articles::{MAX_UPDATES_PER_BATCH, article_status_batch_query};
+
+/// Progress of a download, as written to its row
+#[derive(Clone, Copy, Debug)]
+struct QueuedProgress {
+    progress: f32,
+    speed_bps: u64,
+    downloaded_bytes: u64,
+}
+
+/// An event envelope waiting to be journaled
+#[derive(Clone, Debug)]
+struct QueuedEvent {
+    seq: u64,
+    event_type: String,
+    envelope: String,
+    created_at: i64,
+}
+
+/// Writes queued since the last flush
+#[derive(Debug, Default)]
+pub(super) struct PendingWrites {
+    /// Newest progress of each download
+    progress: HashMap<DownloadId, QueuedProgress>,
+    /// Newest status of each article
+    articles: HashMap<i64, i32>,
+    /// Events for the journal, oldest first
+    events: Vec<QueuedEvent>,
+    /// How many journaled events to keep
+    journal_size: usize,
+}
+
+impl PendingWrites {
+    fn is_empty(&self) -> bool {
+        self.progress.is_empty() && self.articles.is_empty() && self.events.is_empty()
+    }
+
+    /// Put back writes a failed flush took, unless newer ones were queued meanwhile
+    fn restore(&mut self, older: PendingWrites) {
+        for (id, progress) in older.progress {
+            self.progress.entry(id).or_insert(progress);
+        }
+        for (article_id, status) in older.articles {
+            self.articles.entry(article_id).or_insert(status);
+        }
+        let newer = std::mem::replace(&mut self.events, older.events);
+        self.events.extend(newer);
+        if self.journal_size == 0 {
+            self.journal_size = older.journal_size;
+        }
+    }
+}
+
+impl Database {
+    fn pending_writes(&self) -> std::sync::MutexGuard<'_, PendingWrites> {
+        self.pending_writes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue a progress update, replacing any not yet written for the download
+    ///
+    /// Written by the next [`flush_pending_writes`](Self::flush_pending_writes).
+    pub fn queue_progress(
+        &self,
+        id: DownloadId,
+        progress: f32,
+        speed_bps: u64,
+        downloaded_bytes: u64,
+    ) {
+        self.pending_writes().progress.insert(
+            id,
+            QueuedProgress {
+                progress,
+                speed_bps,
+                downloaded_bytes,
+            },
+        );
+    }
+
+    /// Queue article status changes, replacing any not yet written for the same articles
+    ///
+    /// Written by the next [`flush_pending_writes`](Self::flush_pending_writes).
+    pub fn queue_article_statuses(&self, updates: &[(i64, i32)]) {
+        self.pending_writes()
+            .articles
+            .extend(updates.iter().copied());
+    }
+
+    /// Queue an event for the journal, which keeps the newest `journal_size` events
+    ///
+    /// Written by the next [`flush_pending_writes`](Self::flush_pending_writes).
+    pub fn queue_journal_event(&self, envelope: &EventEnvelope, journal_size: usize) {
+        let json = match serde_json::to_string(envelope) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!(seq = envelope.seq, error = %e, "Failed to serialize event for the journal");
+                return;
+            }
+        };
+
+        let mut pending = self.pending_writes();
+        pending.journal_size = journal_size;
+        pending.events.push(QueuedEvent {
+            seq: envelope.seq,
+            event_type: envelope.event_type.clone(),
+            envelope: json,
+            created_at: envelope.timestamp.timestamp(),
+        });
+    }
+
+    /// Drop a download's queued progress, so a later flush can't overwrite newer values
+    pub(super) fn discard_queued_progress(&self, id: DownloadId) {
+        self.pending_writes().progress.remove(&id);
+    }
+
+    /// Write every queued update in a single transaction
+    ///
+    /// If the transaction fails the updates stay queued for the next flush.
+    pub async fn flush_pending_writes(&self) -> Result<()> {
+        let pending = std::mem::take(&mut *self.pending_writes());
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(e) = self.write_pending(&pending).await {
+            self.pending_writes().restore(pending);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    async fn write_pending(&self, pending: &PendingWrites) -> Result<()> {
+        let query_failed = |e: sqlx::Error| {
+            Error::Database(DatabaseError::QueryFailed(format!(
+                "Failed to flush queued progress: {}",
+                e
+            )))
+        };
+
+        let mut tx = self.writer.begin().await.map_err(query_failed)?;
+
+        for (id, queued) in &pending.progress {
+            sqlx::query(
+                "UPDATE downloads SET progress = ?, speed_bps = ?, downloaded_bytes = ? WHERE id = ?",
+            )
+            .bind(queued.progress)
+            .bind(queued.speed_bps as i64)
+            .bind(queued.downloaded_bytes as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(query_failed)?;
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let articles: Vec<(i64, i32)> = pending.articles.iter().map(|(&a, &s)| (a, s)).collect();
+        for chunk in articles.chunks(MAX_UPDATES_PER_BATCH) {
+            article_status_batch_query(chunk, now)
+                .build()
+                .execute(&mut *tx)
+                .await
+                .map_err(query_failed)?;
+        }
+
+        for event in &pending.events {
+            sqlx::query(
+                "INSERT OR REPLACE INTO event_journal (seq, event_type, envelope, created_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(event.seq as i64)
+            .bind(&event.event_type)
+            .bind(&event.envelope)
+            .bind(event.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(query_failed)?;
+        }
+        if !pending.events.is_empty() {
+            // Counted rather than derived from the newest seq, which skips progress events
+            sqlx::query(
+                "DELETE FROM event_journal WHERE seq NOT IN (SELECT seq FROM event_journal ORDER BY seq DESC LIMIT ?)",
+            )
+            .bind(pending.journal_size as i64)
+                .execute(&mut *tx)
+                .await
+                .map_err(query_failed)?;
+        }
+
+        tx.commit().await.map_err(query_failed)?;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,21 @@
 //! Obfuscated filename detection and handling
 //!
 //! Usenet releases often use obfuscated (random) filenames. This module provides
-//! heuristics to detect such filenames and utilities to determine proper names.
+//! heuristics to detect such filenames and utilities to determine proper names, and
+//! restores the names RAR volumes need to be extracted as a set. Post-processing
+//! renames files through a [`DeobfuscationPipeline`] of [`DeobfuscationStrategy`]s.
 
+mod strategy;
+
+pub use strategy::{
+    DeobfuscationContext, DeobfuscationPipeline, DeobfuscationStrategy, FileRename,
+    HashNameStrategy, NzbMetaStrategy, Par2NameStrategy, RarVolumeStrategy,
+};
+
+use crate::extraction::{RarVolume, read_rar_volume};
 use std::fs;
 use std::path::{Path, PathBuf};
+use tracing::debug;
 
 /// Minimum string length required to reliably detect high entropy.
 /// Shorter strings can appear random by chance.
@@ -263,6 +274,125 @@ pub fn find_largest_file(files: &[PathBuf]) -> Option<PathBuf> {
     largest_idx.map(|idx| files[idx].clone())
 }
 
+/// Rename obfuscated RAR volumes so the extractor can follow the volume chain
+///
+/// RAR extractors find the next volume by name (`name.part02.rar` after
+/// `name.part01.rar`, or `name.r00` after `name.rar` for old-style sets), which
+/// breaks when a post's volumes have random names. Files in `dir` that don't have a
+/// RAR volume name are identified as volumes by their headers, ordered by the volume
+/// number the headers record, and renamed after the first volume, in the naming
+/// scheme the set was created with.
+///
+/// Nothing is renamed unless the volumes found form exactly one complete set, since
+/// sets can't be told apart by their headers and a partial chain would be wrong.
+///
+/// # Returns
+///
+/// The `(old, new)` paths of the renamed volumes, empty if nothing was renamed
+pub fn rename_rar_volumes(dir: &Path) -> std::io::Result<Vec<(PathBuf, PathBuf)>> {
+    let renames = plan_rar_volume_renames(dir)?;
+    for (old, new) in &renames {
+        fs::rename(old, new)?;
+    }
+    Ok(renames)
+}
+
+/// The renames [`rename_rar_volumes`] would make, without making them
+fn plan_rar_volume_renames(dir: &Path) -> std::io::Result<Vec<(PathBuf, PathBuf)>> {
+    let mut volumes: Vec<(PathBuf, RarVolume)> = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() || has_rar_volume_name(&path) {
+            continue;
+        }
+        if let Some(volume) = read_rar_volume(&path).filter(|volume| volume.volume) {
+            volumes.push((path, volume));
+        }
+    }
+    if volumes.len() < 2 {
+        return Ok(Vec::new());
+    }
+
+    volumes.sort_by_key(|(_, volume)| volume.number);
+    let numbered_in_order = volumes
+        .iter()
+        .enumerate()
+        .all(|(index, (_, volume))| volume.number == Some(index as u64));
+    let new_numbering = volumes[0].1.new_numbering;
+    if !numbered_in_order
+        || volumes
+            .iter()
+            .any(|(_, volume)| volume.new_numbering != new_numbering)
+    {
+        debug!(
+            ?dir,
+            count = volumes.len(),
+            "RAR volumes don't form a single complete set, not renaming"
+        );
+        return Ok(Vec::new());
+    }
+
+    let Some(stem) = volumes[0]
+        .0
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+    else {
+        return Ok(Vec::new());
+    };
+    let Some(names) = rar_volume_names(&stem, volumes.len(), new_numbering) else {
+        debug!(
+            ?dir,
+            count = volumes.len(),
+            "too many RAR volumes for old-style names"
+        );
+        return Ok(Vec::new());
+    };
+    let renames: Vec<(PathBuf, PathBuf)> = volumes
+        .into_iter()
+        .zip(names)
+        .map(|((path, _), name)| (path, dir.join(name)))
+        .collect();
+    if let Some((_, taken)) = renames.iter().find(|(_, new)| new.exists()) {
+        debug!(?taken, "RAR volume name already taken, not renaming");
+        return Ok(Vec::new());
+    }
+    Ok(renames)
+}
+
+/// Whether a file already has a RAR volume name (`.rar`, `.r00`, `.r01`, ...)
+fn has_rar_volume_name(path: &Path) -> bool {
+    let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+        return false;
+    };
+    let ext = ext.to_ascii_lowercase();
+    ext == "rar"
+        || (ext.len() == 3 && ext.starts_with('r') && ext[1..].bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Volume file names for a set of `count` volumes
+///
+/// New-style names are `stem.part01.rar`, `stem.part02.rar`, ... with at least two
+/// digits. Old-style names are `stem.rar`, `stem.r00` ... `stem.r99`; larger old-style
+/// sets aren't supported and return `None`.
+fn rar_volume_names(stem: &str, count: usize, new_numbering: bool) -> Option<Vec<String>> {
+    if new_numbering {
+        let width = count.to_string().len().max(2);
+        return Some(
+            (1..=count)
+                .map(|number| format!("{}.part{:0width$}.rar", stem, number))
+                .collect(),
+        );
+    }
+    if count > 101 {
+        return None;
+    }
+    Some(
+        std::iter::once(format!("{}.rar", stem))
+            .chain((0..count - 1).map(|number| format!("{}.r{:02}", stem, number)))
+            .collect(),
+    )
+}
+
 // unwrap/expect are acceptable in tests for concise failure-on-error assertions
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 #[cfg(test)]
@@ -527,4 +657,130 @@ mod tests {
         let name = determine_final_name(job_name, None, &extracted);
         assert_eq!(name, "Movie.Name.2024");
     }
+
+    /// A RAR 5 volume: signature plus a main header with the volume number
+    fn rar5_volume(number: u8) -> Vec<u8> {
+        let mut data = b"Rar!\x1a\x07\x01\x00".to_vec();
+        // CRC32, header size, type 1 (main), header flags 0, archive flags
+        data.extend_from_slice(&[0, 0, 0, 0, 0x05, 0x01, 0x00]);
+        if number == 0 {
+            data.push(0x01);
+        } else {
+            data.extend_from_slice(&[0x03, number]);
+        }
+        data.extend_from_slice(b"compressed data");
+        data
+    }
+
+    /// An old-style RAR 4 volume, numbered in its end-of-archive header
+    fn rar4_volume(number: u16) -> Vec<u8> {
+        // MHD_VOLUME, plus MHD_FIRSTVOLUME on the first
+        let flags: u16 = if number == 0 { 0x0101 } else { 0x0001 };
+        let mut data = b"Rar!\x1a\x07\x00".to_vec();
+        data.extend_from_slice(&[0x00, 0x00, 0x73]);
+        data.extend_from_slice(&flags.to_le_bytes());
+        data.extend_from_slice(&[0x0d, 0x00, 0, 0, 0, 0, 0, 0]);
+        data.extend_from_slice(b"compressed data");
+
+        // End-of-archive header with EARC_VOLNUMBER
+        let mut end = vec![0x7b, 0x08, 0x00, 0x09, 0x00];
+        end.extend_from_slice(&number.to_le_bytes());
+        let mut crc = flate2::Crc::new();
+        crc.update(&end);
+        data.extend_from_slice(&((crc.sum() & 0xffff) as u16).to_le_bytes());
+        data.extend_from_slice(&end);
+        data
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_rename_rar_volumes_new_numbering() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("9c2f.bin"), rar5_volume(1)).unwrap();
+        fs::write(dir.join("4e7a"), rar5_volume(2)).unwrap();
+        fs::write(dir.join("b81d.bin"), rar5_volume(0)).unwrap();
+        fs::write(dir.join("movie.nfo"), b"info").unwrap();
+
+        let renamed = rename_rar_volumes(dir).unwrap();
+
+        assert_eq!(renamed.len(), 3);
+        assert_eq!(
+            file_names(dir),
+            vec![
+                "b81d.part01.rar",
+                "b81d.part02.rar",
+                "b81d.part03.rar",
+                "movie.nfo"
+            ]
+        );
+        assert_eq!(
+            fs::read(dir.join("b81d.part02.rar")).unwrap(),
+            rar5_volume(1)
+        );
+    }
+
+    #[test]
+    fn test_rename_rar_volumes_old_numbering() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("x1.bin"), rar4_volume(2)).unwrap();
+        fs::write(dir.join("x2.bin"), rar4_volume(0)).unwrap();
+        fs::write(dir.join("x3.bin"), rar4_volume(1)).unwrap();
+
+        rename_rar_volumes(dir).unwrap();
+
+        assert_eq!(file_names(dir), vec!["x2.r00", "x2.r01", "x2.rar"]);
+        assert_eq!(fs::read(dir.join("x2.r01")).unwrap(), rar4_volume(2));
+    }
+
+    #[test]
+    fn test_rename_rar_volumes_leaves_incomplete_or_mixed_sets() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+
+        // Missing the second volume
+        fs::write(dir.join("a.bin"), rar5_volume(0)).unwrap();
+        fs::write(dir.join("b.bin"), rar5_volume(2)).unwrap();
+        assert!(rename_rar_volumes(dir).unwrap().is_empty());
+
+        // Two first volumes: two sets that can't be told apart
+        fs::write(dir.join("c.bin"), rar5_volume(1)).unwrap();
+        fs::write(dir.join("d.bin"), rar5_volume(0)).unwrap();
+        assert!(rename_rar_volumes(dir).unwrap().is_empty());
+        assert_eq!(file_names(dir), vec!["a.bin", "b.bin", "c.bin", "d.bin"]);
+    }
+
+    #[test]
+    fn test_rename_rar_volumes_ignores_named_volumes_and_single_archives() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("show.part1.rar"), rar5_volume(0)).unwrap();
+        fs::write(dir.join("show.r00"), rar4_volume(1)).unwrap();
+        fs::write(dir.join("f00d.bin"), rar5_volume(1)).unwrap();
+
+        assert!(rename_rar_volumes(dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rar_volume_names_widths() {
+        assert_eq!(
+            rar_volume_names("set", 2, true).unwrap(),
+            vec!["set.part01.rar", "set.part02.rar"]
+        );
+        assert_eq!(
+            rar_volume_names("set", 120, true).unwrap()[119],
+            "set.part120.rar"
+        );
+        assert_eq!(rar_volume_names("set", 101, false).unwrap()[100], "set.r99");
+        assert!(rar_volume_names("set", 102, false).is_none());
+    }
 }
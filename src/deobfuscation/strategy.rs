@@ -0,0 +1,519 @@
+//! Renaming strategies run by post-processing, and the pipeline running them.
+
+use crate::config::{DeobfuscationConfig, PathSanitizationConfig};
+use crate::error::Result;
+use crate::parity::{compute_16k_md5, parse_par2_file_entries};
+use crate::types::DownloadId;
+use crate::utils::sanitize_filename;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tracing::{debug, info, warn};
+
+use super::{is_obfuscated, plan_rar_volume_renames};
+
+/// What a strategy knows about the download whose folder it looks at
+#[derive(Debug, Clone)]
+pub struct DeobfuscationContext<'a> {
+    /// Download being post-processed
+    pub download_id: DownloadId,
+    /// Folder holding the downloaded files
+    pub dir: &'a Path,
+    /// NZB file name without the `.nzb` extension
+    pub job_name: &'a str,
+    /// Title from the NZB's `<meta type="name">`, if any
+    pub nzb_meta_name: Option<&'a str>,
+    /// Names shorter than this (without extension) are never considered obfuscated
+    pub min_length: usize,
+}
+
+/// A file a strategy wants renamed, within the download folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRename {
+    /// Current path of the file
+    pub path: PathBuf,
+    /// New file name, in the same folder
+    pub new_name: String,
+}
+
+/// One way of finding better names for a download's files
+///
+/// Strategies run in pipeline order before archives are extracted, each seeing the
+/// folder as the strategies before it left it. A strategy only proposes renames; the
+/// pipeline carries them out, skipping any whose file is outside the folder, whose
+/// new name isn't a plain file name or is already taken.
+///
+/// Add one with
+/// [`UsenetDownloader::insert_deobfuscation_strategy`](crate::UsenetDownloader::insert_deobfuscation_strategy).
+#[async_trait]
+pub trait DeobfuscationStrategy: Send + Sync {
+    /// Name of the strategy, used in logs and matched against the `deobfuscation` flags
+    fn name(&self) -> &str;
+
+    /// Files in `ctx.dir` to rename
+    async fn renames(&self, ctx: &DeobfuscationContext<'_>) -> Result<Vec<FileRename>>;
+}
+
+/// Ordered deobfuscation strategies, shared by every clone
+#[derive(Clone)]
+pub struct DeobfuscationPipeline {
+    strategies: Arc<RwLock<Vec<Arc<dyn DeobfuscationStrategy>>>>,
+}
+
+impl Default for DeobfuscationPipeline {
+    /// The built-in strategies: PAR2 names, RAR volumes, NZB file name, NZB meta title
+    fn default() -> Self {
+        Self::from_strategies(vec![
+            Arc::new(Par2NameStrategy),
+            Arc::new(RarVolumeStrategy),
+            Arc::new(HashNameStrategy),
+            Arc::new(NzbMetaStrategy),
+        ])
+    }
+}
+
+impl std::fmt::Debug for DeobfuscationPipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl DeobfuscationPipeline {
+    /// A pipeline running `strategies` in order
+    pub fn from_strategies(strategies: Vec<Arc<dyn DeobfuscationStrategy>>) -> Self {
+        Self {
+            strategies: Arc::new(RwLock::new(strategies)),
+        }
+    }
+
+    /// Insert a strategy at `index`, or at the end if `index` is past it
+    pub fn insert(&self, index: usize, strategy: Arc<dyn DeobfuscationStrategy>) {
+        let mut strategies = self.strategies.write().unwrap_or_else(|e| e.into_inner());
+        let index = index.min(strategies.len());
+        strategies.insert(index, strategy);
+    }
+
+    /// Names of the strategies, in the order they run
+    pub fn names(&self) -> Vec<String> {
+        self.snapshot()
+            .iter()
+            .map(|strategy| strategy.name().to_string())
+            .collect()
+    }
+
+    /// Run the strategies `config` enables, returning the `(old, new)` paths renamed
+    ///
+    /// A failing strategy or rename is logged and skipped.
+    pub async fn run(
+        &self,
+        ctx: &DeobfuscationContext<'_>,
+        config: &DeobfuscationConfig,
+    ) -> Vec<(PathBuf, PathBuf)> {
+        let mut renamed = Vec::new();
+        for strategy in self.snapshot() {
+            if !config.strategy_enabled(strategy.name()) {
+                continue;
+            }
+            let renames = match strategy.renames(ctx).await {
+                Ok(renames) => renames,
+                Err(e) => {
+                    warn!(
+                        download_id = ctx.download_id.0,
+                        strategy = strategy.name(),
+                        error = %e,
+                        "deobfuscation strategy failed"
+                    );
+                    continue;
+                }
+            };
+
+            let before = renamed.len();
+            for rename in renames {
+                if let Some(paths) = apply_rename(ctx, strategy.name(), rename) {
+                    renamed.push(paths);
+                }
+            }
+            if renamed.len() > before {
+                info!(
+                    download_id = ctx.download_id.0,
+                    strategy = strategy.name(),
+                    count = renamed.len() - before,
+                    "renamed {} obfuscated file(s)",
+                    renamed.len() - before
+                );
+            }
+        }
+        renamed
+    }
+
+    /// The strategies, cloned so no lock is held across an await
+    fn snapshot(&self) -> Vec<Arc<dyn DeobfuscationStrategy>> {
+        self.strategies
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+/// Carry out a proposed rename if it stays inside the folder and overwrites nothing
+fn apply_rename(
+    ctx: &DeobfuscationContext<'_>,
+    strategy: &str,
+    rename: FileRename,
+) -> Option<(PathBuf, PathBuf)> {
+    let plain_name = Path::new(&rename.new_name).file_name() == Some(rename.new_name.as_ref());
+    if rename.path.parent() != Some(ctx.dir) || !plain_name || !rename.path.is_file() {
+        warn!(
+            download_id = ctx.download_id.0,
+            strategy,
+            ?rename,
+            "ignoring rename outside the download folder"
+        );
+        return None;
+    }
+    let new_path = ctx.dir.join(&rename.new_name);
+    if new_path == rename.path {
+        return None;
+    }
+    if new_path.exists() {
+        debug!(?new_path, strategy, "name already taken, not renaming");
+        return None;
+    }
+    match fs::rename(&rename.path, &new_path) {
+        Ok(()) => Some((rename.path, new_path)),
+        Err(e) => {
+            warn!(
+                download_id = ctx.download_id.0,
+                strategy,
+                path = ?rename.path,
+                error = %e,
+                "failed to rename obfuscated file"
+            );
+            None
+        }
+    }
+}
+
+/// Restores the file names recorded in the download's PAR2 files
+///
+/// Each file is matched by the MD5 of its first 16 KiB against the PAR2 File
+/// Description packets, as DirectRename does while downloading.
+pub struct Par2NameStrategy;
+
+#[async_trait]
+impl DeobfuscationStrategy for Par2NameStrategy {
+    fn name(&self) -> &str {
+        "par2_names"
+    }
+
+    async fn renames(&self, ctx: &DeobfuscationContext<'_>) -> Result<Vec<FileRename>> {
+        let (par2_files, other_files): (Vec<_>, Vec<_>) = files_in(ctx.dir)?
+            .into_iter()
+            .partition(|path| is_par2(path));
+
+        let mut names = HashMap::new();
+        for par2 in &par2_files {
+            match parse_par2_file_entries(par2) {
+                Ok(entries) => {
+                    names.extend(entries.into_iter().map(|e| (e.hash_16k, e.filename)));
+                }
+                Err(e) => debug!(?par2, error = %e, "failed to read PAR2 file names"),
+            }
+        }
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut renames = Vec::new();
+        for path in other_files {
+            let Ok(hash) = compute_16k_md5(&path) else {
+                continue;
+            };
+            let Some(name) = names.get(&hash) else {
+                continue;
+            };
+            if path.file_name().and_then(|n| n.to_str()) != Some(name.as_str()) {
+                renames.push(FileRename {
+                    path,
+                    new_name: name.clone(),
+                });
+            }
+        }
+        Ok(renames)
+    }
+}
+
+/// Renames obfuscated RAR volumes so the extractor can follow the set
+///
+/// See [`rename_rar_volumes`](super::rename_rar_volumes).
+pub struct RarVolumeStrategy;
+
+#[async_trait]
+impl DeobfuscationStrategy for RarVolumeStrategy {
+    fn name(&self) -> &str {
+        "rar_volumes"
+    }
+
+    async fn renames(&self, ctx: &DeobfuscationContext<'_>) -> Result<Vec<FileRename>> {
+        Ok(plan_rar_volume_renames(ctx.dir)?
+            .into_iter()
+            .filter_map(|(path, new_path)| {
+                let new_name = new_path.file_name()?.to_str()?.to_string();
+                Some(FileRename { path, new_name })
+            })
+            .collect())
+    }
+}
+
+/// Renames the largest obfuscated file after the NZB file name, if that isn't obfuscated
+///
+/// Obfuscation is detected with [`is_obfuscated`]. Archives and PAR2 files are left to
+/// the other strategies, and the extension is kept.
+pub struct HashNameStrategy;
+
+#[async_trait]
+impl DeobfuscationStrategy for HashNameStrategy {
+    fn name(&self) -> &str {
+        "hash_names"
+    }
+
+    async fn renames(&self, ctx: &DeobfuscationContext<'_>) -> Result<Vec<FileRename>> {
+        rename_largest_obfuscated(ctx, ctx.job_name)
+    }
+}
+
+/// Renames the largest obfuscated file after the NZB meta title, if that isn't obfuscated
+///
+/// Picks files the same way as [`HashNameStrategy`], and is its fallback: it only
+/// renames when the NZB file name is obfuscated too.
+pub struct NzbMetaStrategy;
+
+#[async_trait]
+impl DeobfuscationStrategy for NzbMetaStrategy {
+    fn name(&self) -> &str {
+        "nzb_meta"
+    }
+
+    async fn renames(&self, ctx: &DeobfuscationContext<'_>) -> Result<Vec<FileRename>> {
+        match ctx.nzb_meta_name {
+            Some(name) if is_obfuscated(ctx.job_name) => rename_largest_obfuscated(ctx, name),
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Rename the largest obfuscated non-archive file in the folder to `name`
+fn rename_largest_obfuscated(
+    ctx: &DeobfuscationContext<'_>,
+    name: &str,
+) -> Result<Vec<FileRename>> {
+    let name = sanitize_filename(name.trim(), &PathSanitizationConfig::default());
+    if name.is_empty() || is_obfuscated(&name) {
+        return Ok(Vec::new());
+    }
+
+    let largest = files_in(ctx.dir)?
+        .into_iter()
+        .filter(|path| is_obfuscated_name(path, ctx.min_length))
+        .filter(|path| !is_par2(path) && crate::extraction::detect_archive_type(path).is_none())
+        .filter_map(|path| Some((fs::metadata(&path).ok()?.len(), path)))
+        .max_by_key(|(size, _)| *size);
+    let Some((_, path)) = largest else {
+        return Ok(Vec::new());
+    };
+
+    let new_name = match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => format!("{}.{}", name, ext),
+        None => name,
+    };
+    Ok(vec![FileRename { path, new_name }])
+}
+
+/// Whether a file's name, without extension, is long enough and looks obfuscated
+fn is_obfuscated_name(path: &Path, min_length: usize) -> bool {
+    let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+        return false;
+    };
+    stem.len() >= min_length && is_obfuscated(stem)
+}
+
+fn is_par2(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("par2"))
+}
+
+/// Files directly in `dir`
+fn files_in(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+// unwrap/expect are acceptable in tests for concise failure-on-error assertions
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const OBFUSCATED: &str = "a3f8b2c9d1e5f7a4b6c8d0e2f4a6b8c0";
+
+    fn context<'a>(dir: &'a Path, job_name: &'a str) -> DeobfuscationContext<'a> {
+        DeobfuscationContext {
+            download_id: DownloadId(1),
+            dir,
+            job_name,
+            nzb_meta_name: Some("Meta.Title.2024"),
+            min_length: 12,
+        }
+    }
+
+    /// A PAR2 file with one File Description packet naming `filename`
+    fn par2_file(filename: &str, hash_16k: [u8; 16]) -> Vec<u8> {
+        let mut name = filename.as_bytes().to_vec();
+        name.resize((name.len() + 3) & !3, 0);
+        let mut packet = b"PAR2\0PKT".to_vec();
+        packet.extend_from_slice(&((64 + 56 + name.len()) as u64).to_le_bytes());
+        packet.extend_from_slice(&[0u8; 32]);
+        packet.extend_from_slice(b"PAR 2.0\0FileDesc");
+        packet.extend_from_slice(&[0u8; 32]);
+        packet.extend_from_slice(&hash_16k);
+        packet.extend_from_slice(&1024u64.to_le_bytes());
+        packet.extend_from_slice(&name);
+        packet
+    }
+
+    #[tokio::test]
+    async fn builtin_strategies_restore_par2_names_then_name_after_the_job() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let episode = dir.join(format!("{}.bin", OBFUSCATED));
+        std::fs::write(&episode, b"episode data").unwrap();
+        let hash = compute_16k_md5(&episode).unwrap();
+        std::fs::write(dir.join("index.par2"), par2_file("Show.S01E01.mkv", hash)).unwrap();
+        let movie = dir.join("550e8400-e29b-41d4-a716-446655440000.mkv");
+        std::fs::write(&movie, vec![0u8; 4096]).unwrap();
+        std::fs::write(dir.join("b9c8d7e6f5a4b3c2d1e0.nfo"), b"info").unwrap();
+
+        let renamed = DeobfuscationPipeline::default()
+            .run(
+                &context(dir, "Some.Movie.2024"),
+                &DeobfuscationConfig::default(),
+            )
+            .await;
+
+        assert_eq!(
+            renamed,
+            vec![
+                (episode, dir.join("Show.S01E01.mkv")),
+                (movie, dir.join("Some.Movie.2024.mkv")),
+            ]
+        );
+        assert!(dir.join("b9c8d7e6f5a4b3c2d1e0.nfo").exists());
+    }
+
+    #[tokio::test]
+    async fn nzb_meta_title_is_used_when_the_job_name_is_obfuscated() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let movie = dir.join(format!("{}.mkv", OBFUSCATED));
+        std::fs::write(&movie, b"movie").unwrap();
+
+        let renamed = DeobfuscationPipeline::default()
+            .run(&context(dir, OBFUSCATED), &DeobfuscationConfig::default())
+            .await;
+
+        assert_eq!(renamed, vec![(movie, dir.join("Meta.Title.2024.mkv"))]);
+    }
+
+    #[tokio::test]
+    async fn disabled_strategies_are_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let movie = dir.join(format!("{}.mkv", OBFUSCATED));
+        std::fs::write(&movie, b"movie").unwrap();
+        let config = DeobfuscationConfig {
+            hash_names: false,
+            nzb_meta: false,
+            ..Default::default()
+        };
+
+        let renamed = DeobfuscationPipeline::default()
+            .run(&context(dir, "Some.Movie.2024"), &config)
+            .await;
+
+        assert!(renamed.is_empty());
+        assert!(movie.exists());
+    }
+
+    /// Proposes renaming every file to a fixed name
+    struct FixedName(&'static str);
+
+    #[async_trait]
+    impl DeobfuscationStrategy for FixedName {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn renames(&self, ctx: &DeobfuscationContext<'_>) -> Result<Vec<FileRename>> {
+            Ok(files_in(ctx.dir)?
+                .into_iter()
+                .map(|path| FileRename {
+                    path,
+                    new_name: self.0.to_string(),
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn inserted_strategies_run_in_position_and_cannot_leave_the_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("download");
+        std::fs::create_dir(&dir).unwrap();
+        let movie = dir.join(format!("{}.mkv", OBFUSCATED));
+        std::fs::write(&movie, b"movie").unwrap();
+
+        let escaping =
+            DeobfuscationPipeline::from_strategies(vec![Arc::new(FixedName("../escaped.mkv"))]);
+        assert!(
+            escaping
+                .run(
+                    &context(&dir, "Some.Movie.2024"),
+                    &DeobfuscationConfig::default()
+                )
+                .await
+                .is_empty()
+        );
+        assert!(movie.exists());
+
+        let pipeline = DeobfuscationPipeline::default();
+        pipeline.insert(0, Arc::new(FixedName("Custom.Name.mkv")));
+        assert_eq!(
+            pipeline.names(),
+            [
+                "fixed",
+                "par2_names",
+                "rar_volumes",
+                "hash_names",
+                "nzb_meta"
+            ]
+        );
+
+        let renamed = pipeline
+            .run(
+                &context(&dir, "Some.Movie.2024"),
+                &DeobfuscationConfig::default(),
+            )
+            .await;
+
+        assert_eq!(renamed, vec![(movie, dir.join("Custom.Name.mkv"))]);
+    }
+}
@@ -11,9 +11,6 @@ pub(crate) const ARTICLE_CHANNEL_BUFFER: usize = 500;
 /// Interval between progress update emissions
 const PROGRESS_UPDATE_INTERVAL: Duration = Duration::from_millis(500);
 
-/// Batch size threshold for flushing article status updates to the database
-const ARTICLE_BATCH_SIZE: usize = 100;
-
 /// Parameters for spawning a progress reporter background task
 pub(crate) struct ProgressReporterParams {
     /// Download ID
@@ -31,7 +28,7 @@ pub(crate) struct ProgressReporterParams {
     /// Atomic counter for individually-failed articles
     pub failed_articles: Arc<AtomicU64>,
     /// Event broadcast sender
-    pub event_tx: tokio::sync::broadcast::Sender<Event>,
+    pub event_tx: crate::event_bus::EventSender,
     /// Database handle
     pub db: Arc<crate::db::Database>,
     /// Cancellation token
@@ -86,14 +83,8 @@ pub(crate) fn spawn_progress_reporter(
                         None
                     };
 
-                    if let Err(e) = db.update_progress(
-                        id,
-                        progress_percent,
-                        speed_bps,
-                        current_bytes,
-                    ).await {
-                        tracing::error!(download_id = id.0, error = %e, "Failed to update progress");
-                    }
+                    // Written to the database with the next flush of queued writes
+                    db.queue_progress(id, progress_percent, speed_bps, current_bytes);
 
                     event_tx
                         .send(Event::Downloading {
@@ -114,7 +105,12 @@ pub(crate) fn spawn_progress_reporter(
     })
 }
 
-/// Spawn a background task that batches article status updates for SQLite efficiency.
+/// Spawn a background task that queues article status updates in the database's
+/// write-behind buffer.
+///
+/// Queued updates are written with the next periodic flush (see
+/// [`spawn_write_flusher`]). When the channel closes or the download is cancelled, the
+/// buffer is flushed at once, so the download's results are on disk before it's finalized.
 pub(crate) fn spawn_batch_updater(
     id: DownloadId,
     db: Arc<crate::db::Database>,
@@ -122,51 +118,49 @@ pub(crate) fn spawn_batch_updater(
     cancel_token: tokio_util::sync::CancellationToken,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        let mut buffer = Vec::with_capacity(ARTICLE_BATCH_SIZE);
-        let mut interval = tokio::time::interval(Duration::from_millis(500));
-        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-
         loop {
             tokio::select! {
                 msg = batch_rx.recv() => {
-                    let Some((article_id, status)) = msg else {
+                    let Some(update) = msg else {
                         // Channel closed — flush remaining and exit
                         break;
                     };
-                    buffer.push((article_id, status));
-
-                    if buffer.len() >= ARTICLE_BATCH_SIZE {
-                        if let Err(e) = db.update_articles_status_batch(&buffer).await {
-                            tracing::error!(download_id = id.0, batch_size = buffer.len(), error = %e, "Failed to batch update article statuses");
-                        }
-                        buffer.clear();
-                    }
-                }
-                _ = interval.tick() => {
-                    if !buffer.is_empty() {
-                        if let Err(e) = db.update_articles_status_batch(&buffer).await {
-                            tracing::error!(download_id = id.0, batch_size = buffer.len(), error = %e, "Failed to batch update article statuses");
-                        }
-                        buffer.clear();
-                    }
+                    db.queue_article_statuses(&[update]);
                 }
                 _ = cancel_token.cancelled() => {
-                    if !buffer.is_empty() && let Err(e) = db.update_articles_status_batch(&buffer).await {
-                        tracing::error!(download_id = id.0, batch_size = buffer.len(), error = %e, "Failed to batch update article statuses on cancellation");
-                    }
                     break;
                 }
             }
         }
 
-        // Final flush when task ends (channel closed)
-        while let Ok((article_id, status)) = batch_rx.try_recv() {
-            buffer.push((article_id, status));
+        // Final flush when task ends
+        while let Ok(update) = batch_rx.try_recv() {
+            db.queue_article_statuses(&[update]);
         }
-        if !buffer.is_empty()
-            && let Err(e) = db.update_articles_status_batch(&buffer).await
-        {
-            tracing::error!(download_id = id.0, batch_size = buffer.len(), error = %e, "Failed to flush remaining article statuses");
+        if let Err(e) = db.flush_pending_writes().await {
+            tracing::error!(download_id = id.0, error = %e, "Failed to flush remaining article statuses");
+        }
+    })
+}
+
+/// Spawn a background task that writes the database's queued progress every `interval`
+///
+/// Exits once the database has been dropped.
+pub(crate) fn spawn_write_flusher(
+    db: std::sync::Weak<crate::db::Database>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            let Some(db) = db.upgrade() else {
+                break;
+            };
+            if let Err(e) = db.flush_pending_writes().await {
+                tracing::warn!(error = %e, "Failed to flush queued progress");
+            }
         }
     })
 }
@@ -240,7 +234,7 @@ mod tests {
     #[tokio::test]
     async fn progress_reporter_emits_downloading_events() {
         let (db, download_id, _temp) = setup_db().await;
-        let (event_tx, mut event_rx) = tokio::sync::broadcast::channel(100);
+        let (event_tx, mut event_rx) = crate::event_bus::channel(100);
         let cancel_token = tokio_util::sync::CancellationToken::new();
 
         let _handle = spawn_progress_reporter(ProgressReporterParams {
@@ -290,7 +284,7 @@ mod tests {
     #[tokio::test]
     async fn progress_reporter_uses_byte_percentage_when_size_known() {
         let (db, download_id, _temp) = setup_db().await;
-        let (event_tx, mut event_rx) = tokio::sync::broadcast::channel(100);
+        let (event_tx, mut event_rx) = crate::event_bus::channel(100);
         let cancel_token = tokio_util::sync::CancellationToken::new();
 
         let _handle = spawn_progress_reporter(ProgressReporterParams {
@@ -327,7 +321,7 @@ mod tests {
     #[tokio::test]
     async fn progress_reporter_uses_article_percentage_when_size_zero() {
         let (db, download_id, _temp) = setup_db().await;
-        let (event_tx, mut event_rx) = tokio::sync::broadcast::channel(100);
+        let (event_tx, mut event_rx) = crate::event_bus::channel(100);
         let cancel_token = tokio_util::sync::CancellationToken::new();
 
         let _handle = spawn_progress_reporter(ProgressReporterParams {
@@ -364,7 +358,7 @@ mod tests {
     #[tokio::test]
     async fn progress_reporter_stops_on_cancellation() {
         let (db, download_id, _temp) = setup_db().await;
-        let (event_tx, _rx) = tokio::sync::broadcast::channel(100);
+        let (event_tx, _rx) = crate::event_bus::channel(100);
         let cancel_token = tokio_util::sync::CancellationToken::new();
 
         let handle = spawn_progress_reporter(ProgressReporterParams {
@@ -393,29 +387,32 @@ mod tests {
     // ── Batch updater tests ─────────────────────────────────────────────
 
     #[tokio::test]
-    async fn batch_updater_flushes_at_size_threshold() {
-        let (db, download_id, article_ids, _temp) = setup_db_with_articles(100).await;
+    async fn batch_updater_queues_updates_until_flushed() {
+        let (db, download_id, article_ids, _temp) = setup_db_with_articles(150).await;
         let cancel_token = tokio_util::sync::CancellationToken::new();
         let (batch_tx, batch_rx) = tokio::sync::mpsc::channel(500);
 
         let handle = spawn_batch_updater(download_id, db.clone(), batch_rx, cancel_token.clone());
 
-        // Send exactly ARTICLE_BATCH_SIZE (100) updates
         for &article_id in &article_ids {
             batch_tx
                 .send((article_id, article_status::DOWNLOADED))
                 .await
                 .unwrap();
         }
-
-        // Give the updater a moment to flush (threshold hit → immediate flush)
         tokio::time::sleep(Duration::from_millis(200)).await;
 
+        // Queued, not written yet
+        let pending = db.get_pending_articles(download_id).await.unwrap();
+        assert_eq!(pending.len(), 150);
+
+        // One flush writes them all, across several statements
+        db.flush_pending_writes().await.unwrap();
         let pending = db.get_pending_articles(download_id).await.unwrap();
         assert_eq!(
             pending.len(),
             0,
-            "All 100 articles should be flushed at batch threshold, but {} still pending",
+            "All 150 articles should be written by the flush, but {} still pending",
             pending.len()
         );
 
@@ -424,34 +421,28 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn batch_updater_flushes_on_timer() {
-        let (db, download_id, article_ids, _temp) = setup_db_with_articles(10).await;
-        let cancel_token = tokio_util::sync::CancellationToken::new();
-        let (batch_tx, batch_rx) = tokio::sync::mpsc::channel(500);
-
-        let handle = spawn_batch_updater(download_id, db.clone(), batch_rx, cancel_token.clone());
-
-        // Send fewer than ARTICLE_BATCH_SIZE updates
-        for &article_id in &article_ids {
-            batch_tx
-                .send((article_id, article_status::DOWNLOADED))
-                .await
-                .unwrap();
-        }
-
-        // Wait for timer flush (interval is 500ms, add margin)
-        tokio::time::sleep(Duration::from_millis(1500)).await;
-
+    async fn write_flusher_writes_queued_progress_periodically() {
+        let (db, download_id, article_ids, _temp) = setup_db_with_articles(3).await;
+        db.queue_progress(download_id, 10.0, 100, 1000);
+        db.queue_progress(download_id, 40.0, 200, 4000);
+        db.queue_article_statuses(&[(article_ids[0], article_status::DOWNLOADED)]);
+
+        let handle = spawn_write_flusher(Arc::downgrade(&db), Duration::from_millis(50));
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        // Only the newest progress is written
+        let download = db.get_download(download_id).await.unwrap().unwrap();
+        assert_eq!(download.downloaded_bytes, 4000);
+        assert_eq!(download.speed_bps, 200);
         let pending = db.get_pending_articles(download_id).await.unwrap();
-        assert_eq!(
-            pending.len(),
-            0,
-            "All 10 articles should be flushed by timer, but {} still pending",
-            pending.len()
-        );
+        assert_eq!(pending.len(), 2);
 
-        cancel_token.cancel();
-        handle.await.unwrap();
+        // Stops once the database is gone
+        drop(db);
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("flusher should stop after the database is dropped")
+            .unwrap();
     }
 
     #[tokio::test]
@@ -473,8 +464,8 @@ mod tests {
         // Drop sender to close the channel
         drop(batch_tx);
 
-        // Wait for the interval timer to flush remaining items (1s interval + margin)
-        tokio::time::sleep(Duration::from_millis(1500)).await;
+        // Wait for the updater to flush remaining items as it exits
+        tokio::time::sleep(Duration::from_millis(200)).await;
 
         // Verify all 5 articles were flushed
         let pending = db.get_pending_articles(download_id).await.unwrap();
@@ -485,7 +476,6 @@ mod tests {
             pending.len()
         );
 
-        // Cancel to stop the task (loop continues on interval after channel close)
         cancel_token.cancel();
         handle.await.unwrap();
     }
@@ -0,0 +1,207 @@
+//! Backup and restore of the full application state.
+//!
+//! A backup is a zip archive holding a manifest, the effective configuration (including
+//! runtime categories and schedule rules) and a snapshot of the database, which carries
+//! the queue, history and RSS feeds. Restoring replaces the database contents and the
+//! runtime-mutable settings in place; everything else in the archived configuration is
+//! returned to the caller to persist for the next start.
+
+use crate::config::Config;
+use crate::db::{Database, LATEST_SCHEMA_VERSION};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use super::UsenetDownloader;
+
+/// Archive layout version, bumped on incompatible changes
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const CONFIG_ENTRY: &str = "config.json";
+const DATABASE_ENTRY: &str = "usenet-dl.db";
+
+/// Describes what a backup archive contains
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupManifest {
+    format_version: u32,
+    /// Crate version that wrote the archive
+    app_version: String,
+    /// Database schema version of the snapshot
+    schema_version: i64,
+    /// RFC 3339 timestamp
+    created_at: String,
+}
+
+impl UsenetDownloader {
+    /// Write a backup archive of the configuration and database to `path`
+    ///
+    /// The archive contains passwords and API keys from the configuration; store it
+    /// accordingly. Safe to call while downloads are running.
+    pub async fn export_backup(&self, path: &Path) -> Result<()> {
+        let scratch = self.backup_scratch_dir().await?;
+        let result = self.write_backup(path, &scratch).await;
+        let _ = tokio::fs::remove_dir_all(&scratch).await;
+        result
+    }
+
+    /// Restore the state saved by [`export_backup`] from the archive at `path`
+    ///
+    /// Replaces the queue, history, RSS feeds and statistics with the archived ones,
+    /// and applies the archived categories and schedule rules. Refused with
+    /// [`Error::Busy`] while downloads or post-processing are running.
+    ///
+    /// Returns the archived configuration. Settings that can't change at runtime
+    /// (servers, directories, API) only take effect once the caller saves it and
+    /// restarts with it.
+    ///
+    /// [`export_backup`]: UsenetDownloader::export_backup
+    pub async fn import_backup(&self, path: &Path) -> Result<Config> {
+        self.ensure_writable()?;
+
+        if !self.queue_state.active_downloads.lock().await.is_empty()
+            || self
+                .queue_state
+                .post_processing_active
+                .load(Ordering::SeqCst)
+                > 0
+        {
+            return Err(Error::Busy(
+                "pause or drain active downloads before restoring a backup".to_string(),
+            ));
+        }
+
+        let scratch = self.backup_scratch_dir().await?;
+        let result = self.restore_backup(path, &scratch).await;
+        let _ = tokio::fs::remove_dir_all(&scratch).await;
+        result
+    }
+
+    /// Create an empty working directory for backup files under the temp directory
+    pub(crate) async fn backup_scratch_dir(&self) -> Result<PathBuf> {
+        let dir = self.config.download.temp_dir.join(format!(
+            ".backup-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        tokio::fs::create_dir_all(&dir).await?;
+        Ok(dir)
+    }
+
+    async fn write_backup(&self, path: &Path, scratch: &Path) -> Result<()> {
+        let db_path = scratch.join(DATABASE_ENTRY);
+        self.db.snapshot_to(&db_path).await?;
+
+        let mut config = (*self.config).clone();
+        config.persistence.categories = self.get_categories().await;
+        config.persistence.schedule_rules = self.get_schedule_rules().await;
+        let config_json = serde_json::to_vec_pretty(&config)?;
+
+        let manifest_json = serde_json::to_vec_pretty(&BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: LATEST_SCHEMA_VERSION,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        })?;
+
+        let archive_path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let zip_err = |e: zip::result::ZipError| Error::Io(std::io::Error::other(e));
+            let options = zip::write::FileOptions::default();
+
+            let mut writer = zip::ZipWriter::new(std::fs::File::create(&archive_path)?);
+            writer
+                .start_file(MANIFEST_ENTRY, options)
+                .map_err(zip_err)?;
+            writer.write_all(&manifest_json)?;
+            writer.start_file(CONFIG_ENTRY, options).map_err(zip_err)?;
+            writer.write_all(&config_json)?;
+            writer
+                .start_file(DATABASE_ENTRY, options)
+                .map_err(zip_err)?;
+            std::io::copy(&mut std::fs::File::open(&db_path)?, &mut writer)?;
+            writer.finish().map_err(zip_err)?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| Error::Other(format!("Backup task panicked: {}", e)))??;
+
+        tracing::info!(path = %path.display(), "Exported backup");
+        Ok(())
+    }
+
+    async fn restore_backup(&self, path: &Path, scratch: &Path) -> Result<Config> {
+        let db_path = scratch.join(DATABASE_ENTRY);
+        let (manifest, config) = {
+            let path = path.to_path_buf();
+            let db_path = db_path.clone();
+            tokio::task::spawn_blocking(move || read_backup(&path, &db_path))
+                .await
+                .map_err(|e| Error::Other(format!("Restore task panicked: {}", e)))??
+        };
+
+        // Bring an older snapshot up to the current schema before copying from it
+        Database::new(&db_path).await?.close().await;
+        self.db.restore_from(&db_path).await?;
+
+        *self.runtime_config.categories.write().await = config.persistence.categories.clone();
+        *self.runtime_config.schedule_rules.write().await =
+            config.persistence.schedule_rules.clone();
+
+        // The in-memory queue still refers to the replaced downloads
+        self.queue_state.queue.lock().await.clear();
+        self.restore_queue().await?;
+
+        tracing::info!(
+            path = %path.display(),
+            created_at = %manifest.created_at,
+            app_version = %manifest.app_version,
+            "Restored backup"
+        );
+        Ok(config)
+    }
+}
+
+/// Validate the archive at `path`, extract its database to `db_path` and parse the rest
+fn read_backup(path: &Path, db_path: &Path) -> Result<(BackupManifest, Config)> {
+    let invalid = |e: zip::result::ZipError| Error::InvalidBackup(e.to_string());
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?).map_err(invalid)?;
+
+    let manifest: BackupManifest = {
+        let mut json = String::new();
+        archive
+            .by_name(MANIFEST_ENTRY)
+            .map_err(invalid)?
+            .read_to_string(&mut json)?;
+        serde_json::from_str(&json)
+            .map_err(|e| Error::InvalidBackup(format!("malformed manifest: {}", e)))?
+    };
+    if manifest.format_version != BACKUP_FORMAT_VERSION {
+        return Err(Error::InvalidBackup(format!(
+            "unsupported backup format version {}",
+            manifest.format_version
+        )));
+    }
+    if manifest.schema_version > LATEST_SCHEMA_VERSION {
+        return Err(Error::InvalidBackup(format!(
+            "backup was written by a newer version ({}) with database schema {}",
+            manifest.app_version, manifest.schema_version
+        )));
+    }
+
+    let config: Config = {
+        let mut json = String::new();
+        archive
+            .by_name(CONFIG_ENTRY)
+            .map_err(invalid)?
+            .read_to_string(&mut json)?;
+        serde_json::from_str(&json)
+            .map_err(|e| Error::InvalidBackup(format!("malformed configuration: {}", e)))?
+    };
+
+    let mut entry = archive.by_name(DATABASE_ENTRY).map_err(invalid)?;
+    std::io::copy(&mut entry, &mut std::fs::File::create(db_path)?)?;
+
+    Ok((manifest, config))
+}
@@ -0,0 +1,238 @@
+//! Batch (download group) management and completion hooks.
+
+use crate::error::{DatabaseError, Error, Result};
+use crate::types::{BatchInfo, DownloadId, DownloadInfo, Event, Priority, Status};
+
+use super::UsenetDownloader;
+
+impl UsenetDownloader {
+    /// Create a new, empty batch
+    ///
+    /// Downloads join the batch by passing its ID in [`crate::types::DownloadOptions::batch_id`].
+    /// Once every member has finished (post-processing done or failed), a single
+    /// [`Event::BatchComplete`] is emitted and `OnBatchComplete` scripts run.
+    pub async fn create_batch(&self, name: &str) -> Result<i64> {
+        self.ensure_writable()?;
+
+        let id = self.db.insert_batch(name).await?;
+        tracing::info!(batch_id = id, name, "batch created");
+        Ok(id)
+    }
+
+    /// Get a batch with aggregated progress across its member downloads
+    ///
+    /// Returns `Ok(None)` if the batch doesn't exist.
+    pub async fn get_batch(&self, id: i64) -> Result<Option<BatchInfo>> {
+        let Some(batch) = self.db.get_batch(id).await? else {
+            return Ok(None);
+        };
+        let downloads = self.db.list_batch_downloads(id).await?;
+        let done: std::collections::HashSet<i64> =
+            self.db.list_batch_done_ids(id).await?.into_iter().collect();
+
+        let mut succeeded = 0;
+        let mut failed = 0;
+        let mut speed_bps = 0u64;
+        let mut size_bytes = 0u64;
+        let mut downloaded_bytes = 0u64;
+        let mut infos = Vec::with_capacity(downloads.len());
+
+        for d in downloads {
+            let status = Status::from_i32(d.status);
+            // Downloads are briefly Complete before post-processing starts, so only
+            // count members the batch has recorded as done
+            if done.contains(&d.id) {
+                match status {
+                    Status::Complete => succeeded += 1,
+                    Status::Failed | Status::FailedUnrepairable => failed += 1,
+                    _ => {}
+                }
+            }
+            if status == Status::Downloading {
+                speed_bps += d.speed_bps as u64;
+            }
+            size_bytes += d.size_bytes as u64;
+            downloaded_bytes += d.downloaded_bytes as u64;
+
+            let incomplete_path = d.incomplete_path(&self.config.download.temp_dir);
+            let duplicate = if status == Status::HeldDuplicate {
+                self.db
+                    .get_held_duplicate(DownloadId(d.id))
+                    .await?
+                    .map(Into::into)
+            } else {
+                None
+            };
+            let error = d.failure();
+            infos.push(DownloadInfo {
+                id: DownloadId(d.id),
+                name: d.name,
+                category: d.category,
+                status,
+                progress: d.progress,
+                speed_bps: d.speed_bps as u64,
+                size_bytes: d.size_bytes as u64,
+                downloaded_bytes: d.downloaded_bytes as u64,
+                eta_seconds: None,
+                priority: Priority::from_i32(d.priority),
+                created_at: chrono::DateTime::from_timestamp(d.created_at, 0)
+                    .unwrap_or_else(chrono::Utc::now),
+                started_at: d
+                    .started_at
+                    .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0)),
+                incomplete_path,
+                duplicate,
+                post_processing_paused: d.post_processing_paused,
+                deferred_url: self.db.get_deferred_nzb(DownloadId(d.id)).await?,
+                stall_reason: self.stall_reason(DownloadId(d.id)),
+                error,
+            });
+        }
+
+        let progress = if size_bytes > 0 {
+            (downloaded_bytes as f32 / size_bytes as f32) * 100.0
+        } else {
+            0.0
+        };
+
+        Ok(Some(BatchInfo {
+            id: batch.id,
+            name: batch.name,
+            total: infos.len(),
+            succeeded,
+            failed,
+            progress,
+            speed_bps,
+            size_bytes,
+            downloaded_bytes,
+            created_at: chrono::DateTime::from_timestamp(batch.created_at, 0)
+                .unwrap_or_else(chrono::Utc::now),
+            completed_at: batch
+                .completed_at
+                .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0)),
+            downloads: infos,
+        }))
+    }
+
+    /// Check that a batch exists and can still accept new downloads
+    pub(crate) async fn ensure_batch_open(&self, batch_id: i64) -> Result<()> {
+        match self.db.get_batch(batch_id).await? {
+            None => Err(Error::Database(DatabaseError::NotFound(format!(
+                "Batch {} not found",
+                batch_id
+            )))),
+            Some(batch) if batch.completed_at.is_some() => {
+                Err(Error::Database(DatabaseError::ConstraintViolation(
+                    format!("Batch {} is already complete", batch_id),
+                )))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Record that a download reached its final state and fire the batch hook if it was the last
+    ///
+    /// No-op for downloads that aren't part of a batch. Errors are logged rather than returned
+    /// so a batch bookkeeping failure never changes the outcome of the download itself.
+    pub(crate) async fn finish_batch_member(&self, id: DownloadId) {
+        let batch_id = match self.db.get_download_batch_id(id).await {
+            Ok(Some(batch_id)) => batch_id,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(download_id = id.0, error = %e, "Failed to look up download batch");
+                return;
+            }
+        };
+
+        if let Err(e) = self.db.mark_batch_member_done(id).await {
+            tracing::warn!(download_id = id.0, batch_id, error = %e, "Failed to mark batch member done");
+            return;
+        }
+
+        self.try_complete_batch(batch_id).await;
+    }
+
+    /// Complete a batch if all of its members are done, emitting the event and running scripts
+    pub(crate) async fn try_complete_batch(&self, batch_id: i64) {
+        match self.db.try_complete_batch(batch_id).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => {
+                tracing::warn!(batch_id, error = %e, "Failed to check batch completion");
+                return;
+            }
+        }
+
+        let info = match self.get_batch(batch_id).await {
+            Ok(Some(info)) => info,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(batch_id, error = %e, "Failed to load completed batch");
+                return;
+            }
+        };
+
+        tracing::info!(
+            batch_id,
+            succeeded = info.succeeded,
+            failed = info.failed,
+            "batch complete"
+        );
+
+        self.emit_event(Event::BatchComplete {
+            id: batch_id,
+            name: info.name.clone(),
+            succeeded: info.succeeded,
+            failed: info.failed,
+        });
+
+        self.trigger_batch_scripts(&info);
+    }
+
+    /// Run global scripts subscribed to `OnBatchComplete`
+    fn trigger_batch_scripts(&self, info: &BatchInfo) {
+        let event_type = crate::config::ScriptEvent::OnBatchComplete;
+        let matching: Vec<_> = self
+            .config
+            .notifications
+            .scripts
+            .iter()
+            .filter(|s| s.events.contains(&event_type))
+            .collect();
+
+        if matching.is_empty() {
+            return;
+        }
+
+        let status = if info.failed == 0 {
+            "complete"
+        } else {
+            "failed"
+        };
+        let download_ids = info
+            .downloads
+            .iter()
+            .map(|d| d.id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let mut env_vars = std::collections::HashMap::new();
+        env_vars.insert("USENET_DL_BATCH_ID".to_string(), info.id.to_string());
+        env_vars.insert("USENET_DL_BATCH_NAME".to_string(), info.name.clone());
+        env_vars.insert("USENET_DL_STATUS".to_string(), status.to_string());
+        env_vars.insert("USENET_DL_SIZE".to_string(), info.size_bytes.to_string());
+        env_vars.insert(
+            "USENET_DL_BATCH_SUCCEEDED".to_string(),
+            info.succeeded.to_string(),
+        );
+        env_vars.insert(
+            "USENET_DL_BATCH_FAILED".to_string(),
+            info.failed.to_string(),
+        );
+        env_vars.insert("USENET_DL_BATCH_DOWNLOADS".to_string(), download_ids);
+
+        for script in matching {
+            self.run_script_async(&script.path, script.timeout, &env_vars);
+        }
+    }
+}
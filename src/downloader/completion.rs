@@ -0,0 +1,237 @@
+//! Completion checks before downloading (propagation checks).
+//!
+//! Asks every server whether a random sample of a download's articles exists with
+//! `STAT`, which transfers no article data, to estimate how much of the download is
+//! available before spending bandwidth on it.
+
+use crate::error::{DownloadError, Error, Result, failure_code};
+use crate::types::{CompletionCheck, DownloadId, Event, ServerCompletion, Status};
+use futures::StreamExt;
+use rand::seq::SliceRandom;
+use std::sync::Arc;
+
+use super::UsenetDownloader;
+use super::server_pools::ServerPools;
+
+/// Answer of one server about one article
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArticleCheck {
+    /// The server has the article
+    Available,
+    /// The server answered that the article does not exist
+    Missing,
+    /// The server could not be asked (connection or protocol error)
+    Error,
+}
+
+impl UsenetDownloader {
+    /// Estimate how much of a download is available on the configured servers
+    ///
+    /// Checks `sample_size` randomly chosen articles (default:
+    /// `completion_check.sample_size`) on every server. An article counts as available
+    /// if any server has it; articles no server could answer for are left out of the
+    /// estimate.
+    pub async fn check_completion(
+        &self,
+        id: DownloadId,
+        sample_size: Option<usize>,
+    ) -> Result<CompletionCheck> {
+        if self.db.get_download(id).await?.is_none() {
+            return Err(Error::Download(DownloadError::NotFound { id: id.0 }));
+        }
+
+        let articles = self.db.get_articles(id).await?;
+        let sample_size = sample_size
+            .unwrap_or(self.config.download.completion_check.sample_size)
+            .max(1);
+        let message_ids: Vec<String> = articles
+            .choose_multiple(&mut rand::thread_rng(), sample_size)
+            .map(|article| {
+                if article.message_id.starts_with('<') {
+                    article.message_id.clone()
+                } else {
+                    format!("<{}>", article.message_id)
+                }
+            })
+            .collect();
+
+        // Servers out of rotation wouldn't serve the download, so they aren't asked
+        let current = self.servers.current();
+        let enabled: Vec<usize> = (0..current.pools.len())
+            .filter(|&i| current.is_enabled(i))
+            .collect();
+        let mut results = Vec::with_capacity(enabled.len());
+        for &pool_idx in &enabled {
+            let connections = current
+                .servers
+                .get(pool_idx)
+                .map_or(1, |server| server.connections.max(1));
+            results.push(
+                stat_articles(
+                    Arc::clone(&current),
+                    pool_idx,
+                    message_ids.clone(),
+                    connections,
+                )
+                .await,
+            );
+        }
+
+        let servers: Vec<String> = enabled
+            .iter()
+            .map(|&i| current.servers[i].host.clone())
+            .collect();
+        let check = summarize_completion(id, articles.len(), message_ids.len(), &servers, &results);
+
+        tracing::info!(
+            download_id = id.0,
+            sampled = check.sampled_articles,
+            available = check.available_articles,
+            missing = check.missing_articles,
+            "Completion check finished"
+        );
+        Ok(check)
+    }
+
+    /// Run the completion check for a newly added download if enabled
+    ///
+    /// Returns `false` if the download was failed because its estimated completion is
+    /// below `completion_check.min_completion`. A check that can't reach any server
+    /// lets the download through.
+    pub(crate) async fn passes_completion_check(&self, id: DownloadId) -> bool {
+        let config = &self.config.download.completion_check;
+        let check = match self.check_completion(id, None).await {
+            Ok(check) => check,
+            Err(e) => {
+                tracing::warn!(download_id = id.0, error = %e, "Completion check failed");
+                return true;
+            }
+        };
+        let Some(completion) = check.estimated_completion else {
+            return true;
+        };
+        if completion >= config.min_completion {
+            return true;
+        }
+
+        let error = format!(
+            "Estimated completion {:.1}% is below the required {:.1}% ({} of {} sampled articles missing)",
+            completion * 100.0,
+            config.min_completion * 100.0,
+            check.missing_articles,
+            check.sampled_articles
+        );
+        tracing::warn!(download_id = id.0, %error, "Download failed completion check");
+        let _ = self.db.update_status(id, Status::Failed.to_i32()).await;
+        let _ = self
+            .db
+            .set_failure(id, failure_code::MISSING_ARTICLES, &error)
+            .await;
+        self.record_history(id).await;
+        self.emit_event(Event::DownloadFailed {
+            id,
+            error,
+            articles_succeeded: None,
+            articles_failed: None,
+            articles_total: Some(check.total_articles as u64),
+        });
+        self.finish_batch_member(id).await;
+        false
+    }
+}
+
+/// `STAT` each message ID on one server, using up to `connections` connections
+async fn stat_articles(
+    servers: Arc<ServerPools>,
+    pool_idx: usize,
+    message_ids: Vec<String>,
+    connections: usize,
+) -> Vec<ArticleCheck> {
+    futures::stream::iter(message_ids)
+        .map(|message_id| {
+            let servers = Arc::clone(&servers);
+            async move {
+                let tracer = &servers.tracer;
+                let Some(pool) = servers.pools.get(pool_idx) else {
+                    return ArticleCheck::Error;
+                };
+                servers.mark_used(pool_idx);
+                let start = std::time::Instant::now();
+                let mut conn = match pool.get().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        tracer.record(pool_idx, "CONNECT", &e.to_string(), start.elapsed());
+                        return ArticleCheck::Error;
+                    }
+                };
+                let result = conn.stat(&message_id).await;
+                if tracer.is_enabled() {
+                    let response = match &result {
+                        Ok(_) => "223".to_string(),
+                        Err(e) => e.to_string(),
+                    };
+                    tracer.record(
+                        pool_idx,
+                        &format!("STAT {}", message_id),
+                        &response,
+                        start.elapsed(),
+                    );
+                }
+                match result {
+                    Ok(_) => ArticleCheck::Available,
+                    Err(nntp_rs::NntpError::NoSuchArticle(_)) => ArticleCheck::Missing,
+                    Err(_) => ArticleCheck::Error,
+                }
+            }
+        })
+        .buffered(connections)
+        .collect()
+        .await
+}
+
+/// Combine per-server answers (`results[server][article]`) into a completion estimate
+pub(crate) fn summarize_completion(
+    download_id: DownloadId,
+    total_articles: usize,
+    sampled_articles: usize,
+    servers: &[String],
+    results: &[Vec<ArticleCheck>],
+) -> CompletionCheck {
+    let mut available_articles = 0;
+    let mut missing_articles = 0;
+    for article in 0..sampled_articles {
+        let answers = || results.iter().filter_map(|server| server.get(article));
+        if answers().any(|&answer| answer == ArticleCheck::Available) {
+            available_articles += 1;
+        } else if answers().any(|&answer| answer == ArticleCheck::Missing) {
+            missing_articles += 1;
+        }
+    }
+
+    let answered = available_articles + missing_articles;
+    let estimated_completion = (answered > 0).then(|| available_articles as f64 / answered as f64);
+
+    let servers = results
+        .iter()
+        .enumerate()
+        .map(|(i, answers)| {
+            let count = |kind| answers.iter().filter(|&&answer| answer == kind).count();
+            ServerCompletion {
+                server: servers.get(i).cloned().unwrap_or_default(),
+                available: count(ArticleCheck::Available),
+                missing: count(ArticleCheck::Missing),
+                errors: count(ArticleCheck::Error),
+            }
+        })
+        .collect();
+
+    CompletionCheck {
+        download_id,
+        total_articles,
+        sampled_articles,
+        available_articles,
+        missing_articles,
+        estimated_completion,
+        servers,
+    }
+}
@@ -1,5 +1,9 @@
 //! Runtime configuration updates — speed limits, categories, schedule rules.
 
+use crate::config::{Config, REDACTED};
+use crate::{Error, Result};
+use serde_json::Value;
+
 use super::UsenetDownloader;
 
 impl UsenetDownloader {
@@ -69,6 +73,145 @@ impl UsenetDownloader {
         );
     }
 
+    /// Get the current bandwidth mode
+    pub fn get_bandwidth_mode(&self) -> crate::types::BandwidthMode {
+        self.runtime_config
+            .bandwidth
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .mode
+    }
+
+    /// Switch the bandwidth mode, e.g. when the host moves to a metered connection
+    ///
+    /// [`Metered`](crate::types::BandwidthMode::Metered) applies
+    /// `download.metered.speed_limit_bps` and keeps downloads larger than
+    /// `download.metered.max_job_bytes` queued (forced ones excepted);
+    /// [`Unlimited`](crate::types::BandwidthMode::Unlimited) removes the speed limit.
+    /// Returning to [`Normal`](crate::types::BandwidthMode::Normal) restores the speed
+    /// limit that applied when normal mode was left. Speed limit changes made while not
+    /// in normal mode last until the mode changes again.
+    pub async fn set_bandwidth_mode(&self, mode: crate::types::BandwidthMode) {
+        use crate::types::BandwidthMode;
+
+        let limit_bps = {
+            let mut state = self
+                .runtime_config
+                .bandwidth
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if state.mode == mode {
+                return;
+            }
+            if state.mode == BandwidthMode::Normal {
+                state.normal_limit = self.get_speed_limit();
+            }
+            state.mode = mode;
+            match mode {
+                BandwidthMode::Normal => state.normal_limit,
+                BandwidthMode::Metered => self
+                    .config
+                    .download
+                    .metered
+                    .speed_limit_bps
+                    .or(state.normal_limit),
+                BandwidthMode::Unlimited => None,
+            }
+        };
+
+        tracing::info!(?mode, "Bandwidth mode changed");
+        self.set_speed_limit(limit_bps).await;
+        self.emit_event(crate::types::Event::BandwidthModeChanged { mode });
+    }
+
+    /// Get the current global burst size in bytes (None = one second of the speed limit)
+    pub fn get_speed_limit_burst(&self) -> Option<u64> {
+        self.speed_limiter.get_burst()
+    }
+
+    /// Set the global token bucket size in bytes
+    ///
+    /// `None` resets to one second's worth of the speed limit. Takes effect within
+    /// one refill interval without interrupting running transfers.
+    pub fn set_speed_limit_burst(&self, burst_bytes: Option<u64>) {
+        self.speed_limiter.set_burst(burst_bytes);
+        tracing::info!(burst_bytes = ?burst_bytes, "Speed limit burst changed");
+    }
+
+    /// Set the speed limit for one configured server
+    ///
+    /// `server_id` is the server's index in [`get_servers`]. The server limit applies
+    /// on top of the global limit. Returns `false` if there is no such server.
+    ///
+    /// [`get_servers`]: UsenetDownloader::get_servers
+    pub fn set_server_speed_limit(&self, server_id: usize, limit_bps: Option<u64>) -> bool {
+        let servers = self.servers.current();
+        let Some(limiter) = servers.speed_limiters.get(server_id) else {
+            return false;
+        };
+        limiter.set_limit(limit_bps);
+        tracing::info!(server_id, limit_bps = ?limit_bps, "Server speed limit changed");
+        true
+    }
+
+    /// Set the speed limit for a single download
+    ///
+    /// The download limit applies on top of the global limit (and the limit of whichever
+    /// server serves each article). It can be set while the download is queued or running
+    /// and lasts until the download finishes; it is not persisted across restarts.
+    pub async fn set_download_speed_limit(
+        &self,
+        id: crate::types::DownloadId,
+        limit_bps: Option<u64>,
+    ) -> crate::Result<()> {
+        if self.db.get_download(id).await?.is_none() {
+            return Err(crate::error::Error::Download(
+                crate::error::DownloadError::NotFound { id: id.0 },
+            ));
+        }
+
+        self.download_speed_limiter(id).set_limit(limit_bps);
+        tracing::info!(download_id = id.0, limit_bps = ?limit_bps, "Download speed limit changed");
+        Ok(())
+    }
+
+    /// Get (or create) the speed limiter for a download
+    ///
+    /// New limiters are unlimited children of the global limiter, created held while
+    /// another download is forced.
+    pub(crate) fn download_speed_limiter(
+        &self,
+        id: crate::types::DownloadId,
+    ) -> crate::speed_limiter::SpeedLimiter {
+        let mut limiters = self
+            .runtime_config
+            .download_speed_limiters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        limiters
+            .entry(id)
+            .or_insert_with(|| {
+                let limiter = self.speed_limiter.child(None);
+                let forced = self
+                    .queue_state
+                    .forced_downloads
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+                limiter.set_held(!forced.is_empty() && !forced.contains(&id));
+                limiter
+            })
+            .clone()
+    }
+
+    /// Drop a finished download's speed limiter
+    pub(crate) fn release_download_speed_limiter(&self, id: crate::types::DownloadId) {
+        self.runtime_config
+            .download_speed_limiters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id);
+    }
+
     /// Update runtime-changeable configuration settings
     ///
     /// This method updates configuration settings that can be safely changed while the
@@ -85,6 +228,97 @@ impl UsenetDownloader {
         }
     }
 
+    /// Get the configuration currently in effect
+    ///
+    /// Starts from the configuration the downloader was created with and reflects
+    /// runtime changes: speed limits (global, burst and per server), servers, categories
+    /// and schedule rules.
+    pub async fn effective_config(&self) -> Config {
+        let mut config = (*self.config).clone();
+        config.download.speed_limit_bps = self.get_speed_limit();
+        config.download.speed_limit_burst_bytes = self.get_speed_limit_burst();
+        config.servers = self.get_servers();
+        config.persistence.categories = self.get_categories().await;
+        config.persistence.schedule_rules = self.get_schedule_rules().await;
+        config
+    }
+
+    /// Apply a partial configuration update and return the configuration now in effect
+    ///
+    /// `patch` is merged into the effective configuration in its JSON form: objects are
+    /// merged recursively, `null` resets a field to its default, and arrays replace the
+    /// current array with each object element merged onto the element at the same index
+    /// (so `{"servers": [{"connections": 20}]}` only changes the first server's
+    /// connections, and drops any others). String values equal to the redaction
+    /// placeholder returned by the API keep the current secret.
+    ///
+    /// The merged configuration is parsed and validated as a whole. Only settings that
+    /// can change at runtime may differ from the effective configuration: the global
+    /// and per-server speed limits, the burst size, categories and schedule rules.
+    /// Errors are [`Error::Config`] with `key` set to the offending field's path, e.g.
+    /// `servers[0].connections`. Nothing is applied unless the whole patch is valid.
+    pub async fn patch_config(&self, patch: &Value) -> Result<Config> {
+        if !patch.is_object() {
+            return Err(Error::Config {
+                message: "config patch must be a JSON object".to_string(),
+                key: None,
+            });
+        }
+
+        let current = self.effective_config().await;
+        let current_json = serde_json::to_value(&current)?;
+        let mut merged = current_json.clone();
+        merge_patch(&mut merged, patch);
+
+        let mut updated = Config::from_json_value(&merged)?;
+        updated.tools.parity_handler = current.tools.parity_handler.clone();
+        updated.validate()?;
+
+        let updated_json = serde_json::to_value(&updated)?;
+        if let Some(path) = unknown_field(patch, &updated_json, String::new()) {
+            return Err(Error::Config {
+                message: format!("{}: unknown field", path),
+                key: Some(path),
+            });
+        }
+
+        // Everything outside the runtime-mutable settings must be unchanged
+        let mut fixed = updated.clone();
+        fixed.download.speed_limit_bps = current.download.speed_limit_bps;
+        fixed.download.speed_limit_burst_bytes = current.download.speed_limit_burst_bytes;
+        for (server, current_server) in fixed.servers.iter_mut().zip(&current.servers) {
+            server.speed_limit_bps = current_server.speed_limit_bps;
+        }
+        fixed.persistence.categories = current.persistence.categories.clone();
+        fixed.persistence.schedule_rules = current.persistence.schedule_rules.clone();
+        if let Some(path) =
+            first_difference(&current_json, &serde_json::to_value(&fixed)?, String::new())
+        {
+            return Err(Error::Config {
+                message: format!("{}: can't be changed while running; restart to apply", path),
+                key: Some(path),
+            });
+        }
+
+        if updated.download.speed_limit_bps != current.download.speed_limit_bps {
+            self.set_speed_limit(updated.download.speed_limit_bps).await;
+        }
+        if updated.download.speed_limit_burst_bytes != current.download.speed_limit_burst_bytes {
+            self.set_speed_limit_burst(updated.download.speed_limit_burst_bytes);
+        }
+        for (id, (server, current_server)) in
+            updated.servers.iter().zip(&current.servers).enumerate()
+        {
+            if server.speed_limit_bps != current_server.speed_limit_bps {
+                self.set_server_speed_limit(id, server.speed_limit_bps);
+            }
+        }
+        *self.runtime_config.categories.write().await = updated.persistence.categories;
+        *self.runtime_config.schedule_rules.write().await = updated.persistence.schedule_rules;
+
+        Ok(self.effective_config().await)
+    }
+
     /// Create or update a category
     ///
     /// This method adds a new category or updates an existing one with the provided configuration.
@@ -263,3 +497,89 @@ impl UsenetDownloader {
         }
     }
 }
+
+/// Merge `patch` into `target` (see [`UsenetDownloader::patch_config`] for the rules)
+fn merge_patch(target: &mut Value, patch: &Value) {
+    match (target, patch) {
+        (Value::String(_), Value::String(s)) if s == REDACTED => {}
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(key);
+                } else if let Some(existing) = target.get_mut(key) {
+                    merge_patch(existing, value);
+                } else {
+                    target.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        (Value::Array(target), Value::Array(patch)) => {
+            target.truncate(patch.len());
+            for (i, value) in patch.iter().enumerate() {
+                match target.get_mut(i) {
+                    Some(existing) if existing.is_object() && value.is_object() => {
+                        merge_patch(existing, value)
+                    }
+                    Some(existing) => *existing = value.clone(),
+                    None => target.push(value.clone()),
+                }
+            }
+        }
+        (target, patch) => *target = patch.clone(),
+    }
+}
+
+/// Path of the first non-null field in `patch` that doesn't exist in `config`
+fn unknown_field(patch: &Value, config: &Value, path: String) -> Option<String> {
+    match (patch, config) {
+        (Value::Object(patch), Value::Object(config)) => patch.iter().find_map(|(key, value)| {
+            let path = join_path(&path, key);
+            match config.get(key) {
+                _ if value.is_null() => None,
+                Some(existing) => unknown_field(value, existing, path),
+                None => Some(path),
+            }
+        }),
+        (Value::Array(patch), Value::Array(config)) => patch
+            .iter()
+            .zip(config)
+            .enumerate()
+            .find_map(|(i, (value, existing))| {
+                unknown_field(value, existing, format!("{}[{}]", path, i))
+            }),
+        _ => None,
+    }
+}
+
+/// Path of the first value that differs between `a` and `b`
+fn first_difference(a: &Value, b: &Value, path: String) -> Option<String> {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            keys.into_iter().find_map(|key| {
+                let path = join_path(&path, key);
+                match (a.get(key), b.get(key)) {
+                    (Some(a), Some(b)) => first_difference(a, b, path),
+                    _ => Some(path),
+                }
+            })
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => a
+            .iter()
+            .zip(b)
+            .enumerate()
+            .find_map(|(i, (a, b))| first_difference(a, b, format!("{}[{}]", path, i))),
+        _ if a == b => None,
+        _ => Some(path),
+    }
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
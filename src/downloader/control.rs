@@ -1,7 +1,7 @@
-//! Download lifecycle control — pause, resume, cancel, priority, reprocess.
+//! Download lifecycle control — pause, resume, cancel, priority, category, rename, reprocess.
 
-use crate::error::{DatabaseError, DownloadError, Error, Result};
-use crate::types::{DownloadId, Event, Priority, Stage, Status};
+use crate::error::{DatabaseError, DownloadError, Error, Result, ToHttpStatus};
+use crate::types::{DownloadId, DownloadOptions, Event, PauseScope, Priority, Stage, Status};
 use std::path::PathBuf;
 
 use super::UsenetDownloader;
@@ -14,6 +14,9 @@ impl UsenetDownloader {
     /// (after completing the current article). The download will be marked as
     /// Paused in the database and can be resumed later with `resume()`.
     ///
+    /// Its post-processing is paused too; see [`pause_scoped`](Self::pause_scoped) to
+    /// pause only one of the two.
+    ///
     /// # Arguments
     ///
     /// * `id` - The download ID to pause
@@ -36,6 +39,18 @@ impl UsenetDownloader {
     /// # }
     /// ```
     pub async fn pause(&self, id: DownloadId) -> Result<()> {
+        self.pause_scoped(id, PauseScope::All).await
+    }
+
+    /// Pause a download's downloading, its post-processing, or both
+    ///
+    /// Pausing downloading stops a queued or downloading job and marks it Paused, freeing
+    /// its bandwidth; a job that's already post-processing is left alone. Pausing
+    /// post-processing lets the job keep downloading but makes it wait before its
+    /// post-processing starts; a run that has already started finishes.
+    pub async fn pause_scoped(&self, id: DownloadId, scope: PauseScope) -> Result<()> {
+        self.ensure_writable()?;
+
         // Fetch download from database
         let download = self.db.get_download(id).await?.ok_or_else(|| {
             Error::Database(DatabaseError::NotFound(format!(
@@ -48,22 +63,34 @@ impl UsenetDownloader {
 
         // Check if download can be paused
         match current_status {
-            Status::Paused => {
-                // Already paused, nothing to do
-                return Ok(());
-            }
-            Status::Complete | Status::Failed => {
+            Status::Complete
+            | Status::Failed
+            | Status::FailedUnrepairable
+            | Status::HeldDuplicate
+            | Status::WaitingForPassword => {
                 return Err(Error::Download(DownloadError::InvalidState {
                     id: id.into(),
                     operation: "pause".to_string(),
                     current_state: format!("{:?}", current_status),
                 }));
             }
-            Status::Queued | Status::Downloading | Status::Processing => {
+            Status::Queued | Status::Downloading | Status::Paused | Status::Processing => {
                 // Can be paused
             }
         }
 
+        if scope.includes_post_processing() && !download.post_processing_paused {
+            self.db.set_post_processing_paused(id, true).await?;
+            self.queue_state.post_processing_paused.send_modify(|_| {});
+        }
+
+        // Already paused, or nothing left to download
+        if !scope.includes_download()
+            || matches!(current_status, Status::Paused | Status::Processing)
+        {
+            return Ok(());
+        }
+
         // If download is actively running, cancel its task
         let mut active_downloads = self.queue_state.active_downloads.lock().await;
         if let Some(cancel_token) = active_downloads.get(&id) {
@@ -92,6 +119,9 @@ impl UsenetDownloader {
     /// Downloads resume at the article level - any articles that were already
     /// downloaded are skipped, and only pending articles are fetched.
     ///
+    /// Its post-processing is resumed too; see [`resume_scoped`](Self::resume_scoped)
+    /// to resume only one of the two.
+    ///
     /// # Arguments
     ///
     /// * `id` - The download ID to resume
@@ -115,6 +145,16 @@ impl UsenetDownloader {
     /// # }
     /// ```
     pub async fn resume(&self, id: DownloadId) -> Result<()> {
+        self.resume_scoped(id, PauseScope::All).await
+    }
+
+    /// Resume a download's downloading, its post-processing, or both
+    ///
+    /// Post-processing that was waiting on the download's own pause starts, unless
+    /// post-processing is also paused queue-wide.
+    pub async fn resume_scoped(&self, id: DownloadId, scope: PauseScope) -> Result<()> {
+        self.ensure_writable()?;
+
         // Fetch download from database
         let download = self.db.get_download(id).await?.ok_or_else(|| {
             Error::Database(DatabaseError::NotFound(format!(
@@ -127,14 +167,15 @@ impl UsenetDownloader {
 
         // Check if download can be resumed
         match current_status {
-            Status::Paused => {
+            Status::Paused | Status::Queued | Status::Downloading | Status::Processing => {
                 // Can be resumed
             }
-            Status::Queued | Status::Downloading | Status::Processing => {
-                // Already active, nothing to do (idempotent)
-                return Ok(());
-            }
-            Status::Complete | Status::Failed => {
+            // A held duplicate has to be accepted instead, a parked job given a password
+            Status::Complete
+            | Status::Failed
+            | Status::FailedUnrepairable
+            | Status::HeldDuplicate
+            | Status::WaitingForPassword => {
                 return Err(Error::Download(DownloadError::InvalidState {
                     id: id.into(),
                     operation: "resume".to_string(),
@@ -143,6 +184,16 @@ impl UsenetDownloader {
             }
         }
 
+        if scope.includes_post_processing() && download.post_processing_paused {
+            self.db.set_post_processing_paused(id, false).await?;
+            self.queue_state.post_processing_paused.send_modify(|_| {});
+        }
+
+        // Already active (idempotent)
+        if !scope.includes_download() || current_status != Status::Paused {
+            return Ok(());
+        }
+
         // Update status back to Queued
         self.db.update_status(id, Status::Queued.to_i32()).await?;
 
@@ -220,8 +271,10 @@ impl UsenetDownloader {
     /// # }
     /// ```
     pub async fn cancel(&self, id: DownloadId) -> Result<()> {
+        self.ensure_writable()?;
+
         // Verify download exists
-        let _download = self.db.get_download(id).await?.ok_or_else(|| {
+        let download = self.db.get_download(id).await?.ok_or_else(|| {
             Error::Database(DatabaseError::NotFound(format!(
                 "Download {} not found",
                 id
@@ -242,11 +295,7 @@ impl UsenetDownloader {
         self.remove_from_queue(id).await;
 
         // Delete downloaded files from temp directory
-        let download_temp_dir = self
-            .config
-            .download
-            .temp_dir
-            .join(format!("download_{}", id.0));
+        let download_temp_dir = download.incomplete_path(&self.config.download.temp_dir);
         if download_temp_dir.exists()
             && let Err(e) = tokio::fs::remove_dir_all(&download_temp_dir).await
         {
@@ -259,15 +308,126 @@ impl UsenetDownloader {
             // Continue anyway - database deletion is more important
         }
 
+        // Remember the batch so removing its last pending member can complete it
+        let batch_id = self.db.get_download_batch_id(id).await?;
+
         // Delete download from database (cascades to articles, passwords)
         self.db.delete_download(id).await?;
 
         // Emit Removed event
         self.emit_event(crate::types::Event::Removed { id });
 
+        if let Some(batch_id) = batch_id {
+            self.try_complete_batch(batch_id).await;
+        }
+
         Ok(())
     }
 
+    /// Accept a download held as a duplicate
+    ///
+    /// The download is queued as if it hadn't been detected as a duplicate.
+    /// Fails with `InvalidState` if the download isn't held.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use usenet_dl::*;
+    /// # async fn example(downloader: UsenetDownloader, id: DownloadId) -> Result<()> {
+    /// // Download it anyway
+    /// downloader.accept_duplicate(id).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn accept_duplicate(&self, id: DownloadId) -> Result<()> {
+        self.ensure_writable()?;
+
+        let download = self.held_duplicate(id, "accept").await?;
+
+        self.db.delete_held_duplicate(id).await?;
+        self.db.update_status(id, Status::Queued.to_i32()).await?;
+
+        let options = crate::types::DownloadOptions {
+            category: download.category,
+            ..Default::default()
+        };
+        self.finalize_nzb_addition(id, &download.name, &options)
+            .await
+    }
+
+    /// Discard a download held as a duplicate
+    ///
+    /// The download is removed like [`cancel`](Self::cancel). Fails with
+    /// `InvalidState` if the download isn't held.
+    pub async fn discard_duplicate(&self, id: DownloadId) -> Result<()> {
+        self.ensure_writable()?;
+
+        self.held_duplicate(id, "discard").await?;
+        self.cancel(id).await
+    }
+
+    /// Retry extraction of a download waiting for a password with `password`
+    ///
+    /// The password replaces the one the download was added with, and post-processing
+    /// runs again from the extract stage. If it doesn't open the archives either, the
+    /// download goes back to waiting. Fails with a configuration error for an empty
+    /// password, and with `InvalidState` unless the download is waiting for one.
+    pub async fn submit_password(&self, id: DownloadId, password: &str) -> Result<()> {
+        self.ensure_writable()?;
+
+        if password.is_empty() {
+            return Err(Error::Config {
+                message: "password must not be empty".to_string(),
+                key: Some("password".to_string()),
+            });
+        }
+
+        let download = self
+            .db
+            .get_download(id)
+            .await?
+            .ok_or(Error::Download(DownloadError::NotFound { id: id.into() }))?;
+        let current_status = Status::from_i32(download.status);
+        if current_status != Status::WaitingForPassword {
+            return Err(Error::Download(DownloadError::InvalidState {
+                id: id.into(),
+                operation: "submit password".to_string(),
+                current_state: format!("{:?}", current_status),
+            }));
+        }
+
+        let passwords = self.db.get_download_passwords(id).await?;
+        self.db
+            .set_download_passwords(id, Some(password), passwords.nzb_password.as_deref())
+            .await?;
+
+        tracing::info!(
+            download_id = id.0,
+            "Password submitted, retrying extraction"
+        );
+        self.reprocess_from_stage(id, Stage::Extract).await
+    }
+
+    /// Fetch a download, failing unless it's held as a duplicate
+    async fn held_duplicate(&self, id: DownloadId, operation: &str) -> Result<crate::db::Download> {
+        let download = self
+            .db
+            .get_download(id)
+            .await?
+            .ok_or(Error::Download(DownloadError::NotFound { id: id.into() }))?;
+
+        let current_status = Status::from_i32(download.status);
+        if current_status != Status::HeldDuplicate {
+            return Err(Error::Download(DownloadError::InvalidState {
+                id: id.into(),
+                operation: operation.to_string(),
+                current_state: format!("{:?}", current_status),
+            }));
+        }
+
+        Ok(download)
+    }
+
     /// Set the priority of a download
     ///
     /// This method changes the priority of a download. If the download is queued,
@@ -290,6 +450,8 @@ impl UsenetDownloader {
     /// # }
     /// ```
     pub async fn set_priority(&self, id: DownloadId, priority: Priority) -> Result<()> {
+        self.ensure_writable()?;
+
         // Verify download exists
         let download = self.db.get_download(id).await?.ok_or_else(|| {
             Error::Database(DatabaseError::NotFound(format!(
@@ -317,6 +479,126 @@ impl UsenetDownloader {
         Ok(())
     }
 
+    /// Move a download to another category, or out of any category with `None`
+    ///
+    /// The download takes the destination and post-processing mode of the new
+    /// category (or the defaults without one), replacing any it was added with.
+    ///
+    /// Fails with a configuration error for an unknown category, and with
+    /// `InvalidState` while the download is post-processing or after it completed,
+    /// since its destination is then in use.
+    pub async fn set_category(&self, id: DownloadId, category: Option<&str>) -> Result<()> {
+        self.ensure_writable()?;
+
+        if let Some(category) = category
+            && !self
+                .runtime_config
+                .categories
+                .read()
+                .await
+                .contains_key(category)
+        {
+            return Err(Error::Config {
+                message: format!("unknown category {:?}", category),
+                key: Some("category".to_string()),
+            });
+        }
+
+        let download = self
+            .db
+            .get_download(id)
+            .await?
+            .ok_or(Error::Download(DownloadError::NotFound { id: id.0 }))?;
+
+        let current_status = Status::from_i32(download.status);
+        if matches!(current_status, Status::Processing | Status::Complete) {
+            return Err(Error::Download(DownloadError::InvalidState {
+                id: id.into(),
+                operation: "set category".to_string(),
+                current_state: format!("{:?}", current_status),
+            }));
+        }
+
+        let options = DownloadOptions {
+            category: category.map(str::to_string),
+            ..Default::default()
+        };
+        let (destination, post_process) = self.resolve_destination_and_post_process(&options).await;
+        self.db
+            .update_category(
+                id,
+                category,
+                &destination.to_string_lossy(),
+                post_process.to_i32(),
+            )
+            .await?;
+
+        tracing::info!(download_id = id.0, category = ?category, "Download category changed");
+        Ok(())
+    }
+
+    /// Rename a download
+    ///
+    /// Sets the download's name and job name, which post-processing uses for password
+    /// lookups and deobfuscation. If the destination directory is named after the job,
+    /// it follows the rename. The temp directory is keyed by download ID and needs no
+    /// change. Everything is updated in one database statement, then
+    /// [`Event::Renamed`] is emitted.
+    ///
+    /// Fails with a configuration error for an empty name or one containing a path
+    /// separator, and with `InvalidState` while the download is post-processing or
+    /// after it completed, since its files are being or have been moved.
+    pub async fn rename_download(&self, id: DownloadId, new_name: &str) -> Result<()> {
+        self.ensure_writable()?;
+
+        let new_name = new_name.trim();
+        if new_name.is_empty() || new_name.contains(['/', '\\']) || new_name == ".." {
+            return Err(Error::Config {
+                message: format!("invalid download name {:?}", new_name),
+                key: Some("name".to_string()),
+            });
+        }
+
+        let download = self
+            .db
+            .get_download(id)
+            .await?
+            .ok_or(Error::Download(DownloadError::NotFound { id: id.0 }))?;
+
+        let current_status = Status::from_i32(download.status);
+        if matches!(current_status, Status::Processing | Status::Complete) {
+            return Err(Error::Download(DownloadError::InvalidState {
+                id: id.into(),
+                operation: "rename".to_string(),
+                current_state: format!("{:?}", current_status),
+            }));
+        }
+
+        let old_name = download.name;
+        let destination = PathBuf::from(&download.destination);
+        let destination = if destination
+            .file_name()
+            .is_some_and(|last| last == old_name.as_str())
+        {
+            destination.with_file_name(new_name)
+        } else {
+            destination
+        };
+
+        self.db
+            .rename_download(id, new_name, &destination.to_string_lossy())
+            .await?;
+
+        tracing::info!(download_id = id.0, old = %old_name, new = %new_name, "Download renamed");
+        self.emit_event(Event::Renamed {
+            id,
+            old: old_name,
+            new: new_name.to_string(),
+        });
+
+        Ok(())
+    }
+
     /// Re-run post-processing on a completed or failed download
     ///
     /// This method allows re-running the post-processing pipeline on a download.
@@ -327,19 +609,40 @@ impl UsenetDownloader {
     ///
     /// The download files must still exist in the temp directory for reprocessing to work.
     pub async fn reprocess(&self, id: DownloadId) -> Result<()> {
+        self.start_reprocess(id, None).await
+    }
+
+    /// Re-run post-processing starting at a specific stage
+    ///
+    /// Runs `stage` and every later stage of the full pipeline (verify, repair, extract,
+    /// move, cleanup) against the files still in the temp directory, without re-downloading
+    /// articles. The download's PostProcess mode is ignored, so e.g. `Stage::Extract` extracts
+    /// even for a download originally added with `PostProcess::Verify`.
+    ///
+    /// Returns `Error::NotSupported` for `Stage::Download` and `Stage::DirectUnpack`.
+    pub async fn reprocess_from_stage(&self, id: DownloadId, stage: Stage) -> Result<()> {
+        if matches!(stage, Stage::Download | Stage::DirectUnpack) {
+            return Err(Error::NotSupported(format!(
+                "Cannot reprocess from the {:?} stage",
+                stage
+            )));
+        }
+        self.start_reprocess(id, Some(stage)).await
+    }
+
+    /// Shared setup for [`Self::reprocess`] and [`Self::reprocess_from_stage`]
+    async fn start_reprocess(&self, id: DownloadId, from_stage: Option<Stage>) -> Result<()> {
+        self.ensure_writable()?;
+
         // Get download from database
-        let _download = self
+        let download = self
             .db
             .get_download(id)
             .await?
             .ok_or_else(|| Error::NotFound(format!("Download {} not found", id.0)))?;
 
         // Determine download path (temp directory)
-        let download_path = self
-            .config
-            .download
-            .temp_dir
-            .join(format!("download_{}", id.0));
+        let download_path = download.incomplete_path(&self.config.download.temp_dir);
 
         // Verify download files still exist
         if !download_path.exists() {
@@ -352,6 +655,7 @@ impl UsenetDownloader {
         tracing::info!(
             download_id = id.0,
             path = %download_path.display(),
+            ?from_stage,
             "Starting reprocessing"
         );
 
@@ -370,7 +674,11 @@ impl UsenetDownloader {
         // This will run asynchronously
         let downloader = self.clone();
         tokio::spawn(async move {
-            if let Err(e) = downloader.start_post_processing(id).await {
+            let result = match downloader.acquire_post_processing_slot(id).await {
+                Ok(_slot) => downloader.run_post_processing(id, from_stage).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
                 tracing::error!(
                     download_id = id.0,
                     error = %e,
@@ -391,6 +699,8 @@ impl UsenetDownloader {
     /// - Extraction settings changed
     /// - User wants to re-extract without re-downloading
     pub async fn reextract(&self, id: DownloadId) -> Result<()> {
+        self.ensure_writable()?;
+
         // Get download from database
         let download = self
             .db
@@ -399,11 +709,7 @@ impl UsenetDownloader {
             .ok_or_else(|| Error::NotFound(format!("Download {} not found", id.0)))?;
 
         // Determine download path (temp directory)
-        let download_path = self
-            .config
-            .download
-            .temp_dir
-            .join(format!("download_{}", id.0));
+        let download_path = download.incomplete_path(&self.config.download.temp_dir);
 
         // Verify download files still exist
         if !download_path.exists() {
@@ -439,10 +745,13 @@ impl UsenetDownloader {
         let downloader = self.clone();
         let destination = PathBuf::from(download.destination.clone());
         let post_processor = self.processing.post_processor.clone();
+        let options = self.run_options(download.category.as_deref()).await;
+        let guard = self.post_processing_guard();
         tokio::spawn(async move {
+            let _guard = guard;
             // Run re-extraction (extract + move, skip verify/repair)
             match post_processor
-                .reextract(id, download_path, destination)
+                .reextract(id, download_path, destination, &options)
                 .await
             {
                 Ok(final_path) => {
@@ -467,6 +776,7 @@ impl UsenetDownloader {
         download: crate::db::Download,
     ) {
         tracing::info!(download_id = id.0, ?final_path, "Re-extraction complete");
+        self.write_completion_sidecar(&download, &final_path).await;
 
         // Update status to complete
         if let Err(e) = self.db.update_status(id, Status::Complete.to_i32()).await {
@@ -476,6 +786,7 @@ impl UsenetDownloader {
                 "Failed to update status to complete"
             );
         }
+        self.record_history(id).await;
 
         // Emit Complete event
         self.emit_event(Event::Complete {
@@ -495,16 +806,17 @@ impl UsenetDownloader {
         });
 
         // Trigger scripts for complete event
-        self.trigger_scripts(super::webhooks::TriggerScriptsParams {
+        let scripts = self.trigger_scripts(super::webhooks::TriggerScriptsParams {
             event_type: crate::config::ScriptEvent::OnComplete,
             download_id: id,
             name: download.name.clone(),
             category: download.category.clone(),
             status: "complete".to_string(),
-            destination: Some(final_path),
+            destination: Some(final_path.clone()),
             error: None,
             size_bytes: download.size_bytes as u64,
         });
+        self.announce_ready_for_import(id, download.category, final_path, scripts);
     }
 
     /// Handle re-extraction failure
@@ -516,6 +828,9 @@ impl UsenetDownloader {
     ) {
         // Convert error to string once, reuse throughout
         let error_msg = error.to_string();
+        self.processing.post_processor.take_moved_files(id);
+        self.processing.post_processor.take_summary(id);
+        let files_kept = self.discard_intermediate_files(&download).await;
 
         tracing::error!(
             download_id = id.0,
@@ -533,20 +848,25 @@ impl UsenetDownloader {
         }
 
         // Set error message
-        if let Err(db_err) = self.db.set_error(id, &error_msg).await {
+        if let Err(db_err) = self
+            .db
+            .set_failure(id, error.error_code(), &error_msg)
+            .await
+        {
             tracing::error!(
                 download_id = id.0,
                 error = %db_err,
                 "Failed to set error message"
             );
         }
+        self.record_history(id).await;
 
         // Emit Failed event
         self.emit_event(Event::Failed {
             id,
             stage: Stage::Extract,
             error: error_msg.clone(),
-            files_kept: true,
+            files_kept,
         });
 
         // Trigger webhooks for failed event
@@ -575,78 +895,128 @@ impl UsenetDownloader {
 
     /// Pause all active downloads
     ///
-    /// This method pauses all downloads that are currently queued, downloading, or processing.
-    /// Already paused, completed, or failed downloads are not affected.
+    /// This method pauses all downloads that are currently queued or downloading, and
+    /// post-processing queue-wide. Already paused, completed, or failed downloads are
+    /// not affected.
     pub async fn pause_all(&self) -> Result<()> {
-        // Get all downloads that can be paused (Queued, Downloading, Processing)
-        let all_downloads = self.db.list_downloads().await?;
-
-        let mut paused_count = 0;
-
-        for download in all_downloads {
-            let status = Status::from_i32(download.status);
-
-            // Only pause active downloads
-            match status {
-                Status::Queued | Status::Downloading | Status::Processing => {
-                    if let Err(e) = self.pause(DownloadId(download.id)).await {
-                        tracing::warn!(
-                            download_id = download.id,
-                            error = %e,
-                            "Failed to pause download during pause_all"
-                        );
-                        // Continue with other downloads
-                    } else {
-                        paused_count += 1;
+        self.pause_queue(PauseScope::All).await
+    }
+
+    /// Pause downloading, post-processing, or both for the whole queue
+    ///
+    /// Pausing downloading pauses every queued or downloading job and emits
+    /// [`Event::QueuePaused`]. Pausing post-processing makes every job wait before its
+    /// post-processing starts, including after a restart, and emits
+    /// [`Event::PostProcessingPaused`]; runs that already started finish.
+    pub async fn pause_queue(&self, scope: PauseScope) -> Result<()> {
+        self.ensure_writable()?;
+
+        if scope.includes_download() {
+            let mut paused_count = 0;
+
+            for download in self.db.list_downloads().await? {
+                // Only pause downloads that still have something to download
+                match Status::from_i32(download.status) {
+                    Status::Queued | Status::Downloading => {
+                        if let Err(e) = self
+                            .pause_scoped(DownloadId(download.id), PauseScope::Download)
+                            .await
+                        {
+                            tracing::warn!(
+                                download_id = download.id,
+                                error = %e,
+                                "Failed to pause download during pause_all"
+                            );
+                            // Continue with other downloads
+                        } else {
+                            paused_count += 1;
+                        }
+                    }
+                    Status::Paused
+                    | Status::Processing
+                    | Status::Complete
+                    | Status::Failed
+                    | Status::FailedUnrepairable
+                    | Status::HeldDuplicate
+                    | Status::WaitingForPassword => {
+                        // Skip paused, post-processing and finished downloads and parked ones
                     }
-                }
-                Status::Paused | Status::Complete | Status::Failed => {
-                    // Skip already paused/finished downloads
                 }
             }
-        }
 
-        tracing::info!(paused_count = paused_count, "Paused all active downloads");
+            tracing::info!(paused_count = paused_count, "Paused all active downloads");
 
-        // Emit global QueuePaused event
-        self.emit_event(crate::types::Event::QueuePaused);
+            // Emit global QueuePaused event
+            self.emit_event(Event::QueuePaused);
+        }
+
+        if scope.includes_post_processing() {
+            self.db.set_post_processing_paused_all(true).await?;
+            self.queue_state.post_processing_paused.send_replace(true);
+            tracing::info!("Paused post-processing");
+            self.emit_event(Event::PostProcessingPaused);
+        }
 
         Ok(())
     }
 
     /// Resume all paused downloads
     ///
-    /// This method resumes all downloads that are currently paused.
-    /// Downloads in other states (queued, downloading, complete, failed) are not affected.
+    /// This method resumes all downloads that are currently paused, and post-processing
+    /// queue-wide and per download. Downloads in other states (queued, downloading,
+    /// complete, failed) are not affected.
     pub async fn resume_all(&self) -> Result<()> {
-        // Get all paused downloads
-        let paused_downloads = self
-            .db
-            .list_downloads_by_status(Status::Paused.to_i32())
-            .await?;
-
-        let mut resumed_count = 0;
+        self.resume_queue(PauseScope::All).await
+    }
 
-        for download in paused_downloads {
-            if let Err(e) = self.resume(DownloadId(download.id)).await {
-                tracing::warn!(
-                    download_id = download.id,
-                    error = %e,
-                    "Failed to resume download during resume_all"
-                );
-                // Continue with other downloads
-            } else {
-                resumed_count += 1;
+    /// Resume downloading, post-processing, or both for the whole queue
+    ///
+    /// Resuming post-processing also lifts the post-processing pauses of individual
+    /// downloads, as resuming downloading resumes individually paused downloads.
+    pub async fn resume_queue(&self, scope: PauseScope) -> Result<()> {
+        self.ensure_writable()?;
+
+        if scope.includes_download() {
+            // Get all paused downloads
+            let paused_downloads = self
+                .db
+                .list_downloads_by_status(Status::Paused.to_i32())
+                .await?;
+
+            let mut resumed_count = 0;
+
+            for download in paused_downloads {
+                if let Err(e) = self
+                    .resume_scoped(DownloadId(download.id), PauseScope::Download)
+                    .await
+                {
+                    tracing::warn!(
+                        download_id = download.id,
+                        error = %e,
+                        "Failed to resume download during resume_all"
+                    );
+                    // Continue with other downloads
+                } else {
+                    resumed_count += 1;
+                }
             }
-        }
 
-        tracing::info!(
-            resumed_count = resumed_count,
-            "Resumed all paused downloads"
-        );
+            tracing::info!(
+                resumed_count = resumed_count,
+                "Resumed all paused downloads"
+            );
+
+            // Emit global QueueResumed event
+            self.emit_event(Event::QueueResumed);
+        }
 
-        // Emit global QueueResumed event
-        self.emit_event(crate::types::Event::QueueResumed);
+        if scope.includes_post_processing() {
+            self.db.set_post_processing_paused_all(false).await?;
+            self.db.clear_post_processing_paused().await?;
+            self.queue_state.post_processing_paused.send_replace(false);
+            tracing::info!("Resumed post-processing");
+            self.emit_event(Event::PostProcessingResumed);
+        }
 
         Ok(())
     }
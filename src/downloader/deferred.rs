@@ -0,0 +1,244 @@
+//! Downloads whose NZB is fetched only when they start.
+//!
+//! Indexer links can expire, so an NZB for an item far down a long queue may be gone by
+//! the time the item's turn comes. [`UsenetDownloader::add_nzb_url_deferred`] queues a
+//! download holding just the URL; the queue processor fetches and ingests the NZB
+//! right before the download starts. If the URL has expired by then,
+//! `download.nzb_fallback_url` can find the NZB by release name instead.
+
+use crate::db::FetchedNzb;
+use crate::error::{Error, Result, ToHttpStatus, failure_code};
+use crate::types::{DownloadId, DownloadOptions, Event};
+use crate::utils::extract_filename_from_url;
+
+use super::UsenetDownloader;
+use super::nzb::fetch_nzb_or_status;
+
+impl UsenetDownloader {
+    /// Queue a download whose NZB is fetched from `url` when it's about to start
+    ///
+    /// Until then the download has no files and a size of 0. `name` defaults to the last
+    /// part of the URL's path, and is then replaced by the fetched NZB's file name.
+    /// Only the name-based duplicate detection methods apply when adding; the disk space
+    /// check, `download.reject_extensions` and the completion check apply once the NZB is
+    /// fetched, and fail the download like an NZB that can't be fetched or parsed.
+    pub async fn add_nzb_url_deferred(
+        &self,
+        url: &str,
+        name: Option<&str>,
+        options: DownloadOptions,
+    ) -> Result<DownloadId> {
+        self.ensure_writable()?;
+
+        if !self
+            .queue_state
+            .accepting_new
+            .load(std::sync::atomic::Ordering::SeqCst)
+        {
+            return Err(Error::ShuttingDown);
+        }
+
+        let parsed = url::Url::parse(url)
+            .map_err(|e| Error::InvalidNzb(format!("Invalid NZB URL '{}': {}", url, e)))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(Error::InvalidNzb(format!(
+                "NZB URL '{}' must use http or https",
+                url
+            )));
+        }
+
+        if let Some(batch_id) = options.batch_id {
+            self.ensure_batch_open(batch_id).await?;
+        }
+
+        let name = name
+            .map(str::to_string)
+            .unwrap_or_else(|| extract_filename_from_url(url));
+
+        // Without the NZB there is no hash to compare, so only the name-based methods can match
+        let held_duplicate = self.handle_duplicate_check(&[], &name).await?;
+
+        let (destination, post_process) = self.resolve_destination_and_post_process(&options).await;
+        let new_download = crate::db::NewDownload {
+            name: name.clone(),
+            nzb_path: url.to_string(),
+            nzb_meta_name: None,
+            nzb_hash: None,
+            job_name: Some(name.clone()),
+            category: options.category.clone(),
+            destination: destination.to_string_lossy().into_owned(),
+            post_process: post_process.to_i32(),
+            priority: options.priority as i32,
+            status: crate::types::Status::Queued.to_i32(),
+            size_bytes: 0,
+        };
+        let download_id = self.db.insert_download(&new_download).await?;
+        self.db.set_deferred_nzb(download_id, url).await?;
+        self.assign_incomplete_dir(download_id, &name, &options)
+            .await?;
+
+        if options.password.is_some() {
+            self.db
+                .set_download_passwords(download_id, options.password.as_deref(), None)
+                .await?;
+        }
+        if let Some(batch_id) = options.batch_id {
+            self.db.set_download_batch(download_id, batch_id).await?;
+        }
+        if let Some(temp_dir) = &options.temp_dir {
+            self.db
+                .set_temp_dir(download_id, &temp_dir.to_string_lossy())
+                .await?;
+        }
+
+        if let Some(duplicate) = held_duplicate {
+            self.hold_duplicate(download_id, &name, duplicate).await?;
+            return Ok(download_id);
+        }
+
+        self.finalize_nzb_addition(download_id, &name, &options)
+            .await?;
+
+        Ok(download_id)
+    }
+
+    /// Fetch and ingest a download's deferred NZB, if it has one
+    ///
+    /// Returns whether the download can start. When it can't, the download has been
+    /// failed and recorded in history.
+    pub(super) async fn fetch_deferred_nzb(&self, id: DownloadId) -> bool {
+        let url = match self.db.get_deferred_nzb(id).await {
+            Ok(Some(url)) => url,
+            Ok(None) => return true,
+            Err(e) => {
+                self.fail_added_download(
+                    id,
+                    e.error_code(),
+                    format!("Failed to look up deferred NZB: {}", e),
+                )
+                .await;
+                return false;
+            }
+        };
+
+        tracing::info!(download_id = id.0, %url, "Fetching deferred NZB");
+        match self.ingest_deferred_nzb(id, &url).await {
+            Ok(can_start) => can_start,
+            Err(e) => {
+                let error = format!("Failed to fetch NZB from '{}': {}", url, e);
+                tracing::warn!(download_id = id.0, %error, "Deferred NZB fetch failed");
+                // A fetch that fails shows up as an I/O error; anything else kept its cause
+                let code = match &e {
+                    Error::Io(_) => failure_code::NZB_FETCH_FAILED,
+                    e => e.error_code(),
+                };
+                self.fail_added_download(id, code, error).await;
+                false
+            }
+        }
+    }
+
+    async fn ingest_deferred_nzb(&self, id: DownloadId, url: &str) -> Result<bool> {
+        let download = self
+            .db
+            .get_download(id)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("Download {} not found", id)))?;
+
+        let (fetched_name, content) = self
+            .fetch_deferred(id, url, download.release_name())
+            .await?;
+        let (mut nzb, nzb_meta_name, nzb_password, nzb_hash) =
+            self.parse_and_validate_nzb(&content, &fetched_name).await?;
+        let rejected_files = self.remove_rejected_files(&mut nzb);
+
+        // A name given when adding is kept; one taken from the URL gives way to the NZB's
+        let name = if download.name == extract_filename_from_url(url) {
+            fetched_name
+        } else {
+            download.name
+        };
+        let job_name = nzb_meta_name.clone().unwrap_or_else(|| name.clone());
+        let size_bytes = nzb.total_bytes();
+
+        let passwords = self.db.get_download_passwords(id).await?;
+        self.insert_articles_and_passwords(
+            &nzb,
+            id,
+            passwords.password.as_deref(),
+            nzb_password.as_deref(),
+        )
+        .await?;
+        self.db
+            .complete_deferred_nzb(
+                id,
+                &FetchedNzb {
+                    name: name.clone(),
+                    nzb_meta_name,
+                    nzb_hash,
+                    job_name: job_name.clone(),
+                    size_bytes: size_bytes as i64,
+                },
+            )
+            .await?;
+        self.backup_nzb(id, &job_name, &content).await;
+
+        if !self.accept_rejected_files(id, &nzb, rejected_files).await {
+            return Ok(false);
+        }
+        if self.config.download.completion_check.check_on_add
+            && !self.passes_completion_check(id).await
+        {
+            return Ok(false);
+        }
+
+        self.emit_event(Event::NzbFetched {
+            id,
+            name,
+            size_bytes,
+        });
+        Ok(true)
+    }
+
+    /// Fetch a deferred NZB, trying `download.nzb_fallback_url` if its URL is gone
+    ///
+    /// Emits [`Event::NzbFetchFailed`] with the reason for each failed attempt when no
+    /// NZB could be fetched.
+    async fn fetch_deferred(
+        &self,
+        id: DownloadId,
+        url: &str,
+        release_name: &str,
+    ) -> Result<(String, Vec<u8>)> {
+        let mut reasons = Vec::new();
+        match fetch_nzb_or_status(url).await {
+            Ok(Ok(fetched)) => return Ok(fetched),
+            Ok(Err(status)) => {
+                reasons.push(format!("{} returned {}", url, status));
+                if matches!(
+                    status,
+                    reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE
+                ) && let Some(template) = &self.config.download.nzb_fallback_url
+                {
+                    let fallback = template.replace("{name}", &urlencoding::encode(release_name));
+                    tracing::info!(download_id = id.0, %fallback, "NZB URL is gone, trying fallback");
+                    match fetch_nzb_or_status(&fallback).await {
+                        Ok(Ok(fetched)) => return Ok(fetched),
+                        Ok(Err(status)) => {
+                            reasons.push(format!("fallback {} returned {}", fallback, status))
+                        }
+                        Err(e) => reasons.push(format!("fallback {}: {}", fallback, e)),
+                    }
+                }
+            }
+            Err(e) => reasons.push(e.to_string()),
+        }
+
+        self.emit_event(Event::NzbFetchFailed {
+            id,
+            url: url.to_string(),
+            reasons: reasons.clone(),
+        });
+        Err(Error::Io(std::io::Error::other(reasons.join(", then "))))
+    }
+}
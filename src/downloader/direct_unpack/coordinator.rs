@@ -43,7 +43,7 @@ pub(crate) struct DirectUnpackCoordinator {
     download_id: DownloadId,
     db: Arc<Database>,
     config: Arc<Config>,
-    event_tx: tokio::sync::broadcast::Sender<Event>,
+    event_tx: crate::event_bus::EventSender,
     cancel_token: CancellationToken,
     download_temp_dir: PathBuf,
     /// Shared counter — set by the article download pipeline on each failure
@@ -60,7 +60,7 @@ impl DirectUnpackCoordinator {
         download_id: DownloadId,
         db: Arc<Database>,
         config: Arc<Config>,
-        event_tx: tokio::sync::broadcast::Sender<Event>,
+        event_tx: crate::event_bus::EventSender,
         cancel_token: CancellationToken,
         download_temp_dir: PathBuf,
         failed_articles: Arc<AtomicU64>,
@@ -338,12 +338,26 @@ impl DirectUnpackCoordinator {
             .await
             .ok()
             .flatten();
+        let download_passwords = self
+            .db
+            .get_download_passwords(self.download_id)
+            .await
+            .unwrap_or_default();
+        let provider = self.config.tools.password_provider.as_deref();
+        let release_name = match provider {
+            Some(_) => match self.db.get_download(self.download_id).await {
+                Ok(Some(download)) => download.release_name().to_string(),
+                _ => String::new(),
+            },
+            None => String::new(),
+        };
         let passwords = PasswordList::collect(
             cached_pw.as_deref(),
-            None,
-            None,
+            download_passwords.password.as_deref(),
+            download_passwords.nzb_password.as_deref(),
             self.config.tools.password_file.as_deref(),
             self.config.tools.try_empty_password,
+            provider.map(|p| (p, release_name.as_str())),
         )
         .await;
 
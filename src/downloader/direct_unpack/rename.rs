@@ -63,7 +63,7 @@ impl DirectRenameState {
         current_filename: &str,
         temp_dir: &Path,
         db: &Database,
-        event_tx: &tokio::sync::broadcast::Sender<Event>,
+        event_tx: &crate::event_bus::EventSender,
     ) -> Option<(String, String)> {
         if !self.metadata_loaded || self.hash_to_name.is_empty() {
             return None;
@@ -46,6 +46,7 @@ fn write_all_at(_file: &std::fs::File, _buf: &[u8], _offset: u64) -> std::io::Re
 use crate::types::DownloadId;
 
 use super::context::{ArticleProvider, BatchResultVec, OutputFiles, is_missing_article_error};
+use super::damage::DamageTracker;
 
 /// Result of decoding a single article: Ok(article_id, file_index, segment_number, decoded_bytes) or Err(article_id, error_message).
 type DecodeResult = Result<(i64, i32, i32, u64), (i64, String)>;
@@ -71,6 +72,7 @@ pub(super) fn aggregate_results(results: BatchResultVec) -> super::orchestration
     }
 
     super::orchestration::DownloadResults {
+        unrepairable: None,
         success_count,
         failed_count,
         first_error,
@@ -107,6 +109,8 @@ pub(super) struct FetchArticleBatchParams {
     pub(super) pipeline_depth: usize,
     /// Tracker for per-file article completion (DirectUnpack notification)
     pub(super) file_completion_tracker: Arc<super::context::FileCompletionTracker>,
+    /// Tracker for missing data against the PAR2 recovery capacity
+    pub(super) damage_tracker: Arc<DamageTracker>,
 }
 
 /// Fetch a single batch of articles via pipelined NNTP commands.
@@ -130,6 +134,7 @@ pub(super) async fn fetch_article_batch(
         output_files,
         pipeline_depth,
         file_completion_tracker,
+        damage_tracker,
     } = params;
     let batch_size = article_batch.len();
 
@@ -184,6 +189,7 @@ pub(super) async fn fetch_article_batch(
                     failed_articles,
                     output_files,
                     file_completion_tracker,
+                    damage_tracker,
                 })
                 .await;
             }
@@ -326,6 +332,26 @@ pub(super) struct RetryArticlesParams {
     pub(super) output_files: Arc<OutputFiles>,
     /// Tracker for per-file article completion (DirectUnpack notification)
     pub(super) file_completion_tracker: Arc<super::context::FileCompletionTracker>,
+    /// Tracker for missing data against the PAR2 recovery capacity
+    pub(super) damage_tracker: Arc<DamageTracker>,
+}
+
+/// Count a missing article as damage, cancelling the download once it can't be repaired
+fn record_damage(
+    id: DownloadId,
+    damage_tracker: &DamageTracker,
+    article: &crate::db::Article,
+    cancel_token: &tokio_util::sync::CancellationToken,
+) {
+    if damage_tracker.record_missing(article) && !cancel_token.is_cancelled() {
+        tracing::warn!(
+            download_id = id.0,
+            missing_bytes = damage_tracker.missing_bytes(),
+            recovery_bytes = damage_tracker.remaining_capacity(),
+            "Missing data exceeds PAR2 recovery capacity, aborting download"
+        );
+        cancel_token.cancel();
+    }
 }
 
 /// Retry each article in a failed batch individually (pipeline_depth=1).
@@ -348,6 +374,7 @@ pub(super) async fn retry_articles_individually(
         failed_articles,
         output_files,
         file_completion_tracker,
+        damage_tracker,
     } = params;
     let batch_size = article_batch.len();
     let mut successful_results = Vec::new();
@@ -395,6 +422,7 @@ pub(super) async fn retry_articles_individually(
                             "Failed to decode/write article during individual retry"
                         );
                         failed_articles.fetch_add(1, Ordering::Relaxed);
+                        record_damage(id, &damage_tracker, article, &cancel_token);
                         if first_error.is_none() {
                             first_error = Some(format!("Failed to decode/write article: {}", e));
                         }
@@ -414,6 +442,7 @@ pub(super) async fn retry_articles_individually(
                     "Article missing (empty response)"
                 );
                 failed_articles.fetch_add(1, Ordering::Relaxed);
+                record_damage(id, &damage_tracker, article, &cancel_token);
                 if first_error.is_none() {
                     first_error = Some(format!("No such article: {}", article.message_id));
                 }
@@ -430,6 +459,7 @@ pub(super) async fn retry_articles_individually(
                     "Article fetch failed during individual retry"
                 );
                 failed_articles.fetch_add(1, Ordering::Relaxed);
+                record_damage(id, &damage_tracker, article, &cancel_token);
                 if first_error.is_none() {
                     first_error = Some(format!("No such article: {}", article.message_id));
                 }
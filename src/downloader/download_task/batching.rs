@@ -10,6 +10,7 @@ use crate::types::{DownloadId, Event, Status};
 
 use super::batch_processor::{FetchArticleBatchParams, fetch_article_batch};
 use super::context::{BatchResultVec, DownloadTaskContext, OutputFiles};
+use super::damage::DamageTracker;
 use super::orchestration::DownloadResults;
 
 /// Fetch the download record and its pending articles, transitioning to Downloading state.
@@ -47,6 +48,10 @@ pub(super) async fn fetch_download_record(
         ctx.remove_from_active().await;
         return None;
     }
+    // New articles can change the files an earlier post-processing run checked
+    if let Err(e) = ctx.db.clear_all_stage_checkpoints(id).await {
+        tracing::warn!(download_id = id.0, error = %e, "Failed to clear stage checkpoints");
+    }
 
     // Emit Downloading event (initial progress 0%)
     ctx.event_tx
@@ -73,6 +78,18 @@ pub(super) async fn fetch_download_record(
     Some((download, pending_articles))
 }
 
+/// Parameters for downloading all pending articles of a download
+pub(super) struct DownloadArticlesParams<'a> {
+    pub(super) ctx: &'a DownloadTaskContext,
+    pub(super) pending_articles: Vec<crate::db::Article>,
+    pub(super) total_size_bytes: u64,
+    pub(super) download_temp_dir: &'a std::path::Path,
+    pub(super) output_files: &'a Arc<OutputFiles>,
+    pub(super) failed_articles: &'a Arc<AtomicU64>,
+    pub(super) file_completion_tracker: &'a Arc<super::context::FileCompletionTracker>,
+    pub(super) damage_tracker: &'a Arc<DamageTracker>,
+}
+
 /// Download all pending articles in parallel batches with progress tracking.
 ///
 /// Sets up background tasks for progress reporting and database batching,
@@ -80,15 +97,17 @@ pub(super) async fn fetch_download_record(
 ///
 /// The `failed_articles` counter is created externally so it can be shared with
 /// the DirectUnpack coordinator (which cancels on any article failure).
-pub(super) async fn download_articles(
-    ctx: &DownloadTaskContext,
-    pending_articles: Vec<crate::db::Article>,
-    total_size_bytes: u64,
-    download_temp_dir: &std::path::Path,
-    output_files: &Arc<OutputFiles>,
-    failed_articles: &Arc<AtomicU64>,
-    file_completion_tracker: &Arc<super::context::FileCompletionTracker>,
-) -> DownloadResults {
+pub(super) async fn download_articles(params: DownloadArticlesParams<'_>) -> DownloadResults {
+    let DownloadArticlesParams {
+        ctx,
+        pending_articles,
+        total_size_bytes,
+        download_temp_dir,
+        output_files,
+        failed_articles,
+        file_completion_tracker,
+        damage_tracker,
+    } = params;
     let id = ctx.id;
     let total_articles = pending_articles.len();
     let counters = DownloadCounters {
@@ -117,9 +136,18 @@ pub(super) async fn download_articles(
         ctx.cancel_token.clone(),
     );
 
-    // Calculate concurrency and split articles into batches
+    // Calculate concurrency from the servers in rotation and split articles into batches
+    let servers = ctx.downloader.servers.current();
+    let enabled_servers: Vec<_> = servers
+        .servers
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| servers.is_enabled(i))
+        .map(|(_, server)| server.clone())
+        .collect();
     let (concurrency, pipeline_depth, article_batches) =
-        prepare_batches(&ctx.config, pending_articles, None);
+        prepare_batches(&ctx.config, &enabled_servers, pending_articles, None);
+    let concurrency = ctx.downloader.download_concurrency(id, concurrency);
 
     // Download all batches in parallel
     let results = download_all_batches(DownloadAllBatchesParams {
@@ -135,6 +163,7 @@ pub(super) async fn download_articles(
         concurrency,
         pipeline_depth,
         file_completion_tracker,
+        damage_tracker,
     })
     .await;
 
@@ -146,6 +175,13 @@ pub(super) async fn download_articles(
     let mut agg = super::batch_processor::aggregate_results(results);
     agg.total_articles = total_articles;
     agg.individually_failed = counters.failed_articles.load(Ordering::Relaxed);
+    if damage_tracker.is_unrepairable() {
+        agg.unrepairable = Some(format!(
+            "{} bytes of data are missing but only {} bytes of PAR2 recovery data are available",
+            damage_tracker.missing_bytes(),
+            damage_tracker.remaining_capacity()
+        ));
+    }
     agg
 }
 
@@ -248,16 +284,17 @@ pub(super) fn spawn_fast_fail_watcher(
 
 /// Calculate concurrency settings and split articles into pipeline-sized batches.
 ///
+/// Concurrency and pipeline depth come from `servers`, the servers currently in use.
 /// When `download_files` is provided and DirectRename is enabled, PAR2 file articles
 /// are sorted to the front so their metadata is available early for renaming obfuscated files.
 pub(super) fn prepare_batches(
     config: &crate::config::Config,
+    servers: &[crate::config::ServerConfig],
     mut pending_articles: Vec<crate::db::Article>,
     download_files: Option<&[crate::db::DownloadFile]>,
 ) -> (usize, usize, Vec<Vec<crate::db::Article>>) {
-    let concurrency: usize = config.servers.iter().map(|s| s.connections).sum();
-    let pipeline_depth = config
-        .servers
+    let concurrency: usize = servers.iter().map(|s| s.connections).sum();
+    let pipeline_depth = servers
         .first()
         .map(|s| s.pipeline_depth.max(1))
         .unwrap_or(10);
@@ -306,6 +343,7 @@ struct DownloadAllBatchesParams<'a> {
     concurrency: usize,
     pipeline_depth: usize,
     file_completion_tracker: &'a Arc<super::context::FileCompletionTracker>,
+    damage_tracker: &'a Arc<DamageTracker>,
 }
 
 /// Download all article batches in parallel using a buffered stream.
@@ -323,6 +361,7 @@ async fn download_all_batches(params: DownloadAllBatchesParams<'_>) -> BatchResu
         concurrency,
         pipeline_depth,
         file_completion_tracker,
+        damage_tracker,
     } = params;
     stream::iter(article_batches)
         .map(|article_batch| {
@@ -336,6 +375,7 @@ async fn download_all_batches(params: DownloadAllBatchesParams<'_>) -> BatchResu
             let failed_articles = Arc::clone(failed_articles);
             let output_files = Arc::clone(output_files);
             let file_completion_tracker = Arc::clone(file_completion_tracker);
+            let damage_tracker = Arc::clone(damage_tracker);
 
             async move {
                 fetch_article_batch(FetchArticleBatchParams {
@@ -352,11 +392,14 @@ async fn download_all_batches(params: DownloadAllBatchesParams<'_>) -> BatchResu
                     output_files,
                     pipeline_depth,
                     file_completion_tracker,
+                    damage_tracker,
                 })
                 .await
             }
         })
-        .buffer_unordered(concurrency)
+        // At least one batch at a time: with every server removed, batches fail rather
+        // than never starting
+        .buffer_unordered(concurrency.max(1))
         .collect()
         .await
 }
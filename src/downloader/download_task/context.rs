@@ -18,16 +18,34 @@ pub(crate) struct OutputFiles {
 }
 
 impl OutputFiles {
-    /// Create OutputFiles by pre-creating empty files for each download file entry.
+    /// Create OutputFiles by opening (or creating) the file for each download file entry.
+    ///
+    /// Existing files are kept as they are so data written before a restart or
+    /// carried over by a queue import survives.
     pub(super) fn create(
         download_files: &[crate::db::DownloadFile],
         temp_dir: &std::path::Path,
     ) -> std::io::Result<Self> {
         let mut files = HashMap::with_capacity(download_files.len());
         for df in download_files {
+            // Held files aren't fetched until repair asks for them
+            if df.held != 0 {
+                continue;
+            }
             let path = temp_dir.join(&df.filename);
-            let file = std::fs::File::create(&path)?;
-            files.insert(df.file_index, (file, df.filename.clone(), std::sync::atomic::AtomicBool::new(false)));
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)?;
+            files.insert(
+                df.file_index,
+                (
+                    file,
+                    df.filename.clone(),
+                    std::sync::atomic::AtomicBool::new(false),
+                ),
+            );
         }
         Ok(Self { files })
     }
@@ -98,13 +116,60 @@ pub(crate) trait ArticleProvider: Send + Sync {
 }
 
 /// Production [`ArticleProvider`] that iterates NNTP connection pools.
+///
+/// The pools are looked up for every fetch, so servers added, updated or removed at
+/// runtime apply to downloads that are already running.
 pub(crate) struct NntpArticleProvider {
-    pools: Arc<Vec<nntp_rs::NntpPool>>,
+    servers: Arc<super::super::server_pools::ServerRegistry>,
+    usage: Arc<super::super::stats::UsageCollector>,
+    /// Download this provider fetches for (for usage statistics)
+    download_id: DownloadId,
+    /// Category of the download this provider fetches for (for usage statistics)
+    category: Option<String>,
 }
 
 impl NntpArticleProvider {
-    pub(crate) fn new(pools: Arc<Vec<nntp_rs::NntpPool>>) -> Self {
-        Self { pools }
+    pub(crate) fn new(
+        servers: Arc<super::super::server_pools::ServerRegistry>,
+        usage: Arc<super::super::stats::UsageCollector>,
+        download_id: DownloadId,
+        category: Option<String>,
+    ) -> Self {
+        Self {
+            servers,
+            usage,
+            download_id,
+            category,
+        }
+    }
+}
+
+/// Record a pipelined fetch in the NNTP trace, one entry per command sent
+fn trace_fetch(
+    tracer: &super::super::nntp_trace::NntpTracer,
+    pool_idx: usize,
+    message_ids: &[&str],
+    result: &nntp_rs::Result<Vec<nntp_rs::NntpBinaryResponse>>,
+    elapsed: std::time::Duration,
+) {
+    match result {
+        Ok(responses) => {
+            for (id, response) in message_ids.iter().zip(responses) {
+                tracer.record(
+                    pool_idx,
+                    &format!("ARTICLE <{}>", id),
+                    &format!("{} {}", response.code, response.message),
+                    elapsed,
+                );
+            }
+        }
+        // The pipeline aborts on the first error, so only the batch outcome is known
+        Err(e) => tracer.record(
+            pool_idx,
+            &format!("ARTICLE x{} (pipelined)", message_ids.len()),
+            &e.to_string(),
+            elapsed,
+        ),
     }
 }
 
@@ -115,21 +180,59 @@ impl ArticleProvider for NntpArticleProvider {
         message_ids: &[&str],
         pipeline_depth: usize,
     ) -> nntp_rs::Result<Vec<nntp_rs::NntpBinaryResponse>> {
-        if self.pools.is_empty() {
+        let servers = self.servers.current();
+        if servers.pools.is_empty() {
             return Err(nntp_rs::NntpError::Other(
                 "No NNTP pools configured".to_string(),
             ));
         }
 
         let mut last_error = None;
-        for (pool_idx, pool) in self.pools.iter().enumerate() {
+        for pool_idx in servers.fetch_order() {
+            let pool = &servers.pools[pool_idx];
+            servers.mark_used(pool_idx);
+            let checkout_start = std::time::Instant::now();
             match pool.get().await {
                 Ok(mut conn) => {
-                    return conn
+                    let fetch_start = std::time::Instant::now();
+                    let result = conn
                         .fetch_articles_pipelined(message_ids, pipeline_depth)
                         .await;
+                    if servers.tracer.is_enabled() {
+                        trace_fetch(
+                            &servers.tracer,
+                            pool_idx,
+                            message_ids,
+                            &result,
+                            fetch_start.elapsed(),
+                        );
+                    }
+                    if let Ok(responses) = &result {
+                        let bytes: u64 = responses.iter().map(|r| r.data.len() as u64).sum();
+                        servers.add_quota_usage(pool_idx, bytes);
+                        if let Some(server) = servers.servers.get(pool_idx) {
+                            self.usage.record(
+                                self.download_id,
+                                &server.host,
+                                self.category.as_deref(),
+                                bytes,
+                            );
+                        }
+                        // Charge this server's budget; the global and per-download budgets
+                        // are charged by the caller
+                        if let Some(limiter) = servers.speed_limiters.get(pool_idx) {
+                            limiter.acquire(bytes).await;
+                        }
+                    }
+                    return result;
                 }
                 Err(e) => {
+                    servers.tracer.record(
+                        pool_idx,
+                        "CONNECT",
+                        &e.to_string(),
+                        checkout_start.elapsed(),
+                    );
                     tracing::warn!(
                         pool_index = pool_idx,
                         error = %e,
@@ -140,8 +243,15 @@ impl ArticleProvider for NntpArticleProvider {
             }
         }
 
-        Err(last_error
-            .unwrap_or_else(|| nntp_rs::NntpError::Other("All NNTP servers failed".to_string())))
+        Err(last_error.unwrap_or_else(|| {
+            let out_of_quota = (0..servers.pools.len())
+                .any(|i| servers.is_enabled(i) && servers.quota_exhausted(i));
+            nntp_rs::NntpError::Other(if out_of_quota {
+                "All NNTP servers are disabled or out of quota".to_string()
+            } else {
+                "All NNTP servers are disabled".to_string()
+            })
+        }))
     }
 }
 
@@ -154,7 +264,7 @@ pub(super) type BatchResultVec = Vec<std::result::Result<Vec<(i32, u64)>, (Strin
 pub(crate) struct DownloadTaskContext {
     pub(crate) id: DownloadId,
     pub(crate) db: Arc<crate::db::Database>,
-    pub(crate) event_tx: tokio::sync::broadcast::Sender<Event>,
+    pub(crate) event_tx: crate::event_bus::EventSender,
     pub(crate) article_provider: Arc<dyn ArticleProvider>,
     pub(crate) config: Arc<crate::config::Config>,
     pub(crate) active_downloads: Arc<
@@ -174,24 +284,47 @@ impl DownloadTaskContext {
         active.remove(&self.id);
     }
 
-    /// Mark the download as failed with an error message and emit the failure event.
-    pub(super) async fn mark_failed(&self, error: &str) {
-        self.mark_failed_with_stats(error, None, None, None).await;
+    /// Mark the download as failed with a failure code and error message and emit the
+    /// failure event.
+    pub(super) async fn mark_failed(&self, code: &str, error: &str) {
+        self.mark_failed_with_stats(code, error, None, None, None)
+            .await;
     }
 
-    /// Mark the download as failed with an error message and optional article stats.
+    /// Mark the download as failed with a failure code, error message and optional
+    /// article stats.
     pub(super) async fn mark_failed_with_stats(
         &self,
+        code: &str,
         error: &str,
         articles_succeeded: Option<u64>,
         articles_failed: Option<u64>,
         articles_total: Option<u64>,
     ) {
-        let _ = self
-            .db
-            .update_status(self.id, Status::Failed.to_i32())
-            .await;
-        let _ = self.db.set_error(self.id, error).await;
+        self.mark_failed_as(
+            Status::Failed,
+            code,
+            error,
+            articles_succeeded,
+            articles_failed,
+            articles_total,
+        )
+        .await;
+    }
+
+    /// Mark the download with a failure `status` (e.g. [`Status::FailedUnrepairable`]).
+    pub(super) async fn mark_failed_as(
+        &self,
+        status: Status,
+        code: &str,
+        error: &str,
+        articles_succeeded: Option<u64>,
+        articles_failed: Option<u64>,
+        articles_total: Option<u64>,
+    ) {
+        let _ = self.db.update_status(self.id, status.to_i32()).await;
+        let _ = self.db.set_failure(self.id, code, error).await;
+        self.downloader.record_history(self.id).await;
         self.event_tx
             .send(Event::DownloadFailed {
                 id: self.id,
@@ -201,11 +334,19 @@ impl DownloadTaskContext {
                 articles_total,
             })
             .ok();
+        self.downloader.finish_batch_member(self.id).await;
     }
 
-    /// Spawn post-processing as an independent background task.
-    pub(super) fn spawn_post_processing(self) {
+    /// Remove this download from active tracking and spawn post-processing as an
+    /// independent background task.
+    ///
+    /// The post-processing guard is taken before the download leaves the active map,
+    /// so [`UsenetDownloader::drain`] never observes a gap between the two phases.
+    pub(super) async fn spawn_post_processing(self) {
+        let guard = self.downloader.post_processing_guard();
+        self.remove_from_active().await;
         tokio::spawn(async move {
+            let _guard = guard;
             if let Err(e) = self.downloader.start_post_processing(self.id).await {
                 tracing::error!(
                     download_id = self.id.0,
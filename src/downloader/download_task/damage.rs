@@ -0,0 +1,104 @@
+//! Damage tracking — detect downloads that have lost more data than PAR2 can repair.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Tracks missing article bytes against the PAR2 recovery capacity declared in the NZB.
+///
+/// Article sizes from the NZB are compared on both sides: the bytes of all PAR2 recovery
+/// volumes (`*.volNN+MM.par2`) form the capacity, missing data-file bytes use it up, and
+/// missing recovery-volume bytes shrink it. Missing segments of the PAR2 index file cost
+/// nothing. Downloads without PAR2 files are never considered unrepairable here; the
+/// failure ratio rules handle them.
+#[derive(Debug, Default)]
+pub(crate) struct DamageTracker {
+    /// Whether the NZB contains any PAR2 files
+    has_par2: bool,
+    /// Total article bytes of the PAR2 recovery volumes
+    recovery_capacity: u64,
+    /// Indices of files that are PAR2 recovery volumes
+    recovery_files: HashSet<i32>,
+    /// Indices of PAR2 files that carry no recovery data (the index file)
+    index_files: HashSet<i32>,
+    /// Missing bytes in data files
+    missing_data: AtomicU64,
+    /// Missing bytes in recovery volumes
+    missing_recovery: AtomicU64,
+    /// Set once the damage exceeded the remaining recovery capacity
+    unrepairable: AtomicBool,
+}
+
+impl DamageTracker {
+    /// Build a tracker from all files and articles of a download
+    ///
+    /// Articles already marked failed by an earlier run count as missing.
+    pub(crate) fn new(files: &[crate::db::DownloadFile], articles: &[crate::db::Article]) -> Self {
+        let mut recovery_files = HashSet::new();
+        let mut index_files = HashSet::new();
+        for file in files {
+            if is_recovery_volume(&file.filename) {
+                recovery_files.insert(file.file_index);
+            } else if file.filename.to_lowercase().ends_with(".par2") {
+                index_files.insert(file.file_index);
+            }
+        }
+
+        let recovery_capacity = articles
+            .iter()
+            .filter(|article| recovery_files.contains(&article.file_index))
+            .map(|article| article.size_bytes.max(0) as u64)
+            .sum();
+
+        let tracker = Self {
+            has_par2: !recovery_files.is_empty() || !index_files.is_empty(),
+            recovery_capacity,
+            recovery_files,
+            index_files,
+            ..Default::default()
+        };
+        for article in articles
+            .iter()
+            .filter(|article| article.status == crate::db::article_status::FAILED)
+        {
+            tracker.record_missing(article);
+        }
+        tracker
+    }
+
+    /// Record a missing article; returns true if the download is now unrepairable
+    pub(crate) fn record_missing(&self, article: &crate::db::Article) -> bool {
+        let size = article.size_bytes.max(0) as u64;
+        if self.recovery_files.contains(&article.file_index) {
+            self.missing_recovery.fetch_add(size, Ordering::Relaxed);
+        } else if !self.index_files.contains(&article.file_index) {
+            self.missing_data.fetch_add(size, Ordering::Relaxed);
+        }
+
+        if self.has_par2 && self.missing_bytes() > self.remaining_capacity() {
+            self.unrepairable.store(true, Ordering::Relaxed);
+        }
+        self.is_unrepairable()
+    }
+
+    /// Whether the missing data exceeded what the recovery volumes can repair
+    pub(crate) fn is_unrepairable(&self) -> bool {
+        self.unrepairable.load(Ordering::Relaxed)
+    }
+
+    /// Bytes missing from data files
+    pub(crate) fn missing_bytes(&self) -> u64 {
+        self.missing_data.load(Ordering::Relaxed)
+    }
+
+    /// Recovery bytes still available
+    pub(crate) fn remaining_capacity(&self) -> u64 {
+        self.recovery_capacity
+            .saturating_sub(self.missing_recovery.load(Ordering::Relaxed))
+    }
+}
+
+/// Whether a filename is a PAR2 recovery volume (`name.vol03+04.par2`)
+fn is_recovery_volume(filename: &str) -> bool {
+    let lower = filename.to_lowercase();
+    lower.ends_with(".par2") && lower.contains(".vol")
+}
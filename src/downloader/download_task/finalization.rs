@@ -1,5 +1,6 @@
 //! Download finalization — evaluate results and set final download status.
 
+use crate::error::failure_code;
 use crate::types::{Event, Status};
 
 use super::context::DownloadTaskContext;
@@ -21,6 +22,7 @@ pub(super) async fn finalize_download(
         first_error,
         total_articles,
         individually_failed,
+        unrepairable,
     } = results;
 
     // Combine batch-level failures with individual article failures
@@ -28,6 +30,26 @@ pub(super) async fn finalize_download(
     let total = success_count as u64 + total_failed;
     let max_failure_ratio = ctx.config.download.max_failure_ratio;
 
+    if let Some(reason) = unrepairable {
+        tracing::error!(
+            download_id = id.0,
+            total_failed = total_failed,
+            succeeded = success_count,
+            "Download aborted - damage exceeds PAR2 recovery capacity"
+        );
+        ctx.mark_failed_as(
+            Status::FailedUnrepairable,
+            failure_code::UNREPAIRABLE,
+            &format!("Unrepairable: {}", reason),
+            Some(success_count as u64),
+            Some(total_failed),
+            Some(total_articles as u64),
+        )
+        .await;
+        ctx.remove_from_active().await;
+        return;
+    }
+
     // Handle partial or total failures
     if total_failed > 0 {
         tracing::warn!(
@@ -75,6 +97,7 @@ pub(super) async fn finalize_download(
                 "Download failed - too many article failures"
             );
             ctx.mark_failed_with_stats(
+                failure_code::MISSING_ARTICLES,
                 &error_msg,
                 Some(success_count as u64),
                 Some(total_failed),
@@ -131,6 +154,5 @@ pub(super) async fn finalize_download(
             articles_total: Some(total_articles as u64),
         })
         .ok();
-    ctx.remove_from_active().await;
-    ctx.spawn_post_processing();
+    ctx.spawn_post_processing().await;
 }
@@ -5,13 +5,19 @@
 //! - [`orchestration`] - Top-level download task lifecycle
 //! - [`batching`] - Record fetching, batch preparation, parallel downloading
 //! - [`batch_processor`] - Pipelined NNTP fetch, yEnc decode, per-article retry
+//! - [`damage`] - Missing data tracking against PAR2 recovery capacity
+//! - [`shared_articles`] - Fetching articles shared by several downloads once
+//! - [`simulated`] - Made-up article fetches for simulated downloads
 //! - [`finalization`] - Result evaluation and final status
 
 mod batch_processor;
 mod batching;
 mod context;
+mod damage;
 mod finalization;
 mod orchestration;
+mod shared_articles;
+mod simulated;
 
 // unwrap/expect are acceptable in tests for concise failure-on-error assertions
 #[allow(clippy::unwrap_used, clippy::expect_used)]
@@ -19,5 +25,7 @@ mod orchestration;
 mod tests;
 
 // Re-export public API so consumers don't need to change
-pub(crate) use context::{DownloadTaskContext, NntpArticleProvider};
+pub(crate) use context::{ArticleProvider, DownloadTaskContext, NntpArticleProvider};
 pub(crate) use orchestration::run_download_task;
+pub(crate) use shared_articles::SharedArticleCache;
+pub(crate) use simulated::SimulatedArticleProvider;
@@ -5,11 +5,14 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64};
 
 use crate::config::PostProcess;
+use crate::error::ToHttpStatus;
 use crate::types::Event;
 
-use super::batching::{download_articles, fetch_download_record};
+use super::batching::{DownloadArticlesParams, download_articles, fetch_download_record};
 use super::context::{DownloadTaskContext, OutputFiles};
+use super::damage::DamageTracker;
 use super::finalization::finalize_download;
+use super::shared_articles::SharingArticleProvider;
 
 /// Aggregated result counts from downloading article batches.
 pub(super) struct DownloadResults {
@@ -20,6 +23,8 @@ pub(super) struct DownloadResults {
     pub(super) total_articles: usize,
     /// Count of individually-failed articles (tracked via atomic, separate from batch failures)
     pub(super) individually_failed: u64,
+    /// Why the download can't be repaired, if missing data exceeded the PAR2 recovery capacity
+    pub(super) unrepairable: Option<String>,
 }
 
 /// Core download task -- orchestrates the full lifecycle of a single download.
@@ -31,7 +36,7 @@ pub(super) struct DownloadResults {
 ///    3b. Spawn DirectUnpack coordinator (if enabled)
 /// 4. Evaluate results and finalize status
 /// 5. Trigger post-processing
-pub(crate) async fn run_download_task(ctx: DownloadTaskContext) {
+pub(crate) async fn run_download_task(mut ctx: DownloadTaskContext) {
     let id = ctx.id;
 
     // Phase 1: Fetch download record and pending articles
@@ -62,8 +67,7 @@ pub(crate) async fn run_download_task(ctx: DownloadTaskContext) {
                         articles_total: None,
                     })
                     .ok();
-                ctx.remove_from_active().await;
-                ctx.spawn_post_processing();
+                ctx.spawn_post_processing().await;
                 return;
             }
             Err(e) => {
@@ -79,15 +83,12 @@ pub(crate) async fn run_download_task(ctx: DownloadTaskContext) {
     }
 
     // Phase 3: Create temp directory
-    let download_temp_dir = ctx
-        .config
-        .download
-        .temp_dir
-        .join(format!("download_{}", id.0));
+    let download_temp_dir = download.incomplete_path(&ctx.config.download.temp_dir);
     if let Err(e) = tokio::fs::create_dir_all(&download_temp_dir).await {
         let msg = format!("Failed to create temp directory: {}", e);
         tracing::error!(download_id = id.0, error = %e, "Failed to create temp directory");
-        ctx.mark_failed(&msg).await;
+        ctx.mark_failed(crate::Error::Io(e).error_code(), &msg)
+            .await;
         ctx.remove_from_active().await;
         return;
     }
@@ -98,7 +99,7 @@ pub(crate) async fn run_download_task(ctx: DownloadTaskContext) {
         Err(e) => {
             let msg = format!("Failed to get download files: {}", e);
             tracing::error!(download_id = id.0, error = %e, "Failed to get download files");
-            ctx.mark_failed(&msg).await;
+            ctx.mark_failed(e.error_code(), &msg).await;
             ctx.remove_from_active().await;
             return;
         }
@@ -115,7 +116,8 @@ pub(crate) async fn run_download_task(ctx: DownloadTaskContext) {
             Err(e) => {
                 let msg = format!("Failed to create output files: {}", e);
                 tracing::error!(download_id = id.0, error = %e, "Failed to create output files");
-                ctx.mark_failed(&msg).await;
+                ctx.mark_failed(crate::Error::Io(e).error_code(), &msg)
+                    .await;
                 ctx.remove_from_active().await;
                 return;
             }
@@ -167,18 +169,42 @@ pub(crate) async fn run_download_task(ctx: DownloadTaskContext) {
         None
     };
 
+    // Track missing data against the PAR2 recovery capacity to abort hopeless downloads
+    let damage_tracker = if ctx.config.download.abort_unrepairable {
+        match ctx.db.get_articles(id).await {
+            Ok(articles) => DamageTracker::new(&download_files, &articles),
+            Err(e) => {
+                tracing::warn!(
+                    download_id = id.0,
+                    error = %e,
+                    "Failed to load articles for damage tracking"
+                );
+                DamageTracker::default()
+            }
+        }
+    } else {
+        DamageTracker::default()
+    };
+    let damage_tracker = Arc::new(damage_tracker);
+
+    // Phase 3d: Articles other queued downloads also need are fetched once and shared
+    if ctx.config.download.deduplicate_articles {
+        share_articles(&mut ctx).await;
+    }
+
     // Phase 4: Download articles
     let _total_articles = pending_articles.len();
     let total_size_bytes = download.size_bytes as u64;
-    let results = download_articles(
-        &ctx,
+    let results = download_articles(DownloadArticlesParams {
+        ctx: &ctx,
         pending_articles,
         total_size_bytes,
-        &download_temp_dir,
-        &output_files,
-        &failed_articles,
-        &file_completion_tracker,
-    )
+        download_temp_dir: &download_temp_dir,
+        output_files: &output_files,
+        failed_articles: &failed_articles,
+        file_completion_tracker: &file_completion_tracker,
+        damage_tracker: &damage_tracker,
+    })
     .await;
 
     // Signal DirectUnpack coordinator that downloading is done
@@ -203,3 +229,33 @@ pub(crate) async fn run_download_task(ctx: DownloadTaskContext) {
     // Phase 5: Finalize based on results
     finalize_download(ctx, results, total_size_bytes).await;
 }
+
+/// Route the download's fetches through the shared article cache if other unfinished
+/// downloads need some of the same articles
+async fn share_articles(ctx: &mut DownloadTaskContext) {
+    let readers = match ctx.db.shared_article_readers(ctx.id).await {
+        Ok(readers) => readers,
+        Err(e) => {
+            tracing::warn!(
+                download_id = ctx.id.0,
+                error = %e,
+                "Failed to look up articles shared with other downloads"
+            );
+            return;
+        }
+    };
+    if readers.is_empty() {
+        return;
+    }
+
+    tracing::info!(
+        download_id = ctx.id.0,
+        shared_articles = readers.len(),
+        "Sharing articles with other queued downloads"
+    );
+    ctx.article_provider = Arc::new(SharingArticleProvider::new(
+        Arc::clone(&ctx.article_provider),
+        Arc::clone(&ctx.downloader.shared_articles),
+        readers,
+    ));
+}
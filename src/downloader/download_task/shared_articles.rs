@@ -0,0 +1,206 @@
+//! Article deduplication — fetch articles shared by several queued downloads once.
+//!
+//! Cross-posted releases often end up queued as separate NZBs that reference the same
+//! message-IDs. When a download fetches an article that other unfinished downloads
+//! still need, the raw article body is kept in the [`SharedArticleCache`]; those
+//! downloads then take it from there instead of the servers and decode it into their own
+//! files. An entry is deleted once every download that needed it has taken it. The cache
+//! lives in `temp_dir` and is cleared on startup.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use super::context::ArticleProvider;
+
+/// Directory under `temp_dir` holding shared article bodies
+const SHARED_ARTICLES_DIR: &str = ".shared-articles";
+
+/// Article bodies fetched by one download and still needed by others
+pub(crate) struct SharedArticleCache {
+    dir: PathBuf,
+    /// Downloads still expected to take each cached article, by message-ID
+    readers: Mutex<HashMap<String, usize>>,
+}
+
+impl SharedArticleCache {
+    /// Create an empty cache in `temp_dir`
+    pub(crate) fn new(temp_dir: &std::path::Path) -> Self {
+        Self {
+            dir: temp_dir.join(SHARED_ARTICLES_DIR),
+            readers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Remove the articles a previous run left behind
+    pub(crate) async fn clear(&self) {
+        self.lock().clear();
+        if let Err(e) = tokio::fs::remove_dir_all(&self.dir).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::warn!(path = %self.dir.display(), error = %e, "Failed to clear shared article cache");
+        }
+    }
+
+    fn path(&self, message_id: &str) -> PathBuf {
+        use sha2::{Digest, Sha256};
+        self.dir
+            .join(format!("{:x}", Sha256::digest(message_id.as_bytes())))
+    }
+
+    /// Whether an article is cached
+    pub(crate) fn contains(&self, message_id: &str) -> bool {
+        self.lock().contains_key(message_id)
+    }
+
+    /// Keep an article body for `readers` other downloads
+    pub(crate) async fn store(&self, message_id: &str, data: &[u8], readers: usize) {
+        if readers == 0 || self.contains(message_id) {
+            return;
+        }
+        let path = self.path(message_id);
+        let written = match tokio::fs::create_dir_all(&self.dir).await {
+            Ok(()) => tokio::fs::write(&path, data).await,
+            Err(e) => Err(e),
+        };
+        match written {
+            // Only listed once the file is complete, so readers never see a partial body
+            Ok(()) => {
+                self.lock().insert(message_id.to_string(), readers);
+            }
+            Err(e) => {
+                tracing::warn!(message_id, error = %e, "Failed to cache shared article");
+            }
+        }
+    }
+
+    /// Take a cached article body, deleting it once its last reader has taken it
+    pub(crate) async fn take(&self, message_id: &str) -> Option<Vec<u8>> {
+        let remaining = {
+            let mut readers = self.lock();
+            let remaining = readers.get_mut(message_id)?;
+            *remaining = remaining.saturating_sub(1);
+            let remaining = *remaining;
+            if remaining == 0 {
+                readers.remove(message_id);
+            }
+            remaining
+        };
+
+        let path = self.path(message_id);
+        let data = tokio::fs::read(&path).await.ok();
+        if remaining == 0
+            && let Err(e) = tokio::fs::remove_file(&path).await
+        {
+            tracing::debug!(message_id, error = %e, "Failed to remove shared article");
+        }
+        data
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, usize>> {
+        self.readers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// [`ArticleProvider`] that serves articles from the [`SharedArticleCache`] when another
+/// download already fetched them, and caches the ones other downloads still need
+pub(super) struct SharingArticleProvider {
+    inner: Arc<dyn ArticleProvider>,
+    cache: Arc<SharedArticleCache>,
+    /// Other downloads needing each of this download's articles, by bare message-ID
+    readers: HashMap<String, usize>,
+}
+
+impl SharingArticleProvider {
+    /// Wrap `inner` for a download whose articles are also needed by other downloads
+    /// as listed in `readers` (message-ID and number of other downloads)
+    pub(super) fn new(
+        inner: Arc<dyn ArticleProvider>,
+        cache: Arc<SharedArticleCache>,
+        readers: Vec<(String, i64)>,
+    ) -> Self {
+        Self {
+            inner,
+            cache,
+            readers: readers
+                .into_iter()
+                .map(|(message_id, count)| (bare(&message_id).to_string(), count.max(0) as usize))
+                .collect(),
+        }
+    }
+
+    /// Fetch the articles at `indices` from the servers into `responses`, caching the
+    /// ones other downloads need
+    async fn fetch_into(
+        &self,
+        message_ids: &[&str],
+        indices: &[usize],
+        pipeline_depth: usize,
+        responses: &mut [Option<nntp_rs::NntpBinaryResponse>],
+    ) -> nntp_rs::Result<()> {
+        if indices.is_empty() {
+            return Ok(());
+        }
+        let ids: Vec<&str> = indices.iter().map(|&i| message_ids[i]).collect();
+        let fetched = self.inner.fetch_articles(&ids, pipeline_depth).await?;
+        for (&i, response) in indices.iter().zip(fetched) {
+            let message_id = bare(message_ids[i]);
+            if let Some(&readers) = self.readers.get(message_id) {
+                self.cache.store(message_id, &response.data, readers).await;
+            }
+            responses[i] = Some(response);
+        }
+        Ok(())
+    }
+}
+
+/// Message-ID without the angle brackets, as stored for some NZBs
+fn bare(message_id: &str) -> &str {
+    message_id.trim_start_matches('<').trim_end_matches('>')
+}
+
+#[async_trait::async_trait]
+impl ArticleProvider for SharingArticleProvider {
+    async fn fetch_articles(
+        &self,
+        message_ids: &[&str],
+        pipeline_depth: usize,
+    ) -> nntp_rs::Result<Vec<nntp_rs::NntpBinaryResponse>> {
+        let mut responses: Vec<Option<nntp_rs::NntpBinaryResponse>> =
+            message_ids.iter().map(|_| None).collect();
+
+        // Fetch what isn't cached first, so a failed batch leaves the cache untouched
+        let (cached, uncached): (Vec<usize>, Vec<usize>) =
+            (0..message_ids.len()).partition(|&i| self.cache.contains(bare(message_ids[i])));
+        self.fetch_into(message_ids, &uncached, pipeline_depth, &mut responses)
+            .await?;
+
+        // Entries can go missing if another reader took the last copy in the meantime
+        let mut gone = Vec::new();
+        for i in cached {
+            let message_id = bare(message_ids[i]);
+            match self.cache.take(message_id).await {
+                Some(data) => {
+                    tracing::debug!(message_id, bytes = data.len(), "Using shared article");
+                    responses[i] = Some(nntp_rs::NntpBinaryResponse {
+                        code: 222,
+                        message: "Body follows (shared)".to_string(),
+                        data,
+                    });
+                }
+                None => gone.push(i),
+            }
+        }
+        self.fetch_into(message_ids, &gone, pipeline_depth, &mut responses)
+            .await?;
+
+        responses
+            .into_iter()
+            .map(|response| {
+                response.ok_or_else(|| {
+                    nntp_rs::NntpError::Other("Article missing from pipelined response".to_string())
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,191 @@
+//! Simulated article fetches for `download.simulate`.
+//!
+//! [`SimulatedArticleProvider`] answers fetches without contacting a server. Each of a
+//! file's articles is given a slice of the file in segment order, sized in proportion
+//! to the article's size in the NZB, and is returned as a yEnc part for that slice. The
+//! data comes from the file of the same name in `seed_dir` if there is one (whose size
+//! then decides the file's size), and is zeros otherwise. Responses are held back to
+//! keep the download as a whole, across concurrent fetches, to the configured speed.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::config::SimulateConfig;
+use crate::db::Database;
+use crate::speed_limiter::SpeedLimiter;
+use crate::types::DownloadId;
+
+use super::context::ArticleProvider;
+
+/// yEnc line length of simulated articles
+const YENC_LINE_LENGTH: usize = 128;
+
+/// The part of a file one article stands for
+#[derive(Debug, Clone, PartialEq)]
+struct ArticleSlice {
+    filename: String,
+    /// 1-based part number and number of parts in the file
+    part: u32,
+    total_parts: u32,
+    /// Byte offset of the slice in the file (0-based)
+    offset: u64,
+    len: u64,
+    file_size: u64,
+    /// File the slice is read from, if seeded
+    seed: Option<PathBuf>,
+}
+
+/// [`ArticleProvider`] that makes articles up instead of fetching them
+pub(crate) struct SimulatedArticleProvider {
+    db: Arc<Database>,
+    download_id: DownloadId,
+    /// Shared by every fetch, so concurrent batches split `speed_bps` between them
+    limiter: SpeedLimiter,
+    seed_dir: Option<PathBuf>,
+    /// Slices by message-ID (without angle brackets), loaded on the first fetch
+    slices: tokio::sync::OnceCell<HashMap<String, ArticleSlice>>,
+}
+
+impl SimulatedArticleProvider {
+    pub(crate) fn new(db: Arc<Database>, download_id: DownloadId, config: &SimulateConfig) -> Self {
+        Self {
+            db,
+            download_id,
+            limiter: SpeedLimiter::new((config.speed_bps > 0).then_some(config.speed_bps)),
+            seed_dir: config.seed_dir.clone(),
+            slices: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    async fn slices(&self) -> nntp_rs::Result<&HashMap<String, ArticleSlice>> {
+        self.slices
+            .get_or_try_init(|| async {
+                let articles = self.db.get_articles(self.download_id).await?;
+                let files = self.db.get_download_files(self.download_id).await?;
+                let filenames: HashMap<i32, String> = files
+                    .into_iter()
+                    .map(|file| (file.file_index, file.filename))
+                    .collect();
+                Ok::<_, crate::Error>(article_slices(
+                    &articles,
+                    &filenames,
+                    self.seed_dir.as_deref(),
+                ))
+            })
+            .await
+            .map_err(|e| nntp_rs::NntpError::Other(format!("Simulation failed: {}", e)))
+    }
+}
+
+/// Lay out each file's articles, in segment order, over the file's bytes
+fn article_slices(
+    articles: &[crate::db::Article],
+    filenames: &HashMap<i32, String>,
+    seed_dir: Option<&std::path::Path>,
+) -> HashMap<String, ArticleSlice> {
+    let mut by_file: HashMap<i32, Vec<&crate::db::Article>> = HashMap::new();
+    for article in articles {
+        by_file.entry(article.file_index).or_default().push(article);
+    }
+
+    let mut slices = HashMap::with_capacity(articles.len());
+    for (file_index, mut file_articles) in by_file {
+        file_articles.sort_by_key(|article| article.segment_number);
+        let filename = filenames
+            .get(&file_index)
+            .cloned()
+            .unwrap_or_else(|| format!("file_{}", file_index));
+        let nzb_size: u64 = file_articles
+            .iter()
+            .map(|article| article.size_bytes.max(0) as u64)
+            .sum();
+        let seed = seed_dir
+            .map(|dir| dir.join(&filename))
+            .and_then(|path| Some((std::fs::metadata(&path).ok()?.len(), path)));
+        let file_size = seed.as_ref().map_or(nzb_size, |(len, _)| *len);
+
+        // Offset of the byte that's `nzb_offset` into the NZB's sizes
+        let scale = |nzb_offset: u64| match nzb_size {
+            0 => 0,
+            _ => (u128::from(file_size) * u128::from(nzb_offset) / u128::from(nzb_size)) as u64,
+        };
+        let total_parts = file_articles.len() as u32;
+        let mut nzb_offset = 0;
+        for (i, article) in file_articles.into_iter().enumerate() {
+            let offset = scale(nzb_offset);
+            nzb_offset += article.size_bytes.max(0) as u64;
+            slices.insert(
+                bare_message_id(&article.message_id).to_string(),
+                ArticleSlice {
+                    filename: filename.clone(),
+                    part: i as u32 + 1,
+                    total_parts,
+                    offset,
+                    len: scale(nzb_offset) - offset,
+                    file_size,
+                    seed: seed.as_ref().map(|(_, path)| path.clone()),
+                },
+            );
+        }
+    }
+    slices
+}
+
+/// The slice's bytes, read from its seed file or zeros
+async fn slice_data(slice: &ArticleSlice) -> std::io::Result<Vec<u8>> {
+    let mut data = vec![0u8; slice.len as usize];
+    if let Some(seed) = &slice.seed {
+        let mut file = tokio::fs::File::open(seed).await?;
+        file.seek(std::io::SeekFrom::Start(slice.offset)).await?;
+        file.read_exact(&mut data).await?;
+    }
+    Ok(data)
+}
+
+fn bare_message_id(message_id: &str) -> &str {
+    message_id.trim_start_matches('<').trim_end_matches('>')
+}
+
+#[async_trait::async_trait]
+impl ArticleProvider for SimulatedArticleProvider {
+    async fn fetch_articles(
+        &self,
+        message_ids: &[&str],
+        _pipeline_depth: usize,
+    ) -> nntp_rs::Result<Vec<nntp_rs::NntpBinaryResponse>> {
+        let slices = self.slices().await?;
+
+        let mut responses = Vec::with_capacity(message_ids.len());
+        let mut bytes = 0u64;
+        for message_id in message_ids {
+            let Some(slice) = slices.get(bare_message_id(message_id)) else {
+                return Err(nntp_rs::NntpError::NoSuchArticle(message_id.to_string()));
+            };
+            let data = slice_data(slice).await.map_err(nntp_rs::NntpError::Io)?;
+            let encoded = nntp_rs::yenc_encode(
+                &data,
+                &slice.filename,
+                YENC_LINE_LENGTH,
+                Some((
+                    slice.part,
+                    slice.total_parts,
+                    slice.offset + 1,
+                    slice.offset + slice.len,
+                    slice.file_size,
+                )),
+            )?;
+            bytes += encoded.len() as u64;
+            responses.push(nntp_rs::NntpBinaryResponse {
+                code: 220,
+                message: format!("0 {} article (simulated)", message_id),
+                data: encoded,
+            });
+        }
+
+        self.limiter.acquire(bytes).await;
+        Ok(responses)
+    }
+}
@@ -29,6 +29,10 @@ fn server(connections: usize, pipeline_depth: usize) -> ServerConfig {
         connections,
         priority: 0,
         pipeline_depth,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
     }
 }
 
@@ -71,7 +75,7 @@ fn prepare_batches_sums_connections_across_all_servers() {
     let config = config_with_servers(vec![server(8, 10), server(4, 10)]);
     let articles = (0..5).map(|i| make_article(i, i as i32, 100)).collect();
 
-    let (concurrency, _, _) = prepare_batches(&config, articles, None);
+    let (concurrency, _, _) = prepare_batches(&config, &config.servers, articles, None);
 
     assert_eq!(
         concurrency, 12,
@@ -84,7 +88,7 @@ fn prepare_batches_single_server_concurrency() {
     let config = config_with_servers(vec![server(20, 10)]);
     let articles = vec![make_article(1, 1, 100)];
 
-    let (concurrency, _, _) = prepare_batches(&config, articles, None);
+    let (concurrency, _, _) = prepare_batches(&config, &config.servers, articles, None);
 
     assert_eq!(concurrency, 20);
 }
@@ -94,7 +98,7 @@ fn prepare_batches_no_servers_gives_zero_concurrency() {
     let config = config_with_servers(vec![]);
     let articles = vec![make_article(1, 1, 100)];
 
-    let (concurrency, _, _) = prepare_batches(&config, articles, None);
+    let (concurrency, _, _) = prepare_batches(&config, &config.servers, articles, None);
 
     assert_eq!(concurrency, 0);
 }
@@ -108,7 +112,7 @@ fn prepare_batches_uses_first_server_pipeline_depth() {
     let config = config_with_servers(vec![server(4, 25), server(4, 50)]);
     let articles = (0..100).map(|i| make_article(i, i as i32, 100)).collect();
 
-    let (_, pipeline_depth, _) = prepare_batches(&config, articles, None);
+    let (_, pipeline_depth, _) = prepare_batches(&config, &config.servers, articles, None);
 
     assert_eq!(
         pipeline_depth, 25,
@@ -121,7 +125,7 @@ fn prepare_batches_clamps_zero_pipeline_depth_to_one() {
     let config = config_with_servers(vec![server(4, 0)]);
     let articles = (0..5).map(|i| make_article(i, i as i32, 100)).collect();
 
-    let (_, pipeline_depth, _) = prepare_batches(&config, articles, None);
+    let (_, pipeline_depth, _) = prepare_batches(&config, &config.servers, articles, None);
 
     assert_eq!(
         pipeline_depth, 1,
@@ -134,7 +138,7 @@ fn prepare_batches_defaults_pipeline_depth_when_no_servers() {
     let config = config_with_servers(vec![]);
     let articles = vec![make_article(1, 1, 100)];
 
-    let (_, pipeline_depth, _) = prepare_batches(&config, articles, None);
+    let (_, pipeline_depth, _) = prepare_batches(&config, &config.servers, articles, None);
 
     assert_eq!(
         pipeline_depth, 10,
@@ -151,7 +155,7 @@ fn prepare_batches_creates_correct_batch_sizes() {
     let config = config_with_servers(vec![server(4, 3)]);
     let articles: Vec<_> = (0..10).map(|i| make_article(i, i as i32, 100)).collect();
 
-    let (_, _, batches) = prepare_batches(&config, articles, None);
+    let (_, _, batches) = prepare_batches(&config, &config.servers, articles, None);
 
     assert_eq!(batches.len(), 4, "10 articles / batch size 3 = 4 batches");
     assert_eq!(batches[0].len(), 3);
@@ -165,7 +169,7 @@ fn prepare_batches_preserves_article_order() {
     let config = config_with_servers(vec![server(2, 2)]);
     let articles: Vec<_> = (0..4).map(|i| make_article(i, i as i32, 100)).collect();
 
-    let (_, _, batches) = prepare_batches(&config, articles, None);
+    let (_, _, batches) = prepare_batches(&config, &config.servers, articles, None);
 
     assert_eq!(batches[0][0].id, 0);
     assert_eq!(batches[0][1].id, 1);
@@ -177,7 +181,7 @@ fn prepare_batches_preserves_article_order() {
 fn prepare_batches_empty_articles_produces_no_batches() {
     let config = config_with_servers(vec![server(4, 10)]);
 
-    let (_, _, batches) = prepare_batches(&config, vec![], None);
+    let (_, _, batches) = prepare_batches(&config, &config.servers, vec![], None);
 
     assert!(batches.is_empty());
 }
@@ -187,7 +191,7 @@ fn prepare_batches_single_article_makes_one_batch() {
     let config = config_with_servers(vec![server(4, 10)]);
     let articles = vec![make_article(42, 1, 500)];
 
-    let (_, _, batches) = prepare_batches(&config, articles, None);
+    let (_, _, batches) = prepare_batches(&config, &config.servers, articles, None);
 
     assert_eq!(batches.len(), 1);
     assert_eq!(batches[0].len(), 1);
@@ -199,7 +203,7 @@ fn prepare_batches_exactly_one_batch_when_articles_equal_pipeline_depth() {
     let config = config_with_servers(vec![server(4, 5)]);
     let articles: Vec<_> = (0..5).map(|i| make_article(i, i as i32, 100)).collect();
 
-    let (_, _, batches) = prepare_batches(&config, articles, None);
+    let (_, _, batches) = prepare_batches(&config, &config.servers, articles, None);
 
     assert_eq!(batches.len(), 1);
     assert_eq!(batches[0].len(), 5);
@@ -481,7 +485,7 @@ async fn make_test_context(
 ) -> (
     DownloadTaskContext,
     tempfile::TempDir,
-    tokio::sync::broadcast::Receiver<crate::types::Event>,
+    crate::event_bus::EventReceiver,
 ) {
     use crate::parity::NoOpParityHandler;
 
@@ -500,7 +504,7 @@ async fn make_test_context(
         .unwrap();
 
     // Broadcast channel
-    let (event_tx, event_rx) = tokio::sync::broadcast::channel(1000);
+    let (event_tx, event_rx) = crate::event_bus::channel(1000);
 
     // Speed limiter (unlimited)
     let speed_limiter = crate::speed_limiter::SpeedLimiter::new(config.download.speed_limit_bps);
@@ -522,6 +526,24 @@ async fn make_test_context(
         concurrent_limit,
         active_downloads: active_downloads.clone(),
         accepting_new: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true)),
+        draining: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        post_processing_active: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        work_finished: std::sync::Arc::new(tokio::sync::Notify::new()),
+        drain_pending: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        forced_downloads: std::sync::Arc::new(std::sync::Mutex::new(
+            std::collections::HashSet::new(),
+        )),
+        fast_lane_slot: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
+        fast_lane_downloads: std::sync::Arc::new(std::sync::Mutex::new(
+            std::collections::HashSet::new(),
+        )),
+        post_processing_paused: std::sync::Arc::new(tokio::sync::watch::Sender::new(false)),
+        post_processing_slots: std::sync::Arc::new(tokio::sync::Semaphore::new(
+            config.processing.max_parallel_jobs,
+        )),
+        post_processing_queue: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+        stalls: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+        stall_signals: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
     };
 
     // Runtime config
@@ -534,6 +556,10 @@ async fn make_test_context(
         categories,
         schedule_rules,
         next_schedule_rule_id,
+        download_speed_limiters: std::sync::Arc::new(std::sync::Mutex::new(
+            std::collections::HashMap::new(),
+        )),
+        bandwidth: std::sync::Arc::new(std::sync::Mutex::new(Default::default())),
     };
 
     // Parity + post-processor
@@ -553,8 +579,23 @@ async fn make_test_context(
     let downloader = super::super::UsenetDownloader {
         db: db_arc.clone(),
         event_tx: event_tx.clone(),
+        envelopes: super::super::events::EnvelopeRelay::spawn(
+            &event_tx,
+            db_arc.clone(),
+            0,
+            config_arc.persistence.event_journal_size,
+        ),
         config: config_arc.clone(),
-        nntp_pools: std::sync::Arc::new(Vec::new()),
+        servers: std::sync::Arc::new(
+            super::super::server_pools::ServerRegistry::new(&config_arc)
+                .await
+                .unwrap(),
+        ),
+        usage: std::sync::Arc::new(super::super::stats::UsageCollector::default()),
+        shared_articles: std::sync::Arc::new(super::SharedArticleCache::new(
+            &config_arc.download.temp_dir,
+        )),
+        digests: std::sync::Arc::new(crate::notifications::digest::DigestBuffer::new(0)),
         speed_limiter: speed_limiter.clone(),
         queue_state,
         runtime_config,
@@ -694,6 +735,7 @@ async fn finalize_all_success_marks_complete() {
             first_error: None,
             total_articles: 10,
             individually_failed: 0,
+            unrepairable: None,
         },
         1000,
     )
@@ -735,6 +777,7 @@ async fn finalize_above_threshold_marks_failed() {
             first_error: Some("batch fetch failed".to_string()),
             total_articles: 10,
             individually_failed: 0,
+            unrepairable: None,
         },
         1000,
     )
@@ -780,6 +823,7 @@ async fn finalize_at_exact_boundary_marks_complete() {
             first_error: Some("some error".to_string()),
             total_articles: 100,
             individually_failed: 0,
+            unrepairable: None,
         },
         1000,
     )
@@ -819,6 +863,7 @@ async fn finalize_zero_success_always_fails() {
             first_error: Some("total failure".to_string()),
             total_articles: 5,
             individually_failed: 0,
+            unrepairable: None,
         },
         1000,
     )
@@ -878,6 +923,7 @@ async fn fetch_article_batch_cancelled() {
             std::collections::HashMap::new(),
             fct_tx,
         )),
+        damage_tracker: Arc::new(super::damage::DamageTracker::default()),
     })
     .await;
 
@@ -923,6 +969,7 @@ async fn fetch_article_batch_success_writes_files() {
             std::collections::HashMap::new(),
             fct_tx,
         )),
+        damage_tracker: Arc::new(super::damage::DamageTracker::default()),
     })
     .await;
 
@@ -994,6 +1041,7 @@ async fn fetch_article_batch_provider_error() {
             std::collections::HashMap::new(),
             fct_tx,
         )),
+        damage_tracker: Arc::new(super::damage::DamageTracker::default()),
     })
     .await;
 
@@ -1048,9 +1096,7 @@ async fn run_download_task_full_lifecycle() {
     insert_test_articles(&ctx.db, dl_id, 3).await;
 
     // Need to configure a server for batching (pipeline_depth=1 so each article is its own batch)
-    let mut config = (*ctx.config).clone();
-    config.servers = vec![server(1, 1)];
-    ctx.config = std::sync::Arc::new(config);
+    ctx.downloader.add_server(server(1, 1)).await.unwrap();
 
     let db = ctx.db.clone();
     run_download_task(ctx).await;
@@ -1130,6 +1176,7 @@ async fn retry_articles_individually_partial_success() {
             std::collections::HashMap::new(),
             fct_tx,
         )),
+        damage_tracker: Arc::new(super::damage::DamageTracker::default()),
     })
     .await;
 
@@ -1204,6 +1251,7 @@ async fn retry_articles_individually_all_missing() {
             std::collections::HashMap::new(),
             fct_tx,
         )),
+        damage_tracker: Arc::new(super::damage::DamageTracker::default()),
     })
     .await;
 
@@ -1313,6 +1361,7 @@ async fn configurable_failure_ratio_from_config() {
             first_error: Some("missing article".to_string()),
             total_articles: 100,
             individually_failed: 0,
+            unrepairable: None,
         },
         10000,
     )
@@ -1357,6 +1406,7 @@ async fn partial_success_emits_complete_with_stats() {
             first_error: Some("missing".to_string()),
             total_articles: 100,
             individually_failed: 5, // 5 batch + 5 individual = 10 total
+            unrepairable: None,
         },
         10000,
     )
@@ -1440,6 +1490,7 @@ async fn finalize_download_keeps_partially_paused_download_paused() {
             first_error: None,
             total_articles: 1,
             individually_failed: 0,
+            unrepairable: None,
         },
         100,
     )
@@ -1491,3 +1542,314 @@ async fn run_download_task_empty_articles() {
         "status should be Downloading (empty articles skip finalize)"
     );
 }
+
+// ===================================================================
+// damage tracking tests
+// ===================================================================
+
+fn make_file(file_index: i32, filename: &str) -> crate::db::DownloadFile {
+    crate::db::DownloadFile {
+        id: file_index as i64,
+        download_id: 1,
+        file_index,
+        filename: filename.to_string(),
+        subject: None,
+        total_segments: 1,
+        paused: 0,
+        held: 0,
+        completed: 0,
+        original_filename: None,
+    }
+}
+
+fn make_file_article(id: i64, file_index: i32, size: i64) -> crate::db::Article {
+    crate::db::Article {
+        file_index,
+        ..make_article(id, 1, size)
+    }
+}
+
+/// Data file 0, PAR2 index file 1 and 300 bytes of recovery volumes in files 2 and 3
+fn par2_download() -> (Vec<crate::db::DownloadFile>, Vec<crate::db::Article>) {
+    let files = vec![
+        make_file(0, "movie.mkv"),
+        make_file(1, "movie.par2"),
+        make_file(2, "movie.vol00+01.par2"),
+        make_file(3, "movie.VOL01+02.PAR2"),
+    ];
+    let articles = vec![
+        make_file_article(1, 0, 500),
+        make_file_article(2, 0, 500),
+        make_file_article(3, 1, 50),
+        make_file_article(4, 2, 100),
+        make_file_article(5, 3, 200),
+    ];
+    (files, articles)
+}
+
+#[test]
+fn damage_within_recovery_capacity_is_repairable() {
+    let (files, articles) = par2_download();
+    let tracker = super::damage::DamageTracker::new(&files, &articles);
+    assert_eq!(tracker.remaining_capacity(), 300);
+
+    // The PAR2 index file carries no recovery data, so losing it costs nothing
+    assert!(!tracker.record_missing(&articles[2]));
+    assert_eq!(tracker.missing_bytes(), 0);
+
+    assert!(!tracker.record_missing(&make_file_article(6, 0, 300)));
+    assert!(!tracker.is_unrepairable(), "300 missing of 300 recoverable");
+}
+
+#[test]
+fn damage_beyond_recovery_capacity_is_unrepairable() {
+    let (files, articles) = par2_download();
+    let tracker = super::damage::DamageTracker::new(&files, &articles);
+
+    // Losing a recovery volume shrinks what the rest can repair
+    assert!(!tracker.record_missing(&articles[4]));
+    assert_eq!(tracker.remaining_capacity(), 100);
+
+    assert!(tracker.record_missing(&make_file_article(6, 0, 150)));
+    assert!(tracker.is_unrepairable());
+}
+
+#[test]
+fn damage_counts_articles_failed_in_earlier_runs() {
+    let (files, mut articles) = par2_download();
+    articles[0].status = crate::db::article_status::FAILED;
+
+    let tracker = super::damage::DamageTracker::new(&files, &articles);
+    assert_eq!(tracker.missing_bytes(), 500);
+    assert!(tracker.is_unrepairable());
+}
+
+#[test]
+fn damage_without_par2_is_never_unrepairable() {
+    let files = vec![make_file(0, "movie.mkv")];
+    let articles = vec![make_file_article(1, 0, 500)];
+    let tracker = super::damage::DamageTracker::new(&files, &articles);
+
+    assert!(!tracker.record_missing(&articles[0]));
+    assert!(!tracker.is_unrepairable());
+}
+
+#[tokio::test]
+async fn retry_articles_individually_cancels_once_unrepairable() {
+    let provider = Arc::new(MockArticleProvider::with_responses(vec![
+        Err(nntp_rs::NntpError::NoSuchArticle(
+            "<article-1@test>".to_string(),
+        )),
+        Err(nntp_rs::NntpError::NoSuchArticle(
+            "<article-2@test>".to_string(),
+        )),
+    ]));
+
+    let (files, articles) = par2_download();
+    let damage_tracker = Arc::new(super::damage::DamageTracker::new(&files, &articles));
+    let cancel_token = tokio_util::sync::CancellationToken::new();
+    let failed_articles = Arc::new(AtomicU64::new(0));
+    let (batch_tx, _batch_rx) = tokio::sync::mpsc::channel(100);
+    let temp_dir = tempfile::tempdir().unwrap();
+    let (fct_tx, _fct_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let result = super::batch_processor::retry_articles_individually(RetryArticlesParams {
+        id: crate::types::DownloadId(1),
+        // 500 missing bytes exceed the 300 bytes of recovery data
+        article_batch: articles[..2].to_vec(),
+        article_provider: provider,
+        batch_tx,
+        cancel_token: cancel_token.clone(),
+        download_temp_dir: temp_dir.path().to_path_buf(),
+        downloaded_bytes: Arc::new(AtomicU64::new(0)),
+        downloaded_articles: Arc::new(AtomicU64::new(0)),
+        failed_articles: failed_articles.clone(),
+        output_files: empty_output_files(),
+        file_completion_tracker: Arc::new(super::context::FileCompletionTracker::new(
+            std::collections::HashMap::new(),
+            fct_tx,
+        )),
+        damage_tracker: Arc::clone(&damage_tracker),
+    })
+    .await;
+
+    assert!(result.is_err());
+    assert!(damage_tracker.is_unrepairable());
+    assert!(cancel_token.is_cancelled(), "download should be aborted");
+    assert_eq!(
+        failed_articles.load(Ordering::Relaxed),
+        1,
+        "no further articles are fetched after the abort"
+    );
+}
+
+#[tokio::test]
+async fn finalize_unrepairable_marks_failed_unrepairable() {
+    let provider = Arc::new(MockArticleProvider::succeeding(vec![]));
+    let (mut ctx, temp_dir, mut rx) = make_test_context(provider).await;
+
+    let new_dl = make_new_download(&temp_dir);
+    let dl_id = ctx.db.insert_download(&new_dl).await.unwrap();
+    ctx.id = dl_id;
+    let db = ctx.db.clone();
+
+    finalize_download(
+        ctx,
+        DownloadResults {
+            success_count: 95,
+            failed_count: 0,
+            first_error: None,
+            total_articles: 100,
+            individually_failed: 5,
+            unrepairable: Some("too much missing".to_string()),
+        },
+        1000,
+    )
+    .await;
+
+    let db_dl = db.get_download(dl_id).await.unwrap().unwrap();
+    assert_eq!(
+        db_dl.status,
+        crate::types::Status::FailedUnrepairable.to_i32(),
+        "an unrepairable download fails even below the failure ratio"
+    );
+    assert!(db_dl.error_message.unwrap().contains("too much missing"));
+
+    match rx.try_recv().unwrap() {
+        crate::types::Event::DownloadFailed { id, error, .. } => {
+            assert_eq!(id, dl_id);
+            assert!(error.starts_with("Unrepairable"));
+        }
+        other => panic!("expected DownloadFailed event, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn nntp_provider_fails_without_connecting_when_all_servers_disabled() {
+    let (downloader, _temp_dir) = crate::downloader::test_helpers::create_test_downloader().await;
+    downloader.add_server(server(1, 1)).await.unwrap();
+    downloader.disable_server(0, None).await.unwrap();
+
+    let provider = super::NntpArticleProvider::new(
+        Arc::clone(&downloader.servers),
+        Arc::clone(&downloader.usage),
+        crate::types::DownloadId(1),
+        None,
+    );
+    match provider.fetch_articles(&["a@example"], 1).await {
+        Err(nntp_rs::NntpError::Other(message)) => {
+            assert_eq!(message, "All NNTP servers are disabled");
+        }
+        other => panic!(
+            "expected the disabled error, got {:?}",
+            other.map(|r| r.len())
+        ),
+    }
+}
+
+/// Article provider that answers with each message-ID as the body and records requests
+#[derive(Default)]
+struct RecordingArticleProvider {
+    requests: std::sync::Mutex<Vec<Vec<String>>>,
+    fail: bool,
+}
+
+#[async_trait::async_trait]
+impl ArticleProvider for RecordingArticleProvider {
+    async fn fetch_articles(
+        &self,
+        message_ids: &[&str],
+        _pipeline_depth: usize,
+    ) -> nntp_rs::Result<Vec<nntp_rs::NntpBinaryResponse>> {
+        self.requests
+            .lock()
+            .unwrap()
+            .push(message_ids.iter().map(|id| id.to_string()).collect());
+        if self.fail {
+            return Err(nntp_rs::NntpError::Other("connection reset".to_string()));
+        }
+        Ok(message_ids
+            .iter()
+            .map(|id| nntp_rs::NntpBinaryResponse {
+                code: 222,
+                message: "Body follows".into(),
+                data: id.as_bytes().to_vec(),
+            })
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn shared_articles_are_fetched_once_and_removed_after_last_reader() {
+    use super::shared_articles::SharingArticleProvider;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache = Arc::new(super::SharedArticleCache::new(temp_dir.path()));
+
+    // The first download needs one article that one other download also needs
+    let first_inner = Arc::new(RecordingArticleProvider::default());
+    let first = SharingArticleProvider::new(
+        first_inner.clone(),
+        Arc::clone(&cache),
+        vec![("shared@example".to_string(), 1)],
+    );
+    let responses = first
+        .fetch_articles(&["<shared@example>", "<own@example>"], 10)
+        .await
+        .unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(
+        *first_inner.requests.lock().unwrap(),
+        [vec!["<shared@example>", "<own@example>"]]
+    );
+    assert!(cache.contains("shared@example"));
+    assert!(!cache.contains("own@example"));
+
+    // The second download takes it from the cache, in request order
+    let second_inner = Arc::new(RecordingArticleProvider::default());
+    let second = SharingArticleProvider::new(second_inner.clone(), Arc::clone(&cache), vec![]);
+    let responses = second
+        .fetch_articles(&["<other@example>", "<shared@example>"], 10)
+        .await
+        .unwrap();
+    assert_eq!(responses[0].data, b"<other@example>");
+    assert_eq!(responses[1].data, b"<shared@example>");
+    assert_eq!(
+        *second_inner.requests.lock().unwrap(),
+        [vec!["<other@example>"]]
+    );
+
+    // Its only reader took it, so it's gone
+    assert!(!cache.contains("shared@example"));
+    let dir = temp_dir.path().join(".shared-articles");
+    assert_eq!(std::fs::read_dir(dir).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn failed_fetch_leaves_shared_articles_cached() {
+    use super::shared_articles::SharingArticleProvider;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache = Arc::new(super::SharedArticleCache::new(temp_dir.path()));
+    cache.store("shared@example", b"body", 1).await;
+
+    let failing = SharingArticleProvider::new(
+        Arc::new(RecordingArticleProvider {
+            fail: true,
+            ..Default::default()
+        }),
+        Arc::clone(&cache),
+        vec![],
+    );
+    assert!(
+        failing
+            .fetch_articles(&["<shared@example>", "<own@example>"], 10)
+            .await
+            .is_err()
+    );
+    assert!(cache.contains("shared@example"));
+
+    cache.clear().await;
+    assert!(!cache.contains("shared@example"));
+    assert!(!temp_dir.path().join(".shared-articles").exists());
+}
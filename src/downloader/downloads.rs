@@ -0,0 +1,161 @@
+//! Download listing and queue statistics.
+
+use crate::db::Download;
+use crate::error::Result;
+use crate::types::{DownloadId, DownloadInfo, DuplicateInfo, Priority, QueueStats, Status};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use super::UsenetDownloader;
+
+impl UsenetDownloader {
+    /// List all downloads in the queue, in any state
+    ///
+    /// Downloads held as possible duplicates carry the download they duplicate, and
+    /// deferred ones the URL their NZB will be fetched from.
+    pub async fn list_downloads(&self) -> Result<Vec<DownloadInfo>> {
+        let mut held_duplicates: HashMap<i64, DuplicateInfo> = self
+            .db
+            .list_held_duplicates()
+            .await?
+            .into_iter()
+            .map(|held| (held.download_id, DuplicateInfo::from(held)))
+            .collect();
+        let mut deferred_urls: HashMap<i64, String> =
+            self.db.list_deferred_nzbs().await?.into_iter().collect();
+
+        let temp_dir = &self.config.download.temp_dir;
+        Ok(self
+            .db
+            .list_downloads()
+            .await?
+            .into_iter()
+            .map(|download| {
+                let duplicate = held_duplicates.remove(&download.id);
+                let deferred_url = deferred_urls.remove(&download.id);
+                let stall_reason = self.stall_reason(DownloadId(download.id));
+                download_info(download, temp_dir, duplicate, deferred_url, stall_reason)
+            })
+            .collect())
+    }
+
+    /// Get a single download, or `None` if no download has this ID
+    pub async fn get_download(&self, id: DownloadId) -> Result<Option<DownloadInfo>> {
+        let Some(download) = self.db.get_download(id).await? else {
+            return Ok(None);
+        };
+
+        let duplicate = if download.status == Status::HeldDuplicate.to_i32() {
+            self.db
+                .get_held_duplicate(id)
+                .await?
+                .map(DuplicateInfo::from)
+        } else {
+            None
+        };
+        let deferred_url = self.db.get_deferred_nzb(id).await?;
+        Ok(Some(download_info(
+            download,
+            &self.config.download.temp_dir,
+            duplicate,
+            deferred_url,
+            self.stall_reason(id),
+        )))
+    }
+
+    /// Counts, sizes and speed of the downloads in the queue
+    pub async fn queue_stats(&self) -> Result<QueueStats> {
+        let downloads = self.db.get_all_downloads().await?;
+
+        let mut stats = QueueStats {
+            total: downloads.len(),
+            queued: 0,
+            downloading: 0,
+            paused: 0,
+            processing: 0,
+            total_speed_bps: 0,
+            total_size_bytes: 0,
+            downloaded_bytes: 0,
+            overall_progress: 0.0,
+            speed_limit_bps: self.speed_limiter.get_limit(),
+            accepting_new: self.queue_state.accepting_new.load(Ordering::SeqCst),
+            draining: self.is_draining(),
+            post_processing_paused: *self.queue_state.post_processing_paused.borrow(),
+            post_processing_running: self
+                .config
+                .processing
+                .max_parallel_jobs
+                .saturating_sub(self.queue_state.post_processing_slots.available_permits()),
+            post_processing_queued: self
+                .queue_state
+                .post_processing_queue
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .len(),
+        };
+        for download in &downloads {
+            match Status::from_i32(download.status) {
+                Status::Queued => stats.queued += 1,
+                Status::Downloading => {
+                    stats.downloading += 1;
+                    stats.total_speed_bps += download.speed_bps as u64;
+                }
+                Status::Paused => stats.paused += 1,
+                Status::Processing => stats.processing += 1,
+                _ => {}
+            }
+            stats.total_size_bytes += download.size_bytes as u64;
+            stats.downloaded_bytes += download.downloaded_bytes as u64;
+        }
+        if stats.total_size_bytes > 0 {
+            stats.overall_progress =
+                (stats.downloaded_bytes as f32 / stats.total_size_bytes as f32) * 100.0;
+        }
+
+        Ok(stats)
+    }
+}
+
+/// Build the public view of a download record
+fn download_info(
+    download: Download,
+    temp_dir: &Path,
+    duplicate: Option<DuplicateInfo>,
+    deferred_url: Option<String>,
+    stall_reason: Option<String>,
+) -> DownloadInfo {
+    let incomplete_path = download.incomplete_path(temp_dir);
+    let error = download.failure();
+    let remaining = download
+        .size_bytes
+        .saturating_sub(download.downloaded_bytes);
+    let eta_seconds = (download.speed_bps > 0
+        && download.status == Status::Downloading.to_i32()
+        && remaining > 0)
+        .then(|| (remaining as u64) / (download.speed_bps as u64));
+
+    DownloadInfo {
+        id: DownloadId(download.id),
+        name: download.name,
+        category: download.category,
+        status: Status::from_i32(download.status),
+        progress: download.progress,
+        speed_bps: download.speed_bps as u64,
+        size_bytes: download.size_bytes as u64,
+        downloaded_bytes: download.downloaded_bytes as u64,
+        eta_seconds,
+        priority: Priority::from_i32(download.priority),
+        created_at: chrono::DateTime::from_timestamp(download.created_at, 0)
+            .unwrap_or_else(chrono::Utc::now),
+        started_at: download
+            .started_at
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0)),
+        incomplete_path,
+        duplicate,
+        post_processing_paused: download.post_processing_paused,
+        deferred_url,
+        stall_reason,
+        error,
+    }
+}
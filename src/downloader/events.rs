@@ -0,0 +1,147 @@
+//! Sequenced JSON event envelopes.
+//!
+//! Events are emitted on the [`event_bus`](crate::event_bus) from many places. A relay task
+//! subscribes to it and wraps each event in an [`EventEnvelope`], so every envelope
+//! subscriber sees the same sequence number for the same event. Envelopes are sent on a
+//! second event bus, so a slow envelope subscriber has its progress envelopes coalesced
+//! like any other subscriber. The relay also queues
+//! each envelope for the database's event journal, from which
+//! [`UsenetDownloader::subscribe_from`] replays events a subscriber missed. Progress
+//! events are left out of the journal: the next one supersedes them, and journaling
+//! each would undo the batching of progress writes.
+
+use crate::Result;
+use crate::db::Database;
+use crate::event_bus::{self, Coalesce, EventReceiver, EventSender, RecvError};
+use crate::types::EventEnvelope;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use super::UsenetDownloader;
+
+/// Envelopes buffered per subscriber, matching the event channel
+const ENVELOPE_CHANNEL_CAPACITY: usize = 1000;
+
+/// Broadcasts every event as an [`EventEnvelope`]
+#[derive(Clone)]
+pub(crate) struct EnvelopeRelay {
+    envelope_tx: EventSender<EventEnvelope>,
+}
+
+impl EnvelopeRelay {
+    /// Start wrapping the events sent on `event_tx`, numbering them after `last_seq`
+    ///
+    /// Envelopes are journaled in `db`, which keeps the newest `journal_size` (none if 0).
+    /// The relay task ends once every sender of `event_tx` has been dropped.
+    pub(crate) fn spawn(
+        event_tx: &EventSender,
+        db: Arc<Database>,
+        last_seq: u64,
+        journal_size: usize,
+    ) -> Self {
+        let (envelope_tx, _rx) = event_bus::channel(ENVELOPE_CHANNEL_CAPACITY);
+        let mut event_rx = event_tx.subscribe();
+        let tx = envelope_tx.clone();
+
+        tokio::spawn(async move {
+            let mut seq = last_seq;
+            loop {
+                match event_rx.recv().await {
+                    Ok(event) => {
+                        seq += 1;
+                        let envelope = EventEnvelope::new(seq, &event);
+                        // Journaled before it's sent, so a replay started after sending includes it
+                        if journal_size > 0 && event.coalesce_key().is_none() {
+                            db.queue_journal_event(&envelope, journal_size);
+                        }
+                        tx.send(envelope).ok();
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        // Skip the lost events' numbers so subscribers can see the gap
+                        seq += skipped;
+                        tracing::warn!(skipped, "Event envelope relay lagged");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        Self { envelope_tx }
+    }
+
+    fn subscribe(&self) -> EventReceiver<EventEnvelope> {
+        self.envelope_tx.subscribe()
+    }
+}
+
+/// Journaled events after a sequence number, followed by live ones
+///
+/// Returned by [`UsenetDownloader::subscribe_from`].
+pub struct EventSubscription {
+    replay: VecDeque<EventEnvelope>,
+    live: EventReceiver<EventEnvelope>,
+    last_seq: u64,
+}
+
+impl EventSubscription {
+    /// Receive the next envelope
+    ///
+    /// Returns the replayed envelopes first, then live ones, each once and in sequence
+    /// order. Like [`EventReceiver::recv`], fails with `RecvError::Lagged` if this subscriber
+    /// fell behind (resubscribe from the last sequence number received to recover) and
+    /// with `RecvError::Closed` once the downloader is gone.
+    pub async fn recv(&mut self) -> std::result::Result<EventEnvelope, RecvError> {
+        if let Some(envelope) = self.replay.pop_front() {
+            self.last_seq = envelope.seq;
+            return Ok(envelope);
+        }
+
+        loop {
+            let envelope = self.live.recv().await?;
+            if envelope.seq > self.last_seq {
+                self.last_seq = envelope.seq;
+                return Ok(envelope);
+            }
+        }
+    }
+
+    /// Sequence number of the last envelope received, or the one subscribed from
+    pub fn last_seq(&self) -> u64 {
+        self.last_seq
+    }
+}
+
+impl UsenetDownloader {
+    /// Subscribe to events wrapped in a versioned [`EventEnvelope`]
+    ///
+    /// Unlike [`subscribe`](Self::subscribe), the JSON form of an envelope stays the same
+    /// across releases with the same [`EVENT_ENVELOPE_VERSION`](crate::types::EVENT_ENVELOPE_VERSION),
+    /// so this suits consumers that forward events to other programs. Each envelope carries
+    /// a sequence number and the time the event was emitted. Like `subscribe`, each
+    /// subscriber queues up to 1000 envelopes and a slow one has its progress envelopes
+    /// coalesced, which shows as a gap in the sequence numbers; only if that frees no room
+    /// is the oldest envelope dropped and `RecvError::Lagged` returned.
+    pub fn subscribe_json(&self) -> EventReceiver<EventEnvelope> {
+        self.envelopes.subscribe()
+    }
+
+    /// Subscribe to envelopes, starting with the journaled ones after `since_seq`
+    ///
+    /// Lets a subscriber that lagged or disconnected pick up where it left off. Only the
+    /// newest `persistence.event_journal_size` events are journaled; if older ones were
+    /// already dropped, the replay starts with the oldest kept, so the gap shows in the
+    /// sequence numbers. Progress events (`downloading`, `verify_progress`, ...) aren't
+    /// journaled, so their sequence numbers are skipped in the replay.
+    pub async fn subscribe_from(&self, since_seq: u64) -> Result<EventSubscription> {
+        // Subscribe first so nothing sent while the journal is read is missed
+        let live = self.envelopes.subscribe();
+        self.db.flush_pending_writes().await?;
+        let replay = self.db.list_events_since(since_seq).await?;
+
+        Ok(EventSubscription {
+            replay: replay.into(),
+            live,
+            last_seq: since_seq,
+        })
+    }
+}
@@ -0,0 +1,123 @@
+//! Liveness and readiness probes.
+
+use crate::db::LATEST_SCHEMA_VERSION;
+use crate::types::{HealthCheck, HealthReport};
+
+use super::UsenetDownloader;
+
+/// File written (and removed) to verify the temp directory is writable
+const TEMP_DIR_PROBE_FILE: &str = ".usenet-dl-ready-probe";
+
+impl UsenetDownloader {
+    /// Run the liveness probe
+    ///
+    /// Liveness only answers "is the process up and able to respond". By default no checks run;
+    /// enabling `health.live_check_database` additionally requires the database to answer queries.
+    pub async fn liveness(&self) -> HealthReport {
+        let health = &self.config.server.api.health;
+        let mut checks = Vec::new();
+
+        if health.live_check_database {
+            checks.push(match self.db.current_version().await {
+                Ok(_) => HealthCheck::passed("database"),
+                Err(e) => HealthCheck::failed("database", e.to_string()),
+            });
+        }
+
+        HealthReport::from_checks(checks)
+    }
+
+    /// Run the readiness probe
+    ///
+    /// Readiness answers "should traffic be routed here". Depending on `health` configuration it
+    /// verifies that:
+    /// 1. All database migrations have been applied
+    /// 2. At least one NNTP server is configured (or reachable, with `ready_probe_servers`)
+    /// 3. The temp directory is writable
+    pub async fn readiness(&self) -> HealthReport {
+        let health = &self.config.server.api.health;
+        let mut checks = Vec::new();
+
+        if health.ready_check_database {
+            checks.push(self.check_database_migrated().await);
+        }
+        if health.ready_check_servers {
+            checks.push(self.check_servers(health.ready_probe_servers).await);
+        }
+        if health.ready_check_temp_dir {
+            checks.push(self.check_temp_dir_writable().await);
+        }
+
+        HealthReport::from_checks(checks)
+    }
+
+    /// Check that the database schema is at the latest migration
+    async fn check_database_migrated(&self) -> HealthCheck {
+        match self.db.current_version().await {
+            Ok(version) if version >= LATEST_SCHEMA_VERSION => HealthCheck::passed("database"),
+            Ok(version) => HealthCheck::failed(
+                "database",
+                format!(
+                    "schema version {} is behind expected version {}",
+                    version, LATEST_SCHEMA_VERSION
+                ),
+            ),
+            Err(e) => HealthCheck::failed("database", e.to_string()),
+        }
+    }
+
+    /// Check that at least one server is configured, optionally connecting to each in turn
+    async fn check_servers(&self, probe: bool) -> HealthCheck {
+        let servers = self.get_servers();
+        if servers.is_empty() {
+            return HealthCheck::failed("servers", "no NNTP servers configured");
+        }
+
+        if !probe {
+            return HealthCheck::passed("servers");
+        }
+
+        let mut last_error = None;
+        for server in &servers {
+            let result = self.test_server(server).await;
+            if result.success {
+                return HealthCheck::passed("servers");
+            }
+            last_error = result.error;
+        }
+
+        HealthCheck::failed(
+            "servers",
+            format!(
+                "no NNTP server reachable: {}",
+                last_error.unwrap_or_else(|| "unknown error".to_string())
+            ),
+        )
+    }
+
+    /// Check that a file can be created and removed in the temp directory
+    async fn check_temp_dir_writable(&self) -> HealthCheck {
+        let probe_path = self.config.download.temp_dir.join(TEMP_DIR_PROBE_FILE);
+
+        match tokio::fs::write(&probe_path, b"ok").await {
+            Ok(()) => {
+                if let Err(e) = tokio::fs::remove_file(&probe_path).await {
+                    tracing::warn!(
+                        path = %probe_path.display(),
+                        error = %e,
+                        "Failed to remove temp dir readiness probe file"
+                    );
+                }
+                HealthCheck::passed("temp_dir")
+            }
+            Err(e) => HealthCheck::failed(
+                "temp_dir",
+                format!(
+                    "temp directory '{}' is not writable: {}",
+                    self.config.download.temp_dir.display(),
+                    e
+                ),
+            ),
+        }
+    }
+}
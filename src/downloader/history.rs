@@ -0,0 +1,254 @@
+//! History recording and retrying finished downloads from history.
+
+use crate::config::PostProcess;
+use crate::db;
+use crate::error::{Error, Result};
+use crate::types::{DownloadId, DownloadOptions, HistoryPage, Status};
+use std::path::PathBuf;
+
+use super::UsenetDownloader;
+use super::nzb::SQLITE_BATCH_SIZE;
+
+impl UsenetDownloader {
+    /// Record a download that just completed or failed in history
+    ///
+    /// Failures are logged rather than returned: history is informational and must not
+    /// turn a finished download into a failed one.
+    pub(crate) async fn record_history(&self, id: DownloadId) {
+        if let Err(e) = self.flush_download_traffic(id).await {
+            tracing::warn!(download_id = id.0, error = %e, "Failed to record download traffic");
+        }
+        if let Err(e) = self.db.record_download_history(id).await {
+            tracing::warn!(download_id = id.0, error = %e, "Failed to record download history");
+        }
+    }
+
+    /// Get a page of download history, most recently completed first
+    ///
+    /// `status` limits the page to entries that ended in that status (`Complete`,
+    /// `Failed` or `FailedUnrepairable`; other statuses match nothing).
+    pub async fn get_history(
+        &self,
+        status: Option<Status>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<HistoryPage> {
+        let status = status.map(|s| s.to_i32());
+        let items = self.db.query_history(status, limit, offset).await?;
+        let total = self.db.count_history(status).await?;
+        Ok(HistoryPage {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Search history by name and category, best matches first
+    ///
+    /// Each word of `search` has to start a word of the entry's name or category.
+    /// `status` and pagination work as in [`get_history`](Self::get_history); `total`
+    /// counts the matching entries.
+    pub async fn search_history(
+        &self,
+        search: &str,
+        status: Option<Status>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<HistoryPage> {
+        let status = status.map(|s| s.to_i32());
+        let items = self
+            .db
+            .search_history(search, status, limit, offset)
+            .await?;
+        let total = self.db.count_history_search(search, status).await?;
+        Ok(HistoryPage {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Delete history entries, returning how many were deleted
+    ///
+    /// `before` (Unix timestamp) keeps entries completed at or after it, and `status`
+    /// keeps entries with any other status. Without either, all history is deleted.
+    pub async fn clear_history(&self, before: Option<i64>, status: Option<Status>) -> Result<u64> {
+        self.ensure_writable()?;
+        self.db
+            .delete_history_filtered(before, status.map(|s| s.to_i32()))
+            .await
+    }
+
+    /// Queue a new attempt of the download recorded by a history entry
+    ///
+    /// The new download is built from the files and articles stored for the original
+    /// download, so the original must not have been deleted. `options` override the
+    /// original's settings: without a category the original category is kept, and
+    /// while the category is unchanged the original destination and post-processing
+    /// mode are kept too. The original's passwords are kept, and a new password replaces
+    /// both the one it was added with and the one found to be correct.
+    ///
+    /// Duplicate detection is skipped. The history entry is linked to the new download.
+    pub async fn retry_history(
+        &self,
+        history_id: i64,
+        options: DownloadOptions,
+    ) -> Result<DownloadId> {
+        self.ensure_writable()?;
+
+        if !self
+            .queue_state
+            .accepting_new
+            .load(std::sync::atomic::Ordering::SeqCst)
+        {
+            return Err(Error::ShuttingDown);
+        }
+
+        let entry = self
+            .db
+            .get_history_entry(history_id)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("History entry {} not found", history_id)))?;
+        let source_id = entry.download_id.ok_or_else(|| {
+            Error::NotFound(format!(
+                "History entry {} has no stored download to retry",
+                history_id
+            ))
+        })?;
+        let source = self.db.get_download(source_id).await?.ok_or_else(|| {
+            Error::NotFound(format!(
+                "Download {} recorded by history entry {} was removed",
+                source_id, history_id
+            ))
+        })?;
+        if !matches!(
+            Status::from_i32(source.status),
+            Status::Complete | Status::Failed | Status::FailedUnrepairable
+        ) {
+            return Err(Error::Busy(format!(
+                "Download {} is still in progress",
+                source_id
+            )));
+        }
+
+        if let Some(batch_id) = options.batch_id {
+            self.ensure_batch_open(batch_id).await?;
+        }
+        self.check_disk_space(source.size_bytes).await?;
+
+        let mut options = options;
+        if options.category.is_none() || options.category == source.category {
+            options
+                .destination
+                .get_or_insert_with(|| PathBuf::from(&source.destination));
+            options
+                .post_process
+                .get_or_insert(PostProcess::from_i32(source.post_process));
+        }
+        options.category = options.category.or(source.category.clone());
+        options.temp_dir = options
+            .temp_dir
+            .or_else(|| source.temp_dir.as_ref().map(PathBuf::from));
+        let source_passwords = self.db.get_download_passwords(source_id).await?;
+        let password = options.password.clone().or(source_passwords.password);
+        let cached_password = match options.password {
+            Some(_) => None,
+            None => self.db.get_cached_password(source_id).await?,
+        };
+
+        let (destination, post_process) = self.resolve_destination_and_post_process(&options).await;
+        let download_id = self
+            .db
+            .insert_download(&db::NewDownload {
+                name: source.name.clone(),
+                nzb_path: source.nzb_path.clone(),
+                nzb_meta_name: source.nzb_meta_name.clone(),
+                nzb_hash: source.nzb_hash.clone(),
+                job_name: source.job_name.clone(),
+                category: options.category.clone(),
+                destination: destination.to_string_lossy().into_owned(),
+                post_process: post_process.to_i32(),
+                priority: options.priority as i32,
+                status: Status::Queued.to_i32(),
+                size_bytes: source.size_bytes,
+            })
+            .await?;
+        self.assign_incomplete_dir(download_id, source.release_name(), &options)
+            .await?;
+
+        // Files keep their NZB names: deobfuscation renames apply to the old attempt
+        let files: Vec<db::NewDownloadFile> = self
+            .db
+            .get_download_files(source_id)
+            .await?
+            .into_iter()
+            .map(|file| db::NewDownloadFile {
+                download_id,
+                file_index: file.file_index,
+                filename: file.original_filename.unwrap_or(file.filename),
+                subject: file.subject,
+                total_segments: file.total_segments,
+            })
+            .collect();
+        self.db.insert_files_batch(&files).await?;
+
+        let articles: Vec<db::NewArticle> = self
+            .db
+            .get_articles(source_id)
+            .await?
+            .into_iter()
+            .map(|article| db::NewArticle {
+                download_id,
+                message_id: article.message_id,
+                segment_number: article.segment_number,
+                file_index: article.file_index,
+                size_bytes: article.size_bytes,
+            })
+            .collect();
+        for chunk in articles.chunks(SQLITE_BATCH_SIZE) {
+            self.db.insert_articles_batch(chunk).await?;
+        }
+
+        if password.is_some() || source_passwords.nzb_password.is_some() {
+            self.db
+                .set_download_passwords(
+                    download_id,
+                    password.as_deref(),
+                    source_passwords.nzb_password.as_deref(),
+                )
+                .await?;
+        }
+        if let Some(password) = cached_password {
+            self.db.set_correct_password(download_id, &password).await?;
+        }
+        // A deferred NZB that was never fetched is fetched again when the retry starts
+        if let Some(url) = self.db.get_deferred_nzb(source_id).await? {
+            self.db.set_deferred_nzb(download_id, &url).await?;
+        }
+        if let Some(batch_id) = options.batch_id {
+            self.db.set_download_batch(download_id, batch_id).await?;
+        }
+        if let Some(temp_dir) = &options.temp_dir {
+            self.db
+                .set_temp_dir(download_id, &temp_dir.to_string_lossy())
+                .await?;
+        }
+        self.db
+            .set_history_retried_as(history_id, download_id)
+            .await?;
+
+        tracing::info!(
+            history_id,
+            original_id = source_id.0,
+            download_id = download_id.0,
+            "Retrying download from history"
+        );
+
+        self.finalize_nzb_addition(download_id, &source.name, &options)
+            .await?;
+
+        Ok(download_id)
+    }
+}
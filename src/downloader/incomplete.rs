@@ -0,0 +1,92 @@
+//! In-progress folder naming according to `download.incomplete_layout`.
+
+use std::path::PathBuf;
+
+use crate::config::{IncompleteLayout, PathSanitizationConfig};
+use crate::error::Result;
+use crate::types::{DownloadId, DownloadOptions};
+use crate::utils::sanitize_filename;
+
+use super::UsenetDownloader;
+
+/// Longest job folder name in bytes, leaving room for a collision suffix
+const MAX_FOLDER_NAME_BYTES: usize = 200;
+
+/// Folder grouping downloads without a category in the category layout
+const UNCATEGORIZED_FOLDER: &str = "uncategorized";
+
+impl UsenetDownloader {
+    /// Pick and create the download's in-progress folder for a named layout
+    ///
+    /// The `download_{id}` default is left to [`crate::db::Download::incomplete_path`].
+    /// Creating the folder reserves its name, so two downloads added at once with the
+    /// same name still get separate folders.
+    pub(super) async fn assign_incomplete_dir(
+        &self,
+        download_id: DownloadId,
+        job_name: &str,
+        options: &DownloadOptions,
+    ) -> Result<()> {
+        let sanitization = &self.config.processing.path_sanitization;
+        let parent = match self.config.download.incomplete_layout {
+            IncompleteLayout::Id => return Ok(()),
+            IncompleteLayout::Job => PathBuf::new(),
+            IncompleteLayout::Date => {
+                PathBuf::from(chrono::Local::now().format("%Y-%m-%d").to_string())
+            }
+            IncompleteLayout::Category => PathBuf::from(
+                options
+                    .category
+                    .as_deref()
+                    .and_then(|category| folder_name(category, sanitization))
+                    .unwrap_or_else(|| UNCATEGORIZED_FOLDER.to_string()),
+            ),
+        };
+        let name = folder_name(job_name, sanitization)
+            .unwrap_or_else(|| format!("download_{}", download_id.0));
+
+        let base = options
+            .temp_dir
+            .as_deref()
+            .unwrap_or(&self.config.download.temp_dir);
+        tokio::fs::create_dir_all(base.join(&parent)).await?;
+
+        let mut suffix = 1;
+        let incomplete_dir = loop {
+            let candidate = match suffix {
+                1 => parent.join(&name),
+                n => parent.join(format!("{} ({})", name, n)),
+            };
+            match tokio::fs::create_dir(base.join(&candidate)).await {
+                Ok(()) => break candidate,
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => suffix += 1,
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        tracing::debug!(
+            download_id = download_id.0,
+            incomplete_dir = %incomplete_dir.display(),
+            "assigned in-progress folder"
+        );
+        self.db
+            .set_incomplete_dir(download_id, &incomplete_dir.to_string_lossy())
+            .await
+    }
+}
+
+/// A single safe path component derived from a job or category name
+///
+/// Returns `None` if nothing usable is left (e.g. the name was only dots).
+pub(super) fn folder_name(name: &str, sanitization: &PathSanitizationConfig) -> Option<String> {
+    let sanitized = sanitize_filename(name, sanitization).replace(['/', '\\'], "_");
+    // A leading dot would hide the folder and could clash with internal dirs
+    let trimmed = sanitized.trim().trim_start_matches('.').trim_start();
+
+    let mut end = trimmed.len().min(MAX_FOLDER_NAME_BYTES);
+    while !trimmed.is_char_boundary(end) {
+        end -= 1;
+    }
+    let truncated = trimmed[..end].trim_end();
+    (!truncated.is_empty()).then(|| truncated.to_string())
+}
@@ -1,11 +1,141 @@
 //! Startup and shutdown coordination.
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::types::{DownloadId, Event, Status};
 
 use super::UsenetDownloader;
 
 impl UsenetDownloader {
+    /// Drain the downloader for a maintenance window
+    ///
+    /// Stops accepting new downloads and stops starting queued ones, then waits until
+    /// every active download and in-flight post-processing run has finished. Once the
+    /// downloader is idle an [`Event::Drained`] is emitted and this method returns.
+    /// `Drained` is emitted once per drain: if a drain is already under way, this waits
+    /// for it instead of starting another.
+    ///
+    /// Queued and paused downloads are left untouched; they are picked up again after
+    /// [`cancel_drain`](Self::cancel_drain) or on the next start.
+    ///
+    /// If the drain is cancelled while waiting, this method returns early without
+    /// emitting `Drained`.
+    pub async fn drain(&self) -> Result<()> {
+        if self.begin_drain() {
+            self.finish_drain().await;
+        } else {
+            self.wait_until_idle().await;
+        }
+        Ok(())
+    }
+
+    /// Enter maintenance mode without waiting for the downloader to become idle
+    ///
+    /// Returns true if this started a drain, in which case the caller is expected to
+    /// run [`finish_drain`](Self::finish_drain); false if one is already under way.
+    pub(crate) fn begin_drain(&self) -> bool {
+        self.queue_state
+            .accepting_new
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        if !self
+            .queue_state
+            .draining
+            .swap(true, std::sync::atomic::Ordering::SeqCst)
+        {
+            tracing::info!("Draining: no new downloads will be accepted or started");
+        }
+        !self
+            .queue_state
+            .drain_pending
+            .swap(true, std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Wait for the drain started by [`begin_drain`](Self::begin_drain) and emit
+    /// [`Event::Drained`] once the downloader is idle
+    pub(crate) async fn finish_drain(&self) {
+        if self.wait_until_idle().await {
+            tracing::info!("Drain complete - downloader is idle");
+            self.emit_event(Event::Drained);
+        } else {
+            tracing::info!("Drain cancelled before the downloader became idle");
+        }
+    }
+
+    /// Wait until no download or post-processing run is active
+    ///
+    /// Returns false if the drain is cancelled first.
+    async fn wait_until_idle(&self) -> bool {
+        loop {
+            // Registered before checking, so work finishing in between still wakes us
+            let finished = self.queue_state.work_finished.notified();
+            tokio::pin!(finished);
+            finished.as_mut().enable();
+
+            if !self.is_draining() {
+                return false;
+            }
+
+            let active_count = self.queue_state.active_downloads.lock().await.len();
+            let processing_count = self
+                .queue_state
+                .post_processing_active
+                .load(std::sync::atomic::Ordering::SeqCst);
+            if active_count == 0 && processing_count == 0 {
+                return true;
+            }
+
+            tracing::debug!(
+                active_count,
+                processing_count,
+                "Waiting for downloads and post-processing to drain"
+            );
+            finished.await;
+        }
+    }
+
+    /// Leave maintenance mode after [`drain`](Self::drain)
+    ///
+    /// Re-enables accepting new downloads and lets the queue processor start queued
+    /// downloads again. Does nothing if the downloader is not draining (in particular,
+    /// it never re-opens a downloader that is shutting down).
+    pub fn cancel_drain(&self) {
+        if self
+            .queue_state
+            .draining
+            .swap(false, std::sync::atomic::Ordering::SeqCst)
+        {
+            self.queue_state
+                .accepting_new
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+            self.queue_state
+                .drain_pending
+                .store(false, std::sync::atomic::Ordering::SeqCst);
+            self.queue_state.work_finished.notify_waiters();
+            tracing::info!("Drain cancelled - accepting new downloads again");
+        }
+    }
+
+    /// Whether the downloader is currently draining (or drained) for maintenance
+    pub fn is_draining(&self) -> bool {
+        self.queue_state
+            .draining
+            .load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Whether the downloader runs in read-only mode (see [`Config::read_only`])
+    ///
+    /// [`Config::read_only`]: crate::config::Config::read_only
+    pub fn is_read_only(&self) -> bool {
+        self.config.read_only
+    }
+
+    /// Refuse queue changes on a read-only instance
+    pub(crate) fn ensure_writable(&self) -> Result<()> {
+        if self.is_read_only() {
+            return Err(Error::ReadOnly);
+        }
+        Ok(())
+    }
+
     /// Gracefully shut down the downloader
     ///
     /// This method performs a graceful shutdown sequence:
@@ -51,7 +181,20 @@ impl UsenetDownloader {
             }
         }
 
+        // Read-only instances leave the shared database to the writable one
+        if self.is_read_only() {
+            let _ = self.event_tx.send(Event::Shutdown);
+            tracing::info!("Graceful shutdown complete (read-only, database left untouched)");
+            return Ok(());
+        }
+
         // 4. Persist final state
+        if let Err(e) = self.db.flush_pending_writes().await {
+            tracing::error!(error = %e, "Failed to flush queued progress during shutdown");
+        }
+        if let Err(e) = self.rollup_usage_stats().await {
+            tracing::warn!(error = %e, "Failed to roll up usage statistics during shutdown");
+        }
         if let Err(e) = self.persist_all_state().await {
             tracing::error!(error = %e, "Failed to persist final state during shutdown");
             // Continue with shutdown even if persistence fails
@@ -67,6 +210,9 @@ impl UsenetDownloader {
             tracing::info!("Marked clean shutdown in database");
         }
 
+        // Digest-mode webhooks get what's left of their current period
+        self.send_webhook_digests().await;
+
         // 6. Emit shutdown event
         let _ = self.event_tx.send(Event::Shutdown);
 
@@ -2,34 +2,61 @@
 //!
 //! The `UsenetDownloader` struct and its methods are organized by domain:
 //! - [`queue`] - Priority queue management
+//! - [`batch`] - Download groups and batch completion hooks
 //! - [`control`] - Download lifecycle control (pause/resume/cancel)
 //! - [`config_ops`] - Runtime configuration updates
 //! - [`rss`] - RSS feed management
-//! - [`server`] - Server connectivity testing
+//! - [`server`] - Server connectivity testing and NNTP traces
+//! - [`server_pools`] - Adding, updating and removing servers at runtime
+//! - [`nntp_trace`] - Per-server NNTP command trace buffers
+//! - [`stats`] - Usage statistics per server and category
+//! - [`health`] - Liveness and readiness probes
 //! - [`lifecycle`] - Startup and shutdown coordination
+//! - [`backup`] - Backup and restore of configuration and database
 //! - [`nzb`] - NZB file parsing and ingestion
+//! - [`deferred`] - Downloads whose NZB is fetched when they start
+//! - [`history`] - History recording and retries
 //! - [`webhooks`] - Webhook and script notifications
+//! - [`events`] - Sequenced JSON event envelopes
 //! - [`tasks`] - Legacy download task spawning
 //! - [`queue_processor`] - Queue processing and orchestration
 //! - [`download_task`] - Core download execution
 //! - [`background_tasks`] - Progress reporting and batch updates
 //! - [`services`] - Background service starters
+//! - [`watchdog`] - Stalled download detection
 //! - [`post_process`] - Post-processing pipeline entry
 
 mod background_tasks;
+mod backup;
+mod batch;
+mod completion;
 mod config_ops;
 mod control;
+mod deferred;
 pub(crate) mod direct_unpack;
 mod download_task;
+mod downloads;
+mod events;
+mod health;
+mod history;
+mod incomplete;
 mod lifecycle;
+mod nntp_trace;
 mod nzb;
+mod nzb_backup;
+mod nzb_split;
 mod post_process;
 mod queue;
+mod queue_export;
 mod queue_processor;
 mod rss;
 mod server;
+mod server_pools;
 mod services;
+mod sidecar;
+mod stats;
 mod tasks;
+mod watchdog;
 mod webhooks;
 
 // unwrap/expect are acceptable in tests for concise failure-on-error assertions
@@ -41,6 +68,7 @@ pub(crate) mod test_helpers;
 mod tests;
 
 // Re-export parameter structs for testing
+pub use events::EventSubscription;
 pub use webhooks::{TriggerScriptsParams, TriggerWebhooksParams};
 
 use crate::config::Config;
@@ -67,6 +95,40 @@ pub(crate) struct QueueState {
     >,
     /// Flag to indicate whether new downloads are accepted (set to false during shutdown)
     pub(crate) accepting_new: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    /// Flag set while draining for maintenance (queued downloads are not started)
+    pub(crate) draining: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    /// Number of post-processing runs currently in flight
+    pub(crate) post_processing_active: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    /// Signalled when a download task or post-processing run ends, or a drain is cancelled
+    pub(crate) work_finished: std::sync::Arc<tokio::sync::Notify>,
+    /// Set from the start of a drain until it's cancelled, so each drain waits and emits
+    /// `Drained` once
+    pub(crate) drain_pending: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    /// Active `force` priority downloads, which hold back every other download
+    pub(crate) forced_downloads:
+        std::sync::Arc<std::sync::Mutex<std::collections::HashSet<DownloadId>>>,
+    /// Slot for a small download started while every download slot is taken
+    pub(crate) fast_lane_slot: std::sync::Arc<tokio::sync::Semaphore>,
+    /// Downloads running in the fast lane, which fetch over its reserved connections only
+    pub(crate) fast_lane_downloads:
+        std::sync::Arc<std::sync::Mutex<std::collections::HashSet<DownloadId>>>,
+    /// Whether post-processing is paused queue-wide; also signalled when a download's
+    /// own post-processing pause changes, so waiting runs re-check
+    pub(crate) post_processing_paused: std::sync::Arc<tokio::sync::watch::Sender<bool>>,
+    /// Semaphore to limit concurrent post-processing runs (respects max_parallel_jobs config)
+    pub(crate) post_processing_slots: std::sync::Arc<tokio::sync::Semaphore>,
+    /// Downloads waiting for a post-processing slot, oldest first
+    pub(crate) post_processing_queue: std::sync::Arc<std::sync::Mutex<Vec<DownloadId>>>,
+    /// Downloads the stall watchdog acted on, with the latest reason and the number of stalls
+    pub(crate) stalls: std::sync::Arc<
+        std::sync::Mutex<std::collections::HashMap<DownloadId, watchdog::StallRecord>>,
+    >,
+    /// Running download tasks and post-processing, each with a channel to stop it when it stalls
+    pub(crate) stall_signals: std::sync::Arc<
+        std::sync::Mutex<
+            std::collections::HashMap<DownloadId, tokio::sync::oneshot::Sender<watchdog::Stalled>>,
+        >,
+    >,
 }
 
 /// Runtime-mutable configuration (separate from static config)
@@ -81,6 +143,20 @@ pub(crate) struct RuntimeConfig {
         std::sync::Arc<tokio::sync::RwLock<Vec<crate::config::ScheduleRule>>>,
     /// Next schedule rule ID counter
     pub(crate) next_schedule_rule_id: std::sync::Arc<std::sync::atomic::AtomicI64>,
+    /// Per-download speed limiters, each a child of the global limiter
+    pub(crate) download_speed_limiters: std::sync::Arc<
+        std::sync::Mutex<std::collections::HashMap<DownloadId, speed_limiter::SpeedLimiter>>,
+    >,
+    /// Bandwidth mode set by the host application
+    pub(crate) bandwidth: std::sync::Arc<std::sync::Mutex<BandwidthState>>,
+}
+
+/// Current bandwidth mode, and the speed limit to go back to in normal mode
+#[derive(Debug, Default)]
+pub(crate) struct BandwidthState {
+    pub(crate) mode: crate::types::BandwidthMode,
+    /// Global speed limit when normal mode was left
+    pub(crate) normal_limit: Option<u64>,
 }
 
 /// Post-processing and parity handling
@@ -98,12 +174,20 @@ pub struct UsenetDownloader {
     /// Database instance for persistence (wrapped in Arc for sharing across tasks)
     /// Public for integration tests to query download status
     pub db: std::sync::Arc<Database>,
-    /// Event broadcast channel sender (multiple subscribers supported)
-    pub(crate) event_tx: tokio::sync::broadcast::Sender<crate::types::Event>,
+    /// Event bus sender (multiple subscribers supported)
+    pub(crate) event_tx: crate::event_bus::EventSender,
+    /// Events re-broadcast as sequenced JSON envelopes
+    pub(crate) envelopes: events::EnvelopeRelay,
     /// Configuration (wrapped in Arc for sharing across tasks)
     pub(crate) config: std::sync::Arc<Config>,
-    /// NNTP connection pools (one per server, wrapped in Arc for sharing across tasks)
-    pub(crate) nntp_pools: std::sync::Arc<Vec<nntp_rs::NntpPool>>,
+    /// Servers in use with their NNTP connection pools, speed limiters and traces
+    pub(crate) servers: std::sync::Arc<server_pools::ServerRegistry>,
+    /// Usage counters awaiting rollup into the statistics tables
+    pub(crate) usage: std::sync::Arc<stats::UsageCollector>,
+    /// Articles fetched for one download that other queued downloads also need
+    pub(crate) shared_articles: std::sync::Arc<download_task::SharedArticleCache>,
+    /// Payloads held for digest-mode webhooks until their next digest
+    pub(crate) digests: std::sync::Arc<crate::notifications::digest::DigestBuffer>,
     /// Global speed limiter shared across all downloads (token bucket algorithm)
     pub(crate) speed_limiter: speed_limiter::SpeedLimiter,
     /// Queue and download state management
@@ -120,6 +204,7 @@ pub(crate) struct QueuedDownload {
     pub(crate) id: DownloadId,
     pub(crate) priority: Priority,
     pub(crate) created_at: i64, // Unix timestamp for tie-breaking
+    pub(crate) size_bytes: u64,
 }
 
 // Implement Ord for BinaryHeap (max-heap by default)
@@ -150,7 +235,7 @@ impl UsenetDownloader {
     /// - Opens/creates the SQLite database
     /// - Runs migrations
     /// - Creates NNTP connection pools for each configured server
-    /// - Sets up the event broadcast channel
+    /// - Sets up the event bus
     pub async fn new(config: Config) -> Result<Self> {
         // Ensure download and temp directories exist
         tokio::fs::create_dir_all(&config.download.download_dir)
@@ -178,24 +263,35 @@ impl UsenetDownloader {
                 ))
             })?;
 
-        // Initialize database
-        let db = Database::new(&config.persistence.database_path).await?;
+        // Initialize database (read-only instances leave migrations to a writable one)
+        let db = if config.read_only {
+            let db = Database::open(&config.persistence.database_path).await?;
+            let version = db.current_version().await?;
+            if version < crate::db::LATEST_SCHEMA_VERSION {
+                return Err(Error::Config {
+                    message: format!(
+                        "read_only: database schema is at version {} but version {} is required; \
+                         start a writable instance to migrate it first",
+                        version,
+                        crate::db::LATEST_SCHEMA_VERSION
+                    ),
+                    key: Some("read_only".to_string()),
+                });
+            }
+            db
+        } else {
+            let db = Database::new(&config.persistence.database_path).await?;
 
-        // Mark that we're starting up (for unclean shutdown detection)
-        db.set_clean_start().await?;
+            // Mark that we're starting up (for unclean shutdown detection)
+            db.set_clean_start().await?;
+            db
+        };
 
-        // Create broadcast channel with buffer size of 1000 events
-        // This allows multiple subscribers to receive all events independently
-        let (event_tx, _rx) = tokio::sync::broadcast::channel(1000);
+        // Create the event bus; each subscriber queues up to 1000 events independently
+        let (event_tx, _rx) = crate::event_bus::channel(1000);
 
         // Create NNTP connection pools for each server
-        let mut nntp_pools = Vec::with_capacity(config.servers.len());
-        for server in &config.servers {
-            let pool = nntp_rs::NntpPool::new(server.clone().into(), server.connections as u32)
-                .await
-                .map_err(|e| Error::Nntp(format!("Failed to create NNTP pool: {}", e)))?;
-            nntp_pools.push(pool);
-        }
+        let servers = server_pools::ServerRegistry::new(&config).await?;
 
         // Create priority queue (empty initially, will be loaded from database on startup)
         let queue =
@@ -212,6 +308,20 @@ impl UsenetDownloader {
 
         // Create speed limiter with configured limit (or unlimited if not set)
         let speed_limiter = speed_limiter::SpeedLimiter::new(config.download.speed_limit_bps);
+        speed_limiter.set_burst(config.download.speed_limit_burst_bytes);
+
+        // Password lookups go through the injected provider, else the configured endpoint
+        let mut config = config;
+        if config.tools.password_provider.is_none()
+            && let Some(url) = &config.tools.password_lookup_url
+        {
+            config.tools.password_provider = Some(std::sync::Arc::new(
+                crate::extraction::HttpPasswordProvider::new(
+                    url.clone(),
+                    config.tools.password_lookup_timeout,
+                )?,
+            ));
+        }
 
         // Create config Arc early so we can share it
         let config_arc = std::sync::Arc::new(config.clone());
@@ -236,29 +346,53 @@ impl UsenetDownloader {
                 std::sync::Arc::clone(handler)
             } else if let Some(ref par2_path) = config.tools.par2_path {
                 // Use explicitly configured binary path
-                std::sync::Arc::new(CliParityHandler::new(par2_path.clone()))
+                std::sync::Arc::new(
+                    CliParityHandler::new(par2_path.clone())
+                        .with_process_priority(config.tools.process_priority.clone()),
+                )
             } else if config.tools.search_path {
                 // Search PATH for par2 binary
                 CliParityHandler::from_path()
-                    .map(|h| std::sync::Arc::new(h) as std::sync::Arc<dyn ParityHandler>)
+                    .map(|h| {
+                        std::sync::Arc::new(
+                            h.with_process_priority(config.tools.process_priority.clone()),
+                        ) as std::sync::Arc<dyn ParityHandler>
+                    })
                     .unwrap_or_else(|| std::sync::Arc::new(NoOpParityHandler))
             } else {
                 // No binary configured and PATH search disabled
                 std::sync::Arc::new(NoOpParityHandler)
             };
 
-        // Log parity handler capabilities
+        // Probe the handler's optional features, then log its capabilities
+        parity_handler.probe().await;
         let parity_caps = parity_handler.capabilities();
         tracing::info!(
             parity_handler = parity_handler.name(),
             can_verify = parity_caps.can_verify,
             can_repair = parity_caps.can_repair,
+            can_create = parity_caps.can_create,
+            multithreaded = parity_caps.multithreaded,
+            version = parity_caps.version.as_deref(),
             "Parity handler initialized"
         );
 
         // Create database Arc for sharing
         let db_arc = std::sync::Arc::new(db);
 
+        // Sequence numbers continue from the journal; read-only instances don't journal
+        let journal_size = if config.read_only {
+            0
+        } else {
+            config.persistence.event_journal_size
+        };
+        let envelopes = events::EnvelopeRelay::spawn(
+            &event_tx,
+            db_arc.clone(),
+            db_arc.latest_event_seq().await?,
+            journal_size,
+        );
+
         // Create post-processing pipeline executor
         let post_processor = std::sync::Arc::new(post_processing::PostProcessor::new(
             event_tx.clone(),
@@ -273,6 +407,28 @@ impl UsenetDownloader {
             concurrent_limit,
             active_downloads,
             accepting_new: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true)),
+            draining: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            post_processing_active: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            work_finished: std::sync::Arc::new(tokio::sync::Notify::new()),
+            drain_pending: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            forced_downloads: std::sync::Arc::new(std::sync::Mutex::new(
+                std::collections::HashSet::new(),
+            )),
+            fast_lane_slot: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
+            fast_lane_downloads: std::sync::Arc::new(std::sync::Mutex::new(
+                std::collections::HashSet::new(),
+            )),
+            post_processing_paused: std::sync::Arc::new(tokio::sync::watch::Sender::new(
+                db_arc.is_post_processing_paused().await?,
+            )),
+            post_processing_slots: std::sync::Arc::new(tokio::sync::Semaphore::new(
+                config_arc.processing.max_parallel_jobs,
+            )),
+            post_processing_queue: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            stalls: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            stall_signals: std::sync::Arc::new(std::sync::Mutex::new(
+                std::collections::HashMap::new(),
+            )),
         };
 
         // Group runtime configuration
@@ -280,8 +436,14 @@ impl UsenetDownloader {
             categories,
             schedule_rules,
             next_schedule_rule_id,
+            download_speed_limiters: std::sync::Arc::new(std::sync::Mutex::new(
+                std::collections::HashMap::new(),
+            )),
+            bandwidth: std::sync::Arc::new(std::sync::Mutex::new(BandwidthState::default())),
         };
 
+        crate::log_stream::configure(&config.logging);
+
         // Group post-processing pipeline
         let processing = ProcessingPipeline {
             post_processor,
@@ -291,20 +453,68 @@ impl UsenetDownloader {
         let downloader = Self {
             db: db_arc,
             event_tx,
+            envelopes,
             config: config_arc,
-            nntp_pools: std::sync::Arc::new(nntp_pools),
+            servers: std::sync::Arc::new(servers),
+            usage: std::sync::Arc::new(stats::UsageCollector::default()),
+            shared_articles: std::sync::Arc::new(download_task::SharedArticleCache::new(
+                &config.download.temp_dir,
+            )),
+            digests: std::sync::Arc::new(crate::notifications::digest::DigestBuffer::new(
+                chrono::Utc::now().timestamp(),
+            )),
             speed_limiter,
             queue_state,
             runtime_config,
             processing,
         };
 
+        // Read-only instances only report the queue; the writable instance runs it
+        if downloader.is_read_only() {
+            tracing::info!(
+                "Read-only mode: queue changes, folder watching and RSS auto-download are disabled"
+            );
+            return Ok(downloader);
+        }
+
+        // Buffered progress and article results are written on an interval
+        background_tasks::spawn_write_flusher(
+            std::sync::Arc::downgrade(&downloader.db),
+            std::time::Duration::from_millis(
+                downloader.config.persistence.progress_flush_interval_ms,
+            ),
+        );
+
+        if downloader.config.download.simulate.enabled {
+            tracing::warn!("Simulation mode: article fetches are made up, no server is contacted");
+        }
+
+        // Shared articles are only tracked in memory, so a previous run's are orphaned
+        downloader.shared_articles.clear().await;
+
+        // Linked originals whose retention ran out while we weren't running
+        if downloader.config.download.move_mode == crate::config::MoveMode::Link {
+            crate::post_processing::remove_expired_linked_originals(
+                &downloader.config.download.temp_dir,
+                downloader.config.download.link_retention,
+            )
+            .await;
+        }
+
+        // Servers disabled in a previous session stay out of rotation
+        downloader
+            .restore_disabled_servers(&downloader.servers.current())
+            .await?;
+        downloader
+            .load_quota_usage(&downloader.servers.current())
+            .await?;
+
         // Restore any incomplete downloads from database (from previous session)
         let needs_post_processing = downloader.restore_queue().await?;
         for id in needs_post_processing {
             let dl = downloader.clone();
             tokio::spawn(async move {
-                if let Err(e) = dl.start_post_processing(id).await {
+                if let Err(e) = dl.resume_post_processing(id).await {
                     tracing::error!(download_id = id.0, error = %e, "Post-processing failed during restore");
                 }
             });
@@ -316,8 +526,10 @@ impl UsenetDownloader {
     /// Subscribe to download events
     ///
     /// Multiple subscribers are supported. Each subscriber receives all events independently.
-    /// Events are buffered, but if a subscriber falls behind by more than 1000 events,
-    /// it will receive a `RecvError::Lagged` error.
+    /// Each subscriber has its own queue of up to 1000 events. When a subscriber falls
+    /// behind, its queued progress events are coalesced (only the newest per download and
+    /// kind is kept); only if that frees no room is the oldest event dropped and
+    /// `RecvError::Lagged` returned.
     ///
     /// # Examples
     ///
@@ -347,7 +559,7 @@ impl UsenetDownloader {
     ///     Ok(())
     /// }
     /// ```
-    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<crate::types::Event> {
+    pub fn subscribe(&self) -> crate::event_bus::EventReceiver {
         self.event_tx.subscribe()
     }
 
@@ -389,6 +601,9 @@ impl UsenetDownloader {
             parity: crate::types::ParityCapabilitiesInfo {
                 can_verify: parity_caps.can_verify,
                 can_repair: parity_caps.can_repair,
+                can_create: parity_caps.can_create,
+                multithreaded: parity_caps.multithreaded,
+                version: parity_caps.version,
                 handler: handler_name,
             },
         }
@@ -397,7 +612,7 @@ impl UsenetDownloader {
     /// Emit an event to all subscribers
     ///
     /// This is an internal helper method used throughout the codebase to emit events.
-    /// Events are sent to all active subscribers via the broadcast channel.
+    /// Events are sent to all active subscribers via the event bus.
     ///
     /// If there are no active subscribers, the event is silently dropped (ok() converts Err to None).
     /// This allows the download process to continue even if no one is listening to events.
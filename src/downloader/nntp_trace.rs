@@ -0,0 +1,136 @@
+//! Per-server NNTP command tracing for protocol debugging.
+//!
+//! When `logging.nntp_trace` is enabled, every command the downloader issues and the
+//! response it gets back is kept in a fixed-size ring buffer per server. Configured
+//! passwords and `AUTHINFO PASS` arguments are redacted before anything is stored.
+
+use crate::config::{Config, ServerConfig};
+use crate::types::NntpTraceEntry;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Replacement text for redacted credentials
+const REDACTED: &str = "[redacted]";
+
+/// Ring buffers of recent NNTP exchanges, one per configured server
+pub(crate) struct NntpTracer {
+    enabled: bool,
+    capacity: usize,
+    /// Configured server passwords, scrubbed from every recorded line
+    secrets: Vec<String>,
+    /// Indexed like the servers in use (and the NNTP pools)
+    buffers: Vec<Arc<Mutex<VecDeque<NntpTraceEntry>>>>,
+}
+
+impl NntpTracer {
+    /// Create a tracer for the servers in `config`
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            enabled: config.logging.nntp_trace,
+            capacity: config.logging.nntp_trace_capacity,
+            secrets: server_secrets(&config.servers),
+            buffers: config.servers.iter().map(|_| Arc::default()).collect(),
+        }
+    }
+
+    /// Create a tracer for a changed server list
+    ///
+    /// `previous[i]` is the index in `self` of the server now at index `i`, if it was
+    /// already there; its recorded exchanges carry over.
+    pub(crate) fn rebuild(&self, servers: &[ServerConfig], previous: &[Option<usize>]) -> Self {
+        Self {
+            enabled: self.enabled,
+            capacity: self.capacity,
+            secrets: server_secrets(servers),
+            buffers: (0..servers.len())
+                .map(|i| {
+                    previous
+                        .get(i)
+                        .copied()
+                        .flatten()
+                        .and_then(|previous| self.buffers.get(previous))
+                        .cloned()
+                        .unwrap_or_default()
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether tracing is enabled (callers skip building trace strings otherwise)
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Record one exchange for the server at `server_index`
+    pub(crate) fn record(
+        &self,
+        server_index: usize,
+        command: &str,
+        response: &str,
+        duration: Duration,
+    ) {
+        if !self.enabled || self.capacity == 0 {
+            return;
+        }
+        let Some(buffer) = self.buffers.get(server_index) else {
+            return;
+        };
+
+        let entry = NntpTraceEntry {
+            timestamp: chrono::Utc::now(),
+            command: self.redact(command),
+            response: self.redact(response),
+            duration_ms: duration.as_millis() as u64,
+        };
+
+        tracing::trace!(
+            server_index,
+            command = %entry.command,
+            response = %entry.response,
+            duration_ms = entry.duration_ms,
+            "nntp exchange"
+        );
+
+        let mut buffer = buffer.lock().unwrap_or_else(|e| e.into_inner());
+        if buffer.len() >= self.capacity {
+            buffer.pop_front();
+        }
+        buffer.push_back(entry);
+    }
+
+    /// Recorded exchanges for a server, oldest first (`None` for an unknown server)
+    pub(crate) fn entries(&self, server_index: usize) -> Option<Vec<NntpTraceEntry>> {
+        let buffer = self.buffers.get(server_index)?;
+        let buffer = buffer.lock().unwrap_or_else(|e| e.into_inner());
+        Some(buffer.iter().cloned().collect())
+    }
+
+    /// Strip credentials from a command or response line
+    fn redact(&self, line: &str) -> String {
+        let mut redacted = if line
+            .get(..13)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("AUTHINFO PASS"))
+        {
+            format!("AUTHINFO PASS {}", REDACTED)
+        } else {
+            line.to_string()
+        };
+
+        for secret in &self.secrets {
+            if redacted.contains(secret.as_str()) {
+                redacted = redacted.replace(secret.as_str(), REDACTED);
+            }
+        }
+        redacted
+    }
+}
+
+/// Configured server passwords, scrubbed from every recorded line
+fn server_secrets(servers: &[ServerConfig]) -> Vec<String> {
+    servers
+        .iter()
+        .filter_map(|server| server.password.clone())
+        .filter(|password| !password.is_empty())
+        .collect()
+}
@@ -1,7 +1,7 @@
 //! NZB file parsing, ingestion, duplicate detection, and disk space checks.
 
 use crate::db;
-use crate::error::{Error, Result};
+use crate::error::{Error, Result, failure_code};
 use crate::types::{DownloadId, DownloadOptions, DuplicateInfo, Event, Status};
 use crate::utils::extract_filename_from_response;
 
@@ -9,7 +9,7 @@ use super::UsenetDownloader;
 
 /// SQLite has a limit of ~999 variables per query. With 6 columns per article,
 /// we can insert at most 166 articles per batch (166 * 6 = 996 < 999).
-const SQLITE_BATCH_SIZE: usize = 166;
+pub(super) const SQLITE_BATCH_SIZE: usize = 166;
 
 /// Parse a filename from an NZB subject line.
 ///
@@ -18,7 +18,7 @@ const SQLITE_BATCH_SIZE: usize = 166;
 ///
 /// Falls back to `file_{index}` if no quoted filename is found, but we return
 /// just the parsed portion here — the caller provides a fallback index.
-fn parse_filename_from_subject(subject: &str) -> String {
+pub(super) fn parse_filename_from_subject(subject: &str) -> String {
     // Look for the first quoted string in the subject
     if let Some(start) = subject.find('"')
         && let Some(end) = subject[start + 1..].find('"')
@@ -35,9 +35,102 @@ fn parse_filename_from_subject(subject: &str) -> String {
     format!("file_{:x}", hasher.finish())
 }
 
+/// Recovery blocks a PAR2 volume carries, from its name (`Show.vol03+04.par2` carries 4)
+///
+/// None for anything that isn't a PAR2 recovery volume, including the index file.
+pub(super) fn par2_volume_blocks(filename: &str) -> Option<u32> {
+    let lower = filename.to_lowercase();
+    let name = lower.strip_suffix(".par2")?;
+    let (_, volume) = name.rsplit_once(".vol")?;
+    let (first, blocks) = volume.split_once(['+', '-'])?;
+    if first.is_empty() || !first.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    blocks.parse().ok()
+}
+
+/// Name of the file an NZB subject refers to, if it has a rejected extension
+///
+/// Subjects without a quoted filename are checked word by word.
+fn rejected_filename(config: &crate::config::DownloadConfig, subject: &str) -> Option<String> {
+    let filename = parse_filename_from_subject(subject);
+    if config.is_rejected(&filename) {
+        return Some(filename);
+    }
+    subject
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| matches!(c, '"' | '\'' | '(' | ')' | '[' | ']')))
+        .find(|word| config.is_rejected(word))
+        .map(str::to_string)
+}
+
 /// Timeout for HTTP requests when fetching NZB files from URLs.
 const NZB_FETCH_TIMEOUT_SECS: u64 = 30;
 
+/// NZB files fetched at the same time by [`UsenetDownloader::add_nzb_urls`]
+const NZB_FETCH_CONCURRENCY: usize = 4;
+
+/// Fetch an NZB over HTTP(S), returning a name for it and its content
+///
+/// The name comes from the `Content-Disposition` header, or else the URL.
+pub(super) async fn fetch_nzb(url: &str) -> Result<(String, Vec<u8>)> {
+    fetch_nzb_or_status(url).await?.map_err(|status| {
+        Error::Io(std::io::Error::other(format!(
+            "HTTP error fetching NZB: {} {}",
+            status, url
+        )))
+    })
+}
+
+/// Fetch an NZB like [`fetch_nzb`], returning an unsuccessful HTTP status separately
+pub(super) async fn fetch_nzb_or_status(
+    url: &str,
+) -> Result<std::result::Result<(String, Vec<u8>), reqwest::StatusCode>> {
+    // Create HTTP client with timeout to prevent hanging
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(NZB_FETCH_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| {
+            Error::Io(std::io::Error::other(format!(
+                "Failed to create HTTP client: {}",
+                e
+            )))
+        })?;
+
+    // Fetch NZB from URL with timeout
+    let response = client.get(url).send().await.map_err(|e| {
+        let error_msg = if e.is_timeout() {
+            format!(
+                "Timeout fetching NZB from URL '{}' (exceeded {} seconds)",
+                url, NZB_FETCH_TIMEOUT_SECS
+            )
+        } else if e.is_connect() {
+            format!("Connection failed for URL '{}': {}", url, e)
+        } else {
+            format!("Failed to fetch NZB from URL '{}': {}", url, e)
+        };
+        Error::Io(std::io::Error::other(error_msg))
+    })?;
+
+    // Check HTTP status
+    if !response.status().is_success() {
+        return Ok(Err(response.status()));
+    }
+
+    // Extract filename from Content-Disposition header or URL
+    let name = extract_filename_from_response(&response, url);
+
+    // Read response body
+    let content = response.bytes().await.map_err(|e| {
+        Error::Io(std::io::Error::other(format!(
+            "Failed to read response body from '{}': {}",
+            url, e
+        )))
+    })?;
+
+    Ok(Ok((name, content.to_vec())))
+}
+
 impl UsenetDownloader {
     /// Add an NZB to the download queue from raw bytes
     ///
@@ -88,6 +181,8 @@ impl UsenetDownloader {
         name: &str,
         options: DownloadOptions,
     ) -> Result<DownloadId> {
+        self.ensure_writable()?;
+
         // Check if accepting new downloads (reject during shutdown)
         if !self
             .queue_state
@@ -97,12 +192,18 @@ impl UsenetDownloader {
             return Err(Error::ShuttingDown);
         }
 
+        // Reject unknown or already completed batches before doing any work
+        if let Some(batch_id) = options.batch_id {
+            self.ensure_batch_open(batch_id).await?;
+        }
+
         // Parse and validate NZB, extract metadata
-        let (nzb, nzb_meta_name, nzb_password, nzb_hash) =
+        let (mut nzb, nzb_meta_name, nzb_password, nzb_hash) =
             self.parse_and_validate_nzb(content, name).await?;
+        let rejected_files = self.remove_rejected_files(&mut nzb);
 
         // Check for duplicates before proceeding
-        self.handle_duplicate_check(content, name).await?;
+        let held_duplicate = self.handle_duplicate_check(content, name).await?;
 
         // Determine destination directory and post-processing mode from category
         let (destination, post_process) = self.resolve_destination_and_post_process(&options).await;
@@ -111,9 +212,6 @@ impl UsenetDownloader {
         // Use NZB meta title if available, otherwise the provided name
         let job_name = nzb_meta_name.clone().unwrap_or_else(|| name.to_string());
 
-        // Merge NZB password with provided password (provided takes priority)
-        let final_password = options.password.clone().or(nzb_password);
-
         // Create and insert download record
         let download_id = self
             .create_download_record(
@@ -121,16 +219,53 @@ impl UsenetDownloader {
                 &nzb,
                 nzb_meta_name,
                 nzb_hash,
-                job_name,
+                job_name.clone(),
                 &options,
                 destination,
                 post_process,
             )
             .await?;
 
-        // Insert all articles and cache password
-        self.insert_articles_and_password(&nzb, download_id, final_password)
-            .await?;
+        // Insert all articles and store passwords (the provided one is tried first)
+        self.insert_articles_and_passwords(
+            &nzb,
+            download_id,
+            options.password.as_deref(),
+            nzb_password.as_deref(),
+        )
+        .await?;
+
+        self.backup_nzb(download_id, &job_name, content).await;
+
+        if let Some(batch_id) = options.batch_id {
+            self.db.set_download_batch(download_id, batch_id).await?;
+        }
+
+        if let Some(temp_dir) = &options.temp_dir {
+            self.db
+                .set_temp_dir(download_id, &temp_dir.to_string_lossy())
+                .await?;
+        }
+
+        if !self
+            .accept_rejected_files(download_id, &nzb, rejected_files)
+            .await
+        {
+            return Ok(download_id);
+        }
+
+        // Fail downloads that are too incomplete on the servers instead of queuing them
+        if self.config.download.completion_check.check_on_add
+            && !self.passes_completion_check(download_id).await
+        {
+            return Ok(download_id);
+        }
+
+        // Leave duplicates out of the queue until the user decides what to do with them
+        if let Some(duplicate) = held_duplicate {
+            self.hold_duplicate(download_id, name, duplicate).await?;
+            return Ok(download_id);
+        }
 
         // Emit events, trigger webhooks, and add to queue
         self.finalize_nzb_addition(download_id, name, &options)
@@ -142,10 +277,24 @@ impl UsenetDownloader {
     /// Parse and validate NZB content, extract metadata
     ///
     /// Returns: (parsed NZB, meta name, password, hash)
-    async fn parse_and_validate_nzb(
+    pub(super) async fn parse_and_validate_nzb(
         &self,
         content: &[u8],
         _name: &str,
+    ) -> Result<(nntp_rs::Nzb, Option<String>, Option<String>, String)> {
+        let parsed = Self::parse_nzb_content(content)?;
+
+        // Check if sufficient disk space is available
+        self.check_disk_space(parsed.0.total_bytes() as i64).await?;
+
+        Ok(parsed)
+    }
+
+    /// Parse and validate NZB content, extract metadata, without checking disk space
+    ///
+    /// Returns: (parsed NZB, meta name, password, hash)
+    pub(super) fn parse_nzb_content(
+        content: &[u8],
     ) -> Result<(nntp_rs::Nzb, Option<String>, Option<String>, String)> {
         // Parse NZB content from bytes to string
         let nzb_string = String::from_utf8(content.to_vec())
@@ -163,12 +312,6 @@ impl UsenetDownloader {
         let nzb_meta_name = nzb.meta.get("title").map(|s| s.to_string());
         let nzb_password = nzb.meta.get("password").map(|s| s.to_string());
 
-        // Calculate total size
-        let size_bytes = nzb.total_bytes() as i64;
-
-        // Check if sufficient disk space is available
-        self.check_disk_space(size_bytes).await?;
-
         // Calculate NZB hash for duplicate detection (sha256)
         use sha2::{Digest, Sha256};
         let mut hasher = Sha256::new();
@@ -179,8 +322,175 @@ impl UsenetDownloader {
         Ok((nzb, nzb_meta_name, nzb_password, nzb_hash))
     }
 
+    /// Remove the files matching `download.reject_extensions`, returning their names
+    pub(super) fn remove_rejected_files(&self, nzb: &mut nntp_rs::Nzb) -> Vec<String> {
+        let mut rejected = Vec::new();
+        nzb.files.retain(
+            |file| match rejected_filename(&self.config.download, &file.subject) {
+                Some(filename) => {
+                    rejected.push(filename);
+                    false
+                }
+                None => true,
+            },
+        );
+        rejected
+    }
+
+    /// Report the files `remove_rejected_files` took out of a just-added download
+    ///
+    /// Fails the download, returning false, if `download.reject_action` is `fail` or
+    /// no files are left.
+    pub(super) async fn accept_rejected_files(
+        &self,
+        id: DownloadId,
+        nzb: &nntp_rs::Nzb,
+        rejected_files: Vec<String>,
+    ) -> bool {
+        if rejected_files.is_empty() {
+            return true;
+        }
+
+        if self.config.download.reject_action == crate::config::RejectAction::Fail
+            || nzb.files.is_empty()
+        {
+            let error = format!(
+                "Contains files with a rejected extension: {}",
+                rejected_files.join(", ")
+            );
+            tracing::warn!(download_id = id.0, %error, "Download rejected");
+            self.fail_added_download(id, failure_code::REJECTED_FILES, error)
+                .await;
+            return false;
+        }
+
+        self.emit_event(Event::FilesRejected {
+            id,
+            files: rejected_files,
+        });
+        true
+    }
+
+    /// Fail a download that was added but can't be queued
+    ///
+    /// `code` is the machine-readable reason, e.g. one of [`failure_code`]'s.
+    ///
+    /// [`failure_code`]: crate::error::failure_code
+    pub(super) async fn fail_added_download(&self, id: DownloadId, code: &str, error: String) {
+        let _ = self.db.update_status(id, Status::Failed.to_i32()).await;
+        let _ = self.db.set_failure(id, code, &error).await;
+        self.record_history(id).await;
+        self.emit_event(Event::DownloadFailed {
+            id,
+            error,
+            articles_succeeded: None,
+            articles_failed: None,
+            articles_total: None,
+        });
+        self.finish_batch_member(id).await;
+    }
+
+    /// Report what adding an NZB would do without queueing it
+    ///
+    /// Parses and validates the NZB like [`add_nzb_content`](Self::add_nzb_content),
+    /// then runs duplicate detection, the disk space check and category resolution.
+    /// Nothing is written and no events are emitted. Duplicates and a failed disk space
+    /// check are reported instead of returned as errors; an unparseable NZB is an error.
+    pub async fn validate_nzb_content(
+        &self,
+        content: &[u8],
+        name: &str,
+        options: &DownloadOptions,
+    ) -> Result<crate::types::NzbValidation> {
+        let (nzb, nzb_meta_name, nzb_password, _nzb_hash) = Self::parse_nzb_content(content)?;
+        let size_bytes = nzb.total_bytes();
+
+        let mut warnings = Vec::new();
+        if let Err(e) = self.check_disk_space(size_bytes as i64).await {
+            warnings.push(e.to_string());
+        }
+
+        let duplicate = self.check_duplicate(content, name).await;
+        let duplicate_action = duplicate
+            .as_ref()
+            .map(|_| self.config.processing.duplicate.action);
+
+        let category_configured = match &options.category {
+            Some(category) => {
+                let configured = self
+                    .runtime_config
+                    .categories
+                    .read()
+                    .await
+                    .contains_key(category);
+                if !configured {
+                    warnings.push(format!(
+                        "Category '{}' is not configured, the defaults apply",
+                        category
+                    ));
+                }
+                configured
+            }
+            None => false,
+        };
+        let (destination, post_process) = self.resolve_destination_and_post_process(options).await;
+
+        let rejected: Vec<String> = nzb
+            .files
+            .iter()
+            .filter_map(|file| rejected_filename(&self.config.download, &file.subject))
+            .collect();
+        if !rejected.is_empty() {
+            let outcome = if self.config.download.reject_action == crate::config::RejectAction::Fail
+                || rejected.len() == nzb.files.len()
+            {
+                "the download would fail"
+            } else {
+                "they would be skipped"
+            };
+            warnings.push(format!(
+                "Files with a rejected extension, {}: {}",
+                outcome,
+                rejected.join(", ")
+            ));
+        }
+
+        let files: Vec<crate::types::NzbFilePreview> = nzb
+            .files
+            .iter()
+            .map(|file| crate::types::NzbFilePreview {
+                filename: parse_filename_from_subject(&file.subject),
+                subject: file.subject.clone(),
+                segments: file.segments.len(),
+                size_bytes: file.total_bytes(),
+            })
+            .collect();
+
+        Ok(crate::types::NzbValidation {
+            name: name.to_string(),
+            job_name: nzb_meta_name.unwrap_or_else(|| name.to_string()),
+            has_password: nzb_password.is_some(),
+            size_bytes,
+            segment_count: files.iter().map(|file| file.segments).sum(),
+            files,
+            duplicate,
+            duplicate_action,
+            category: options.category.clone(),
+            category_configured,
+            destination,
+            post_process,
+            warnings,
+        })
+    }
+
     /// Check for duplicates and handle according to configuration
-    async fn handle_duplicate_check(&self, content: &[u8], name: &str) -> Result<()> {
+    ///
+    /// Returns the duplicate when the download should be held instead of queued.
+    pub(super) async fn handle_duplicate_check(
+        &self,
+        content: &[u8],
+        name: &str,
+    ) -> Result<Option<DuplicateInfo>> {
         if let Some(dup_info) = self.check_duplicate(content, name).await {
             // Emit warning event about duplicate
             self.emit_event(Event::DuplicateDetected {
@@ -206,15 +516,52 @@ impl UsenetDownloader {
                     // Note: We already emitted the event above, but that's fine
                     // The event is informational in Allow mode
                 }
+                crate::config::DuplicateAction::Ask => {
+                    return Ok(Some(dup_info));
+                }
             }
         }
+        Ok(None)
+    }
+
+    /// Mark a new download as a held duplicate and record what it duplicates
+    pub(super) async fn hold_duplicate(
+        &self,
+        download_id: DownloadId,
+        name: &str,
+        duplicate: DuplicateInfo,
+    ) -> Result<()> {
+        self.db
+            .set_held_duplicate(&db::HeldDuplicate {
+                download_id: download_id.0,
+                method: duplicate.method.to_i32(),
+                existing_id: duplicate.existing_id.0,
+                existing_name: duplicate.existing_name.clone(),
+            })
+            .await?;
+        self.db
+            .update_status(download_id, Status::HeldDuplicate.to_i32())
+            .await?;
+
+        tracing::info!(
+            download_id = download_id.0,
+            existing_id = duplicate.existing_id.0,
+            "Holding duplicate download until it's accepted or discarded"
+        );
+
+        self.emit_event(Event::DuplicateHeld {
+            id: download_id,
+            name: name.to_string(),
+            duplicate,
+        });
+
         Ok(())
     }
 
     /// Determine destination directory and post-processing mode from category
     ///
     /// Returns: (destination, post_process)
-    async fn resolve_destination_and_post_process(
+    pub(super) async fn resolve_destination_and_post_process(
         &self,
         options: &DownloadOptions,
     ) -> (std::path::PathBuf, crate::config::PostProcess) {
@@ -248,7 +595,7 @@ impl UsenetDownloader {
 
     /// Create download record and insert into database
     #[allow(clippy::too_many_arguments)]
-    async fn create_download_record(
+    pub(super) async fn create_download_record(
         &self,
         name: &str,
         nzb: &nntp_rs::Nzb,
@@ -264,7 +611,7 @@ impl UsenetDownloader {
             nzb_path: format!("memory:{}", name), // Stored in memory, not from file
             nzb_meta_name,
             nzb_hash: Some(nzb_hash),
-            job_name: Some(job_name),
+            job_name: Some(job_name.clone()),
             category: options.category.clone(),
             destination: destination.to_string_lossy().into_owned(),
             post_process: post_process.to_i32(),
@@ -273,15 +620,19 @@ impl UsenetDownloader {
             size_bytes: nzb.total_bytes() as i64,
         };
 
-        self.db.insert_download(&new_download).await
+        let download_id = self.db.insert_download(&new_download).await?;
+        self.assign_incomplete_dir(download_id, &job_name, options)
+            .await?;
+        Ok(download_id)
     }
 
-    /// Insert all download files, articles (segments), and cache password if provided
-    async fn insert_articles_and_password(
+    /// Insert all download files, articles (segments), and the download's passwords
+    pub(super) async fn insert_articles_and_passwords(
         &self,
         nzb: &nntp_rs::Nzb,
         download_id: DownloadId,
-        password: Option<String>,
+        password: Option<&str>,
+        nzb_password: Option<&str>,
     ) -> Result<()> {
         // Build download_files rows — one per NZB file with parsed filename
         let download_files: Vec<db::NewDownloadFile> = nzb
@@ -301,6 +652,22 @@ impl UsenetDownloader {
             .collect();
         self.db.insert_files_batch(&download_files).await?;
 
+        // Recovery volumes wait until repair needs them, if there's an index file to verify with
+        if self.config.download.hold_par2_volumes {
+            let volumes: Vec<i32> = download_files
+                .iter()
+                .filter(|file| par2_volume_blocks(&file.filename).is_some())
+                .map(|file| file.file_index)
+                .collect();
+            let has_index = download_files.iter().any(|file| {
+                file.filename.to_lowercase().ends_with(".par2")
+                    && par2_volume_blocks(&file.filename).is_none()
+            });
+            if has_index && !volumes.is_empty() {
+                self.db.hold_files(download_id, &volumes).await?;
+            }
+        }
+
         // Insert all articles (segments) for resume support (batch insert for performance)
         let articles: Vec<db::NewArticle> = nzb
             .files
@@ -320,16 +687,18 @@ impl UsenetDownloader {
             self.db.insert_articles_batch(chunk).await?;
         }
 
-        // Cache password if provided
-        if let Some(password) = password {
-            self.db.set_correct_password(download_id, &password).await?;
+        // Keep both passwords: extraction tries the provided one before the NZB's
+        if password.is_some() || nzb_password.is_some() {
+            self.db
+                .set_download_passwords(download_id, password, nzb_password)
+                .await?;
         }
 
         Ok(())
     }
 
     /// Emit events, trigger webhooks, and add to queue
-    async fn finalize_nzb_addition(
+    pub(super) async fn finalize_nzb_addition(
         &self,
         download_id: DownloadId,
         name: &str,
@@ -390,56 +759,35 @@ impl UsenetDownloader {
     ///
     /// This method fetches an NZB file from a given HTTP(S) URL and adds it to the queue.
     pub async fn add_nzb_url(&self, url: &str, options: DownloadOptions) -> Result<DownloadId> {
-        // Create HTTP client with timeout to prevent hanging
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(NZB_FETCH_TIMEOUT_SECS))
-            .build()
-            .map_err(|e| {
-                Error::Io(std::io::Error::other(format!(
-                    "Failed to create HTTP client: {}",
-                    e
-                )))
-            })?;
-
-        // Fetch NZB from URL with timeout
-        let response = client.get(url).send().await.map_err(|e| {
-            let error_msg = if e.is_timeout() {
-                format!(
-                    "Timeout fetching NZB from URL '{}' (exceeded {} seconds)",
-                    url, NZB_FETCH_TIMEOUT_SECS
-                )
-            } else if e.is_connect() {
-                format!("Connection failed for URL '{}': {}", url, e)
-            } else {
-                format!("Failed to fetch NZB from URL '{}': {}", url, e)
-            };
-            Error::Io(std::io::Error::other(error_msg))
-        })?;
+        self.ensure_writable()?;
 
-        // Check HTTP status
-        if !response.status().is_success() {
-            return Err(Error::Io(std::io::Error::other(format!(
-                "HTTP error fetching NZB: {} {}",
-                response.status(),
-                url
-            ))));
-        }
-
-        // Extract filename from Content-Disposition header or URL
-        let name = extract_filename_from_response(&response, url);
-
-        // Read response body
-        let content = response.bytes().await.map_err(|e| {
-            Error::Io(std::io::Error::other(format!(
-                "Failed to read response body from '{}': {}",
-                url, e
-            )))
-        })?;
+        let (name, content) = fetch_nzb(url).await?;
 
         // Delegate to add_nzb_content
         self.add_nzb_content(&content, &name, options).await
     }
 
+    /// Add an NZB from each of several URLs, all with the same options
+    ///
+    /// Each URL is added as by [`add_nzb_url`](Self::add_nzb_url), a few at a time. Returns
+    /// one result per URL, in the order given; a URL that fails doesn't stop the others.
+    pub async fn add_nzb_urls(
+        &self,
+        urls: &[String],
+        options: DownloadOptions,
+    ) -> Vec<Result<DownloadId>> {
+        use futures::StreamExt;
+
+        futures::stream::iter(urls.to_vec())
+            .map(|url| {
+                let options = options.clone();
+                async move { self.add_nzb_url(&url, options).await }
+            })
+            .buffered(NZB_FETCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
     /// Mark an NZB file as processed in the database
     ///
     /// This is used by the folder watcher with WatchFolderAction::Keep to track
@@ -0,0 +1,87 @@
+//! Copies of added NZB files kept in `persistence.nzb_backup_dir`.
+//!
+//! Each NZB is written as `{download_id}-{job_name}.nzb` when its download is created,
+//! so a job can be re-queued after its data, database or history entry is lost. Old
+//! copies are pruned after every write according to `nzb_backup_max_age` and
+//! `nzb_backup_max_count`. Backup failures are logged and never fail the addition.
+
+use crate::config::PersistenceConfig;
+use crate::types::DownloadId;
+use crate::utils::job_file_stem;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::UsenetDownloader;
+
+impl UsenetDownloader {
+    /// Store a copy of an added NZB if `persistence.nzb_backup_dir` is set
+    pub(super) async fn backup_nzb(&self, download_id: DownloadId, job_name: &str, content: &[u8]) {
+        let persistence = &self.config.persistence;
+        let Some(dir) = &persistence.nzb_backup_dir else {
+            return;
+        };
+
+        let path = dir.join(backup_file_name(download_id, job_name));
+        let result = async {
+            tokio::fs::create_dir_all(dir).await?;
+            tokio::fs::write(&path, content).await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(
+                download_id = download_id.0,
+                path = %path.display(),
+                error = %e,
+                "Failed to back up NZB"
+            );
+            return;
+        }
+
+        if let Err(e) = prune_nzb_backups(dir, persistence).await {
+            tracing::warn!(dir = %dir.display(), error = %e, "Failed to prune NZB backups");
+        }
+    }
+}
+
+/// `{download_id}-{job_name}.nzb`, with the job name made safe as a single file name
+fn backup_file_name(download_id: DownloadId, job_name: &str) -> String {
+    format!("{}-{}.nzb", download_id.0, job_file_stem(job_name))
+}
+
+/// Delete `.nzb` files older than the max age, then the oldest beyond the max count
+async fn prune_nzb_backups(dir: &Path, persistence: &PersistenceConfig) -> std::io::Result<usize> {
+    if persistence.nzb_backup_max_age.is_none() && persistence.nzb_backup_max_count.is_none() {
+        return Ok(0);
+    }
+
+    let mut backups: Vec<(PathBuf, SystemTime)> = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let is_nzb = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("nzb"));
+        let metadata = entry.metadata().await?;
+        if is_nzb && metadata.is_file() {
+            backups.push((path, metadata.modified()?));
+        }
+    }
+    // Newest first
+    backups.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for (index, (path, modified)) in backups.iter().enumerate() {
+        let too_old = persistence
+            .nzb_backup_max_age
+            .is_some_and(|max_age| now.duration_since(*modified).is_ok_and(|age| age > max_age));
+        let too_many = persistence
+            .nzb_backup_max_count
+            .is_some_and(|max_count| index >= max_count);
+        if too_old || too_many {
+            tokio::fs::remove_file(path).await?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
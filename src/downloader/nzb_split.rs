@@ -0,0 +1,183 @@
+//! Splitting a pack NZB (e.g. a season) into a download per media file.
+
+use crate::error::Result;
+use crate::types::{DownloadId, DownloadOptions};
+
+use super::UsenetDownloader;
+use super::nzb::parse_filename_from_subject;
+
+/// Extensions of files that are a media item of their own (video and audio)
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "mkv", "mp4", "avi", "m4v", "mov", "wmv", "ts", "mpg", "mpeg", "flac", "mp3", "m4a", "m4b",
+];
+
+impl UsenetDownloader {
+    /// Add an NZB holding several independent media files as a download per file
+    ///
+    /// The NZB's files are grouped by name: `Show.S01E01.mkv` takes its RAR volumes,
+    /// PAR2 files, subtitles and sample (`Show.S01E01.part01.rar`,
+    /// `Show.S01E01.vol03+04.par2`, `Show.S01E01.en.srt`, ...) into a download named
+    /// `Show.S01E01`. Each is added as by [`add_nzb_content`](Self::add_nzb_content), so
+    /// it's verified, extracted, moved and reported complete on its own. Files belonging
+    /// to no item, such as the pack's `.nfo`, go into every download and are fetched
+    /// once when `deduplicate_articles` is on. PAR2 files covering the whole pack can't
+    /// verify a single item and are left out.
+    ///
+    /// An NZB with fewer than two media files is added as a single download. Returns one
+    /// result per download, in NZB order; an item that fails to add (e.g. a duplicate)
+    /// doesn't stop the others.
+    pub async fn add_nzb_content_split(
+        &self,
+        content: &[u8],
+        name: &str,
+        options: DownloadOptions,
+    ) -> Vec<Result<DownloadId>> {
+        let nzb = match Self::parse_nzb_content(content) {
+            Ok((nzb, ..)) => nzb,
+            Err(e) => return vec![Err(e)],
+        };
+
+        let items = split_media_items(&nzb);
+        if items.is_empty() {
+            return vec![self.add_nzb_content(content, name, options).await];
+        }
+
+        tracing::info!(
+            name,
+            items = items.len(),
+            "Splitting NZB into a download per media file"
+        );
+        let mut results = Vec::with_capacity(items.len());
+        for (item_name, item_nzb) in items {
+            let item_content = item_nzb.to_xml();
+            results.push(
+                self.add_nzb_content(item_content.as_bytes(), &item_name, options.clone())
+                    .await,
+            );
+        }
+        results
+    }
+}
+
+/// The media items of `nzb`, each named after its files and with an NZB of its own
+///
+/// Empty when there are fewer than two items, so there's nothing to split.
+pub(super) fn split_media_items(nzb: &nntp_rs::Nzb) -> Vec<(String, nntp_rs::Nzb)> {
+    let stems: Vec<(String, String)> = nzb
+        .files
+        .iter()
+        .map(|file| {
+            let filename = parse_filename_from_subject(&file.subject);
+            let stem = item_stem(&filename);
+            (filename, stem)
+        })
+        .collect();
+
+    // Items are named after their media files and archives, in NZB order; a sample
+    // (`Show.S01E01.sample.mkv`) is part of the item it extends
+    let mut items: Vec<String> = Vec::new();
+    for (filename, stem) in &stems {
+        if is_item_content(filename) && !items.contains(stem) {
+            items.push(stem.clone());
+        }
+    }
+    let samples: Vec<String> = items
+        .iter()
+        .filter(|stem| {
+            items
+                .iter()
+                .any(|other| other != *stem && belongs_to(stem, other))
+        })
+        .cloned()
+        .collect();
+    items.retain(|stem| !samples.contains(stem));
+    if items.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut groups = vec![Vec::new(); items.len()];
+    for (file, (filename, stem)) in nzb.files.iter().zip(&stems) {
+        let item = items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| belongs_to(stem, item))
+            .max_by_key(|(_, item)| item.len());
+        match item {
+            Some((i, _)) => groups[i].push(file.clone()),
+            None if filename.to_ascii_lowercase().ends_with(".par2") => {
+                tracing::debug!(filename, "Leaving out PAR2 file covering the whole pack");
+            }
+            None => groups.iter_mut().for_each(|group| group.push(file.clone())),
+        }
+    }
+
+    items
+        .into_iter()
+        .zip(groups)
+        .map(|(item, files)| {
+            let mut meta = nzb.meta.clone();
+            meta.insert("title".to_string(), item.clone());
+            (item, nntp_rs::Nzb { meta, files })
+        })
+        .collect()
+}
+
+/// Name a file is grouped by: its filename without volume and archive extensions
+///
+/// `Show.S01E01.part01.rar`, `Show.S01E01.vol03+04.par2`, `Show.S01E01.7z.001` and
+/// `Show.S01E01.mkv` all give `Show.S01E01`.
+fn item_stem(filename: &str) -> String {
+    let lower = filename.to_ascii_lowercase();
+    let is_numbered = |part: &str, prefix: &str| {
+        part.strip_prefix(prefix)
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+    };
+
+    let end = if let Some(name) = lower.strip_suffix(".par2") {
+        match name.rsplit_once(".vol") {
+            Some((base, volume))
+                if !volume.is_empty()
+                    && volume
+                        .bytes()
+                        .all(|b| b.is_ascii_digit() || b == b'+' || b == b'-') =>
+            {
+                base.len()
+            }
+            _ => name.len(),
+        }
+    } else if let Some((name, extension)) = lower.rsplit_once('.') {
+        match name.rsplit_once('.') {
+            // Show.part01.rar
+            Some((base, part)) if extension == "rar" && is_numbered(part, "part") => base.len(),
+            // Show.7z.001, Show.mkv.001
+            Some((base, _)) if is_numbered(extension, "") => base.len(),
+            _ => name.len(),
+        }
+    } else {
+        lower.len()
+    };
+    filename[..end].to_string()
+}
+
+/// Whether `filename` makes up a media item: a media file or an archive volume
+fn is_item_content(filename: &str) -> bool {
+    let lower = filename.to_ascii_lowercase();
+    let Some((_, extension)) = lower.rsplit_once('.') else {
+        return false;
+    };
+    MEDIA_EXTENSIONS.contains(&extension)
+        || matches!(extension, "rar" | "7z" | "zip")
+        || (extension.len() == 3
+            && extension.starts_with('r')
+            && extension[1..].bytes().all(|b| b.is_ascii_digit()))
+        || (!extension.is_empty() && extension.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Whether a file with stem `stem` belongs to `item` (`Show.S01E01.en` to `Show.S01E01`)
+fn belongs_to(stem: &str, item: &str) -> bool {
+    let (stem, item) = (stem.to_ascii_lowercase(), item.to_ascii_lowercase());
+    stem == item
+        || stem
+            .strip_prefix(&item)
+            .is_some_and(|rest| rest.starts_with(['.', '-', '_', ' ']))
+}
@@ -1,18 +1,87 @@
 //! Post-processing pipeline entry point.
 
-use crate::error::{Error, Result};
-use crate::types::{DownloadId, Event, Status};
+use crate::config::{StallAction, VerifyPolicy};
+use crate::error::{DownloadError, Error, PostProcessError, Result, ToHttpStatus};
+use crate::post_processing::{MoveOptions, RunOptions};
+use crate::types::{DownloadArtifact, DownloadArtifacts, DownloadId, Event, Stage, Status};
 use std::path::PathBuf;
 
 use super::UsenetDownloader;
 
+/// RAII marker for an in-flight post-processing run.
+///
+/// Held for the duration of a pipeline run so that [`UsenetDownloader::drain`] can
+/// tell when post-processing has settled. Dropping the guard decrements the counter
+/// and wakes a waiting drain.
+pub(crate) struct PostProcessGuard {
+    active: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    finished: std::sync::Arc<tokio::sync::Notify>,
+}
+
+impl Drop for PostProcessGuard {
+    fn drop(&mut self) {
+        self.active
+            .fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+        self.finished.notify_waiters();
+    }
+}
+
 impl UsenetDownloader {
+    /// Register an extractor for an archive format the built-in extractors don't handle
+    ///
+    /// It applies to every extraction from now on, including those of downloads already
+    /// queued, and is consulted before the built-in extractors and any extractor
+    /// registered before it. See [`Extractor`](crate::extraction::Extractor).
+    pub fn register_extractor(&self, extractor: std::sync::Arc<dyn crate::extraction::Extractor>) {
+        tracing::info!(extractor = extractor.name(), "registered custom extractor");
+        self.processing
+            .post_processor
+            .extractors()
+            .register(extractor);
+    }
+
+    /// Insert a deobfuscation strategy at `index` in the pipeline, or at the end
+    ///
+    /// The built-in strategies run in the order `par2_names`, `rar_volumes`,
+    /// `hash_names`, `nzb_meta`; index 0 runs the new strategy before all of them. It
+    /// applies to every post-processing run from now on, while `deobfuscation.enabled`
+    /// is set. See [`DeobfuscationStrategy`](crate::deobfuscation::DeobfuscationStrategy).
+    pub fn insert_deobfuscation_strategy(
+        &self,
+        index: usize,
+        strategy: std::sync::Arc<dyn crate::deobfuscation::DeobfuscationStrategy>,
+    ) {
+        tracing::info!(
+            strategy = strategy.name(),
+            index,
+            "registered custom deobfuscation strategy"
+        );
+        self.processing
+            .post_processor
+            .deobfuscation()
+            .insert(index, strategy);
+    }
+
+    /// Register an in-flight post-processing run
+    pub(crate) fn post_processing_guard(&self) -> PostProcessGuard {
+        let active = std::sync::Arc::clone(&self.queue_state.post_processing_active);
+        active.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        PostProcessGuard {
+            active,
+            finished: std::sync::Arc::clone(&self.queue_state.work_finished),
+        }
+    }
+
     /// Start post-processing for a completed download
     ///
     /// This is the entry point to the post-processing pipeline. It coordinates
     /// verification, repair, extraction, moving, and cleanup based on the
     /// configured PostProcess mode.
     ///
+    /// While post-processing is paused, queue-wide or for this download, the download
+    /// is marked Processing and waits here until it's resumed. It then waits for a free
+    /// post-processing slot (`processing.max_parallel_jobs`).
+    ///
     /// # Arguments
     ///
     /// * `download_id` - The download to post-process
@@ -37,12 +106,227 @@ impl UsenetDownloader {
     /// }
     /// ```
     pub async fn start_post_processing(&self, download_id: DownloadId) -> Result<()> {
-        tracing::info!(download_id = download_id.0, "starting post-processing");
+        self.queue_post_processing(download_id, None).await
+    }
+
+    /// Post-process a download that was in post-processing when the last session ended
+    ///
+    /// Picks the pipeline up at the stage journaled before the interruption. Moves and
+    /// cleanups continue where they stopped, since files already moved are gone from the
+    /// temp folder. An interrupted extraction starts over in an emptied `extracted`
+    /// folder. Without a journaled stage, or with one in verify or repair, the pipeline
+    /// runs again from the start.
+    pub(crate) async fn resume_post_processing(&self, download_id: DownloadId) -> Result<()> {
+        let from_stage = match self.db.get_post_process_stage(download_id).await? {
+            Some(stage) => self.recovery_stage(download_id, stage).await?,
+            None => None,
+        };
+        tracing::info!(
+            download_id = download_id.0,
+            ?from_stage,
+            "resuming interrupted post-processing"
+        );
+        self.queue_post_processing(download_id, from_stage).await
+    }
+
+    /// Stage to resume a pipeline interrupted in `stage` at, or None to start it over
+    async fn recovery_stage(&self, download_id: DownloadId, stage: Stage) -> Result<Option<Stage>> {
+        let download = self
+            .db
+            .get_download(download_id)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("download {} not found", download_id.0)))?;
+        let post_process = crate::config::PostProcess::from_i32(download.post_process);
+        // Resuming past extraction always ends in a move, so only runs that move resume there
+        let moves_files = match post_process {
+            crate::config::PostProcess::UnpackAndCleanup => true,
+            crate::config::PostProcess::Unpack => {
+                download.direct_unpack_state == super::direct_unpack::direct_unpack_state::COMPLETED
+                    && download.direct_unpack_extracted_count > 0
+            }
+            _ => false,
+        };
+
+        match stage {
+            Stage::Move | Stage::Cleanup => Ok(Some(stage)),
+            Stage::Scan if moves_files => Ok(Some(stage)),
+            Stage::Extract | Stage::Scan => {
+                let extracted = download
+                    .incomplete_path(&self.config.download.temp_dir)
+                    .join("extracted");
+                if tokio::fs::try_exists(&extracted).await.unwrap_or(false) {
+                    tracing::info!(
+                        download_id = download_id.0,
+                        path = %extracted.display(),
+                        "removing output of interrupted extraction"
+                    );
+                    tokio::fs::remove_dir_all(&extracted).await?;
+                }
+                Ok((moves_files && stage == Stage::Extract).then_some(stage))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Wait for post-processing to be unpaused and a free slot, then run the pipeline
+    async fn queue_post_processing(
+        &self,
+        download_id: DownloadId,
+        from_stage: Option<Stage>,
+    ) -> Result<()> {
+        let _slot = loop {
+            // Shown as processing while it waits; a run that starts right away sets it itself
+            if self.is_post_processing_paused_for(download_id).await?
+                || self.queue_state.post_processing_slots.available_permits() == 0
+            {
+                self.db
+                    .update_status(download_id, Status::Processing.to_i32())
+                    .await?;
+            }
+            self.wait_while_post_processing_paused(download_id).await?;
+            let slot = self.acquire_post_processing_slot(download_id).await?;
+            // Paused while waiting for the slot: give it up for the next download
+            if !self.is_post_processing_paused_for(download_id).await? {
+                break slot;
+            }
+        };
+        self.run_post_processing(download_id, from_stage).await
+    }
+
+    /// Whether post-processing is paused queue-wide or for `download_id`
+    async fn is_post_processing_paused_for(&self, download_id: DownloadId) -> Result<bool> {
+        Ok(*self.queue_state.post_processing_paused.borrow()
+            || self
+                .db
+                .get_download(download_id)
+                .await?
+                .is_some_and(|download| download.post_processing_paused))
+    }
+
+    /// Wait while post-processing is paused queue-wide or for `download_id`
+    async fn wait_while_post_processing_paused(&self, download_id: DownloadId) -> Result<()> {
+        let mut changes = self.queue_state.post_processing_paused.subscribe();
+        let mut waiting = false;
+        loop {
+            // Marked seen before checking, so a change during the check isn't missed
+            changes.borrow_and_update();
+            if !self.is_post_processing_paused_for(download_id).await? {
+                if waiting {
+                    tracing::info!(download_id = download_id.0, "post-processing resumed");
+                }
+                return Ok(());
+            }
+            if !waiting {
+                tracing::info!(
+                    download_id = download_id.0,
+                    "post-processing paused, waiting to be resumed"
+                );
+                waiting = true;
+            }
+            if changes.changed().await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Wait for one of the `max_parallel_jobs` post-processing slots
+    ///
+    /// Runs that can't start right away join the post-processing queue, get
+    /// [`Event::PostProcessQueued`] with their place in it, and start in arrival order.
+    /// The slot is held until the returned permit is dropped.
+    pub(crate) async fn acquire_post_processing_slot(
+        &self,
+        download_id: DownloadId,
+    ) -> Result<tokio::sync::OwnedSemaphorePermit> {
+        let slots = std::sync::Arc::clone(&self.queue_state.post_processing_slots);
+        if let Ok(permit) = slots.clone().try_acquire_owned() {
+            return Ok(permit);
+        }
+
+        let position = {
+            let mut queue = self
+                .queue_state
+                .post_processing_queue
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            queue.push(download_id);
+            queue.len()
+        };
+        tracing::info!(
+            download_id = download_id.0,
+            position,
+            "waiting for a post-processing slot"
+        );
+        self.emit_event(Event::PostProcessQueued {
+            id: download_id,
+            position,
+        });
+
+        let permit = slots.acquire_owned().await;
+        self.queue_state
+            .post_processing_queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|&id| id != download_id);
+        permit.map_err(|_| Error::ShuttingDown)
+    }
+
+    /// Move stage settings for a download in `category`
+    ///
+    /// Each setting the category overrides replaces the one from the config.
+    pub(crate) async fn move_options(&self, category: Option<&str>) -> MoveOptions {
+        let mut options = MoveOptions::from_config(&self.config);
+        let categories = self.runtime_config.categories.read().await;
+        if let Some(category) = category.and_then(|name| categories.get(name)) {
+            if let Some(flatten) = category.flatten_single_folder {
+                options.flatten_single_folder = flatten;
+            }
+            if let Some(junk_filter) = &category.junk_filter {
+                options.junk_filter = junk_filter.clone();
+            }
+        }
+        options
+    }
+
+    /// Verify policy for a download in `category`, the category's if it overrides the config
+    pub(crate) async fn verify_policy(&self, category: Option<&str>) -> VerifyPolicy {
+        let categories = self.runtime_config.categories.read().await;
+        category
+            .and_then(|name| categories.get(name))
+            .and_then(|category| category.verify_policy)
+            .unwrap_or(self.config.processing.verify_policy)
+    }
+
+    /// Pipeline settings for a download in `category`, with the category's overrides
+    pub(crate) async fn run_options(&self, category: Option<&str>) -> RunOptions {
+        RunOptions {
+            move_options: self.move_options(category).await,
+            verify_policy: self.verify_policy(category).await,
+            cancel: tokio_util::sync::CancellationToken::new(),
+        }
+    }
+
+    /// Run the post-processing pipeline, optionally starting at `from_stage`
+    ///
+    /// With `from_stage` set, the download's PostProcess mode and DirectUnpack state are
+    /// ignored and every stage from `from_stage` onward runs against the temp folder.
+    pub(crate) async fn run_post_processing(
+        &self,
+        download_id: DownloadId,
+        from_stage: Option<Stage>,
+    ) -> Result<()> {
+        tracing::info!(
+            download_id = download_id.0,
+            ?from_stage,
+            "starting post-processing"
+        );
+        let _guard = self.post_processing_guard();
 
         // Update status to Processing
         self.db
             .update_status(download_id, Status::Processing.to_i32())
             .await?;
+        self.db.set_post_process_stage(download_id, None).await?;
 
         // Get download info from database
         let download = self
@@ -52,11 +336,7 @@ impl UsenetDownloader {
             .ok_or_else(|| Error::NotFound(format!("download {} not found", download_id.0)))?;
 
         // Determine download path (temp directory)
-        let download_path = self
-            .config
-            .download
-            .temp_dir
-            .join(format!("download_{}", download_id.0));
+        let download_path = download.incomplete_path(&self.config.download.temp_dir);
 
         // Determine final destination
         let destination = PathBuf::from(&download.destination);
@@ -78,32 +358,82 @@ impl UsenetDownloader {
             .await
             .unwrap_or(0);
 
-        // Execute post-processing pipeline
-        let pipeline_result = if direct_unpack_completed
-            && direct_unpack_extracted_count > 0
-            && matches!(
-                post_process,
-                crate::config::PostProcess::Unpack | crate::config::PostProcess::UnpackAndCleanup
-            ) {
-            tracing::info!(
-                download_id = download_id.0,
-                extracted_count = direct_unpack_extracted_count,
-                "DirectUnpack extracted {} files — skipping verify/repair/extract, running move+cleanup only",
-                direct_unpack_extracted_count
-            );
-            self.processing
-                .post_processor
-                .run_move_and_cleanup(download_id, download_path, destination)
-                .await
-        } else {
-            self.processing
-                .post_processor
-                .start_post_processing(download_id, download_path, post_process, destination)
-                .await
+        let options = self.run_options(download.category.as_deref()).await;
+
+        // Execute post-processing pipeline, unless the stall watchdog stops it first
+        let mut stall_rx = self.stall_signal(download_id);
+        let pipeline = async {
+            if let Some(stage) = from_stage {
+                self.processing
+                    .post_processor
+                    .run_from_stage(download_id, download_path, stage, destination, &options)
+                    .await
+            } else if direct_unpack_completed
+                && direct_unpack_extracted_count > 0
+                && matches!(
+                    post_process,
+                    crate::config::PostProcess::Unpack
+                        | crate::config::PostProcess::UnpackAndCleanup
+                )
+            {
+                tracing::info!(
+                    download_id = download_id.0,
+                    extracted_count = direct_unpack_extracted_count,
+                    "DirectUnpack extracted {} files — skipping verify/repair/extract, running move+cleanup only",
+                    direct_unpack_extracted_count
+                );
+                self.processing
+                    .post_processor
+                    .run_move_and_cleanup(download_id, download_path, destination, &options)
+                    .await
+            } else {
+                self.processing
+                    .post_processor
+                    .start_post_processing(
+                        download_id,
+                        download_path,
+                        post_process,
+                        destination,
+                        &options,
+                    )
+                    .await
+            }
+        };
+        tokio::pin!(pipeline);
+        let (result, stalled) = tokio::select! {
+            result = &mut pipeline => (result, None),
+            Ok(stalled) = &mut stall_rx => {
+                // Stopped at the next stage, or at once while extracting; wait for that
+                options.cancel.cancel();
+                (pipeline.await, Some(stalled))
+            }
+        };
+        drop(stall_rx);
+        self.clear_stall_signal(download_id);
+        if let Err(e) = self.db.set_post_process_stage(download_id, None).await {
+            tracing::warn!(download_id = download_id.0, error = %e, "failed to clear post-processing stage");
+        }
+        let pipeline_result = match stalled {
+            None => result,
+            Some(stalled) if stalled.action == StallAction::Fail => {
+                Err(Error::PostProcess(PostProcessError::Stalled {
+                    id: download_id.0,
+                    reason: stalled.reason,
+                }))
+            }
+            // The watchdog starts post-processing over once this run is gone
+            Some(stalled) => {
+                let _ = stalled.exited.send(());
+                return Ok(());
+            }
         };
 
         match pipeline_result {
             Ok(final_path) => {
+                if let Err(e) = self.db.clear_all_stage_checkpoints(download_id).await {
+                    tracing::warn!(download_id = download_id.0, error = %e, "failed to clear stage checkpoints");
+                }
+                self.write_completion_sidecar(&download, &final_path).await;
                 self.handle_post_process_success(
                     download_id,
                     download.name,
@@ -113,12 +443,28 @@ impl UsenetDownloader {
                 )
                 .await
             }
+            Err(Error::PostProcess(
+                e @ (PostProcessError::AllPasswordsFailed { .. }
+                | PostProcessError::NoPasswordsAvailable { .. }),
+            )) => self.wait_for_password(download_id, e).await,
             Err(e) => {
+                if matches!(
+                    e,
+                    Error::PostProcess(
+                        PostProcessError::VerificationFailed { .. }
+                            | PostProcessError::RepairFailed { .. }
+                    )
+                ) && self.fetch_extra_pars(download_id).await?
+                {
+                    return Ok(());
+                }
+                let files_kept = self.discard_intermediate_files(&download).await;
                 self.handle_post_process_failure(
                     download_id,
                     download.name,
                     download.category,
                     download.size_bytes as u64,
+                    files_kept,
                     e,
                 )
                 .await
@@ -126,6 +472,71 @@ impl UsenetDownloader {
         }
     }
 
+    /// Park a download whose archives no password opened until one is submitted
+    ///
+    /// The files stay in the temp folder; [`UsenetDownloader::submit_password`] retries
+    /// extraction with the submitted password.
+    async fn wait_for_password(&self, download_id: DownloadId, e: PostProcessError) -> Result<()> {
+        let archive = match &e {
+            PostProcessError::AllPasswordsFailed { archive, .. }
+            | PostProcessError::NoPasswordsAvailable { archive } => archive.clone(),
+            _ => PathBuf::new(),
+        };
+        let e = Error::PostProcess(e);
+        self.processing.post_processor.take_moved_files(download_id);
+        self.processing.post_processor.take_summary(download_id);
+
+        self.db
+            .update_status(download_id, Status::WaitingForPassword.to_i32())
+            .await?;
+        self.db
+            .set_failure(download_id, e.error_code(), &e.to_string())
+            .await?;
+        self.event_tx
+            .send(Event::WaitingForPassword {
+                id: download_id,
+                archive,
+            })
+            .ok();
+
+        tracing::warn!(download_id = download_id.0, error = %e, "waiting for an extraction password");
+        Ok(())
+    }
+
+    /// Queue a download's held PAR2 volumes after repair came up short of blocks
+    ///
+    /// Returns false if there were none. Otherwise the download goes back to the queue
+    /// to fetch them, and post-processing runs again once they've arrived.
+    async fn fetch_extra_pars(&self, download_id: DownloadId) -> Result<bool> {
+        let released = self.db.release_held_files(download_id).await?;
+        if released.is_empty() {
+            return Ok(false);
+        }
+
+        let blocks = released
+            .iter()
+            .filter_map(|file| super::nzb::par2_volume_blocks(&file.filename))
+            .sum();
+        tracing::info!(
+            download_id = download_id.0,
+            files = released.len(),
+            blocks,
+            "repair is short of recovery blocks, fetching held PAR2 volumes"
+        );
+        self.db
+            .update_status(download_id, Status::Queued.to_i32())
+            .await?;
+        self.add_to_queue(download_id).await?;
+        self.event_tx
+            .send(Event::FetchingExtraPars {
+                id: download_id,
+                files: released.len(),
+                blocks,
+            })
+            .ok();
+        Ok(true)
+    }
+
     /// Handle successful post-processing: update status, emit events, trigger webhooks/scripts.
     async fn handle_post_process_success(
         &self,
@@ -138,6 +549,7 @@ impl UsenetDownloader {
         self.db
             .update_status(download_id, Status::Complete.to_i32())
             .await?;
+        self.record_history(download_id).await;
 
         self.event_tx
             .send(Event::Complete {
@@ -156,7 +568,7 @@ impl UsenetDownloader {
             error: None,
         });
 
-        self.trigger_scripts(super::webhooks::TriggerScriptsParams {
+        let mut scripts = self.trigger_scripts(super::webhooks::TriggerScriptsParams {
             event_type: crate::config::ScriptEvent::OnPostProcessComplete,
             download_id,
             name: name.clone(),
@@ -166,24 +578,64 @@ impl UsenetDownloader {
             error: None,
             size_bytes,
         });
-        self.trigger_scripts(super::webhooks::TriggerScriptsParams {
+        scripts.extend(self.trigger_scripts(super::webhooks::TriggerScriptsParams {
             event_type: crate::config::ScriptEvent::OnComplete,
             download_id,
             name,
-            category,
+            category: category.clone(),
             status: "complete".to_string(),
-            destination: Some(final_path),
+            destination: Some(final_path.clone()),
             error: None,
             size_bytes,
-        });
+        }));
+        self.announce_ready_for_import(download_id, category, final_path, scripts);
 
         tracing::info!(
             download_id = download_id.0,
             "post-processing completed successfully"
         );
+
+        self.finish_batch_member(download_id).await;
         Ok(())
     }
 
+    /// Emit [`Event::ReadyForImport`] once every completion script succeeded
+    ///
+    /// Waits for `scripts` in the background; if any of them fails the event is not
+    /// emitted (the failure is reported through [`Event::ScriptFailed`]).
+    pub(crate) fn announce_ready_for_import(
+        &self,
+        download_id: DownloadId,
+        category: Option<String>,
+        final_path: PathBuf,
+        scripts: Vec<tokio::task::JoinHandle<bool>>,
+    ) {
+        let event = Event::ReadyForImport {
+            id: download_id,
+            category,
+            final_path,
+            files: self.processing.post_processor.take_moved_files(download_id),
+        };
+        if scripts.is_empty() {
+            self.emit_event(event);
+            return;
+        }
+
+        let event_tx = self.event_tx.clone();
+        tokio::spawn(async move {
+            for script in scripts {
+                if !matches!(script.await, Ok(true)) {
+                    tracing::info!(
+                        download_id = download_id.0,
+                        "completion script failed, not announcing download as ready for import"
+                    );
+                    return;
+                }
+            }
+            event_tx.send(event).ok();
+        });
+    }
+
     /// Handle failed post-processing: update status, emit events, trigger webhooks/scripts.
     async fn handle_post_process_failure(
         &self,
@@ -191,21 +643,27 @@ impl UsenetDownloader {
         name: String,
         category: Option<String>,
         size_bytes: u64,
+        files_kept: bool,
         e: Error,
     ) -> Result<()> {
         let error_message = e.to_string();
+        self.processing.post_processor.take_moved_files(download_id);
+        self.processing.post_processor.take_summary(download_id);
 
         self.db
             .update_status(download_id, Status::Failed.to_i32())
             .await?;
-        self.db.set_error(download_id, &error_message).await?;
+        self.db
+            .set_failure(download_id, e.error_code(), &error_message)
+            .await?;
+        self.record_history(download_id).await;
 
         self.event_tx
             .send(Event::Failed {
                 id: download_id,
                 stage: crate::types::Stage::Extract, // Default to Extract stage
                 error: error_message.clone(),
-                files_kept: true, // Default: keep files on failure
+                files_kept,
             })
             .ok();
 
@@ -231,6 +689,85 @@ impl UsenetDownloader {
         });
 
         tracing::error!(download_id = download_id.0, error = %e, "post-processing failed");
+
+        self.finish_batch_member(download_id).await;
         Err(e)
     }
+
+    /// Delete a failed download's temp folder unless intermediate files are kept
+    ///
+    /// Returns whether the files are still on disk.
+    pub(crate) async fn discard_intermediate_files(&self, download: &crate::db::Download) -> bool {
+        if self.config.processing.keep_intermediate_on_failure {
+            return true;
+        }
+
+        let download_path = download.incomplete_path(&self.config.download.temp_dir);
+        match tokio::fs::remove_dir_all(&download_path).await {
+            Ok(()) => false,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => {
+                tracing::warn!(
+                    download_id = download.id,
+                    path = %download_path.display(),
+                    error = %e,
+                    "Failed to delete temp folder of failed download"
+                );
+                true
+            }
+        }
+    }
+
+    /// List the files left in a download's temp folder
+    ///
+    /// After a failed repair or extraction these are what can still be salvaged by
+    /// hand. The list is empty once the folder is gone.
+    pub async fn download_artifacts(&self, id: DownloadId) -> Result<DownloadArtifacts> {
+        let download = self
+            .db
+            .get_download(id)
+            .await?
+            .ok_or(Error::Download(DownloadError::NotFound { id: id.0 }))?;
+        let path = download.incomplete_path(&self.config.download.temp_dir);
+
+        let mut files = Vec::new();
+        collect_artifacts(&path, &path, &mut files).await?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let total_bytes = files.iter().map(|f| f.size_bytes).sum();
+        Ok(DownloadArtifacts {
+            id,
+            path,
+            files,
+            total_bytes,
+        })
+    }
+}
+
+/// Collect every file below `dir`, with paths relative to `root`
+fn collect_artifacts<'a>(
+    root: &'a std::path::Path,
+    dir: &'a std::path::Path,
+    files: &'a mut Vec<DownloadArtifact>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
+    Box::pin(async move {
+        let mut entries = match tokio::fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let metadata = entry.metadata().await?;
+            if metadata.is_dir() {
+                collect_artifacts(root, &path, files).await?;
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                files.push(DownloadArtifact {
+                    path: relative.to_path_buf(),
+                    size_bytes: metadata.len(),
+                });
+            }
+        }
+        Ok(())
+    })
 }
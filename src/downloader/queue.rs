@@ -1,7 +1,7 @@
 //! Priority queue management for download ordering.
 
 use crate::error::{DatabaseError, Error, Result};
-use crate::types::{DownloadId, Priority, Status};
+use crate::types::{BandwidthMode, DownloadId, Priority, Status};
 
 use super::{QueuedDownload, UsenetDownloader};
 
@@ -31,6 +31,7 @@ impl UsenetDownloader {
             id,
             priority: Priority::from_i32(download.priority),
             created_at: download.created_at,
+            size_bytes: download.size_bytes.max(0) as u64,
         };
 
         // Add to priority queue
@@ -68,6 +69,50 @@ impl UsenetDownloader {
         was_removed
     }
 
+    /// Take the next download that may start from the priority queue
+    ///
+    /// While the bandwidth mode is metered, downloads larger than
+    /// `download.metered.max_job_bytes` are passed over and stay queued, unless forced.
+    pub(crate) fn pop_startable(
+        &self,
+        queue: &mut std::collections::BinaryHeap<QueuedDownload>,
+    ) -> Option<QueuedDownload> {
+        let max_job_bytes = self.metered_max_job_bytes();
+        pop_first(queue, |item| {
+            max_job_bytes
+                .is_none_or(|max| item.priority == Priority::Force || item.size_bytes <= max)
+        })
+    }
+
+    /// Take the next download that may start while every download slot is taken
+    ///
+    /// That's a forced download or, when `lane_open`, one that fits the fast lane: at
+    /// most `download.fast_lane.max_job_bytes` (and the metered limit). Downloads whose
+    /// size isn't known yet, such as deferred ones, don't fit.
+    pub(crate) fn pop_fast_lane(
+        &self,
+        queue: &mut std::collections::BinaryHeap<QueuedDownload>,
+        lane_open: bool,
+    ) -> Option<QueuedDownload> {
+        let max_job_bytes = self
+            .metered_max_job_bytes()
+            .map_or(self.config.download.fast_lane.max_job_bytes, |max| {
+                max.min(self.config.download.fast_lane.max_job_bytes)
+            });
+        pop_first(queue, |item| {
+            item.priority == Priority::Force
+                || (lane_open && item.size_bytes > 0 && item.size_bytes <= max_job_bytes)
+        })
+    }
+
+    /// Size cap the current bandwidth mode puts on starting downloads
+    fn metered_max_job_bytes(&self) -> Option<u64> {
+        match self.get_bandwidth_mode() {
+            BandwidthMode::Metered => self.config.download.metered.max_job_bytes,
+            BandwidthMode::Normal | BandwidthMode::Unlimited => None,
+        }
+    }
+
     /// Restore incomplete downloads from database on startup
     ///
     /// This method is called automatically during initialization to restore
@@ -150,3 +195,19 @@ impl UsenetDownloader {
         Ok(needs_post_processing)
     }
 }
+
+/// Pop the highest priority download `startable` accepts, leaving the others queued
+fn pop_first(
+    queue: &mut std::collections::BinaryHeap<QueuedDownload>,
+    startable: impl Fn(&QueuedDownload) -> bool,
+) -> Option<QueuedDownload> {
+    let mut passed_over = Vec::new();
+    let next = loop {
+        match queue.pop() {
+            Some(item) if !startable(&item) => passed_over.push(item),
+            next => break next,
+        }
+    };
+    queue.extend(passed_over);
+    next
+}
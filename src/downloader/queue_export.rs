@@ -0,0 +1,445 @@
+//! Export and import of the pending queue for moving it to another instance.
+//!
+//! A queue export is a zip archive holding a manifest with each pending download's
+//! options and progress, an NZB per download rebuilt from its stored articles, and
+//! the partially written files its progress refers to. Importing adds the downloads
+//! to the existing queue; nothing already there is touched.
+
+use crate::config::PostProcess;
+use crate::db::{self, article_status};
+use crate::types::{DownloadId, DownloadOptions, Priority, Status};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::UsenetDownloader;
+
+/// Archive layout version, bumped on incompatible changes
+const QUEUE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "queue.json";
+
+/// Describes the downloads in a queue export
+#[derive(Debug, Serialize, Deserialize)]
+struct QueueManifest {
+    format_version: u32,
+    /// Crate version that wrote the archive
+    app_version: String,
+    /// RFC 3339 timestamp
+    created_at: String,
+    /// Downloads in the order they were added
+    downloads: Vec<ExportedDownload>,
+}
+
+/// One pending download in a queue export
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportedDownload {
+    name: String,
+    category: Option<String>,
+    destination: PathBuf,
+    post_process: PostProcess,
+    priority: Priority,
+    paused: bool,
+    /// Password given when the download was added (the NZB's own is in the NZB)
+    password: Option<String>,
+    /// Archive entry holding the NZB
+    nzb: String,
+    /// Downloaded articles as a hex bitmap, in file and segment order
+    progress: String,
+    /// Archive entries holding partially written files, by file index
+    data: HashMap<i32, String>,
+}
+
+impl UsenetDownloader {
+    /// Write the pending queue to a portable archive at `path`
+    ///
+    /// Includes queued, downloading and paused downloads with their options and
+    /// progress, so [`import_queue`] on another instance picks up where this one
+    /// left off. Safe to call while downloads are running. Returns the number of
+    /// downloads written.
+    ///
+    /// [`import_queue`]: UsenetDownloader::import_queue
+    pub async fn export_queue(&self, path: &Path) -> Result<usize> {
+        let mut downloads = Vec::new();
+        for status in [Status::Queued, Status::Downloading, Status::Paused] {
+            downloads.extend(self.db.list_downloads_by_status(status.to_i32()).await?);
+        }
+        downloads.sort_by_key(|d| d.id);
+
+        let mut manifest = QueueManifest {
+            format_version: QUEUE_FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            downloads: Vec::with_capacity(downloads.len()),
+        };
+        // Archive entry name -> NZB content or file to copy
+        let mut nzbs = Vec::new();
+        let mut data_files = Vec::new();
+
+        for (n, download) in downloads.iter().enumerate() {
+            let id = DownloadId(download.id);
+            let files = self.db.get_download_files(id).await?;
+            // Read progress before copying files: an article is only marked downloaded
+            // once its data is written, so the copied data covers the bitmap
+            let articles = self.db.get_articles(id).await?;
+            let passwords = self.db.get_download_passwords(id).await?;
+
+            let nzb_entry = format!("nzb/{}.nzb", n);
+            nzbs.push((
+                nzb_entry.clone(),
+                rebuild_nzb(download, &files, &articles, passwords.nzb_password).to_xml(),
+            ));
+
+            let downloaded: Vec<bool> = articles
+                .iter()
+                .map(|a| a.status == article_status::DOWNLOADED)
+                .collect();
+            let mut data = HashMap::new();
+            if downloaded.contains(&true) {
+                let temp_dir = download.incomplete_path(&self.config.download.temp_dir);
+                for file in &files {
+                    let source = temp_dir.join(&file.filename);
+                    if tokio::fs::try_exists(&source).await.unwrap_or(false) {
+                        let entry = format!("data/{}/{}", n, file.file_index);
+                        data_files.push((entry.clone(), source));
+                        data.insert(file.file_index, entry);
+                    }
+                }
+            }
+
+            manifest.downloads.push(ExportedDownload {
+                name: download.name.clone(),
+                category: download.category.clone(),
+                destination: PathBuf::from(&download.destination),
+                post_process: PostProcess::from_i32(download.post_process),
+                priority: Priority::from_i32(download.priority),
+                paused: download.status == Status::Paused.to_i32(),
+                password: passwords.password,
+                nzb: nzb_entry,
+                progress: encode_bitmap(&downloaded),
+                data,
+            });
+        }
+
+        let count = manifest.downloads.len();
+        let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+        let archive_path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let zip_err = |e: zip::result::ZipError| Error::Io(std::io::Error::other(e));
+            let options = zip::write::FileOptions::default().large_file(true);
+
+            let mut writer = zip::ZipWriter::new(std::fs::File::create(&archive_path)?);
+            writer
+                .start_file(MANIFEST_ENTRY, options)
+                .map_err(zip_err)?;
+            writer.write_all(&manifest_json)?;
+            for (entry, xml) in nzbs {
+                writer.start_file(entry, options).map_err(zip_err)?;
+                writer.write_all(xml.as_bytes())?;
+            }
+            for (entry, source) in data_files {
+                writer.start_file(entry, options).map_err(zip_err)?;
+                std::io::copy(&mut std::fs::File::open(&source)?, &mut writer)?;
+            }
+            writer.finish().map_err(zip_err)?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| Error::Other(format!("Queue export task panicked: {}", e)))??;
+
+        tracing::info!(path = %path.display(), count, "Exported queue");
+        Ok(count)
+    }
+
+    /// Add the downloads from a [`export_queue`] archive at `path` to the queue
+    ///
+    /// Priorities, categories, post-processing, passwords and paused state carry over,
+    /// as does download progress. Downloads with a category take their destination
+    /// from this instance's category; the rest keep their archived destination.
+    /// Duplicate detection is skipped. Returns the IDs of the added downloads.
+    ///
+    /// [`export_queue`]: UsenetDownloader::export_queue
+    pub async fn import_queue(&self, path: &Path) -> Result<Vec<DownloadId>> {
+        self.ensure_writable()?;
+        if !self
+            .queue_state
+            .accepting_new
+            .load(std::sync::atomic::Ordering::SeqCst)
+        {
+            return Err(Error::ShuttingDown);
+        }
+
+        let scratch = self.backup_scratch_dir().await?;
+        let result = self.restore_queue_export(path, &scratch).await;
+        let _ = tokio::fs::remove_dir_all(&scratch).await;
+        result
+    }
+
+    async fn restore_queue_export(&self, path: &Path, scratch: &Path) -> Result<Vec<DownloadId>> {
+        let manifest = {
+            let path = path.to_path_buf();
+            let scratch = scratch.to_path_buf();
+            tokio::task::spawn_blocking(move || read_queue_export(&path, &scratch))
+                .await
+                .map_err(|e| Error::Other(format!("Queue import task panicked: {}", e)))??
+        };
+
+        let mut ids = Vec::with_capacity(manifest.downloads.len());
+        for exported in &manifest.downloads {
+            ids.push(self.import_download(exported, scratch).await?);
+        }
+
+        tracing::info!(
+            path = %path.display(),
+            count = ids.len(),
+            created_at = %manifest.created_at,
+            "Imported queue"
+        );
+        Ok(ids)
+    }
+
+    /// Add one exported download, restore its progress and queue or pause it
+    async fn import_download(
+        &self,
+        exported: &ExportedDownload,
+        scratch: &Path,
+    ) -> Result<DownloadId> {
+        let content = tokio::fs::read(scratch.join(&exported.nzb)).await?;
+        let (nzb, nzb_meta_name, nzb_password, nzb_hash) = self
+            .parse_and_validate_nzb(&content, &exported.name)
+            .await?;
+
+        let options = DownloadOptions {
+            category: exported.category.clone(),
+            destination: exported
+                .category
+                .is_none()
+                .then(|| exported.destination.clone()),
+            post_process: Some(exported.post_process),
+            priority: exported.priority,
+            password: exported.password.clone(),
+            ..Default::default()
+        };
+        let (destination, post_process) = self.resolve_destination_and_post_process(&options).await;
+        let job_name = nzb_meta_name
+            .clone()
+            .unwrap_or_else(|| exported.name.clone());
+
+        let download_id = self
+            .create_download_record(
+                &exported.name,
+                &nzb,
+                nzb_meta_name,
+                nzb_hash,
+                job_name.clone(),
+                &options,
+                destination,
+                post_process,
+            )
+            .await?;
+        self.insert_articles_and_passwords(
+            &nzb,
+            download_id,
+            options.password.as_deref(),
+            nzb_password.as_deref(),
+        )
+        .await?;
+        self.backup_nzb(download_id, &job_name, &content).await;
+
+        self.restore_progress(download_id, exported, scratch)
+            .await?;
+
+        if exported.paused {
+            self.db
+                .update_status(download_id, Status::Paused.to_i32())
+                .await?;
+        } else {
+            self.finalize_nzb_addition(download_id, &exported.name, &options)
+                .await?;
+        }
+
+        Ok(download_id)
+    }
+
+    /// Mark the archived downloaded articles and put their files in place
+    async fn restore_progress(
+        &self,
+        download_id: DownloadId,
+        exported: &ExportedDownload,
+        scratch: &Path,
+    ) -> Result<()> {
+        let articles = self.db.get_articles(download_id).await?;
+        let downloaded = decode_bitmap(&exported.progress, articles.len())?;
+        if !downloaded.contains(&true) {
+            return Ok(());
+        }
+
+        let Some(download) = self.db.get_download(download_id).await? else {
+            return Ok(());
+        };
+        let temp_dir = download.incomplete_path(&self.config.download.temp_dir);
+        tokio::fs::create_dir_all(&temp_dir).await?;
+        for file in self.db.get_download_files(download_id).await? {
+            if let Some(entry) = exported.data.get(&file.file_index) {
+                tokio::fs::rename(scratch.join(entry), temp_dir.join(&file.filename)).await?;
+            }
+        }
+
+        let mut updates = Vec::new();
+        let mut downloaded_bytes = 0u64;
+        let mut incomplete_files = std::collections::HashSet::new();
+        for (article, done) in articles.iter().zip(&downloaded) {
+            if *done {
+                updates.push((article.id, article_status::DOWNLOADED));
+                downloaded_bytes += article.size_bytes as u64;
+            } else {
+                incomplete_files.insert(article.file_index);
+            }
+        }
+        self.db.update_articles_status_batch(&updates).await?;
+
+        let mut file_indexes: Vec<i32> = articles.iter().map(|a| a.file_index).collect();
+        file_indexes.dedup();
+        for file_index in file_indexes {
+            if !incomplete_files.contains(&file_index) {
+                self.db.mark_file_completed(download_id, file_index).await?;
+            }
+        }
+
+        let progress = if download.size_bytes > 0 {
+            downloaded_bytes as f32 / download.size_bytes as f32
+        } else {
+            0.0
+        };
+        self.db
+            .update_progress(download_id, progress, 0, downloaded_bytes)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Rebuild a download's NZB from its stored files and articles
+///
+/// Posters, dates and newsgroups aren't stored, so they're left empty; articles are
+/// fetched by message ID, which is all downloading needs.
+fn rebuild_nzb(
+    download: &db::Download,
+    files: &[db::DownloadFile],
+    articles: &[db::Article],
+    nzb_password: Option<String>,
+) -> nntp_rs::Nzb {
+    let mut meta = HashMap::new();
+    if let Some(title) = &download.nzb_meta_name {
+        meta.insert("title".to_string(), title.clone());
+    }
+    if let Some(password) = nzb_password {
+        meta.insert("password".to_string(), password);
+    }
+
+    let files = files
+        .iter()
+        .map(|file| nntp_rs::NzbFile {
+            poster: String::new(),
+            date: 0,
+            subject: file
+                .subject
+                .clone()
+                .unwrap_or_else(|| file.filename.clone()),
+            groups: Vec::new(),
+            segments: articles
+                .iter()
+                .filter(|a| a.file_index == file.file_index)
+                .map(|a| nntp_rs::NzbSegment {
+                    bytes: a.size_bytes as u64,
+                    number: a.segment_number as u32,
+                    message_id: a.message_id.clone(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    nntp_rs::Nzb { meta, files }
+}
+
+/// Validate the archive at `path`, extract its entries below `scratch` and parse the manifest
+fn read_queue_export(path: &Path, scratch: &Path) -> Result<QueueManifest> {
+    let invalid = |e: zip::result::ZipError| Error::InvalidBackup(e.to_string());
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?).map_err(invalid)?;
+
+    let manifest: QueueManifest = {
+        let mut json = String::new();
+        archive
+            .by_name(MANIFEST_ENTRY)
+            .map_err(invalid)?
+            .read_to_string(&mut json)?;
+        serde_json::from_str(&json)
+            .map_err(|e| Error::InvalidBackup(format!("malformed queue manifest: {}", e)))?
+    };
+    if manifest.format_version != QUEUE_FORMAT_VERSION {
+        return Err(Error::InvalidBackup(format!(
+            "unsupported queue export format version {}",
+            manifest.format_version
+        )));
+    }
+
+    for download in &manifest.downloads {
+        for entry in std::iter::once(&download.nzb).chain(download.data.values()) {
+            // Entries are extracted below the scratch dir, so they must stay inside it
+            if !Path::new(entry)
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)))
+            {
+                return Err(Error::InvalidBackup(format!(
+                    "invalid archive entry name: {}",
+                    entry
+                )));
+            }
+            let dest = scratch.join(entry);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut source = archive.by_name(entry).map_err(invalid)?;
+            std::io::copy(&mut source, &mut std::fs::File::create(&dest)?)?;
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Encode flags as hex, eight per byte with the first flag in the lowest bit
+fn encode_bitmap(bits: &[bool]) -> String {
+    bits.chunks(8)
+        .map(|chunk| {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, bit)| byte | (u8::from(*bit) << i));
+            format!("{:02x}", byte)
+        })
+        .collect()
+}
+
+/// Decode `len` flags written by [`encode_bitmap`]
+fn decode_bitmap(hex: &str, len: usize) -> Result<Vec<bool>> {
+    let invalid = || Error::InvalidBackup("malformed progress bitmap".to_string());
+    if hex.len() != len.div_ceil(8) * 2 {
+        return Err(invalid());
+    }
+
+    let mut bits = Vec::with_capacity(len);
+    for i in 0..len.div_ceil(8) {
+        let byte = hex
+            .get(i * 2..i * 2 + 2)
+            .and_then(|s| u8::from_str_radix(s, 16).ok())
+            .ok_or_else(invalid)?;
+        for bit in 0..8 {
+            if bits.len() < len {
+                bits.push(byte & (1 << bit) != 0);
+            }
+        }
+    }
+    Ok(bits)
+}
@@ -5,6 +5,7 @@ use std::time::Duration;
 
 use super::UsenetDownloader;
 use super::download_task::DownloadTaskContext;
+use crate::types::{DownloadId, Event, Priority};
 
 /// Interval between queue polling attempts when the queue is empty
 const QUEUE_POLL_INTERVAL: Duration = Duration::from_millis(100);
@@ -18,42 +19,101 @@ impl UsenetDownloader {
     /// 3. Spawns a download task for that download
     /// 4. Repeats until shutdown
     ///
+    /// A download added with [`add_nzb_url_deferred`](Self::add_nzb_url_deferred) has its
+    /// NZB fetched by the spawned task before it starts downloading.
+    ///
     /// The queue processor ensures downloads are started in priority order and
     /// respects the configured concurrency limit.
     ///
+    /// # Forced Downloads
+    ///
+    /// A [`Priority::Force`] download starts right away even when the concurrency limit
+    /// is reached, and preempts the other active downloads: they stop fetching new
+    /// articles (articles already in flight finish) so every connection serves the
+    /// forced download. They resume once the last forced download finishes
+    /// downloading. [`Event::DownloadsPreempted`] and [`Event::PreemptionEnded`] report
+    /// both transitions.
+    ///
+    /// # Fast Lane
+    ///
+    /// With `download.fast_lane` enabled, a download of at most `fast_lane.max_job_bytes`
+    /// starts in one extra slot when every other slot is taken, fetching over the
+    /// `fast_lane.connections` reserved for it (see
+    /// [`download_concurrency`](Self::download_concurrency)).
+    ///
     /// # Parallel Download Behavior
     ///
     /// Each spawned download task downloads articles **in parallel** using all configured
-    /// NNTP connections. The concurrency is automatically calculated as the sum of connections
+    /// NNTP connections (less those reserved for the fast lane). The concurrency is automatically calculated as the sum of connections
     /// across all servers (e.g., 50 connections = 50 articles downloading simultaneously).
     pub fn start_queue_processor(&self) -> tokio::task::JoinHandle<()> {
+        if self.is_read_only() {
+            tracing::info!("Read-only mode, skipping queue processor");
+            return tokio::spawn(async {});
+        }
+
         let queue = self.queue_state.queue.clone();
         let concurrent_limit = self.queue_state.concurrent_limit.clone();
+        let fast_lane_slot = self.queue_state.fast_lane_slot.clone();
         let db = self.db.clone();
         let event_tx = self.event_tx.clone();
-        let nntp_pools = self.nntp_pools.clone();
         let config = self.config.clone();
         let active_downloads = self.queue_state.active_downloads.clone();
-        let speed_limiter = self.speed_limiter.clone();
+        let draining = self.queue_state.draining.clone();
         let downloader = self.clone();
 
         tokio::spawn(async move {
             loop {
-                // Get the next download from the queue (keep full item for re-push on failure)
+                // While draining, leave queued downloads where they are
+                if draining.load(std::sync::atomic::Ordering::SeqCst) {
+                    tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
+                    continue;
+                }
+
+                // Get the next download from the queue (keep full item for re-push on failure).
+                // With every slot taken, only a forced download or one fitting the fast
+                // lane may start
+                let mut lane_permit = None;
                 let queued_item = {
                     let mut queue_guard = queue.lock().await;
-                    queue_guard.pop()
+                    if config.download.fast_lane.enabled
+                        && concurrent_limit.available_permits() == 0
+                    {
+                        lane_permit = fast_lane_slot.clone().try_acquire_owned().ok();
+                        downloader.pop_fast_lane(&mut queue_guard, lane_permit.is_some())
+                    } else {
+                        downloader.pop_startable(&mut queue_guard)
+                    }
                 };
 
                 if let Some(item) = queued_item {
                     let id = item.id;
 
-                    // Acquire a permit from the semaphore (blocks if at max concurrent downloads)
-                    let permit = concurrent_limit.clone().acquire_owned().await;
+                    let forced = item.priority == Priority::Force;
+
+                    // Acquire a permit from the semaphore (blocks if at max concurrent
+                    // downloads); forced downloads take one if free and start regardless
+                    let fast_lane = !forced && lane_permit.is_some();
+                    let permit = if fast_lane {
+                        Ok(lane_permit)
+                    } else if forced {
+                        match concurrent_limit.clone().try_acquire_owned() {
+                            Ok(p) => Ok(Some(p)),
+                            Err(tokio::sync::TryAcquireError::NoPermits) => Ok(None),
+                            Err(tokio::sync::TryAcquireError::Closed) => Err(()),
+                        }
+                    } else {
+                        concurrent_limit
+                            .clone()
+                            .acquire_owned()
+                            .await
+                            .map(Some)
+                            .map_err(|_| ())
+                    };
 
                     let permit = match permit {
                         Ok(p) => p,
-                        Err(_) => {
+                        Err(()) => {
                             // Semaphore closed — re-push the item so it isn't lost
                             let mut queue_guard = queue.lock().await;
                             queue_guard.push(item);
@@ -61,6 +121,23 @@ impl UsenetDownloader {
                         }
                     };
 
+                    if forced {
+                        downloader.start_preemption(id);
+                    }
+                    if fast_lane {
+                        tracing::info!(
+                            download_id = id.0,
+                            size_bytes = item.size_bytes,
+                            "Download slots taken, starting small download in the fast lane"
+                        );
+                        downloader
+                            .queue_state
+                            .fast_lane_downloads
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .insert(id);
+                    }
+
                     // Create cancellation token for this download
                     let cancel_token = tokio_util::sync::CancellationToken::new();
 
@@ -70,30 +147,193 @@ impl UsenetDownloader {
                         active.insert(id, cancel_token.clone());
                     }
 
+                    // Usage statistics are broken down by category
+                    let category = match db.get_download(id).await {
+                        Ok(download) => download.and_then(|d| d.category),
+                        Err(e) => {
+                            tracing::warn!(
+                                download_id = id.0,
+                                error = %e,
+                                "Failed to look up download category"
+                            );
+                            None
+                        }
+                    };
+
+                    let article_provider: Arc<dyn super::download_task::ArticleProvider> =
+                        if config.download.simulate.enabled {
+                            Arc::new(super::download_task::SimulatedArticleProvider::new(
+                                Arc::clone(&db),
+                                id,
+                                &config.download.simulate,
+                            ))
+                        } else {
+                            Arc::new(super::download_task::NntpArticleProvider::new(
+                                Arc::clone(&downloader.servers),
+                                Arc::clone(&downloader.usage),
+                                id,
+                                category,
+                            ))
+                        };
+
                     let ctx = DownloadTaskContext {
                         id,
                         db: Arc::clone(&db),
                         event_tx: event_tx.clone(),
-                        article_provider: Arc::new(super::download_task::NntpArticleProvider::new(
-                            Arc::clone(&nntp_pools),
-                        )),
+                        article_provider,
                         config: Arc::clone(&config),
                         active_downloads: Arc::clone(&active_downloads),
-                        speed_limiter: speed_limiter.clone(),
+                        speed_limiter: downloader.download_speed_limiter(id),
                         cancel_token,
                         downloader: downloader.clone(),
                     };
 
                     // Spawn the download task
+                    let task_downloader = downloader.clone();
                     tokio::spawn(async move {
                         let _permit = permit;
-                        super::download_task::run_download_task(ctx).await;
+                        let stall_rx = task_downloader.stall_signal(id);
+                        let cancel_token = ctx.cancel_token.clone();
+                        let active_downloads = Arc::clone(&ctx.active_downloads);
+                        // A deferred NZB is fetched now; if that fails the download is failed
+                        let stalled = if task_downloader.fetch_deferred_nzb(id).await {
+                            tokio::select! {
+                                _ = super::download_task::run_download_task(ctx) => None,
+                                // Fetches that hang don't see the cancellation token, so the
+                                // task is dropped; cancelling stops its progress reporting
+                                Ok(stalled) = stall_rx => {
+                                    cancel_token.cancel();
+                                    active_downloads.lock().await.remove(&id);
+                                    Some(stalled)
+                                }
+                            }
+                        } else {
+                            ctx.active_downloads.lock().await.remove(&id);
+                            None
+                        };
+                        task_downloader.clear_stall_signal(id);
+                        if let Err(e) = task_downloader.flush_download_traffic(id).await {
+                            tracing::warn!(download_id = id.0, error = %e, "Failed to record download traffic");
+                        }
+                        if forced {
+                            task_downloader.end_preemption(id);
+                        }
+                        if fast_lane {
+                            task_downloader
+                                .queue_state
+                                .fast_lane_downloads
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .remove(&id);
+                        }
+                        task_downloader.release_download_speed_limiter(id);
+                        if let Err(e) = task_downloader.rollup_usage_stats().await {
+                            tracing::warn!(error = %e, "Failed to roll up usage statistics");
+                        }
+                        if let Some(stalled) = stalled {
+                            task_downloader.act_on_stalled_download(id, stalled).await;
+                        }
+                        task_downloader.queue_state.work_finished.notify_waiters();
                     });
                 } else {
-                    // Queue is empty, wait a bit before checking again
+                    // Queue is empty (or only holds deferred downloads), wait a bit before
+                    // checking again
                     tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
                 }
             }
         })
     }
+
+    /// Number of articles `id` fetches at a time, out of `connections` in rotation
+    ///
+    /// With the fast lane enabled, its reserved connections go to the download in the
+    /// fast lane and every other download leaves them free (keeping at least one).
+    pub(crate) fn download_concurrency(&self, id: DownloadId, connections: usize) -> usize {
+        let fast_lane = &self.config.download.fast_lane;
+        if !fast_lane.enabled {
+            return connections;
+        }
+
+        let reserved = fast_lane.connections.min(connections);
+        let in_fast_lane = self
+            .queue_state
+            .fast_lane_downloads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&id);
+        if in_fast_lane {
+            reserved
+        } else {
+            (connections - reserved).max(1)
+        }
+    }
+
+    /// Hold back every download except the forced ones while `id` downloads
+    pub(crate) fn start_preemption(&self, id: DownloadId) {
+        let mut preempted = Vec::new();
+        {
+            let limiters = self
+                .runtime_config
+                .download_speed_limiters
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let mut forced = self
+                .queue_state
+                .forced_downloads
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            forced.insert(id);
+
+            for (&other, limiter) in limiters.iter() {
+                let hold = !forced.contains(&other);
+                if hold && !limiter.is_held() {
+                    preempted.push(other);
+                }
+                limiter.set_held(hold);
+            }
+        }
+
+        preempted.sort();
+        tracing::info!(
+            download_id = id.0,
+            preempted = ?preempted,
+            "Forced download started, holding back other downloads"
+        );
+        self.emit_event(Event::DownloadsPreempted { id, preempted });
+    }
+
+    /// Release the held back downloads once the last forced download is done
+    pub(crate) fn end_preemption(&self, id: DownloadId) {
+        let mut resumed = Vec::new();
+        {
+            let limiters = self
+                .runtime_config
+                .download_speed_limiters
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let mut forced = self
+                .queue_state
+                .forced_downloads
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if !forced.remove(&id) || !forced.is_empty() {
+                return;
+            }
+
+            for (&other, limiter) in limiters.iter() {
+                if limiter.is_held() {
+                    resumed.push(other);
+                    limiter.set_held(false);
+                }
+            }
+        }
+
+        resumed.sort();
+        tracing::info!(
+            download_id = id.0,
+            resumed = ?resumed,
+            "Forced downloads finished, resuming other downloads"
+        );
+        self.emit_event(Event::PreemptionEnded { id, resumed });
+    }
 }
@@ -8,43 +8,21 @@ use super::UsenetDownloader;
 impl UsenetDownloader {
     /// Get all RSS feeds
     pub async fn get_rss_feeds(&self) -> Result<Vec<crate::config::RssFeedConfig>> {
-        use std::time::Duration;
+        Ok(self
+            .list_rss_feeds()
+            .await?
+            .into_iter()
+            .map(|(_, _, config)| config)
+            .collect())
+    }
 
-        let feeds = self.db.get_all_rss_feeds().await?;
+    /// Get all RSS feeds with their IDs and names
+    pub async fn list_rss_feeds(&self) -> Result<Vec<(i64, String, crate::config::RssFeedConfig)>> {
         let mut result = Vec::new();
-
-        for feed in feeds {
-            // Get filters for this feed
-            let filter_rows = self.db.get_rss_filters(feed.id).await?;
-            let filters = filter_rows
-                .into_iter()
-                .map(|row| crate::config::RssFilter {
-                    name: row.name,
-                    include: row
-                        .include_patterns
-                        .and_then(|s| serde_json::from_str(&s).ok())
-                        .unwrap_or_default(),
-                    exclude: row
-                        .exclude_patterns
-                        .and_then(|s| serde_json::from_str(&s).ok())
-                        .unwrap_or_default(),
-                    min_size: row.min_size.map(|s| s as u64),
-                    max_size: row.max_size.map(|s| s as u64),
-                    max_age: row.max_age_secs.map(|s| Duration::from_secs(s as u64)),
-                })
-                .collect();
-
-            result.push(crate::config::RssFeedConfig {
-                url: feed.url,
-                check_interval: Duration::from_secs(feed.check_interval_secs as u64),
-                category: feed.category,
-                filters,
-                auto_download: feed.auto_download != 0,
-                priority: crate::types::Priority::from_i32(feed.priority),
-                enabled: feed.enabled != 0,
-            });
+        for feed in self.db.get_all_rss_feeds().await? {
+            let config = self.rss_feed_config(&feed).await?;
+            result.push((feed.id, feed.name, config));
         }
-
         Ok(result)
     }
 
@@ -53,13 +31,22 @@ impl UsenetDownloader {
         &self,
         id: i64,
     ) -> Result<Option<(i64, String, crate::config::RssFeedConfig)>> {
-        use std::time::Duration;
-
         let feed = match self.db.get_rss_feed(id).await? {
             Some(f) => f,
             None => return Ok(None),
         };
 
+        let config = self.rss_feed_config(&feed).await?;
+        Ok(Some((feed.id, feed.name, config)))
+    }
+
+    /// Rebuild a feed's configuration from its database row and filters
+    async fn rss_feed_config(
+        &self,
+        feed: &crate::db::RssFeed,
+    ) -> Result<crate::config::RssFeedConfig> {
+        use std::time::Duration;
+
         // Get filters for this feed
         let filter_rows = self.db.get_rss_filters(feed.id).await?;
         let filters = filter_rows
@@ -68,29 +55,36 @@ impl UsenetDownloader {
                 name: row.name,
                 include: row
                     .include_patterns
-                    .map(|s| serde_json::from_str(&s).unwrap_or_default())
+                    .and_then(|s| serde_json::from_str(&s).ok())
                     .unwrap_or_default(),
                 exclude: row
                     .exclude_patterns
-                    .map(|s| serde_json::from_str(&s).unwrap_or_default())
+                    .and_then(|s| serde_json::from_str(&s).ok())
                     .unwrap_or_default(),
                 min_size: row.min_size.map(|s| s as u64),
                 max_size: row.max_size.map(|s| s as u64),
                 max_age: row.max_age_secs.map(|s| Duration::from_secs(s as u64)),
+                action: crate::config::RssFilterAction::from_i32(row.action),
+                category: row.category,
+                priority: row.priority.map(crate::types::Priority::from_i32),
             })
             .collect();
 
-        let config = crate::config::RssFeedConfig {
-            url: feed.url,
+        Ok(crate::config::RssFeedConfig {
+            url: feed.url.clone(),
             check_interval: Duration::from_secs(feed.check_interval_secs as u64),
-            category: feed.category,
+            category: feed.category.clone(),
             filters,
             auto_download: feed.auto_download != 0,
             priority: crate::types::Priority::from_i32(feed.priority),
             enabled: feed.enabled != 0,
-        };
-
-        Ok(Some((feed.id, feed.name, config)))
+            auth: feed
+                .auth
+                .as_deref()
+                .and_then(|s| serde_json::from_str(s).ok())
+                .unwrap_or_default(),
+            preference: feed.preference,
+        })
     }
 
     /// Add a new RSS feed
@@ -99,6 +93,8 @@ impl UsenetDownloader {
         name: &str,
         config: crate::config::RssFeedConfig,
     ) -> Result<i64> {
+        let auth_json = Self::rss_auth_json(&config.auth)?;
+
         // Insert the feed
         let feed_id = self
             .db
@@ -110,6 +106,8 @@ impl UsenetDownloader {
                 auto_download: config.auto_download,
                 priority: config.priority as i32,
                 enabled: config.enabled,
+                auth: auth_json.as_deref(),
+                preference: config.preference,
             })
             .await?;
 
@@ -136,6 +134,9 @@ impl UsenetDownloader {
                     min_size: filter.min_size.map(|s| s as i64),
                     max_size: filter.max_size.map(|s| s as i64),
                     max_age_secs: filter.max_age.map(|d| d.as_secs() as i64),
+                    action: filter.action as i32,
+                    category: filter.category.as_deref(),
+                    priority: filter.priority.map(|p| p as i32),
                 })
                 .await?;
         }
@@ -150,6 +151,8 @@ impl UsenetDownloader {
         name: &str,
         config: crate::config::RssFeedConfig,
     ) -> Result<bool> {
+        let auth_json = Self::rss_auth_json(&config.auth)?;
+
         // Update the feed
         let updated = self
             .db
@@ -162,6 +165,8 @@ impl UsenetDownloader {
                 auto_download: config.auto_download,
                 priority: config.priority as i32,
                 enabled: config.enabled,
+                auth: auth_json.as_deref(),
+                preference: config.preference,
             })
             .await?;
 
@@ -194,6 +199,9 @@ impl UsenetDownloader {
                     min_size: filter.min_size.map(|s| s as i64),
                     max_size: filter.max_size.map(|s| s as i64),
                     max_age_secs: filter.max_age.map(|d| d.as_secs() as i64),
+                    action: filter.action as i32,
+                    category: filter.category.as_deref(),
+                    priority: filter.priority.map(|p| p as i32),
                 })
                 .await?;
         }
@@ -201,6 +209,14 @@ impl UsenetDownloader {
         Ok(true)
     }
 
+    /// Serialize feed authentication for the database (`None` when there is none)
+    fn rss_auth_json(auth: &crate::config::RssFeedAuth) -> Result<Option<String>> {
+        if *auth == crate::config::RssFeedAuth::default() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::to_string(auth)?))
+    }
+
     /// Delete an RSS feed
     pub async fn delete_rss_feed(&self, id: i64) -> Result<bool> {
         self.db.delete_rss_feed(id).await
@@ -208,6 +224,8 @@ impl UsenetDownloader {
 
     /// Force check an RSS feed now (for manual triggering via API)
     pub async fn check_rss_feed_now(&self, id: i64) -> Result<usize> {
+        self.ensure_writable()?;
+
         // Get the feed configuration
         let (feed_id, _name, config) = match self.get_rss_feed(id).await? {
             Some(f) => f,
@@ -1,10 +1,25 @@
-//! Server connectivity testing.
+//! Server connectivity testing and speed tests.
 
 use crate::config::ServerConfig;
-use crate::types::{ServerCapabilities, ServerTestResult};
+use crate::types::{
+    NntpTraceEntry, ServerCapabilities, ServerTestResult, SpeedTestOptions, SpeedTestResult,
+    SpeedTestRun,
+};
 
 use super::UsenetDownloader;
 
+/// Newsgroup sampled by speed tests when no group or message IDs are given
+const DEFAULT_SPEEDTEST_GROUP: &str = "alt.binaries.test";
+
+/// Bytes downloaded per speed test run when sampling a group
+const DEFAULT_SPEEDTEST_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Most recent articles considered when sampling a group
+const SPEEDTEST_OVERVIEW_WINDOW: u64 = 2000;
+
+/// Share of the best throughput a connection count must reach to be recommended
+const SPEEDTEST_RECOMMEND_RATIO: f64 = 0.95;
+
 impl UsenetDownloader {
     /// Test connectivity and authentication for a server configuration
     ///
@@ -34,6 +49,11 @@ impl UsenetDownloader {
     ///         password: Some("pass".to_string()),
     ///         connections: 10,
     ///         priority: 0,
+    ///         pipeline_depth: 10,
+    ///         speed_limit_bps: None,
+    ///         idle_timeout: None,
+    ///         cost: 0.0,
+    ///         quota_bytes: None,
     ///     };
     ///
     ///     let result = downloader.test_server(&server).await;
@@ -100,10 +120,261 @@ impl UsenetDownloader {
     /// Returns a list of server names and their test results.
     pub async fn test_all_servers(&self) -> Vec<(String, ServerTestResult)> {
         let mut results = Vec::new();
-        for server in self.config.servers.iter() {
+        for server in self.get_servers().iter() {
             let result = self.test_server(server).await;
             results.push((server.host.clone(), result));
         }
         results
     }
+
+    /// Recent NNTP exchanges for a configured server, oldest first
+    ///
+    /// `server_id` is the server's index in [`get_servers`]. Returns `None` if there
+    /// is no such server, and an empty list unless `logging.nntp_trace` is enabled.
+    ///
+    /// [`get_servers`]: UsenetDownloader::get_servers
+    pub fn nntp_trace(&self, server_id: usize) -> Option<Vec<NntpTraceEntry>> {
+        self.servers.current().tracer.entries(server_id)
+    }
+
+    /// Measure sustained download throughput from a configured server
+    ///
+    /// Downloads the same sample of articles once per connection count and reports the
+    /// throughput of each run, to help tune `connections` and `pipeline_depth`. The sample
+    /// is either `options.message_ids` or the most recent articles of a test group, up to
+    /// `options.bytes`. Test connections are opened separately from the download pools and
+    /// bypass speed limits; pause the queue first if the provider caps total connections.
+    ///
+    /// Returns `None` if there is no server at `server_id` (its index in [`get_servers`]).
+    ///
+    /// [`get_servers`]: UsenetDownloader::get_servers
+    pub async fn speed_test(
+        &self,
+        server_id: usize,
+        options: SpeedTestOptions,
+    ) -> Option<SpeedTestResult> {
+        let server = self.get_servers().get(server_id)?.clone();
+        let pipeline_depth = options
+            .pipeline_depth
+            .unwrap_or(server.pipeline_depth)
+            .max(1);
+        let connection_counts = if options.connection_counts.is_empty() {
+            default_connection_counts(server.connections)
+        } else {
+            options
+                .connection_counts
+                .iter()
+                .copied()
+                .filter(|&count| count > 0)
+                .collect()
+        };
+
+        let mut result = SpeedTestResult {
+            server: server.host.clone(),
+            pipeline_depth,
+            sample_articles: 0,
+            runs: Vec::new(),
+            recommended_connections: None,
+            error: None,
+        };
+
+        let nntp_config = std::sync::Arc::new(nntp_rs::ServerConfig::from(server.clone()));
+
+        let message_ids = if options.message_ids.is_empty() {
+            let group = options.group.as_deref().unwrap_or(DEFAULT_SPEEDTEST_GROUP);
+            let bytes = options.bytes.unwrap_or(DEFAULT_SPEEDTEST_BYTES);
+            match sample_group(&nntp_config, &server, group, bytes).await {
+                Ok(ids) if !ids.is_empty() => ids,
+                Ok(_) => {
+                    result.error = Some(format!("No articles found in {}", group));
+                    return Some(result);
+                }
+                Err(e) => {
+                    result.error = Some(format!("Failed to sample {}: {}", group, e));
+                    return Some(result);
+                }
+            }
+        } else {
+            options
+                .message_ids
+                .iter()
+                .map(|id| {
+                    if id.starts_with('<') {
+                        id.clone()
+                    } else {
+                        format!("<{}>", id)
+                    }
+                })
+                .collect()
+        };
+        result.sample_articles = message_ids.len();
+
+        for connections in connection_counts {
+            match speed_test_run(
+                &nntp_config,
+                &server,
+                &message_ids,
+                connections,
+                pipeline_depth,
+            )
+            .await
+            {
+                Ok(run) => {
+                    tracing::info!(
+                        server = %server.host,
+                        connections,
+                        bytes_per_sec = run.bytes_per_sec,
+                        "Speed test run complete"
+                    );
+                    result.runs.push(run);
+                }
+                Err(e) => {
+                    result.error = Some(format!(
+                        "Speed test with {} connections failed: {}",
+                        connections, e
+                    ));
+                    break;
+                }
+            }
+        }
+
+        result.recommended_connections = recommend_connections(&result.runs);
+        Some(result)
+    }
+}
+
+/// Connection counts measured when none are requested: 1, 2, 4, ... up to `max`
+pub(crate) fn default_connection_counts(max: usize) -> Vec<usize> {
+    let max = max.max(1);
+    let mut counts = Vec::new();
+    let mut count = 1;
+    while count < max {
+        counts.push(count);
+        count *= 2;
+    }
+    counts.push(max);
+    counts
+}
+
+/// Fewest connections reaching [`SPEEDTEST_RECOMMEND_RATIO`] of the best throughput
+pub(crate) fn recommend_connections(runs: &[SpeedTestRun]) -> Option<usize> {
+    let best = runs.iter().map(|run| run.bytes_per_sec).max()?;
+    if best == 0 {
+        return None;
+    }
+    runs.iter()
+        .filter(|run| run.bytes_per_sec as f64 >= best as f64 * SPEEDTEST_RECOMMEND_RATIO)
+        .map(|run| run.connections)
+        .min()
+}
+
+/// Pick the newest articles from an overview until their combined size reaches `max_bytes`
+pub(crate) fn select_sample(mut entries: Vec<nntp_rs::XoverEntry>, max_bytes: u64) -> Vec<String> {
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.article_number));
+    let mut total = 0u64;
+    let mut ids = Vec::new();
+    for entry in entries {
+        if total >= max_bytes {
+            break;
+        }
+        total += entry.bytes as u64;
+        ids.push(entry.message_id);
+    }
+    ids
+}
+
+/// Open an authenticated connection for a speed test
+async fn connect(
+    nntp_config: &std::sync::Arc<nntp_rs::ServerConfig>,
+    server: &ServerConfig,
+) -> nntp_rs::Result<nntp_rs::NntpClient> {
+    let mut client = nntp_rs::NntpClient::connect(std::sync::Arc::clone(nntp_config)).await?;
+    if server.username.is_some() {
+        client.authenticate().await?;
+    }
+    Ok(client)
+}
+
+/// Message IDs of recent articles in `group`, about `max_bytes` in total
+async fn sample_group(
+    nntp_config: &std::sync::Arc<nntp_rs::ServerConfig>,
+    server: &ServerConfig,
+    group: &str,
+    max_bytes: u64,
+) -> nntp_rs::Result<Vec<String>> {
+    let mut client = connect(nntp_config, server).await?;
+    let info = client.select_group(group).await?;
+    if info.count == 0 {
+        return Ok(Vec::new());
+    }
+    let first = info
+        .last
+        .saturating_sub(SPEEDTEST_OVERVIEW_WINDOW - 1)
+        .max(info.first);
+    let entries = client
+        .fetch_xover(&format!("{}-{}", first, info.last))
+        .await?;
+    Ok(select_sample(entries, max_bytes))
+}
+
+/// Download `message_ids` split across `connections` parallel connections
+async fn speed_test_run(
+    nntp_config: &std::sync::Arc<nntp_rs::ServerConfig>,
+    server: &ServerConfig,
+    message_ids: &[String],
+    connections: usize,
+    pipeline_depth: usize,
+) -> nntp_rs::Result<SpeedTestRun> {
+    let clients =
+        futures::future::try_join_all((0..connections).map(|_| connect(nntp_config, server)))
+            .await?;
+
+    // Deal articles out round-robin so every connection gets a similar share
+    let mut shares: Vec<Vec<&str>> = vec![Vec::new(); connections];
+    for (i, id) in message_ids.iter().enumerate() {
+        shares[i % connections].push(id.as_str());
+    }
+
+    let start = std::time::Instant::now();
+    let outcomes =
+        futures::future::join_all(clients.into_iter().zip(shares).map(|(mut client, ids)| {
+            async move {
+                let mut bytes = 0u64;
+                let mut failed = 0usize;
+                for chunk in ids.chunks(pipeline_depth) {
+                    match client.fetch_articles_pipelined(chunk, pipeline_depth).await {
+                        Ok(responses) => {
+                            bytes += responses.iter().map(|r| r.data.len() as u64).sum::<u64>();
+                        }
+                        // A missing article aborts the pipeline; count the chunk and move on
+                        Err(nntp_rs::NntpError::NoSuchArticle(_)) => failed += chunk.len(),
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok((bytes, failed))
+            }
+        }))
+        .await;
+    let elapsed = start.elapsed();
+
+    let mut bytes = 0u64;
+    let mut failed_articles = 0usize;
+    for outcome in outcomes {
+        let (b, f) = outcome?;
+        bytes += b;
+        failed_articles += f;
+    }
+
+    let secs = elapsed.as_secs_f64();
+    Ok(SpeedTestRun {
+        connections,
+        bytes,
+        duration_ms: elapsed.as_millis() as u64,
+        bytes_per_sec: if secs > 0.0 {
+            (bytes as f64 / secs) as u64
+        } else {
+            0
+        },
+        failed_articles,
+    })
 }
@@ -0,0 +1,592 @@
+//! Runtime server management — adding, updating and removing NNTP servers.
+//!
+//! The configured servers, their connection pools, speed limiters and command traces
+//! are kept together in a [`ServerPools`] snapshot, all indexed alike. Each change
+//! builds a new snapshot that reuses the state of unaffected servers and swaps it in.
+//! Article fetches take the current snapshot for every batch, so a replaced or removed
+//! pool gets no new requests once the swap happens and is closed when the requests
+//! already running on it finish.
+//!
+//! Connections are opened as downloads need them. Once a server's connections have sat
+//! idle for its idle timeout, its pool is swapped for a fresh one in the same way, so
+//! the old connections close without holding any open while the queue is empty.
+//!
+//! A server can also be taken out of rotation, for a while or until it's re-enabled,
+//! without changing its configuration. Disables are persisted by server key (see
+//! [`server_key`]) so they survive restarts and index changes.
+//!
+//! Fetches try servers in the order given by [`ServerPools::fetch_order`]: in server
+//! order, or with `server_routing = "least_cost"` cheapest first among the servers with
+//! quota left. Quota usage is counted as articles are fetched and recomputed from the
+//! usage statistics at startup and on every rollup, so usage older than 30 days frees
+//! up quota again.
+
+use crate::config::{Config, REDACTED, ServerConfig, ServerRouting};
+use crate::speed_limiter::SpeedLimiter;
+use crate::types::ServerStatus;
+use crate::{Error, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use super::UsenetDownloader;
+use super::nntp_trace::NntpTracer;
+
+/// Servers in use and their per-server state, indexed like `servers`
+pub(crate) struct ServerPools {
+    /// Server configurations (speed limits as configured; the limiters hold the current ones)
+    pub(crate) servers: Vec<ServerConfig>,
+    /// NNTP connection pools
+    pub(crate) pools: Vec<Arc<nntp_rs::NntpPool>>,
+    /// Per-server speed limiters, applied on top of the global limit
+    pub(crate) speed_limiters: Vec<SpeedLimiter>,
+    /// NNTP command traces
+    pub(crate) tracer: NntpTracer,
+    /// When each server is back in rotation, as a unix timestamp: 0 if it's enabled,
+    /// `i64::MAX` if it's disabled until re-enabled
+    pub(crate) disabled_until: Vec<Arc<AtomicI64>>,
+    /// When each server's pool last handed out a connection, as a unix timestamp
+    pub(crate) last_used: Vec<Arc<AtomicI64>>,
+    /// Bytes each server has served in the last 30 days, counted against `quota_bytes`
+    pub(crate) quota_used: Vec<Arc<AtomicU64>>,
+    /// How fetches pick among the servers
+    pub(crate) routing: ServerRouting,
+}
+
+impl ServerPools {
+    /// Create pools, limiters and traces for the servers in `config`
+    async fn new(config: &Config) -> Result<Self> {
+        let mut pools = Vec::with_capacity(config.servers.len());
+        for server in &config.servers {
+            pools.push(build_pool(server).await?);
+        }
+        Ok(Self {
+            servers: config.servers.clone(),
+            pools,
+            speed_limiters: config
+                .servers
+                .iter()
+                .map(|server| SpeedLimiter::new(server.speed_limit_bps))
+                .collect(),
+            tracer: NntpTracer::new(config),
+            disabled_until: config
+                .servers
+                .iter()
+                .map(|_| Arc::new(AtomicI64::new(0)))
+                .collect(),
+            last_used: config
+                .servers
+                .iter()
+                .map(|_| Arc::new(AtomicI64::new(Utc::now().timestamp())))
+                .collect(),
+            quota_used: config
+                .servers
+                .iter()
+                .map(|_| Arc::new(AtomicU64::new(0)))
+                .collect(),
+            routing: config.download.server_routing,
+        })
+    }
+
+    /// Build the state for `servers`, reusing what it can from `self`
+    ///
+    /// `previous[i]` is the index in `self` of the server now at index `i`, if it was
+    /// already there. Such servers keep their speed limiter, trace, disabled state, last
+    /// use and quota usage, and their pool unless the connection settings changed.
+    async fn rebuild(
+        &self,
+        servers: Vec<ServerConfig>,
+        previous: &[Option<usize>],
+    ) -> Result<Self> {
+        let mut pools = Vec::with_capacity(servers.len());
+        let mut speed_limiters = Vec::with_capacity(servers.len());
+        let mut disabled_until = Vec::with_capacity(servers.len());
+        let mut last_used = Vec::with_capacity(servers.len());
+        let mut quota_used = Vec::with_capacity(servers.len());
+        for (server, previous) in servers.iter().zip(previous) {
+            let reused = previous.and_then(|i| {
+                let old = self.servers.get(i)?;
+                Some((i, same_connection(old, server)))
+            });
+            let pool = match reused {
+                Some((i, true)) => Arc::clone(&self.pools[i]),
+                _ => build_pool(server).await?,
+            };
+            pools.push(pool);
+            speed_limiters.push(match reused {
+                Some((i, _)) => self.speed_limiters[i].clone(),
+                None => SpeedLimiter::new(server.speed_limit_bps),
+            });
+            disabled_until.push(match reused {
+                Some((i, _)) => Arc::clone(&self.disabled_until[i]),
+                None => Arc::new(AtomicI64::new(0)),
+            });
+            last_used.push(match reused {
+                Some((i, _)) => Arc::clone(&self.last_used[i]),
+                None => Arc::new(AtomicI64::new(Utc::now().timestamp())),
+            });
+            quota_used.push(match reused {
+                Some((i, _)) => Arc::clone(&self.quota_used[i]),
+                None => Arc::new(AtomicU64::new(0)),
+            });
+        }
+
+        Ok(Self {
+            tracer: self.tracer.rebuild(&servers, previous),
+            servers,
+            pools,
+            speed_limiters,
+            disabled_until,
+            last_used,
+            quota_used,
+            routing: self.routing,
+        })
+    }
+
+    /// Whether article fetches may use the server at `index`
+    pub(crate) fn is_enabled(&self, index: usize) -> bool {
+        self.disabled_until
+            .get(index)
+            .is_none_or(|until| until.load(Ordering::Relaxed) <= Utc::now().timestamp())
+    }
+
+    /// Note that the pool of the server at `index` is handing out a connection
+    pub(crate) fn mark_used(&self, index: usize) {
+        if let Some(last_used) = self.last_used.get(index) {
+            last_used.store(Utc::now().timestamp(), Ordering::Relaxed);
+        }
+    }
+
+    /// Count `bytes` fetched from the server at `index` against its quota
+    pub(crate) fn add_quota_usage(&self, index: usize, bytes: u64) {
+        if let Some(used) = self.quota_used.get(index) {
+            used.fetch_add(bytes, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether the server at `index` has used up its `quota_bytes`
+    pub(crate) fn quota_exhausted(&self, index: usize) -> bool {
+        match (self.servers.get(index), self.quota_used.get(index)) {
+            (Some(server), Some(used)) => server
+                .quota_bytes
+                .is_some_and(|quota| used.load(Ordering::Relaxed) >= quota),
+            _ => false,
+        }
+    }
+
+    /// Indexes of the servers a fetch tries, in the order it tries them
+    ///
+    /// Disabled servers are left out, and so are servers out of quota when routing by
+    /// cost. Servers of equal cost keep their configured order.
+    pub(crate) fn fetch_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.pools.len())
+            .filter(|&i| self.is_enabled(i))
+            .collect();
+        if self.routing == ServerRouting::LeastCost {
+            order.retain(|&i| !self.quota_exhausted(i));
+            order.sort_by(|&a, &b| self.servers[a].cost.total_cmp(&self.servers[b].cost));
+        }
+        order
+    }
+
+    /// Rotation status of the server at `index`
+    fn status(&self, index: usize) -> ServerStatus {
+        let enabled = self.is_enabled(index);
+        let until = self.disabled_until[index].load(Ordering::Relaxed);
+        ServerStatus {
+            id: index,
+            host: self.servers[index].host.clone(),
+            enabled,
+            disabled_until: (!enabled && until != i64::MAX)
+                .then(|| DateTime::from_timestamp(until, 0))
+                .flatten(),
+        }
+    }
+
+    /// Server configurations with the speed limits currently in effect
+    fn effective_servers(&self) -> Vec<ServerConfig> {
+        self.servers
+            .iter()
+            .zip(&self.speed_limiters)
+            .map(|(server, limiter)| ServerConfig {
+                speed_limit_bps: limiter.get_limit(),
+                ..server.clone()
+            })
+            .collect()
+    }
+}
+
+/// The current [`ServerPools`], replaced as a whole when servers change
+pub(crate) struct ServerRegistry {
+    current: RwLock<Arc<ServerPools>>,
+    /// Held while a change builds its new pools, so changes apply one at a time
+    changes: tokio::sync::Mutex<()>,
+}
+
+impl ServerRegistry {
+    /// Create the registry with pools for the servers in `config`
+    pub(crate) async fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            current: RwLock::new(Arc::new(ServerPools::new(config).await?)),
+            changes: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// The servers in use right now
+    pub(crate) fn current(&self) -> Arc<ServerPools> {
+        Arc::clone(&self.current.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    fn replace(&self, pools: ServerPools) {
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(pools);
+    }
+}
+
+impl UsenetDownloader {
+    /// Get the configured servers
+    ///
+    /// Servers are listed by index (the `server_id` of the other server methods), which
+    /// is the order they're tried unless `server_routing` is `least_cost`. Speed limits
+    /// are the ones currently in effect.
+    pub fn get_servers(&self) -> Vec<ServerConfig> {
+        self.servers.current().effective_servers()
+    }
+
+    /// Add a server at runtime
+    ///
+    /// The server is tried after the existing ones. Downloads that are already running
+    /// start using it with their next batch of articles. Returns the new server's index.
+    /// Changes are not persisted; restarting uses the configured servers again.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::Config`] if the server configuration is invalid, with `key` set to the
+    /// offending field (e.g. `servers[2].connections`).
+    pub async fn add_server(&self, server: ServerConfig) -> Result<usize> {
+        let _change = self.servers.changes.lock().await;
+        let current = self.servers.current();
+        let id = current.servers.len();
+        server.validate(id)?;
+
+        let mut servers = current.servers.clone();
+        servers.push(server);
+        let previous: Vec<_> = (0..id).map(Some).chain([None]).collect();
+        let updated = current.rebuild(servers, &previous).await?;
+        self.restore_disabled_servers(&updated).await?;
+        self.load_quota_usage(&updated).await?;
+        self.servers.replace(updated);
+
+        tracing::info!(server_id = id, "Server added");
+        Ok(id)
+    }
+
+    /// Replace the configuration of a server at runtime
+    ///
+    /// The server's connection pool is rebuilt if its connection settings (host, port,
+    /// TLS, credentials or connection count) changed: new requests go to the new pool
+    /// while requests already running finish on the old one. A password equal to the
+    /// redaction placeholder shown by the API keeps the current password. A disabled
+    /// server stays disabled.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NotFound`] if there is no server at `server_id`, [`Error::Config`] if the
+    /// server configuration is invalid.
+    pub async fn update_server(&self, server_id: usize, mut server: ServerConfig) -> Result<()> {
+        let _change = self.servers.changes.lock().await;
+        let current = self.servers.current();
+        let Some(existing) = current.servers.get(server_id) else {
+            return Err(server_not_found(server_id));
+        };
+        if server.password.as_deref() == Some(REDACTED) {
+            server.password = existing.password.clone();
+        }
+        server.validate(server_id)?;
+
+        let speed_limit_bps = server.speed_limit_bps;
+        let (old_key, new_key) = (server_key(existing), server_key(&server));
+        let mut servers = current.servers.clone();
+        servers[server_id] = server;
+        let previous: Vec<_> = (0..servers.len()).map(Some).collect();
+        let updated = current.rebuild(servers, &previous).await?;
+        updated.speed_limiters[server_id].set_limit(speed_limit_bps);
+        self.load_quota_usage(&updated).await?;
+        if old_key != new_key && !updated.is_enabled(server_id) && !self.is_read_only() {
+            // Keep the persisted disable under the server's new key
+            let until = updated.disabled_until[server_id].load(Ordering::Relaxed);
+            self.db.clear_server_disabled(&old_key).await?;
+            self.db
+                .set_server_disabled(&new_key, (until != i64::MAX).then_some(until))
+                .await?;
+        }
+        self.servers.replace(updated);
+
+        tracing::info!(server_id, "Server updated");
+        Ok(())
+    }
+
+    /// Remove a server at runtime
+    ///
+    /// Servers after it move up one index. Its connection pool gets no new requests and
+    /// closes once the requests already running on it finish.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NotFound`] if there is no server at `server_id`.
+    pub async fn remove_server(&self, server_id: usize) -> Result<()> {
+        let _change = self.servers.changes.lock().await;
+        let current = self.servers.current();
+        if server_id >= current.servers.len() {
+            return Err(server_not_found(server_id));
+        }
+
+        let mut servers = current.servers.clone();
+        servers.remove(server_id);
+        let previous: Vec<_> = (0..current.servers.len())
+            .filter(|&i| i != server_id)
+            .map(Some)
+            .collect();
+        self.servers
+            .replace(current.rebuild(servers, &previous).await?);
+
+        tracing::info!(server_id, "Server removed");
+        Ok(())
+    }
+
+    /// Whether each server is in rotation, in server order
+    pub fn server_statuses(&self) -> Vec<ServerStatus> {
+        let current = self.servers.current();
+        (0..current.servers.len())
+            .map(|i| current.status(i))
+            .collect()
+    }
+
+    /// Take a server out of rotation
+    ///
+    /// Article fetches skip the server, for `duration` or until [`enable_server`] if
+    /// `None`. Requests already running on it finish. The disable is persisted and
+    /// applies again after a restart, and to a server added later with the same host,
+    /// port and username.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NotFound`] if there is no server at `server_id`, [`Error::ReadOnly`] on a
+    /// read-only instance.
+    ///
+    /// [`enable_server`]: UsenetDownloader::enable_server
+    pub async fn disable_server(
+        &self,
+        server_id: usize,
+        duration: Option<Duration>,
+    ) -> Result<ServerStatus> {
+        self.ensure_writable()?;
+        let _change = self.servers.changes.lock().await;
+        let current = self.servers.current();
+        let Some(server) = current.servers.get(server_id) else {
+            return Err(server_not_found(server_id));
+        };
+
+        let until = duration.map(|duration| {
+            let secs = i64::try_from(duration.as_secs()).unwrap_or(i64::MAX);
+            Utc::now().timestamp().saturating_add(secs)
+        });
+        self.db
+            .set_server_disabled(&server_key(server), until)
+            .await?;
+        current.disabled_until[server_id].store(until.unwrap_or(i64::MAX), Ordering::Relaxed);
+
+        tracing::info!(server_id, disabled_until = until, "Server disabled");
+        Ok(current.status(server_id))
+    }
+
+    /// Put a disabled server back into rotation
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NotFound`] if there is no server at `server_id`, [`Error::ReadOnly`] on a
+    /// read-only instance.
+    pub async fn enable_server(&self, server_id: usize) -> Result<ServerStatus> {
+        self.ensure_writable()?;
+        let _change = self.servers.changes.lock().await;
+        let current = self.servers.current();
+        let Some(server) = current.servers.get(server_id) else {
+            return Err(server_not_found(server_id));
+        };
+
+        self.db.clear_server_disabled(&server_key(server)).await?;
+        current.disabled_until[server_id].store(0, Ordering::Relaxed);
+
+        tracing::info!(server_id, "Server enabled");
+        Ok(current.status(server_id))
+    }
+
+    /// Close the connections of servers whose pool has been idle past its idle timeout
+    ///
+    /// A server's timeout is its `idle_timeout`, else `connection_idle_timeout`. The
+    /// idle connections are closed by swapping in a fresh pool, which opens connections
+    /// again when they're next needed. Pools with connections in use are left alone.
+    /// Runs periodically once [`start_connection_reaper`] is called. Returns how many
+    /// servers' connections were closed.
+    ///
+    /// [`start_connection_reaper`]: UsenetDownloader::start_connection_reaper
+    pub(crate) async fn close_idle_connections(&self, now: i64) -> Result<usize> {
+        let _change = self.servers.changes.lock().await;
+        let current = self.servers.current();
+
+        let mut idle = Vec::new();
+        for (i, server) in current.servers.iter().enumerate() {
+            let Some(timeout) = server
+                .idle_timeout
+                .or(self.config.download.connection_idle_timeout)
+            else {
+                continue;
+            };
+            let state = current.pools[i].state();
+            if state.connections > state.idle_connections {
+                current.mark_used(i);
+                continue;
+            }
+            let last_used = current.last_used[i].load(Ordering::Relaxed);
+            if idle_for(state.idle_connections, last_used, timeout, now) {
+                idle.push(i);
+            }
+        }
+        if idle.is_empty() {
+            return Ok(0);
+        }
+
+        self.replace_pools(&current, &idle).await?;
+        for &i in &idle {
+            tracing::info!(
+                server_id = i,
+                connections = current.pools[i].idle_connections(),
+                "Closed idle server connections"
+            );
+        }
+
+        Ok(idle.len())
+    }
+
+    /// Close all of a server's NNTP connections and open fresh ones as they're needed
+    ///
+    /// For when the provider has invalidated the current sessions, e.g. after a VPN
+    /// reconnect changed the public IP. The server's pool is swapped for a fresh one:
+    /// new requests get new connections, and requests already running on the old ones
+    /// finish before those are closed. The server's rotation status is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NotFound`] if there is no server at `server_id`.
+    pub async fn reconnect_server(&self, server_id: usize) -> Result<ServerStatus> {
+        let _change = self.servers.changes.lock().await;
+        let current = self.servers.current();
+        if server_id >= current.servers.len() {
+            return Err(server_not_found(server_id));
+        }
+
+        self.replace_pools(&current, &[server_id]).await?;
+        tracing::info!(
+            server_id,
+            connections = current.pools[server_id].state().connections,
+            "Reconnecting server"
+        );
+        Ok(self.servers.current().status(server_id))
+    }
+
+    /// Swap in a snapshot that gives the servers at `indices` fresh, empty pools
+    ///
+    /// The caller holds the registry's change lock.
+    async fn replace_pools(&self, current: &ServerPools, indices: &[usize]) -> Result<()> {
+        let previous: Vec<_> = (0..current.servers.len()).map(Some).collect();
+        let mut updated = current.rebuild(current.servers.clone(), &previous).await?;
+        for &i in indices {
+            updated.pools[i] = build_pool(&updated.servers[i]).await?;
+        }
+        self.servers.replace(updated);
+        Ok(())
+    }
+
+    /// Set each server's quota usage in `pools` from the last 30 days of usage statistics
+    ///
+    /// Usage is matched by hostname, as in the statistics, and includes bytes not yet
+    /// rolled up.
+    pub(crate) async fn load_quota_usage(&self, pools: &ServerPools) -> Result<()> {
+        if pools
+            .servers
+            .iter()
+            .all(|server| server.quota_bytes.is_none())
+        {
+            return Ok(());
+        }
+        let since = Utc::now().date_naive() - chrono::Duration::days(29);
+        let rows = self
+            .db
+            .get_usage_since(&since.format("%Y-%m-%d").to_string())
+            .await?;
+        let mut by_host: HashMap<&str, u64> = HashMap::new();
+        for row in &rows {
+            *by_host.entry(row.server.as_str()).or_default() += row.bytes.max(0) as u64;
+        }
+        for (server, used) in pools.servers.iter().zip(&pools.quota_used) {
+            let bytes = by_host.get(server.host.as_str()).copied().unwrap_or(0)
+                + self.usage.pending_for_server(&server.host);
+            used.store(bytes, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Apply the persisted disables to the servers in `pools`
+    pub(crate) async fn restore_disabled_servers(&self, pools: &ServerPools) -> Result<()> {
+        if self.is_read_only() {
+            return Ok(());
+        }
+        let disabled = self
+            .db
+            .list_disabled_servers(Utc::now().timestamp())
+            .await?;
+        for (server, until) in pools.servers.iter().zip(&pools.disabled_until) {
+            let key = server_key(server);
+            if let Some(row) = disabled.iter().find(|row| row.server == key) {
+                until.store(row.disabled_until.unwrap_or(i64::MAX), Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Create the connection pool for a server
+async fn build_pool(server: &ServerConfig) -> Result<Arc<nntp_rs::NntpPool>> {
+    nntp_rs::NntpPool::new(server.clone().into(), server.connections as u32)
+        .await
+        .map(Arc::new)
+        .map_err(|e| Error::Nntp(format!("Failed to create NNTP pool: {}", e)))
+}
+
+/// Whether a pool holding `idle` connections, none in use, has been idle for `timeout`
+pub(crate) fn idle_for(idle: u32, last_used: i64, timeout: Duration, now: i64) -> bool {
+    idle > 0 && now.saturating_sub(last_used) >= timeout.as_secs() as i64
+}
+
+/// Whether two server configurations can share a connection pool
+fn same_connection(a: &ServerConfig, b: &ServerConfig) -> bool {
+    a.host == b.host
+        && a.port == b.port
+        && a.tls == b.tls
+        && a.username == b.username
+        && a.password == b.password
+        && a.connections == b.connections
+}
+
+/// Key a server's disable is persisted under: `host:port`, prefixed with `username@`
+fn server_key(server: &ServerConfig) -> String {
+    match &server.username {
+        Some(username) => format!("{}@{}:{}", username, server.host, server.port),
+        None => format!("{}:{}", server.host, server.port),
+    }
+}
+
+fn server_not_found(server_id: usize) -> Error {
+    Error::NotFound(format!("Server {} not found", server_id))
+}
@@ -1,19 +1,32 @@
-//! Background service starters — folder watcher, RSS scheduler, and time-based scheduler.
+//! Background service starters — folder watcher, RSS scheduler, time-based scheduler,
+//! usage statistics rollup, idle connection reaper, MQTT publisher and webhook digests.
 
 use crate::config;
 use crate::error::Result;
 use crate::folder_watcher;
+use crate::notifications::mqtt::MqttPublisher;
 use crate::rss_manager;
 use crate::rss_scheduler;
 use crate::scheduler;
 
 use super::UsenetDownloader;
 
+/// Interval between usage statistics rollups
+const STATS_ROLLUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How often the connection reaper looks for idle server connections
+const CONNECTION_REAPER_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
 impl UsenetDownloader {
     /// Start the folder watcher background task
     pub fn start_folder_watcher(&self) -> Result<tokio::task::JoinHandle<()>> {
         let watch_folders = self.config.automation.watch_folders.clone();
 
+        if self.is_read_only() {
+            tracing::info!("Read-only mode, skipping folder watcher");
+            return Ok(tokio::spawn(async {}));
+        }
+
         if watch_folders.is_empty() {
             tracing::info!("No watch folders configured, skipping folder watcher");
             return Ok(tokio::spawn(async {}));
@@ -37,6 +50,11 @@ impl UsenetDownloader {
     pub fn start_rss_scheduler(&self) -> tokio::task::JoinHandle<()> {
         let rss_feeds = self.config.automation.rss_feeds.clone();
 
+        if self.is_read_only() {
+            tracing::info!("Read-only mode, skipping RSS scheduler");
+            return tokio::spawn(async {});
+        }
+
         if rss_feeds.is_empty() {
             tracing::info!("No RSS feeds configured, skipping RSS scheduler");
             return tokio::spawn(async {});
@@ -70,6 +88,12 @@ impl UsenetDownloader {
     pub fn start_scheduler(&self) -> tokio::task::JoinHandle<()> {
         let schedule_rules = self.config.persistence.schedule_rules.clone();
 
+        // Schedule rules pause and resume the queue, which a read-only instance can't do
+        if self.is_read_only() {
+            tracing::info!("Read-only mode, skipping scheduler task");
+            return tokio::spawn(async {});
+        }
+
         if schedule_rules.is_empty() {
             tracing::info!("No schedule rules configured, skipping scheduler task");
             return tokio::spawn(async {});
@@ -83,6 +107,10 @@ impl UsenetDownloader {
                 let start_time =
                     chrono::NaiveTime::parse_from_str(&rule.start_time, "%H:%M").ok()?;
                 let end_time = chrono::NaiveTime::parse_from_str(&rule.end_time, "%H:%M").ok()?;
+                let dates = rule
+                    .date_range()
+                    .ok()?
+                    .map(|(start, end)| scheduler::DateRange { start, end });
 
                 let days: Vec<scheduler::Weekday> = rule
                     .days
@@ -114,6 +142,7 @@ impl UsenetDownloader {
                     end_time,
                     action,
                     enabled: rule.enabled,
+                    dates,
                 })
             })
             .collect();
@@ -131,4 +160,113 @@ impl UsenetDownloader {
 
         handle
     }
+
+    /// Start the task that rolls usage counters up into the statistics tables every minute
+    ///
+    /// Counters are also rolled up after each download and on shutdown, so this only
+    /// matters for keeping `GET /stats` current while downloads are running.
+    pub fn start_stats_rollup(&self) -> tokio::task::JoinHandle<()> {
+        let downloader = self.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(STATS_ROLLUP_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                if let Err(e) = downloader.rollup_usage_stats().await {
+                    tracing::warn!(error = %e, "Failed to roll up usage statistics");
+                }
+            }
+        });
+
+        tracing::info!("Usage statistics rollup task started");
+
+        handle
+    }
+
+    /// Start the task that closes idle server connections every 10 seconds
+    ///
+    /// Closes a server's connections once they've been idle for its `idle_timeout`, or
+    /// `connection_idle_timeout` if it has none. Servers without either keep idle
+    /// connections for 5 minutes.
+    pub fn start_connection_reaper(&self) -> tokio::task::JoinHandle<()> {
+        let downloader = self.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CONNECTION_REAPER_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                if let Err(e) = downloader
+                    .close_idle_connections(chrono::Utc::now().timestamp())
+                    .await
+                {
+                    tracing::warn!(error = %e, "Failed to close idle server connections");
+                }
+            }
+        });
+
+        tracing::info!("Idle connection reaper started");
+
+        handle
+    }
+
+    /// Start the task that publishes download events to the configured MQTT broker
+    ///
+    /// See [`MqttPublisher`] for the topics and payloads. Returns a task that exits
+    /// immediately when `mqtt` isn't configured.
+    pub fn start_mqtt_publisher(&self) -> tokio::task::JoinHandle<()> {
+        let Some(config) = self.config.notifications.mqtt.clone() else {
+            return tokio::spawn(async {});
+        };
+        let publisher = MqttPublisher::new(config, self.db.clone());
+
+        let handle = tokio::spawn(publisher.run(self.subscribe()));
+
+        tracing::info!("MQTT publisher started");
+
+        handle
+    }
+
+    /// Start the task that sends each digest-mode webhook its digest every
+    /// `digest_interval`
+    ///
+    /// Returns a task that exits immediately when no webhook has a `digest_interval`.
+    pub fn start_webhook_digests(&self) -> tokio::task::JoinHandle<()> {
+        let digests: Vec<(usize, std::time::Duration)> = self
+            .config
+            .notifications
+            .webhooks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, webhook)| webhook.digest_interval.map(|period| (index, period)))
+            .collect();
+
+        if digests.is_empty() {
+            return tokio::spawn(async {});
+        }
+
+        let webhook_count = digests.len();
+        let downloader = self.clone();
+
+        let handle = tokio::spawn(async move {
+            futures::future::join_all(digests.into_iter().map(|(index, period)| {
+                let downloader = downloader.clone();
+                async move {
+                    let mut interval =
+                        tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+                    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                    loop {
+                        interval.tick().await;
+                        downloader.send_webhook_digest(index).await;
+                    }
+                }
+            }))
+            .await;
+        });
+
+        tracing::info!(webhooks = webhook_count, "Webhook digest task started");
+
+        handle
+    }
 }
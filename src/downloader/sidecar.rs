@@ -0,0 +1,171 @@
+//! Metadata sidecar written into the destination of completed downloads.
+//!
+//! Enabled with `processing.completion_sidecar`. The file is named after the job
+//! (`{job_name}.usenet-dl.json` or `.usenet-dl.nfo`) so jobs sharing a destination
+//! folder don't overwrite each other's sidecars.
+
+use crate::config::SidecarFormat;
+use crate::db::Download;
+use crate::post_processing::PostProcessSummary;
+use crate::types::DownloadId;
+use crate::utils::job_file_stem;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::UsenetDownloader;
+
+/// Message-IDs of the first articles recorded in the sidecar
+const MESSAGE_ID_SAMPLE_SIZE: i64 = 5;
+
+/// Contents of a completion sidecar
+#[derive(Debug, Serialize)]
+struct CompletionSidecar {
+    download_id: i64,
+    /// Name the NZB was added under
+    name: String,
+    /// Title from the NZB's metadata
+    nzb_title: Option<String>,
+    job_name: Option<String>,
+    category: Option<String>,
+    nzb_hash: Option<String>,
+    size_bytes: i64,
+    message_ids: Vec<String>,
+    added_at: DateTime<Utc>,
+    started_at: Option<DateTime<Utc>>,
+    downloaded_at: Option<DateTime<Utc>>,
+    completed_at: DateTime<Utc>,
+    post_processing: PostProcessSummary,
+}
+
+impl UsenetDownloader {
+    /// Write the completion sidecar for a download that finished post-processing
+    ///
+    /// Does nothing unless `processing.completion_sidecar` is set. Failures are
+    /// logged and never fail the download.
+    pub(super) async fn write_completion_sidecar(&self, download: &Download, final_path: &Path) {
+        let id = DownloadId(download.id);
+        let summary = self.processing.post_processor.take_summary(id);
+        let format = self.config.processing.completion_sidecar;
+        if format == SidecarFormat::None {
+            return;
+        }
+
+        let message_ids = match self.db.sample_message_ids(id, MESSAGE_ID_SAMPLE_SIZE).await {
+            Ok(message_ids) => message_ids,
+            Err(e) => {
+                tracing::warn!(download_id = id.0, error = %e, "Failed to read message IDs for sidecar");
+                Vec::new()
+            }
+        };
+        let timestamp = |ts: i64| DateTime::from_timestamp(ts, 0);
+        let sidecar = CompletionSidecar {
+            download_id: download.id,
+            name: download.name.clone(),
+            nzb_title: download.nzb_meta_name.clone(),
+            job_name: download.job_name.clone(),
+            category: download.category.clone(),
+            nzb_hash: download.nzb_hash.clone(),
+            size_bytes: download.size_bytes,
+            message_ids,
+            added_at: timestamp(download.created_at).unwrap_or_else(Utc::now),
+            started_at: download.started_at.and_then(timestamp),
+            downloaded_at: download.completed_at.and_then(timestamp),
+            completed_at: Utc::now(),
+            post_processing: summary,
+        };
+
+        let (extension, contents) = match format {
+            SidecarFormat::None => return,
+            SidecarFormat::Json => match serde_json::to_string_pretty(&sidecar) {
+                Ok(json) => ("json", json),
+                Err(e) => {
+                    tracing::warn!(download_id = id.0, error = %e, "Failed to serialize sidecar");
+                    return;
+                }
+            },
+            SidecarFormat::Nfo => ("nfo", sidecar.to_nfo()),
+        };
+
+        let job_name = download.job_name.as_deref().unwrap_or(&download.name);
+        let path = sidecar_dir(final_path).join(format!(
+            "{}.usenet-dl.{}",
+            job_file_stem(job_name),
+            extension
+        ));
+        if let Err(e) = tokio::fs::write(&path, contents).await {
+            tracing::warn!(
+                download_id = id.0,
+                path = %path.display(),
+                error = %e,
+                "Failed to write completion sidecar"
+            );
+        }
+    }
+}
+
+/// The folder a download's files ended up in
+fn sidecar_dir(final_path: &Path) -> PathBuf {
+    if final_path.is_dir() {
+        final_path.to_path_buf()
+    } else {
+        final_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| final_path.to_path_buf())
+    }
+}
+
+impl CompletionSidecar {
+    /// Render as plain-text `key: value` lines, omitting empty values
+    fn to_nfo(&self) -> String {
+        let mut nfo = String::new();
+        let mut line = |key: &str, value: &dyn std::fmt::Display| {
+            let _ = writeln!(nfo, "{}: {}", key, value);
+        };
+        let join = |values: &[String]| values.join(", ");
+        let summary = &self.post_processing;
+
+        line("Download ID", &self.download_id);
+        line("Name", &self.name);
+        if let Some(title) = &self.nzb_title {
+            line("NZB Title", title);
+        }
+        if let Some(job_name) = &self.job_name {
+            line("Job Name", job_name);
+        }
+        if let Some(category) = &self.category {
+            line("Category", category);
+        }
+        if let Some(hash) = &self.nzb_hash {
+            line("NZB Hash", hash);
+        }
+        line("Size", &self.size_bytes);
+        if !self.message_ids.is_empty() {
+            line("Message-IDs", &join(&self.message_ids));
+        }
+        line("Added", &self.added_at.to_rfc3339());
+        if let Some(started_at) = self.started_at {
+            line("Started", &started_at.to_rfc3339());
+        }
+        if let Some(downloaded_at) = self.downloaded_at {
+            line("Downloaded", &downloaded_at.to_rfc3339());
+        }
+        line("Completed", &self.completed_at.to_rfc3339());
+        line("Verified", &summary.verified);
+        line("Damaged", &summary.damaged);
+        if !summary.repaired_files.is_empty() {
+            line("Repaired Files", &join(&summary.repaired_files));
+        }
+        line("Direct Unpack", &summary.direct_unpack);
+        if !summary.extracted_archives.is_empty() {
+            line("Extracted Archives", &join(&summary.extracted_archives));
+        }
+        if !summary.failed_archives.is_empty() {
+            line("Failed Archives", &join(&summary.failed_archives));
+        }
+        line("Extracted Files", &summary.extracted_files);
+        nfo
+    }
+}
@@ -0,0 +1,208 @@
+//! Usage statistics: bytes downloaded per server and per category.
+//!
+//! Article fetches add to in-memory counters, which are periodically rolled up into
+//! daily rows in the `usage_stats` table. Weekly and monthly figures are summed from
+//! the daily rows when queried. The same fetches are counted per download, and added to
+//! the download's row when its task ends so they end up in its history entry.
+
+use crate::Result;
+use crate::types::{
+    CategoryUsage, DailyUsage, DownloadId, Event, ServerUsage, StatsPeriod, UsageStats,
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::UsenetDownloader;
+
+/// Date format used for the `day` column and in API responses
+const DAY_FORMAT: &str = "%Y-%m-%d";
+
+/// Bytes downloaded since the last rollup, keyed by (server, category), and since each
+/// download's last flush, keyed by server
+#[derive(Default)]
+pub(crate) struct UsageCollector {
+    pending: Mutex<HashMap<(String, String), u64>>,
+    downloads: Mutex<HashMap<DownloadId, HashMap<String, u64>>>,
+}
+
+impl UsageCollector {
+    /// Count bytes fetched from the server with hostname `server` for download `id` in `category`
+    pub(crate) fn record(&self, id: DownloadId, server: &str, category: Option<&str>, bytes: u64) {
+        self.add(
+            server.to_string(),
+            category.unwrap_or_default().to_string(),
+            bytes,
+        );
+        self.add_download(id, server.to_string(), bytes);
+    }
+
+    fn add_download(&self, id: DownloadId, server: String, bytes: u64) {
+        if bytes == 0 {
+            return;
+        }
+        let mut downloads = self.downloads.lock().unwrap_or_else(|e| e.into_inner());
+        *downloads.entry(id).or_default().entry(server).or_default() += bytes;
+    }
+
+    /// Take the bytes fetched for download `id` since the last take, by server
+    pub(super) fn take_download(&self, id: DownloadId) -> HashMap<String, u64> {
+        self.downloads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id)
+            .unwrap_or_default()
+    }
+
+    fn add(&self, server: String, category: String, bytes: u64) {
+        if bytes == 0 {
+            return;
+        }
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        *pending.entry((server, category)).or_default() += bytes;
+    }
+
+    /// Bytes fetched from the server with hostname `server` since the last rollup
+    pub(super) fn pending_for_server(&self, server: &str) -> u64 {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|((host, _), _)| host == server)
+            .map(|(_, bytes)| bytes)
+            .sum()
+    }
+
+    /// Take all pending counters, leaving the collector empty
+    fn take(&self) -> HashMap<(String, String), u64> {
+        std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl UsenetDownloader {
+    /// Write buffered usage counters to the database
+    ///
+    /// Bytes are attributed to the current UTC day, and the servers' quota usage is
+    /// recomputed from the result. Emits [`Event::StatsRollup`] when anything was written. Runs periodically once [`start_stats_rollup`] is called,
+    /// after every download and on shutdown.
+    ///
+    /// [`start_stats_rollup`]: UsenetDownloader::start_stats_rollup
+    pub async fn rollup_usage_stats(&self) -> Result<()> {
+        let pending = self.usage.take();
+        if pending.is_empty() {
+            return self.load_quota_usage(&self.servers.current()).await;
+        }
+
+        let day = chrono::Utc::now().format(DAY_FORMAT).to_string();
+        let mut total = 0u64;
+        let mut failed = None;
+        for ((server, category), bytes) in pending {
+            if failed.is_some() {
+                // Keep the rest for the next rollup
+                self.usage.add(server, category, bytes);
+                continue;
+            }
+            match self.db.add_usage(&day, &server, &category, bytes).await {
+                Ok(()) => total += bytes,
+                Err(e) => {
+                    self.usage.add(server, category, bytes);
+                    failed = Some(e);
+                }
+            }
+        }
+
+        if total > 0 {
+            self.emit_event(Event::StatsRollup {
+                date: day,
+                bytes: total,
+            });
+        }
+
+        if let Some(e) = failed {
+            return Err(e);
+        }
+        self.load_quota_usage(&self.servers.current()).await
+    }
+
+    /// Add the bytes fetched for a download since the last flush to its row
+    ///
+    /// Runs when the download's task ends and before it's recorded in history. On
+    /// failure the bytes are kept for the next flush.
+    pub(crate) async fn flush_download_traffic(&self, id: DownloadId) -> Result<()> {
+        let traffic = self.usage.take_download(id);
+        if traffic.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(e) = self.db.add_download_traffic(id, &traffic).await {
+            for (server, bytes) in traffic {
+                self.usage.add_download(id, server, bytes);
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Get usage statistics for a rolling period ending today (UTC)
+    ///
+    /// Only includes bytes already rolled up; see [`rollup_usage_stats`].
+    ///
+    /// [`rollup_usage_stats`]: UsenetDownloader::rollup_usage_stats
+    pub async fn usage_stats(&self, period: StatsPeriod) -> Result<UsageStats> {
+        let end = chrono::Utc::now().date_naive();
+        let start = end - chrono::Duration::days(period.days() - 1);
+        let start_str = start.format(DAY_FORMAT).to_string();
+
+        let rows = self.db.get_usage_since(&start_str).await?;
+
+        let mut servers: HashMap<String, u64> = HashMap::new();
+        let mut categories: HashMap<String, u64> = HashMap::new();
+        let mut days: HashMap<String, u64> = HashMap::new();
+        let mut total_bytes = 0u64;
+        for row in rows {
+            let bytes = row.bytes.max(0) as u64;
+            total_bytes += bytes;
+            *servers.entry(row.server).or_default() += bytes;
+            *categories.entry(row.category).or_default() += bytes;
+            *days.entry(row.day).or_default() += bytes;
+        }
+
+        let mut servers: Vec<ServerUsage> = servers
+            .into_iter()
+            .map(|(server, bytes)| ServerUsage { server, bytes })
+            .collect();
+        servers.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.server.cmp(&b.server)));
+
+        let mut categories: Vec<CategoryUsage> = categories
+            .into_iter()
+            .map(|(category, bytes)| CategoryUsage {
+                category: (!category.is_empty()).then_some(category),
+                bytes,
+            })
+            .collect();
+        categories.sort_by(|a, b| {
+            b.bytes
+                .cmp(&a.bytes)
+                .then_with(|| a.category.cmp(&b.category))
+        });
+
+        let daily = start
+            .iter_days()
+            .take(period.days() as usize)
+            .map(|day| {
+                let date = day.format(DAY_FORMAT).to_string();
+                let bytes = days.get(&date).copied().unwrap_or(0);
+                DailyUsage { date, bytes }
+            })
+            .collect();
+
+        Ok(UsageStats {
+            period,
+            start: start_str,
+            end: end.format(DAY_FORMAT).to_string(),
+            total_bytes,
+            servers,
+            categories,
+            daily,
+        })
+    }
+}
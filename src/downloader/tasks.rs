@@ -5,14 +5,14 @@
 
 use crate::db::article_status;
 use crate::db::{Article, Database};
-use crate::error::{DatabaseError, Error, Result};
+use crate::error::{DatabaseError, Error, Result, failure_code};
+use crate::event_bus::EventSender;
 use crate::types::{DownloadId, Event, Status};
 use futures::stream::{self, StreamExt};
 use nntp_rs::NntpPool;
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
-use tokio::sync::broadcast;
 
 use super::UsenetDownloader;
 
@@ -113,7 +113,7 @@ fn tally_results(
 /// Handle download failure by updating status and emitting events
 async fn handle_download_failure(
     db: &Database,
-    event_tx: &broadcast::Sender<Event>,
+    event_tx: &EventSender,
     download_id: DownloadId,
     failures: usize,
     successes: usize,
@@ -130,7 +130,8 @@ async fn handle_download_failure(
 
     db.update_status(download_id, Status::Failed.to_i32())
         .await?;
-    db.set_error(download_id, &error_msg).await?;
+    db.set_failure(download_id, failure_code::MISSING_ARTICLES, &error_msg)
+        .await?;
 
     event_tx
         .send(Event::DownloadFailed {
@@ -151,7 +152,7 @@ async fn handle_download_failure(
 /// Parameters for emitting final progress
 struct FinalProgressParams<'a> {
     db: &'a Database,
-    event_tx: &'a broadcast::Sender<Event>,
+    event_tx: &'a EventSender,
     download_id: DownloadId,
     downloaded_bytes: u64,
     downloaded_articles: u64,
@@ -222,7 +223,7 @@ impl UsenetDownloader {
     ) -> tokio::task::JoinHandle<Result<()>> {
         let db = self.db.clone();
         let event_tx = self.event_tx.clone();
-        let nntp_pools = self.nntp_pools.clone();
+        let servers = self.servers.clone();
         let config = self.config.clone();
         let downloader = self.clone();
 
@@ -242,6 +243,7 @@ impl UsenetDownloader {
             db.update_status(download_id, Status::Downloading.to_i32())
                 .await?;
             db.set_started(download_id).await?;
+            db.clear_all_stage_checkpoints(download_id).await?;
 
             // Emit Downloading event (initial progress 0%)
             event_tx
@@ -291,10 +293,7 @@ impl UsenetDownloader {
             let download_start = std::time::Instant::now();
 
             // Create temp directory for this download
-            let download_temp_dir = config
-                .download
-                .temp_dir
-                .join(format!("download_{}", download_id));
+            let download_temp_dir = download.incomplete_path(&config.download.temp_dir);
             tokio::fs::create_dir_all(&download_temp_dir)
                 .await
                 .map_err(|e| {
@@ -305,23 +304,31 @@ impl UsenetDownloader {
                 })?;
 
             // Calculate concurrency limit from server connections
-            let concurrency: usize = config.servers.iter().map(|s| s.connections).sum();
+            let concurrency: usize = servers
+                .current()
+                .servers
+                .iter()
+                .map(|s| s.connections)
+                .sum();
 
             // Download articles in parallel using buffered stream
             let results: Vec<std::result::Result<(i32, u64), String>> =
                 stream::iter(pending_articles)
                     .map(|article| {
-                        let nntp_pools = Arc::clone(&nntp_pools);
+                        let servers = Arc::clone(&servers);
                         let db = Arc::clone(&db);
                         let download_temp_dir = download_temp_dir.clone();
                         let downloaded_articles = Arc::clone(&downloaded_articles);
                         let downloaded_bytes = Arc::clone(&downloaded_bytes);
 
                         async move {
-                            // Get a connection from the first NNTP pool
-                            let pool = nntp_pools
-                                .first()
-                                .ok_or_else(|| "No NNTP pools configured".to_string())?;
+                            // Get a connection from the first NNTP pool in rotation
+                            let servers = servers.current();
+                            let pool_idx = (0..servers.pools.len())
+                                .find(|&i| servers.is_enabled(i))
+                                .ok_or_else(|| "No NNTP pools available".to_string())?;
+                            servers.mark_used(pool_idx);
+                            let pool = &servers.pools[pool_idx];
 
                             let result = fetch_article(
                                 pool,
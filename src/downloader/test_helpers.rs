@@ -30,10 +30,7 @@ pub(crate) async fn create_test_downloader() -> (UsenetDownloader, tempfile::Tem
         .unwrap();
 
     // Create broadcast channel
-    let (event_tx, _rx) = tokio::sync::broadcast::channel(1000);
-
-    // No NNTP pools since we have no servers
-    let nntp_pools = Vec::new();
+    let (event_tx, _rx) = crate::event_bus::channel(1000);
 
     // Create priority queue
     let queue = Arc::new(tokio::sync::Mutex::new(std::collections::BinaryHeap::new()));
@@ -81,6 +78,20 @@ pub(crate) async fn create_test_downloader() -> (UsenetDownloader, tempfile::Tem
         concurrent_limit,
         active_downloads,
         accepting_new: Arc::new(std::sync::atomic::AtomicBool::new(true)),
+        draining: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        post_processing_active: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        work_finished: Arc::new(tokio::sync::Notify::new()),
+        drain_pending: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        forced_downloads: Arc::new(std::sync::Mutex::new(std::collections::HashSet::new())),
+        fast_lane_slot: Arc::new(tokio::sync::Semaphore::new(1)),
+        fast_lane_downloads: Arc::new(std::sync::Mutex::new(std::collections::HashSet::new())),
+        post_processing_paused: Arc::new(tokio::sync::watch::Sender::new(false)),
+        post_processing_slots: Arc::new(tokio::sync::Semaphore::new(
+            config.processing.max_parallel_jobs,
+        )),
+        post_processing_queue: Arc::new(std::sync::Mutex::new(Vec::new())),
+        stalls: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+        stall_signals: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
     };
 
     // Group runtime configuration
@@ -88,6 +99,8 @@ pub(crate) async fn create_test_downloader() -> (UsenetDownloader, tempfile::Tem
         categories,
         schedule_rules,
         next_schedule_rule_id,
+        download_speed_limiters: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+        bandwidth: Arc::new(std::sync::Mutex::new(Default::default())),
     };
 
     // Group post-processing pipeline
@@ -96,11 +109,28 @@ pub(crate) async fn create_test_downloader() -> (UsenetDownloader, tempfile::Tem
         parity_handler,
     };
 
+    let envelopes = super::events::EnvelopeRelay::spawn(
+        &event_tx,
+        db_arc.clone(),
+        0,
+        config.persistence.event_journal_size,
+    );
+
     let downloader = UsenetDownloader {
         db: db_arc,
+        envelopes,
         event_tx,
         config: config_arc,
-        nntp_pools: Arc::new(nntp_pools),
+        servers: Arc::new(
+            super::server_pools::ServerRegistry::new(&config)
+                .await
+                .unwrap(),
+        ),
+        usage: Arc::new(super::stats::UsageCollector::default()),
+        shared_articles: Arc::new(super::download_task::SharedArticleCache::new(
+            &config.download.temp_dir,
+        )),
+        digests: Arc::new(crate::notifications::digest::DigestBuffer::new(0)),
         speed_limiter,
         queue_state,
         runtime_config,
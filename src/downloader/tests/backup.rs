@@ -0,0 +1,141 @@
+use super::*;
+use crate::config::{CategoryConfig, ScheduleAction, ScheduleRule};
+
+fn test_rule(name: &str) -> ScheduleRule {
+    ScheduleRule {
+        name: name.to_string(),
+        days: vec![],
+        start_time: "00:00".to_string(),
+        end_time: "06:00".to_string(),
+        action: ScheduleAction::Unlimited,
+        enabled: true,
+        start_date: None,
+        end_date: None,
+    }
+}
+
+#[tokio::test]
+async fn export_and_import_round_trip() {
+    let (source, source_dir) = create_test_downloader().await;
+    let id = source
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "migrated",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+    source
+        .add_or_update_category(
+            "tv",
+            CategoryConfig {
+                destination: source_dir.path().join("tv"),
+                post_process: None,
+                scripts: vec![],
+                flatten_single_folder: None,
+                junk_filter: None,
+                verify_policy: None,
+            },
+        )
+        .await;
+    source.add_schedule_rule(test_rule("night")).await;
+
+    let archive = source_dir.path().join("backup.zip");
+    source.export_backup(&archive).await.unwrap();
+    assert!(archive.exists());
+
+    let (target, _target_dir) = create_test_downloader().await;
+    target
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "local", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    let config = target.import_backup(&archive).await.unwrap();
+    assert!(config.persistence.categories.contains_key("tv"));
+
+    let downloads = target.db.list_downloads().await.unwrap();
+    assert_eq!(downloads.len(), 1, "local downloads are replaced");
+    assert_eq!(downloads[0].id, id.0);
+    assert_eq!(downloads[0].name, "migrated");
+
+    let queue = target.queue_state.queue.lock().await;
+    assert_eq!(
+        queue.len(),
+        1,
+        "queue is rebuilt from the restored database"
+    );
+    assert_eq!(queue.peek().unwrap().id, id);
+    drop(queue);
+
+    assert!(target.get_categories().await.contains_key("tv"));
+    let rules = target.get_schedule_rules().await;
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].name, "night");
+
+    // Scratch files are cleaned up
+    let leftovers: Vec<_> = std::fs::read_dir(&target.config.download.temp_dir)
+        .unwrap()
+        .collect();
+    assert!(leftovers.is_empty());
+}
+
+#[tokio::test]
+async fn import_rejects_invalid_archive() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+    let archive = temp_dir.path().join("not-a-backup.zip");
+    std::fs::write(&archive, b"definitely not a zip").unwrap();
+
+    let result = downloader.import_backup(&archive).await;
+    assert!(
+        matches!(result, Err(Error::InvalidBackup(_))),
+        "got {:?}",
+        result.map(|_| ())
+    );
+}
+
+#[tokio::test]
+async fn import_rejects_archive_from_newer_schema() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+    let archive = temp_dir.path().join("future.zip");
+    {
+        use std::io::Write;
+        let mut writer = ::zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+        writer
+            .start_file("manifest.json", ::zip::write::FileOptions::default())
+            .unwrap();
+        writer
+            .write_all(
+                br#"{"format_version":1,"app_version":"99.0.0","schema_version":9999,"created_at":"2030-01-01T00:00:00Z"}"#,
+            )
+            .unwrap();
+        writer.finish().unwrap();
+    }
+
+    let result = downloader.import_backup(&archive).await;
+    assert!(
+        matches!(result, Err(Error::InvalidBackup(ref msg)) if msg.contains("newer version")),
+        "got {:?}",
+        result.map(|_| ())
+    );
+}
+
+#[tokio::test]
+async fn import_refused_while_downloads_are_active() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+    let archive = temp_dir.path().join("backup.zip");
+    downloader.export_backup(&archive).await.unwrap();
+
+    downloader
+        .queue_state
+        .active_downloads
+        .lock()
+        .await
+        .insert(DownloadId(1), tokio_util::sync::CancellationToken::new());
+
+    let result = downloader.import_backup(&archive).await;
+    assert!(
+        matches!(result, Err(Error::Busy(_))),
+        "got {:?}",
+        result.map(|_| ())
+    );
+}
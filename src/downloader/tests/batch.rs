@@ -0,0 +1,220 @@
+use super::*;
+
+async fn add_to_batch(downloader: &UsenetDownloader, name: &str, batch_id: i64) -> DownloadId {
+    downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            name,
+            DownloadOptions {
+                batch_id: Some(batch_id),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_batch_aggregates_member_downloads() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let batch_id = downloader.create_batch("Season 1").await.unwrap();
+    let e1 = add_to_batch(&downloader, "S01E01", batch_id).await;
+    let e2 = add_to_batch(&downloader, "S01E02", batch_id).await;
+    // Not part of the batch
+    downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "other", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    downloader
+        .db
+        .update_progress(e1, 50.0, 1000, 640_000)
+        .await
+        .unwrap();
+
+    let batch = downloader.get_batch(batch_id).await.unwrap().unwrap();
+    assert_eq!(batch.name, "Season 1");
+    assert_eq!(batch.total, 2);
+    assert_eq!(
+        batch.downloads.iter().map(|d| d.id).collect::<Vec<_>>(),
+        vec![e1, e2]
+    );
+    assert_eq!(batch.size_bytes, 2 * 1_280_000);
+    assert_eq!(batch.downloaded_bytes, 640_000);
+    assert!((batch.progress - 25.0).abs() < 0.01);
+    assert_eq!(batch.succeeded, 0);
+    assert_eq!(batch.failed, 0);
+    assert!(batch.completed_at.is_none());
+}
+
+#[tokio::test]
+async fn test_get_unknown_batch_returns_none() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    assert!(downloader.get_batch(999).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_add_to_unknown_batch_is_rejected() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let result = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "orphan",
+            DownloadOptions {
+                batch_id: Some(42),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(Error::Database(crate::error::DatabaseError::NotFound(_)))
+    ));
+    assert!(downloader.db.list_downloads().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_batch_completes_once_when_last_member_finishes() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut events = downloader.subscribe();
+
+    let batch_id = downloader.create_batch("Season 1").await.unwrap();
+    let e1 = add_to_batch(&downloader, "S01E01", batch_id).await;
+    let e2 = add_to_batch(&downloader, "S01E02", batch_id).await;
+
+    downloader
+        .db
+        .update_status(e1, Status::Complete.to_i32())
+        .await
+        .unwrap();
+    downloader.finish_batch_member(e1).await;
+    assert!(
+        downloader
+            .get_batch(batch_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .completed_at
+            .is_none(),
+        "batch must stay open while a member is pending"
+    );
+
+    downloader
+        .db
+        .update_status(e2, Status::Failed.to_i32())
+        .await
+        .unwrap();
+    downloader.finish_batch_member(e2).await;
+    // A repeated finish must not fire the hook again
+    downloader.finish_batch_member(e2).await;
+
+    let mut completions = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let Event::BatchComplete {
+            id,
+            succeeded,
+            failed,
+            ..
+        } = event
+        {
+            completions.push((id, succeeded, failed));
+        }
+    }
+    assert_eq!(completions, vec![(batch_id, 1, 1)]);
+
+    let batch = downloader.get_batch(batch_id).await.unwrap().unwrap();
+    assert!(batch.completed_at.is_some());
+    assert_eq!(batch.succeeded, 1);
+    assert_eq!(batch.failed, 1);
+
+    // A completed batch no longer accepts downloads
+    let result = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "late",
+            DownloadOptions {
+                batch_id: Some(batch_id),
+                ..Default::default()
+            },
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::Database(
+            crate::error::DatabaseError::ConstraintViolation(_)
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn test_complete_status_alone_does_not_finish_batch_member() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let batch_id = downloader.create_batch("Pack").await.unwrap();
+    let e1 = add_to_batch(&downloader, "part1", batch_id).await;
+    let e2 = add_to_batch(&downloader, "part2", batch_id).await;
+
+    // e1 finished downloading but hasn't been post-processed yet
+    downloader
+        .db
+        .update_status(e1, Status::Complete.to_i32())
+        .await
+        .unwrap();
+    downloader
+        .db
+        .update_status(e2, Status::Complete.to_i32())
+        .await
+        .unwrap();
+    downloader.finish_batch_member(e2).await;
+
+    let batch = downloader.get_batch(batch_id).await.unwrap().unwrap();
+    assert!(batch.completed_at.is_none());
+    assert_eq!(batch.succeeded, 1, "only the post-processed member counts");
+}
+
+#[tokio::test]
+async fn test_removing_last_pending_member_completes_batch() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut events = downloader.subscribe();
+
+    let batch_id = downloader.create_batch("Pack").await.unwrap();
+    let e1 = add_to_batch(&downloader, "part1", batch_id).await;
+    let e2 = add_to_batch(&downloader, "part2", batch_id).await;
+
+    downloader
+        .db
+        .update_status(e1, Status::Complete.to_i32())
+        .await
+        .unwrap();
+    downloader.finish_batch_member(e1).await;
+
+    downloader.cancel(e2).await.unwrap();
+
+    let completed = std::iter::from_fn(|| events.try_recv().ok()).any(
+        |e| matches!(e, Event::BatchComplete { id, succeeded: 1, failed: 0, .. } if id == batch_id),
+    );
+    assert!(
+        completed,
+        "removing the last pending member should complete the batch"
+    );
+}
+
+#[tokio::test]
+async fn test_post_processing_finishes_batch_member() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut events = downloader.subscribe();
+
+    let batch_id = downloader.create_batch("Single").await.unwrap();
+    let id = add_to_batch(&downloader, "only", batch_id).await;
+
+    // Post-processing either succeeds or fails with no files on disk; both finish the member
+    let _ = downloader.start_post_processing(id).await;
+
+    let completed = std::iter::from_fn(|| events.try_recv().ok())
+        .any(|e| matches!(e, Event::BatchComplete { id, .. } if id == batch_id));
+    assert!(completed);
+}
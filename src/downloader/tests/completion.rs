@@ -0,0 +1,84 @@
+use super::*;
+use crate::downloader::completion::{ArticleCheck, summarize_completion};
+
+#[test]
+fn summary_counts_article_available_if_any_server_has_it() {
+    use ArticleCheck::*;
+
+    let servers = vec!["primary".to_string(), "backup".to_string()];
+    let results = vec![
+        vec![Available, Missing, Missing, Error],
+        vec![Missing, Available, Missing, Error],
+    ];
+    let check = summarize_completion(DownloadId(7), 40, 4, &servers, &results);
+
+    assert_eq!(check.download_id, DownloadId(7));
+    assert_eq!(check.total_articles, 40);
+    assert_eq!(check.sampled_articles, 4);
+    assert_eq!(check.available_articles, 2);
+    assert_eq!(check.missing_articles, 1);
+    // The article neither server could answer for is left out of the estimate
+    assert_eq!(check.estimated_completion, Some(2.0 / 3.0));
+
+    assert_eq!(check.servers.len(), 2);
+    assert_eq!(check.servers[0].server, "primary");
+    assert_eq!(check.servers[0].available, 1);
+    assert_eq!(check.servers[0].missing, 2);
+    assert_eq!(check.servers[0].errors, 1);
+    assert_eq!(check.servers[1].server, "backup");
+    assert_eq!(check.servers[1].available, 1);
+}
+
+#[test]
+fn summary_without_answers_has_no_estimate() {
+    let check = summarize_completion(
+        DownloadId(1),
+        10,
+        2,
+        &["primary".to_string()],
+        &[vec![ArticleCheck::Error, ArticleCheck::Error]],
+    );
+    assert_eq!(check.available_articles, 0);
+    assert_eq!(check.missing_articles, 0);
+    assert_eq!(check.estimated_completion, None);
+
+    let check = summarize_completion(DownloadId(1), 10, 2, &[], &[]);
+    assert_eq!(check.estimated_completion, None);
+    assert!(check.servers.is_empty());
+}
+
+#[tokio::test]
+async fn check_completion_samples_stored_articles() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "check-me",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+    let total = downloader.db.get_articles(id).await.unwrap().len();
+
+    let check = downloader.check_completion(id, Some(1)).await.unwrap();
+    assert_eq!(check.total_articles, total);
+    assert_eq!(check.sampled_articles, 1);
+
+    // Asking for more than the download has samples every article
+    let check = downloader.check_completion(id, Some(1000)).await.unwrap();
+    assert_eq!(check.sampled_articles, total);
+    // No servers are configured, so nothing could be answered
+    assert_eq!(check.estimated_completion, None);
+}
+
+#[tokio::test]
+async fn check_completion_of_unknown_download_is_not_found() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let result = downloader.check_completion(DownloadId(999), None).await;
+    assert!(matches!(
+        result,
+        Err(Error::Download(crate::error::DownloadError::NotFound {
+            id: 999
+        }))
+    ));
+}
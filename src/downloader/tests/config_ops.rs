@@ -0,0 +1,176 @@
+use super::*;
+use crate::config::{CategoryConfig, REDACTED};
+
+fn error_key(result: Result<Config>) -> Option<String> {
+    match result {
+        Err(Error::Config { key, .. }) => key,
+        other => panic!("expected a config error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn patch_config_applies_runtime_settings() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+    let destination = temp_dir.path().join("tv");
+
+    let config = downloader
+        .patch_config(&serde_json::json!({
+            "speed_limit_bps": 5_000_000,
+            "persistence": {
+                "categories": {
+                    "tv": {"destination": destination}
+                },
+                "schedule_rules": [{
+                    "name": "night",
+                    "start_time": "00:00",
+                    "end_time": "06:00",
+                    "action": {"type": "unlimited"}
+                }]
+            }
+        }))
+        .await
+        .unwrap();
+
+    assert_eq!(config.download.speed_limit_bps, Some(5_000_000));
+    assert_eq!(downloader.get_speed_limit(), Some(5_000_000));
+    assert_eq!(
+        downloader.get_categories().await["tv"].destination,
+        destination
+    );
+    assert_eq!(downloader.get_schedule_rules().await[0].name, "night");
+    assert_eq!(config.persistence.schedule_rules.len(), 1);
+
+    // null resets to the default and removes map entries
+    let config = downloader
+        .patch_config(&serde_json::json!({
+            "speed_limit_bps": null,
+            "persistence": {"categories": {"tv": null}}
+        }))
+        .await
+        .unwrap();
+    assert_eq!(config.download.speed_limit_bps, None);
+    assert_eq!(downloader.get_speed_limit(), None);
+    assert!(downloader.get_categories().await.is_empty());
+}
+
+#[tokio::test]
+async fn patch_config_rejects_restart_only_settings() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let result = downloader
+        .patch_config(&serde_json::json!({"max_concurrent_downloads": 10}))
+        .await;
+    assert_eq!(
+        error_key(result).as_deref(),
+        Some("max_concurrent_downloads")
+    );
+
+    let result = downloader
+        .patch_config(&serde_json::json!({"retry": {"max_attempts": 9}}))
+        .await;
+    assert_eq!(error_key(result).as_deref(), Some("retry.max_attempts"));
+}
+
+#[tokio::test]
+async fn patch_config_applies_nothing_when_invalid() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let result = downloader
+        .patch_config(&serde_json::json!({
+            "speed_limit_bps": 1_000,
+            "persistence": {
+                "schedule_rules": [{
+                    "name": "broken",
+                    "start_time": "noon",
+                    "end_time": "06:00",
+                    "action": {"type": "unlimited"}
+                }]
+            }
+        }))
+        .await;
+    assert_eq!(
+        error_key(result).as_deref(),
+        Some("persistence.schedule_rules[0].start_time")
+    );
+    assert_eq!(downloader.get_speed_limit(), None);
+    assert!(downloader.get_schedule_rules().await.is_empty());
+}
+
+#[tokio::test]
+async fn patch_config_rejects_unknown_fields() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let result = downloader
+        .patch_config(&serde_json::json!({"retry": {"max_attempt": 3}}))
+        .await;
+    assert_eq!(error_key(result).as_deref(), Some("retry.max_attempt"));
+}
+
+#[tokio::test]
+async fn patch_config_merges_server_array_elements() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader
+        .add_server(crate::config::ServerConfig {
+            host: "news.example.com".to_string(),
+            port: 563,
+            tls: true,
+            username: Some("user".to_string()),
+            password: Some("secret".to_string()),
+            connections: 10,
+            priority: 0,
+            pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
+        })
+        .await
+        .unwrap();
+
+    // The redaction placeholder keeps the current password
+    let config = downloader
+        .patch_config(&serde_json::json!({
+            "servers": [{"speed_limit_bps": 2_000_000, "password": REDACTED}]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(config.servers[0].speed_limit_bps, Some(2_000_000));
+    assert_eq!(config.servers[0].password.as_deref(), Some("secret"));
+    assert_eq!(
+        downloader.servers.current().speed_limiters[0].get_limit(),
+        Some(2_000_000)
+    );
+
+    let result = downloader
+        .patch_config(&serde_json::json!({"servers": [{"connections": 0}]}))
+        .await;
+    assert_eq!(error_key(result).as_deref(), Some("servers[0].connections"));
+
+    let result = downloader
+        .patch_config(&serde_json::json!({"servers": [{"connections": 20}]}))
+        .await;
+    assert_eq!(error_key(result).as_deref(), Some("servers[0].connections"));
+}
+
+#[tokio::test]
+async fn effective_config_reflects_runtime_changes() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+    downloader.set_speed_limit(Some(1_000)).await;
+    downloader
+        .add_or_update_category(
+            "movies",
+            CategoryConfig {
+                destination: temp_dir.path().join("movies"),
+                post_process: None,
+                scripts: vec![],
+                flatten_single_folder: None,
+                junk_filter: None,
+                verify_policy: None,
+            },
+        )
+        .await;
+
+    let config = downloader.effective_config().await;
+    assert_eq!(config.download.speed_limit_bps, Some(1_000));
+    assert!(config.persistence.categories.contains_key("movies"));
+}
@@ -699,3 +699,335 @@ async fn test_pause_all_resume_all_cycle() {
     assert_eq!(d1.status, Status::Queued.to_i32());
     assert_eq!(d2.status, Status::Queued.to_i32());
 }
+
+#[tokio::test]
+async fn test_rename_download_updates_name_and_emits_event() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "a3f8b2c9d1e5",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+    let before = downloader.db.get_download(id).await.unwrap().unwrap();
+    let mut events = downloader.subscribe();
+
+    downloader
+        .rename_download(id, "  Movie.Name.2024.1080p  ")
+        .await
+        .unwrap();
+
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.name, "Movie.Name.2024.1080p");
+    assert_eq!(download.release_name(), "Movie.Name.2024.1080p");
+    assert_eq!(
+        download.destination, before.destination,
+        "a shared destination directory must not be renamed"
+    );
+
+    match events.try_recv() {
+        Ok(crate::types::Event::Renamed {
+            id: event_id,
+            old,
+            new,
+        }) => {
+            assert_eq!(event_id, id);
+            assert_eq!(old, "a3f8b2c9d1e5");
+            assert_eq!(new, "Movie.Name.2024.1080p");
+        }
+        other => panic!("expected Renamed event, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_rename_download_renames_job_destination() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+
+    let id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "obfuscated",
+            DownloadOptions {
+                destination: Some(temp_dir.path().join("tv").join("obfuscated")),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    downloader.rename_download(id, "Show.S01E01").await.unwrap();
+
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(
+        std::path::PathBuf::from(download.destination),
+        temp_dir.path().join("tv").join("Show.S01E01")
+    );
+}
+
+#[tokio::test]
+async fn test_rename_download_rejects_invalid_names() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let id = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    for name in ["", "   ", "..", "../escape", "a\\b"] {
+        let result = downloader.rename_download(id, name).await;
+        assert!(
+            matches!(result, Err(Error::Config { .. })),
+            "{:?} should be rejected, got {:?}",
+            name,
+            result
+        );
+    }
+
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.name, "test");
+}
+
+#[tokio::test]
+async fn test_rename_download_rejects_complete_and_unknown() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let id = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+    downloader
+        .db
+        .update_status(id, Status::Complete.to_i32())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        downloader.rename_download(id, "new").await,
+        Err(Error::Download(DownloadError::InvalidState { .. }))
+    ));
+    assert!(matches!(
+        downloader.rename_download(DownloadId(99999), "new").await,
+        Err(Error::Download(DownloadError::NotFound { .. }))
+    ));
+}
+
+#[tokio::test]
+async fn test_set_category_applies_category_destination() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+    let tv_dir = temp_dir.path().join("tv");
+    downloader
+        .add_or_update_category(
+            "tv",
+            crate::config::CategoryConfig {
+                destination: tv_dir.clone(),
+                post_process: Some(crate::config::PostProcess::None),
+                scripts: vec![],
+                flatten_single_folder: None,
+                junk_filter: None,
+                verify_policy: None,
+            },
+        )
+        .await;
+
+    let id = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+    let before = downloader.db.get_download(id).await.unwrap().unwrap();
+
+    downloader.set_category(id, Some("tv")).await.unwrap();
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.category.as_deref(), Some("tv"));
+    assert_eq!(download.destination, tv_dir.to_string_lossy());
+    assert_eq!(
+        download.post_process,
+        crate::config::PostProcess::None.to_i32()
+    );
+
+    downloader.set_category(id, None).await.unwrap();
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.category, None);
+    assert_eq!(download.destination, before.destination);
+    assert_eq!(download.post_process, before.post_process);
+}
+
+#[tokio::test]
+async fn test_set_category_rejects_unknown_category_and_complete() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let id = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+    assert!(matches!(
+        downloader.set_category(id, Some("missing")).await,
+        Err(Error::Config { .. })
+    ));
+
+    downloader
+        .db
+        .update_status(id, Status::Complete.to_i32())
+        .await
+        .unwrap();
+    assert!(matches!(
+        downloader.set_category(id, None).await,
+        Err(Error::Download(DownloadError::InvalidState { .. }))
+    ));
+    assert!(matches!(
+        downloader.set_category(DownloadId(99999), None).await,
+        Err(Error::Download(DownloadError::NotFound { .. }))
+    ));
+}
+
+// Scoped pause/resume tests
+
+#[tokio::test]
+async fn test_pause_scoped_post_processing_keeps_downloading() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let id = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    downloader
+        .pause_scoped(id, PauseScope::PostProcessing)
+        .await
+        .unwrap();
+
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.status, Status::Queued.to_i32());
+    assert!(download.post_processing_paused);
+
+    // Resuming only downloading leaves post-processing paused
+    downloader
+        .resume_scoped(id, PauseScope::Download)
+        .await
+        .unwrap();
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert!(download.post_processing_paused);
+
+    downloader
+        .resume_scoped(id, PauseScope::PostProcessing)
+        .await
+        .unwrap();
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert!(!download.post_processing_paused);
+}
+
+#[tokio::test]
+async fn test_pause_scoped_download_leaves_post_processing() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let id = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    downloader
+        .pause_scoped(id, PauseScope::Download)
+        .await
+        .unwrap();
+
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.status, Status::Paused.to_i32());
+    assert!(!download.post_processing_paused);
+
+    // A plain resume lifts both
+    downloader
+        .pause_scoped(id, PauseScope::PostProcessing)
+        .await
+        .unwrap();
+    downloader.resume(id).await.unwrap();
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.status, Status::Queued.to_i32());
+    assert!(!download.post_processing_paused);
+}
+
+#[tokio::test]
+async fn test_pause_queue_download_scope_skips_processing() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let queued = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test1", DownloadOptions::default())
+        .await
+        .unwrap();
+    let processing = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test2", DownloadOptions::default())
+        .await
+        .unwrap();
+    downloader
+        .db
+        .update_status(processing, Status::Processing.to_i32())
+        .await
+        .unwrap();
+
+    downloader.pause_queue(PauseScope::Download).await.unwrap();
+
+    let d1 = downloader.db.get_download(queued).await.unwrap().unwrap();
+    let d2 = downloader
+        .db
+        .get_download(processing)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(d1.status, Status::Paused.to_i32());
+    assert_eq!(d2.status, Status::Processing.to_i32());
+    assert!(!downloader.db.is_post_processing_paused().await.unwrap());
+    assert!(
+        !downloader
+            .queue_stats()
+            .await
+            .unwrap()
+            .post_processing_paused
+    );
+}
+
+#[tokio::test]
+async fn test_pause_queue_post_processing_scope() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let id = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+    let mut events = downloader.subscribe();
+
+    downloader
+        .pause_queue(PauseScope::PostProcessing)
+        .await
+        .unwrap();
+
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.status, Status::Queued.to_i32());
+    assert!(downloader.db.is_post_processing_paused().await.unwrap());
+    assert!(
+        downloader
+            .queue_stats()
+            .await
+            .unwrap()
+            .post_processing_paused
+    );
+    assert!(matches!(events.try_recv(), Ok(Event::PostProcessingPaused)));
+
+    // Resuming the queue's post-processing also lifts per-download pauses
+    downloader
+        .pause_scoped(id, PauseScope::PostProcessing)
+        .await
+        .unwrap();
+    downloader
+        .resume_queue(PauseScope::PostProcessing)
+        .await
+        .unwrap();
+
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert!(!download.post_processing_paused);
+    assert!(!downloader.db.is_post_processing_paused().await.unwrap());
+    assert!(matches!(
+        events.try_recv(),
+        Ok(Event::PostProcessingResumed)
+    ));
+}
@@ -6,6 +6,8 @@ async fn test_check_disk_space_sufficient() {
     let temp_dir = tempfile::tempdir().unwrap();
     let mut config = Config::default();
     config.download.download_dir = temp_dir.path().to_path_buf();
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.persistence.database_path = temp_dir.path().join("test.db");
     config.processing.disk_space.enabled = true;
     config.processing.disk_space.min_free_space = 1024 * 1024; // 1 MB buffer
     config.processing.disk_space.size_multiplier = 2.5;
@@ -28,6 +30,8 @@ async fn test_check_disk_space_disabled() {
     let temp_dir = tempfile::tempdir().unwrap();
     let mut config = Config::default();
     config.download.download_dir = temp_dir.path().to_path_buf();
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.persistence.database_path = temp_dir.path().join("test.db");
     config.processing.disk_space.enabled = false; // Disable checking
 
     let downloader = UsenetDownloader::new(config).await.unwrap();
@@ -48,6 +52,8 @@ async fn test_check_disk_space_insufficient() {
     let temp_dir = tempfile::tempdir().unwrap();
     let mut config = Config::default();
     config.download.download_dir = temp_dir.path().to_path_buf();
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.persistence.database_path = temp_dir.path().join("test.db");
     config.processing.disk_space.enabled = true;
 
     // Get actual available space
@@ -82,6 +88,8 @@ async fn test_check_disk_space_multiplier() {
     let temp_dir = tempfile::tempdir().unwrap();
     let mut config = Config::default();
     config.download.download_dir = temp_dir.path().to_path_buf();
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.persistence.database_path = temp_dir.path().join("test.db");
     config.processing.disk_space.enabled = true;
     config.processing.disk_space.min_free_space = 0; // No buffer for this test
     config.processing.disk_space.size_multiplier = 3.0; // 3x multiplier
@@ -0,0 +1,62 @@
+use super::*;
+
+#[tokio::test]
+async fn list_and_get_downloads_return_download_info() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "listed",
+            DownloadOptions {
+                priority: Priority::High,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let downloads = downloader.list_downloads().await.unwrap();
+    assert_eq!(downloads.len(), 1);
+    assert_eq!(downloads[0].id, id);
+    assert_eq!(downloads[0].name, "listed");
+    assert_eq!(downloads[0].status, Status::Queued);
+    assert_eq!(downloads[0].priority, Priority::High);
+    assert_eq!(downloads[0].eta_seconds, None);
+    assert!(downloads[0].duplicate.is_none());
+
+    let download = downloader.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.id, id);
+    assert_eq!(download.incomplete_path, downloads[0].incomplete_path);
+
+    assert!(
+        downloader
+            .get_download(DownloadId(999))
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn queue_stats_counts_downloads_by_status() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let first = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "first", DownloadOptions::default())
+        .await
+        .unwrap();
+    downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "second", DownloadOptions::default())
+        .await
+        .unwrap();
+    downloader.pause(first).await.unwrap();
+
+    let stats = downloader.queue_stats().await.unwrap();
+    assert_eq!(stats.total, 2);
+    assert_eq!(stats.queued, 1);
+    assert_eq!(stats.paused, 1);
+    assert_eq!(stats.downloading, 0);
+    assert!(stats.total_size_bytes > 0);
+    assert_eq!(stats.overall_progress, 0.0);
+    assert!(stats.accepting_new);
+    assert!(!stats.draining);
+}
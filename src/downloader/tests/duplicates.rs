@@ -501,3 +501,173 @@ async fn test_add_nzb_content_duplicate_allow() {
     // Note: In Allow mode, the event is still emitted (informational)
     // This is acceptable behavior - the action determines whether to block, not whether to emit
 }
+
+/// Create a downloader that asks about duplicates, with one download already added
+async fn create_ask_downloader(
+    temp_dir: &tempfile::TempDir,
+) -> (UsenetDownloader, DownloadId, &'static [u8]) {
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.processing.duplicate = config::DuplicateConfig {
+        enabled: true,
+        action: config::DuplicateAction::Ask,
+        methods: vec![config::DuplicateMethod::NzbHash],
+    };
+
+    let downloader = UsenetDownloader::new(config).await.unwrap();
+
+    let nzb_content: &'static [u8] = br#"<?xml version="1.0" encoding="UTF-8"?>
+<nzb xmlns="http://www.newzbin.com/DTD/2003/nzb">
+  <file poster="test@example.com" date="1234567890" subject="test.bin (1/1)">
+<groups>
+  <group>alt.binaries.test</group>
+</groups>
+<segments>
+  <segment bytes="1024" number="1">test-message-id@example.com</segment>
+</segments>
+  </file>
+</nzb>"#;
+
+    let existing = downloader
+        .add_nzb_content(nzb_content, "test.nzb", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    (downloader, existing, nzb_content)
+}
+
+#[tokio::test]
+async fn test_add_nzb_content_duplicate_ask_holds_download() {
+    let temp_dir = tempdir().unwrap();
+    let (downloader, existing, nzb_content) = create_ask_downloader(&temp_dir).await;
+    let mut events = downloader.subscribe();
+
+    let held = downloader
+        .add_nzb_content(nzb_content, "test-copy.nzb", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    let download = downloader.db.get_download(held).await.unwrap().unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::HeldDuplicate);
+    assert_eq!(
+        downloader.queue_state.queue.lock().await.len(),
+        1,
+        "Held duplicate should stay out of the queue"
+    );
+
+    let duplicate: DuplicateInfo = downloader
+        .db
+        .get_held_duplicate(held)
+        .await
+        .unwrap()
+        .unwrap()
+        .into();
+    assert_eq!(duplicate.existing_id, existing);
+    assert_eq!(duplicate.existing_name, "test.nzb");
+    assert_eq!(duplicate.method, config::DuplicateMethod::NzbHash);
+
+    let mut saw_held = false;
+    while let Ok(Ok(event)) =
+        tokio::time::timeout(std::time::Duration::from_millis(100), events.recv()).await
+    {
+        match event {
+            Event::DuplicateHeld {
+                id,
+                name,
+                duplicate,
+            } => {
+                assert_eq!(id, held);
+                assert_eq!(name, "test-copy.nzb");
+                assert_eq!(duplicate.existing_id, existing);
+                saw_held = true;
+            }
+            Event::Queued { id, .. } => assert_ne!(id, held, "Held duplicate was queued"),
+            _ => {}
+        }
+    }
+    assert!(saw_held, "Expected DuplicateHeld event");
+
+    // A held duplicate can't be resumed or paused, only accepted or discarded
+    assert!(matches!(
+        downloader.resume(held).await,
+        Err(Error::Download(DownloadError::InvalidState { .. }))
+    ));
+    assert!(matches!(
+        downloader.pause(held).await,
+        Err(Error::Download(DownloadError::InvalidState { .. }))
+    ));
+}
+
+#[tokio::test]
+async fn test_accept_duplicate_queues_download() {
+    let temp_dir = tempdir().unwrap();
+    let (downloader, _existing, nzb_content) = create_ask_downloader(&temp_dir).await;
+
+    let held = downloader
+        .add_nzb_content(nzb_content, "test-copy.nzb", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    downloader.accept_duplicate(held).await.unwrap();
+
+    let download = downloader.db.get_download(held).await.unwrap().unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Queued);
+    assert!(
+        downloader
+            .db
+            .get_held_duplicate(held)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(downloader.queue_state.queue.lock().await.len(), 2);
+
+    // Once accepted it's an ordinary download
+    assert!(matches!(
+        downloader.accept_duplicate(held).await,
+        Err(Error::Download(DownloadError::InvalidState { .. }))
+    ));
+}
+
+#[tokio::test]
+async fn test_discard_duplicate_removes_download() {
+    let temp_dir = tempdir().unwrap();
+    let (downloader, existing, nzb_content) = create_ask_downloader(&temp_dir).await;
+
+    let held = downloader
+        .add_nzb_content(nzb_content, "test-copy.nzb", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    // Only held duplicates can be discarded this way
+    assert!(matches!(
+        downloader.discard_duplicate(existing).await,
+        Err(Error::Download(DownloadError::InvalidState { .. }))
+    ));
+
+    downloader.discard_duplicate(held).await.unwrap();
+
+    assert!(downloader.db.get_download(held).await.unwrap().is_none());
+    assert!(
+        downloader
+            .db
+            .get_held_duplicate(held)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        downloader
+            .db
+            .get_download(existing)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert!(matches!(
+        downloader.discard_duplicate(held).await,
+        Err(Error::Download(DownloadError::NotFound { .. }))
+    ));
+}
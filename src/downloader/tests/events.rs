@@ -0,0 +1,99 @@
+use super::*;
+
+#[tokio::test]
+async fn test_subscribe_json_wraps_events_in_sequenced_envelopes() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut envelopes = downloader.subscribe_json();
+
+    downloader.emit_event(Event::Queued {
+        id: DownloadId(7),
+        name: "Envelope".to_string(),
+    });
+    downloader.emit_event(Event::QueuePaused);
+
+    let first = tokio::time::timeout(Duration::from_secs(1), envelopes.recv())
+        .await
+        .expect("envelope within 1s")
+        .unwrap();
+    assert_eq!(first.version, crate::types::EVENT_ENVELOPE_VERSION);
+    assert_eq!(first.event_type, "queued");
+    assert_eq!(
+        first.payload,
+        serde_json::json!({"id": 7, "name": "Envelope"})
+    );
+
+    let second = tokio::time::timeout(Duration::from_secs(1), envelopes.recv())
+        .await
+        .expect("envelope within 1s")
+        .unwrap();
+    assert_eq!(second.event_type, "queue_paused");
+    assert_eq!(second.seq, first.seq + 1);
+    assert_eq!(second.payload, serde_json::json!({}));
+    assert!(second.timestamp >= first.timestamp);
+}
+
+#[tokio::test]
+async fn test_subscribe_from_replays_missed_events_then_live_ones() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut envelopes = downloader.subscribe_json();
+
+    downloader.emit_event(Event::QueuePaused);
+    downloader.emit_event(Event::QueueResumed);
+    let first = tokio::time::timeout(Duration::from_secs(1), envelopes.recv())
+        .await
+        .expect("envelope within 1s")
+        .unwrap();
+    let second = tokio::time::timeout(Duration::from_secs(1), envelopes.recv())
+        .await
+        .expect("envelope within 1s")
+        .unwrap();
+
+    // A subscriber that saw only the first event picks up from there
+    let mut subscription = downloader.subscribe_from(first.seq).await.unwrap();
+    downloader.emit_event(Event::Drained);
+
+    let replayed = tokio::time::timeout(Duration::from_secs(1), subscription.recv())
+        .await
+        .expect("replayed envelope within 1s")
+        .unwrap();
+    assert_eq!(replayed.seq, second.seq);
+    assert_eq!(replayed.event_type, "queue_resumed");
+
+    let live = tokio::time::timeout(Duration::from_secs(1), subscription.recv())
+        .await
+        .expect("live envelope within 1s")
+        .unwrap();
+    assert_eq!(live.seq, second.seq + 1);
+    assert_eq!(live.event_type, "drained");
+    assert_eq!(subscription.last_seq(), live.seq);
+}
+
+#[tokio::test]
+async fn test_progress_events_are_not_journaled() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut envelopes = downloader.subscribe_json();
+
+    downloader.emit_event(Event::Downloading {
+        id: DownloadId(1),
+        percent: 50.0,
+        speed_bps: 1000,
+        failed_articles: None,
+        total_articles: None,
+        health_percent: None,
+    });
+    downloader.emit_event(Event::QueuePaused);
+    for _ in 0..2 {
+        tokio::time::timeout(Duration::from_secs(1), envelopes.recv())
+            .await
+            .expect("envelope within 1s")
+            .unwrap();
+    }
+
+    let mut subscription = downloader.subscribe_from(0).await.unwrap();
+    let replayed = tokio::time::timeout(Duration::from_secs(1), subscription.recv())
+        .await
+        .expect("replayed envelope within 1s")
+        .unwrap();
+    assert_eq!(replayed.event_type, "queue_paused");
+    assert_eq!(replayed.seq, 2, "the progress event's number is skipped");
+}
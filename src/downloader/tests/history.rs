@@ -0,0 +1,198 @@
+use super::*;
+
+/// Add a download and let post-processing fail on it, recording it in history
+async fn failed_history_entry(downloader: &UsenetDownloader) -> (DownloadId, i64) {
+    let id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "retry-me",
+            DownloadOptions {
+                post_process: Some(crate::config::PostProcess::UnpackAndCleanup),
+                password: Some("original".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    downloader.queue_state.queue.lock().await.clear();
+
+    // The download directory doesn't exist, so extraction fails
+    assert!(downloader.start_post_processing(id).await.is_err());
+
+    let history = downloader.db.query_history(None, 10, 0).await.unwrap();
+    assert_eq!(history.len(), 1, "the failure is recorded in history");
+    assert_eq!(history[0].download_id, Some(id));
+    assert_eq!(history[0].status, Status::Failed);
+    (id, history[0].id)
+}
+
+#[tokio::test]
+async fn retry_requeues_stored_articles_with_original_settings() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let (original, history_id) = failed_history_entry(&downloader).await;
+
+    let retried = downloader
+        .retry_history(history_id, DownloadOptions::default())
+        .await
+        .unwrap();
+    assert_ne!(retried, original);
+
+    let old = downloader.db.get_download(original).await.unwrap().unwrap();
+    let new = downloader.db.get_download(retried).await.unwrap().unwrap();
+    assert_eq!(new.name, old.name);
+    assert_eq!(new.destination, old.destination);
+    assert_eq!(new.post_process, old.post_process);
+    assert_eq!(Status::from_i32(new.status), Status::Queued);
+    assert_eq!(
+        downloader.db.count_articles(retried).await.unwrap(),
+        downloader.db.count_articles(original).await.unwrap()
+    );
+    let passwords = downloader.db.get_download_passwords(retried).await.unwrap();
+    assert_eq!(passwords.password, Some("original".to_string()));
+    assert_eq!(passwords.nzb_password, Some("testpass123".to_string()));
+
+    let entry = downloader
+        .db
+        .get_history_entry(history_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.retried_as, Some(retried));
+    let queued: Vec<DownloadId> = downloader
+        .queue_state
+        .queue
+        .lock()
+        .await
+        .iter()
+        .map(|item| item.id)
+        .collect();
+    assert_eq!(queued, vec![retried]);
+}
+
+#[tokio::test]
+async fn retry_applies_new_category_and_password() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+    let tv_dir = temp_dir.path().join("tv");
+    downloader
+        .add_or_update_category(
+            "tv",
+            crate::config::CategoryConfig {
+                destination: tv_dir.clone(),
+                post_process: Some(crate::config::PostProcess::None),
+                scripts: vec![],
+                flatten_single_folder: None,
+                junk_filter: None,
+                verify_policy: None,
+            },
+        )
+        .await;
+    let (_, history_id) = failed_history_entry(&downloader).await;
+
+    let retried = downloader
+        .retry_history(
+            history_id,
+            DownloadOptions {
+                category: Some("tv".to_string()),
+                password: Some("corrected".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let new = downloader.db.get_download(retried).await.unwrap().unwrap();
+    assert_eq!(new.category.as_deref(), Some("tv"));
+    assert_eq!(new.destination, tv_dir.to_string_lossy());
+    assert_eq!(new.post_process, crate::config::PostProcess::None.to_i32());
+    assert_eq!(
+        downloader
+            .db
+            .get_download_passwords(retried)
+            .await
+            .unwrap()
+            .password,
+        Some("corrected".to_string())
+    );
+}
+
+#[tokio::test]
+async fn retry_fails_when_original_download_is_gone_or_unknown() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let (original, history_id) = failed_history_entry(&downloader).await;
+
+    let err = downloader
+        .retry_history(history_id + 1, DownloadOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::NotFound(_)), "got {:?}", err);
+
+    downloader.db.delete_download(original).await.unwrap();
+    let err = downloader
+        .retry_history(history_id, DownloadOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::NotFound(_)), "got {:?}", err);
+}
+
+#[tokio::test]
+async fn retry_refuses_downloads_still_in_progress() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let (original, history_id) = failed_history_entry(&downloader).await;
+    downloader
+        .db
+        .update_status(original, Status::Downloading.to_i32())
+        .await
+        .unwrap();
+
+    let err = downloader
+        .retry_history(history_id, DownloadOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Busy(_)), "got {:?}", err);
+}
+
+#[tokio::test]
+async fn get_history_pages_and_filters_by_status() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    failed_history_entry(&downloader).await;
+
+    let page = downloader.get_history(None, 10, 0).await.unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!((page.limit, page.offset), (10, 0));
+
+    let failed = downloader
+        .get_history(Some(Status::Failed), 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(failed.total, 1);
+    let complete = downloader
+        .get_history(Some(Status::Complete), 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(complete.total, 0);
+    assert!(complete.items.is_empty());
+
+    let past_end = downloader.get_history(None, 10, 1).await.unwrap();
+    assert_eq!(past_end.total, 1);
+    assert!(past_end.items.is_empty());
+}
+
+#[tokio::test]
+async fn clear_history_deletes_matching_entries() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    failed_history_entry(&downloader).await;
+
+    let deleted = downloader
+        .clear_history(None, Some(Status::Complete))
+        .await
+        .unwrap();
+    assert_eq!(deleted, 0);
+
+    let deleted = downloader
+        .clear_history(None, Some(Status::Failed))
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+    assert_eq!(downloader.get_history(None, 10, 0).await.unwrap().total, 0);
+}
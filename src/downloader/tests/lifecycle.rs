@@ -15,6 +15,7 @@ async fn test_queue_persistence_enables_restore() {
             database_path: db_path.clone(),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         download: config::DownloadConfig {
             temp_dir: temp_dir.path().join("temp"),
@@ -61,6 +62,7 @@ async fn test_queue_persistence_enables_restore() {
             database_path: db_path.clone(),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         download: config::DownloadConfig {
             temp_dir: temp_dir.path().join("temp"),
@@ -605,6 +607,7 @@ async fn test_restore_queue_called_on_startup() {
                 database_path: db_path.clone(),
                 schedule_rules: vec![],
                 categories: std::collections::HashMap::new(),
+                ..Default::default()
             },
             servers: vec![],
             download: config::DownloadConfig {
@@ -642,6 +645,7 @@ async fn test_restore_queue_called_on_startup() {
             database_path: db_path.clone(),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         servers: vec![],
         download: config::DownloadConfig {
@@ -682,6 +686,7 @@ async fn test_resume_after_simulated_crash() {
                 database_path: db_path.clone(),
                 schedule_rules: vec![],
                 categories: std::collections::HashMap::new(),
+                ..Default::default()
             },
             servers: vec![],
             download: config::DownloadConfig {
@@ -750,6 +755,7 @@ async fn test_resume_after_simulated_crash() {
             database_path: db_path.clone(),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         servers: vec![],
         download: config::DownloadConfig {
@@ -1337,6 +1343,7 @@ async fn test_graceful_shutdown_and_recovery_on_restart() {
                 database_path: db_path.clone(),
                 schedule_rules: vec![],
                 categories: std::collections::HashMap::new(),
+                ..Default::default()
             },
             servers: vec![],
             download: config::DownloadConfig {
@@ -1440,6 +1447,7 @@ async fn test_graceful_shutdown_and_recovery_on_restart() {
                 database_path: db_path.clone(),
                 schedule_rules: vec![],
                 categories: std::collections::HashMap::new(),
+                ..Default::default()
             },
             servers: vec![],
             download: config::DownloadConfig {
@@ -1508,3 +1516,188 @@ async fn test_graceful_shutdown_and_recovery_on_restart() {
         );
     }
 }
+
+#[tokio::test]
+async fn test_drain_when_idle_emits_drained_and_rejects_new_downloads() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut events = downloader.subscribe();
+
+    tokio::time::timeout(Duration::from_secs(2), downloader.drain())
+        .await
+        .expect("drain of an idle downloader should finish immediately")
+        .unwrap();
+
+    assert!(downloader.is_draining());
+    let drained = tokio::time::timeout(Duration::from_secs(1), async {
+        while let Ok(event) = events.recv().await {
+            if matches!(event, Event::Drained) {
+                return true;
+            }
+        }
+        false
+    })
+    .await
+    .unwrap();
+    assert!(drained, "Drained event should be emitted once idle");
+
+    let result = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "during-drain",
+            DownloadOptions::default(),
+        )
+        .await;
+    assert!(
+        matches!(result, Err(Error::ShuttingDown)),
+        "new downloads must be rejected while drained, got {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_drain_waits_for_active_downloads_and_post_processing() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let token = tokio_util::sync::CancellationToken::new();
+    downloader
+        .queue_state
+        .active_downloads
+        .lock()
+        .await
+        .insert(DownloadId(1), token);
+    let guard = downloader.post_processing_guard();
+
+    let drain_handle = tokio::spawn({
+        let downloader = downloader.clone();
+        async move { downloader.drain().await }
+    });
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert!(
+        !drain_handle.is_finished(),
+        "drain must wait while a download is active"
+    );
+
+    downloader
+        .queue_state
+        .active_downloads
+        .lock()
+        .await
+        .remove(&DownloadId(1));
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert!(
+        !drain_handle.is_finished(),
+        "drain must wait while post-processing is in flight"
+    );
+
+    drop(guard);
+    tokio::time::timeout(Duration::from_secs(2), drain_handle)
+        .await
+        .expect("drain should finish once post-processing completes")
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_cancel_drain_reopens_queue() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    downloader.drain().await.unwrap();
+    downloader.cancel_drain();
+
+    assert!(!downloader.is_draining());
+    downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "after-drain",
+            DownloadOptions::default(),
+        )
+        .await
+        .expect("downloads should be accepted after cancelling the drain");
+}
+
+#[tokio::test]
+async fn test_cancel_drain_does_not_reopen_after_shutdown() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    downloader.shutdown().await.unwrap();
+    downloader.cancel_drain();
+
+    let result = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "after-shutdown",
+            DownloadOptions::default(),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::ShuttingDown)));
+}
+
+/// Config for an instance using the database in `dir`
+fn read_only_config(dir: &std::path::Path, read_only: bool) -> Config {
+    let mut config = Config::default();
+    config.persistence.database_path = dir.join("usenet-dl.db");
+    config.download.download_dir = dir.join("downloads");
+    config.download.temp_dir = dir.join("temp");
+    config.read_only = read_only;
+    config
+}
+
+#[tokio::test]
+async fn test_read_only_serves_queue_but_refuses_changes() {
+    let temp_dir = tempdir().unwrap();
+
+    let writer = UsenetDownloader::new(read_only_config(temp_dir.path(), false))
+        .await
+        .unwrap();
+    let id = writer
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "shared", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    let standby = UsenetDownloader::new(read_only_config(temp_dir.path(), true))
+        .await
+        .unwrap();
+    assert!(standby.is_read_only());
+
+    // The queue is visible but not restored into this instance's priority queue
+    let downloads = standby.db.list_downloads().await.unwrap();
+    assert_eq!(downloads.len(), 1);
+    assert_eq!(downloads[0].id, id.0);
+    assert!(standby.queue_state.queue.lock().await.is_empty());
+
+    assert!(matches!(
+        standby
+            .add_nzb_content(SAMPLE_NZB.as_bytes(), "other", DownloadOptions::default())
+            .await,
+        Err(Error::ReadOnly)
+    ));
+    assert!(matches!(standby.pause(id).await, Err(Error::ReadOnly)));
+    assert!(matches!(standby.cancel(id).await, Err(Error::ReadOnly)));
+    assert!(matches!(
+        standby.set_priority(id, Priority::High).await,
+        Err(Error::ReadOnly)
+    ));
+    assert!(matches!(standby.pause_all().await, Err(Error::ReadOnly)));
+
+    // Nothing changed in the shared database
+    let download = writer.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Queued);
+    assert_eq!(download.priority, Priority::Normal as i32);
+
+    // Shutting down the standby doesn't mark the running writer as cleanly stopped
+    standby.shutdown().await.unwrap();
+    assert!(writer.db.was_unclean_shutdown().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_read_only_requires_migrated_database() {
+    let temp_dir = tempdir().unwrap();
+
+    let result = UsenetDownloader::new(read_only_config(temp_dir.path(), true)).await;
+
+    match result {
+        Err(Error::Config { key, .. }) => assert_eq!(key.as_deref(), Some("read_only")),
+        Err(e) => panic!("expected a config error, got {:?}", e),
+        Ok(_) => panic!("read-only downloader opened an unmigrated database"),
+    }
+}
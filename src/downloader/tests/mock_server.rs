@@ -0,0 +1,128 @@
+use super::*;
+use crate::config::ServerConfig;
+use crate::testing::MockNntpServer;
+use crate::types::Event;
+
+async fn downloader_for(
+    temp_dir: &tempfile::TempDir,
+    servers: Vec<ServerConfig>,
+) -> UsenetDownloader {
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.servers = servers;
+    UsenetDownloader::new(config).await.unwrap()
+}
+
+/// Run the queue until `id` completes or fails
+async fn run_until_done(downloader: &UsenetDownloader, id: DownloadId) -> Event {
+    let mut events = downloader.subscribe();
+    let handle = downloader.start_queue_processor();
+    let event = tokio::time::timeout(Duration::from_secs(30), async {
+        loop {
+            match events.recv().await {
+                Ok(event @ Event::Complete { id: done, .. }) if done == id => return event,
+                Ok(event @ Event::DownloadFailed { id: failed, .. }) if failed == id => {
+                    return event;
+                }
+                Ok(_) => {}
+                Err(e) => panic!("event stream ended: {:?}", e),
+            }
+        }
+    })
+    .await
+    .expect("download should finish");
+    handle.abort();
+    event
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn download_from_mock_server_fetches_every_article() {
+    let temp_dir = tempdir().unwrap();
+    let mock = MockNntpServer::start().await.unwrap();
+    mock.require_auth("user", "secret");
+    let data: Vec<u8> = (0..=255).cycle().take(100_000).collect();
+    let nzb = mock.add_file("data.bin", &data, 32_000);
+    let downloader = downloader_for(&temp_dir, vec![mock.server_config()]).await;
+
+    let id = downloader
+        .add_nzb_content(nzb.as_bytes(), "data", DownloadOptions::default())
+        .await
+        .unwrap();
+    match run_until_done(&downloader, id).await {
+        Event::Complete { .. } => {}
+        other => panic!("expected the download to complete, got {:?}", other),
+    }
+    let mut requests = mock.requests();
+    requests.sort();
+    assert_eq!(
+        requests,
+        [
+            "data.bin.1@mock",
+            "data.bin.2@mock",
+            "data.bin.3@mock",
+            "data.bin.4@mock"
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn download_fails_when_mock_server_answers_430() {
+    let temp_dir = tempdir().unwrap();
+    let mock = MockNntpServer::start().await.unwrap();
+    let nzb = mock.add_file("gone.bin", &[7u8; 30_000], 10_000);
+    for part in 1..=3 {
+        mock.remove_article(&format!("gone.bin.{}@mock", part));
+    }
+    let downloader = downloader_for(&temp_dir, vec![mock.server_config()]).await;
+
+    let id = downloader
+        .add_nzb_content(nzb.as_bytes(), "gone", DownloadOptions::default())
+        .await
+        .unwrap();
+    match run_until_done(&downloader, id).await {
+        Event::DownloadFailed { .. } => {}
+        other => panic!("expected the download to fail, got {:?}", other),
+    }
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(download.status, Status::Failed.to_i32());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn articles_are_fetched_from_backup_while_primary_is_disabled() {
+    let temp_dir = tempdir().unwrap();
+    let primary = MockNntpServer::start().await.unwrap();
+    let backup = MockNntpServer::start().await.unwrap();
+    let data = [3u8; 30_000];
+    let nzb = primary.add_file("split.bin", &data, 10_000);
+    backup.add_file("split.bin", &data, 10_000);
+    let downloader = downloader_for(
+        &temp_dir,
+        vec![
+            primary.server_config(),
+            ServerConfig {
+                priority: 1,
+                ..backup.server_config()
+            },
+        ],
+    )
+    .await;
+    downloader.disable_server(0, None).await.unwrap();
+
+    let id = downloader
+        .add_nzb_content(nzb.as_bytes(), "split", DownloadOptions::default())
+        .await
+        .unwrap();
+    match run_until_done(&downloader, id).await {
+        Event::Complete { .. } => {}
+        other => panic!("expected the download to complete, got {:?}", other),
+    }
+    assert!(primary.requests().is_empty());
+    let mut requests = backup.requests();
+    requests.sort();
+    assert_eq!(
+        requests,
+        ["split.bin.1@mock", "split.bin.2@mock", "split.bin.3@mock"]
+    );
+}
@@ -3,14 +3,24 @@ pub(crate) use crate::*;
 pub(crate) use std::time::{Duration, Instant};
 pub(crate) use tempfile::tempdir;
 
+mod backup;
+mod batch;
+mod completion;
+mod config_ops;
 mod control;
 mod control_unit;
 mod disk_space;
+mod downloads;
 mod duplicates;
+mod events;
+mod history;
 mod lifecycle;
+mod mock_server;
 mod nzb;
+mod nzb_backup;
 mod post_process;
 mod queue;
+mod queue_export;
 mod queue_processor;
 mod queue_unit;
 mod rss;
@@ -18,5 +28,9 @@ mod rss_unit;
 mod scheduler;
 mod scripts;
 mod server;
+mod server_pools;
+mod simulate;
 mod speed;
+mod stats;
+mod watchdog;
 mod webhooks;
@@ -46,13 +46,22 @@ async fn test_add_nzb_content_extracts_metadata() {
     assert_eq!(download.nzb_meta_name, Some("Test Download".to_string()));
     assert_eq!(download.job_name, Some("Test Download".to_string())); // Uses meta title
 
-    // Check password was cached
-    let cached_password = downloader
+    // Check the NZB password was stored; it isn't known to be correct yet
+    let passwords = downloader
         .db
-        .get_cached_password(download_id)
+        .get_download_passwords(download_id)
         .await
         .unwrap();
-    assert_eq!(cached_password, Some("testpass123".to_string()));
+    assert_eq!(passwords.nzb_password, Some("testpass123".to_string()));
+    assert_eq!(passwords.password, None);
+    assert_eq!(
+        downloader
+            .db
+            .get_cached_password(download_id)
+            .await
+            .unwrap(),
+        None
+    );
 }
 
 #[tokio::test]
@@ -108,13 +117,14 @@ async fn test_add_nzb_content_with_options() {
     assert_eq!(download.category, Some("test_category".to_string()));
     assert_eq!(download.priority, Priority::High as i32);
 
-    // Check provided password overrides NZB password
-    let cached_password = downloader
+    // Check provided password is stored alongside the NZB password
+    let passwords = downloader
         .db
-        .get_cached_password(download_id)
+        .get_download_passwords(download_id)
         .await
         .unwrap();
-    assert_eq!(cached_password, Some("override_password".to_string()));
+    assert_eq!(passwords.password, Some("override_password".to_string()));
+    assert_eq!(passwords.nzb_password, Some("testpass123".to_string()));
 }
 
 #[tokio::test]
@@ -579,6 +589,7 @@ async fn test_start_folder_watcher_with_configured_folders() {
             database_path: temp_dir.path().join("test.db"),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         servers: vec![],
         automation: config::AutomationConfig {
@@ -586,7 +597,10 @@ async fn test_start_folder_watcher_with_configured_folders() {
                 path: watch_path.clone(),
                 after_import: config::WatchFolderAction::Delete,
                 category: Some("test".to_string()),
+                mode: config::WatchMode::Auto,
                 scan_interval: Duration::from_secs(5),
+                processed_dir: None,
+                error_dir: None,
             }],
             ..Default::default()
         },
@@ -630,6 +644,7 @@ async fn test_start_folder_watcher_creates_missing_directory() {
             database_path: temp_dir.path().join("test.db"),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         servers: vec![],
         automation: config::AutomationConfig {
@@ -637,7 +652,10 @@ async fn test_start_folder_watcher_creates_missing_directory() {
                 path: watch_path.clone(),
                 after_import: config::WatchFolderAction::MoveToProcessed,
                 category: None,
+                mode: config::WatchMode::Auto,
                 scan_interval: Duration::from_secs(5),
+                processed_dir: None,
+                error_dir: None,
             }],
             ..Default::default()
         },
@@ -681,3 +699,668 @@ fn test_extract_job_name() {
     // Just .nzb extension
     assert_eq!(UsenetDownloader::extract_job_name(".nzb"), "");
 }
+
+#[tokio::test]
+async fn test_add_nzb_with_custom_temp_dir() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+    let scratch = temp_dir.path().join("scratch");
+
+    let custom_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "scratch.nzb",
+            DownloadOptions {
+                temp_dir: Some(scratch.clone()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let default_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "default.nzb",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let custom = downloader
+        .db
+        .get_download(custom_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(custom.temp_dir.as_deref(), Some(scratch.to_str().unwrap()));
+    assert_eq!(
+        custom.incomplete_path(&downloader.config.download.temp_dir),
+        scratch.join(format!("download_{}", custom_id))
+    );
+
+    let default = downloader
+        .db
+        .get_download(default_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(default.temp_dir, None);
+    assert_eq!(
+        default.incomplete_path(&downloader.config.download.temp_dir),
+        downloader
+            .config
+            .download
+            .temp_dir
+            .join(format!("download_{}", default_id))
+    );
+
+    // Cancelling removes the in-progress data from the custom location
+    let incomplete = scratch.join(format!("download_{}", custom_id));
+    std::fs::create_dir_all(&incomplete).unwrap();
+    std::fs::write(incomplete.join("article_1.dat"), b"partial").unwrap();
+    downloader.cancel(custom_id).await.unwrap();
+    assert!(
+        !incomplete.exists(),
+        "cancel should delete the custom temp dir"
+    );
+}
+
+#[tokio::test]
+async fn test_job_layout_names_folders_after_jobs_without_collisions() {
+    let (mut downloader, _temp_dir) = create_test_downloader().await;
+    let mut config = (*downloader.config).clone();
+    config.download.incomplete_layout = config::IncompleteLayout::Job;
+    downloader.config = std::sync::Arc::new(config);
+
+    let mut paths = Vec::new();
+    for _ in 0..2 {
+        let id = downloader
+            .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+            .await
+            .unwrap();
+        let download = downloader.db.get_download(id).await.unwrap().unwrap();
+        paths.push(download.incomplete_path(&downloader.config.download.temp_dir));
+    }
+
+    let temp = &downloader.config.download.temp_dir;
+    assert_eq!(
+        paths,
+        vec![temp.join("Test Download"), temp.join("Test Download (2)")]
+    );
+    assert!(paths.iter().all(|path| path.is_dir()));
+}
+
+#[tokio::test]
+async fn test_category_layout_groups_folders_by_category() {
+    let (mut downloader, _temp_dir) = create_test_downloader().await;
+    let mut config = (*downloader.config).clone();
+    config.download.incomplete_layout = config::IncompleteLayout::Category;
+    downloader.config = std::sync::Arc::new(config);
+
+    let tv = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "test",
+            DownloadOptions {
+                category: Some("tv".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let none = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    let temp = &downloader.config.download.temp_dir;
+    let tv = downloader.db.get_download(tv).await.unwrap().unwrap();
+    assert_eq!(tv.incomplete_path(temp), temp.join("tv/Test Download"));
+    let none = downloader.db.get_download(none).await.unwrap().unwrap();
+    assert_eq!(
+        none.incomplete_path(temp),
+        temp.join("uncategorized/Test Download")
+    );
+}
+
+#[test]
+fn test_incomplete_folder_name_is_a_single_safe_component() {
+    use crate::downloader::incomplete::folder_name;
+
+    let sanitization = config::PathSanitizationConfig::default();
+    assert_eq!(
+        folder_name("../Show/S01 ", &sanitization).as_deref(),
+        Some("_Show_S01")
+    );
+    assert_eq!(
+        folder_name(".shared-articles", &sanitization).as_deref(),
+        Some("shared-articles")
+    );
+    assert_eq!(folder_name(" ... ", &sanitization), None);
+    assert!(folder_name(&"é".repeat(150), &sanitization).unwrap().len() <= 200);
+}
+
+/// NZB with a release file and an executable
+const NZB_WITH_EXECUTABLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<nzb xmlns="http://www.newzbin.com/DTD/2003/nzb">
+  <file poster="user@example.com" date="1234567890" subject="&quot;release.mkv&quot; yEnc (1/1)">
+<groups>
+  <group>alt.binaries.test</group>
+</groups>
+<segments>
+  <segment bytes="1000" number="1">mkv@example.com</segment>
+</segments>
+  </file>
+  <file poster="user@example.com" date="1234567890" subject="Setup.EXE (1/1)">
+<groups>
+  <group>alt.binaries.test</group>
+</groups>
+<segments>
+  <segment bytes="500" number="1">exe@example.com</segment>
+</segments>
+  </file>
+</nzb>"#;
+
+async fn create_rejecting_downloader(
+    action: config::RejectAction,
+) -> (UsenetDownloader, tempfile::TempDir) {
+    let temp_dir = tempdir().unwrap();
+    let config = Config {
+        persistence: crate::config::PersistenceConfig {
+            database_path: temp_dir.path().join("test.db"),
+            ..Default::default()
+        },
+        download: config::DownloadConfig {
+            download_dir: temp_dir.path().join("downloads"),
+            temp_dir: temp_dir.path().join("temp"),
+            reject_extensions: vec!["exe".to_string(), ".scr".to_string()],
+            reject_action: action,
+            ..Default::default()
+        },
+        servers: vec![],
+        ..Default::default()
+    };
+    (UsenetDownloader::new(config).await.unwrap(), temp_dir)
+}
+
+#[tokio::test]
+async fn test_add_nzb_content_skips_rejected_files() {
+    let (downloader, _temp_dir) = create_rejecting_downloader(config::RejectAction::Skip).await;
+    let mut events = downloader.subscribe();
+
+    let download_id = downloader
+        .add_nzb_content(
+            NZB_WITH_EXECUTABLE.as_bytes(),
+            "release",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(download.status, Status::Queued.to_i32());
+    assert_eq!(download.size_bytes, 1000);
+
+    let files = downloader.db.get_download_files(download_id).await.unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].filename, "release.mkv");
+    assert_eq!(downloader.db.count_articles(download_id).await.unwrap(), 1);
+
+    let rejected = loop {
+        match events.try_recv().unwrap() {
+            Event::FilesRejected { id, files } => break (id, files),
+            _ => continue,
+        }
+    };
+    assert_eq!(rejected, (download_id, vec!["Setup.EXE".to_string()]));
+}
+
+#[tokio::test]
+async fn test_add_nzb_content_fails_download_with_rejected_files() {
+    let (downloader, _temp_dir) = create_rejecting_downloader(config::RejectAction::Fail).await;
+
+    let download_id = downloader
+        .add_nzb_content(
+            NZB_WITH_EXECUTABLE.as_bytes(),
+            "release",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(download.status, Status::Failed.to_i32());
+    assert_eq!(download.error_code.as_deref(), Some("rejected_files"));
+    assert!(download.error_message.unwrap().contains("Setup.EXE"));
+    assert!(
+        !downloader
+            .queue_state
+            .queue
+            .lock()
+            .await
+            .iter()
+            .any(|queued| queued.id == download_id)
+    );
+}
+
+// Deferred NZB Tests
+
+#[tokio::test]
+async fn test_add_nzb_url_deferred_fetches_when_started() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/getnzb"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header(
+                    "Content-Disposition",
+                    "attachment; filename=\"Show.S01E01.nzb\"",
+                )
+                .set_body_bytes(SAMPLE_NZB),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Nothing is fetched when adding
+    let url = format!("{}/getnzb", mock_server.uri());
+    let download_id = downloader
+        .add_nzb_url_deferred(&url, None, DownloadOptions::default())
+        .await
+        .unwrap();
+    assert!(mock_server.received_requests().await.unwrap().is_empty());
+
+    let info = downloader.get_download(download_id).await.unwrap().unwrap();
+    assert_eq!(info.name, "getnzb");
+    assert_eq!(info.size_bytes, 0);
+    assert_eq!(info.status, Status::Queued);
+    assert_eq!(info.deferred_url.as_deref(), Some(url.as_str()));
+    assert!(
+        downloader
+            .db
+            .get_articles(download_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let mut events = downloader.subscribe();
+    assert!(downloader.fetch_deferred_nzb(download_id).await);
+
+    let info = downloader.get_download(download_id).await.unwrap().unwrap();
+    assert_eq!(info.name, "Show.S01E01");
+    assert!(info.size_bytes > 0);
+    assert_eq!(info.deferred_url, None);
+    assert_eq!(
+        downloader.db.get_articles(download_id).await.unwrap().len(),
+        2
+    );
+    let fetched = std::iter::from_fn(|| events.try_recv().ok()).any(|event| {
+        matches!(event, Event::NzbFetched { id, ref name, .. } if id == download_id && name == "Show.S01E01")
+    });
+    assert!(fetched);
+
+    // Already fetched, so nothing more to do
+    assert!(downloader.fetch_deferred_nzb(download_id).await);
+}
+
+#[tokio::test]
+async fn test_add_nzb_url_deferred_keeps_given_name_and_password() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/release.nzb"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(SAMPLE_NZB))
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/release.nzb", mock_server.uri());
+    let download_id = downloader
+        .add_nzb_url_deferred(
+            &url,
+            Some("My Release"),
+            DownloadOptions {
+                password: Some("secret".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(downloader.fetch_deferred_nzb(download_id).await);
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(download.name, "My Release");
+    let passwords = downloader
+        .db
+        .get_download_passwords(download_id)
+        .await
+        .unwrap();
+    assert_eq!(passwords.password.as_deref(), Some("secret"));
+    assert_eq!(passwords.nzb_password.as_deref(), Some("testpass123"));
+}
+
+#[tokio::test]
+async fn test_add_nzb_url_deferred_fails_download_when_fetch_fails() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/expired.nzb"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/expired.nzb", mock_server.uri());
+    let download_id = downloader
+        .add_nzb_url_deferred(&url, None, DownloadOptions::default())
+        .await
+        .unwrap();
+    assert!(!downloader.fetch_deferred_nzb(download_id).await);
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(download.status, Status::Failed.to_i32());
+    assert!(download.error_message.unwrap().contains(&url));
+
+    let error = downloader
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .error
+        .unwrap();
+    assert_eq!(error.code, "nzb_fetch_failed");
+    assert!(error.message.contains(&url));
+    assert!(error.hint.is_some());
+}
+
+#[tokio::test]
+async fn test_add_nzb_url_deferred_rejects_invalid_url() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    for url in ["not a url", "ftp://example.com/file.nzb"] {
+        let result = downloader
+            .add_nzb_url_deferred(url, None, DownloadOptions::default())
+            .await;
+        assert!(matches!(result, Err(Error::InvalidNzb(_))), "{url}");
+    }
+    assert!(downloader.db.list_downloads().await.unwrap().is_empty());
+}
+
+async fn create_fallback_downloader(fallback_url: String) -> (UsenetDownloader, tempfile::TempDir) {
+    let temp_dir = tempdir().unwrap();
+    let config = Config {
+        persistence: crate::config::PersistenceConfig {
+            database_path: temp_dir.path().join("test.db"),
+            ..Default::default()
+        },
+        download: config::DownloadConfig {
+            download_dir: temp_dir.path().join("downloads"),
+            temp_dir: temp_dir.path().join("temp"),
+            nzb_fallback_url: Some(fallback_url),
+            ..Default::default()
+        },
+        servers: vec![],
+        ..Default::default()
+    };
+    (UsenetDownloader::new(config).await.unwrap(), temp_dir)
+}
+
+#[tokio::test]
+async fn test_deferred_nzb_uses_fallback_url_when_gone() {
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/expired.nzb"))
+        .respond_with(ResponseTemplate::new(410))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .and(query_param("name", "My Release"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(SAMPLE_NZB))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let (downloader, _temp_dir) =
+        create_fallback_downloader(format!("{}/search?name={{name}}", mock_server.uri())).await;
+    let url = format!("{}/expired.nzb", mock_server.uri());
+    let download_id = downloader
+        .add_nzb_url_deferred(&url, Some("My Release"), DownloadOptions::default())
+        .await
+        .unwrap();
+
+    assert!(downloader.fetch_deferred_nzb(download_id).await);
+    assert_eq!(
+        downloader.db.get_articles(download_id).await.unwrap().len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_deferred_nzb_fetch_failure_reports_each_attempt() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/expired.nzb"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/broken.nzb"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let (downloader, _temp_dir) =
+        create_fallback_downloader(format!("{}/search?name={{name}}", mock_server.uri())).await;
+    let mut events = downloader.subscribe();
+
+    // A 404 tries the fallback before failing
+    let expired = format!("{}/expired.nzb", mock_server.uri());
+    let download_id = downloader
+        .add_nzb_url_deferred(&expired, None, DownloadOptions::default())
+        .await
+        .unwrap();
+    assert!(!downloader.fetch_deferred_nzb(download_id).await);
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(download.status, Status::Failed.to_i32());
+
+    let reasons = std::iter::from_fn(|| events.try_recv().ok())
+        .find_map(|event| match event {
+            Event::NzbFetchFailed { id, url, reasons } if id == download_id => {
+                assert_eq!(url, expired);
+                Some(reasons)
+            }
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(reasons.len(), 2);
+    assert!(reasons[0].contains("404"));
+    assert!(reasons[1].starts_with("fallback"));
+
+    // Other errors don't
+    let broken = format!("{}/broken.nzb", mock_server.uri());
+    let download_id = downloader
+        .add_nzb_url_deferred(&broken, None, DownloadOptions::default())
+        .await
+        .unwrap();
+    assert!(!downloader.fetch_deferred_nzb(download_id).await);
+    let reasons = std::iter::from_fn(|| events.try_recv().ok())
+        .find_map(|event| match event {
+            Event::NzbFetchFailed { id, reasons, .. } if id == download_id => Some(reasons),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(reasons.len(), 1);
+    assert!(reasons[0].contains("500"));
+}
+
+#[test]
+fn test_par2_volume_blocks_come_from_volume_names() {
+    use crate::downloader::nzb::par2_volume_blocks;
+
+    assert_eq!(par2_volume_blocks("Show.vol03+04.par2"), Some(4));
+    assert_eq!(par2_volume_blocks("Show.S01.VOL127+128.PAR2"), Some(128));
+    assert_eq!(par2_volume_blocks("Show.vol0-1.par2"), Some(1));
+    assert_eq!(par2_volume_blocks("Show.par2"), None);
+    assert_eq!(par2_volume_blocks("Show.volume.par2"), None);
+    assert_eq!(par2_volume_blocks("Show.vol03+04.rar"), None);
+}
+
+// -----------------------------------------------------------------------
+// splitting packs into a download per media file
+// -----------------------------------------------------------------------
+
+/// NZB with a single-segment file per name
+fn pack_nzb(filenames: &[&str]) -> String {
+    let files: String = filenames
+        .iter()
+        .map(|filename| {
+            format!(
+                r#"  <file poster="user@example.com" date="1234567890" subject="&quot;{filename}&quot; yEnc (1/1)">
+    <groups><group>alt.binaries.test</group></groups>
+    <segments><segment bytes="1000" number="1">{filename}@example.com</segment></segments>
+  </file>
+"#
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<nzb xmlns="http://www.newzbin.com/DTD/2003/nzb">
+  <head><meta type="title">Show.S01</meta></head>
+{files}</nzb>"#
+    )
+}
+
+#[tokio::test]
+async fn test_add_nzb_content_split_adds_a_download_per_media_file() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let nzb = pack_nzb(&[
+        "Show.S01.nfo",
+        "Show.S01.par2",
+        "Show.S01E01.mkv",
+        "Show.S01E01.en.srt",
+        "Show.S01E01.sample.mkv",
+        "Show.S01E01.par2",
+        "Show.S01E01.vol00+01.par2",
+        "Show.S01E02.part01.rar",
+        "Show.S01E02.part02.rar",
+        "Show.S01E02.r00",
+    ]);
+
+    let ids: Vec<DownloadId> = downloader
+        .add_nzb_content_split(nzb.as_bytes(), "Show.S01", DownloadOptions::default())
+        .await
+        .into_iter()
+        .map(|result| result.unwrap())
+        .collect();
+    assert_eq!(ids.len(), 2);
+
+    let mut downloads = Vec::new();
+    for &id in &ids {
+        let download = downloader.db.get_download(id).await.unwrap().unwrap();
+        let files: Vec<String> = downloader
+            .db
+            .get_download_files(id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|file| file.filename)
+            .collect();
+        downloads.push((download.name, download.job_name, files));
+    }
+
+    // The pack's .nfo goes with every item, its PAR2 set with none
+    assert_eq!(
+        downloads,
+        vec![
+            (
+                "Show.S01E01".to_string(),
+                Some("Show.S01E01".to_string()),
+                vec![
+                    "Show.S01.nfo".to_string(),
+                    "Show.S01E01.mkv".to_string(),
+                    "Show.S01E01.en.srt".to_string(),
+                    "Show.S01E01.sample.mkv".to_string(),
+                    "Show.S01E01.par2".to_string(),
+                    "Show.S01E01.vol00+01.par2".to_string(),
+                ]
+            ),
+            (
+                "Show.S01E02".to_string(),
+                Some("Show.S01E02".to_string()),
+                vec![
+                    "Show.S01.nfo".to_string(),
+                    "Show.S01E02.part01.rar".to_string(),
+                    "Show.S01E02.part02.rar".to_string(),
+                    "Show.S01E02.r00".to_string(),
+                ]
+            ),
+        ]
+    );
+    assert_eq!(downloader.queue_state.queue.lock().await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_add_nzb_content_split_keeps_single_items_whole() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let nzb = pack_nzb(&["Movie.mkv", "Movie.nfo", "Movie.par2"]);
+
+    let results = downloader
+        .add_nzb_content_split(nzb.as_bytes(), "Movie", DownloadOptions::default())
+        .await;
+    assert_eq!(results.len(), 1);
+
+    let id = *results[0].as_ref().unwrap();
+    assert_eq!(downloader.db.get_download_files(id).await.unwrap().len(), 3);
+
+    let results = downloader
+        .add_nzb_content_split(b"not an nzb", "broken", DownloadOptions::default())
+        .await;
+    assert!(matches!(results[..], [Err(Error::InvalidNzb(_))]));
+}
@@ -0,0 +1,93 @@
+use super::*;
+use std::time::{Duration, SystemTime};
+
+async fn downloader_with_backups(
+    temp_dir: &tempfile::TempDir,
+    max_age: Option<Duration>,
+    max_count: Option<usize>,
+) -> UsenetDownloader {
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.persistence.nzb_backup_dir = Some(temp_dir.path().join("nzb-backups"));
+    config.persistence.nzb_backup_max_age = max_age;
+    config.persistence.nzb_backup_max_count = max_count;
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.servers = vec![];
+    UsenetDownloader::new(config).await.unwrap()
+}
+
+/// Write a `.nzb` file into the backup directory with the given age
+fn write_aged_backup(dir: &std::path::Path, name: &str, age: Duration) -> std::path::PathBuf {
+    std::fs::create_dir_all(dir).unwrap();
+    let path = dir.join(name);
+    let file = std::fs::File::create(&path).unwrap();
+    file.set_modified(SystemTime::now() - age).unwrap();
+    path
+}
+
+#[tokio::test]
+async fn test_added_nzb_is_backed_up_by_id_and_job_name() {
+    let temp_dir = tempdir().unwrap();
+    let downloader = downloader_with_backups(&temp_dir, None, None).await;
+    let content = SAMPLE_NZB.replace("Test Download", "Show: S01/E01");
+
+    let id = downloader
+        .add_nzb_content(content.as_bytes(), "show", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    let backup = temp_dir
+        .path()
+        .join("nzb-backups")
+        .join(format!("{}-Show_ S01_E01.nzb", id.0));
+    assert_eq!(std::fs::read_to_string(&backup).unwrap(), content);
+}
+
+#[tokio::test]
+async fn test_nzb_backups_beyond_max_count_are_pruned() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path().join("nzb-backups");
+    let old = write_aged_backup(&dir, "1-old.nzb", Duration::from_secs(3600));
+    let unrelated = write_aged_backup(&dir, "notes.txt", Duration::from_secs(3600));
+    let downloader = downloader_with_backups(&temp_dir, None, Some(1)).await;
+
+    let id = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    assert!(!old.exists(), "oldest backup beyond the count is deleted");
+    assert!(unrelated.exists(), "non-NZB files are left alone");
+    assert!(dir.join(format!("{}-Test Download.nzb", id.0)).exists());
+}
+
+#[tokio::test]
+async fn test_nzb_backups_older_than_max_age_are_pruned() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path().join("nzb-backups");
+    let expired = write_aged_backup(&dir, "1-expired.nzb", Duration::from_secs(2 * 86400));
+    let recent = write_aged_backup(&dir, "2-recent.nzb", Duration::from_secs(3600));
+    let downloader =
+        downloader_with_backups(&temp_dir, Some(Duration::from_secs(86400)), None).await;
+
+    downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    assert!(!expired.exists());
+    assert!(recent.exists());
+}
+
+#[tokio::test]
+async fn test_no_backup_without_backup_dir() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+
+    downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "test", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    assert!(!temp_dir.path().join("nzb-backups").exists());
+}
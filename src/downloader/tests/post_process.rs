@@ -442,8 +442,12 @@ async fn test_reprocess_missing_files_returns_not_found() {
     let downloader_isolated = UsenetDownloader {
         db: downloader.db.clone(),
         event_tx: downloader.event_tx.clone(),
+        envelopes: downloader.envelopes.clone(),
         config: config_arc,
-        nntp_pools: downloader.nntp_pools.clone(),
+        servers: downloader.servers.clone(),
+        usage: downloader.usage.clone(),
+        shared_articles: downloader.shared_articles.clone(),
+        digests: downloader.digests.clone(),
         speed_limiter: downloader.speed_limiter.clone(),
         queue_state: downloader.queue_state.clone(),
         runtime_config: downloader.runtime_config.clone(),
@@ -518,3 +522,940 @@ async fn test_post_process_success_preserves_download_name_and_category() {
         "status should be Complete"
     );
 }
+
+/// Test that reprocess_from_stage(Move) moves temp files to the destination without extracting
+#[tokio::test]
+async fn test_reprocess_from_move_stage_moves_temp_files() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    // PostProcess::None would normally skip the move — starting at Move must run it anyway
+    let download_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "reprocess-move.nzb",
+            DownloadOptions {
+                post_process: Some(crate::config::PostProcess::None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    downloader
+        .db
+        .update_status(download_id, Status::Failed.to_i32())
+        .await
+        .unwrap();
+
+    let download_temp_dir = downloader
+        .config
+        .download
+        .temp_dir
+        .join(format!("download_{}", download_id.0));
+    tokio::fs::create_dir_all(&download_temp_dir).await.unwrap();
+    tokio::fs::write(download_temp_dir.join("payload.bin"), b"data")
+        .await
+        .unwrap();
+
+    let mut events = downloader.subscribe();
+    downloader
+        .reprocess_from_stage(download_id, Stage::Move)
+        .await
+        .unwrap();
+
+    let mut found_moving = false;
+    let mut found_complete = false;
+    for _ in 0..30 {
+        match tokio::time::timeout(Duration::from_millis(100), events.recv()).await {
+            Ok(Ok(Event::Moving { id, .. })) if id == download_id => found_moving = true,
+            Ok(Ok(Event::Complete { id, .. })) if id == download_id => {
+                found_complete = true;
+                break;
+            }
+            Ok(Ok(_)) => continue,
+            _ => break,
+        }
+    }
+    assert!(found_moving, "starting at Move should emit a Moving event");
+    assert!(
+        found_complete,
+        "reprocessing should finish with a Complete event"
+    );
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Complete);
+    assert!(
+        std::path::Path::new(&download.destination)
+            .join("payload.bin")
+            .exists(),
+        "temp files should have been moved to the destination"
+    );
+}
+
+/// Test that a successful run emits ReadyForImport listing the moved files
+#[tokio::test]
+async fn test_post_process_success_emits_ready_for_import_with_moved_files() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let download_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "ready.nzb",
+            DownloadOptions {
+                post_process: Some(crate::config::PostProcess::None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    downloader
+        .db
+        .update_status(download_id, Status::Failed.to_i32())
+        .await
+        .unwrap();
+
+    let download_temp_dir = downloader
+        .config
+        .download
+        .temp_dir
+        .join(format!("download_{}", download_id.0));
+    tokio::fs::create_dir_all(download_temp_dir.join("Subs"))
+        .await
+        .unwrap();
+    tokio::fs::write(download_temp_dir.join("episode.mkv"), b"video")
+        .await
+        .unwrap();
+    tokio::fs::write(download_temp_dir.join("Subs/episode.srt"), b"subs")
+        .await
+        .unwrap();
+
+    let mut events = downloader.subscribe();
+    downloader
+        .reprocess_from_stage(download_id, Stage::Move)
+        .await
+        .unwrap();
+
+    let mut ready = None;
+    let mut complete_seen = false;
+    for _ in 0..30 {
+        match tokio::time::timeout(Duration::from_millis(100), events.recv()).await {
+            Ok(Ok(Event::Complete { id, .. })) if id == download_id => complete_seen = true,
+            Ok(Ok(Event::ReadyForImport {
+                id,
+                category,
+                final_path,
+                files,
+            })) if id == download_id => {
+                ready = Some((category, final_path, files));
+                break;
+            }
+            Ok(Ok(_)) => continue,
+            _ => break,
+        }
+    }
+    assert!(
+        complete_seen,
+        "Complete must be emitted before ReadyForImport"
+    );
+    let (category, final_path, mut files) = ready.expect("ReadyForImport should be emitted");
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let destination = std::path::PathBuf::from(&download.destination);
+    assert_eq!(category, None);
+    assert_eq!(final_path, destination);
+    files.sort();
+    assert_eq!(
+        files,
+        vec![
+            destination.join("Subs").join("episode.srt"),
+            destination.join("episode.mkv"),
+        ]
+    );
+}
+
+/// Test that reprocess_from_stage() rejects stages that can't be re-run from the temp folder
+#[tokio::test]
+async fn test_reprocess_from_stage_rejects_download_stage() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let download_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "reprocess-invalid.nzb",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    for stage in [Stage::Download, Stage::DirectUnpack] {
+        let result = downloader.reprocess_from_stage(download_id, stage).await;
+        assert!(
+            matches!(result, Err(Error::NotSupported(_))),
+            "{:?} should be rejected, got {:?}",
+            stage,
+            result
+        );
+    }
+
+    // Rejected requests must not touch the download
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Queued);
+}
+
+/// Create a downloader whose next post-processing run fails to move its files,
+/// because the download folder is a file
+async fn create_failing_download(
+    keep_intermediate_on_failure: bool,
+) -> (UsenetDownloader, DownloadId, tempfile::TempDir) {
+    let temp_dir = tempdir().unwrap();
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.processing.keep_intermediate_on_failure = keep_intermediate_on_failure;
+    let downloader = UsenetDownloader::new(config).await.unwrap();
+    std::fs::remove_dir(temp_dir.path().join("downloads")).unwrap();
+    std::fs::write(temp_dir.path().join("downloads"), b"in the way").unwrap();
+
+    let download_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "test.nzb",
+            DownloadOptions {
+                post_process: Some(crate::config::PostProcess::UnpackAndCleanup),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let download_path = temp_dir
+        .path()
+        .join("temp")
+        .join(format!("download_{}", download_id.0));
+    std::fs::create_dir_all(download_path.join("subs")).unwrap();
+    std::fs::write(download_path.join("movie.mkv"), b"salvageable video").unwrap();
+    std::fs::write(download_path.join("subs").join("movie.srt"), b"subtitles").unwrap();
+
+    (downloader, download_id, temp_dir)
+}
+
+#[tokio::test]
+async fn test_post_process_failure_keeps_intermediate_files_as_artifacts() {
+    let (downloader, download_id, _temp_dir) = create_failing_download(true).await;
+
+    assert!(downloader.start_post_processing(download_id).await.is_err());
+
+    let artifacts = downloader.download_artifacts(download_id).await.unwrap();
+    let paths: Vec<_> = artifacts.files.iter().map(|f| f.path.clone()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            std::path::PathBuf::from("movie.mkv"),
+            std::path::Path::new("subs").join("movie.srt"),
+        ]
+    );
+    assert_eq!(artifacts.total_bytes, 17 + 9);
+    assert!(
+        artifacts
+            .path
+            .ends_with(format!("download_{}", download_id.0))
+    );
+}
+
+#[tokio::test]
+async fn test_post_process_failure_deletes_intermediate_files_when_not_kept() {
+    let (downloader, download_id, _temp_dir) = create_failing_download(false).await;
+    let mut events = downloader.subscribe();
+
+    assert!(downloader.start_post_processing(download_id).await.is_err());
+
+    let artifacts = downloader.download_artifacts(download_id).await.unwrap();
+    assert!(!artifacts.path.exists(), "temp folder should be deleted");
+    assert!(artifacts.files.is_empty());
+    assert_eq!(artifacts.total_bytes, 0);
+
+    let mut files_kept = None;
+    while let Ok(Ok(event)) = tokio::time::timeout(Duration::from_millis(100), events.recv()).await
+    {
+        if let Event::Failed {
+            id,
+            files_kept: kept,
+            ..
+        } = event
+            && id == download_id
+        {
+            files_kept = Some(kept);
+        }
+    }
+    assert_eq!(files_kept, Some(false));
+}
+
+#[tokio::test]
+async fn test_download_artifacts_unknown_download_is_not_found() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let result = downloader.download_artifacts(DownloadId(999)).await;
+    assert!(matches!(
+        result,
+        Err(Error::Download(DownloadError::NotFound { id: 999 }))
+    ));
+}
+
+/// Create a downloader writing `format` sidecars, with a queued download whose temp
+/// folder holds one plain file
+async fn create_sidecar_download(
+    format: crate::config::SidecarFormat,
+) -> (UsenetDownloader, DownloadId, tempfile::TempDir) {
+    let temp_dir = tempdir().unwrap();
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.processing.completion_sidecar = format;
+    let downloader = UsenetDownloader::new(config).await.unwrap();
+
+    let download_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "sidecar.nzb",
+            DownloadOptions {
+                post_process: Some(crate::config::PostProcess::UnpackAndCleanup),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let download_path = temp_dir
+        .path()
+        .join("temp")
+        .join(format!("download_{}", download_id.0));
+    std::fs::create_dir_all(&download_path).unwrap();
+    std::fs::write(download_path.join("movie.mkv"), b"video").unwrap();
+
+    (downloader, download_id, temp_dir)
+}
+
+#[tokio::test]
+async fn test_post_process_success_writes_json_sidecar() {
+    let (downloader, download_id, temp_dir) =
+        create_sidecar_download(crate::config::SidecarFormat::Json).await;
+
+    downloader.start_post_processing(download_id).await.unwrap();
+
+    let downloads = temp_dir.path().join("downloads");
+    assert!(downloads.join("movie.mkv").exists());
+    let sidecar: serde_json::Value = serde_json::from_slice(
+        &std::fs::read(downloads.join("Test Download.usenet-dl.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(sidecar["download_id"], download_id.0);
+    assert_eq!(sidecar["name"], "sidecar.nzb");
+    assert_eq!(sidecar["nzb_title"], "Test Download");
+    assert!(sidecar["category"].is_null());
+    assert!(!sidecar["message_ids"].as_array().unwrap().is_empty());
+    assert!(sidecar["added_at"].is_string());
+    assert!(sidecar["completed_at"].is_string());
+    assert_eq!(sidecar["post_processing"]["verified"], true);
+    assert_eq!(sidecar["post_processing"]["damaged"], false);
+    assert_eq!(sidecar["post_processing"]["direct_unpack"], false);
+    assert_eq!(sidecar["post_processing"]["extracted_files"], 0);
+}
+
+#[tokio::test]
+async fn test_post_process_success_writes_nfo_sidecar() {
+    let (downloader, download_id, temp_dir) =
+        create_sidecar_download(crate::config::SidecarFormat::Nfo).await;
+
+    downloader.start_post_processing(download_id).await.unwrap();
+
+    let nfo = std::fs::read_to_string(
+        temp_dir
+            .path()
+            .join("downloads")
+            .join("Test Download.usenet-dl.nfo"),
+    )
+    .unwrap();
+    assert!(nfo.contains(&format!("Download ID: {}\n", download_id.0)));
+    assert!(nfo.contains("Name: sidecar.nzb\n"));
+    assert!(nfo.contains("NZB Title: Test Download\n"));
+    assert!(nfo.contains("Message-IDs: "));
+    assert!(nfo.contains("Verified: true\n"));
+    assert!(!nfo.contains("Category:"));
+}
+
+#[tokio::test]
+async fn test_post_process_writes_no_sidecar_by_default() {
+    let (downloader, download_id, temp_dir) =
+        create_sidecar_download(crate::config::SidecarFormat::default()).await;
+
+    downloader.start_post_processing(download_id).await.unwrap();
+
+    let names: Vec<_> = std::fs::read_dir(temp_dir.path().join("downloads"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(names, vec![std::ffi::OsString::from("movie.mkv")]);
+}
+
+#[tokio::test]
+async fn test_start_post_processing_waits_while_paused() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let download_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "test.nzb",
+            DownloadOptions {
+                post_process: Some(crate::config::PostProcess::None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    downloader
+        .pause_queue(PauseScope::PostProcessing)
+        .await
+        .unwrap();
+
+    let task = {
+        let downloader = downloader.clone();
+        tokio::spawn(async move { downloader.start_post_processing(download_id).await })
+    };
+
+    // Held as Processing while paused
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!task.is_finished());
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Processing);
+
+    downloader
+        .resume_queue(PauseScope::PostProcessing)
+        .await
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(5), task)
+        .await
+        .expect("post-processing should start after resuming")
+        .unwrap()
+        .unwrap();
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Complete);
+}
+
+#[tokio::test]
+async fn test_start_post_processing_waits_for_a_free_slot() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut events = downloader.subscribe();
+
+    let download_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "test.nzb",
+            DownloadOptions {
+                post_process: Some(crate::config::PostProcess::None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    // Every slot taken by other runs
+    let busy = std::sync::Arc::clone(&downloader.queue_state.post_processing_slots)
+        .acquire_many_owned(downloader.config.processing.max_parallel_jobs as u32)
+        .await
+        .unwrap();
+
+    let task = {
+        let downloader = downloader.clone();
+        tokio::spawn(async move { downloader.start_post_processing(download_id).await })
+    };
+
+    let queued = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Ok(Event::PostProcessQueued { id, position }) = events.recv().await {
+                return (id, position);
+            }
+        }
+    })
+    .await
+    .expect("should be queued for post-processing");
+    assert_eq!(queued, (download_id, 1));
+
+    let stats = downloader.queue_stats().await.unwrap();
+    assert_eq!(stats.post_processing_queued, 1);
+    assert_eq!(
+        stats.post_processing_running,
+        downloader.config.processing.max_parallel_jobs
+    );
+    assert!(!task.is_finished());
+
+    drop(busy);
+    tokio::time::timeout(Duration::from_secs(5), task)
+        .await
+        .expect("post-processing should start once a slot frees up")
+        .unwrap()
+        .unwrap();
+
+    let stats = downloader.queue_stats().await.unwrap();
+    assert_eq!(stats.post_processing_queued, 0);
+    assert_eq!(stats.post_processing_running, 0);
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Complete);
+}
+
+#[tokio::test]
+async fn test_move_options_follow_category() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+    let junk_filter = crate::config::JunkFilterConfig {
+        enabled: true,
+        ..Default::default()
+    };
+    for (name, flatten, junk_filter) in [
+        ("tv", Some(true), Some(junk_filter.clone())),
+        ("movies", None, None),
+    ] {
+        downloader
+            .add_or_update_category(
+                name,
+                crate::config::CategoryConfig {
+                    destination: temp_dir.path().join(name),
+                    post_process: None,
+                    scripts: vec![],
+                    flatten_single_folder: flatten,
+                    junk_filter,
+                    verify_policy: None,
+                },
+            )
+            .await;
+    }
+
+    let tv = downloader.move_options(Some("tv")).await;
+    assert!(tv.flatten_single_folder);
+    assert_eq!(tv.junk_filter, junk_filter);
+    // Without an override, the config applies
+    let defaults = crate::post_processing::MoveOptions::from_config(&downloader.config);
+    assert_eq!(downloader.move_options(Some("movies")).await, defaults);
+    assert_eq!(downloader.move_options(Some("unknown")).await, defaults);
+    assert_eq!(downloader.move_options(None).await, defaults);
+}
+
+#[tokio::test]
+async fn test_verify_policy_follows_category() {
+    use crate::config::VerifyPolicy;
+
+    let (downloader, temp_dir) = create_test_downloader().await;
+    for (name, verify_policy) in [("trusted", Some(VerifyPolicy::Never)), ("movies", None)] {
+        downloader
+            .add_or_update_category(
+                name,
+                crate::config::CategoryConfig {
+                    destination: temp_dir.path().join(name),
+                    post_process: None,
+                    scripts: vec![],
+                    flatten_single_folder: None,
+                    junk_filter: None,
+                    verify_policy,
+                },
+            )
+            .await;
+    }
+
+    assert_eq!(
+        downloader.verify_policy(Some("trusted")).await,
+        VerifyPolicy::Never
+    );
+    assert_eq!(
+        downloader.verify_policy(Some("movies")).await,
+        VerifyPolicy::WhenParsPresent
+    );
+    assert_eq!(
+        downloader.verify_policy(None).await,
+        VerifyPolicy::WhenParsPresent
+    );
+}
+
+/// Parity handler whose verify always comes up short of recovery blocks
+struct ShortOfBlocksParityHandler;
+
+#[async_trait::async_trait]
+impl crate::parity::ParityHandler for ShortOfBlocksParityHandler {
+    async fn verify(&self, _par2_file: &std::path::Path) -> Result<crate::parity::VerifyResult> {
+        Ok(crate::parity::VerifyResult {
+            is_complete: false,
+            damaged_blocks: 5,
+            recovery_blocks_available: 0,
+            repairable: false,
+            damaged_files: vec!["movie.mkv".to_string()],
+            missing_files: vec![],
+        })
+    }
+
+    async fn repair(&self, _par2_file: &std::path::Path) -> Result<crate::parity::RepairResult> {
+        unreachable!("verify never reports the files as repairable")
+    }
+
+    fn capabilities(&self) -> crate::parity::ParityCapabilities {
+        crate::parity::ParityCapabilities {
+            can_verify: true,
+            can_repair: true,
+            ..Default::default()
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "short-of-blocks-test"
+    }
+}
+
+fn nzb_file(name: &str, message_id: &str) -> String {
+    format!(
+        r#"  <file poster="user@example.com" date="1234567890" subject="&quot;{}&quot; yEnc (1/1)">
+    <groups><group>alt.binaries.test</group></groups>
+    <segments><segment bytes="1000" number="1">{}</segment></segments>
+  </file>
+"#,
+        name, message_id
+    )
+}
+
+#[tokio::test]
+async fn test_repair_short_of_blocks_fetches_held_par2_volumes() {
+    let temp_dir = tempdir().unwrap();
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.download.hold_par2_volumes = true;
+    config.tools.parity_handler = Some(std::sync::Arc::new(ShortOfBlocksParityHandler));
+    let downloader = UsenetDownloader::new(config).await.unwrap();
+
+    let nzb = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<nzb xmlns=\"http://www.newzbin.com/DTD/2003/nzb\">\n{}{}{}{}</nzb>",
+        nzb_file("movie.mkv", "mkv@example.com"),
+        nzb_file("movie.par2", "par2@example.com"),
+        nzb_file("movie.vol00+01.par2", "vol1@example.com"),
+        nzb_file("movie.vol01+02.par2", "vol2@example.com"),
+    );
+    let download_id = downloader
+        .add_nzb_content(nzb.as_bytes(), "movie.nzb", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    // Only the data file and the index file are fetched up front
+    let pending = downloader
+        .db
+        .get_pending_articles(download_id)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 2);
+
+    let download_path = temp_dir
+        .path()
+        .join("temp")
+        .join(format!("download_{}", download_id.0));
+    std::fs::create_dir_all(&download_path).unwrap();
+    std::fs::write(download_path.join("movie.mkv"), b"damaged video").unwrap();
+    std::fs::write(download_path.join("movie.par2"), b"index").unwrap();
+
+    let mut events = downloader.subscribe();
+    downloader.start_post_processing(download_id).await.unwrap();
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Queued);
+    let pending = downloader
+        .db
+        .get_pending_articles(download_id)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 4);
+    let fetching = loop {
+        match events.try_recv().unwrap() {
+            Event::FetchingExtraPars { id, files, blocks } => break (id, files, blocks),
+            _ => continue,
+        }
+    };
+    assert_eq!(fetching, (download_id, 2, 3));
+
+    // Nothing is left to fetch the second time round, so the download fails
+    assert!(downloader.start_post_processing(download_id).await.is_err());
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Failed);
+}
+
+#[tokio::test]
+async fn test_download_waits_for_password_until_one_is_submitted() {
+    let temp_dir = tempdir().unwrap();
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.tools.try_empty_password = false;
+    let downloader = UsenetDownloader::new(config).await.unwrap();
+
+    // No password given, in the NZB or otherwise
+    let nzb = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<nzb xmlns=\"http://www.newzbin.com/DTD/2003/nzb\">\n{}</nzb>",
+        nzb_file("movie.zip", "zip@example.com"),
+    );
+    let download_id = downloader
+        .add_nzb_content(
+            nzb.as_bytes(),
+            "locked.nzb",
+            DownloadOptions {
+                post_process: Some(crate::config::PostProcess::UnpackAndCleanup),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let download_path = temp_dir
+        .path()
+        .join("temp")
+        .join(format!("download_{}", download_id.0));
+    std::fs::create_dir_all(&download_path).unwrap();
+    let mut zip =
+        ::zip::ZipWriter::new(std::fs::File::create(download_path.join("movie.zip")).unwrap());
+    zip.start_file("movie.mkv", ::zip::write::FileOptions::default())
+        .unwrap();
+    std::io::Write::write_all(&mut zip, b"video").unwrap();
+    zip.finish().unwrap();
+
+    // Nothing to submit while the download isn't waiting
+    assert!(matches!(
+        downloader.submit_password(download_id, "secret").await,
+        Err(Error::Download(DownloadError::InvalidState { .. }))
+    ));
+
+    let mut events = downloader.subscribe();
+    downloader.start_post_processing(download_id).await.unwrap();
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        Status::from_i32(download.status),
+        Status::WaitingForPassword
+    );
+    assert_eq!(
+        download.error_code.as_deref(),
+        Some("no_passwords_available")
+    );
+    assert!(download_path.join("movie.zip").exists());
+    let archive = loop {
+        match events.try_recv().unwrap() {
+            Event::WaitingForPassword { id, archive } if id == download_id => break archive,
+            Event::Failed { .. } => panic!("a download without passwords shouldn't fail"),
+            _ => continue,
+        }
+    };
+    assert_eq!(archive, download_path.join("movie.zip"));
+
+    assert!(matches!(
+        downloader.submit_password(download_id, "").await,
+        Err(Error::Config { .. })
+    ));
+    downloader
+        .submit_password(download_id, "secret")
+        .await
+        .unwrap();
+    let passwords = downloader
+        .db
+        .get_download_passwords(download_id)
+        .await
+        .unwrap();
+    assert_eq!(passwords.password.as_deref(), Some("secret"));
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let download = downloader
+            .db
+            .get_download(download_id)
+            .await
+            .unwrap()
+            .unwrap();
+        if Status::from_i32(download.status) == Status::Complete {
+            break;
+        }
+        assert!(Instant::now() < deadline, "extraction was never retried");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(temp_dir.path().join("downloads").join("movie.mkv").exists());
+}
+
+/// Add a download left in Processing by a crash during `stage`, with `files` in its temp folder
+async fn create_interrupted_download(
+    downloader: &UsenetDownloader,
+    post_process: crate::config::PostProcess,
+    stage: Stage,
+    files: &[&str],
+) -> (DownloadId, std::path::PathBuf) {
+    let download_id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "interrupted.nzb",
+            DownloadOptions {
+                post_process: Some(post_process),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    downloader
+        .db
+        .update_status(download_id, Status::Processing.to_i32())
+        .await
+        .unwrap();
+    downloader
+        .db
+        .set_post_process_stage(download_id, Some(stage))
+        .await
+        .unwrap();
+
+    let download_temp_dir = downloader
+        .config
+        .download
+        .temp_dir
+        .join(format!("download_{}", download_id.0));
+    for file in files {
+        let path = download_temp_dir.join(file);
+        tokio::fs::create_dir_all(path.parent().unwrap())
+            .await
+            .unwrap();
+        tokio::fs::write(path, b"data").await.unwrap();
+    }
+    (download_id, download_temp_dir)
+}
+
+/// A move cut short by a crash continues with the files left in the temp folder
+#[tokio::test]
+async fn test_resume_post_processing_continues_interrupted_move() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let (download_id, _) = create_interrupted_download(
+        &downloader,
+        crate::config::PostProcess::UnpackAndCleanup,
+        Stage::Move,
+        &["extracted/second.bin"],
+    )
+    .await;
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let destination = std::path::PathBuf::from(&download.destination);
+    tokio::fs::create_dir_all(&destination).await.unwrap();
+    tokio::fs::write(destination.join("first.bin"), b"data")
+        .await
+        .unwrap();
+
+    let mut events = downloader.subscribe();
+    downloader
+        .resume_post_processing(download_id)
+        .await
+        .unwrap();
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Complete);
+    assert!(destination.join("first.bin").exists());
+    assert!(destination.join("second.bin").exists());
+    assert!(
+        !destination.join("first (1).bin").exists(),
+        "files moved before the crash are not moved again"
+    );
+    assert_eq!(
+        downloader
+            .db
+            .get_post_process_stage(download_id)
+            .await
+            .unwrap(),
+        None,
+        "the journal is cleared once the pipeline has finished"
+    );
+    while let Ok(event) = events.try_recv() {
+        assert!(
+            !matches!(event, Event::Verifying { .. } | Event::Extracting { .. }),
+            "stages before the move are skipped, got {:?}",
+            event
+        );
+    }
+}
+
+/// An extraction cut short by a crash starts over without its partial output
+#[tokio::test]
+async fn test_resume_post_processing_restarts_interrupted_extraction() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let (download_id, download_temp_dir) = create_interrupted_download(
+        &downloader,
+        crate::config::PostProcess::Unpack,
+        Stage::Extract,
+        &["payload.bin", "extracted/partial.bin"],
+    )
+    .await;
+
+    downloader
+        .resume_post_processing(download_id)
+        .await
+        .unwrap();
+
+    let download = downloader
+        .db
+        .get_download(download_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Complete);
+    assert!(
+        !download_temp_dir.join("extracted").exists(),
+        "partial extraction output is removed before extracting again"
+    );
+    assert!(download_temp_dir.join("payload.bin").exists());
+}
@@ -0,0 +1,158 @@
+use super::*;
+use crate::config::CategoryConfig;
+use crate::db::article_status;
+use std::path::PathBuf;
+
+async fn add_category(downloader: &UsenetDownloader, name: &str, dir: &std::path::Path) {
+    downloader
+        .add_or_update_category(
+            name,
+            CategoryConfig {
+                destination: dir.join(name),
+                post_process: None,
+                scripts: vec![],
+                flatten_single_folder: None,
+                junk_filter: None,
+                verify_policy: None,
+            },
+        )
+        .await;
+}
+
+#[tokio::test]
+async fn export_and_import_round_trip() {
+    let (source, source_dir) = create_test_downloader().await;
+    add_category(&source, "tv", source_dir.path()).await;
+    let id = source
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "migrated",
+            DownloadOptions {
+                category: Some("tv".to_string()),
+                priority: Priority::High,
+                password: Some("given".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let paused_id = source
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "paused", DownloadOptions::default())
+        .await
+        .unwrap();
+    source.pause(paused_id).await.unwrap();
+
+    // First segment downloaded and written to the partial file
+    let articles = source.db.get_articles(id).await.unwrap();
+    source
+        .db
+        .update_article_status(articles[0].id, article_status::DOWNLOADED)
+        .await
+        .unwrap();
+    let download = source.db.get_download(id).await.unwrap().unwrap();
+    let files = source.db.get_download_files(id).await.unwrap();
+    let temp_dir = download.incomplete_path(&source.config.download.temp_dir);
+    std::fs::create_dir_all(&temp_dir).unwrap();
+    std::fs::write(temp_dir.join(&files[0].filename), b"first segment").unwrap();
+
+    // Finished downloads stay behind
+    let done = source
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "done", DownloadOptions::default())
+        .await
+        .unwrap();
+    source
+        .db
+        .update_status(done, Status::Complete.to_i32())
+        .await
+        .unwrap();
+
+    let archive = source_dir.path().join("queue.zip");
+    assert_eq!(source.export_queue(&archive).await.unwrap(), 2);
+
+    let (target, target_dir) = create_test_downloader().await;
+    add_category(&target, "tv", target_dir.path()).await;
+    let existing = target
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "local", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    let ids = target.import_queue(&archive).await.unwrap();
+    assert_eq!(ids.len(), 2);
+    assert!(
+        target.db.get_download(existing).await.unwrap().is_some(),
+        "existing downloads are kept"
+    );
+
+    let imported = target.db.get_download(ids[0]).await.unwrap().unwrap();
+    assert_eq!(imported.name, "migrated");
+    assert_eq!(imported.category.as_deref(), Some("tv"));
+    assert_eq!(imported.priority, Priority::High as i32);
+    assert_eq!(imported.status, Status::Queued.to_i32());
+    assert_eq!(
+        PathBuf::from(&imported.destination),
+        target_dir.path().join("tv"),
+        "destination comes from the importing instance's category"
+    );
+    assert_eq!(imported.job_name.as_deref(), Some("Test Download"));
+    assert_eq!(imported.downloaded_bytes, 768000);
+
+    let passwords = target.db.get_download_passwords(ids[0]).await.unwrap();
+    assert_eq!(passwords.password.as_deref(), Some("given"));
+    assert_eq!(passwords.nzb_password.as_deref(), Some("testpass123"));
+
+    let statuses: Vec<i32> = target
+        .db
+        .get_articles(ids[0])
+        .await
+        .unwrap()
+        .iter()
+        .map(|a| a.status)
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![article_status::DOWNLOADED, article_status::PENDING]
+    );
+    let files = target.db.get_download_files(ids[0]).await.unwrap();
+    let partial = imported
+        .incomplete_path(&target.config.download.temp_dir)
+        .join(&files[0].filename);
+    assert_eq!(std::fs::read(partial).unwrap(), b"first segment");
+
+    let paused = target.db.get_download(ids[1]).await.unwrap().unwrap();
+    assert_eq!(paused.name, "paused");
+    assert_eq!(paused.status, Status::Paused.to_i32());
+
+    let queue = target.queue_state.queue.lock().await;
+    assert_eq!(queue.len(), 2, "paused downloads aren't queued");
+    assert!(queue.iter().all(|item| item.id != ids[1]));
+    drop(queue);
+
+    // Scratch files are cleaned up
+    let leftovers: Vec<_> = std::fs::read_dir(&target.config.download.temp_dir)
+        .unwrap()
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with(".backup-"))
+        .collect();
+    assert!(leftovers.is_empty());
+}
+
+#[tokio::test]
+async fn import_rejects_invalid_archive() {
+    let (downloader, temp_dir) = create_test_downloader().await;
+
+    let archive = temp_dir.path().join("queue.zip");
+    std::fs::write(&archive, b"not a zip").unwrap();
+    assert!(matches!(
+        downloader.import_queue(&archive).await,
+        Err(Error::InvalidBackup(_))
+    ));
+
+    // A backup archive isn't a queue export
+    let backup = temp_dir.path().join("backup.zip");
+    downloader.export_backup(&backup).await.unwrap();
+    assert!(matches!(
+        downloader.import_queue(&backup).await,
+        Err(Error::InvalidBackup(_))
+    ));
+    assert!(downloader.db.list_downloads().await.unwrap().is_empty());
+}
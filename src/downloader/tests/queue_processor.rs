@@ -41,6 +41,7 @@ async fn push_to_queue(
         id,
         priority,
         created_at,
+        size_bytes: 1000,
     });
 }
 
@@ -240,3 +241,277 @@ async fn re_pushes_on_semaphore_close() {
     let item = downloader.queue_state.queue.lock().await.pop().unwrap();
     assert_eq!(item.id, id, "Re-pushed item should have the same ID");
 }
+
+// -----------------------------------------------------------------------
+// forced downloads
+// -----------------------------------------------------------------------
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn forced_download_starts_past_concurrency_limit() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    // No permits at all: only a forced download can start
+    let downloader = {
+        let mut d = downloader;
+        d.queue_state.concurrent_limit = std::sync::Arc::new(tokio::sync::Semaphore::new(0));
+        d
+    };
+
+    let force_id = insert_test_download(&downloader, "force", Priority::Force).await;
+    push_to_queue(&downloader, force_id, Priority::Force, 1).await;
+
+    let mut events = downloader.subscribe();
+    let handle = downloader.start_queue_processor();
+
+    // The download fails right away (no NNTP pools), ending the preemption
+    let mut seen = Vec::new();
+    let _ = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while let Ok(event) = events.recv().await {
+            match event {
+                Event::DownloadsPreempted { id, .. } => seen.push((id, "preempted")),
+                Event::Downloading { id, .. } if !seen.contains(&(id, "downloading")) => {
+                    seen.push((id, "downloading"))
+                }
+                Event::PreemptionEnded { id, .. } => {
+                    seen.push((id, "ended"));
+                    break;
+                }
+                _ => {}
+            }
+        }
+    })
+    .await;
+
+    assert_eq!(
+        seen,
+        vec![
+            (force_id, "preempted"),
+            (force_id, "downloading"),
+            (force_id, "ended")
+        ]
+    );
+    assert!(
+        downloader
+            .queue_state
+            .forced_downloads
+            .lock()
+            .unwrap()
+            .is_empty()
+    );
+
+    handle.abort();
+}
+
+#[tokio::test]
+async fn preemption_holds_other_downloads_until_last_forced_finishes() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut events = downloader.subscribe();
+
+    let active = downloader.download_speed_limiter(DownloadId(1));
+    let first_forced = DownloadId(2);
+    let second_forced = DownloadId(3);
+
+    downloader.start_preemption(first_forced);
+    assert!(active.is_held());
+    assert!(!downloader.download_speed_limiter(first_forced).is_held());
+    match events.try_recv().unwrap() {
+        Event::DownloadsPreempted { id, preempted } => {
+            assert_eq!(id, first_forced);
+            assert_eq!(preempted, vec![DownloadId(1)]);
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+
+    // Downloads starting meanwhile are held from the start
+    let late = downloader.download_speed_limiter(DownloadId(4));
+    assert!(late.is_held());
+
+    // A second forced download runs alongside the first
+    downloader.start_preemption(second_forced);
+    assert!(!downloader.download_speed_limiter(second_forced).is_held());
+    assert!(matches!(
+        events.try_recv().unwrap(),
+        Event::DownloadsPreempted { preempted, .. } if preempted.is_empty()
+    ));
+
+    downloader.end_preemption(first_forced);
+    assert!(active.is_held(), "still held while a forced download runs");
+    assert!(events.try_recv().is_err());
+
+    downloader.end_preemption(second_forced);
+    assert!(!active.is_held());
+    assert!(!late.is_held());
+    match events.try_recv().unwrap() {
+        Event::PreemptionEnded { id, resumed } => {
+            assert_eq!(id, second_forced);
+            assert_eq!(resumed, vec![DownloadId(1), DownloadId(4)]);
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+}
+
+// -----------------------------------------------------------------------
+// metered bandwidth mode defers large downloads
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+async fn metered_mode_passes_over_large_downloads() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let max = downloader.config.download.metered.max_job_bytes.unwrap();
+    let queued = |id, priority, created_at, size_bytes| QueuedDownload {
+        id: DownloadId(id),
+        priority,
+        created_at,
+        size_bytes,
+    };
+    let mut queue = std::collections::BinaryHeap::from(vec![
+        queued(1, Priority::High, 1, max + 1),
+        queued(2, Priority::Normal, 2, max),
+        queued(3, Priority::Low, 3, max + 1),
+    ]);
+
+    downloader
+        .set_bandwidth_mode(crate::types::BandwidthMode::Metered)
+        .await;
+    assert_eq!(
+        downloader.pop_startable(&mut queue).unwrap().id,
+        DownloadId(2)
+    );
+    assert!(downloader.pop_startable(&mut queue).is_none());
+    assert_eq!(queue.len(), 2, "large downloads stay queued");
+
+    // Forced downloads start whatever their size
+    queue.push(queued(4, Priority::Force, 4, max + 1));
+    assert_eq!(
+        downloader.pop_startable(&mut queue).unwrap().id,
+        DownloadId(4)
+    );
+
+    downloader
+        .set_bandwidth_mode(crate::types::BandwidthMode::Normal)
+        .await;
+    assert_eq!(
+        downloader.pop_startable(&mut queue).unwrap().id,
+        DownloadId(1)
+    );
+}
+
+// -----------------------------------------------------------------------
+// small job fast lane
+// -----------------------------------------------------------------------
+
+/// Test downloader with the fast lane enabled and no free download slots
+async fn create_fast_lane_downloader() -> (UsenetDownloader, tempfile::TempDir) {
+    let (mut downloader, temp_dir) = create_test_downloader().await;
+    let mut config = (*downloader.config).clone();
+    config.download.fast_lane.enabled = true;
+    downloader.config = std::sync::Arc::new(config);
+    downloader.queue_state.concurrent_limit = std::sync::Arc::new(tokio::sync::Semaphore::new(0));
+    (downloader, temp_dir)
+}
+
+#[tokio::test]
+async fn fast_lane_takes_small_downloads_only() {
+    let (downloader, _temp_dir) = create_fast_lane_downloader().await;
+    let max = downloader.config.download.fast_lane.max_job_bytes;
+    let queued = |id, priority, created_at, size_bytes| QueuedDownload {
+        id: DownloadId(id),
+        priority,
+        created_at,
+        size_bytes,
+    };
+    let mut queue = std::collections::BinaryHeap::from(vec![
+        queued(1, Priority::High, 1, max + 1),
+        queued(2, Priority::Normal, 2, 0),
+        queued(3, Priority::Normal, 3, max),
+        queued(4, Priority::Low, 4, 1000),
+    ]);
+
+    // A closed lane only lets forced downloads through
+    assert!(downloader.pop_fast_lane(&mut queue, false).is_none());
+    queue.push(queued(5, Priority::Force, 5, max + 1));
+    assert_eq!(
+        downloader.pop_fast_lane(&mut queue, false).unwrap().id,
+        DownloadId(5)
+    );
+
+    // An open one takes the highest priority download that fits, skipping unknown sizes
+    assert_eq!(
+        downloader.pop_fast_lane(&mut queue, true).unwrap().id,
+        DownloadId(3)
+    );
+    assert_eq!(
+        downloader.pop_fast_lane(&mut queue, true).unwrap().id,
+        DownloadId(4)
+    );
+    assert!(downloader.pop_fast_lane(&mut queue, true).is_none());
+    assert_eq!(queue.len(), 2, "large and unsized downloads stay queued");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fast_lane_starts_small_download_while_slots_are_taken() {
+    let (downloader, _temp_dir) = create_fast_lane_downloader().await;
+    let max = downloader.config.download.fast_lane.max_job_bytes;
+
+    let large_id = insert_test_download(&downloader, "large", Priority::High).await;
+    let small_id = insert_test_download(&downloader, "small", Priority::Normal).await;
+    {
+        let mut queue = downloader.queue_state.queue.lock().await;
+        queue.push(QueuedDownload {
+            id: large_id,
+            priority: Priority::High,
+            created_at: 1,
+            size_bytes: max + 1,
+        });
+        queue.push(QueuedDownload {
+            id: small_id,
+            priority: Priority::Normal,
+            created_at: 2,
+            size_bytes: 1000,
+        });
+    }
+
+    let mut events = downloader.subscribe();
+    let handle = downloader.start_queue_processor();
+
+    let started = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        loop {
+            if let Ok(Event::Downloading { id, .. }) = events.recv().await {
+                break id;
+            }
+        }
+    })
+    .await
+    .expect("small download should start in the fast lane");
+    handle.abort();
+
+    assert_eq!(started, small_id);
+    let queue = downloader.queue_state.queue.lock().await;
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.peek().unwrap().id, large_id);
+}
+
+#[tokio::test]
+async fn fast_lane_reserves_connections() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    assert_eq!(downloader.download_concurrency(DownloadId(1), 20), 20);
+
+    let (downloader, _temp_dir) = create_fast_lane_downloader().await;
+    let reserved = downloader.config.download.fast_lane.connections;
+    downloader
+        .queue_state
+        .fast_lane_downloads
+        .lock()
+        .unwrap()
+        .insert(DownloadId(2));
+
+    assert_eq!(
+        downloader.download_concurrency(DownloadId(1), 20),
+        20 - reserved
+    );
+    assert_eq!(downloader.download_concurrency(DownloadId(2), 20), reserved);
+
+    // Too few connections to spare: the fast lane gets them all, others keep one
+    assert_eq!(downloader.download_concurrency(DownloadId(1), 2), 1);
+    assert_eq!(downloader.download_concurrency(DownloadId(2), 2), 2);
+}
@@ -10,6 +10,7 @@ async fn test_start_rss_scheduler_with_feeds() {
             database_path: temp_dir.path().join("test.db"),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         servers: vec![],
         automation: config::AutomationConfig {
@@ -21,6 +22,8 @@ async fn test_start_rss_scheduler_with_feeds() {
                 auto_download: true,
                 priority: Priority::Normal,
                 enabled: true,
+                auth: Default::default(),
+                preference: 0,
             }],
             ..Default::default()
         },
@@ -55,6 +58,7 @@ async fn test_start_rss_scheduler_respects_shutdown() {
             database_path: temp_dir.path().join("test.db"),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         servers: vec![],
         automation: config::AutomationConfig {
@@ -66,6 +70,8 @@ async fn test_start_rss_scheduler_respects_shutdown() {
                 auto_download: false,
                 priority: Priority::Normal,
                 enabled: true,
+                auth: Default::default(),
+                preference: 0,
             }],
             ..Default::default()
         },
@@ -106,6 +112,7 @@ async fn test_start_rss_scheduler_with_multiple_feeds() {
             database_path: temp_dir.path().join("test.db"),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         servers: vec![],
         automation: config::AutomationConfig {
@@ -118,6 +125,8 @@ async fn test_start_rss_scheduler_with_multiple_feeds() {
                     auto_download: true,
                     priority: Priority::High,
                     enabled: true,
+                    auth: Default::default(),
+                    preference: 0,
                 },
                 config::RssFeedConfig {
                     url: "https://example.com/feed2.xml".to_string(),
@@ -127,6 +136,8 @@ async fn test_start_rss_scheduler_with_multiple_feeds() {
                     auto_download: false,
                     priority: Priority::Normal,
                     enabled: false, // Disabled feed should be skipped
+                    auth: Default::default(),
+                    preference: 0,
                 },
             ],
             ..Default::default()
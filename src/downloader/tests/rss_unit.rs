@@ -1,4 +1,4 @@
-use crate::config::{RssFeedConfig, RssFilter};
+use crate::config::{RssFeedConfig, RssFilter, RssFilterAction};
 use crate::downloader::test_helpers::create_test_downloader;
 use crate::types::Priority;
 use std::time::Duration;
@@ -17,6 +17,9 @@ fn make_feed_config_with_filters() -> RssFeedConfig {
                 min_size: Some(500_000_000),
                 max_size: Some(50_000_000_000),
                 max_age: Some(Duration::from_secs(86400)),
+                action: Default::default(),
+                category: None,
+                priority: None,
             },
             RssFilter {
                 name: "No Spam".to_string(),
@@ -25,11 +28,16 @@ fn make_feed_config_with_filters() -> RssFeedConfig {
                 min_size: None,
                 max_size: None,
                 max_age: None,
+                action: Default::default(),
+                category: None,
+                priority: None,
             },
         ],
         auto_download: true,
         priority: Priority::High,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     }
 }
 
@@ -92,10 +100,15 @@ async fn add_feed_with_empty_include_exclude_stores_null_patterns() {
             min_size: None,
             max_size: None,
             max_age: None,
+            action: Default::default(),
+            category: None,
+            priority: None,
         }],
         auto_download: false,
         priority: Priority::Normal,
         enabled: false,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let id = dl.add_rss_feed("Empty Feed", config).await.unwrap();
@@ -112,6 +125,51 @@ async fn add_feed_with_empty_include_exclude_stores_null_patterns() {
     );
 }
 
+#[tokio::test]
+async fn add_feed_round_trips_auth() {
+    let (dl, _tmp) = create_test_downloader().await;
+
+    let plain = dl
+        .add_rss_feed("Plain", make_feed_config_with_filters())
+        .await
+        .unwrap();
+    assert!(
+        dl.db
+            .get_rss_feed(plain)
+            .await
+            .unwrap()
+            .unwrap()
+            .auth
+            .is_none(),
+        "a feed without auth should store NULL"
+    );
+
+    let auth = crate::config::RssFeedAuth {
+        headers: [("X-Api-Key".to_string(), "key".to_string())].into(),
+        username: Some("user".to_string()),
+        password: Some("pass".to_string()),
+        api_key: Some("secret".to_string()),
+        api_key_param: "r".to_string(),
+        ..Default::default()
+    };
+    let config = RssFeedConfig {
+        auth: auth.clone(),
+        ..make_feed_config_with_filters()
+    };
+    let id = dl.add_rss_feed("Indexer", config).await.unwrap();
+
+    let (_, _, loaded) = dl.get_rss_feed(id).await.unwrap().unwrap();
+    assert_eq!(loaded.auth, auth);
+
+    let config = RssFeedConfig {
+        auth: Default::default(),
+        ..loaded
+    };
+    dl.update_rss_feed(id, "Indexer", config).await.unwrap();
+    let (_, _, loaded) = dl.get_rss_feed(id).await.unwrap().unwrap();
+    assert_eq!(loaded.auth, crate::config::RssFeedAuth::default());
+}
+
 // ---------------------------------------------------------------
 // get_rss_feeds (all)
 // ---------------------------------------------------------------
@@ -130,6 +188,8 @@ async fn get_feeds_returns_all_with_reconstructed_configs() {
             auto_download: true,
             priority: Priority::Normal,
             enabled: true,
+            auth: Default::default(),
+            preference: 0,
         },
     )
     .await
@@ -148,10 +208,15 @@ async fn get_feeds_returns_all_with_reconstructed_configs() {
                 min_size: Some(1_000_000),
                 max_size: None,
                 max_age: Some(Duration::from_secs(7200)),
+                action: Default::default(),
+                category: None,
+                priority: None,
             }],
             auto_download: false,
             priority: Priority::Low,
             enabled: false,
+            auth: Default::default(),
+            preference: 0,
         },
     )
     .await
@@ -265,10 +330,15 @@ async fn update_feed_replaces_config_and_filters() {
             min_size: None,
             max_size: None,
             max_age: None,
+            action: Default::default(),
+            category: None,
+            priority: None,
         }],
         auto_download: false,
         priority: Priority::Low,
         enabled: false,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let updated = dl
@@ -312,10 +382,15 @@ async fn update_nonexistent_feed_returns_false_and_skips_filter_insertion() {
             min_size: None,
             max_size: None,
             max_age: None,
+            action: Default::default(),
+            category: None,
+            priority: None,
         }],
         auto_download: false,
         priority: Priority::Normal,
         enabled: false,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let updated = dl.update_rss_feed(99999, "Ghost", config).await.unwrap();
@@ -386,10 +461,15 @@ async fn add_then_get_all_preserves_filter_json_patterns() {
             min_size: Some(100_000_000),
             max_size: Some(5_000_000_000),
             max_age: Some(Duration::from_secs(172800)),
+            action: Default::default(),
+            category: None,
+            priority: None,
         }],
         auto_download: true,
         priority: Priority::Force,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     dl.add_rss_feed("Pattern Feed", config).await.unwrap();
@@ -409,3 +489,44 @@ async fn add_then_get_all_preserves_filter_json_patterns() {
     assert_eq!(f.max_age, Some(Duration::from_secs(172800)));
     assert_eq!(feeds[0].priority, Priority::Force);
 }
+
+#[tokio::test]
+async fn add_feed_round_trips_preference() {
+    let (dl, _tmp) = create_test_downloader().await;
+
+    let config = RssFeedConfig {
+        preference: -5,
+        ..make_feed_config_with_filters()
+    };
+    let id = dl.add_rss_feed("Preferred", config).await.unwrap();
+    let (_, _, loaded) = dl.get_rss_feed(id).await.unwrap().unwrap();
+    assert_eq!(loaded.preference, -5);
+
+    let config = RssFeedConfig {
+        preference: 3,
+        ..loaded
+    };
+    dl.update_rss_feed(id, "Preferred", config).await.unwrap();
+    let (_, _, loaded) = dl.get_rss_feed(id).await.unwrap().unwrap();
+    assert_eq!(loaded.preference, 3);
+}
+
+#[tokio::test]
+async fn add_feed_round_trips_filter_actions() {
+    let (dl, _tmp) = create_test_downloader().await;
+
+    let mut config = make_feed_config_with_filters();
+    config.filters[0].action = RssFilterAction::DownloadPaused;
+    config.filters[0].category = Some("4k".to_string());
+    config.filters[0].priority = Some(Priority::Low);
+    config.filters[1].action = RssFilterAction::Reject;
+    let id = dl.add_rss_feed("Actions", config).await.unwrap();
+
+    let (_, _, loaded) = dl.get_rss_feed(id).await.unwrap().unwrap();
+    assert_eq!(loaded.filters[0].action, RssFilterAction::DownloadPaused);
+    assert_eq!(loaded.filters[0].category.as_deref(), Some("4k"));
+    assert_eq!(loaded.filters[0].priority, Some(Priority::Low));
+    assert_eq!(loaded.filters[1].action, RssFilterAction::Reject);
+    assert_eq!(loaded.filters[1].category, None);
+    assert_eq!(loaded.filters[1].priority, None);
+}
@@ -17,8 +17,11 @@ async fn test_start_scheduler_with_rules() {
                     limit_bps: 1_000_000,
                 },
                 enabled: true,
+                start_date: None,
+                end_date: None,
             }],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         servers: vec![],
         ..Default::default()
@@ -57,8 +60,11 @@ async fn test_start_scheduler_respects_shutdown() {
                 end_time: "17:00".to_string(),
                 action: config::ScheduleAction::Unlimited,
                 enabled: true,
+                start_date: None,
+                end_date: None,
             }],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         servers: vec![],
         ..Default::default()
@@ -8,6 +8,8 @@ async fn test_server_health_check_invalid_server() {
     let temp_dir = tempfile::tempdir().unwrap();
     let mut config = Config::default();
     config.download.download_dir = temp_dir.path().to_path_buf();
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.persistence.database_path = temp_dir.path().join("test.db");
 
     let downloader = UsenetDownloader::new(config).await.unwrap();
 
@@ -21,6 +23,10 @@ async fn test_server_health_check_invalid_server() {
         connections: 1,
         priority: 0,
         pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
     };
 
     let result = downloader.test_server(&server).await;
@@ -55,6 +61,8 @@ async fn test_server_health_check_result_structure() {
     let temp_dir = tempfile::tempdir().unwrap();
     let mut config = Config::default();
     config.download.download_dir = temp_dir.path().to_path_buf();
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.persistence.database_path = temp_dir.path().join("test.db");
 
     let downloader = UsenetDownloader::new(config).await.unwrap();
 
@@ -67,6 +75,10 @@ async fn test_server_health_check_result_structure() {
         connections: 1,
         priority: 0,
         pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
     };
 
     let result = downloader.test_server(&server).await;
@@ -91,6 +103,8 @@ async fn test_all_servers_empty_config() {
     let temp_dir = tempfile::tempdir().unwrap();
     let mut config = Config::default();
     config.download.download_dir = temp_dir.path().to_path_buf();
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.persistence.database_path = temp_dir.path().join("test.db");
     config.servers = vec![]; // No servers configured
 
     let downloader = UsenetDownloader::new(config).await.unwrap();
@@ -113,6 +127,8 @@ async fn test_all_servers_multiple_servers() {
     let temp_dir = tempfile::tempdir().unwrap();
     let mut config = Config::default();
     config.download.download_dir = temp_dir.path().to_path_buf();
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.persistence.database_path = temp_dir.path().join("test.db");
 
     // Add multiple test servers
     config.servers = vec![
@@ -125,6 +141,10 @@ async fn test_all_servers_multiple_servers() {
             connections: 1,
             priority: 0,
             pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
         },
         crate::config::ServerConfig {
             host: "server2.invalid".to_string(),
@@ -135,6 +155,10 @@ async fn test_all_servers_multiple_servers() {
             connections: 1,
             priority: 1,
             pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
         },
         crate::config::ServerConfig {
             host: "server3.invalid".to_string(),
@@ -145,6 +169,10 @@ async fn test_all_servers_multiple_servers() {
             connections: 1,
             priority: 2,
             pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
         },
     ];
 
@@ -193,3 +221,194 @@ async fn test_server_capabilities_structure() {
     println!("ServerCapabilities correctly serializes/deserializes");
     println!("  JSON: {}", json);
 }
+
+fn traced_config(capacity: usize) -> Config {
+    let mut config = Config::default();
+    config.servers = vec![crate::config::ServerConfig {
+        host: "news.example.com".to_string(),
+        port: 563,
+        tls: true,
+        username: Some("user".to_string()),
+        password: Some("hunter2".to_string()),
+        connections: 1,
+        priority: 0,
+        pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
+    }];
+    config.logging.nntp_trace = true;
+    config.logging.nntp_trace_capacity = capacity;
+    config
+}
+
+#[test]
+fn nntp_trace_keeps_most_recent_entries() {
+    let tracer = crate::downloader::nntp_trace::NntpTracer::new(&traced_config(2));
+
+    for id in ["a@x", "b@x", "c@x"] {
+        tracer.record(
+            0,
+            &format!("ARTICLE <{id}>"),
+            "220 0 article",
+            Duration::from_millis(12),
+        );
+    }
+
+    let entries = tracer.entries(0).unwrap();
+    let commands: Vec<_> = entries.iter().map(|e| e.command.as_str()).collect();
+    assert_eq!(commands, ["ARTICLE <b@x>", "ARTICLE <c@x>"]);
+    assert_eq!(entries[0].duration_ms, 12);
+    assert!(tracer.entries(1).is_none(), "unknown server has no trace");
+}
+
+#[test]
+fn nntp_trace_redacts_credentials() {
+    let tracer = crate::downloader::nntp_trace::NntpTracer::new(&traced_config(10));
+
+    tracer.record(0, "authinfo pass hunter2", "281 ok", Duration::ZERO);
+    tracer.record(
+        0,
+        "CONNECT",
+        "auth failed for password hunter2",
+        Duration::ZERO,
+    );
+
+    let entries = tracer.entries(0).unwrap();
+    assert_eq!(entries[0].command, "AUTHINFO PASS [redacted]");
+    assert_eq!(entries[1].response, "auth failed for password [redacted]");
+}
+
+#[test]
+fn nntp_trace_records_nothing_when_disabled() {
+    let mut config = traced_config(10);
+    config.logging.nntp_trace = false;
+    let tracer = crate::downloader::nntp_trace::NntpTracer::new(&config);
+
+    tracer.record(0, "ARTICLE <a@x>", "220 0 article", Duration::ZERO);
+
+    assert_eq!(tracer.entries(0).unwrap().len(), 0);
+}
+
+#[test]
+fn speed_test_default_connection_counts_double_up_to_max() {
+    use crate::downloader::server::default_connection_counts;
+
+    assert_eq!(default_connection_counts(1), vec![1]);
+    assert_eq!(default_connection_counts(8), vec![1, 2, 4, 8]);
+    assert_eq!(default_connection_counts(20), vec![1, 2, 4, 8, 16, 20]);
+    assert_eq!(default_connection_counts(0), vec![1]);
+}
+
+#[test]
+fn speed_test_recommends_fewest_connections_near_best() {
+    use crate::downloader::server::recommend_connections;
+
+    let run = |connections, bytes_per_sec| crate::types::SpeedTestRun {
+        connections,
+        bytes: 0,
+        duration_ms: 0,
+        bytes_per_sec,
+        failed_articles: 0,
+    };
+
+    assert_eq!(recommend_connections(&[]), None);
+    assert_eq!(
+        recommend_connections(&[run(1, 10), run(2, 19), run(4, 38), run(8, 40)]),
+        Some(4),
+        "4 connections reach 95% of the best run"
+    );
+    assert_eq!(recommend_connections(&[run(1, 0), run(2, 0)]), None);
+}
+
+#[test]
+fn speed_test_sample_takes_newest_articles_up_to_size() {
+    use crate::downloader::server::select_sample;
+
+    let entry = |article_number, bytes| nntp_rs::XoverEntry {
+        article_number,
+        subject: String::new(),
+        author: String::new(),
+        date: String::new(),
+        message_id: format!("<{}@test>", article_number),
+        references: String::new(),
+        bytes,
+        lines: 0,
+    };
+
+    let ids = select_sample(vec![entry(1, 500), entry(3, 500), entry(2, 500)], 800);
+    assert_eq!(ids, vec!["<3@test>".to_string(), "<2@test>".to_string()]);
+}
+
+#[tokio::test]
+async fn speed_test_unknown_server_returns_none() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let result = downloader
+        .speed_test(0, crate::types::SpeedTestOptions::default())
+        .await;
+
+    assert!(result.is_none());
+}
+
+#[tokio::test]
+async fn speed_test_reports_connection_failure() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.servers = vec![crate::config::ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 1, // nothing listens here
+        tls: false,
+        username: None,
+        password: None,
+        connections: 2,
+        priority: 0,
+        pipeline_depth: 4,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
+    }];
+    let downloader = UsenetDownloader::new(config).await.unwrap();
+
+    let result = downloader
+        .speed_test(
+            0,
+            crate::types::SpeedTestOptions {
+                message_ids: vec!["part1@test".to_string()],
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.server, "127.0.0.1");
+    assert_eq!(result.pipeline_depth, 4);
+    assert_eq!(result.sample_articles, 1);
+    assert!(result.runs.is_empty());
+    assert!(result.recommended_connections.is_none());
+    assert!(result.error.unwrap().contains("1 connections"));
+}
+
+#[tokio::test]
+async fn test_server_against_mock_server() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mock = crate::testing::MockNntpServer::start().await.unwrap();
+    mock.require_auth("user", "secret");
+
+    let result = downloader.test_server(&mock.server_config()).await;
+    assert!(result.success, "got {:?}", result.error);
+    assert!(result.capabilities.is_some());
+
+    let server = crate::config::ServerConfig {
+        password: Some("wrong".to_string()),
+        ..mock.server_config()
+    };
+    let result = downloader.test_server(&server).await;
+    assert!(!result.success);
+    assert!(result.error.unwrap().contains("uthentication"));
+}
@@ -0,0 +1,362 @@
+use super::*;
+use crate::config::{ServerConfig, ServerRouting};
+use crate::downloader::server_pools::idle_for;
+use chrono::Utc;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+fn server(host: &str, connections: usize) -> ServerConfig {
+    ServerConfig {
+        host: host.to_string(),
+        port: 563,
+        tls: true,
+        username: Some("user".to_string()),
+        password: Some("secret".to_string()),
+        connections,
+        priority: 0,
+        pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
+    }
+}
+
+fn error_key(result: Result<impl std::fmt::Debug>) -> Option<String> {
+    match result {
+        Err(Error::Config { key, .. }) => key,
+        other => panic!("expected a config error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn add_server_appends_to_server_list() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    assert_eq!(
+        downloader.add_server(server("primary", 8)).await.unwrap(),
+        0
+    );
+    assert_eq!(downloader.add_server(server("backup", 4)).await.unwrap(), 1);
+
+    let hosts: Vec<_> = downloader
+        .get_servers()
+        .into_iter()
+        .map(|server| server.host)
+        .collect();
+    assert_eq!(hosts, ["primary", "backup"]);
+    let current = downloader.servers.current();
+    assert_eq!(current.pools.len(), 2);
+    assert_eq!(current.speed_limiters.len(), 2);
+    assert!(downloader.nntp_trace(1).is_some());
+    assert_eq!(downloader.effective_config().await.servers.len(), 2);
+
+    let result = downloader.add_server(server("", 8)).await;
+    assert_eq!(error_key(result).as_deref(), Some("servers[2].host"));
+    assert_eq!(downloader.get_servers().len(), 2);
+}
+
+#[tokio::test]
+async fn update_server_rebuilds_pool_only_when_connection_changes() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader.add_server(server("primary", 8)).await.unwrap();
+    downloader.add_server(server("backup", 4)).await.unwrap();
+    let before = downloader.servers.current();
+
+    // A new speed limit keeps the pool
+    let mut limited = server("primary", 8);
+    limited.speed_limit_bps = Some(1_000_000);
+    downloader.update_server(0, limited).await.unwrap();
+    let after = downloader.servers.current();
+    assert!(std::sync::Arc::ptr_eq(&before.pools[0], &after.pools[0]));
+    assert_eq!(downloader.get_servers()[0].speed_limit_bps, Some(1_000_000));
+
+    // More connections rebuild it; the other server keeps its pool
+    let mut updated = server("primary", 16);
+    updated.password = Some(crate::config::REDACTED.to_string());
+    downloader.update_server(0, updated).await.unwrap();
+    let after = downloader.servers.current();
+    assert!(!std::sync::Arc::ptr_eq(&before.pools[0], &after.pools[0]));
+    assert!(std::sync::Arc::ptr_eq(&before.pools[1], &after.pools[1]));
+    assert_eq!(after.servers[0].connections, 16);
+    assert_eq!(
+        after.servers[0].password.as_deref(),
+        Some("secret"),
+        "redaction placeholder keeps the current password"
+    );
+
+    let result = downloader.update_server(1, server("backup", 0)).await;
+    assert_eq!(error_key(result).as_deref(), Some("servers[1].connections"));
+    assert!(matches!(
+        downloader.update_server(2, server("other", 4)).await,
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn remove_server_shifts_later_servers() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader.add_server(server("primary", 8)).await.unwrap();
+    downloader.add_server(server("backup", 4)).await.unwrap();
+    assert!(downloader.set_server_speed_limit(1, Some(2_000_000)));
+    let before = downloader.servers.current();
+
+    downloader.remove_server(0).await.unwrap();
+
+    let servers = downloader.get_servers();
+    assert_eq!(servers.len(), 1);
+    assert_eq!(servers[0].host, "backup");
+    assert_eq!(
+        servers[0].speed_limit_bps,
+        Some(2_000_000),
+        "runtime speed limit moves with the server"
+    );
+    let after = downloader.servers.current();
+    assert!(std::sync::Arc::ptr_eq(&before.pools[1], &after.pools[0]));
+    assert!(downloader.nntp_trace(1).is_none());
+
+    assert!(matches!(
+        downloader.remove_server(1).await,
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn disable_server_takes_it_out_of_rotation_until_ttl_or_enable() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader.add_server(server("primary", 8)).await.unwrap();
+    downloader.add_server(server("backup", 4)).await.unwrap();
+
+    let status = downloader
+        .disable_server(0, Some(std::time::Duration::from_secs(3600)))
+        .await
+        .unwrap();
+    assert!(!status.enabled);
+    let until = status.disabled_until.unwrap().timestamp();
+    let expected = chrono::Utc::now().timestamp() + 3600;
+    assert!((expected - 5..=expected).contains(&until));
+
+    // Without a duration the server stays disabled, with no end time
+    let status = downloader.disable_server(1, None).await.unwrap();
+    assert!(!status.enabled);
+    assert!(status.disabled_until.is_none());
+
+    let statuses = downloader.server_statuses();
+    assert_eq!(statuses.len(), 2);
+    assert!(statuses.iter().all(|status| !status.enabled));
+
+    let status = downloader.enable_server(1).await.unwrap();
+    assert!(status.enabled);
+    assert!(downloader.servers.current().is_enabled(1));
+
+    // A zero duration expires immediately
+    let status = downloader
+        .disable_server(1, Some(std::time::Duration::ZERO))
+        .await
+        .unwrap();
+    assert!(status.enabled);
+
+    assert!(matches!(
+        downloader.disable_server(2, None).await,
+        Err(Error::NotFound(_))
+    ));
+    assert!(matches!(
+        downloader.enable_server(2).await,
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn disabled_server_is_persisted_by_server_key() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader.add_server(server("primary", 8)).await.unwrap();
+    downloader.add_server(server("backup", 4)).await.unwrap();
+    downloader.disable_server(1, None).await.unwrap();
+
+    // The disable moves with the server when its index changes
+    downloader.remove_server(0).await.unwrap();
+    assert!(!downloader.server_statuses()[0].enabled);
+
+    // A changed server key carries the persisted disable along
+    let mut moved = server("backup", 4);
+    moved.port = 443;
+    downloader.update_server(0, moved).await.unwrap();
+    let now = chrono::Utc::now().timestamp();
+    let keys: Vec<_> = downloader
+        .db
+        .list_disabled_servers(now)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.server)
+        .collect();
+    assert_eq!(keys, ["user@backup:443"]);
+
+    // Re-adding a removed server restores its disable from the database
+    downloader.remove_server(0).await.unwrap();
+    let mut readded = server("backup", 4);
+    readded.port = 443;
+    let id = downloader.add_server(readded).await.unwrap();
+    assert!(!downloader.server_statuses()[id].enabled);
+    let id = downloader.add_server(server("primary", 8)).await.unwrap();
+    assert!(downloader.server_statuses()[id].enabled);
+
+    downloader.enable_server(0).await.unwrap();
+    assert!(
+        downloader
+            .db
+            .list_disabled_servers(now)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn disabled_server_stays_disabled_after_restart() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("usenet-dl.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.servers = vec![server("primary", 8), server("backup", 4)];
+
+    let downloader = UsenetDownloader::new(config.clone()).await.unwrap();
+    downloader
+        .disable_server(1, Some(std::time::Duration::from_secs(3600)))
+        .await
+        .unwrap();
+    let before = downloader.server_statuses();
+    drop(downloader);
+
+    let restarted = UsenetDownloader::new(config).await.unwrap();
+    let after = restarted.server_statuses();
+    assert!(after[0].enabled);
+    assert!(!after[1].enabled);
+    assert_eq!(after[1].disabled_until, before[1].disabled_until);
+}
+
+#[test]
+fn pool_is_idle_once_its_connections_sat_for_the_timeout() {
+    let timeout = Duration::from_secs(60);
+    assert!(!idle_for(2, 1000, timeout, 1059));
+    assert!(idle_for(2, 1000, timeout, 1060));
+    // Nothing to close
+    assert!(!idle_for(0, 1000, timeout, 2000));
+}
+
+#[tokio::test]
+async fn close_idle_connections_keeps_pools_without_idle_connections() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader
+        .add_server(ServerConfig {
+            idle_timeout: Some(Duration::from_secs(60)),
+            ..server("primary", 2)
+        })
+        .await
+        .unwrap();
+    downloader.add_server(server("backup", 2)).await.unwrap();
+    let before = downloader.servers.current();
+
+    let now = Utc::now().timestamp();
+    before.mark_used(0);
+    assert!(before.last_used[0].load(Ordering::Relaxed) >= now);
+
+    assert_eq!(
+        downloader.close_idle_connections(now + 3600).await.unwrap(),
+        0
+    );
+    let after = downloader.servers.current();
+    assert!(Arc::ptr_eq(&before, &after));
+}
+
+#[tokio::test]
+async fn reconnect_server_replaces_only_its_pool() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader.add_server(server("primary", 8)).await.unwrap();
+    downloader.add_server(server("backup", 4)).await.unwrap();
+    let before = downloader.servers.current();
+
+    let status = downloader.reconnect_server(0).await.unwrap();
+    assert!(status.enabled);
+    let after = downloader.servers.current();
+    assert!(!Arc::ptr_eq(&before.pools[0], &after.pools[0]));
+    assert!(Arc::ptr_eq(&before.pools[1], &after.pools[1]));
+
+    assert!(matches!(
+        downloader.reconnect_server(2).await,
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn least_cost_routing_prefers_cheapest_server_with_quota() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.download.server_routing = ServerRouting::LeastCost;
+    config.servers = vec![
+        ServerConfig {
+            cost: 5.0,
+            ..server("unlimited", 2)
+        },
+        ServerConfig {
+            cost: 1.0,
+            quota_bytes: Some(1000),
+            ..server("block", 2)
+        },
+        ServerConfig {
+            cost: 1.0,
+            ..server("cheap", 2)
+        },
+    ];
+    let downloader = UsenetDownloader::new(config).await.unwrap();
+
+    let current = downloader.servers.current();
+    assert_eq!(current.fetch_order(), [1, 2, 0]);
+
+    current.add_quota_usage(1, 1000);
+    assert_eq!(current.fetch_order(), [2, 0], "out of quota");
+
+    // Rollups recompute usage from the last 30 days of statistics
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    let expired = (Utc::now() - chrono::Duration::days(30))
+        .format("%Y-%m-%d")
+        .to_string();
+    downloader
+        .db
+        .add_usage(&expired, "block", "", 5000)
+        .await
+        .unwrap();
+    downloader
+        .db
+        .add_usage(&today, "block", "", 600)
+        .await
+        .unwrap();
+    downloader.rollup_usage_stats().await.unwrap();
+    assert_eq!(current.quota_used[1].load(Ordering::Relaxed), 600);
+    assert_eq!(current.fetch_order(), [1, 2, 0]);
+
+    downloader.disable_server(2, None).await.unwrap();
+    assert_eq!(current.fetch_order(), [1, 0]);
+}
+
+#[tokio::test]
+async fn priority_routing_keeps_server_order() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader
+        .add_server(ServerConfig {
+            cost: 5.0,
+            quota_bytes: Some(10),
+            ..server("primary", 2)
+        })
+        .await
+        .unwrap();
+    downloader.add_server(server("backup", 2)).await.unwrap();
+
+    let current = downloader.servers.current();
+    current.add_quota_usage(0, 100);
+    assert_eq!(current.fetch_order(), [0, 1]);
+}
@@ -0,0 +1,151 @@
+use super::*;
+use crate::types::Event;
+
+/// NZB with one file of `segments` articles of `bytes` each
+fn nzb(filename: &str, segments: usize, bytes: u64) -> String {
+    let segments: String = (1..=segments)
+        .map(|n| {
+            format!(
+                "<segment bytes=\"{}\" number=\"{}\">{}.{}@example.com</segment>",
+                bytes, n, filename, n
+            )
+        })
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<nzb xmlns=\"http://www.newzbin.com/DTD/2003/nzb\">\n  <file poster=\"user@example.com\" date=\"1234567890\" subject=\"&quot;{}&quot; yEnc (1/1)\">\n    <groups><group>alt.binaries.test</group></groups>\n    <segments>{}</segments>\n  </file>\n</nzb>",
+        filename, segments
+    )
+}
+
+async fn simulate(
+    temp_dir: &tempfile::TempDir,
+    seed_dir: Option<std::path::PathBuf>,
+) -> UsenetDownloader {
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.download.simulate.enabled = true;
+    config.download.simulate.speed_bps = 0;
+    config.download.simulate.seed_dir = seed_dir;
+    UsenetDownloader::new(config).await.unwrap()
+}
+
+/// Run the queue until `id` completes, returning where its files went
+async fn run_to_completion(downloader: &UsenetDownloader, id: DownloadId) -> std::path::PathBuf {
+    let mut events = downloader.subscribe();
+    let handle = downloader.start_queue_processor();
+    let path = tokio::time::timeout(Duration::from_secs(30), async {
+        loop {
+            match events.recv().await {
+                Ok(Event::Complete { id: done, path }) if done == id => return path,
+                Ok(Event::DownloadFailed {
+                    id: failed, error, ..
+                }) if failed == id => {
+                    panic!("simulated download failed: {}", error)
+                }
+                Ok(_) => {}
+                Err(e) => panic!("event stream ended: {:?}", e),
+            }
+        }
+    })
+    .await
+    .expect("simulated download should complete");
+    handle.abort();
+    path
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn simulated_download_serves_seed_files() {
+    let temp_dir = tempdir().unwrap();
+    let seed_dir = temp_dir.path().join("seed");
+    std::fs::create_dir_all(&seed_dir).unwrap();
+    let video: Vec<u8> = (0..=255).cycle().take(2500).collect();
+    std::fs::write(seed_dir.join("movie.mkv"), &video).unwrap();
+    let downloader = simulate(&temp_dir, Some(seed_dir)).await;
+
+    let id = downloader
+        .add_nzb_content(
+            nzb("movie.mkv", 3, 4000).as_bytes(),
+            "movie",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+    let path = run_to_completion(&downloader, id).await;
+
+    let file = if path.is_dir() {
+        path.join("movie.mkv")
+    } else {
+        path
+    };
+    assert_eq!(std::fs::read(file).unwrap(), video);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn simulated_download_fills_unseeded_files_with_zeros() {
+    let temp_dir = tempdir().unwrap();
+    let downloader = simulate(&temp_dir, None).await;
+
+    let id = downloader
+        .add_nzb_content(
+            nzb("data.bin", 2, 1500).as_bytes(),
+            "data",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+    let path = run_to_completion(&downloader, id).await;
+
+    let file = if path.is_dir() {
+        path.join("data.bin")
+    } else {
+        path
+    };
+    assert_eq!(std::fs::read(file).unwrap(), vec![0u8; 3000]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn simulated_speed_is_shared_by_concurrent_fetches() {
+    use crate::downloader::download_task::{ArticleProvider, SimulatedArticleProvider};
+
+    let temp_dir = tempdir().unwrap();
+    let downloader = simulate(&temp_dir, None).await;
+    let id = downloader
+        .add_nzb_content(
+            nzb("big.bin", 4, 50_000).as_bytes(),
+            "big",
+            DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+    let provider = std::sync::Arc::new(SimulatedArticleProvider::new(
+        std::sync::Arc::clone(&downloader.db),
+        id,
+        &crate::config::SimulateConfig {
+            enabled: true,
+            speed_bps: 100_000,
+            seed_dir: None,
+        },
+    ));
+
+    // About 200 KB: the first second's worth is free, the rest takes another second
+    let start = std::time::Instant::now();
+    let fetches: Vec<_> = (1..=4)
+        .map(|n| {
+            let provider = std::sync::Arc::clone(&provider);
+            tokio::spawn(async move {
+                let message_id = format!("<big.bin.{}@example.com>", n);
+                provider.fetch_articles(&[&message_id], 1).await.unwrap()
+            })
+        })
+        .collect();
+    for fetch in fetches {
+        assert_eq!(fetch.await.unwrap().len(), 1);
+    }
+    assert!(
+        start.elapsed() >= Duration::from_millis(900),
+        "four concurrent fetches took {:?}",
+        start.elapsed()
+    );
+}
@@ -13,6 +13,7 @@ async fn test_speed_limiter_shared_across_downloads() {
             database_path: db_path,
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         servers: vec![],
         download: config::DownloadConfig {
@@ -216,3 +217,131 @@ async fn test_speed_limit_bandwidth_distribution() {
         expected
     );
 }
+
+#[tokio::test]
+async fn test_set_download_speed_limit_applies_under_global_limit() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let id = downloader
+        .add_nzb_content(SAMPLE_NZB.as_bytes(), "limited", DownloadOptions::default())
+        .await
+        .unwrap();
+
+    downloader
+        .set_download_speed_limit(id, Some(2_000_000))
+        .await
+        .unwrap();
+
+    // The task picks up the same limiter when the download starts
+    let limiter = downloader.download_speed_limiter(id);
+    assert_eq!(limiter.get_limit(), Some(2_000_000));
+
+    // Changing the limit again resizes the existing bucket
+    downloader.set_download_speed_limit(id, None).await.unwrap();
+    assert_eq!(limiter.get_limit(), None);
+
+    downloader.release_download_speed_limiter(id);
+    assert_eq!(downloader.download_speed_limiter(id).get_limit(), None);
+}
+
+#[tokio::test]
+async fn test_set_download_speed_limit_unknown_download() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let result = downloader
+        .set_download_speed_limit(DownloadId(9999), Some(1_000_000))
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(crate::error::Error::Download(
+            crate::error::DownloadError::NotFound { id: 9999 }
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn test_set_server_speed_limit() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader
+        .add_server(crate::config::ServerConfig {
+            host: "news.example.com".to_string(),
+            port: 563,
+            tls: true,
+            username: None,
+            password: None,
+            connections: 1,
+            priority: 0,
+            pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
+        })
+        .await
+        .unwrap();
+
+    assert!(downloader.set_server_speed_limit(0, Some(3_000_000)));
+    assert_eq!(
+        downloader.servers.current().speed_limiters[0].get_limit(),
+        Some(3_000_000)
+    );
+
+    assert!(
+        !downloader.set_server_speed_limit(1, Some(3_000_000)),
+        "unknown server index should be rejected"
+    );
+}
+
+#[tokio::test]
+async fn test_set_speed_limit_burst() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    assert_eq!(downloader.get_speed_limit_burst(), None);
+    downloader.set_speed_limit_burst(Some(64 * 1024));
+    assert_eq!(downloader.get_speed_limit_burst(), Some(64 * 1024));
+    downloader.set_speed_limit_burst(None);
+    assert_eq!(downloader.get_speed_limit_burst(), None);
+}
+
+#[tokio::test]
+async fn test_bandwidth_mode_switches_and_restores_speed_limit() {
+    use crate::types::BandwidthMode;
+
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    downloader.set_speed_limit(Some(5_000_000)).await;
+    let mut rx = downloader.subscribe();
+
+    assert_eq!(downloader.get_bandwidth_mode(), BandwidthMode::Normal);
+
+    downloader.set_bandwidth_mode(BandwidthMode::Metered).await;
+    assert_eq!(downloader.get_bandwidth_mode(), BandwidthMode::Metered);
+    assert_eq!(
+        downloader.get_speed_limit(),
+        downloader.config.download.metered.speed_limit_bps
+    );
+    assert!(matches!(
+        rx.recv().await.unwrap(),
+        crate::types::Event::SpeedLimitChanged { .. }
+    ));
+    assert!(matches!(
+        rx.recv().await.unwrap(),
+        crate::types::Event::BandwidthModeChanged {
+            mode: BandwidthMode::Metered
+        }
+    ));
+
+    downloader
+        .set_bandwidth_mode(BandwidthMode::Unlimited)
+        .await;
+    assert_eq!(downloader.get_speed_limit(), None);
+
+    // Back to the limit that applied before normal mode was left
+    downloader.set_bandwidth_mode(BandwidthMode::Normal).await;
+    assert_eq!(downloader.get_speed_limit(), Some(5_000_000));
+
+    // Setting the current mode again changes nothing
+    while rx.try_recv().is_ok() {}
+    downloader.set_bandwidth_mode(BandwidthMode::Normal).await;
+    assert!(rx.try_recv().is_err());
+}
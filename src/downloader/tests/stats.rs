@@ -0,0 +1,95 @@
+use super::*;
+use crate::types::{DownloadId, Event, StatsPeriod};
+
+#[tokio::test]
+async fn rollup_writes_usage_and_emits_event() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let mut events = downloader.subscribe();
+
+    let id = DownloadId(1);
+    downloader
+        .usage
+        .record(id, "news.example.com", Some("tv"), 1_000);
+    downloader
+        .usage
+        .record(id, "news.example.com", Some("tv"), 500);
+    downloader.usage.record(id, "backup.example.com", None, 250);
+    downloader
+        .usage
+        .record(id, "backup.example.com", Some("tv"), 0); // nothing fetched
+
+    downloader.rollup_usage_stats().await.unwrap();
+
+    match events.recv().await.unwrap() {
+        Event::StatsRollup { bytes, .. } => assert_eq!(bytes, 1_750),
+        other => panic!("Expected StatsRollup event, got {:?}", other),
+    }
+
+    let stats = downloader.usage_stats(StatsPeriod::Week).await.unwrap();
+    assert_eq!(stats.period, StatsPeriod::Week);
+    assert_eq!(stats.total_bytes, 1_750);
+    assert_eq!(stats.daily.len(), 7);
+    assert_eq!(stats.daily.first().unwrap().date, stats.start);
+    assert_eq!(stats.daily.last().unwrap().date, stats.end);
+    assert_eq!(stats.daily.last().unwrap().bytes, 1_750);
+
+    assert_eq!(stats.servers[0].server, "news.example.com");
+    assert_eq!(stats.servers[0].bytes, 1_500);
+    assert_eq!(stats.servers[1].server, "backup.example.com");
+    assert_eq!(stats.servers[1].bytes, 250);
+
+    assert_eq!(stats.categories[0].category.as_deref(), Some("tv"));
+    assert_eq!(stats.categories[0].bytes, 1_500);
+    assert_eq!(stats.categories[1].category, None);
+
+    // Counters were drained, so a second rollup adds nothing
+    downloader.rollup_usage_stats().await.unwrap();
+    let stats = downloader.usage_stats(StatsPeriod::Day).await.unwrap();
+    assert_eq!(stats.total_bytes, 1_750);
+    assert_eq!(stats.daily.len(), 1);
+}
+
+#[tokio::test]
+async fn usage_stats_empty_period() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+
+    let stats = downloader.usage_stats(StatsPeriod::Month).await.unwrap();
+
+    assert_eq!(stats.total_bytes, 0);
+    assert!(stats.servers.is_empty());
+    assert!(stats.categories.is_empty());
+    assert_eq!(stats.daily.len(), 30);
+    assert!(stats.daily.iter().all(|day| day.bytes == 0));
+}
+
+#[tokio::test]
+async fn download_traffic_is_recorded_in_history() {
+    let (downloader, _temp_dir) = create_test_downloader().await;
+    let id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "traffic",
+            crate::types::DownloadOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    downloader.usage.record(id, "news.example.com", None, 1_000);
+    downloader.usage.record(id, "backup.example.com", None, 250);
+    downloader
+        .usage
+        .record(DownloadId(id.0 + 1), "news.example.com", None, 99);
+    downloader.record_history(id).await;
+
+    let page = downloader.get_history(None, 10, 0).await.unwrap();
+    let entry = &page.items[0];
+    assert_eq!(entry.fetched_bytes, 1_250);
+    assert_eq!(entry.server_bytes.len(), 2);
+    assert_eq!(entry.server_bytes[0].server, "news.example.com");
+    assert_eq!(entry.server_bytes[0].bytes, 1_000);
+
+    // Only this download's bytes were taken
+    downloader.flush_download_traffic(id).await.unwrap();
+    let other = downloader.usage.take_download(DownloadId(id.0 + 1));
+    assert_eq!(other.get("news.example.com"), Some(&99));
+}
@@ -0,0 +1,408 @@
+use super::*;
+use crate::config::{ServerConfig, StallAction};
+use crate::downloader::watchdog::StallWatch;
+use crate::types::{Event, Priority, Status};
+
+async fn create_stall_downloader(
+    action: StallAction,
+    servers: Vec<ServerConfig>,
+) -> (UsenetDownloader, tempfile::TempDir) {
+    let temp_dir = tempdir().unwrap();
+    let config = Config {
+        persistence: crate::config::PersistenceConfig {
+            database_path: temp_dir.path().join("test.db"),
+            ..Default::default()
+        },
+        download: config::DownloadConfig {
+            download_dir: temp_dir.path().join("downloads"),
+            temp_dir: temp_dir.path().join("temp"),
+            stall: config::StallConfig {
+                enabled: true,
+                action,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        servers,
+        ..Default::default()
+    };
+    (UsenetDownloader::new(config).await.unwrap(), temp_dir)
+}
+
+fn server() -> ServerConfig {
+    ServerConfig {
+        host: "news.example.com".to_string(),
+        port: 563,
+        tls: true,
+        username: None,
+        password: None,
+        connections: 4,
+        priority: 0,
+        pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
+    }
+}
+
+/// Add a download and mark it as downloading, as if its task had started
+async fn add_downloading(downloader: &UsenetDownloader, priority: Priority) -> DownloadId {
+    let id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "stalled",
+            DownloadOptions {
+                priority,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    downloader.remove_from_queue(id).await;
+    downloader
+        .db
+        .update_status(id, Status::Downloading.to_i32())
+        .await
+        .unwrap();
+    id
+}
+
+async fn status(downloader: &UsenetDownloader, id: DownloadId) -> Status {
+    Status::from_i32(
+        downloader
+            .db
+            .get_download(id)
+            .await
+            .unwrap()
+            .unwrap()
+            .status,
+    )
+}
+
+#[tokio::test]
+async fn test_stalled_download_is_retried() {
+    let (downloader, _temp_dir) = create_stall_downloader(StallAction::Retry, vec![server()]).await;
+    let id = add_downloading(&downloader, Priority::Normal).await;
+    let mut events = downloader.subscribe();
+
+    let mut watch = StallWatch::default();
+    let start = Instant::now();
+    downloader.check_stalls(&mut watch, start).await.unwrap();
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(300))
+        .await
+        .unwrap();
+    assert_eq!(status(&downloader, id).await, Status::Downloading);
+
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(600))
+        .await
+        .unwrap();
+    assert_eq!(status(&downloader, id).await, Status::Queued);
+    let info = downloader.get_download(id).await.unwrap().unwrap();
+    assert_eq!(
+        info.stall_reason.as_deref(),
+        Some("no data received for 600s")
+    );
+    assert_eq!(info.priority, Priority::Normal);
+
+    let event = tokio::time::timeout(Duration::from_secs(1), async {
+        loop {
+            if let Ok(Event::Stalled {
+                id: stalled_id,
+                action,
+                attempts,
+                ..
+            }) = events.recv().await
+            {
+                return (stalled_id, action, attempts);
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(event, (id, StallAction::Retry, 1));
+}
+
+#[tokio::test]
+async fn test_stalled_download_requeued_at_lower_priority() {
+    let (downloader, _temp_dir) =
+        create_stall_downloader(StallAction::Requeue, vec![server()]).await;
+    let id = add_downloading(&downloader, Priority::High).await;
+
+    let mut watch = StallWatch::default();
+    let start = Instant::now();
+    downloader.check_stalls(&mut watch, start).await.unwrap();
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(600))
+        .await
+        .unwrap();
+
+    let info = downloader.get_download(id).await.unwrap().unwrap();
+    assert_eq!(info.status, Status::Queued);
+    assert_eq!(info.priority, Priority::Normal);
+}
+
+#[tokio::test]
+async fn test_download_is_not_stalled_while_progressing_or_without_servers() {
+    let (downloader, _temp_dir) = create_stall_downloader(StallAction::Fail, vec![server()]).await;
+    let id = add_downloading(&downloader, Priority::Normal).await;
+
+    let mut watch = StallWatch::default();
+    let start = Instant::now();
+    downloader.check_stalls(&mut watch, start).await.unwrap();
+    downloader
+        .db
+        .update_progress(id, 10.0, 1000, 4096)
+        .await
+        .unwrap();
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(600))
+        .await
+        .unwrap();
+    assert_eq!(status(&downloader, id).await, Status::Downloading);
+
+    // With every server out of rotation, nothing can be received
+    downloader.disable_server(0, None).await.unwrap();
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(1800))
+        .await
+        .unwrap();
+    assert_eq!(status(&downloader, id).await, Status::Downloading);
+    assert!(
+        downloader
+            .get_download(id)
+            .await
+            .unwrap()
+            .unwrap()
+            .stall_reason
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_download_is_not_stalled_while_held_for_forced_download() {
+    let (downloader, _temp_dir) = create_stall_downloader(StallAction::Fail, vec![server()]).await;
+    let id = add_downloading(&downloader, Priority::Normal).await;
+    let limiter = downloader.download_speed_limiter(id);
+
+    let mut watch = StallWatch::default();
+    let start = Instant::now();
+    downloader.check_stalls(&mut watch, start).await.unwrap();
+    limiter.set_held(true);
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(1800))
+        .await
+        .unwrap();
+    assert_eq!(status(&downloader, id).await, Status::Downloading);
+
+    // Released: the timeout counts from the first check after the hold
+    limiter.set_held(false);
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(1830))
+        .await
+        .unwrap();
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(1860))
+        .await
+        .unwrap();
+    assert_eq!(status(&downloader, id).await, Status::Downloading);
+    assert!(
+        downloader
+            .get_download(id)
+            .await
+            .unwrap()
+            .unwrap()
+            .stall_reason
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_download_failed_after_max_attempts() {
+    let (downloader, _temp_dir) = create_stall_downloader(StallAction::Retry, vec![server()]).await;
+    let id = add_downloading(&downloader, Priority::Normal).await;
+
+    let mut watch = StallWatch::default();
+    let mut now = Instant::now();
+    for _ in 0..3 {
+        downloader
+            .db
+            .update_status(id, Status::Downloading.to_i32())
+            .await
+            .unwrap();
+        downloader.remove_from_queue(id).await;
+        downloader.check_stalls(&mut watch, now).await.unwrap();
+        now += Duration::from_secs(600);
+        downloader.check_stalls(&mut watch, now).await.unwrap();
+    }
+
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    assert_eq!(Status::from_i32(download.status), Status::Failed);
+    assert!(
+        download
+            .error_message
+            .is_some_and(|e| e.contains("Download stalled: no data received"))
+    );
+}
+
+#[tokio::test]
+async fn test_stalled_stage_stops_running_post_processing() {
+    let (downloader, _temp_dir) = create_stall_downloader(StallAction::Fail, vec![]).await;
+    let id = add_downloading(&downloader, Priority::Normal).await;
+    downloader
+        .db
+        .update_status(id, Status::Processing.to_i32())
+        .await
+        .unwrap();
+
+    let mut watch = StallWatch::default();
+    let start = Instant::now();
+    watch.observe(&Event::Verifying { id }, start);
+
+    // Not running in a pipeline (e.g. waiting for a slot): left alone
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(7200))
+        .await
+        .unwrap();
+    assert!(downloader.stall_reason(id).is_none());
+
+    let stall_rx = downloader.stall_signal(id);
+    watch.observe(&Event::Verifying { id }, start);
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(3600))
+        .await
+        .unwrap();
+    assert!(downloader.stall_reason(id).is_none());
+
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(7200))
+        .await
+        .unwrap();
+    let stalled = stall_rx.await.unwrap();
+    assert_eq!(stalled.action, StallAction::Fail);
+    assert_eq!(stalled.reason, "Verify stage running for 7200s");
+    assert_eq!(
+        downloader.stall_reason(id).as_deref(),
+        Some("Verify stage running for 7200s")
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_stalled_extraction_is_stopped_before_it_starts_over() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = tempdir().unwrap();
+    // The first run's unrar hangs; it notes whether it is still running when the next starts
+    let tool = temp_dir.path().join("unrar");
+    let dir = temp_dir.path().display();
+    std::fs::write(
+        &tool,
+        format!(
+            "#!/bin/sh\n\
+             if [ -e {dir}/first.pid ]; then\n\
+             \x20 kill -0 \"$(cat {dir}/first.pid)\" 2>/dev/null && echo overlap >> {dir}/tool.log\n\
+             \x20 echo second >> {dir}/tool.log\n\
+             \x20 exit 0\n\
+             fi\n\
+             echo $$ > {dir}/first.pid\n\
+             echo first >> {dir}/tool.log\n\
+             sleep 30\n\
+             echo first-finished >> {dir}/tool.log\n"
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let config = Config {
+        persistence: crate::config::PersistenceConfig {
+            database_path: temp_dir.path().join("test.db"),
+            ..Default::default()
+        },
+        download: config::DownloadConfig {
+            download_dir: temp_dir.path().join("downloads"),
+            temp_dir: temp_dir.path().join("temp"),
+            stall: config::StallConfig {
+                enabled: true,
+                action: StallAction::Retry,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        processing: config::ProcessingConfig {
+            extraction: config::ExtractionConfig {
+                prefer_cli: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        tools: config::ToolsConfig {
+            unrar_path: Some(tool),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let downloader = UsenetDownloader::new(config).await.unwrap();
+    let id = downloader
+        .add_nzb_content(
+            SAMPLE_NZB.as_bytes(),
+            "stalled",
+            DownloadOptions {
+                post_process: Some(crate::config::PostProcess::Unpack),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    downloader.remove_from_queue(id).await;
+    let download = downloader.db.get_download(id).await.unwrap().unwrap();
+    let download_path = download.incomplete_path(&downloader.config.download.temp_dir);
+    std::fs::create_dir_all(&download_path).unwrap();
+    std::fs::write(download_path.join("movie.rar"), b"Rar!\x1a\x07\x01\x00").unwrap();
+    let mut events = downloader.subscribe();
+
+    let first_run = tokio::spawn({
+        let downloader = downloader.clone();
+        async move { downloader.start_post_processing(id).await }
+    });
+    let log = temp_dir.path().join("tool.log");
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !log.exists() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    let mut watch = StallWatch::default();
+    let start = Instant::now();
+    watch.observe(
+        &Event::Extracting {
+            id,
+            archive: String::new(),
+            percent: 0.0,
+        },
+        start,
+    );
+    downloader
+        .check_stalls(&mut watch, start + Duration::from_secs(7200))
+        .await
+        .unwrap();
+    first_run.await.unwrap().unwrap();
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            if let Ok(Event::Complete { id: done, .. }) = events.recv().await
+                && done == id
+            {
+                return;
+            }
+        }
+    })
+    .await
+    .unwrap();
+    // The hung tool was killed before the restarted run ran its own
+    assert_eq!(std::fs::read_to_string(&log).unwrap(), "first\nsecond\n");
+}
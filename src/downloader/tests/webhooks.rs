@@ -11,6 +11,7 @@ async fn test_webhook_auth_header() {
             database_path: db_path.clone(),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         download: config::DownloadConfig {
             download_dir: temp_dir.path().join("downloads"),
@@ -23,6 +24,7 @@ async fn test_webhook_auth_header() {
                 events: vec![crate::config::WebhookEvent::OnQueued],
                 auth_header: Some("Bearer test-token-12345".to_string()),
                 timeout: std::time::Duration::from_secs(10),
+                digest_interval: None,
             }],
             ..Default::default()
         },
@@ -63,3 +65,68 @@ async fn test_webhook_auth_header() {
     println!("  Auth header: Bearer test-token-12345");
     println!("  Note: Check httpbin.org response to verify Authorization header was sent");
 }
+
+#[tokio::test]
+async fn digest_webhook_batches_payloads_into_one_request() {
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/digest"))
+        .and(header("Authorization", "Bearer digest-token"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempdir().unwrap();
+    let mut config = Config::default();
+    config.persistence.database_path = temp_dir.path().join("test.db");
+    config.download.download_dir = temp_dir.path().join("downloads");
+    config.download.temp_dir = temp_dir.path().join("temp");
+    config.notifications.webhooks = vec![crate::config::WebhookConfig {
+        url: format!("{}/digest", mock_server.uri()),
+        events: vec![
+            crate::config::WebhookEvent::OnComplete,
+            crate::config::WebhookEvent::OnFailed,
+        ],
+        auth_header: Some("Bearer digest-token".to_string()),
+        timeout: Duration::from_secs(10),
+        digest_interval: Some(Duration::from_secs(3600)),
+    }];
+    let downloader = UsenetDownloader::new(config).await.unwrap();
+
+    for (id, event_type, status) in [
+        (1, crate::config::WebhookEvent::OnComplete, "complete"),
+        (2, crate::config::WebhookEvent::OnFailed, "failed"),
+        (3, crate::config::WebhookEvent::OnComplete, "complete"),
+    ] {
+        downloader.trigger_webhooks(crate::downloader::TriggerWebhooksParams {
+            event_type,
+            download_id: DownloadId(id),
+            name: format!("Show.S01E0{}", id),
+            category: Some("tv".to_string()),
+            status: status.to_string(),
+            destination: None,
+            error: (status == "failed").then(|| "repair failed".to_string()),
+        });
+    }
+
+    // Nothing is sent per download
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(mock_server.received_requests().await.unwrap().is_empty());
+
+    downloader.send_webhook_digest(0).await;
+    let requests = mock_server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    let digest: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(digest["event"], "digest");
+    assert_eq!(digest["completed"], 2);
+    assert_eq!(digest["failed"], 1);
+    assert_eq!(digest["events"].as_array().unwrap().len(), 3);
+    assert_eq!(digest["events"][1]["error"], "repair failed");
+
+    // A period with nothing to report sends nothing
+    downloader.send_webhook_digest(0).await;
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+}
@@ -0,0 +1,356 @@
+//! Stalled download detection.
+//!
+//! [`UsenetDownloader::start_stall_watchdog`] looks for downloads that have received no
+//! data for `download.stall.no_progress_timeout` although servers are in rotation, and
+//! for post-processing stages that have run longer than `download.stall.stage_timeout`.
+//! Downloads held back while a forced download runs aren't stalled.
+//! Download tasks and post-processing runs register a channel while they run; the
+//! watchdog uses it to stop a stalled one, which then gets the configured
+//! [`StallAction`]. The latest reason is kept for `DownloadInfo::stall_reason`.
+
+use crate::config::StallAction;
+use crate::error::{Result, ToHttpStatus, failure_code};
+use crate::event_bus::RecvError;
+use crate::types::{DownloadId, Event, Priority, Stage, Status};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+use super::UsenetDownloader;
+
+/// How often the watchdog looks for stalled downloads
+const STALL_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A download the watchdog acted on
+pub(crate) struct StallRecord {
+    /// Why it last counted as stalled
+    pub(crate) reason: String,
+    /// How many times it has stalled
+    pub(crate) attempts: u32,
+}
+
+/// Sent to a stalled download task or post-processing run to stop it
+#[derive(Debug)]
+pub(crate) struct Stalled {
+    /// What happens to the download once it's stopped
+    pub(crate) action: StallAction,
+    /// Why it counts as stalled
+    pub(crate) reason: String,
+    /// Sent to, or dropped, once the stopped run has exited
+    pub(crate) exited: oneshot::Sender<()>,
+}
+
+/// What the watchdog has seen of running downloads
+#[derive(Default)]
+pub(crate) struct StallWatch {
+    /// Downloading: bytes received so far, and since when that hasn't changed
+    progress: HashMap<DownloadId, (i64, Instant)>,
+    /// Post-processing: the current stage, and when it started
+    stages: HashMap<DownloadId, (Stage, Instant)>,
+}
+
+impl StallWatch {
+    /// Track post-processing stages from the events marking their start
+    pub(crate) fn observe(&mut self, event: &Event, now: Instant) {
+        let (id, stage) = match event {
+            Event::Verifying { id } => (id, Stage::Verify),
+            Event::Repairing { id, .. } => (id, Stage::Repair),
+            Event::Extracting { id, .. } => (id, Stage::Extract),
+            Event::Moving { id, .. } => (id, Stage::Move),
+            Event::Cleaning { id } => (id, Stage::Cleanup),
+            Event::Complete { id, .. } | Event::Failed { id, .. } | Event::Removed { id } => {
+                self.stages.remove(id);
+                return;
+            }
+            _ => return,
+        };
+        // Extracting is sent for each archive and with progress; the stage started with the first
+        if self
+            .stages
+            .get(id)
+            .is_none_or(|(current, _)| *current != stage)
+        {
+            self.stages.insert(*id, (stage, now));
+        }
+    }
+}
+
+impl UsenetDownloader {
+    /// Start the task that detects stalled downloads (see `download.stall`)
+    ///
+    /// Checks every 30 seconds. A download that has received no data for
+    /// `no_progress_timeout` while at least one server is in rotation and it isn't held
+    /// back for a forced download, or whose current post-processing stage has run for
+    /// `stage_timeout`, is stopped and gets the configured [`StallAction`]: retried,
+    /// requeued one priority lower, or failed. Stalled post-processing is stopped before
+    /// its next stage, killing a running extraction tool, and started over rather than
+    /// requeued once it has exited. A download that stalls `max_attempts` times is failed.
+    /// Each stall emits [`Event::Stalled`].
+    /// Returns a task that exits immediately when `download.stall.enabled` is off.
+    pub fn start_stall_watchdog(&self) -> tokio::task::JoinHandle<()> {
+        if !self.config.download.stall.enabled {
+            return tokio::spawn(async {});
+        }
+        let downloader = self.clone();
+        let mut events = self.subscribe();
+
+        let handle = tokio::spawn(async move {
+            let mut watch = StallWatch::default();
+            let mut interval = tokio::time::interval(STALL_CHECK_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        if let Err(e) = downloader.check_stalls(&mut watch, Instant::now()).await {
+                            tracing::warn!(error = %e, "Failed to check for stalled downloads");
+                        }
+                    }
+                    event = events.recv() => match event {
+                        Ok(event) => {
+                            if let Event::Removed { id } = &event {
+                                downloader.forget_stalls(*id);
+                            }
+                            watch.observe(&event, Instant::now());
+                        }
+                        Err(RecvError::Lagged(_)) => {}
+                        Err(RecvError::Closed) => break,
+                    },
+                }
+            }
+        });
+
+        tracing::info!("Stall watchdog started");
+
+        handle
+    }
+
+    /// Act on the downloads that have stalled as of `now`
+    ///
+    /// Runs periodically once [`start_stall_watchdog`] is called. `watch` carries what
+    /// was seen across checks.
+    ///
+    /// [`start_stall_watchdog`]: UsenetDownloader::start_stall_watchdog
+    pub(crate) async fn check_stalls(&self, watch: &mut StallWatch, now: Instant) -> Result<()> {
+        let stall = &self.config.download.stall;
+
+        // With no server to download from, waiting isn't stalling
+        let servers_available = self.server_statuses().iter().any(|server| server.enabled);
+        // Held back while a forced download runs; progress is timed afresh once released
+        let held: HashSet<DownloadId> = self
+            .runtime_config
+            .download_speed_limiters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(_, limiter)| limiter.is_held())
+            .map(|(id, _)| *id)
+            .collect();
+        let downloading = self
+            .db
+            .list_downloads_by_status(Status::Downloading.to_i32())
+            .await?;
+        let downloading_ids: HashSet<DownloadId> =
+            downloading.iter().map(|d| DownloadId(d.id)).collect();
+        watch.progress.retain(|id, _| downloading_ids.contains(id));
+        for download in downloading {
+            let id = DownloadId(download.id);
+            if held.contains(&id) {
+                watch.progress.remove(&id);
+                continue;
+            }
+            let (bytes, since) = watch
+                .progress
+                .entry(id)
+                .or_insert((download.downloaded_bytes, now));
+            if *bytes != download.downloaded_bytes || !servers_available {
+                (*bytes, *since) = (download.downloaded_bytes, now);
+                continue;
+            }
+            let idle = now.saturating_duration_since(*since);
+            if idle >= stall.no_progress_timeout {
+                watch.progress.remove(&id);
+                let reason = format!("no data received for {}s", idle.as_secs());
+                self.handle_stall(id, reason, false).await;
+            }
+        }
+
+        // Stage events can be missed when the subscriber lags, so trust the status
+        let processing: HashSet<DownloadId> = self
+            .db
+            .list_downloads_by_status(Status::Processing.to_i32())
+            .await?
+            .into_iter()
+            .map(|d| DownloadId(d.id))
+            .collect();
+        watch.stages.retain(|id, _| processing.contains(id));
+        let stalled: Vec<(DownloadId, Stage, Duration)> = watch
+            .stages
+            .iter()
+            .map(|(id, (stage, since))| (*id, *stage, now.saturating_duration_since(*since)))
+            .filter(|(_, _, running)| *running >= stall.stage_timeout)
+            .collect();
+        for (id, stage, running) in stalled {
+            watch.stages.remove(&id);
+            let reason = format!("{:?} stage running for {}s", stage, running.as_secs());
+            self.handle_stall(id, reason, true).await;
+        }
+
+        Ok(())
+    }
+
+    /// Record a stall, decide what to do about it, and stop the download
+    async fn handle_stall(&self, id: DownloadId, reason: String, post_processing: bool) {
+        let signal = self
+            .queue_state
+            .stall_signals
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id);
+        // Only a running pipeline can be stopped; one waiting for a slot isn't stalled
+        if post_processing && signal.is_none() {
+            return;
+        }
+
+        let stall = &self.config.download.stall;
+        let attempts = {
+            let mut stalls = self
+                .queue_state
+                .stalls
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let record = stalls.entry(id).or_insert(StallRecord {
+                reason: String::new(),
+                attempts: 0,
+            });
+            record.reason = reason.clone();
+            record.attempts += 1;
+            record.attempts
+        };
+        let action = if attempts >= stall.max_attempts {
+            StallAction::Fail
+        } else {
+            stall.action
+        };
+
+        tracing::warn!(download_id = id.0, %reason, ?action, attempts, "Download stalled");
+        self.emit_event(Event::Stalled {
+            id,
+            reason: reason.clone(),
+            action,
+            attempts,
+        });
+
+        let (exited_tx, exited_rx) = oneshot::channel();
+        let stalled = Stalled {
+            action,
+            reason,
+            exited: exited_tx,
+        };
+        match signal {
+            // A failed run fails the download itself; otherwise it's started over
+            Some(signal) if post_processing => {
+                if signal.send(stalled).is_ok() && action != StallAction::Fail {
+                    // Starting over while the old run still touches the temp folder
+                    // would race it; it stops at its next stage, so don't hold up the
+                    // watchdog meanwhile
+                    let downloader = self.clone();
+                    tokio::spawn(async move {
+                        let _ = exited_rx.await;
+                        if let Err(e) = downloader.reprocess(id).await {
+                            downloader
+                                .fail_added_download(
+                                    id,
+                                    e.error_code(),
+                                    format!("Failed to restart stalled post-processing: {}", e),
+                                )
+                                .await;
+                        }
+                    });
+                }
+            }
+            // The task acts on it once it has stopped
+            Some(signal) => {
+                let _ = signal.send(stalled);
+            }
+            // Marked as downloading, but no task is running it
+            None => self.act_on_stalled_download(id, stalled).await,
+        }
+    }
+
+    /// Retry, requeue or fail a download whose task was stopped for stalling
+    pub(crate) async fn act_on_stalled_download(&self, id: DownloadId, stalled: Stalled) {
+        let result = async {
+            if stalled.action == StallAction::Fail {
+                self.fail_added_download(
+                    id,
+                    failure_code::STALLED,
+                    format!("Download stalled: {}", stalled.reason),
+                )
+                .await;
+                return Ok(());
+            }
+            if stalled.action == StallAction::Requeue
+                && let Some(download) = self.db.get_download(id).await?
+            {
+                let lower = match Priority::from_i32(download.priority) {
+                    Priority::Force => Priority::High,
+                    Priority::High => Priority::Normal,
+                    Priority::Normal | Priority::Low => Priority::Low,
+                };
+                self.db.update_priority(id, lower as i32).await?;
+            }
+            self.db.update_status(id, Status::Queued.to_i32()).await?;
+            self.add_to_queue(id).await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(download_id = id.0, error = %e, "Failed to act on stalled download");
+        }
+    }
+
+    /// Register the running download task or post-processing run for `id`
+    ///
+    /// The receiver gets a [`Stalled`] if the watchdog finds it stalled.
+    pub(crate) fn stall_signal(&self, id: DownloadId) -> oneshot::Receiver<Stalled> {
+        let (tx, rx) = oneshot::channel();
+        self.queue_state
+            .stall_signals
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, tx);
+        rx
+    }
+
+    /// Unregister the finished run for `id`, once its receiver is dropped
+    ///
+    /// Leaves a later run's registration alone, e.g. post-processing that a download
+    /// task started before it finished.
+    pub(crate) fn clear_stall_signal(&self, id: DownloadId) {
+        let mut signals = self
+            .queue_state
+            .stall_signals
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if signals.get(&id).is_some_and(|tx| tx.is_closed()) {
+            signals.remove(&id);
+        }
+    }
+
+    /// Why the stall watchdog last acted on `id`, if it has
+    pub(crate) fn stall_reason(&self, id: DownloadId) -> Option<String> {
+        self.queue_state
+            .stalls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&id)
+            .map(|record| record.reason.clone())
+    }
+
+    fn forget_stalls(&self, id: DownloadId) {
+        self.queue_state
+            .stalls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id);
+    }
+}
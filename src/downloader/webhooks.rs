@@ -49,7 +49,8 @@ impl UsenetDownloader {
     ///
     /// This method sends HTTP POST requests to all configured webhooks that are
     /// subscribed to the given event type. Webhooks are executed asynchronously
-    /// (fire and forget) to avoid blocking the download pipeline.
+    /// (fire and forget) to avoid blocking the download pipeline. Webhooks with a
+    /// `digest_interval` get the payload with their next digest instead.
     pub(crate) fn trigger_webhooks(&self, params: TriggerWebhooksParams) {
         let TriggerWebhooksParams {
             event_type,
@@ -66,8 +67,8 @@ impl UsenetDownloader {
             .notifications
             .webhooks
             .iter()
-            .filter(|w| w.events.contains(&event_type))
-            .cloned()
+            .enumerate()
+            .filter(|(_, w)| w.events.contains(&event_type))
             .collect();
 
         // Early return if no webhooks are subscribed
@@ -75,94 +76,76 @@ impl UsenetDownloader {
             return;
         }
 
+        // Pre-compute event string once (not per webhook)
+        let event_str: &'static str = match event_type {
+            crate::config::WebhookEvent::OnComplete => "complete",
+            crate::config::WebhookEvent::OnFailed => "failed",
+            crate::config::WebhookEvent::OnQueued => "queued",
+        };
+
+        // Build shared payload once - use Arc to share across webhooks
+        let payload = Arc::new(crate::types::WebhookPayload {
+            event: event_str.to_string(),
+            download_id,
+            name,
+            category,
+            status,
+            destination,
+            error,
+            timestamp: chrono::Utc::now().timestamp(),
+        });
+
+        // Digest-mode webhooks get the payload with their next digest
+        let mut immediate_webhooks = Vec::new();
+        for (index, webhook) in matching_webhooks {
+            if webhook.digest_interval.is_some() {
+                self.digests.push(index, payload.as_ref().clone());
+            } else {
+                immediate_webhooks.push(webhook.clone());
+            }
+        }
+
+        if immediate_webhooks.is_empty() {
+            return;
+        }
+
         let event_tx = self.event_tx.clone();
 
         // Spawn async task to send webhooks (fire and forget)
         tokio::spawn(async move {
-            let timestamp = chrono::Utc::now().timestamp();
-
-            // Pre-compute event string once (not per webhook)
-            let event_str: &'static str = match event_type {
-                crate::config::WebhookEvent::OnComplete => "complete",
-                crate::config::WebhookEvent::OnFailed => "failed",
-                crate::config::WebhookEvent::OnQueued => "queued",
-            };
-
-            // Build shared payload once - use Arc to share across webhooks
-            let payload = Arc::new(crate::types::WebhookPayload {
-                event: event_str.to_string(),
-                download_id,
-                name,
-                category,
-                status,
-                destination,
-                error,
-                timestamp,
-            });
-
-            for webhook in matching_webhooks {
-                // Build HTTP client for this webhook
-                let client = reqwest::Client::new();
-                let mut request = client
-                    .post(&webhook.url)
-                    .json(payload.as_ref())
-                    .timeout(webhook.timeout);
-
-                // Add authentication header if configured
-                if let Some(auth) = &webhook.auth_header {
-                    request = request.header("Authorization", auth);
-                }
-
-                // url is moved into the async block, only cloned for error reporting if needed
-                let url = webhook.url;
-                let timeout = webhook.timeout;
-                let result = tokio::time::timeout(timeout, request.send()).await;
-
-                // Handle webhook response
-                match result {
-                    Ok(Ok(response)) => {
-                        if !response.status().is_success() {
-                            let error_msg = format!(
-                                "Webhook returned status {}: {}",
-                                response.status(),
-                                response.text().await.unwrap_or_default()
-                            );
-                            tracing::warn!(url = %url, error = %error_msg, "webhook failed");
-                            event_tx
-                                .send(Event::WebhookFailed {
-                                    url,
-                                    error: error_msg,
-                                })
-                                .ok();
-                        } else {
-                            tracing::debug!(url = %url, "webhook sent successfully");
-                        }
-                    }
-                    Ok(Err(e)) => {
-                        let error_msg = format!("Failed to send webhook: {}", e);
-                        tracing::warn!(url = %url, error = %error_msg, "webhook failed");
-                        event_tx
-                            .send(Event::WebhookFailed {
-                                url,
-                                error: error_msg,
-                            })
-                            .ok();
-                    }
-                    Err(_) => {
-                        let error_msg = format!("Webhook timed out after {:?}", timeout);
-                        tracing::warn!(url = %url, error = %error_msg, "webhook timeout");
-                        event_tx
-                            .send(Event::WebhookFailed {
-                                url,
-                                error: error_msg,
-                            })
-                            .ok();
-                    }
-                }
+            for webhook in immediate_webhooks {
+                send_webhook(&webhook, payload.as_ref(), &event_tx).await;
             }
         });
     }
 
+    /// Send the digest of the webhook at `index` in `webhooks`, if anything happened
+    /// since its last one
+    pub(crate) async fn send_webhook_digest(&self, index: usize) {
+        let Some(webhook) = self.config.notifications.webhooks.get(index) else {
+            return;
+        };
+        let Some(digest) = self.digests.take(index, chrono::Utc::now().timestamp()) else {
+            return;
+        };
+        tracing::debug!(
+            url = %webhook.url,
+            completed = digest.completed,
+            failed = digest.failed,
+            "sending webhook digest"
+        );
+        send_webhook(webhook, &digest, &self.event_tx).await;
+    }
+
+    /// Send the digests of all digest-mode webhooks, e.g. before shutting down
+    pub(crate) async fn send_webhook_digests(&self) {
+        for (index, webhook) in self.config.notifications.webhooks.iter().enumerate() {
+            if webhook.digest_interval.is_some() {
+                self.send_webhook_digest(index).await;
+            }
+        }
+    }
+
     /// Trigger scripts for download events
     ///
     /// This method executes all configured scripts (both global and category-specific)
@@ -173,7 +156,12 @@ impl UsenetDownloader {
     ///
     /// 1. Category-specific scripts (if download has a category)
     /// 2. Global scripts
-    pub(crate) fn trigger_scripts(&self, params: TriggerScriptsParams) {
+    ///
+    /// Returns a handle per started script resolving to whether it succeeded.
+    pub(crate) fn trigger_scripts(
+        &self,
+        params: TriggerScriptsParams,
+    ) -> Vec<tokio::task::JoinHandle<bool>> {
         let TriggerScriptsParams {
             event_type,
             download_id,
@@ -208,6 +196,8 @@ impl UsenetDownloader {
             env_vars.insert("USENET_DL_ERROR".to_string(), err.clone());
         }
 
+        let mut handles = Vec::new();
+
         // Category scripts first
         if let Some(cat_name) = &category
             && let Some(cat_config) = self.config.persistence.categories.get(cat_name)
@@ -232,7 +222,11 @@ impl UsenetDownloader {
                 );
 
                 for script in matching_scripts {
-                    self.run_script_async(&script.path, script.timeout, &cat_env_vars);
+                    handles.push(self.run_script_async(
+                        &script.path,
+                        script.timeout,
+                        &cat_env_vars,
+                    ));
                 }
             }
         }
@@ -247,21 +241,23 @@ impl UsenetDownloader {
             .collect();
 
         for script in matching_global {
-            self.run_script_async(&script.path, script.timeout, &env_vars);
+            handles.push(self.run_script_async(&script.path, script.timeout, &env_vars));
         }
+        handles
     }
 
     /// Execute a script asynchronously (fire and forget)
     ///
     /// This method spawns a tokio task to execute the script with the given
     /// environment variables and timeout. It emits a ScriptFailed event if the
-    /// script fails or times out.
-    fn run_script_async(
+    /// script fails or times out. The returned handle resolves to whether the script
+    /// succeeded.
+    pub(crate) fn run_script_async(
         &self,
         script_path: &std::path::Path,
         timeout: std::time::Duration,
         env_vars: &std::collections::HashMap<String, String>,
-    ) {
+    ) -> tokio::task::JoinHandle<bool> {
         let script_path = script_path.to_path_buf();
         let event_tx = self.event_tx.clone();
         let env_vars = env_vars.clone();
@@ -292,8 +288,10 @@ impl UsenetDownloader {
                                 exit_code,
                             })
                             .ok();
+                        false
                     } else {
                         tracing::debug!(script = ?script_path, "script executed successfully");
+                        true
                     }
                 }
                 Ok(Err(e)) => {
@@ -304,6 +302,7 @@ impl UsenetDownloader {
                             exit_code: None,
                         })
                         .ok();
+                    false
                 }
                 Err(_) => {
                     tracing::warn!(script = ?script_path, timeout = ?timeout, "script timed out");
@@ -313,8 +312,55 @@ impl UsenetDownloader {
                             exit_code: None,
                         })
                         .ok();
+                    false
                 }
             }
-        });
+        })
+    }
+}
+
+/// POST `body` to `webhook`, emitting [`Event::WebhookFailed`] if it doesn't succeed
+async fn send_webhook(
+    webhook: &crate::config::WebhookConfig,
+    body: &impl serde::Serialize,
+    event_tx: &crate::event_bus::EventSender,
+) {
+    // Build HTTP client for this webhook
+    let client = reqwest::Client::new();
+    let mut request = client
+        .post(&webhook.url)
+        .json(body)
+        .timeout(webhook.timeout);
+
+    // Add authentication header if configured
+    if let Some(auth) = &webhook.auth_header {
+        request = request.header("Authorization", auth);
     }
+
+    let url = &webhook.url;
+    let result = tokio::time::timeout(webhook.timeout, request.send()).await;
+
+    // Handle webhook response
+    let error_msg = match result {
+        Ok(Ok(response)) => {
+            if response.status().is_success() {
+                tracing::debug!(url = %url, "webhook sent successfully");
+                return;
+            }
+            format!(
+                "Webhook returned status {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            )
+        }
+        Ok(Err(e)) => format!("Failed to send webhook: {}", e),
+        Err(_) => format!("Webhook timed out after {:?}", webhook.timeout),
+    };
+    tracing::warn!(url = %url, error = %error_msg, "webhook failed");
+    event_tx
+        .send(Event::WebhookFailed {
+            url: url.clone(),
+            error: error_msg,
+        })
+        .ok();
 }
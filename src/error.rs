@@ -98,6 +98,18 @@ pub enum Error {
     #[error("failed to check disk space: {0}")]
     DiskSpaceCheckFailed(String),
 
+    /// Backup or queue export archive is missing entries, malformed or from an incompatible version
+    #[error("invalid backup: {0}")]
+    InvalidBackup(String),
+
+    /// Operation refused because downloads or post-processing are still running
+    #[error("busy: {0}")]
+    Busy(String),
+
+    /// Operation refused because the downloader runs in read-only mode
+    #[error("read-only mode: the queue can't be changed on this instance")]
+    ReadOnly,
+
     /// External tool execution failed (par2, unrar, etc.)
     #[error("external tool error: {0}")]
     ExternalTool(String),
@@ -111,6 +123,107 @@ pub enum Error {
     Other(String),
 }
 
+impl Error {
+    /// Values that go into this error's message, by name
+    ///
+    /// With the message key `error.<code>` (see [`ToHttpStatus::error_code`]), these let
+    /// embedders render the message in another language; see [`crate::messages`].
+    /// Free-form text, such as the cause reported by a tool, is passed as `reason`.
+    pub fn message_params(&self) -> serde_json::Map<String, serde_json::Value> {
+        use serde_json::json;
+
+        let params = match self {
+            Error::Config { message, key } => json!({ "reason": message, "key": key }),
+            Error::Database(
+                DatabaseError::ConnectionFailed(reason)
+                | DatabaseError::MigrationFailed(reason)
+                | DatabaseError::QueryFailed(reason)
+                | DatabaseError::NotFound(reason)
+                | DatabaseError::ConstraintViolation(reason),
+            ) => json!({ "reason": reason }),
+            Error::Sqlx(e) => json!({ "reason": e.to_string() }),
+            Error::Io(e) => json!({ "reason": e.to_string() }),
+            Error::Network(e) => json!({ "reason": e.to_string() }),
+            Error::Serialization(e) => json!({ "reason": e.to_string() }),
+            Error::Nntp(reason)
+            | Error::InvalidNzb(reason)
+            | Error::NotFound(reason)
+            | Error::ApiServerError(reason)
+            | Error::FolderWatch(reason)
+            | Error::Duplicate(reason)
+            | Error::DiskSpaceCheckFailed(reason)
+            | Error::InvalidBackup(reason)
+            | Error::Busy(reason)
+            | Error::ExternalTool(reason)
+            | Error::NotSupported(reason)
+            | Error::Other(reason) => json!({ "reason": reason }),
+            Error::ShuttingDown | Error::ReadOnly => json!({}),
+            Error::InsufficientSpace {
+                required,
+                available,
+            }
+            | Error::Download(DownloadError::InsufficientSpace {
+                required,
+                available,
+            }) => json!({ "required": required, "available": available }),
+            Error::Download(DownloadError::NotFound { id }) => json!({ "id": id }),
+            Error::Download(DownloadError::FilesNotFound { id, path }) => {
+                json!({ "id": id, "path": path })
+            }
+            Error::Download(DownloadError::AlreadyInState { id, state }) => {
+                json!({ "id": id, "state": state })
+            }
+            Error::Download(DownloadError::InvalidState {
+                id,
+                operation,
+                current_state,
+            }) => json!({ "id": id, "operation": operation, "current_state": current_state }),
+            Error::PostProcess(e) => match e {
+                PostProcessError::VerificationFailed { id, reason }
+                | PostProcessError::RepairFailed { id, reason }
+                | PostProcessError::CleanupFailed { id, reason }
+                | PostProcessError::ScanFailed { id, reason }
+                | PostProcessError::Infected { id, reason }
+                | PostProcessError::Stalled { id, reason }
+                | PostProcessError::DirectUnpackFailed { id, reason }
+                | PostProcessError::DirectRenameFailed { id, reason } => {
+                    json!({ "id": id, "reason": reason })
+                }
+                PostProcessError::ExtractionFailed { archive, reason } => {
+                    json!({ "archive": archive, "reason": reason })
+                }
+                PostProcessError::WrongPassword { archive }
+                | PostProcessError::NoPasswordsAvailable { archive } => {
+                    json!({ "archive": archive })
+                }
+                PostProcessError::AllPasswordsFailed { archive, count } => {
+                    json!({ "archive": archive, "count": count })
+                }
+                PostProcessError::MoveFailed {
+                    source_path,
+                    dest_path,
+                    reason,
+                } => {
+                    json!({ "source_path": source_path, "dest_path": dest_path, "reason": reason })
+                }
+                PostProcessError::ChecksumMismatch {
+                    source_path,
+                    dest_path,
+                } => json!({ "source_path": source_path, "dest_path": dest_path }),
+                PostProcessError::FileCollision { path, reason }
+                | PostProcessError::InvalidPath { path, reason } => {
+                    json!({ "path": path, "reason": reason })
+                }
+            },
+        };
+
+        match params {
+            serde_json::Value::Object(params) => params,
+            _ => serde_json::Map::new(),
+        }
+    }
+}
+
 /// Database-related errors
 #[derive(Debug, Error)]
 pub enum DatabaseError {
@@ -248,6 +361,15 @@ pub enum PostProcessError {
         reason: String,
     },
 
+    /// A copied file didn't match its source, e.g. after corruption on network storage
+    #[error("checksum mismatch copying {source_path} to {dest_path}")]
+    ChecksumMismatch {
+        /// The file being copied
+        source_path: PathBuf,
+        /// Where it was being copied to
+        dest_path: PathBuf,
+    },
+
     /// File collision at destination
     #[error("file collision at {path}: {reason}")]
     FileCollision {
@@ -275,6 +397,33 @@ pub enum PostProcessError {
         reason: String,
     },
 
+    /// The virus scanner couldn't be run, exited with an error, or timed out
+    #[error("virus scan failed for download {id}: {reason}")]
+    ScanFailed {
+        /// The download ID for which the scan failed
+        id: i64,
+        /// The reason the scan failed
+        reason: String,
+    },
+
+    /// The virus scanner found infected files
+    #[error("virus scan found infected files in download {id}: {reason}")]
+    Infected {
+        /// The download ID containing infected files
+        id: i64,
+        /// What the scanner reported, and where the files were quarantined
+        reason: String,
+    },
+
+    /// A post-processing stage ran past `download.stall.stage_timeout`
+    #[error("post-processing stalled for download {id}: {reason}")]
+    Stalled {
+        /// The download ID whose post-processing stalled
+        id: i64,
+        /// Which stage stalled and for how long
+        reason: String,
+    },
+
     /// DirectUnpack failed during download
     #[error("DirectUnpack failed for download {id}: {reason}")]
     DirectUnpackFailed {
@@ -337,6 +486,88 @@ pub struct ErrorDetail {
     /// This can include fields like download_id, file paths, validation errors, etc.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<serde_json::Value>,
+
+    /// What can be done about the error, for the codes there's a known remedy for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+
+    /// Values that go into the message, for rendering it in another language
+    /// (see [`crate::messages`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+impl ErrorDetail {
+    /// Describe why a download failed, from its stored failure code and error message
+    ///
+    /// Downloads that failed before failure codes were recorded get `download_failed`.
+    pub fn download_failure(code: Option<&str>, message: impl Into<String>) -> Self {
+        let code = code.unwrap_or(failure_code::DOWNLOAD_FAILED);
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+            details: None,
+            hint: remediation_hint(code).map(str::to_string),
+            params: None,
+        }
+    }
+}
+
+/// Failure codes for downloads that fail without an [`Error`]
+///
+/// Downloads failed by an [`Error`] store its [`ToHttpStatus::error_code`] instead.
+pub mod failure_code {
+    /// Too many articles were missing from every server
+    pub const MISSING_ARTICLES: &str = "missing_articles";
+    /// More data is missing than the PAR2 recovery data can repair
+    pub const UNREPAIRABLE: &str = "unrepairable";
+    /// The NZB of a deferred download couldn't be fetched
+    pub const NZB_FETCH_FAILED: &str = "nzb_fetch_failed";
+    /// The NZB contained files with a rejected extension
+    pub const REJECTED_FILES: &str = "rejected_files";
+    /// The download stopped making progress (see `download.stall`)
+    pub const STALLED: &str = "stalled";
+    /// A download that failed before failure codes were recorded
+    pub const DOWNLOAD_FAILED: &str = "download_failed";
+}
+
+/// What can be done about a failure, by error or failure code
+pub fn remediation_hint(code: &str) -> Option<&'static str> {
+    let hint = match code {
+        failure_code::MISSING_ARTICLES => {
+            "Articles have expired or were taken down; add a server with longer retention or a different backbone, or find another NZB"
+        }
+        failure_code::UNREPAIRABLE => {
+            "Not enough PAR2 recovery data is available; find another NZB or add a server with better completion"
+        }
+        "verification_failed" | "repair_failed" => {
+            "The PAR2 repair couldn't restore the files; retry the download or find another NZB"
+        }
+        "wrong_password" | "all_passwords_failed" | "no_passwords_available" => {
+            "Set the archive password on the download, or add it to the password file, then re-extract"
+        }
+        "extraction_failed" => "Check the archive with an external tool, or retry the download",
+        "disk_full" | "insufficient_space" => {
+            "Free up space on the download or temp disk, then retry the download"
+        }
+        "move_failed" | "checksum_mismatch" => {
+            "Check that the destination is writable and reachable, then retry"
+        }
+        "scan_failed" => "Check that the virus scanner is installed and runs within scan.timeout",
+        "infected" => "The quarantined files should not be opened; delete the download",
+        failure_code::STALLED => {
+            "Check the server connections and disk, or raise the download.stall timeouts"
+        }
+        failure_code::NZB_FETCH_FAILED => {
+            "The NZB link may have expired; grab the release again from the indexer"
+        }
+        "invalid_nzb" => "The NZB is malformed; grab it again or try another one",
+        failure_code::REJECTED_FILES => {
+            "The release contains files with a rejected extension; see download.reject_extensions"
+        }
+        _ => return None,
+    };
+    Some(hint)
 }
 
 impl ApiError {
@@ -347,6 +578,8 @@ impl ApiError {
                 code: code.into(),
                 message: message.into(),
                 details: None,
+                hint: None,
+                params: None,
             },
         }
     }
@@ -362,6 +595,8 @@ impl ApiError {
                 code: code.into(),
                 message: message.into(),
                 details: Some(details),
+                hint: None,
+                params: None,
             },
         }
     }
@@ -406,6 +641,11 @@ pub trait ToHttpStatus {
 
     /// Get the machine-readable error code
     fn error_code(&self) -> &str;
+
+    /// What can be done about the error, if there's a known remedy
+    fn hint(&self) -> Option<&'static str> {
+        remediation_hint(self.error_code())
+    }
 }
 
 impl ToHttpStatus for Error {
@@ -415,6 +655,10 @@ impl ToHttpStatus for Error {
             Error::Config { .. } => 400,
             Error::InvalidNzb(_) => 422, // Unprocessable Entity
             Error::Duplicate(_) => 409,  // Conflict
+            Error::Busy(_) => 409,
+
+            // 403 Forbidden - Instance doesn't accept changes
+            Error::ReadOnly => 403,
 
             // 404 Not Found
             Error::NotFound(_) => 404,
@@ -429,6 +673,7 @@ impl ToHttpStatus for Error {
             Error::PostProcess(_) => 422,
             Error::Download(DownloadError::InsufficientSpace { .. }) => 422,
             Error::InsufficientSpace { .. } => 422,
+            Error::InvalidBackup(_) => 422,
 
             // 500 Internal Server Error - Server-side issues
             Error::Database(_) => 500,
@@ -476,13 +721,18 @@ impl ToHttpStatus for Error {
                 PostProcessError::AllPasswordsFailed { .. } => "all_passwords_failed",
                 PostProcessError::NoPasswordsAvailable { .. } => "no_passwords_available",
                 PostProcessError::MoveFailed { .. } => "move_failed",
+                PostProcessError::ChecksumMismatch { .. } => "checksum_mismatch",
                 PostProcessError::FileCollision { .. } => "file_collision",
                 PostProcessError::CleanupFailed { .. } => "cleanup_failed",
                 PostProcessError::InvalidPath { .. } => "invalid_path",
+                PostProcessError::ScanFailed { .. } => "scan_failed",
+                PostProcessError::Infected { .. } => "infected",
+                PostProcessError::Stalled { .. } => "stalled",
                 PostProcessError::DirectUnpackFailed { .. } => "direct_unpack_failed",
                 PostProcessError::DirectRenameFailed { .. } => "direct_rename_failed",
             },
             Error::InvalidNzb(_) => "invalid_nzb",
+            Error::Io(e) if e.kind() == std::io::ErrorKind::StorageFull => "disk_full",
             Error::Io(_) => "io_error",
             Error::NotFound(_) => "not_found",
             Error::ShuttingDown => "shutting_down",
@@ -493,6 +743,9 @@ impl ToHttpStatus for Error {
             Error::Duplicate(_) => "duplicate",
             Error::InsufficientSpace { .. } => "insufficient_space",
             Error::DiskSpaceCheckFailed(_) => "disk_space_check_failed",
+            Error::InvalidBackup(_) => "invalid_backup",
+            Error::Busy(_) => "busy",
+            Error::ReadOnly => "read_only",
             Error::ExternalTool(_) => "external_tool_error",
             Error::NotSupported(_) => "not_supported",
             Error::Other(_) => "internal_error",
@@ -504,9 +757,14 @@ impl From<Error> for ApiError {
     fn from(error: Error) -> Self {
         let code = error.error_code().to_string();
         let message = error.to_string();
+        let hint = error.hint().map(str::to_string);
+        let params = Some(error.message_params());
 
         // Add contextual details for specific error types
         let details = match &error {
+            Error::Config { key: Some(key), .. } => Some(serde_json::json!({
+                "path": key,
+            })),
             Error::Download(DownloadError::NotFound { id }) => Some(serde_json::json!({
                 "download_id": id,
             })),
@@ -567,6 +825,8 @@ impl From<Error> for ApiError {
                 code,
                 message,
                 details,
+                hint,
+                params,
             },
         }
     }
@@ -606,6 +866,14 @@ mod tests {
                 500,
                 "database_error",
             ),
+            (
+                Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::StorageFull,
+                    "no space left on device",
+                )),
+                500,
+                "disk_full",
+            ),
             (
                 Error::Io(std::io::Error::new(std::io::ErrorKind::NotFound, "gone")),
                 500,
@@ -626,6 +894,13 @@ mod tests {
                 500,
                 "disk_space_check_failed",
             ),
+            (
+                Error::InvalidBackup("missing manifest.json".into()),
+                422,
+                "invalid_backup",
+            ),
+            (Error::Busy("downloads active".into()), 409, "busy"),
+            (Error::ReadOnly, 403, "read_only"),
             (Error::Other("unknown".into()), 500, "internal_error"),
             (Error::Nntp("connection reset".into()), 502, "nntp_error"),
             (Error::ShuttingDown, 503, "shutting_down"),
@@ -766,6 +1041,30 @@ mod tests {
                 422,
                 "invalid_path",
             ),
+            (
+                Error::PostProcess(PostProcessError::ScanFailed {
+                    id: 1,
+                    reason: "scanner timed out".into(),
+                }),
+                422,
+                "scan_failed",
+            ),
+            (
+                Error::PostProcess(PostProcessError::Infected {
+                    id: 1,
+                    reason: "Eicar-Signature FOUND".into(),
+                }),
+                422,
+                "infected",
+            ),
+            (
+                Error::PostProcess(PostProcessError::Stalled {
+                    id: 1,
+                    reason: "Extract stage ran for 7200s".into(),
+                }),
+                422,
+                "stalled",
+            ),
             (
                 Error::PostProcess(PostProcessError::DirectUnpackFailed {
                     id: 1,
@@ -1057,17 +1356,24 @@ mod tests {
     }
 
     #[test]
-    fn api_error_from_config_has_no_details() {
+    fn api_error_from_config_includes_field_path() {
         let err = Error::Config {
             message: "invalid port".into(),
-            key: Some("server.port".into()),
+            key: Some("servers[0].port".into()),
         };
         let api: ApiError = err.into();
 
         assert_eq!(api.error.code, "config_error");
+        assert_eq!(api.error.details.unwrap()["path"], "servers[0].port");
+
+        let err = Error::Config {
+            message: "config patch must be a JSON object".into(),
+            key: None,
+        };
+        let api: ApiError = err.into();
         assert!(
             api.error.details.is_none(),
-            "Config errors should not have structured details"
+            "Config errors without a key should not have structured details"
         );
     }
 
@@ -1282,6 +1588,33 @@ mod tests {
         assert_eq!(deserialized.error.details, original.error.details);
     }
 
+    #[test]
+    fn api_error_carries_remediation_hint_for_known_codes() {
+        let api: ApiError = Error::PostProcess(PostProcessError::AllPasswordsFailed {
+            archive: PathBuf::from("/tmp/a.rar"),
+            count: 3,
+        })
+        .into();
+        assert!(api.error.hint.is_some_and(|hint| hint.contains("password")));
+
+        let api: ApiError = Error::NotFound("download 1".into()).into();
+        assert!(api.error.hint.is_none());
+        let json = serde_json::to_value(&api).unwrap();
+        assert!(json["error"].get("hint").is_none());
+    }
+
+    #[test]
+    fn download_failure_defaults_code_for_older_failures() {
+        let detail = ErrorDetail::download_failure(Some(failure_code::MISSING_ARTICLES), "gone");
+        assert_eq!(detail.code, "missing_articles");
+        assert_eq!(detail.message, "gone");
+        assert!(detail.hint.is_some());
+
+        let detail = ErrorDetail::download_failure(None, "failed");
+        assert_eq!(detail.code, "download_failed");
+        assert!(detail.hint.is_none());
+    }
+
     // -----------------------------------------------------------------------
     // Verify that Error -> ApiError preserves the Display message
     // -----------------------------------------------------------------------
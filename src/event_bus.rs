@@ -0,0 +1,458 @@
+//! Event bus with a bounded queue per subscriber.
+//!
+//! Every subscriber gets its own queue of [`Event`]s. When a subscriber falls behind and
+//! its queue fills up, progress events are coalesced first: a queued progress event is
+//! replaced by a newer one of the same kind for the same download. Only when nothing
+//! can be coalesced is the oldest event dropped, which the subscriber sees as
+//! [`RecvError::Lagged`]. A slow consumer therefore loses stale progress updates rather
+//! than completions or failures.
+//!
+//! The API mirrors `tokio::sync::broadcast`: [`channel`] creates a sender and a first
+//! receiver, [`EventSender::subscribe`] adds more, and receivers are closed once every
+//! sender has been dropped. The bus carries [`Event`]s by default; any [`Coalesce`] item
+//! works, which is how [`EventEnvelope`]s reach JSON subscribers.
+
+use crate::types::{DownloadId, Event, EventEnvelope};
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use tokio::sync::Notify;
+
+/// Names of the progress events, which the next one of the same kind supersedes
+const PROGRESS_EVENT_TYPES: [&str; 4] = [
+    "downloading",
+    "verify_progress",
+    "repair_progress",
+    "move_progress",
+];
+
+/// An item carried by the event bus
+pub trait Coalesce: Clone + std::fmt::Debug {
+    /// Kind and download of a progress item; newer ones supersede older ones with the same key
+    fn coalesce_key(&self) -> Option<(&str, DownloadId)>;
+}
+
+impl Coalesce for Event {
+    fn coalesce_key(&self) -> Option<(&str, DownloadId)> {
+        match self {
+            Event::Downloading { id, .. }
+            | Event::VerifyProgress { id, .. }
+            | Event::RepairProgress { id, .. }
+            | Event::MoveProgress { id, .. } => Some((self.event_type(), *id)),
+            _ => None,
+        }
+    }
+}
+
+impl Coalesce for EventEnvelope {
+    fn coalesce_key(&self) -> Option<(&str, DownloadId)> {
+        if !PROGRESS_EVENT_TYPES.contains(&self.event_type.as_str()) {
+            return None;
+        }
+        let id = self.payload.get("id")?.as_i64()?;
+        Some((&self.event_type, DownloadId(id)))
+    }
+}
+
+/// Error returned by [`EventSender::send`] when there are no subscribers
+#[derive(Debug, thiserror::Error)]
+#[error("no event subscribers")]
+pub struct SendError<T: std::fmt::Debug = Event>(pub T);
+
+/// Error returned by [`EventReceiver::recv`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum RecvError {
+    /// Every sender was dropped and no events are left
+    #[error("event channel closed")]
+    Closed,
+    /// The subscriber fell behind and this many events were dropped
+    #[error("event subscriber lagged by {0}")]
+    Lagged(u64),
+}
+
+/// Error returned by [`EventReceiver::try_recv`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TryRecvError {
+    /// No event is queued
+    #[error("no event queued")]
+    Empty,
+    /// Every sender was dropped and no events are left
+    #[error("event channel closed")]
+    Closed,
+    /// The subscriber fell behind and this many events were dropped
+    #[error("event subscriber lagged by {0}")]
+    Lagged(u64),
+}
+
+/// Create an event bus whose subscribers each queue up to `capacity` events
+pub fn channel<T: Coalesce>(capacity: usize) -> (EventSender<T>, EventReceiver<T>) {
+    let sender = EventSender {
+        shared: Arc::new(Shared {
+            capacity: capacity.max(1),
+            senders: AtomicUsize::new(1),
+            subscribers: Mutex::new(Vec::new()),
+        }),
+    };
+    let receiver = sender.subscribe();
+    (sender, receiver)
+}
+
+/// State shared by all senders
+struct Shared<T> {
+    capacity: usize,
+    senders: AtomicUsize,
+    subscribers: Mutex<Vec<Weak<SubscriberQueue<T>>>>,
+}
+
+impl<T> Shared<T> {
+    fn subscribers(&self) -> MutexGuard<'_, Vec<Weak<SubscriberQueue<T>>>> {
+        self.subscribers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// One subscriber's queue
+struct SubscriberQueue<T> {
+    state: Mutex<QueueState<T>>,
+    notify: Notify,
+}
+
+struct QueueState<T> {
+    events: VecDeque<T>,
+    /// Events dropped since the subscriber last received
+    lagged: u64,
+    closed: bool,
+}
+
+impl<T> Default for QueueState<T> {
+    fn default() -> Self {
+        Self {
+            events: VecDeque::new(),
+            lagged: 0,
+            closed: false,
+        }
+    }
+}
+
+impl<T> SubscriberQueue<T> {
+    fn state(&self) -> MutexGuard<'_, QueueState<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn close(&self) {
+        self.state().closed = true;
+        self.notify.notify_one();
+    }
+}
+
+impl<T: Coalesce> SubscriberQueue<T> {
+    fn push(&self, event: T, capacity: usize) {
+        {
+            let mut state = self.state();
+            if state.events.len() >= capacity && !coalesce(&mut state.events, &event) {
+                state.events.pop_front();
+                state.lagged += 1;
+            }
+            state.events.push_back(event);
+        }
+        self.notify.notify_one();
+    }
+}
+
+/// Make room in a full queue by removing a superseded progress event
+///
+/// Prefers the one `incoming` supersedes, then the oldest one superseded by a newer
+/// queued event. Returns false if no queued event is superseded.
+fn coalesce<T: Coalesce>(events: &mut VecDeque<T>, incoming: &T) -> bool {
+    if let Some(key) = incoming.coalesce_key()
+        && let Some(index) = events.iter().position(|e| e.coalesce_key() == Some(key))
+    {
+        events.remove(index);
+        return true;
+    }
+
+    let mut newer = HashSet::new();
+    let mut superseded = None;
+    for (index, event) in events.iter().enumerate().rev() {
+        if let Some(key) = event.coalesce_key()
+            && !newer.insert(key)
+        {
+            superseded = Some(index);
+        }
+    }
+    match superseded {
+        Some(index) => {
+            events.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Sending half of the event bus
+pub struct EventSender<T = Event> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Coalesce> EventSender<T> {
+    /// Queue an event for every subscriber
+    ///
+    /// Returns the number of subscribers, or the event back if there are none.
+    pub fn send(&self, event: T) -> Result<usize, SendError<T>> {
+        // Held while queueing so every subscriber sees concurrent sends in the same order
+        let mut registered = self.shared.subscribers();
+        registered.retain(|queue| queue.strong_count() > 0);
+        let subscribers: Vec<Arc<SubscriberQueue<T>>> =
+            registered.iter().filter_map(Weak::upgrade).collect();
+        let Some((last, rest)) = subscribers.split_last() else {
+            return Err(SendError(event));
+        };
+
+        for queue in rest {
+            queue.push(event.clone(), self.shared.capacity);
+        }
+        last.push(event, self.shared.capacity);
+
+        Ok(subscribers.len())
+    }
+
+    /// Add a subscriber that receives events sent from now on
+    pub fn subscribe(&self) -> EventReceiver<T> {
+        let queue = Arc::new(SubscriberQueue {
+            state: Mutex::new(QueueState::default()),
+            notify: Notify::new(),
+        });
+        self.shared.subscribers().push(Arc::downgrade(&queue));
+        EventReceiver { queue }
+    }
+}
+
+impl<T> EventSender<T> {
+    /// Number of live subscribers
+    pub fn receiver_count(&self) -> usize {
+        self.shared
+            .subscribers()
+            .iter()
+            .filter(|queue| queue.strong_count() > 0)
+            .count()
+    }
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for EventSender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            for queue in self.shared.subscribers().iter().filter_map(Weak::upgrade) {
+                queue.close();
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for EventSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventSender")
+            .field("receivers", &self.receiver_count())
+            .finish()
+    }
+}
+
+/// Receiving half of the event bus, one per subscriber
+pub struct EventReceiver<T = Event> {
+    queue: Arc<SubscriberQueue<T>>,
+}
+
+impl<T> EventReceiver<T> {
+    /// Wait for the next event
+    ///
+    /// Fails with [`RecvError::Lagged`] once after events were dropped, then continues
+    /// with the oldest event still queued.
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(event) => return Ok(event),
+                Err(TryRecvError::Lagged(skipped)) => return Err(RecvError::Lagged(skipped)),
+                Err(TryRecvError::Closed) => return Err(RecvError::Closed),
+                Err(TryRecvError::Empty) => self.queue.notify.notified().await,
+            }
+        }
+    }
+
+    /// Take the next event if one is queued
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.queue.state();
+        if state.lagged > 0 {
+            return Err(TryRecvError::Lagged(std::mem::take(&mut state.lagged)));
+        }
+        match state.events.pop_front() {
+            Some(event) => Ok(event),
+            None if state.closed => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Number of events waiting to be received
+    pub fn len(&self) -> usize {
+        self.queue.state().events.len()
+    }
+
+    /// Whether no events are waiting to be received
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> std::fmt::Debug for EventReceiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventReceiver")
+            .field("queued", &self.len())
+            .finish()
+    }
+}
+
+// unwrap/expect are acceptable in tests for concise failure-on-error assertions
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(id: i64, percent: f32) -> Event {
+        Event::Downloading {
+            id: DownloadId(id),
+            percent,
+            speed_bps: 0,
+            failed_articles: None,
+            total_articles: None,
+            health_percent: None,
+        }
+    }
+
+    fn percent(event: &Event) -> f32 {
+        match event {
+            Event::Downloading { percent, .. } => *percent,
+            other => panic!("expected Downloading, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn every_subscriber_receives_every_event() {
+        let (tx, mut first) = channel(8);
+        let mut second = tx.subscribe();
+
+        assert_eq!(tx.send(Event::QueuePaused).unwrap(), 2);
+
+        assert!(matches!(first.recv().await, Ok(Event::QueuePaused)));
+        assert!(matches!(second.recv().await, Ok(Event::QueuePaused)));
+        assert_eq!(first.try_recv().unwrap_err(), TryRecvError::Empty);
+    }
+
+    #[test]
+    fn send_without_subscribers_returns_the_event() {
+        let (tx, rx) = channel(8);
+        drop(rx);
+
+        let err = tx.send(Event::QueuePaused).unwrap_err();
+        assert!(matches!(err.0, Event::QueuePaused));
+        assert_eq!(tx.receiver_count(), 0);
+    }
+
+    #[test]
+    fn full_queue_keeps_only_latest_progress_per_download() {
+        let (tx, mut rx) = channel(3);
+
+        tx.send(progress(1, 10.0)).unwrap();
+        tx.send(Event::QueuePaused).unwrap();
+        tx.send(progress(2, 10.0)).unwrap();
+        // Full: supersedes download 1's queued progress instead of dropping anything
+        tx.send(progress(1, 20.0)).unwrap();
+
+        assert!(matches!(rx.try_recv(), Ok(Event::QueuePaused)));
+        assert_eq!(percent(&rx.try_recv().unwrap()), 10.0);
+        assert_eq!(percent(&rx.try_recv().unwrap()), 20.0);
+        assert_eq!(rx.try_recv().unwrap_err(), TryRecvError::Empty);
+    }
+
+    #[test]
+    fn full_queue_drops_superseded_progress_before_other_events() {
+        let (tx, mut rx) = channel(3);
+
+        tx.send(progress(1, 10.0)).unwrap();
+        tx.send(progress(1, 20.0)).unwrap();
+        tx.send(Event::QueuePaused).unwrap();
+        tx.send(Event::QueueResumed).unwrap();
+
+        assert_eq!(percent(&rx.try_recv().unwrap()), 20.0);
+        assert!(matches!(rx.try_recv(), Ok(Event::QueuePaused)));
+        assert!(matches!(rx.try_recv(), Ok(Event::QueueResumed)));
+    }
+
+    #[test]
+    fn full_queue_without_progress_drops_oldest_and_reports_lag() {
+        let (tx, mut rx) = channel(2);
+
+        tx.send(Event::QueuePaused).unwrap();
+        tx.send(Event::QueueResumed).unwrap();
+        tx.send(Event::Drained).unwrap();
+
+        assert_eq!(rx.try_recv().unwrap_err(), TryRecvError::Lagged(1));
+        assert!(matches!(rx.try_recv(), Ok(Event::QueueResumed)));
+        assert!(matches!(rx.try_recv(), Ok(Event::Drained)));
+    }
+
+    #[test]
+    fn envelopes_coalesce_like_the_events_they_wrap() {
+        let id = DownloadId(3);
+        let events = [
+            progress(3, 10.0),
+            Event::VerifyProgress { id, percent: 1.0 },
+            Event::RepairProgress { id, percent: 1.0 },
+            Event::MoveProgress { id, percent: 1.0 },
+            Event::QueuePaused,
+        ];
+        for event in &events {
+            let envelope = EventEnvelope::new(1, event);
+            assert_eq!(envelope.coalesce_key(), event.coalesce_key(), "{event:?}");
+        }
+    }
+
+    #[test]
+    fn full_envelope_queue_keeps_only_latest_progress_per_download() {
+        let (tx, mut rx) = channel(2);
+
+        tx.send(EventEnvelope::new(1, &progress(1, 10.0))).unwrap();
+        tx.send(EventEnvelope::new(2, &Event::QueuePaused)).unwrap();
+        tx.send(EventEnvelope::new(3, &progress(1, 20.0))).unwrap();
+
+        let seqs: Vec<u64> = std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|envelope| envelope.seq)
+            .collect();
+        assert_eq!(seqs, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn receivers_close_after_last_sender_is_dropped() {
+        let (tx, mut rx) = channel(8);
+        let tx2 = tx.clone();
+        tx.send(Event::QueuePaused).unwrap();
+        drop(tx);
+
+        let waiting = tokio::spawn(async move {
+            let first = rx.recv().await;
+            let second = rx.recv().await;
+            (first, second)
+        });
+        tokio::task::yield_now().await;
+        drop(tx2);
+
+        let (first, second) = waiting.await.unwrap();
+        assert!(matches!(first, Ok(Event::QueuePaused)));
+        assert_eq!(second.unwrap_err(), RecvError::Closed);
+    }
+}
@@ -0,0 +1,343 @@
+use crate::config::{ProcessPriorityConfig, ToolsConfig};
+use crate::db::Database;
+use crate::error::{Error, PostProcessError, Result};
+use crate::types::DownloadId;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use super::password_list::PasswordList;
+use super::shared::extract_with_passwords_impl;
+
+/// How often a running tool is checked for having exited or been cancelled
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// External binary used by [`CliRarExtractor`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CliTool {
+    /// RARLAB `unrar`
+    Unrar,
+    /// 7-Zip `7z` (or `7zz`), which extracts RAR archives too
+    SevenZip,
+}
+
+/// Archive extractor for RAR files that shells out to `unrar` or `7z`
+///
+/// Used for archives the built-in extractor can't handle (e.g. exotic RAR5 features).
+/// The tool's output is mapped to the same errors the built-in extractor returns,
+/// so password attempts work the same way.
+#[derive(Clone, Debug)]
+pub struct CliRarExtractor {
+    binary_path: PathBuf,
+    tool: CliTool,
+    /// CPU and I/O priority the tool runs at
+    priority: ProcessPriorityConfig,
+    /// Kills a running tool once cancelled
+    cancel: CancellationToken,
+}
+
+impl CliRarExtractor {
+    /// Create a CLI extractor with an explicit binary path
+    pub fn new(binary_path: PathBuf, tool: CliTool) -> Self {
+        Self {
+            binary_path,
+            tool,
+            priority: ProcessPriorityConfig::default(),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Run the tool at the given CPU and I/O priority (Unix only)
+    pub fn with_process_priority(mut self, priority: ProcessPriorityConfig) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Kill the running tool, and start no more, once `cancel` is cancelled
+    ///
+    /// The extraction then fails with [`PostProcessError::ExtractionFailed`].
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Whether the extractor was cancelled, so its failures aren't worth retrying
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Find a binary from the tools configuration
+    ///
+    /// Prefers `unrar_path`, then `sevenzip_path`, then (when `search_path` is set)
+    /// `unrar`, `7z` and `7zz` in PATH. Returns `None` if none is available. The
+    /// tool runs at `process_priority`.
+    pub fn from_config(tools: &ToolsConfig) -> Option<Self> {
+        let found = if let Some(path) = &tools.unrar_path {
+            Some(Self::new(path.clone(), CliTool::Unrar))
+        } else if let Some(path) = &tools.sevenzip_path {
+            Some(Self::new(path.clone(), CliTool::SevenZip))
+        } else if tools.search_path {
+            [
+                ("unrar", CliTool::Unrar),
+                ("7z", CliTool::SevenZip),
+                ("7zz", CliTool::SevenZip),
+            ]
+            .into_iter()
+            .find_map(|(name, tool)| which::which(name).ok().map(|path| Self::new(path, tool)))
+        } else {
+            None
+        };
+        found.map(|cli| cli.with_process_priority(tools.process_priority.clone()))
+    }
+
+    /// The tool this extractor runs
+    pub fn tool(&self) -> CliTool {
+        self.tool
+    }
+
+    /// Try to extract a RAR archive with a single password
+    ///
+    /// Returns the files written to `dest_path`; errors are the same as
+    /// [`RarExtractor::try_extract`](super::RarExtractor::try_extract).
+    pub fn try_extract(
+        &self,
+        archive_path: &Path,
+        password: &str,
+        dest_path: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        debug!(
+            ?archive_path,
+            tool = ?self.tool,
+            password_length = password.len(),
+            ?dest_path,
+            "attempting RAR extraction with external binary"
+        );
+
+        std::fs::create_dir_all(dest_path).map_err(|e| {
+            Error::Io(std::io::Error::other(format!(
+                "failed to create destination: {}",
+                e
+            )))
+        })?;
+
+        let before = snapshot(dest_path);
+
+        let mut command = std::process::Command::new(&self.binary_path);
+        match self.tool {
+            // -p- never prompts for a password when none is given
+            CliTool::Unrar => command
+                .arg("x")
+                .arg("-o+")
+                .arg("-y")
+                .arg(if password.is_empty() {
+                    "-p-".to_string()
+                } else {
+                    format!("-p{}", password)
+                })
+                .arg(archive_path)
+                .arg(format!(
+                    "{}{}",
+                    dest_path.display(),
+                    std::path::MAIN_SEPARATOR
+                )),
+            CliTool::SevenZip => command
+                .arg("x")
+                .arg("-y")
+                .arg("-aoa")
+                .arg(format!("-p{}", password))
+                .arg(format!("-o{}", dest_path.display()))
+                .arg(archive_path),
+        };
+
+        crate::utils::apply_process_priority(&mut command, &self.priority);
+        let (exit_code, text) = self.run(&mut command, archive_path)?;
+        parse_cli_output(self.tool, exit_code, &text, archive_path)?;
+
+        let extracted = changed_files(dest_path, &before);
+        info!(
+            ?archive_path,
+            tool = ?self.tool,
+            extracted_count = extracted.len(),
+            "RAR extraction with external binary successful"
+        );
+        Ok(extracted)
+    }
+
+    /// Run the tool to completion, returning its exit code and output
+    ///
+    /// Blocks; the tool is killed and waited for if the extractor is cancelled.
+    fn run(
+        &self,
+        command: &mut std::process::Command,
+        archive_path: &Path,
+    ) -> Result<(Option<i32>, String)> {
+        let cancelled = || {
+            Error::PostProcess(PostProcessError::ExtractionFailed {
+                archive: archive_path.to_path_buf(),
+                reason: format!("{:?} was cancelled", self.tool),
+            })
+        };
+        if self.cancel.is_cancelled() {
+            return Err(cancelled());
+        }
+
+        let mut child = command
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                Error::ExternalTool(format!(
+                    "Failed to execute {}: {}",
+                    self.binary_path.display(),
+                    e
+                ))
+            })?;
+
+        // Drained on their own threads so a chatty tool can't block on a full pipe
+        let read_all = |mut pipe: Box<dyn Read + Send>| {
+            std::thread::spawn(move || {
+                let mut output = Vec::new();
+                let _ = pipe.read_to_end(&mut output);
+                output
+            })
+        };
+        let stdout = child.stdout.take().map(|pipe| read_all(Box::new(pipe)));
+        let stderr = child.stderr.take().map(|pipe| read_all(Box::new(pipe)));
+
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if self.cancel.is_cancelled() {
+                // Once this returns, the tool no longer touches the destination
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(cancelled());
+            }
+            std::thread::sleep(CANCEL_POLL_INTERVAL);
+        };
+
+        let collect = |reader: Option<std::thread::JoinHandle<Vec<u8>>>| {
+            reader
+                .and_then(|reader| reader.join().ok())
+                .map(|output| String::from_utf8_lossy(&output).into_owned())
+                .unwrap_or_default()
+        };
+        let text = format!("{}\n{}", collect(stdout), collect(stderr));
+        Ok((status.code(), text))
+    }
+
+    /// Extract a RAR archive with password attempts
+    ///
+    /// Tries each password in the list until one works or all fail.
+    /// Caches the successful password in the database.
+    pub async fn extract_with_passwords(
+        &self,
+        download_id: DownloadId,
+        archive_path: &Path,
+        dest_path: &Path,
+        passwords: &PasswordList,
+        db: &Database,
+    ) -> Result<Vec<PathBuf>> {
+        let extractor = self.clone();
+        extract_with_passwords_impl(
+            "RAR (external)",
+            move |archive: &Path, password: &str, dest: &Path| {
+                extractor.try_extract(archive, password, dest)
+            },
+            download_id,
+            archive_path,
+            dest_path,
+            passwords,
+            db,
+        )
+        .await
+    }
+}
+
+/// Map the exit code and output of `unrar` or `7z` to an extraction result
+///
+/// unrar exits with 11 for a wrong password (3 from older versions, which report it
+/// as a CRC error in an encrypted file); 7z exits with 2 and says "Wrong password".
+/// Exit code 1 is a warning from both and counts as success.
+pub(crate) fn parse_cli_output(
+    tool: CliTool,
+    exit_code: Option<i32>,
+    output: &str,
+    archive_path: &Path,
+) -> Result<()> {
+    if matches!(exit_code, Some(0) | Some(1)) {
+        return Ok(());
+    }
+
+    let lower = output.to_lowercase();
+    let wrong_password = lower.contains("wrong password")
+        || lower.contains("incorrect password")
+        || lower.contains("password is incorrect")
+        || (lower.contains("encrypted file")
+            && (lower.contains("crc failed") || lower.contains("checksum error")))
+        || (tool == CliTool::Unrar && exit_code == Some(11));
+    if wrong_password {
+        return Err(Error::PostProcess(PostProcessError::WrongPassword {
+            archive: archive_path.to_path_buf(),
+        }));
+    }
+
+    // Most relevant line first: the last one mentioning an error, else the last line
+    let reason = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .rfind(|line| {
+            let line = line.to_lowercase();
+            line.contains("error") || line.contains("cannot") || line.contains("unexpected")
+        })
+        .or_else(|| output.lines().map(str::trim).rfind(|line| !line.is_empty()))
+        .unwrap_or("no output")
+        .to_string();
+
+    Err(Error::PostProcess(PostProcessError::ExtractionFailed {
+        archive: archive_path.to_path_buf(),
+        reason: match exit_code {
+            Some(code) => format!("{:?} exited with code {}: {}", tool, code, reason),
+            None => format!("{:?} was terminated: {}", tool, reason),
+        },
+    }))
+}
+
+/// Size and modification time of every file below `dir`
+fn snapshot(dir: &Path) -> HashMap<PathBuf, (u64, Option<SystemTime>)> {
+    let mut files = HashMap::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                files.insert(entry.path(), (metadata.len(), metadata.modified().ok()));
+            }
+        }
+    }
+    files
+}
+
+/// Files below `dir` that are new or changed since `before` was taken
+fn changed_files(dir: &Path, before: &HashMap<PathBuf, (u64, Option<SystemTime>)>) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = snapshot(dir)
+        .into_iter()
+        .filter(|(path, state)| before.get(path) != Some(state))
+        .map(|(path, _)| path)
+        .collect();
+    files.sort();
+    files
+}
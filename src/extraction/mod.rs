@@ -1,13 +1,22 @@
 //! Archive extraction with password support
 //!
-//! This module handles extracting RAR, 7z, and ZIP archives with password attempts.
-//! It supports multiple password sources (cached, per-download, NZB meta, global file, empty).
+//! This module handles extracting RAR, 7z, and ZIP archives with password attempts, and
+//! decompressing Zstandard (`.zst`) files.
+//! It supports multiple password sources (cached, per-download, NZB meta, password provider,
+//! global file, empty). RAR archives the built-in extractor can't handle fall back to
+//! an external `unrar` or `7z` binary when one is available. Other formats can be
+//! handled by registering an [`Extractor`].
 
+mod cli;
 mod password_list;
+mod password_provider;
 mod rar;
+mod rar_volume;
+mod registry;
 mod sevenz;
 mod shared;
 mod zip;
+mod zstd;
 
 // unwrap/expect are acceptable in tests for concise failure-on-error assertions
 #[allow(clippy::unwrap_used, clippy::expect_used)]
@@ -15,21 +24,31 @@ mod zip;
 mod tests;
 
 // Re-exports
+pub use cli::{CliRarExtractor, CliTool};
 pub use password_list::PasswordList;
+pub use password_provider::{HttpPasswordProvider, PasswordProvider};
 pub use rar::RarExtractor;
+pub use registry::{Extractor, ExtractorRegistry};
 pub use sevenz::SevenZipExtractor;
-pub use shared::{detect_archive_type, extract_recursive, is_archive};
+pub use shared::{
+    detect_archive_type, detect_disguised_archives, extract_recursive, is_archive,
+    sniff_archive_type,
+};
 pub use zip::ZipExtractor;
+pub use zstd::ZstdExtractor;
+
+pub(crate) use rar_volume::{RarVolume, read_rar_volume};
 
 use crate::db::Database;
 use crate::error::{Error, PostProcessError, Result};
 use crate::types::DownloadId;
 use std::path::{Path, PathBuf};
-use tracing::info;
+use tracing::{info, warn};
 
 /// Unified archive extraction dispatcher
 ///
-/// Detects the archive type and routes to the appropriate extractor (RAR, 7z, or ZIP).
+/// Detects the archive type from the file's signature, falling back to its extension,
+/// and routes to the appropriate extractor (RAR, 7z, ZIP, or Zstandard).
 /// Tries multiple passwords from the PasswordList and caches the successful password.
 ///
 /// # Arguments
@@ -49,7 +68,7 @@ use tracing::info;
 /// use std::path::PathBuf;
 ///
 /// # async fn example(db: &usenet_dl::db::Database) -> usenet_dl::error::Result<()> {
-/// let passwords = PasswordList::collect(None, Some("pass123"), None, None, true);
+/// let passwords = PasswordList::collect(None, Some("pass123"), None, None, true, None);
 /// let files = extract_archive(
 ///     1,
 ///     &PathBuf::from("movie.rar"),
@@ -68,13 +87,43 @@ pub async fn extract_archive(
     passwords: &PasswordList,
     db: &Database,
 ) -> Result<Vec<PathBuf>> {
-    // Detect archive type by extension
-    let archive_type = detect_archive_type(archive_path).ok_or_else(|| {
-        Error::PostProcess(PostProcessError::ExtractionFailed {
-            archive: archive_path.to_path_buf(),
-            reason: format!("unknown archive type for file: {}", archive_path.display()),
-        })
-    })?;
+    extract_archive_with_cli(
+        download_id,
+        archive_path,
+        dest_path,
+        passwords,
+        db,
+        None,
+        false,
+    )
+    .await
+}
+
+/// Archive extraction dispatcher with an external RAR extractor
+///
+/// Like [`extract_archive`], but RAR archives the built-in extractor fails on are
+/// retried with `cli`. With `prefer_cli` the external binary is tried first and the
+/// built-in extractor is the fallback. Password failures are never retried with the
+/// other extractor, since they'd fail the same way, and neither are those of a
+/// cancelled `cli`.
+pub async fn extract_archive_with_cli(
+    download_id: DownloadId,
+    archive_path: &Path,
+    dest_path: &Path,
+    passwords: &PasswordList,
+    db: &Database,
+    cli: Option<&CliRarExtractor>,
+    prefer_cli: bool,
+) -> Result<Vec<PathBuf>> {
+    // The file's signature wins over its extension, which may be misleading
+    let archive_type = sniff_archive_type(archive_path)
+        .or_else(|| shared::archive_type_from_extension(archive_path))
+        .ok_or_else(|| {
+            Error::PostProcess(PostProcessError::ExtractionFailed {
+                archive: archive_path.to_path_buf(),
+                reason: format!("unknown archive type for file: {}", archive_path.display()),
+            })
+        })?;
 
     info!(
         download_id = download_id.0,
@@ -85,16 +134,51 @@ pub async fn extract_archive(
 
     // Route to the appropriate extractor
     match archive_type {
-        crate::types::ArchiveType::Rar => {
-            RarExtractor::extract_with_passwords(
-                download_id,
-                archive_path,
-                dest_path,
-                passwords,
-                db,
-            )
-            .await
-        }
+        crate::types::ArchiveType::Rar => match cli {
+            Some(cli) if prefer_cli => {
+                let result = cli
+                    .extract_with_passwords(download_id, archive_path, dest_path, passwords, db)
+                    .await;
+                if !should_fall_back(&result) || cli.is_cancelled() {
+                    return result;
+                }
+                warn!(
+                    download_id = download_id.0,
+                    ?archive_path,
+                    "external RAR extraction failed, retrying with built-in extractor"
+                );
+                RarExtractor::extract_with_passwords(
+                    download_id,
+                    archive_path,
+                    dest_path,
+                    passwords,
+                    db,
+                )
+                .await
+            }
+            _ => {
+                let result = RarExtractor::extract_with_passwords(
+                    download_id,
+                    archive_path,
+                    dest_path,
+                    passwords,
+                    db,
+                )
+                .await;
+                let Some(cli) = cli.filter(|cli| should_fall_back(&result) && !cli.is_cancelled())
+                else {
+                    return result;
+                };
+                warn!(
+                    download_id = download_id.0,
+                    ?archive_path,
+                    tool = ?cli.tool(),
+                    "built-in RAR extraction failed, retrying with external binary"
+                );
+                cli.extract_with_passwords(download_id, archive_path, dest_path, passwords, db)
+                    .await
+            }
+        },
         crate::types::ArchiveType::SevenZip => {
             SevenZipExtractor::extract_with_passwords(
                 download_id,
@@ -115,5 +199,28 @@ pub async fn extract_archive(
             )
             .await
         }
+        crate::types::ArchiveType::Zstd => {
+            ZstdExtractor::extract_async(archive_path, dest_path).await
+        }
+        // Registered extractors are dispatched by `extract_recursive`, never from here
+        crate::types::ArchiveType::Custom(name) => {
+            Err(Error::PostProcess(PostProcessError::ExtractionFailed {
+                archive: archive_path.to_path_buf(),
+                reason: format!("no built-in extractor for {} archives", name),
+            }))
+        }
+    }
+}
+
+/// Whether a failed RAR extraction is worth retrying with the other extractor
+fn should_fall_back(result: &Result<Vec<PathBuf>>) -> bool {
+    match result {
+        Ok(_) => false,
+        Err(Error::PostProcess(
+            PostProcessError::WrongPassword { .. }
+            | PostProcessError::AllPasswordsFailed { .. }
+            | PostProcessError::NoPasswordsAvailable { .. },
+        )) => false,
+        Err(_) => true,
     }
 }
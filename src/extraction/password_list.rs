@@ -1,4 +1,5 @@
-use tracing::debug;
+use super::password_provider::PasswordProvider;
+use tracing::{debug, warn};
 
 /// Password list collector for archive extraction
 ///
@@ -6,8 +7,12 @@ use tracing::debug;
 /// 1. Cached correct password (from previous successful extraction)
 /// 2. Per-download password (user-specified)
 /// 3. NZB metadata password (embedded in NZB)
-/// 4. Global password file (one password per line)
-/// 5. Empty password (optional fallback)
+/// 4. Password provider lookup by release name (e.g. an HTTP password database)
+/// 5. Global password file (one password per line)
+/// 6. Empty password (optional fallback)
+///
+/// Per-download and NZB metadata passwords may list several passwords separated by
+/// semicolons (`"first;second"`); each is tried in the order given.
 #[derive(Debug)]
 pub struct PasswordList {
     passwords: Vec<String>,
@@ -21,21 +26,40 @@ impl PasswordList {
         nzb_meta_password: Option<&str>,
         global_file: Option<&std::path::Path>,
         try_empty: bool,
+        lookup: Option<(&dyn PasswordProvider, &str)>,
     ) -> Self {
         let mut seen = std::collections::HashSet::new();
         let mut passwords = Vec::new();
 
         // Add in priority order, skip duplicates
         // Use reference in HashSet to avoid double allocation
-        for pw in [cached_correct, download_password, nzb_meta_password]
+        let listed = [download_password, nzb_meta_password]
             .into_iter()
             .flatten()
-        {
+            .flat_map(|list| list.split(';'))
+            .filter(|pw| !pw.is_empty());
+        for pw in cached_correct.into_iter().chain(listed) {
             if seen.insert(pw) {
                 passwords.push(pw.to_string());
             }
         }
 
+        // Ask the provider for this release, tolerating failures
+        if let Some((provider, release_name)) = lookup {
+            match provider.passwords(release_name).await {
+                Ok(found) => {
+                    for pw in found {
+                        if !pw.is_empty() && !passwords.contains(&pw) {
+                            passwords.push(pw);
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!(release = release_name, error = %e, "password lookup failed");
+                }
+            }
+        }
+
         // Add from file - need owned strings for file content
         if let Some(path) = global_file
             && let Ok(file_content) = tokio::fs::read_to_string(path).await
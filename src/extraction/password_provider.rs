@@ -0,0 +1,103 @@
+//! External password sources consulted during password collection.
+
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Source of password candidates for a release, such as a community password database
+///
+/// Consulted by [`PasswordList::collect`](super::PasswordList::collect) after the
+/// download's own passwords and before the global password file. Errors are logged
+/// and otherwise ignored, so a provider that is down never blocks extraction.
+///
+/// Set a custom implementation through
+/// [`ToolsConfig::password_provider`](crate::config::ToolsConfig::password_provider),
+/// or configure [`HttpPasswordProvider`] with `tools.password_lookup_url`.
+#[async_trait]
+pub trait PasswordProvider: Send + Sync {
+    /// Look up password candidates for `release_name`, most likely first
+    async fn passwords(&self, release_name: &str) -> Result<Vec<String>>;
+}
+
+/// Looks up passwords from an HTTP endpoint by release name
+///
+/// The release name is substituted URL-encoded for `{name}` in the URL, or appended as a
+/// `name` query parameter if the URL has no placeholder. The endpoint may answer with a
+/// JSON array of strings, a JSON object with a `passwords` array, or plain text with
+/// one password per line. A 404 response means no passwords are known.
+pub struct HttpPasswordProvider {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpPasswordProvider {
+    /// Create a provider querying `url`, giving up on requests after `timeout`
+    pub fn new(url: impl Into<String>, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self {
+            client,
+            url: url.into(),
+        })
+    }
+
+    /// Request URL for `release_name`
+    fn lookup_url(&self, release_name: &str) -> Result<reqwest::Url> {
+        if self.url.contains("{name}") {
+            let url = self
+                .url
+                .replace("{name}", &urlencoding::encode(release_name));
+            return reqwest::Url::parse(&url)
+                .map_err(|e| Error::Other(format!("Invalid password lookup URL: {}", e)));
+        }
+
+        let mut url = reqwest::Url::parse(&self.url)
+            .map_err(|e| Error::Other(format!("Invalid password lookup URL: {}", e)))?;
+        url.query_pairs_mut().append_pair("name", release_name);
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl PasswordProvider for HttpPasswordProvider {
+    async fn passwords(&self, release_name: &str) -> Result<Vec<String>> {
+        let response = self
+            .client
+            .get(self.lookup_url(release_name)?)
+            .send()
+            .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        let response = response.error_for_status()?;
+        let body = response.text().await?;
+        Ok(parse_passwords(&body))
+    }
+}
+
+/// Parse a lookup response: a JSON array, a JSON object with `passwords`, or plain lines
+fn parse_passwords(body: &str) -> Vec<String> {
+    let strings = |values: &[serde_json::Value]| {
+        values
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect()
+    };
+
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(serde_json::Value::Array(values)) => strings(&values),
+        Ok(serde_json::Value::Object(object)) => object
+            .get("passwords")
+            .and_then(|v| v.as_array())
+            .map(|values| strings(values))
+            .unwrap_or_default(),
+        _ => body
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    }
+}
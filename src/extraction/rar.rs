@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
 use super::password_list::PasswordList;
-use super::shared::extract_with_passwords_impl;
+use super::shared::{entry_destination, extract_with_passwords_impl};
 
 /// Archive extractor for RAR files
 pub struct RarExtractor;
@@ -138,12 +138,7 @@ impl RarExtractor {
             let header = at_file.entry();
 
             // Sanitize filename to prevent path traversal attacks (e.g., "../../../etc/passwd")
-            let sanitized = Path::new(&header.filename)
-                .components()
-                .filter(|c| matches!(c, std::path::Component::Normal(_)))
-                .collect::<PathBuf>();
-
-            if sanitized.as_os_str().is_empty() {
+            let Some(file_path) = entry_destination(dest_path, &header.filename) else {
                 // Skip entries with no valid path components (e.g., pure ".." entries)
                 at_header = at_file.skip().map_err(|e| {
                     Error::PostProcess(PostProcessError::ExtractionFailed {
@@ -152,9 +147,7 @@ impl RarExtractor {
                     })
                 })?;
                 continue;
-            }
-
-            let file_path = dest_path.join(&sanitized);
+            };
 
             // Check if it's a file (not a directory)
             if !header.is_directory() {
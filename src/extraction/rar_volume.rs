@@ -0,0 +1,169 @@
+//! Volume details read from RAR archive headers
+//!
+//! Obfuscated posts name RAR volumes randomly, so their place in a set can't be
+//! taken from the file name. RAR 5 records the volume number in the main archive
+//! header. RAR 3.x/4.x flags the first volume in the main header and records the
+//! number of later volumes in the end-of-archive header at the end of the file.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const RAR4_SIGNATURE: &[u8] = b"Rar!\x1a\x07\x00";
+const RAR5_SIGNATURE: &[u8] = b"Rar!\x1a\x07\x01\x00";
+
+/// Bytes read from the start of a file, enough to cover a RAR main archive header
+pub(crate) const HEADER_LEN: usize = 64;
+
+/// Bytes read from the end of a RAR 4 volume to find its end-of-archive header
+const TAIL_LEN: usize = 64;
+
+/// Volume details of a RAR file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct RarVolume {
+    /// Part of a multi-volume set
+    pub volume: bool,
+    /// 0-based position in the set, when the headers record it
+    pub number: Option<u64>,
+    /// Volumes are named `name.partNN.rar` rather than `name.rar`, `name.r00`, ...
+    pub new_numbering: bool,
+}
+
+/// Read the volume details from a RAR main archive header
+///
+/// Returns `None` when the header isn't a readable RAR 4 or RAR 5 main header
+/// (encrypted headers, truncated files). RAR 4 volumes after the first report no
+/// number, since it's only stored at the end of the file (see [`read_rar_volume`]).
+fn parse_main_header(header: &[u8]) -> Option<RarVolume> {
+    if let Some(rest) = header.strip_prefix(RAR5_SIGNATURE) {
+        return parse_rar5_main_header(rest);
+    }
+    let rest = header.strip_prefix(RAR4_SIGNATURE)?;
+
+    // HEAD_CRC (2), HEAD_TYPE (1, 0x73 for the main header), HEAD_FLAGS (2)
+    const MAIN_HEAD: u8 = 0x73;
+    const MHD_VOLUME: u16 = 0x0001;
+    const MHD_NEWNUMBERING: u16 = 0x0010;
+    const MHD_FIRSTVOLUME: u16 = 0x0100;
+    let [_, _, MAIN_HEAD, low, high, ..] = *rest else {
+        return None;
+    };
+    let flags = u16::from_le_bytes([low, high]);
+    let volume = flags & MHD_VOLUME != 0;
+    Some(RarVolume {
+        volume,
+        number: (volume && flags & MHD_FIRSTVOLUME != 0).then_some(0),
+        new_numbering: flags & MHD_NEWNUMBERING != 0,
+    })
+}
+
+fn parse_rar5_main_header(header: &[u8]) -> Option<RarVolume> {
+    const MAIN_HEADER: u64 = 1;
+    const HFL_EXTRA: u64 = 0x0001;
+    const HFL_DATA: u64 = 0x0002;
+    const MHFL_VOLUME: u64 = 0x0001;
+    const MHFL_VOLNUMBER: u64 = 0x0002;
+
+    // CRC32 (4), then vints: header size, type, flags, [extra size], [data size],
+    // archive flags, [volume number]
+    let mut rest = header.get(4..)?;
+    let mut vint = || {
+        let mut value = 0u64;
+        for (i, byte) in rest.iter().enumerate().take(10) {
+            value |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                rest = &rest[i + 1..];
+                return Some(value);
+            }
+        }
+        None
+    };
+    let _header_size = vint()?;
+    if vint()? != MAIN_HEADER {
+        return None;
+    }
+    let flags = vint()?;
+    if flags & HFL_EXTRA != 0 {
+        vint()?;
+    }
+    if flags & HFL_DATA != 0 {
+        vint()?;
+    }
+    let archive_flags = vint()?;
+    let volume = archive_flags & MHFL_VOLUME != 0;
+    // Only volumes after the first carry a volume number
+    let number = if archive_flags & MHFL_VOLNUMBER != 0 {
+        Some(vint()?)
+    } else {
+        volume.then_some(0)
+    };
+    Some(RarVolume {
+        volume,
+        number,
+        new_numbering: true,
+    })
+}
+
+/// Whether a RAR header belongs to a single archive or the first volume of a set
+///
+/// Headers that can't be parsed (encrypted headers) count as first volumes, since
+/// extracting a later volume only fails while skipping a first one loses the set.
+pub(crate) fn is_first_rar_volume(header: &[u8]) -> bool {
+    parse_main_header(header).is_none_or(|volume| !volume.volume || volume.number == Some(0))
+}
+
+/// Read the volume details of a RAR file, including RAR 4 volume numbers
+///
+/// Returns `None` for files that aren't RAR archives or whose headers can't be read.
+pub(crate) fn read_rar_volume(path: &Path) -> Option<RarVolume> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    (&mut file)
+        .take(HEADER_LEN as u64)
+        .read_to_end(&mut header)
+        .ok()?;
+    let mut volume = parse_main_header(&header)?;
+
+    if volume.volume && volume.number.is_none() {
+        let len = file.metadata().ok()?.len();
+        let tail_len = len.min(TAIL_LEN as u64);
+        file.seek(SeekFrom::Start(len - tail_len)).ok()?;
+        let mut tail = Vec::with_capacity(tail_len as usize);
+        file.read_to_end(&mut tail).ok()?;
+        volume.number = rar4_end_volume_number(&tail);
+    }
+    Some(volume)
+}
+
+/// Volume number from the RAR 4 end-of-archive header at the end of `tail`
+///
+/// The header is found by its type byte and confirmed with its CRC, since the bytes
+/// before it are compressed data that can look like anything.
+fn rar4_end_volume_number(tail: &[u8]) -> Option<u64> {
+    // HEAD_CRC (2), HEAD_TYPE (1), HEAD_FLAGS (2), HEAD_SIZE (2),
+    // [ARC_DATACRC (4)], [VOL_NUMBER (2)]
+    const END_HEAD: u8 = 0x7b;
+    const EARC_DATACRC: u16 = 0x0002;
+    const EARC_VOLNUMBER: u16 = 0x0008;
+
+    (0..tail.len().saturating_sub(6)).rev().find_map(|start| {
+        let block = &tail[start..];
+        if block[2] != END_HEAD {
+            return None;
+        }
+        let size = usize::from(u16::from_le_bytes([block[5], block[6]]));
+        let block = block.get(..size).filter(|_| size >= 7)?;
+        let mut crc = flate2::Crc::new();
+        crc.update(&block[2..]);
+        if (crc.sum() & 0xffff) as u16 != u16::from_le_bytes([block[0], block[1]]) {
+            return None;
+        }
+
+        let flags = u16::from_le_bytes([block[3], block[4]]);
+        if flags & EARC_VOLNUMBER == 0 {
+            return None;
+        }
+        let offset = if flags & EARC_DATACRC != 0 { 11 } else { 7 };
+        let number = block.get(offset..offset + 2)?;
+        Some(u64::from(u16::from_le_bytes([number[0], number[1]])))
+    })
+}
@@ -0,0 +1,122 @@
+//! Extractors registered by library consumers.
+
+use crate::error::Result;
+use crate::types::{ArchiveType, DownloadId};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use super::password_list::PasswordList;
+
+/// Extractor for an archive format the built-in extractors don't handle
+///
+/// Registered extractors are consulted before the built-in ones, in registration
+/// order: the first whose [`detect`](Extractor::detect) accepts a file extracts it.
+/// Files they detect are picked up when scanning a download for archives, count as
+/// nested archives during recursive extraction, and are reported by
+/// [`ExtractorRegistry::detect_archive_type`] as [`ArchiveType::Custom`] with the
+/// extractor's [`name`](Extractor::name).
+///
+/// Register one with
+/// [`UsenetDownloader::register_extractor`](crate::UsenetDownloader::register_extractor).
+#[async_trait]
+pub trait Extractor: Send + Sync {
+    /// Short name of the format, such as `"arj"`
+    fn name(&self) -> &str;
+
+    /// Whether this extractor handles `path`, by its name or contents
+    ///
+    /// Called for every file in a download directory, so it should be cheap.
+    fn detect(&self, path: &Path) -> bool;
+
+    /// Extract `archive_path` into `dest_path`, returning the paths of the files written
+    ///
+    /// `passwords` are the candidates collected for the download, most likely first.
+    /// Return [`PostProcessError::AllPasswordsFailed`](crate::error::PostProcessError::AllPasswordsFailed)
+    /// if none of them work, so the download is reported as needing a password.
+    /// Entries must be kept inside `dest_path`, e.g. by dropping `..` components.
+    async fn extract(
+        &self,
+        download_id: DownloadId,
+        archive_path: &Path,
+        dest_path: &Path,
+        passwords: &PasswordList,
+    ) -> Result<Vec<PathBuf>>;
+}
+
+/// Extractors registered at runtime, shared by every clone
+#[derive(Clone, Default)]
+pub struct ExtractorRegistry {
+    extractors: Arc<RwLock<Vec<Arc<dyn Extractor>>>>,
+}
+
+impl std::fmt::Debug for ExtractorRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.snapshot().iter().map(|e| e.name().to_string()))
+            .finish()
+    }
+}
+
+impl ExtractorRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an extractor, consulted after those registered before it
+    pub fn register(&self, extractor: Arc<dyn Extractor>) {
+        self.extractors
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(extractor);
+    }
+
+    /// Whether no extractors are registered
+    pub fn is_empty(&self) -> bool {
+        self.extractors
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    /// The first registered extractor that handles `path`
+    pub fn find(&self, path: &Path) -> Option<Arc<dyn Extractor>> {
+        self.snapshot().into_iter().find(|e| e.detect(path))
+    }
+
+    /// Archive type of `path`, asking registered extractors before the built-in detection
+    ///
+    /// See [`detect_archive_type`](super::detect_archive_type) for the built-in detection.
+    pub fn detect_archive_type(&self, path: &Path) -> Option<ArchiveType> {
+        match self.find(path) {
+            Some(extractor) => Some(ArchiveType::Custom(extractor.name().to_string())),
+            None => super::detect_archive_type(path),
+        }
+    }
+
+    /// Files directly in `dir` that a registered extractor handles
+    pub fn detect_files(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let extractors = self.snapshot();
+        if extractors.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && extractors.iter().any(|e| e.detect(&path)) {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+
+    /// The registered extractors, cloned so no lock is held across an await
+    fn snapshot(&self) -> Vec<Arc<dyn Extractor>> {
+        self.extractors
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
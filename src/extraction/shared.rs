@@ -1,12 +1,17 @@
-use crate::config::ExtractionConfig;
+use crate::config::{ExtractionConfig, PathSanitizationConfig};
 use crate::db::Database;
 use crate::error::{Error, PostProcessError, Result};
 use crate::types::{ArchiveType, DownloadId};
-use std::path::{Path, PathBuf};
+use crate::utils::{long_path, sanitize_filename};
+
+use super::cli::CliRarExtractor;
+use super::rar_volume::{HEADER_LEN, is_first_rar_volume};
+use std::path::{Component, Path, PathBuf};
 use tokio::task::spawn_blocking;
 use tracing::{debug, info, warn};
 
 use super::password_list::PasswordList;
+use super::registry::ExtractorRegistry;
 
 /// Shared implementation for archive extraction with password attempts.
 ///
@@ -131,21 +136,170 @@ pub(crate) async fn extract_with_passwords_impl(
     }))
 }
 
-/// Detect archive type by file extension
+/// Resolve where an archive entry should be written inside `dest_path`
+///
+/// Only normal components of `entry` are kept, which prevents path traversal
+/// (`../../etc/passwd`). Each component is made valid for the host platform using the
+/// default [`PathSanitizationConfig`] (Windows naming rules on Windows, untouched
+/// elsewhere), and long results get the Windows extended-length prefix. Returns `None`
+/// when nothing safe remains (e.g. a pure `..` entry).
+pub(crate) fn entry_destination(dest_path: &Path, entry: &Path) -> Option<PathBuf> {
+    let sanitization = PathSanitizationConfig::default();
+    let relative = entry
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(match name.to_str() {
+                Some(name) => PathBuf::from(sanitize_filename(name, &sanitization)),
+                None => PathBuf::from(name),
+            }),
+            _ => None,
+        })
+        .collect::<PathBuf>();
+
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+
+    Some(long_path(&dest_path.join(relative)))
+}
+
+/// Detect archive type by file extension, falling back to the file's signature
 ///
 /// Returns the archive type based on the file extension.
-/// Supports RAR (.rar, .r00), 7z (.7z), and ZIP (.zip) formats.
+/// Supports RAR (.rar, .r00, .cbr), 7z (.7z), ZIP (.zip, .cbz) and Zstandard (.zst)
+/// formats. Files with any other extension, or none, are identified by
+/// [`sniff_archive_type`], so obfuscated posts named `.bin` or without an extension
+/// are still recognised.
 pub fn detect_archive_type(path: &Path) -> Option<ArchiveType> {
+    archive_type_from_extension(path).or_else(|| sniff_archive_type(path))
+}
+
+/// Archive type implied by the file extension alone
+pub(crate) fn archive_type_from_extension(path: &Path) -> Option<ArchiveType> {
     let ext = path.extension()?.to_str()?.to_lowercase();
 
     match ext.as_str() {
-        "rar" | "r00" => Some(ArchiveType::Rar),
+        "rar" | "r00" | "cbr" => Some(ArchiveType::Rar),
         "7z" => Some(ArchiveType::SevenZip),
-        "zip" => Some(ArchiveType::Zip),
+        "zip" | "cbz" => Some(ArchiveType::Zip),
+        "zst" => Some(ArchiveType::Zstd),
         _ => None,
     }
 }
 
+/// Detect archive type from the signature at the start of the file
+///
+/// Returns `None` when the file can't be read or starts with no known signature.
+/// Obfuscated releases often ship archives under misleading extensions, so the
+/// signature is trusted over the extension when extracting.
+pub fn sniff_archive_type(path: &Path) -> Option<ArchiveType> {
+    archive_type_from_signature(&read_header(path)?)
+}
+
+/// Read up to [`HEADER_LEN`] bytes from the start of a file
+fn read_header(path: &Path) -> Option<Vec<u8>> {
+    use std::io::Read;
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    std::fs::File::open(path)
+        .ok()?
+        .take(HEADER_LEN as u64)
+        .read_to_end(&mut header)
+        .ok()?;
+    Some(header)
+}
+
+// RAR 1.5-4.x ends "\x1a\x07\x00" and RAR 5 "\x1a\x07\x01\x00"; both share this prefix
+const RAR_SIGNATURE: &[u8] = b"Rar!\x1a\x07";
+
+/// Match the leading bytes of a file against known archive signatures
+fn archive_type_from_signature(header: &[u8]) -> Option<ArchiveType> {
+    const SEVEN_ZIP: &[u8] = b"7z\xbc\xaf\x27\x1c";
+    // Local file header, or the end-of-central-directory record of an empty archive
+    const ZIP: &[u8] = b"PK\x03\x04";
+    const ZIP_EMPTY: &[u8] = b"PK\x05\x06";
+    const ZSTD: &[u8] = b"\x28\xb5\x2f\xfd";
+
+    if header.starts_with(RAR_SIGNATURE) {
+        Some(ArchiveType::Rar)
+    } else if header.starts_with(SEVEN_ZIP) {
+        Some(ArchiveType::SevenZip)
+    } else if header.starts_with(ZIP) || header.starts_with(ZIP_EMPTY) {
+        Some(ArchiveType::Zip)
+    } else if header.starts_with(ZSTD) {
+        Some(ArchiveType::Zstd)
+    } else {
+        None
+    }
+}
+
+/// Archive type of a file whose extension hides that it's an archive
+///
+/// Only files without an archive extension are checked, for a RAR, 7z or Zstandard
+/// signature. RAR volumes after the first are skipped since they're extracted
+/// through the first. ZIP signatures aren't trusted on their own since documents,
+/// e-books and Java archives are ZIP files too.
+fn disguised_archive_type(path: &Path) -> Option<ArchiveType> {
+    if archive_type_from_extension(path).is_some() {
+        return None;
+    }
+    let header = read_header(path)?;
+    match archive_type_from_signature(&header)? {
+        ArchiveType::Rar if is_first_rar_volume(&header) => Some(ArchiveType::Rar),
+        archive_type @ (ArchiveType::SevenZip | ArchiveType::Zstd) => Some(archive_type),
+        ArchiveType::Rar | ArchiveType::Zip | ArchiveType::Custom(_) => None,
+    }
+}
+
+/// Detect archives in a directory whose names don't say they're archives
+///
+/// Complements the extension-based `detect_*_files` scans of each extractor for
+/// obfuscated posts, such as RAR sets whose volumes are named `.bin` or have no
+/// extension. See [`disguised_archive_type`] for which files qualify.
+pub fn detect_disguised_archives(download_path: &Path) -> Result<Vec<PathBuf>> {
+    debug!(?download_path, "detecting archives by signature");
+
+    let entries = std::fs::read_dir(download_path).map_err(|e| {
+        Error::Io(std::io::Error::other(format!(
+            "failed to read directory: {}",
+            e
+        )))
+    })?;
+
+    let mut archives = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| {
+            Error::Io(std::io::Error::other(format!(
+                "failed to read entry: {}",
+                e
+            )))
+        })?;
+        let path = entry.path();
+        if path.is_file() && disguised_archive_type(&path).is_some() {
+            archives.push(path);
+        }
+    }
+
+    debug!("found {} archive(s) by signature", archives.len());
+    Ok(archives)
+}
+
+/// Whether an extracted file should be extracted again as a nested archive
+///
+/// Files with one of the configured `archive_extensions` always are, as are
+/// archives hidden behind a misleading name (see [`disguised_archive_type`]).
+/// Comic archives (.cbr/.cbz) are only extracted when listed in `archive_extensions`.
+/// Files a registered extractor handles always are.
+fn is_nested_archive(
+    path: &Path,
+    archive_extensions: &[String],
+    extractors: &ExtractorRegistry,
+) -> bool {
+    is_archive(path, archive_extensions)
+        || disguised_archive_type(path).is_some()
+        || extractors.find(path).is_some()
+}
+
 /// Check if a file is an archive based on its extension
 ///
 /// Uses the configured list of archive extensions to determine if a file
@@ -180,6 +334,8 @@ pub fn is_archive(path: &Path, archive_extensions: &[String]) -> bool {
 /// * `passwords` - List of passwords to try
 /// * `db` - Database for password caching
 /// * `config` - Extraction configuration (recursion depth, extensions)
+/// * `cli` - External `unrar`/`7z` extractor for RAR archives, if available
+/// * `extractors` - Registered extractors, tried before the built-in ones
 /// * `current_depth` - Current recursion depth (0 for initial call)
 ///
 /// # Returns
@@ -188,12 +344,12 @@ pub fn is_archive(path: &Path, archive_extensions: &[String]) -> bool {
 ///
 /// # Example
 /// ```no_run
-/// use usenet_dl::extraction::{extract_recursive, PasswordList};
+/// use usenet_dl::extraction::{extract_recursive, ExtractorRegistry, PasswordList};
 /// use usenet_dl::config::ExtractionConfig;
 /// use std::path::PathBuf;
 ///
 /// # async fn example(db: &usenet_dl::db::Database) -> usenet_dl::error::Result<()> {
-/// let passwords = PasswordList::collect(None, Some("pass123"), None, None, true);
+/// let passwords = PasswordList::collect(None, Some("pass123"), None, None, true, None);
 /// let config = ExtractionConfig::default();
 /// let files = extract_recursive(
 ///     1,
@@ -202,12 +358,15 @@ pub fn is_archive(path: &Path, archive_extensions: &[String]) -> bool {
 ///     &passwords,
 ///     db,
 ///     &config,
+///     None,
+///     &ExtractorRegistry::new(),
 ///     0,
 /// ).await?;
 /// println!("Extracted {} files (including nested)", files.len());
 /// # Ok(())
 /// # }
 /// ```
+#[allow(clippy::too_many_arguments)]
 pub fn extract_recursive<'a>(
     download_id: DownloadId,
     archive_path: &'a Path,
@@ -215,6 +374,8 @@ pub fn extract_recursive<'a>(
     passwords: &'a PasswordList,
     db: &'a Database,
     config: &'a ExtractionConfig,
+    cli: Option<&'a CliRarExtractor>,
+    extractors: &'a ExtractorRegistry,
     current_depth: u32,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<PathBuf>>> + Send + 'a>> {
     Box::pin(async move {
@@ -228,10 +389,32 @@ pub fn extract_recursive<'a>(
             config.max_recursion_depth
         );
 
-        // Extract the archive
-        let extracted =
-            crate::extraction::extract_archive(download_id, archive_path, dest_path, passwords, db)
-                .await?;
+        // Extract the archive, with a registered extractor if one handles it
+        let extracted = match extractors.find(archive_path) {
+            Some(extractor) => {
+                debug!(
+                    download_id = download_id.0,
+                    ?archive_path,
+                    extractor = extractor.name(),
+                    "extracting with registered extractor"
+                );
+                extractor
+                    .extract(download_id, archive_path, dest_path, passwords)
+                    .await?
+            }
+            None => {
+                crate::extraction::extract_archive_with_cli(
+                    download_id,
+                    archive_path,
+                    dest_path,
+                    passwords,
+                    db,
+                    cli,
+                    config.prefer_cli,
+                )
+                .await?
+            }
+        };
 
         info!(
             download_id = download_id.0,
@@ -258,7 +441,7 @@ pub fn extract_recursive<'a>(
 
         // Check each extracted file to see if it's an archive
         for file in &extracted {
-            if is_archive(file, &config.archive_extensions) {
+            if is_nested_archive(file, &config.archive_extensions, extractors) {
                 info!(
                     download_id = download_id.0,
                     ?file,
@@ -283,6 +466,8 @@ pub fn extract_recursive<'a>(
                     passwords,
                     db,
                     config,
+                    cli,
+                    extractors,
                     current_depth + 1,
                 )
                 .await
@@ -1,6 +1,7 @@
 use crate::db::{Database, NewDownload};
 use crate::error::{Error, PostProcessError};
-use crate::extraction::shared::extract_with_passwords_impl;
+use crate::extraction::rar_volume::is_first_rar_volume;
+use crate::extraction::shared::{entry_destination, extract_with_passwords_impl};
 use crate::extraction::*;
 use crate::types::DownloadId;
 use std::path::{Path, PathBuf};
@@ -72,22 +73,29 @@ fn create_7z_archive(archive_path: &Path, source_dir: &Path) {
 
 #[tokio::test]
 async fn test_password_list_collect_empty() {
-    let passwords = PasswordList::collect(None, None, None, None, false).await;
+    let passwords = PasswordList::collect(None, None, None, None, false, None).await;
     assert!(passwords.is_empty());
     assert_eq!(passwords.len(), 0);
 }
 
 #[tokio::test]
 async fn test_password_list_collect_single() {
-    let passwords = PasswordList::collect(Some("test123"), None, None, None, false).await;
+    let passwords = PasswordList::collect(Some("test123"), None, None, None, false, None).await;
     assert_eq!(passwords.len(), 1);
     assert_eq!(passwords.iter().next().unwrap(), "test123");
 }
 
 #[tokio::test]
 async fn test_password_list_collect_multiple_sources() {
-    let passwords =
-        PasswordList::collect(Some("cached"), Some("download"), Some("nzb"), None, false).await;
+    let passwords = PasswordList::collect(
+        Some("cached"),
+        Some("download"),
+        Some("nzb"),
+        None,
+        false,
+        None,
+    )
+    .await;
     assert_eq!(passwords.len(), 3);
     let password_vec: Vec<&str> = passwords.iter().map(|s| s.as_str()).collect();
     assert_eq!(password_vec[0], "cached");
@@ -104,6 +112,7 @@ async fn test_password_list_collect_deduplication() {
         Some("unique"),
         None,
         false,
+        None,
     )
     .await;
     assert_eq!(passwords.len(), 2);
@@ -114,18 +123,44 @@ async fn test_password_list_collect_deduplication() {
 
 #[tokio::test]
 async fn test_password_list_collect_with_empty() {
-    let passwords = PasswordList::collect(Some("test"), None, None, None, true).await;
+    let passwords = PasswordList::collect(Some("test"), None, None, None, true, None).await;
     assert_eq!(passwords.len(), 2);
     let password_vec: Vec<&str> = passwords.iter().map(|s| s.as_str()).collect();
     assert_eq!(password_vec[0], "test");
     assert_eq!(password_vec[1], "");
 }
 
+#[tokio::test]
+async fn test_password_list_splits_semicolon_lists() {
+    // Download and NZB passwords may list several passwords; the cached one is literal
+    let passwords = PasswordList::collect(
+        Some("cached;literal"),
+        Some("first;second;"),
+        Some("second;nzb"),
+        None,
+        false,
+        None,
+    )
+    .await;
+    let password_vec: Vec<&str> = passwords.iter().map(|s| s.as_str()).collect();
+    assert_eq!(
+        password_vec,
+        vec!["cached;literal", "first", "second", "nzb"]
+    );
+}
+
 #[tokio::test]
 async fn test_password_list_priority_order() {
     // Cached should come first, then download, then nzb
-    let passwords =
-        PasswordList::collect(Some("cached"), Some("download"), Some("nzb"), None, true).await;
+    let passwords = PasswordList::collect(
+        Some("cached"),
+        Some("download"),
+        Some("nzb"),
+        None,
+        true,
+        None,
+    )
+    .await;
     assert_eq!(passwords.len(), 4);
     let password_vec: Vec<&str> = passwords.iter().map(|s| s.as_str()).collect();
     assert_eq!(password_vec[0], "cached"); // Highest priority
@@ -149,7 +184,8 @@ async fn test_password_list_from_file() {
     temp_file.flush().unwrap();
 
     // Test with just file passwords
-    let passwords = PasswordList::collect(None, None, None, Some(temp_file.path()), false).await;
+    let passwords =
+        PasswordList::collect(None, None, None, Some(temp_file.path()), false, None).await;
     assert_eq!(passwords.len(), 4);
     let password_vec: Vec<&str> = passwords.iter().map(|s| s.as_str()).collect();
     assert_eq!(password_vec[0], "password1");
@@ -177,6 +213,7 @@ async fn test_password_list_file_with_priority() {
         Some("nzb"),
         Some(temp_file.path()),
         true,
+        None,
     )
     .await;
 
@@ -192,6 +229,138 @@ async fn test_password_list_file_with_priority() {
     assert_eq!(password_vec[5], ""); // Empty last
 }
 
+/// Provider returning fixed passwords for one release name
+struct FixedProvider {
+    release: &'static str,
+    passwords: Vec<&'static str>,
+}
+
+#[async_trait::async_trait]
+impl PasswordProvider for FixedProvider {
+    async fn passwords(&self, release_name: &str) -> crate::Result<Vec<String>> {
+        if release_name != self.release {
+            return Err(Error::Other(format!("unknown release {}", release_name)));
+        }
+        Ok(self.passwords.iter().map(|s| s.to_string()).collect())
+    }
+}
+
+#[tokio::test]
+async fn test_password_list_provider_between_nzb_and_file() {
+    use std::io::Write;
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(temp_file, "file_pw").unwrap();
+    writeln!(temp_file, "looked_up").unwrap();
+    temp_file.flush().unwrap();
+
+    let provider = FixedProvider {
+        release: "Some.Release",
+        passwords: vec!["looked_up", "cached", ""],
+    };
+    let passwords = PasswordList::collect(
+        Some("cached"),
+        None,
+        Some("nzb"),
+        Some(temp_file.path()),
+        true,
+        Some((&provider, "Some.Release")),
+    )
+    .await;
+
+    let password_vec: Vec<&str> = passwords.iter().map(|s| s.as_str()).collect();
+    assert_eq!(
+        password_vec,
+        vec!["cached", "nzb", "looked_up", "file_pw", ""]
+    );
+}
+
+#[tokio::test]
+async fn test_password_list_ignores_failing_provider() {
+    let provider = FixedProvider {
+        release: "Other.Release",
+        passwords: vec!["never"],
+    };
+    let passwords = PasswordList::collect(
+        Some("cached"),
+        None,
+        None,
+        None,
+        false,
+        Some((&provider, "Some.Release")),
+    )
+    .await;
+
+    let password_vec: Vec<&str> = passwords.iter().map(|s| s.as_str()).collect();
+    assert_eq!(password_vec, vec!["cached"]);
+}
+
+#[tokio::test]
+async fn test_http_password_provider_response_formats() {
+    use axum::response::IntoResponse;
+    use axum::{Json, Router, extract::Path as UrlPath, extract::Query, routing::get};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    let app = Router::new()
+        .route(
+            "/by-name/:name",
+            get(|UrlPath(name): UrlPath<String>| async move {
+                Json(serde_json::json!([format!("{}-pw", name)]))
+            }),
+        )
+        .route(
+            "/search",
+            get(|Query(query): Query<HashMap<String, String>>| async move {
+                match query.get("name").map(String::as_str) {
+                    Some("Known Release") => {
+                        Json(serde_json::json!({"passwords": ["a", "b"]})).into_response()
+                    }
+                    _ => axum::http::StatusCode::NOT_FOUND.into_response(),
+                }
+            }),
+        )
+        .route("/plain", get(|| async { "one\n\n  two  \n" }))
+        .route(
+            "/broken",
+            get(|| async { axum::http::StatusCode::INTERNAL_SERVER_ERROR }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let lookup = |path: &str| {
+        HttpPasswordProvider::new(format!("{}{}", base, path), Duration::from_secs(5)).unwrap()
+    };
+
+    assert_eq!(
+        lookup("/by-name/{name}")
+            .passwords("Some Release")
+            .await
+            .unwrap(),
+        vec!["Some Release-pw"]
+    );
+    assert_eq!(
+        lookup("/search").passwords("Known Release").await.unwrap(),
+        vec!["a", "b"]
+    );
+    assert!(
+        lookup("/search")
+            .passwords("Unknown")
+            .await
+            .unwrap()
+            .is_empty(),
+        "404 means no passwords are known"
+    );
+    assert_eq!(
+        lookup("/plain").passwords("Any").await.unwrap(),
+        vec!["one", "two"]
+    );
+    assert!(lookup("/broken").passwords("Any").await.is_err());
+}
+
 #[test]
 fn test_detect_rar_files_with_rar() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -412,7 +581,7 @@ async fn test_extract_archive_unknown_type() {
 
     let temp_db = NamedTempFile::new().unwrap();
     let db = Database::new(temp_db.path()).await.unwrap();
-    let passwords = PasswordList::collect(None, None, None, None, false).await;
+    let passwords = PasswordList::collect(None, None, None, None, false, None).await;
 
     // Try to extract a non-archive file
     let result = extract_archive(
@@ -441,7 +610,7 @@ async fn test_extract_archive_case_insensitive() {
 
     let temp_db = NamedTempFile::new().unwrap();
     let db = Database::new(temp_db.path()).await.unwrap();
-    let passwords = PasswordList::collect(None, None, None, None, false).await;
+    let passwords = PasswordList::collect(None, None, None, None, false, None).await;
 
     // Test uppercase extensions are handled correctly
     let result = extract_archive(
@@ -490,6 +659,7 @@ async fn test_7z_password_list_integration() {
         Some("nzb_password"),
         None,
         true,
+        None,
     )
     .await;
 
@@ -507,6 +677,7 @@ async fn test_7z_password_list_integration() {
         None,
         None,
         false,
+        None,
     )
     .await;
 
@@ -525,7 +696,7 @@ async fn test_zip_password_list_integration() {
     let _db = Database::new(temp_db.path()).await.unwrap();
 
     // Create a password list with multiple passwords
-    let passwords = PasswordList::collect(None, Some("secret123"), None, None, true).await;
+    let passwords = PasswordList::collect(None, Some("secret123"), None, None, true, None).await;
 
     // Verify password list has expected passwords
     let password_vec: Vec<&str> = passwords.iter().map(|s| s.as_str()).collect();
@@ -549,8 +720,15 @@ async fn test_7z_password_priority_order() {
     let _db = Database::new(temp_db.path()).await.unwrap();
 
     // All password sources
-    let passwords =
-        PasswordList::collect(Some("cached"), Some("download"), Some("nzb"), None, true).await;
+    let passwords = PasswordList::collect(
+        Some("cached"),
+        Some("download"),
+        Some("nzb"),
+        None,
+        true,
+        None,
+    )
+    .await;
 
     let password_vec: Vec<&str> = passwords.iter().map(|s| s.as_str()).collect();
     assert_eq!(password_vec.len(), 4);
@@ -574,6 +752,7 @@ async fn test_zip_password_priority_order() {
         Some("nzb_pw"),
         None,
         true,
+        None,
     )
     .await;
 
@@ -629,6 +808,7 @@ async fn test_7z_password_deduplication() {
         Some("password123"), // Duplicate
         None,
         false,
+        None,
     )
     .await;
 
@@ -652,6 +832,7 @@ async fn test_zip_password_deduplication() {
         None,
         None,
         true, // Empty password
+        None,
     )
     .await;
 
@@ -750,7 +931,7 @@ async fn test_extract_recursive_depth_limit() {
 
     let temp_db = NamedTempFile::new().unwrap();
     let _db = Database::new(temp_db.path()).await.unwrap();
-    let passwords = PasswordList::collect(None, None, None, None, false).await;
+    let passwords = PasswordList::collect(None, None, None, None, false, None).await;
 
     let config = ExtractionConfig {
         max_recursion_depth: 0, // Don't recurse at all
@@ -766,6 +947,8 @@ async fn test_extract_recursive_depth_limit() {
         &passwords,
         &_db,
         &config,
+        None,
+        &ExtractorRegistry::new(),
         0,
     )
     .await;
@@ -780,7 +963,7 @@ async fn test_extract_recursive_respects_depth() {
 
     let temp_db = NamedTempFile::new().unwrap();
     let _db = Database::new(temp_db.path()).await.unwrap();
-    let passwords = PasswordList::collect(None, None, None, None, false).await;
+    let passwords = PasswordList::collect(None, None, None, None, false, None).await;
 
     let config = ExtractionConfig {
         max_recursion_depth: 2, // Allow 2 levels of nesting
@@ -796,6 +979,8 @@ async fn test_extract_recursive_respects_depth() {
         &passwords,
         &_db,
         &config,
+        None,
+        &ExtractorRegistry::new(),
         2, // At max depth
     )
     .await;
@@ -810,7 +995,7 @@ async fn test_extract_recursive_custom_extensions() {
 
     let temp_db = NamedTempFile::new().unwrap();
     let _db = Database::new(temp_db.path()).await.unwrap();
-    let _passwords = PasswordList::collect(None, None, None, None, false);
+    let _passwords = PasswordList::collect(None, None, None, None, false, None);
 
     let config = ExtractionConfig {
         archive_extensions: vec!["rar".to_string()], // Only RAR files
@@ -839,7 +1024,7 @@ async fn test_extract_recursive_no_passwords() {
 
     let temp_db = NamedTempFile::new().unwrap();
     let _db = Database::new(temp_db.path()).await.unwrap();
-    let passwords = PasswordList::collect(None, None, None, None, false).await;
+    let passwords = PasswordList::collect(None, None, None, None, false, None).await;
 
     let config = ExtractionConfig::default();
 
@@ -851,6 +1036,8 @@ async fn test_extract_recursive_no_passwords() {
         &passwords,
         &_db,
         &config,
+        None,
+        &ExtractorRegistry::new(),
         0,
     )
     .await;
@@ -866,7 +1053,7 @@ async fn test_extract_recursive_with_passwords() {
 
     let temp_db = NamedTempFile::new().unwrap();
     let _db = Database::new(temp_db.path()).await.unwrap();
-    let passwords = PasswordList::collect(None, Some("test123"), None, None, true).await;
+    let passwords = PasswordList::collect(None, Some("test123"), None, None, true, None).await;
 
     let config = ExtractionConfig::default();
 
@@ -879,6 +1066,8 @@ async fn test_extract_recursive_with_passwords() {
         &passwords,
         &_db,
         &config,
+        None,
+        &ExtractorRegistry::new(),
         0,
     )
     .await;
@@ -961,7 +1150,7 @@ async fn extract_recursive_at_max_depth_does_not_recurse_into_nested_archives()
     let temp_db = NamedTempFile::new().unwrap();
     let db = Database::new(temp_db.path()).await.unwrap();
     let download_id = db.insert_download(&test_download()).await.unwrap();
-    let passwords = PasswordList::collect(None, None, None, None, true).await;
+    let passwords = PasswordList::collect(None, None, None, None, true, None).await;
 
     // Create a nested ZIP (outer.zip containing inner.zip containing deep_secret.txt)
     let outer_zip = create_nested_zip(temp_dir.path());
@@ -979,6 +1168,8 @@ async fn extract_recursive_at_max_depth_does_not_recurse_into_nested_archives()
         &passwords,
         &db,
         &config,
+        None,
+        &ExtractorRegistry::new(),
         0, // Starting at depth 0 which equals max_recursion_depth
     )
     .await
@@ -1013,7 +1204,7 @@ async fn extract_recursive_below_max_depth_recurses_into_nested_archives() {
     let temp_db = NamedTempFile::new().unwrap();
     let db = Database::new(temp_db.path()).await.unwrap();
     let download_id = db.insert_download(&test_download()).await.unwrap();
-    let passwords = PasswordList::collect(None, None, None, None, true).await;
+    let passwords = PasswordList::collect(None, None, None, None, true, None).await;
 
     // Create a nested ZIP
     let outer_zip = create_nested_zip(temp_dir.path());
@@ -1031,6 +1222,8 @@ async fn extract_recursive_below_max_depth_recurses_into_nested_archives() {
         &passwords,
         &db,
         &config,
+        None,
+        &ExtractorRegistry::new(),
         0, // Starting at depth 0, max is 2 — should recurse
     )
     .await
@@ -1494,7 +1687,7 @@ async fn shared_extract_succeeds_without_password_on_first_try() {
     let db = Database::new(temp_db.path()).await.unwrap();
     let download_id = db.insert_download(&test_download()).await.unwrap();
 
-    let passwords = PasswordList::collect(None, None, None, None, true).await;
+    let passwords = PasswordList::collect(None, None, None, None, true, None).await;
     // passwords = [""]
 
     let archive = PathBuf::from("/fake/archive.zip");
@@ -1538,7 +1731,8 @@ async fn shared_extract_tries_passwords_until_correct_one_found() {
     let db = Database::new(temp_db.path()).await.unwrap();
     let download_id = db.insert_download(&test_download()).await.unwrap();
 
-    let passwords = PasswordList::collect(Some("wrong1"), Some("correct"), None, None, false).await;
+    let passwords =
+        PasswordList::collect(Some("wrong1"), Some("correct"), None, None, false, None).await;
     // passwords = ["wrong1", "correct"]
 
     let archive = PathBuf::from("/fake/archive.zip");
@@ -1584,8 +1778,15 @@ async fn shared_extract_all_passwords_wrong_returns_all_passwords_failed() {
     let db = Database::new(temp_db.path()).await.unwrap();
     let download_id = db.insert_download(&test_download()).await.unwrap();
 
-    let passwords =
-        PasswordList::collect(Some("wrong1"), Some("wrong2"), Some("wrong3"), None, false).await;
+    let passwords = PasswordList::collect(
+        Some("wrong1"),
+        Some("wrong2"),
+        Some("wrong3"),
+        None,
+        false,
+        None,
+    )
+    .await;
 
     let archive = PathBuf::from("/fake/archive.rar");
     let dest = PathBuf::from("/fake/dest");
@@ -1616,7 +1817,7 @@ async fn shared_extract_empty_password_list_returns_no_passwords_available() {
     let db = Database::new(temp_db.path()).await.unwrap();
     let download_id = db.insert_download(&test_download()).await.unwrap();
 
-    let passwords = PasswordList::collect(None, None, None, None, false).await;
+    let passwords = PasswordList::collect(None, None, None, None, false, None).await;
     assert!(passwords.is_empty());
 
     let archive = PathBuf::from("/fake/archive.7z");
@@ -1644,7 +1845,7 @@ async fn shared_extract_non_password_error_propagated_immediately() {
     let db = Database::new(temp_db.path()).await.unwrap();
     let download_id = db.insert_download(&test_download()).await.unwrap();
 
-    let passwords = PasswordList::collect(Some("pw1"), Some("pw2"), None, None, false).await;
+    let passwords = PasswordList::collect(Some("pw1"), Some("pw2"), None, None, false, None).await;
 
     let archive = PathBuf::from("/fake/archive.zip");
     let dest = PathBuf::from("/fake/dest");
@@ -1701,7 +1902,8 @@ async fn zip_extract_with_passwords_succeeds_with_correct_password() {
     create_encrypted_zip(&archive_path, "payload.txt", b"top secret", b"s3cret");
 
     let dest = temp_dir.path().join("extracted");
-    let passwords = PasswordList::collect(Some("wrong"), Some("s3cret"), None, None, false).await;
+    let passwords =
+        PasswordList::collect(Some("wrong"), Some("s3cret"), None, None, false, None).await;
 
     let files =
         ZipExtractor::extract_with_passwords(download_id, &archive_path, &dest, &passwords, &db)
@@ -1734,7 +1936,7 @@ async fn sevenz_extract_with_passwords_succeeds_with_empty_password() {
     create_7z_archive(&archive_path, &src_dir);
 
     let dest = temp_dir.path().join("extracted");
-    let passwords = PasswordList::collect(None, None, None, None, true).await;
+    let passwords = PasswordList::collect(None, None, None, None, true, None).await;
 
     let files = SevenZipExtractor::extract_with_passwords(
         download_id,
@@ -1791,3 +1993,579 @@ fn is_archive_dotfile_without_extension_returns_false() {
         "dotfile with no extension should not match"
     );
 }
+
+// -- entry_destination --
+
+#[test]
+fn entry_destination_keeps_only_normal_components() {
+    let dest = Path::new("/extract");
+
+    assert_eq!(
+        entry_destination(dest, Path::new("Season 1/episode.mkv")),
+        Some(PathBuf::from("/extract/Season 1/episode.mkv"))
+    );
+    assert_eq!(
+        entry_destination(dest, Path::new("../../etc/passwd")),
+        Some(PathBuf::from("/extract/etc/passwd"))
+    );
+    assert_eq!(entry_destination(dest, Path::new("..")), None);
+}
+
+#[cfg(windows)]
+#[test]
+fn entry_destination_applies_windows_naming_rules() {
+    let dest = Path::new(r"C:\extract");
+
+    assert_eq!(
+        entry_destination(dest, Path::new(r"Disc. \CON.nfo")),
+        Some(PathBuf::from(r"C:\extract\Disc\CON_.nfo"))
+    );
+}
+
+// -- CliRarExtractor --
+
+#[test]
+fn parse_cli_output_treats_warnings_as_success() {
+    let archive = Path::new("test.rar");
+
+    assert!(cli::parse_cli_output(CliTool::Unrar, Some(0), "All OK", archive).is_ok());
+    assert!(cli::parse_cli_output(CliTool::SevenZip, Some(1), "WARNING", archive).is_ok());
+}
+
+#[test]
+fn parse_cli_output_detects_wrong_passwords() {
+    let archive = Path::new("test.rar");
+
+    let cases = [
+        (
+            CliTool::Unrar,
+            Some(11),
+            "The specified password is incorrect.",
+        ),
+        (CliTool::Unrar, Some(11), ""),
+        (
+            CliTool::Unrar,
+            Some(3),
+            "test.rar - checksum error in the encrypted file movie.mkv. Corrupt file or wrong password.",
+        ),
+        (
+            CliTool::SevenZip,
+            Some(2),
+            "ERROR: Wrong password : movie.mkv",
+        ),
+    ];
+    for (tool, code, output) in cases {
+        let result = cli::parse_cli_output(tool, code, output, archive);
+        assert!(
+            matches!(
+                result,
+                Err(Error::PostProcess(PostProcessError::WrongPassword { .. }))
+            ),
+            "{:?} exit {:?} with {:?} should be a wrong password, got {:?}",
+            tool,
+            code,
+            output,
+            result
+        );
+    }
+}
+
+#[test]
+fn parse_cli_output_reports_the_error_line() {
+    let output = "UNRAR 7.00 freeware\n\nExtracting from test.rar\n\
+                  test.rar is not RAR archive\nNo files to extract\n";
+
+    match cli::parse_cli_output(CliTool::Unrar, Some(10), output, Path::new("test.rar")) {
+        Err(Error::PostProcess(PostProcessError::ExtractionFailed { reason, .. })) => {
+            assert_eq!(reason, "Unrar exited with code 10: No files to extract");
+        }
+        other => panic!("expected ExtractionFailed, got {:?}", other),
+    }
+
+    match cli::parse_cli_output(
+        CliTool::SevenZip,
+        Some(2),
+        "ERROR: test.rar\nCannot open the file as archive\n\nErrors: 1\n",
+        Path::new("test.rar"),
+    ) {
+        Err(Error::PostProcess(PostProcessError::ExtractionFailed { reason, .. })) => {
+            assert_eq!(reason, "SevenZip exited with code 2: Errors: 1");
+        }
+        other => panic!("expected ExtractionFailed, got {:?}", other),
+    }
+}
+
+/// Write a fake `unrar` that extracts `out.txt` only with the password "secret"
+#[cfg(unix)]
+fn create_fake_unrar(dir: &Path) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join("unrar");
+    std::fs::write(
+        &path,
+        "#!/bin/sh\n\
+         # unrar x -o+ -y -p<password> <archive> <dest>/\n\
+         if [ \"$4\" = \"-psecret\" ]; then\n\
+         \x20 echo extracted > \"$6/out.txt\"\n\
+         \x20 echo \"All OK\"\n\
+         \x20 exit 0\n\
+         fi\n\
+         echo \"The specified password is incorrect.\"\n\
+         exit 11\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn cli_extractor_tries_passwords_until_one_works() {
+    let temp_dir = TempDir::new().unwrap();
+    let cli = CliRarExtractor::new(create_fake_unrar(temp_dir.path()), CliTool::Unrar);
+    let archive = temp_dir.path().join("test.rar");
+    std::fs::write(&archive, b"not really a rar").unwrap();
+    let dest = temp_dir.path().join("extracted");
+
+    let db_file = NamedTempFile::new().unwrap();
+    let db = Database::new(db_file.path()).await.unwrap();
+    let download_id = db.insert_download(&test_download()).await.unwrap();
+    let passwords =
+        PasswordList::collect(None, Some("wrong;secret"), None, None, false, None).await;
+
+    let files = cli
+        .extract_with_passwords(download_id, &archive, &dest, &passwords, &db)
+        .await
+        .unwrap();
+
+    assert_eq!(files, vec![dest.join("out.txt")]);
+    assert_eq!(
+        db.get_cached_password(download_id).await.unwrap(),
+        Some("secret".to_string())
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn extract_archive_falls_back_to_cli_for_rar() {
+    let temp_dir = TempDir::new().unwrap();
+    let cli = CliRarExtractor::new(create_fake_unrar(temp_dir.path()), CliTool::Unrar);
+    let archive = temp_dir.path().join("test.rar");
+    std::fs::write(&archive, b"not really a rar").unwrap();
+    let dest = temp_dir.path().join("extracted");
+
+    let db_file = NamedTempFile::new().unwrap();
+    let db = Database::new(db_file.path()).await.unwrap();
+    let download_id = db.insert_download(&test_download()).await.unwrap();
+    let passwords = PasswordList::collect(None, Some("secret"), None, None, false, None).await;
+
+    // The built-in extractor can't open the file, so the binary gets a turn
+    let files = extract_archive_with_cli(
+        download_id,
+        &archive,
+        &dest,
+        &passwords,
+        &db,
+        Some(&cli),
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(files, vec![dest.join("out.txt")]);
+
+    // Without a binary the built-in failure is returned as-is
+    let result = extract_archive(download_id, &archive, &dest, &passwords, &db).await;
+    assert!(result.is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn cli_extractor_kills_the_tool_once_cancelled() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let tool = temp_dir.path().join("unrar");
+    let finished = temp_dir.path().join("finished");
+    std::fs::write(
+        &tool,
+        format!("#!/bin/sh\nsleep 30\ntouch {}\n", finished.display()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let cancel = tokio_util::sync::CancellationToken::new();
+    let cli = CliRarExtractor::new(tool, CliTool::Unrar).with_cancellation(cancel.clone());
+    let archive = temp_dir.path().join("test.rar");
+    std::fs::write(&archive, b"not really a rar").unwrap();
+    let dest = temp_dir.path().join("extracted");
+
+    let extraction = tokio::task::spawn_blocking(move || cli.try_extract(&archive, "", &dest));
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    cancel.cancel();
+    let result = tokio::time::timeout(std::time::Duration::from_secs(5), extraction)
+        .await
+        .unwrap()
+        .unwrap();
+
+    match result {
+        Err(Error::PostProcess(PostProcessError::ExtractionFailed { reason, .. })) => {
+            assert_eq!(reason, "Unrar was cancelled");
+        }
+        other => panic!("expected ExtractionFailed, got {:?}", other),
+    }
+    assert!(!finished.exists());
+}
+
+// ===========================================================================
+// Zstandard, comic archives and signature sniffing
+// ===========================================================================
+
+#[test]
+fn detect_archive_type_comic_and_zstd_extensions() {
+    use crate::types::ArchiveType;
+    assert_eq!(
+        detect_archive_type(Path::new("issue.cbr")),
+        Some(ArchiveType::Rar)
+    );
+    assert_eq!(
+        detect_archive_type(Path::new("issue.CBZ")),
+        Some(ArchiveType::Zip)
+    );
+    assert_eq!(
+        detect_archive_type(Path::new("movie.mkv.zst")),
+        Some(ArchiveType::Zstd)
+    );
+}
+
+#[test]
+fn sniff_archive_type_ignores_extension() {
+    use crate::types::ArchiveType;
+    let temp_dir = TempDir::new().unwrap();
+
+    let zip = temp_dir.path().join("a3f9c1.bin");
+    create_zip_archive(&zip, "file.txt", b"zip content");
+    assert_eq!(sniff_archive_type(&zip), Some(ArchiveType::Zip));
+
+    let zst = temp_dir.path().join("payload.rar");
+    std::fs::write(&zst, ::zstd::encode_all(&b"zstd content"[..], 0).unwrap()).unwrap();
+    assert_eq!(sniff_archive_type(&zst), Some(ArchiveType::Zstd));
+
+    let rar = temp_dir.path().join("episode.mkv");
+    std::fs::write(&rar, b"Rar!\x1a\x07\x01\x00rest").unwrap();
+    assert_eq!(sniff_archive_type(&rar), Some(ArchiveType::Rar));
+
+    let plain = temp_dir.path().join("video.zip");
+    std::fs::write(&plain, b"not an archive").unwrap();
+    assert_eq!(sniff_archive_type(&plain), None);
+
+    let short = temp_dir.path().join("short.7z");
+    std::fs::write(&short, b"7z").unwrap();
+    assert_eq!(sniff_archive_type(&short), None);
+    assert_eq!(sniff_archive_type(&temp_dir.path().join("missing")), None);
+}
+
+#[tokio::test]
+async fn extract_archive_decompresses_zstd_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_db = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_db.path()).await.unwrap();
+    let download_id = db.insert_download(&test_download()).await.unwrap();
+    let passwords = PasswordList::collect(None, None, None, None, true, None).await;
+
+    let archive = temp_dir.path().join("movie.mkv.zst");
+    std::fs::write(&archive, ::zstd::encode_all(&b"video data"[..], 3).unwrap()).unwrap();
+    let dest = temp_dir.path().join("extracted");
+
+    let files = extract_archive(download_id, &archive, &dest, &passwords, &db)
+        .await
+        .unwrap();
+
+    assert_eq!(files, vec![dest.join("movie.mkv")]);
+    assert_eq!(std::fs::read(&files[0]).unwrap(), b"video data");
+}
+
+#[tokio::test]
+async fn extract_archive_corrupt_zstd_fails_without_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_db = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_db.path()).await.unwrap();
+    let download_id = db.insert_download(&test_download()).await.unwrap();
+    let passwords = PasswordList::collect(None, None, None, None, true, None).await;
+
+    let archive = temp_dir.path().join("broken.zst");
+    let mut data = ::zstd::encode_all(&b"some data to compress"[..], 3).unwrap();
+    data.truncate(data.len() / 2);
+    std::fs::write(&archive, data).unwrap();
+    let dest = temp_dir.path().join("extracted");
+
+    let result = extract_archive(download_id, &archive, &dest, &passwords, &db).await;
+
+    assert!(matches!(
+        result,
+        Err(Error::PostProcess(
+            PostProcessError::ExtractionFailed { .. }
+        ))
+    ));
+    assert!(!dest.join("broken").exists());
+}
+
+#[tokio::test]
+async fn extract_archive_uses_signature_over_misleading_extension() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_db = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_db.path()).await.unwrap();
+    let download_id = db.insert_download(&test_download()).await.unwrap();
+    let passwords = PasswordList::collect(None, None, None, None, true, None).await;
+
+    // A ZIP named like a RAR would fail in the RAR extractor
+    let archive = temp_dir.path().join("release.rar");
+    create_zip_archive(&archive, "file.txt", b"zip inside");
+    let dest = temp_dir.path().join("extracted");
+
+    let files = extract_archive(download_id, &archive, &dest, &passwords, &db)
+        .await
+        .unwrap();
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(std::fs::read(&files[0]).unwrap(), b"zip inside");
+}
+
+#[test]
+fn detect_zst_files_finds_only_zst() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join("a.zst"), b"").unwrap();
+    std::fs::write(temp_dir.path().join("b.ZST"), b"").unwrap();
+    std::fs::write(temp_dir.path().join("c.zip"), b"").unwrap();
+    std::fs::create_dir(temp_dir.path().join("d.zst")).unwrap();
+
+    let mut found = ZstdExtractor::detect_zst_files(temp_dir.path()).unwrap();
+    found.sort();
+
+    assert_eq!(
+        found,
+        vec![temp_dir.path().join("a.zst"), temp_dir.path().join("b.ZST")]
+    );
+}
+
+#[tokio::test]
+async fn extract_recursive_sniffs_nested_archives_with_misleading_names() {
+    use crate::config::ExtractionConfig;
+
+    let temp_dir = TempDir::new().unwrap();
+    let temp_db = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_db.path()).await.unwrap();
+    let download_id = db.insert_download(&test_download()).await.unwrap();
+    let passwords = PasswordList::collect(None, None, None, None, true, None).await;
+
+    // An inner zstd stream hidden as .dat and a ZIP-based e-book that must stay intact
+    let compressed = ::zstd::encode_all(&b"hidden content"[..], 3).unwrap();
+    let book = temp_dir.path().join("book.epub");
+    create_zip_archive(&book, "chapter1.xhtml", b"<html/>");
+    let book_bytes = std::fs::read(&book).unwrap();
+    let outer = temp_dir.path().join("outer.zip");
+    create_zip_archive_multi(
+        &outer,
+        &[("payload.dat", &compressed), ("book.epub", &book_bytes)],
+    );
+    let dest = temp_dir.path().join("extracted");
+
+    let files = extract_recursive(
+        download_id,
+        &outer,
+        &dest,
+        &passwords,
+        &db,
+        &ExtractionConfig::default(),
+        None,
+        &ExtractorRegistry::new(),
+        0,
+    )
+    .await
+    .unwrap();
+
+    let nested = dest.join("nested_payload_1").join("payload.dat");
+    assert!(files.contains(&nested), "got: {files:?}");
+    assert_eq!(std::fs::read(&nested).unwrap(), b"hidden content");
+    assert!(
+        !files.iter().any(|f| f.ends_with("chapter1.xhtml")),
+        "ZIP-based documents shouldn't be extracted, got: {files:?}"
+    );
+}
+
+// ===========================================================================
+// shared.rs — signature fallback for obfuscated names
+// ===========================================================================
+
+/// RAR 4 signature plus a main archive header with the given HEAD_FLAGS
+fn rar4_header(flags: u16) -> Vec<u8> {
+    let mut header = b"Rar!\x1a\x07\x00".to_vec();
+    header.extend_from_slice(&[0x00, 0x00, 0x73]);
+    header.extend_from_slice(&flags.to_le_bytes());
+    header.extend_from_slice(&[0x0d, 0x00, 0, 0, 0, 0, 0, 0]);
+    header
+}
+
+/// RAR 5 signature plus a main archive header with the given archive flags
+fn rar5_header(archive_flags: u8, volume_number: Option<u8>) -> Vec<u8> {
+    let mut header = b"Rar!\x1a\x07\x01\x00".to_vec();
+    // CRC32, header size, type 1 (main), header flags 0
+    header.extend_from_slice(&[0, 0, 0, 0, 0x05, 0x01, 0x00, archive_flags]);
+    header.extend(volume_number);
+    header
+}
+
+#[test]
+fn is_first_rar_volume_reads_volume_flags() {
+    // RAR 4: plain archive, first volume, later volume
+    assert!(is_first_rar_volume(&rar4_header(0x0000)));
+    assert!(is_first_rar_volume(&rar4_header(0x0101)));
+    assert!(!is_first_rar_volume(&rar4_header(0x0011)));
+
+    // RAR 5: plain archive, first volume, later volume
+    assert!(is_first_rar_volume(&rar5_header(0x00, None)));
+    assert!(is_first_rar_volume(&rar5_header(0x01, None)));
+    assert!(!is_first_rar_volume(&rar5_header(0x03, Some(1))));
+
+    // Truncated headers can't rule out a first volume
+    assert!(is_first_rar_volume(b"Rar!\x1a\x07\x01\x00\x00"));
+}
+
+#[test]
+fn detect_archive_type_falls_back_to_signature() {
+    use crate::types::ArchiveType;
+    let temp_dir = TempDir::new().unwrap();
+
+    let no_extension = temp_dir.path().join("a8f3e21c");
+    std::fs::write(&no_extension, rar5_header(0x00, None)).unwrap();
+    assert_eq!(detect_archive_type(&no_extension), Some(ArchiveType::Rar));
+
+    let bin = temp_dir.path().join("a8f3e21c.bin");
+    create_zip_archive(&bin, "file.txt", b"content");
+    assert_eq!(detect_archive_type(&bin), Some(ArchiveType::Zip));
+
+    let video = temp_dir.path().join("movie.mkv");
+    std::fs::write(&video, b"\x1a\x45\xdf\xa3 matroska").unwrap();
+    assert_eq!(detect_archive_type(&video), None);
+}
+
+#[test]
+fn detect_disguised_archives_finds_first_volumes_and_skips_known_names() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    std::fs::write(dir.join("0a1b2c.bin"), rar5_header(0x01, None)).unwrap();
+    std::fs::write(dir.join("0a1b2d.bin"), rar5_header(0x03, Some(1))).unwrap();
+    std::fs::write(dir.join("9f8e7d"), rar4_header(0x0000)).unwrap();
+    std::fs::write(dir.join("release.rar"), rar4_header(0x0000)).unwrap();
+    create_zip_archive(&dir.join("document.docx"), "word/document.xml", b"<w/>");
+    std::fs::write(dir.join("movie.mkv"), b"\x1a\x45\xdf\xa3 matroska").unwrap();
+
+    let mut found = detect_disguised_archives(dir).unwrap();
+    found.sort();
+
+    assert_eq!(found, vec![dir.join("0a1b2c.bin"), dir.join("9f8e7d")]);
+}
+
+// ===========================================================================
+// registry.rs — registered extractors
+// ===========================================================================
+
+/// Extractor for `.fake` files: copies the file's contents to `<stem>` in the destination
+struct FakeExtractor;
+
+#[async_trait::async_trait]
+impl Extractor for FakeExtractor {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "fake")
+    }
+
+    async fn extract(
+        &self,
+        _download_id: DownloadId,
+        archive_path: &Path,
+        dest_path: &Path,
+        _passwords: &PasswordList,
+    ) -> crate::error::Result<Vec<PathBuf>> {
+        let stem = archive_path.file_stem().unwrap();
+        std::fs::create_dir_all(dest_path)?;
+        let out = dest_path.join(stem);
+        std::fs::copy(archive_path, &out)?;
+        Ok(vec![out])
+    }
+}
+
+#[test]
+fn registry_detect_archive_type_prefers_registered_extractors() {
+    use crate::types::ArchiveType;
+
+    let registry = ExtractorRegistry::new();
+    assert!(registry.is_empty());
+    assert_eq!(
+        registry.detect_archive_type(Path::new("file.fake")),
+        None,
+        "nothing registered yet"
+    );
+
+    registry.register(std::sync::Arc::new(FakeExtractor));
+
+    assert!(!registry.is_empty());
+    assert_eq!(
+        registry.detect_archive_type(Path::new("file.fake")),
+        Some(ArchiveType::Custom("fake".to_string()))
+    );
+    assert_eq!(
+        registry.detect_archive_type(Path::new("file.rar")),
+        Some(ArchiveType::Rar)
+    );
+    assert_eq!(registry.detect_archive_type(Path::new("file.txt")), None);
+    assert_eq!(
+        registry.clone().find(Path::new("a.fake")).unwrap().name(),
+        "fake",
+        "clones share the registered extractors"
+    );
+}
+
+#[tokio::test]
+async fn extract_recursive_uses_registered_extractors_at_every_depth() {
+    use crate::config::ExtractionConfig;
+
+    let temp_dir = TempDir::new().unwrap();
+    let temp_db = NamedTempFile::new().unwrap();
+    let db = Database::new(temp_db.path()).await.unwrap();
+    let download_id = db.insert_download(&test_download()).await.unwrap();
+    let passwords = PasswordList::collect(None, None, None, None, true, None).await;
+    let registry = ExtractorRegistry::new();
+    registry.register(std::sync::Arc::new(FakeExtractor));
+
+    // A custom archive holding a ZIP, which holds another custom archive
+    let innermost = temp_dir.path().join("innermost.fake");
+    std::fs::write(&innermost, b"custom content").unwrap();
+    let zip = temp_dir.path().join("middle.zip");
+    create_zip_archive(&zip, "innermost.fake", &std::fs::read(&innermost).unwrap());
+    let outer = temp_dir.path().join("middle.zip.fake");
+    std::fs::copy(&zip, &outer).unwrap();
+    let dest = temp_dir.path().join("extracted");
+
+    let files = extract_recursive(
+        download_id,
+        &outer,
+        &dest,
+        &passwords,
+        &db,
+        &ExtractionConfig::default(),
+        None,
+        &registry,
+        0,
+    )
+    .await
+    .unwrap();
+
+    let extracted = dest
+        .join("nested_middle_1")
+        .join("nested_innermost_2")
+        .join("innermost");
+    assert!(files.contains(&extracted), "got: {files:?}");
+    assert_eq!(std::fs::read(&extracted).unwrap(), b"custom content");
+}
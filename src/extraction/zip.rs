@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
 use super::password_list::PasswordList;
-use super::shared::extract_with_passwords_impl;
+use super::shared::{entry_destination, extract_with_passwords_impl};
 
 /// Archive extractor for ZIP files
 pub struct ZipExtractor;
@@ -104,8 +104,11 @@ impl ZipExtractor {
         archive_path: &Path,
     ) -> Result<Option<PathBuf>> {
         // Get the file path
-        let file_path = match file.enclosed_name() {
-            Some(path) => dest_path.join(path),
+        let file_path = match file
+            .enclosed_name()
+            .and_then(|path| entry_destination(dest_path, path))
+        {
+            Some(path) => path,
             None => {
                 warn!("skipping entry with unsafe path");
                 return Ok(None);
@@ -0,0 +1,111 @@
+use crate::error::{Error, PostProcessError, Result};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+use super::shared::entry_destination;
+
+/// Extractor for Zstandard-compressed files
+///
+/// A `.zst` file holds a single compressed stream rather than a set of entries, so
+/// extraction produces one file named after the archive without its `.zst` suffix.
+/// Zstandard has no encryption, so no passwords are involved.
+pub struct ZstdExtractor;
+
+impl ZstdExtractor {
+    /// Detect Zstandard-compressed files in a directory
+    pub fn detect_zst_files(download_path: &Path) -> Result<Vec<PathBuf>> {
+        debug!(?download_path, "detecting zstd files");
+
+        let entries = std::fs::read_dir(download_path).map_err(|e| {
+            Error::Io(std::io::Error::other(format!(
+                "failed to read directory: {}",
+                e
+            )))
+        })?;
+
+        let mut archives = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| {
+                Error::Io(std::io::Error::other(format!(
+                    "failed to read entry: {}",
+                    e
+                )))
+            })?;
+            let path = entry.path();
+            if path.is_file() && has_zst_extension(&path) {
+                archives.push(path);
+            }
+        }
+
+        debug!("found {} zstd file(s)", archives.len());
+        Ok(archives)
+    }
+
+    /// Decompress a Zstandard file into `dest_path`
+    pub fn extract(archive_path: &Path, dest_path: &Path) -> Result<Vec<PathBuf>> {
+        debug!(?archive_path, ?dest_path, "attempting zstd decompression");
+
+        let extraction_failed = |reason: String| {
+            Error::PostProcess(PostProcessError::ExtractionFailed {
+                archive: archive_path.to_path_buf(),
+                reason,
+            })
+        };
+
+        // "movie.mkv.zst" decompresses to "movie.mkv"; a file whose extension doesn't
+        // say zstd (detected by its signature) keeps its whole name
+        let file_name = if has_zst_extension(archive_path) {
+            archive_path.file_stem()
+        } else {
+            archive_path.file_name()
+        };
+        let output_path = file_name
+            .and_then(|name| entry_destination(dest_path, Path::new(name)))
+            .ok_or_else(|| extraction_failed("no usable output file name".to_string()))?;
+
+        std::fs::create_dir_all(dest_path).map_err(|e| {
+            Error::Io(std::io::Error::other(format!(
+                "failed to create destination: {}",
+                e
+            )))
+        })?;
+
+        let input = std::fs::File::open(archive_path).map_err(|e| {
+            Error::Io(std::io::Error::other(format!(
+                "failed to open zstd file: {}",
+                e
+            )))
+        })?;
+        let mut output = std::fs::File::create(&output_path)?;
+        if let Err(e) = zstd::stream::copy_decode(input, &mut output) {
+            drop(output);
+            let _ = std::fs::remove_file(&output_path);
+            return Err(extraction_failed(format!(
+                "failed to decompress zstd stream: {}",
+                e
+            )));
+        }
+
+        info!(?archive_path, ?output_path, "zstd decompression successful");
+        Ok(vec![output_path])
+    }
+
+    /// Decompress a Zstandard file without blocking the async runtime
+    pub async fn extract_async(archive_path: &Path, dest_path: &Path) -> Result<Vec<PathBuf>> {
+        let archive_path = archive_path.to_path_buf();
+        let dest_path = dest_path.to_path_buf();
+        tokio::task::spawn_blocking(move || Self::extract(&archive_path, &dest_path))
+            .await
+            .map_err(|e| {
+                Error::Io(std::io::Error::other(format!(
+                    "zstd decompression task failed: {}",
+                    e
+                )))
+            })?
+    }
+}
+
+fn has_zst_extension(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zst"))
+}
@@ -2,15 +2,23 @@
 //!
 //! This module provides filesystem watching capabilities to automatically import NZB files
 //! from monitored directories. It supports:
-//! - Automatic detection of new `.nzb` files
-//! - Configurable post-import actions (delete, move to processed folder, or keep)
+//! - Automatic detection of new `.nzb` files, gzipped `.nzb.gz` files, and `.zip`/`.rar`
+//!   archives holding one or more NZBs (each is imported; the archive is then handled by
+//!   the post-import action)
+//! - Configurable post-import actions (delete, move to processed folder, rename with an
+//!   `.imported` suffix, or keep)
+//! - Files that can't be imported are moved to an error folder with a reason file and
+//!   reported through [`crate::types::Event::NzbImportFailed`]
 //! - Per-folder category assignment
 //! - Non-recursive watching (only monitors the specified directory, not subdirectories)
+//! - Polling for folders where filesystem events don't fire (NFS, SMB, Docker bind mounts);
+//!   in the default `auto` mode network filesystems are polled, and a folder whose probe
+//!   file produces no event within a few seconds falls back to polling
 //!
 //! # Example
 //!
 //! ```no_run
-//! use usenet_dl::{UsenetDownloader, config::{Config, WatchFolderConfig, WatchFolderAction}};
+//! use usenet_dl::{UsenetDownloader, config::{Config, WatchFolderConfig, WatchFolderAction, WatchMode}};
 //! use usenet_dl::folder_watcher::FolderWatcher;
 //! use std::sync::Arc;
 //! use std::time::Duration;
@@ -23,7 +31,10 @@
 //!     path: "/path/to/watch/folder".into(),
 //!     after_import: WatchFolderAction::MoveToProcessed,
 //!     category: Some("movies".to_string()),
+//!     mode: WatchMode::Auto,
 //!     scan_interval: Duration::from_secs(5),
+//!     processed_dir: None,
+//!     error_dir: None,
 //! };
 //!
 //! let mut watcher = FolderWatcher::new(downloader, vec![watch_config])?;
@@ -36,17 +47,47 @@
 //! ```
 
 use crate::UsenetDownloader;
-use crate::config::{WatchFolderAction, WatchFolderConfig};
+use crate::config::{WatchFolderAction, WatchFolderConfig, WatchMode};
 use crate::error::{Error, Result};
 use crate::types::DownloadOptions;
 use notify::{
-    Config as NotifyConfig, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    Config as NotifyConfig, Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode,
+    Watcher,
 };
-use std::path::Path;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
+/// Name of the file written to a folder in `auto` mode to check that events arrive
+const PROBE_FILE_NAME: &str = ".usenet-dl-watch-probe";
+
+/// How long `auto` mode waits for the probe file's event before polling the folder
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long a polled file's size must stay unchanged before it's imported
+const SETTLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Filesystem types that don't reliably deliver change events
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "9p",
+    "afs",
+    "ceph",
+    "glusterfs",
+    "virtiofs",
+    "fuse.sshfs",
+    "fuse.grpcfuse",
+    "fakeowner",
+];
+
 /// Watches folders for new NZB files and automatically adds them to the download queue
 pub struct FolderWatcher {
     /// Filesystem watcher instance
@@ -55,6 +96,18 @@ pub struct FolderWatcher {
     /// Channel for receiving filesystem events
     rx: mpsc::UnboundedReceiver<notify::Result<Event>>,
 
+    /// Sender half of `rx`, handed to poll watchers
+    tx: mpsc::UnboundedSender<notify::Result<Event>>,
+
+    /// Poll watchers for folders that don't get filesystem events, keyed by folder
+    poll_watchers: HashMap<PathBuf, PollWatcher>,
+
+    /// Probe files still waiting for their event, keyed by probe path
+    probes: HashMap<PathBuf, PathBuf>,
+
+    /// When folders with pending probes fall back to polling
+    probe_deadline: Option<Instant>,
+
     /// Reference to the downloader for adding NZBs
     downloader: Arc<UsenetDownloader>,
 
@@ -75,19 +128,16 @@ impl FolderWatcher {
         let (tx, rx) = mpsc::unbounded_channel();
 
         // Create watcher with debouncing to avoid duplicate events
-        let watcher = RecommendedWatcher::new(
-            move |res| {
-                if let Err(e) = tx.send(res) {
-                    error!("Failed to send filesystem event: {}", e);
-                }
-            },
-            NotifyConfig::default(),
-        )
-        .map_err(|e| Error::FolderWatch(e.to_string()))?;
+        let watcher = RecommendedWatcher::new(event_sender(tx.clone()), NotifyConfig::default())
+            .map_err(|e| Error::FolderWatch(e.to_string()))?;
 
         Ok(Self {
             watcher,
             rx,
+            tx,
+            poll_watchers: HashMap::new(),
+            probes: HashMap::new(),
+            probe_deadline: None,
             downloader,
             configs,
         })
@@ -95,35 +145,139 @@ impl FolderWatcher {
 
     /// Start watching all configured folders
     ///
-    /// This method registers all folders with the filesystem watcher.
+    /// This method registers all folders with the filesystem watcher, or with a poll
+    /// watcher depending on their [`WatchMode`]. In `auto` mode a probe file is written to
+    /// each event-watched folder; [`run`](Self::run) switches folders whose probe isn't
+    /// seen in time to polling.
     ///
     /// # Errors
     /// Returns error if any folder cannot be watched (e.g., doesn't exist, permission denied)
     pub fn start(&mut self) -> Result<()> {
-        for config in &self.configs {
+        for i in 0..self.configs.len() {
+            let config = &self.configs[i];
+            let path = config.path.clone();
+
             // Create directory if it doesn't exist
-            if !config.path.exists() {
-                std::fs::create_dir_all(&config.path).map_err(|e| {
+            if !path.exists() {
+                std::fs::create_dir_all(&path).map_err(|e| {
                     Error::FolderWatch(format!("Failed to create watch folder: {}", e))
                 })?;
-                info!("Created watch folder: {}", config.path.display());
+                info!("Created watch folder: {}", path.display());
             }
 
-            // Start watching the directory
-            self.watcher
-                .watch(&config.path, RecursiveMode::NonRecursive)
-                .map_err(|e| Error::FolderWatch(format!("Failed to watch folder: {}", e)))?;
+            let network_fs = match config.mode {
+                WatchMode::Auto => network_fs_type(&path),
+                WatchMode::Events | WatchMode::Poll => None,
+            };
+            if let Some(fs_type) = &network_fs {
+                info!(
+                    "Watch folder {} is on a {} filesystem, polling it",
+                    path.display(),
+                    fs_type
+                );
+            }
+
+            if config.mode == WatchMode::Poll || network_fs.is_some() {
+                self.start_polling(&path, config.scan_interval)?;
+            } else {
+                // Start watching the directory
+                self.watcher
+                    .watch(&path, RecursiveMode::NonRecursive)
+                    .map_err(|e| Error::FolderWatch(format!("Failed to watch folder: {}", e)))?;
 
+                if config.mode == WatchMode::Auto {
+                    self.write_probe(&path);
+                }
+            }
+
+            let config = &self.configs[i];
             info!(
-                "Watching folder: {} (category: {:?})",
-                config.path.display(),
-                config.category.as_deref().unwrap_or("default")
+                "Watching folder: {} (category: {:?}, polled: {})",
+                path.display(),
+                config.category.as_deref().unwrap_or("default"),
+                self.is_polled(&path)
             );
         }
 
         Ok(())
     }
 
+    /// Whether a folder is scanned by a poll watcher rather than filesystem events
+    pub fn is_polled(&self, folder: &Path) -> bool {
+        self.poll_watchers.contains_key(folder)
+    }
+
+    /// Scan a folder every `interval` instead of relying on filesystem events
+    fn start_polling(&mut self, folder: &Path, interval: Duration) -> Result<()> {
+        let mut poll_watcher = PollWatcher::new(
+            event_sender(self.tx.clone()),
+            NotifyConfig::default().with_poll_interval(interval),
+        )
+        .map_err(|e| Error::FolderWatch(e.to_string()))?;
+        poll_watcher
+            .watch(folder, RecursiveMode::NonRecursive)
+            .map_err(|e| Error::FolderWatch(format!("Failed to poll folder: {}", e)))?;
+
+        self.poll_watchers
+            .insert(folder.to_path_buf(), poll_watcher);
+        Ok(())
+    }
+
+    /// Write a probe file to an event-watched folder so [`run`](Self::run) can tell
+    /// whether its events arrive
+    fn write_probe(&mut self, folder: &Path) {
+        let probe = folder.join(PROBE_FILE_NAME);
+        if let Err(e) = std::fs::write(&probe, b"") {
+            warn!(
+                "Failed to write watch probe to {}, assuming events work: {}",
+                folder.display(),
+                e
+            );
+            return;
+        }
+
+        self.probes.insert(probe, folder.to_path_buf());
+        self.probe_deadline = Some(Instant::now() + PROBE_TIMEOUT);
+    }
+
+    /// Mark the probes among `paths` as seen, removing their files
+    fn confirm_probes(&mut self, paths: &[PathBuf]) {
+        for path in paths {
+            if let Some(folder) = self.probes.remove(path) {
+                debug!("Filesystem events confirmed for {}", folder.display());
+                remove_probe(path);
+            }
+        }
+        if self.probes.is_empty() {
+            self.probe_deadline = None;
+        }
+    }
+
+    /// Switch the folders whose probe never produced an event to polling
+    fn expire_probes(&mut self) {
+        self.probe_deadline = None;
+        for (probe, folder) in std::mem::take(&mut self.probes) {
+            remove_probe(&probe);
+
+            let interval = match self.configs.iter().find(|c| c.path == folder) {
+                Some(config) => config.scan_interval,
+                None => continue,
+            };
+            warn!(
+                "No filesystem events arrived for {}, polling it every {:?}",
+                folder.display(),
+                interval
+            );
+
+            if let Err(e) = self.watcher.unwatch(&folder) {
+                debug!("Failed to unwatch {}: {}", folder.display(), e);
+            }
+            if let Err(e) = self.start_polling(&folder, interval) {
+                error!("Failed to poll {}: {}", folder.display(), e);
+            }
+        }
+    }
+
     /// Run the folder watcher event loop
     ///
     /// This is the main event loop that processes filesystem events.
@@ -131,9 +285,25 @@ impl FolderWatcher {
     pub async fn run(mut self) {
         info!("Folder watcher started");
 
-        while let Some(result) = self.rx.recv().await {
+        loop {
+            let result = tokio::select! {
+                result = self.rx.recv() => match result {
+                    Some(result) => result,
+                    None => break,
+                },
+                _ = tokio::time::sleep_until(self.probe_deadline.unwrap_or_else(Instant::now)),
+                    if self.probe_deadline.is_some() =>
+                {
+                    self.expire_probes();
+                    continue;
+                }
+            };
+
             match result {
                 Ok(event) => {
+                    if !self.probes.is_empty() {
+                        self.confirm_probes(&event.paths);
+                    }
                     if let Err(e) = self.handle_event(event).await {
                         error!("Error handling folder event: {}", e);
                     }
@@ -149,22 +319,26 @@ impl FolderWatcher {
 
     /// Stop watching all folders
     pub fn stop(self) {
-        // Dropping the watcher will automatically stop watching
+        // Dropping the watchers will automatically stop watching
         drop(self.watcher);
+        drop(self.poll_watchers);
+        for probe in self.probes.keys() {
+            remove_probe(probe);
+        }
         info!("Folder watcher stopped");
     }
 
     /// Handle a filesystem event
     ///
     /// Processes filesystem events from the watcher and triggers NZB processing for creation/modification events.
-    /// Only `.nzb` files are processed; other file types are ignored.
+    /// Only NZBs and NZB containers (see [`ImportKind`]) are processed; other file types are ignored.
     async fn handle_event(&self, event: Event) -> Result<()> {
         // We only care about file creation events
         match event.kind {
             EventKind::Create(_) | EventKind::Modify(_) => {
                 for path in event.paths {
-                    if self.is_nzb_file(&path) {
-                        self.process_nzb_file(&path).await?;
+                    if let Some(kind) = ImportKind::detect(&path) {
+                        self.process_nzb_file(&path, kind).await?;
                     }
                 }
             }
@@ -176,24 +350,21 @@ impl FolderWatcher {
         Ok(())
     }
 
-    /// Check if a file is an NZB file
-    ///
-    /// Determines if a file path has the `.nzb` extension (case-insensitive).
-    fn is_nzb_file(&self, path: &Path) -> bool {
-        path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| ext.eq_ignore_ascii_case("nzb"))
-            .unwrap_or(false)
-    }
-
-    /// Process a newly detected NZB file
+    /// Process a newly detected NZB file or NZB container
     ///
     /// This method:
     /// 1. Identifies the watch folder configuration for the file
     /// 2. Waits briefly to ensure the file is fully written
-    /// 3. Adds the NZB to the download queue with the configured category
-    /// 4. Executes the after_import action (delete, move, or keep)
-    async fn process_nzb_file(&self, path: &Path) -> Result<()> {
+    /// 3. Adds each NZB it holds to the download queue with the configured category
+    /// 4. Executes the after_import action (delete, move, rename, or keep)
+    ///
+    /// For a container, entries that fail to import are logged and reported through
+    /// [`crate::types::Event::NzbImportFailed`] while the rest are still queued; it's an error
+    /// only if none of them could be. A file that can't be imported at all because it's
+    /// malformed, holds no NZBs or is a duplicate is moved to the error folder so it isn't
+    /// retried on every change; errors that may clear up (shutdown, read-only mode, database
+    /// trouble) leave it in place.
+    async fn process_nzb_file(&self, path: &Path, kind: ImportKind) -> Result<()> {
         debug!("Processing NZB file: {}", path.display());
 
         // Find the config for this folder
@@ -201,45 +372,127 @@ impl FolderWatcher {
 
         // Add a small delay to ensure file is fully written
         // Some applications write files in chunks
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
 
-        // Add to download queue
-        let options = DownloadOptions {
-            category: config.category.clone(),
-            ..Default::default()
-        };
+        // Polled folders are often network shares where a file shows up long before it's
+        // fully written, so wait for its size to settle
+        if self.is_polled(&config.path) {
+            wait_until_settled(path, SETTLE_INTERVAL).await;
+        }
 
-        match self.downloader.add_nzb(path, options).await {
-            Ok(id) => {
-                info!(
-                    "Added NZB from watch folder: {} (download_id: {}, category: {:?})",
-                    path.display(),
-                    id,
-                    config.category.as_deref().unwrap_or("default")
-                );
+        // A later event for a file that was already moved away
+        if !path.exists() {
+            return Ok(());
+        }
 
-                // Handle after_import action
-                if let Err(e) = self.handle_after_import(path, config).await {
+        let owned_path = path.to_path_buf();
+        let nzbs = match tokio::task::spawn_blocking(move || read_nzbs(&owned_path, kind))
+            .await
+            .map_err(|e| Error::FolderWatch(format!("NZB read task panicked: {}", e)))?
+        {
+            Ok(nzbs) => nzbs,
+            Err(e) => return Err(self.reject(path, config, e).await),
+        };
+
+        // Add to download queue
+        let mut added = 0;
+        let mut failures = Vec::new();
+        for (name, content) in nzbs {
+            let options = DownloadOptions {
+                category: config.category.clone(),
+                ..Default::default()
+            };
+
+            match self
+                .downloader
+                .add_nzb_content(&content, &name, options)
+                .await
+            {
+                Ok(id) => {
+                    info!(
+                        "Added NZB from watch folder: {} ({}, download_id: {}, category: {:?})",
+                        path.display(),
+                        name,
+                        id,
+                        config.category.as_deref().unwrap_or("default")
+                    );
+                    added += 1;
+                }
+                Err(e) => {
                     error!(
-                        "Failed to handle after_import action for {}: {}",
+                        "Failed to add NZB from watch folder {} ({}): {}",
                         path.display(),
+                        name,
                         e
                     );
+                    failures.push((name, e));
                 }
             }
-            Err(e) => {
-                error!(
-                    "Failed to add NZB from watch folder {}: {}",
-                    path.display(),
-                    e
-                );
-                return Err(e);
-            }
+        }
+
+        if added == 0 {
+            let error = failures.pop().map(|(_, e)| e).unwrap_or_else(|| {
+                Error::FolderWatch(format!("No NZB files found in {}", path.display()))
+            });
+            return Err(self.reject(path, config, error).await);
+        }
+
+        for (name, e) in failures {
+            self.downloader
+                .emit_event(crate::types::Event::NzbImportFailed {
+                    path: path.to_path_buf(),
+                    reason: format!("{}: {}", name, e),
+                });
+        }
+
+        // Handle after_import action
+        if let Err(e) = self.handle_after_import(path, config).await {
+            error!(
+                "Failed to handle after_import action for {}: {}",
+                path.display(),
+                e
+            );
         }
 
         Ok(())
     }
 
+    /// Handle a file that couldn't be imported, returning the error
+    ///
+    /// Permanent failures move the file to the error folder next to a `<file>.error.txt`
+    /// holding the reason and emit [`crate::types::Event::NzbImportFailed`]; transient ones
+    /// leave the file where it is so the next change retries it.
+    async fn reject(&self, path: &Path, config: &WatchFolderConfig, error: Error) -> Error {
+        if !matches!(
+            error,
+            Error::InvalidNzb(_) | Error::Duplicate(_) | Error::FolderWatch(_)
+        ) {
+            return error;
+        }
+
+        let reason = error.to_string();
+        match move_to_error_folder(path, &config.error_path(), &reason).await {
+            Ok(dest) => warn!(
+                "Moved NZB that failed to import to {}: {}",
+                dest.display(),
+                reason
+            ),
+            Err(e) => error!(
+                "Failed to move {} to the error folder: {}",
+                path.display(),
+                e
+            ),
+        }
+
+        self.downloader
+            .emit_event(crate::types::Event::NzbImportFailed {
+                path: path.to_path_buf(),
+                reason,
+            });
+
+        error
+    }
+
     /// Find the watch folder config that matches this path
     ///
     /// Searches through configured watch folders to find the one containing this file.
@@ -264,7 +517,8 @@ impl FolderWatcher {
     ///
     /// Executes the configured action after successfully adding an NZB to the queue:
     /// - `Delete`: Removes the NZB file
-    /// - `MoveToProcessed`: Moves the file to a `processed` subdirectory
+    /// - `MoveToProcessed`: Moves the file to the processed folder (`processed` by default)
+    /// - `RenameImported`: Renames the file in place with an `.imported` suffix
     /// - `Keep`: Leaves the file in place and marks it as processed in the database
     async fn handle_after_import(&self, path: &Path, config: &WatchFolderConfig) -> Result<()> {
         match config.after_import {
@@ -276,17 +530,14 @@ impl FolderWatcher {
                 info!("Deleted processed NZB: {}", path.display());
             }
             WatchFolderAction::MoveToProcessed => {
-                let parent = path.parent().ok_or_else(|| {
-                    Error::FolderWatch("File has no parent directory".to_string())
-                })?;
-                let processed_dir = parent.join("processed");
+                let processed_dir = config.processed_path();
 
                 // Create processed directory if it doesn't exist
-                if !processed_dir.exists() {
-                    tokio::fs::create_dir(&processed_dir).await.map_err(|e| {
+                tokio::fs::create_dir_all(&processed_dir)
+                    .await
+                    .map_err(|e| {
                         Error::FolderWatch(format!("Failed to create processed directory: {}", e))
                     })?;
-                }
 
                 let dest = processed_dir.join(
                     path.file_name()
@@ -299,6 +550,24 @@ impl FolderWatcher {
                     .map_err(|e| Error::FolderWatch(format!("Failed to move file: {}", e)))?;
                 info!("Moved processed NZB to: {}", dest.display());
             }
+            WatchFolderAction::RenameImported => {
+                let mut name = path
+                    .file_name()
+                    .ok_or_else(|| Error::FolderWatch("File has no filename".to_string()))?
+                    .to_os_string();
+                name.push(".imported");
+                let dest = path.with_file_name(name);
+
+                debug!(
+                    "Renaming NZB file: {} -> {}",
+                    path.display(),
+                    dest.display()
+                );
+                tokio::fs::rename(path, &dest)
+                    .await
+                    .map_err(|e| Error::FolderWatch(format!("Failed to rename file: {}", e)))?;
+                info!("Renamed processed NZB to: {}", dest.display());
+            }
             WatchFolderAction::Keep => {
                 // Keep the file in place, but mark as processed in database
                 debug!("Keeping NZB file in place: {}", path.display());
@@ -314,12 +583,255 @@ impl FolderWatcher {
     }
 }
 
+/// Event handler forwarding notify events into the watcher's channel
+fn event_sender(
+    tx: mpsc::UnboundedSender<notify::Result<Event>>,
+) -> impl Fn(notify::Result<Event>) + Send + 'static {
+    move |res| {
+        if let Err(e) = tx.send(res) {
+            error!("Failed to send filesystem event: {}", e);
+        }
+    }
+}
+
+/// Remove a probe file, ignoring one that's already gone
+fn remove_probe(probe: &Path) {
+    if let Err(e) = std::fs::remove_file(probe)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!("Failed to remove watch probe {}: {}", probe.display(), e);
+    }
+}
+
+/// Wait until a file's size stops changing between checks `interval` apart
+///
+/// Gives up after a minute so a file that keeps growing is still handled.
+async fn wait_until_settled(path: &Path, interval: Duration) {
+    let size = |path: &Path| std::fs::metadata(path).map(|m| m.len()).ok();
+    let mut last = size(path);
+    for _ in 0..(60_000 / interval.as_millis().max(1)) {
+        tokio::time::sleep(interval).await;
+        let current = size(path);
+        if current == last {
+            return;
+        }
+        last = current;
+    }
+}
+
+/// Filesystem type of `path` if it's one that doesn't reliably deliver change events
+#[cfg(target_os = "linux")]
+fn network_fs_type(path: &Path) -> Option<String> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    network_fs_type_in(&mounts, &path)
+}
+
+/// Filesystem type of `path` if it's one that doesn't reliably deliver change events
+#[cfg(not(target_os = "linux"))]
+fn network_fs_type(_path: &Path) -> Option<String> {
+    None
+}
+
+/// Find the mount holding `path` in a `/proc/mounts` listing and return its type if it's
+/// a network or VM-shared filesystem
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn network_fs_type_in(mounts: &str, path: &Path) -> Option<String> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = fields.nth(1)?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then_some((mount_point.len(), fs_type))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, fs_type)| fs_type)
+        .filter(|fs_type| NETWORK_FS_TYPES.contains(fs_type))
+        .map(str::to_string)
+}
+
+/// Move a file that failed to import into `error_dir`, writing the reason next to it
+///
+/// Returns the file's new location.
+async fn move_to_error_folder(path: &Path, error_dir: &Path, reason: &str) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Error::FolderWatch("File has no filename".to_string()))?;
+
+    tokio::fs::create_dir_all(error_dir)
+        .await
+        .map_err(|e| Error::FolderWatch(format!("Failed to create error directory: {}", e)))?;
+
+    let dest = error_dir.join(file_name);
+    tokio::fs::rename(path, &dest)
+        .await
+        .map_err(|e| Error::FolderWatch(format!("Failed to move file: {}", e)))?;
+
+    let mut reason_name = file_name.to_os_string();
+    reason_name.push(".error.txt");
+    tokio::fs::write(error_dir.join(reason_name), format!("{}\n", reason))
+        .await
+        .map_err(|e| Error::FolderWatch(format!("Failed to write error reason: {}", e)))?;
+
+    Ok(dest)
+}
+
+/// Kind of file the folder watcher imports, detected from its name
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ImportKind {
+    /// Plain `.nzb` file
+    Nzb,
+    /// Gzip-compressed `.nzb.gz` file
+    GzippedNzb,
+    /// `.zip` archive holding NZBs
+    Zip,
+    /// `.rar` archive holding NZBs
+    Rar,
+}
+
+impl ImportKind {
+    /// Detect the kind of a file from its name (case-insensitive)
+    fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".nzb") {
+            Some(Self::Nzb)
+        } else if name.ends_with(".nzb.gz") {
+            Some(Self::GzippedNzb)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".rar") {
+            Some(Self::Rar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Download name for an NZB file name: the name without `.nzb` or `.nzb.gz`
+fn nzb_name(file_name: &str) -> String {
+    let lower = file_name.to_ascii_lowercase();
+    let stem_len = [".nzb.gz", ".nzb"]
+        .iter()
+        .find(|suffix| lower.ends_with(*suffix))
+        .map_or(file_name.len(), |suffix| file_name.len() - suffix.len());
+    file_name[..stem_len].to_string()
+}
+
+/// Decompress a gzipped NZB
+fn gunzip(content: &[u8], source: &Path) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    flate2::read::GzDecoder::new(content)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| {
+            Error::FolderWatch(format!("Failed to decompress {}: {}", source.display(), e))
+        })?;
+    Ok(decompressed)
+}
+
+/// Add an archive entry to `nzbs` if it is an NZB (plain or gzipped)
+fn push_archive_entry(
+    nzbs: &mut Vec<(String, Vec<u8>)>,
+    entry_name: &Path,
+    content: Vec<u8>,
+    archive: &Path,
+) -> Result<()> {
+    let Some(file_name) = entry_name.file_name().and_then(|n| n.to_str()) else {
+        return Ok(());
+    };
+    match ImportKind::detect(entry_name) {
+        Some(ImportKind::Nzb) => nzbs.push((nzb_name(file_name), content)),
+        Some(ImportKind::GzippedNzb) => {
+            nzbs.push((nzb_name(file_name), gunzip(&content, archive)?));
+        }
+        _ => debug!(
+            "Skipping non-NZB entry {} in {}",
+            entry_name.display(),
+            archive.display()
+        ),
+    }
+    Ok(())
+}
+
+/// Read the NZBs held by a watched file, as (download name, content) pairs
+///
+/// Blocking; archives are read in memory, nothing is extracted to disk.
+fn read_nzbs(path: &Path, kind: ImportKind) -> Result<Vec<(String, Vec<u8>)>> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| Error::FolderWatch("File has no filename".to_string()))?;
+
+    let mut nzbs = Vec::new();
+    match kind {
+        ImportKind::Nzb | ImportKind::GzippedNzb => {
+            let content = std::fs::read(path).map_err(|e| {
+                Error::FolderWatch(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            let content = if kind == ImportKind::GzippedNzb {
+                gunzip(&content, path)?
+            } else {
+                content
+            };
+            nzbs.push((nzb_name(file_name), content));
+        }
+        ImportKind::Zip => {
+            let open_error = |e: zip::result::ZipError| {
+                Error::FolderWatch(format!("Failed to read {}: {}", path.display(), e))
+            };
+            let file = std::fs::File::open(path).map_err(|e| {
+                Error::FolderWatch(format!("Failed to open {}: {}", path.display(), e))
+            })?;
+            let mut archive = zip::ZipArchive::new(file).map_err(open_error)?;
+            for i in 0..archive.len() {
+                let mut entry = archive.by_index(i).map_err(open_error)?;
+                if entry.is_dir() {
+                    continue;
+                }
+                let entry_name = std::path::PathBuf::from(entry.name());
+                let mut content = Vec::new();
+                entry.read_to_end(&mut content).map_err(|e| {
+                    Error::FolderWatch(format!(
+                        "Failed to read {} in {}: {}",
+                        entry_name.display(),
+                        path.display(),
+                        e
+                    ))
+                })?;
+                push_archive_entry(&mut nzbs, &entry_name, content, path)?;
+            }
+        }
+        ImportKind::Rar => {
+            let rar_error = |e: unrar::error::UnrarError| {
+                Error::FolderWatch(format!("Failed to read {}: {}", path.display(), e))
+            };
+            let mut archive = unrar::Archive::new(path)
+                .open_for_processing()
+                .map_err(rar_error)?;
+            while let Some(entry) = archive.read_header().map_err(rar_error)? {
+                let header = entry.entry();
+                let entry_name = header.filename.clone();
+                if header.is_directory() {
+                    archive = entry.skip().map_err(rar_error)?;
+                    continue;
+                }
+                let (content, next) = entry.read().map_err(rar_error)?;
+                archive = next;
+                push_archive_entry(&mut nzbs, &entry_name, content, path)?;
+            }
+        }
+    }
+
+    Ok(nzbs)
+}
+
 // unwrap/expect are acceptable in tests for concise failure-on-error assertions
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{Config, WatchFolderAction, WatchFolderConfig};
+    use crate::config::{Config, WatchFolderAction, WatchFolderConfig, WatchMode};
     use tempfile::TempDir;
     use tokio::time::{Duration, sleep};
 
@@ -331,17 +843,26 @@ mod tests {
         Arc::new(downloader)
     }
 
-    #[tokio::test]
-    async fn test_is_nzb_file() {
-        let downloader = create_test_downloader().await;
-        let watcher = FolderWatcher::new(downloader, vec![]).unwrap();
+    #[test]
+    fn test_import_kind_detect() {
+        let detect = |name: &str| ImportKind::detect(Path::new(name));
+        assert_eq!(detect("test.nzb"), Some(ImportKind::Nzb));
+        assert_eq!(detect("test.NZB"), Some(ImportKind::Nzb));
+        assert_eq!(detect("/path/to/file.nzb"), Some(ImportKind::Nzb));
+        assert_eq!(detect("test.nzb.gz"), Some(ImportKind::GzippedNzb));
+        assert_eq!(detect("test.NZB.GZ"), Some(ImportKind::GzippedNzb));
+        assert_eq!(detect("test.zip"), Some(ImportKind::Zip));
+        assert_eq!(detect("test.rar"), Some(ImportKind::Rar));
+        assert_eq!(detect("test.txt"), None);
+        assert_eq!(detect("test.gz"), None);
+        assert_eq!(detect("test"), None);
+    }
 
-        assert!(watcher.is_nzb_file(Path::new("test.nzb")));
-        assert!(watcher.is_nzb_file(Path::new("test.NZB")));
-        assert!(watcher.is_nzb_file(Path::new("/path/to/file.nzb")));
-        assert!(!watcher.is_nzb_file(Path::new("test.txt")));
-        assert!(!watcher.is_nzb_file(Path::new("test")));
-        assert!(!watcher.is_nzb_file(Path::new("test.zip")));
+    #[test]
+    fn test_nzb_name_strips_extensions() {
+        assert_eq!(nzb_name("Show.S01E01.nzb"), "Show.S01E01");
+        assert_eq!(nzb_name("Show.S01E01.NZB.gz"), "Show.S01E01");
+        assert_eq!(nzb_name("notes.txt"), "notes.txt");
     }
 
     #[tokio::test]
@@ -354,7 +875,10 @@ mod tests {
             path: watch_path.clone(),
             after_import: WatchFolderAction::Delete,
             category: Some("test".to_string()),
+            mode: WatchMode::Auto,
             scan_interval: Duration::from_secs(5),
+            processed_dir: None,
+            error_dir: None,
         };
 
         let mut watcher = FolderWatcher::new(downloader, vec![config]).unwrap();
@@ -376,7 +900,10 @@ mod tests {
             path: watch_path.clone(),
             after_import: WatchFolderAction::Delete,
             category: Some("test".to_string()),
+            mode: WatchMode::Auto,
             scan_interval: Duration::from_secs(5),
+            processed_dir: None,
+            error_dir: None,
         };
 
         let watcher = FolderWatcher::new(downloader, vec![config]).unwrap();
@@ -410,6 +937,18 @@ mod tests {
         Arc<UsenetDownloader>,
         std::path::PathBuf,
         TempDir,
+    ) {
+        create_watcher_with(|_| {}).await
+    }
+
+    /// Like [`create_watcher_with_watch_dir`], letting the caller adjust the watch config
+    async fn create_watcher_with(
+        configure: impl FnOnce(&mut WatchFolderConfig),
+    ) -> (
+        FolderWatcher,
+        Arc<UsenetDownloader>,
+        std::path::PathBuf,
+        TempDir,
     ) {
         let temp_dir = TempDir::new().unwrap();
         let watch_path = temp_dir.path().join("watch");
@@ -422,12 +961,16 @@ mod tests {
 
         let downloader = Arc::new(UsenetDownloader::new(config).await.unwrap());
 
-        let watch_config = WatchFolderConfig {
+        let mut watch_config = WatchFolderConfig {
             path: watch_path.clone(),
             after_import: WatchFolderAction::Keep,
             category: Some("test-cat".to_string()),
+            mode: WatchMode::Auto,
             scan_interval: Duration::from_secs(5),
+            processed_dir: None,
+            error_dir: None,
         };
+        configure(&mut watch_config);
 
         let watcher = FolderWatcher::new(downloader.clone(), vec![watch_config]).unwrap();
         (watcher, downloader, watch_path, temp_dir)
@@ -561,7 +1104,10 @@ mod tests {
             path: watch_path.clone(),
             after_import: WatchFolderAction::Delete,
             category: Some("movies".to_string()),
+            mode: WatchMode::Auto,
             scan_interval: Duration::from_secs(1),
+            processed_dir: None,
+            error_dir: None,
         };
 
         // Create and start folder watcher
@@ -616,4 +1162,315 @@ mod tests {
         watcher_handle.abort();
         let _ = watcher_handle.await;
     }
+    // =========================================================================
+    // NZB containers
+    // =========================================================================
+
+    fn gzip(content: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// Hand a newly created file to the watcher
+    async fn import(watcher: &FolderWatcher, path: std::path::PathBuf) -> Result<()> {
+        let event = Event {
+            kind: EventKind::Create(notify::event::CreateKind::File),
+            paths: vec![path],
+            attrs: Default::default(),
+        };
+        watcher.handle_event(event).await
+    }
+
+    #[tokio::test]
+    async fn handle_event_imports_gzipped_nzb() {
+        let (watcher, downloader, watch_path, _temp_dir) = create_watcher_with_watch_dir().await;
+
+        let gz_path = watch_path.join("Movie.2024.nzb.gz");
+        std::fs::write(&gz_path, gzip(TEST_NZB.as_bytes())).unwrap();
+        import(&watcher, gz_path).await.unwrap();
+
+        let downloads = downloader.db.list_downloads().await.unwrap();
+        assert_eq!(downloads.len(), 1);
+        assert_eq!(downloads[0].name, "Movie.2024");
+        assert_eq!(downloads[0].category.as_deref(), Some("test-cat"));
+    }
+
+    #[tokio::test]
+    async fn handle_event_imports_every_nzb_in_zip() {
+        use std::io::Write;
+
+        let (watcher, downloader, watch_path, _temp_dir) = create_watcher_with_watch_dir().await;
+
+        let zip_path = watch_path.join("bundle.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        let options = zip::write::FileOptions::default();
+        let second = TEST_NZB.replace("test-msg-id", "second-msg-id");
+        for (name, content) in [
+            ("First.nzb", TEST_NZB.as_bytes().to_vec()),
+            ("nested/Second.nzb.gz", gzip(second.as_bytes())),
+            ("readme.txt", b"not an nzb".to_vec()),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(&content).unwrap();
+        }
+        zip.finish().unwrap();
+
+        import(&watcher, zip_path.clone()).await.unwrap();
+
+        let mut names: Vec<String> = downloader
+            .db
+            .list_downloads()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|d| d.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["First", "Second"]);
+        assert!(zip_path.exists(), "Keep action leaves the archive in place");
+    }
+
+    #[tokio::test]
+    async fn handle_event_rejects_container_without_nzbs() {
+        use std::io::Write;
+
+        let (watcher, downloader, watch_path, _temp_dir) = create_watcher_with_watch_dir().await;
+
+        let zip_path = watch_path.join("empty.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        zip.start_file("readme.txt", zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(b"no nzbs here").unwrap();
+        zip.finish().unwrap();
+        assert!(import(&watcher, zip_path).await.is_err());
+
+        let rar_path = watch_path.join("corrupt.rar");
+        std::fs::write(&rar_path, b"not a rar archive").unwrap();
+        assert!(import(&watcher, rar_path).await.is_err());
+
+        assert!(downloader.db.list_downloads().await.unwrap().is_empty());
+        let error_dir = watch_path.join("error");
+        for name in ["empty.zip", "corrupt.rar"] {
+            assert!(error_dir.join(name).exists(), "{name} moved to error/");
+            assert!(!watch_path.join(name).exists());
+        }
+        let reason = std::fs::read_to_string(error_dir.join("empty.zip.error.txt")).unwrap();
+        assert!(reason.contains("No NZB files found"), "{reason}");
+    }
+
+    // =========================================================================
+    // Post-import actions and the error folder
+    // =========================================================================
+
+    #[tokio::test]
+    async fn move_to_processed_uses_configured_folder() {
+        let (watcher, _downloader, watch_path, _temp_dir) = create_watcher_with(|c| {
+            c.after_import = WatchFolderAction::MoveToProcessed;
+            c.processed_dir = Some("done".into());
+        })
+        .await;
+
+        let nzb_path = watch_path.join("movie.nzb");
+        std::fs::write(&nzb_path, TEST_NZB).unwrap();
+        import(&watcher, nzb_path.clone()).await.unwrap();
+
+        assert!(!nzb_path.exists());
+        assert!(watch_path.join("done").join("movie.nzb").exists());
+        assert!(!watch_path.join("processed").exists());
+    }
+
+    #[tokio::test]
+    async fn move_to_processed_defaults_to_processed_folder() {
+        let (watcher, _downloader, watch_path, _temp_dir) =
+            create_watcher_with(|c| c.after_import = WatchFolderAction::MoveToProcessed).await;
+
+        let nzb_path = watch_path.join("movie.nzb");
+        std::fs::write(&nzb_path, TEST_NZB).unwrap();
+        import(&watcher, nzb_path.clone()).await.unwrap();
+
+        assert!(!nzb_path.exists());
+        assert!(watch_path.join("processed").join("movie.nzb").exists());
+    }
+
+    #[tokio::test]
+    async fn rename_imported_adds_suffix_in_place() {
+        let (watcher, downloader, watch_path, _temp_dir) =
+            create_watcher_with(|c| c.after_import = WatchFolderAction::RenameImported).await;
+
+        let nzb_path = watch_path.join("movie.nzb");
+        std::fs::write(&nzb_path, TEST_NZB).unwrap();
+        import(&watcher, nzb_path.clone()).await.unwrap();
+
+        assert!(!nzb_path.exists());
+        let renamed = watch_path.join("movie.nzb.imported");
+        assert!(renamed.exists());
+        assert_eq!(
+            ImportKind::detect(&renamed),
+            None,
+            "renamed file isn't re-imported"
+        );
+        assert_eq!(downloader.db.list_downloads().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn malformed_nzb_is_moved_to_error_folder_with_event() {
+        let temp_error = TempDir::new().unwrap();
+        let error_dir = temp_error.path().join("failed");
+        let (watcher, downloader, watch_path, _temp_dir) = {
+            let error_dir = error_dir.clone();
+            create_watcher_with(move |c| c.error_dir = Some(error_dir)).await
+        };
+        let mut events = downloader.subscribe();
+
+        let nzb_path = watch_path.join("broken.nzb");
+        std::fs::write(&nzb_path, "this is not xml").unwrap();
+        let err = import(&watcher, nzb_path.clone()).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidNzb(_)), "{err:?}");
+
+        assert!(!nzb_path.exists());
+        assert!(
+            error_dir.join("broken.nzb").exists(),
+            "absolute error_dir is used as is"
+        );
+        let reason = std::fs::read_to_string(error_dir.join("broken.nzb.error.txt")).unwrap();
+        assert!(reason.starts_with("invalid NZB"), "{reason}");
+
+        match events.try_recv() {
+            Ok(crate::types::Event::NzbImportFailed { path, reason }) => {
+                assert_eq!(path, nzb_path);
+                assert!(reason.starts_with("invalid NZB"), "{reason}");
+            }
+            other => panic!("expected NzbImportFailed, got {other:?}"),
+        }
+
+        // A late event for the moved file is a no-op rather than another failure
+        import(&watcher, nzb_path).await.unwrap();
+    }
+
+    // =========================================================================
+    // Polling and backend detection
+    // =========================================================================
+
+    fn watch_config(path: std::path::PathBuf, mode: WatchMode) -> WatchFolderConfig {
+        WatchFolderConfig {
+            path,
+            after_import: WatchFolderAction::Delete,
+            category: None,
+            mode,
+            scan_interval: Duration::from_millis(100),
+            processed_dir: None,
+            error_dir: None,
+        }
+    }
+
+    #[test]
+    fn network_fs_type_uses_the_closest_mount() {
+        let mounts = "\
+/dev/sda1 / ext4 rw,relatime 0 0
+server:/export /mnt/nzb nfs4 rw,relatime 0 0
+//nas/share /mnt/my\\040share cifs rw 0 0
+/dev/sdb1 /mnt/nzb/local ext4 rw 0 0
+";
+        let fs_type = |path: &str| network_fs_type_in(mounts, Path::new(path));
+        assert_eq!(fs_type("/mnt/nzb/watch").as_deref(), Some("nfs4"));
+        assert_eq!(fs_type("/mnt/my share/watch").as_deref(), Some("cifs"));
+        assert_eq!(fs_type("/mnt/nzb/local/watch"), None);
+        assert_eq!(fs_type("/home/user/watch"), None);
+        assert_eq!(
+            fs_type("/mnt/nzbs"),
+            None,
+            "prefix must end at a path component"
+        );
+    }
+
+    #[tokio::test]
+    async fn poll_mode_imports_new_files() {
+        let (mut watcher, downloader, watch_path, _temp_dir) = create_watcher_with(|c| {
+            c.mode = WatchMode::Poll;
+            c.scan_interval = Duration::from_millis(100);
+            c.after_import = WatchFolderAction::Delete;
+        })
+        .await;
+        watcher.start().unwrap();
+        assert!(watcher.is_polled(&watch_path));
+        assert!(
+            !watch_path.join(PROBE_FILE_NAME).exists(),
+            "poll mode writes no probe"
+        );
+
+        let handle = tokio::spawn(watcher.run());
+        let nzb_path = watch_path.join("polled.nzb");
+        std::fs::write(&nzb_path, TEST_NZB).unwrap();
+
+        let mut downloads = Vec::new();
+        for _ in 0..150 {
+            sleep(Duration::from_millis(100)).await;
+            downloads = downloader.db.list_downloads().await.unwrap();
+            // The file is deleted just after the download is added
+            if !downloads.is_empty() && !nzb_path.exists() {
+                break;
+            }
+        }
+        handle.abort();
+
+        assert_eq!(downloads.len(), 1, "polled folder should import the NZB");
+        assert!(!nzb_path.exists(), "Delete action applies to polled files");
+    }
+
+    #[tokio::test]
+    async fn auto_mode_falls_back_to_polling_without_events() {
+        let downloader = create_test_downloader().await;
+        let temp_dir = TempDir::new().unwrap();
+        let watch_path = temp_dir.path().join("watch");
+
+        let config = watch_config(watch_path.clone(), WatchMode::Auto);
+        let mut watcher = FolderWatcher::new(downloader, vec![config]).unwrap();
+        watcher.start().unwrap();
+
+        let probe = watch_path.join(PROBE_FILE_NAME);
+        assert!(probe.exists());
+        assert!(watcher.probe_deadline.is_some());
+        assert!(!watcher.is_polled(&watch_path));
+
+        watcher.expire_probes();
+        assert!(watcher.is_polled(&watch_path));
+        assert!(!probe.exists());
+        assert!(watcher.probe_deadline.is_none());
+    }
+
+    #[tokio::test]
+    async fn auto_mode_keeps_events_once_probe_is_seen() {
+        let downloader = create_test_downloader().await;
+        let temp_dir = TempDir::new().unwrap();
+        let watch_path = temp_dir.path().join("watch");
+
+        let config = watch_config(watch_path.clone(), WatchMode::Auto);
+        let mut watcher = FolderWatcher::new(downloader, vec![config]).unwrap();
+        watcher.start().unwrap();
+
+        let probe = watch_path.join(PROBE_FILE_NAME);
+        watcher.confirm_probes(&[watch_path.join("other.nzb"), probe.clone()]);
+        assert!(!probe.exists());
+        assert!(watcher.probe_deadline.is_none());
+
+        watcher.expire_probes();
+        assert!(!watcher.is_polled(&watch_path));
+    }
+
+    #[tokio::test]
+    async fn events_mode_writes_no_probe() {
+        let downloader = create_test_downloader().await;
+        let temp_dir = TempDir::new().unwrap();
+        let watch_path = temp_dir.path().join("watch");
+
+        let config = watch_config(watch_path.clone(), WatchMode::Events);
+        let mut watcher = FolderWatcher::new(downloader, vec![config]).unwrap();
+        watcher.start().unwrap();
+
+        assert!(!watch_path.join(PROBE_FILE_NAME).exists());
+        assert!(watcher.probe_deadline.is_none());
+        assert!(!watcher.is_polled(&watch_path));
+    }
 }
@@ -28,6 +28,10 @@
 //!                 connections: 10,
 //!                 priority: 0,
 //!                 pipeline_depth: 10,
+//!                 speed_limit_bps: None,
+//!                 idle_timeout: None,
+//!                 cost: 0.0,
+//!                 quota_bytes: None,
 //!             }
 //!         ],
 //!         ..Default::default()
@@ -54,6 +58,9 @@
 
 /// REST API module
 pub mod api;
+/// Typed async client for the REST API
+#[cfg(any(test, feature = "client"))]
+pub mod client;
 /// Configuration types
 pub mod config;
 /// Database persistence layer
@@ -64,10 +71,18 @@ pub mod deobfuscation;
 pub mod downloader;
 /// Error types
 pub mod error;
+/// Event bus with per-subscriber queues
+pub mod event_bus;
 /// Archive extraction
 pub mod extraction;
 /// Folder watching for automatic NZB import
 pub mod folder_watcher;
+/// Live log streaming
+pub mod log_stream;
+/// Message keys and parameters for localizing user-facing text
+pub mod messages;
+/// Event-driven notification publishers (MQTT) and webhook digests
+pub mod notifications;
 /// PAR2 parity handling
 pub mod parity;
 /// Post-processing pipeline
@@ -84,6 +99,9 @@ pub mod scheduler;
 pub mod scheduler_task;
 /// Speed limiting with token bucket
 pub mod speed_limiter;
+/// Mock NNTP server for integration tests
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 /// Core types and events
 pub mod types;
 /// Utility functions
@@ -92,7 +110,7 @@ pub mod utils;
 // Re-export commonly used types
 pub use config::{Config, DuplicateAction, ServerConfig};
 pub use db::Database;
-pub use downloader::UsenetDownloader;
+pub use downloader::{EventSubscription, UsenetDownloader};
 pub use error::{
     ApiError, DatabaseError, DownloadError, Error, ErrorDetail, PostProcessError, Result,
     ToHttpStatus,
@@ -103,8 +121,9 @@ pub use parity::{
 };
 pub use scheduler::{RuleId, ScheduleAction, ScheduleRule, Scheduler, Weekday};
 pub use types::{
-    DownloadId, DownloadInfo, DownloadOptions, DuplicateInfo, Event, HistoryEntry, Priority,
-    QueueStats, ServerCapabilities, ServerTestResult, Stage, Status,
+    BandwidthMode, DownloadId, DownloadInfo, DownloadOptions, DuplicateInfo,
+    EVENT_ENVELOPE_VERSION, Event, EventEnvelope, HealthCheck, HealthReport, HistoryEntry,
+    PauseScope, Priority, QueueStats, ServerCapabilities, ServerTestResult, Stage, Status,
 };
 
 /// Helper function to run the downloader with graceful signal handling.
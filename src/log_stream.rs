@@ -0,0 +1,234 @@
+//! Live log streaming for the REST API.
+//!
+//! usenet-dl doesn't install a tracing subscriber; the application does. Adding
+//! [`layer`] to that subscriber copies every event at or above
+//! `logging.log_stream_level` into a ring buffer of recent entries and a broadcast
+//! channel, which `GET /api/v1/system/log/stream` serves as server-sent events.
+//! Events the application's own filters drop never reach the layer.
+//!
+//! ```no_run
+//! use tracing_subscriber::layer::SubscriberExt;
+//!
+//! let subscriber = tracing_subscriber::registry().with(usenet_dl::log_stream::layer());
+//! tracing::subscriber::set_global_default(subscriber).ok();
+//! ```
+
+use crate::config::{LogLevel, LoggingConfig};
+use crate::types::LogEntry;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::broadcast;
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Live events buffered per stream client before it counts as lagging
+const CHANNEL_CAPACITY: usize = 1024;
+
+static STREAM: OnceLock<LogStream> = OnceLock::new();
+
+/// Shared state behind every [`LogStreamLayer`] and stream client
+struct LogStream {
+    installed: AtomicBool,
+    level: AtomicU8,
+    capacity: AtomicUsize,
+    recent: Mutex<VecDeque<LogEntry>>,
+    sender: broadcast::Sender<LogEntry>,
+}
+
+fn stream() -> &'static LogStream {
+    STREAM.get_or_init(|| {
+        let defaults = LoggingConfig::default();
+        LogStream {
+            installed: AtomicBool::new(false),
+            level: AtomicU8::new(defaults.log_stream_level as u8),
+            capacity: AtomicUsize::new(defaults.log_stream_capacity),
+            recent: Mutex::new(VecDeque::new()),
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+        }
+    })
+}
+
+/// Tracing layer feeding the live log stream
+///
+/// Create it with [`layer`]. All layers share one process-wide buffer.
+#[derive(Debug)]
+pub struct LogStreamLayer {
+    _private: (),
+}
+
+/// Create a layer that feeds `GET /api/v1/system/log/stream`
+pub fn layer() -> LogStreamLayer {
+    stream().installed.store(true, Ordering::Relaxed);
+    LogStreamLayer { _private: () }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for LogStreamLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let stream = stream();
+        let metadata = event.metadata();
+        let level = LogLevel::from_tracing(*metadata.level());
+        if level as u8 > stream.level.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        stream.push(LogEntry {
+            timestamp: chrono::Utc::now(),
+            level,
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        });
+    }
+}
+
+impl LogStream {
+    fn push(&self, entry: LogEntry) {
+        let capacity = self.capacity.load(Ordering::Relaxed);
+        // Send under the lock so a new client sees each entry exactly once
+        let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        if capacity > 0 {
+            while recent.len() >= capacity {
+                recent.pop_front();
+            }
+            recent.push_back(entry.clone());
+        }
+        let _ = self.sender.send(entry);
+    }
+}
+
+/// Apply the `logging` section's capture level and buffer size
+pub(crate) fn configure(config: &LoggingConfig) {
+    let stream = stream();
+    stream
+        .level
+        .store(config.log_stream_level as u8, Ordering::Relaxed);
+    stream
+        .capacity
+        .store(config.log_stream_capacity, Ordering::Relaxed);
+
+    let mut recent = stream.recent.lock().unwrap_or_else(|e| e.into_inner());
+    while recent.len() > config.log_stream_capacity {
+        recent.pop_front();
+    }
+}
+
+/// Whether the application's subscriber includes a [`LogStreamLayer`]
+pub(crate) fn is_installed() -> bool {
+    stream().installed.load(Ordering::Relaxed)
+}
+
+/// Recent entries at or above `level`, and a receiver for everything recorded after them
+pub(crate) fn subscribe(level: LogLevel) -> (Vec<LogEntry>, broadcast::Receiver<LogEntry>) {
+    let stream = stream();
+    let recent = stream.recent.lock().unwrap_or_else(|e| e.into_inner());
+    let entries = recent
+        .iter()
+        .filter(|entry| entry.level <= level)
+        .cloned()
+        .collect();
+    (entries, stream.sender.subscribe())
+}
+
+/// Collects an event's message and remaining fields as JSON
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: serde_json::Value) {
+        if field.name() == "message" {
+            self.message = match value {
+                serde_json::Value::String(message) => message,
+                other => other.to_string(),
+            };
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, format!("{:?}", value).into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+}
+
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn captures_events_with_fields() {
+        let (_, mut receiver) = subscribe(LogLevel::Trace);
+        let subscriber = tracing_subscriber::registry().with(layer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(download_id = 7, name = "release", "log stream test event");
+        });
+
+        let entry = std::iter::from_fn(|| receiver.try_recv().ok())
+            .find(|entry| entry.message == "log stream test event")
+            .unwrap();
+        assert_eq!(entry.level, LogLevel::Info);
+        assert_eq!(entry.fields["download_id"], 7);
+        assert_eq!(entry.fields["name"], "release");
+        assert!(entry.target.starts_with("usenet_dl"));
+
+        let (recent, _) = subscribe(LogLevel::Info);
+        assert!(
+            recent
+                .iter()
+                .any(|entry| entry.message == "log stream test event")
+        );
+        let (recent, _) = subscribe(LogLevel::Error);
+        assert!(
+            !recent
+                .iter()
+                .any(|entry| entry.message == "log stream test event")
+        );
+    }
+
+    #[test]
+    fn skips_events_above_the_capture_level() {
+        let (_, mut receiver) = subscribe(LogLevel::Trace);
+        let subscriber = tracing_subscriber::registry().with(layer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::trace!("log stream trace event");
+        });
+
+        assert!(
+            !std::iter::from_fn(|| receiver.try_recv().ok())
+                .any(|entry| entry.message == "log stream trace event")
+        );
+    }
+}
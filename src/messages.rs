@@ -0,0 +1,175 @@
+//! Message keys and parameters for localizing user-facing text.
+//!
+//! Error messages and events are built in English, but each also has a message key
+//! and the values that go into its text, so embedders can render it in another
+//! language:
+//!
+//! - errors: `error.<code>` (e.g. `error.all_passwords_failed`) with
+//!   [`Error::message_params`], also sent as `params` in API errors
+//! - events: `event.<type>` (e.g. `event.download_failed`) with the event's fields
+//!
+//! A [`MessageCatalog`] turns a key and its parameters into text. A `HashMap` of
+//! templates is one, with `{name}` placeholders filled from the parameters:
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use usenet_dl::messages::MessageCatalog;
+//! use usenet_dl::{ApiError, Error};
+//!
+//! let catalog = HashMap::from([(
+//!     "error.not_found".to_string(),
+//!     "{reason} introuvable".to_string(),
+//! )]);
+//! let api: ApiError = Error::NotFound("Téléchargement 7".into()).into();
+//! assert_eq!(api.error.localized(&catalog), "Téléchargement 7 introuvable");
+//! ```
+//!
+//! [`Error::message_params`]: crate::Error::message_params
+
+use crate::error::ErrorDetail;
+use crate::types::Event;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Parameters of a message, by placeholder name
+pub type MessageParams = Map<String, Value>;
+
+/// Source of localized message text
+pub trait MessageCatalog {
+    /// The text for `key` with `params` filled in, or None if the catalog has no
+    /// text for it (callers then fall back to the English message)
+    fn format(&self, key: &str, params: &MessageParams) -> Option<String>;
+}
+
+impl MessageCatalog for HashMap<String, String> {
+    fn format(&self, key: &str, params: &MessageParams) -> Option<String> {
+        self.get(key)
+            .map(|template| fill_template(template, params))
+    }
+}
+
+/// Replace each `{name}` in `template` with the `name` parameter
+///
+/// Strings are inserted as they are, other values as JSON. Placeholders without a
+/// parameter are left alone.
+pub fn fill_template(template: &str, params: &MessageParams) -> String {
+    let mut text = template.to_string();
+    for (name, value) in params {
+        let value = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        text = text.replace(&format!("{{{}}}", name), &value);
+    }
+    text
+}
+
+impl ErrorDetail {
+    /// Message key of the error, `error.<code>`
+    pub fn message_key(&self) -> String {
+        format!("error.{}", self.code)
+    }
+
+    /// The error's message from `catalog`, or its English message if it has none
+    pub fn localized(&self, catalog: &dyn MessageCatalog) -> String {
+        let params = self.params.clone().unwrap_or_default();
+        catalog
+            .format(&self.message_key(), &params)
+            .unwrap_or_else(|| self.message.clone())
+    }
+}
+
+impl Event {
+    /// Message key of the event, `event.<type>`
+    pub fn message_key(&self) -> String {
+        format!("event.{}", self.event_type())
+    }
+
+    /// Values to fill into the event's message: its fields, as in its JSON
+    pub fn message_params(&self) -> MessageParams {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(mut fields)) => {
+                fields.remove("type");
+                fields
+            }
+            _ => MessageParams::new(),
+        }
+    }
+
+    /// The event's message from `catalog`, if it has one
+    pub fn localized(&self, catalog: &dyn MessageCatalog) -> Option<String> {
+        catalog.format(&self.message_key(), &self.message_params())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ApiError, Error, PostProcessError};
+    use crate::types::DownloadId;
+    use std::path::PathBuf;
+
+    fn catalog() -> HashMap<String, String> {
+        HashMap::from([
+            (
+                "error.all_passwords_failed".to_string(),
+                "Keines der {count} Passwörter passt zu {archive}".to_string(),
+            ),
+            (
+                "event.download_failed".to_string(),
+                "Download {id} fehlgeschlagen: {error}".to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn error_is_localized_with_its_params() {
+        let api: ApiError = Error::PostProcess(PostProcessError::AllPasswordsFailed {
+            archive: PathBuf::from("/tmp/a.rar"),
+            count: 3,
+        })
+        .into();
+
+        assert_eq!(api.error.message_key(), "error.all_passwords_failed");
+        assert_eq!(
+            api.error.localized(&catalog()),
+            "Keines der 3 Passwörter passt zu /tmp/a.rar"
+        );
+    }
+
+    #[test]
+    fn error_without_catalog_entry_keeps_english_message() {
+        let api: ApiError = Error::ShuttingDown.into();
+        assert_eq!(api.error.localized(&catalog()), api.error.message);
+    }
+
+    #[test]
+    fn event_is_localized_with_its_fields() {
+        let event = Event::DownloadFailed {
+            id: DownloadId(7),
+            error: "disk full".to_string(),
+            articles_succeeded: None,
+            articles_failed: None,
+            articles_total: None,
+        };
+
+        assert_eq!(event.message_key(), "event.download_failed");
+        assert!(!event.message_params().contains_key("type"));
+        assert_eq!(
+            event.localized(&catalog()).as_deref(),
+            Some("Download 7 fehlgeschlagen: disk full")
+        );
+        assert!(
+            Event::Removed { id: DownloadId(7) }
+                .localized(&catalog())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn fill_template_leaves_unknown_placeholders() {
+        let params = MessageParams::from_iter([("name".to_string(), Value::from("x"))]);
+        assert_eq!(fill_template("{name} {other}", &params), "x {other}");
+    }
+}
@@ -0,0 +1,122 @@
+//! Digest mode for webhooks.
+//!
+//! A webhook with `digest_interval` set doesn't get a request per download: its
+//! payloads are held in a [`DigestBuffer`] and sent as one
+//! [`WebhookDigest`] per interval, e.g. an hourly summary of completed and failed jobs.
+
+use crate::types::{WebhookDigest, WebhookPayload};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Webhook payloads waiting to be sent as digests, per webhook
+#[derive(Debug)]
+pub(crate) struct DigestBuffer {
+    state: Mutex<DigestState>,
+}
+
+#[derive(Debug)]
+struct DigestState {
+    /// When the buffer was created; the start of every webhook's first period
+    created_at: i64,
+    /// Payloads per webhook, by index in `webhooks`
+    pending: HashMap<usize, Vec<WebhookPayload>>,
+    /// When each webhook's last digest period ended
+    last_sent: HashMap<usize, i64>,
+}
+
+impl DigestBuffer {
+    /// Create an empty buffer whose first periods start at `now`
+    pub(crate) fn new(now: i64) -> Self {
+        Self {
+            state: Mutex::new(DigestState {
+                created_at: now,
+                pending: HashMap::new(),
+                last_sent: HashMap::new(),
+            }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, DigestState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Hold `payload` for the next digest of the webhook at `webhook`
+    pub(crate) fn push(&self, webhook: usize, payload: WebhookPayload) {
+        self.state()
+            .pending
+            .entry(webhook)
+            .or_default()
+            .push(payload);
+    }
+
+    /// End the webhook's current period at `now` and return its digest
+    ///
+    /// Returns `None` when nothing happened in the period; the next period starts at
+    /// `now` either way.
+    pub(crate) fn take(&self, webhook: usize, now: i64) -> Option<WebhookDigest> {
+        let mut state = self.state();
+        let period_start = state
+            .last_sent
+            .insert(webhook, now)
+            .unwrap_or(state.created_at);
+        let events = state.pending.remove(&webhook).unwrap_or_default();
+        if events.is_empty() {
+            return None;
+        }
+
+        let count = |event: &str| events.iter().filter(|p| p.event == event).count();
+        Some(WebhookDigest {
+            event: "digest".to_string(),
+            period_start,
+            period_end: now,
+            completed: count("complete"),
+            failed: count("failed"),
+            events,
+        })
+    }
+}
+
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DownloadId;
+
+    fn payload(event: &str, id: i64, timestamp: i64) -> WebhookPayload {
+        WebhookPayload {
+            event: event.to_string(),
+            download_id: DownloadId(id),
+            name: format!("Download.{}", id),
+            category: None,
+            status: event.to_string(),
+            destination: None,
+            error: None,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn take_summarizes_the_period_per_webhook() {
+        let buffer = DigestBuffer::new(1_000);
+        buffer.push(0, payload("complete", 1, 1_100));
+        buffer.push(0, payload("failed", 2, 1_200));
+        buffer.push(0, payload("complete", 3, 1_300));
+        buffer.push(1, payload("queued", 4, 1_400));
+
+        let digest = buffer.take(0, 4_600).unwrap();
+        assert_eq!((digest.period_start, digest.period_end), (1_000, 4_600));
+        assert_eq!((digest.completed, digest.failed), (2, 1));
+        let ids: Vec<i64> = digest.events.iter().map(|p| p.download_id.0).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        // The next period starts where this one ended, and an empty one sends nothing
+        assert!(buffer.take(0, 8_200).is_none());
+        buffer.push(0, payload("failed", 5, 9_000));
+        let digest = buffer.take(0, 11_800).unwrap();
+        assert_eq!(digest.period_start, 8_200);
+        assert_eq!(digest.failed, 1);
+
+        // Other webhooks keep their own payloads
+        assert_eq!(buffer.take(1, 11_800).unwrap().events.len(), 1);
+    }
+}
@@ -0,0 +1,13 @@
+//! Notification publishers and delivery modes.
+//!
+//! Webhooks and scripts are triggered by the downloader itself; the publishers here
+//! instead subscribe to [`Event`](crate::types::Event)s and forward the ones they're
+//! configured for:
+//!
+//! - [`mqtt`] - Download and queue events on an MQTT broker
+//!
+//! Webhooks with a `digest_interval` have their payloads batched into one summary per
+//! interval (see [`WebhookDigest`](crate::types::WebhookDigest)).
+
+pub(crate) mod digest;
+pub mod mqtt;
@@ -0,0 +1,536 @@
+//! MQTT publisher for home-automation integration.
+//!
+//! [`MqttPublisher`] connects to an MQTT 3.1.1 broker over plain TCP and publishes the
+//! events selected in [`MqttConfig::events`] at QoS 0, so setups such as Home Assistant
+//! can react to downloads:
+//!
+//! | Topic | Retained | Payload |
+//! |-------|----------|---------|
+//! | `{prefix}/added` | no | `{"id", "name", "category"}` |
+//! | `{prefix}/completed` | no | `{"id", "name", "category", "path"}` |
+//! | `{prefix}/failed` | no | `{"id", "name", "category", "stage", "error"}` |
+//! | `{prefix}/queue` | yes | `paused` or `running` |
+//! | `{prefix}/status` | yes | `online`, or `offline` once disconnected (last will) |
+//!
+//! A dropped connection is re-established with a doubling delay of up to a minute, and
+//! the retained topics are published again once it is.
+
+use crate::config::{MqttConfig, MqttEvent};
+use crate::db::Database;
+use crate::event_bus::{EventReceiver, RecvError};
+use crate::types::{DownloadId, Event};
+use serde_json::json;
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::net::tcp::OwnedWriteHalf;
+
+/// How long the broker has to accept a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay before the first reconnect attempt
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest delay between reconnect attempts
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// PINGREQ packet
+const PINGREQ: [u8; 2] = [0xC0, 0x00];
+
+/// DISCONNECT packet
+const DISCONNECT: [u8; 2] = [0xE0, 0x00];
+
+/// A message to publish
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MqttMessage {
+    /// Topic, including the configured prefix
+    pub topic: String,
+    /// JSON for events, a plain state word for retained topics
+    pub payload: String,
+    /// Whether the broker keeps the message for later subscribers
+    pub retain: bool,
+}
+
+/// Publishes download events to an MQTT broker
+pub struct MqttPublisher {
+    config: MqttConfig,
+    db: Arc<Database>,
+    /// Latest payload per retained topic, published again after reconnecting
+    retained: HashMap<String, String>,
+}
+
+impl MqttPublisher {
+    /// Create a publisher; nothing is sent until [`run`](Self::run) is called
+    pub fn new(config: MqttConfig, db: Arc<Database>) -> Self {
+        Self {
+            config,
+            db,
+            retained: HashMap::new(),
+        }
+    }
+
+    /// Publish events from `events` until the event bus closes
+    ///
+    /// Events that arrive while disconnected wait in the subscriber's queue and are
+    /// published after reconnecting.
+    pub async fn run(mut self, mut events: EventReceiver) {
+        let mut delay = INITIAL_RECONNECT_DELAY;
+        loop {
+            match self.connect().await {
+                Ok(stream) => {
+                    tracing::info!(
+                        host = %self.config.host,
+                        port = self.config.port,
+                        "Connected to MQTT broker"
+                    );
+                    delay = INITIAL_RECONNECT_DELAY;
+                    match self.serve(stream, &mut events).await {
+                        Ok(()) => return,
+                        Err(e) => tracing::warn!(error = %e, "MQTT connection lost"),
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        host = %self.config.host,
+                        port = self.config.port,
+                        error = %e,
+                        "Failed to connect to MQTT broker"
+                    );
+                }
+            }
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    }
+
+    /// Build the message for `event`, if it's one of the configured events
+    pub async fn message_for(&self, event: &Event) -> Option<MqttMessage> {
+        let selected = |kind: MqttEvent| self.config.events.contains(&kind);
+        match event {
+            Event::Queued { id, name } if selected(MqttEvent::Added) => {
+                let (_, category) = self.download_details(*id).await;
+                Some(self.event_message(
+                    "added",
+                    json!({ "id": id, "name": name, "category": category }),
+                ))
+            }
+            Event::Complete { id, path } if selected(MqttEvent::Completed) => {
+                let (name, category) = self.download_details(*id).await;
+                Some(self.event_message(
+                    "completed",
+                    json!({ "id": id, "name": name, "category": category, "path": path }),
+                ))
+            }
+            Event::DownloadFailed { id, error, .. } if selected(MqttEvent::Failed) => {
+                let (name, category) = self.download_details(*id).await;
+                Some(self.event_message(
+                    "failed",
+                    json!({
+                        "id": id,
+                        "name": name,
+                        "category": category,
+                        "stage": "download",
+                        "error": error,
+                    }),
+                ))
+            }
+            Event::Failed {
+                id, stage, error, ..
+            } if selected(MqttEvent::Failed) => {
+                let (name, category) = self.download_details(*id).await;
+                Some(self.event_message(
+                    "failed",
+                    json!({
+                        "id": id,
+                        "name": name,
+                        "category": category,
+                        "stage": stage,
+                        "error": error,
+                    }),
+                ))
+            }
+            Event::QueuePaused if selected(MqttEvent::QueuePaused) => {
+                Some(self.retained_message("queue", "paused"))
+            }
+            Event::QueueResumed if selected(MqttEvent::QueuePaused) => {
+                Some(self.retained_message("queue", "running"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Name and category of a download, empty if it's gone from the database
+    async fn download_details(&self, id: DownloadId) -> (String, Option<String>) {
+        match self.db.get_download(id).await {
+            Ok(Some(download)) => (download.name, download.category),
+            _ => (String::new(), None),
+        }
+    }
+
+    fn topic(&self, name: &str) -> String {
+        format!(
+            "{}/{}",
+            self.config.topic_prefix.trim_end_matches('/'),
+            name
+        )
+    }
+
+    fn event_message(&self, name: &str, payload: serde_json::Value) -> MqttMessage {
+        MqttMessage {
+            topic: self.topic(name),
+            payload: payload.to_string(),
+            retain: false,
+        }
+    }
+
+    fn retained_message(&self, name: &str, state: &str) -> MqttMessage {
+        MqttMessage {
+            topic: self.topic(name),
+            payload: state.to_string(),
+            retain: true,
+        }
+    }
+
+    /// Open a connection and wait for the broker to accept it
+    async fn connect(&self) -> io::Result<TcpStream> {
+        let timed_out = |_| io::Error::new(io::ErrorKind::TimedOut, "broker did not respond");
+        let address = (self.config.host.as_str(), self.config.port);
+        let mut stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(address))
+            .await
+            .map_err(timed_out)??;
+        stream
+            .write_all(&connect_packet(&self.config, &self.topic("status")))
+            .await?;
+
+        let mut connack = [0u8; 4];
+        tokio::time::timeout(CONNECT_TIMEOUT, stream.read_exact(&mut connack))
+            .await
+            .map_err(timed_out)??;
+        if connack[..2] != [0x20, 0x02] {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unexpected reply to CONNECT",
+            ));
+        }
+        match connack[3] {
+            0 => Ok(stream),
+            code => Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("broker refused the connection: {}", connack_reason(code)),
+            )),
+        }
+    }
+
+    /// Publish over an accepted connection until it drops or the event bus closes
+    ///
+    /// Returns `Ok` only when the event bus closed.
+    async fn serve(&mut self, stream: TcpStream, events: &mut EventReceiver) -> io::Result<()> {
+        let (mut reader, mut writer) = stream.into_split();
+
+        let online = self.retained_message("status", "online");
+        publish(&mut writer, &online).await?;
+        for (topic, payload) in &self.retained {
+            let message = MqttMessage {
+                topic: topic.clone(),
+                payload: payload.clone(),
+                retain: true,
+            };
+            publish(&mut writer, &message).await?;
+        }
+
+        let keep_alive = self.config.keep_alive;
+        let mut ping =
+            tokio::time::interval_at(tokio::time::Instant::now() + keep_alive, keep_alive);
+        // Only PINGRESPs are expected back; reading just notices a closed connection
+        let mut incoming = [0u8; 64];
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Ok(event) => {
+                        let Some(message) = self.message_for(&event).await else {
+                            continue;
+                        };
+                        if message.retain {
+                            self.retained
+                                .insert(message.topic.clone(), message.payload.clone());
+                        }
+                        publish(&mut writer, &message).await?;
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "MQTT publisher fell behind; events were dropped");
+                    }
+                    Err(RecvError::Closed) => {
+                        writer.write_all(&DISCONNECT).await.ok();
+                        return Ok(());
+                    }
+                },
+                _ = ping.tick() => writer.write_all(&PINGREQ).await?,
+                read = reader.read(&mut incoming) => {
+                    if read? == 0 {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "broker closed the connection",
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Write `message` as a QoS 0 PUBLISH packet
+async fn publish(writer: &mut OwnedWriteHalf, message: &MqttMessage) -> io::Result<()> {
+    writer
+        .write_all(&publish_packet(
+            &message.topic,
+            message.payload.as_bytes(),
+            message.retain,
+        ))
+        .await
+}
+
+/// Build a CONNECT packet with a clean session and a retained `offline` last will
+fn connect_packet(config: &MqttConfig, will_topic: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+    put_string(&mut body, b"MQTT");
+    // Protocol level 4 is MQTT 3.1.1
+    body.push(4);
+
+    // Clean session, will flag, will retain
+    let mut flags = 0x02 | 0x04 | 0x20;
+    if config.username.is_some() {
+        flags |= 0x80;
+    }
+    if config.password.is_some() {
+        flags |= 0x40;
+    }
+    body.push(flags);
+    let keep_alive = u16::try_from(config.keep_alive.as_secs()).unwrap_or(u16::MAX);
+    body.extend_from_slice(&keep_alive.to_be_bytes());
+
+    put_string(&mut body, config.client_id.as_bytes());
+    put_string(&mut body, will_topic.as_bytes());
+    put_string(&mut body, b"offline");
+    if let Some(username) = &config.username {
+        put_string(&mut body, username.as_bytes());
+    }
+    if let Some(password) = &config.password {
+        put_string(&mut body, password.as_bytes());
+    }
+    packet(0x10, &body)
+}
+
+/// Build a QoS 0 PUBLISH packet
+fn publish_packet(topic: &str, payload: &[u8], retain: bool) -> Vec<u8> {
+    let mut body = Vec::with_capacity(2 + topic.len() + payload.len());
+    put_string(&mut body, topic.as_bytes());
+    body.extend_from_slice(payload);
+    packet(0x30 | u8::from(retain), &body)
+}
+
+/// Frame `body` behind the fixed header byte and its variable-length remaining length
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(body.len() + 5);
+    packet.push(header);
+    let mut remaining = body.len();
+    loop {
+        let mut byte = (remaining % 128) as u8;
+        remaining /= 128;
+        if remaining > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if remaining == 0 {
+            break;
+        }
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+/// Append a string prefixed by its 16-bit big-endian length
+fn put_string(buf: &mut Vec<u8>, value: &[u8]) {
+    let len = u16::try_from(value.len()).unwrap_or(u16::MAX);
+    buf.extend_from_slice(&len.to_be_bytes());
+    buf.extend_from_slice(&value[..len as usize]);
+}
+
+/// Description of a CONNACK return code
+fn connack_reason(code: u8) -> &'static str {
+    match code {
+        1 => "unacceptable protocol version",
+        2 => "client identifier rejected",
+        3 => "server unavailable",
+        4 => "bad username or password",
+        5 => "not authorized",
+        _ => "unknown reason",
+    }
+}
+
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn config(port: u16) -> MqttConfig {
+        MqttConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            client_id: "usenet-dl".to_string(),
+            username: None,
+            password: None,
+            topic_prefix: "home/usenet".to_string(),
+            events: vec![
+                MqttEvent::Added,
+                MqttEvent::Completed,
+                MqttEvent::Failed,
+                MqttEvent::QueuePaused,
+            ],
+            keep_alive: Duration::from_secs(60),
+        }
+    }
+
+    async fn database() -> (tempfile::TempDir, Arc<Database>) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("test.db")).await.unwrap();
+        (dir, Arc::new(db))
+    }
+
+    /// Read one packet: its fixed header byte and body
+    async fn read_packet(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+        let header = stream.read_u8().await.unwrap();
+        let mut remaining = 0usize;
+        let mut shift = 0;
+        loop {
+            let byte = stream.read_u8().await.unwrap();
+            remaining |= ((byte & 0x7F) as usize) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        let mut body = vec![0u8; remaining];
+        stream.read_exact(&mut body).await.unwrap();
+        (header, body)
+    }
+
+    /// Split a PUBLISH body into topic and payload
+    fn parse_publish(body: &[u8]) -> (String, String) {
+        let len = u16::from_be_bytes([body[0], body[1]]) as usize;
+        let topic = String::from_utf8(body[2..2 + len].to_vec()).unwrap();
+        let payload = String::from_utf8(body[2 + len..].to_vec()).unwrap();
+        (topic, payload)
+    }
+
+    #[test]
+    fn remaining_length_uses_continuation_bytes() {
+        let framed = packet(0x30, &[0u8; 321]);
+        assert_eq!(&framed[..3], &[0x30, 0xC1, 0x02]);
+        assert_eq!(framed.len(), 3 + 321);
+
+        assert_eq!(packet(0xC0, &[]), PINGREQ.to_vec());
+    }
+
+    #[test]
+    fn connect_packet_carries_credentials_and_last_will() {
+        let mut config = config(1883);
+        config.username = Some("user".to_string());
+        config.password = Some("pass".to_string());
+
+        let framed = connect_packet(&config, "home/usenet/status");
+        let mut expected = vec![0x00, 0x04, b'M', b'Q', b'T', b'T', 0x04, 0xE6, 0x00, 0x3C];
+        for field in ["usenet-dl", "home/usenet/status", "offline", "user", "pass"] {
+            expected.extend_from_slice(&(field.len() as u16).to_be_bytes());
+            expected.extend_from_slice(field.as_bytes());
+        }
+        assert_eq!(framed[0], 0x10);
+        assert_eq!(framed[1] as usize, expected.len());
+        assert_eq!(&framed[2..], &expected[..]);
+    }
+
+    #[tokio::test]
+    async fn message_for_skips_unselected_events() {
+        let (_dir, db) = database().await;
+        let mut config = config(1883);
+        config.events = vec![MqttEvent::Failed];
+        let publisher = MqttPublisher::new(config, db);
+
+        let queued = Event::Queued {
+            id: DownloadId(1),
+            name: "Show.S01E01".to_string(),
+        };
+        assert_eq!(publisher.message_for(&queued).await, None);
+        assert_eq!(publisher.message_for(&Event::QueuePaused).await, None);
+
+        let failed = Event::DownloadFailed {
+            id: DownloadId(1),
+            error: "too many missing articles".to_string(),
+            articles_succeeded: None,
+            articles_failed: None,
+            articles_total: None,
+        };
+        let message = publisher.message_for(&failed).await.unwrap();
+        assert_eq!(message.topic, "home/usenet/failed");
+        assert!(!message.retain);
+        let payload: serde_json::Value = serde_json::from_str(&message.payload).unwrap();
+        assert_eq!(payload["id"], 1);
+        assert_eq!(payload["stage"], "download");
+        assert_eq!(payload["error"], "too many missing articles");
+    }
+
+    #[tokio::test]
+    async fn publishes_events_and_retained_state_to_broker() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (_dir, db) = database().await;
+        let (event_tx, events) = crate::event_bus::channel(16);
+
+        let publisher = MqttPublisher::new(config(port), db);
+        let task = tokio::spawn(publisher.run(events));
+
+        let (mut broker, _) = listener.accept().await.unwrap();
+        let (header, _) = read_packet(&mut broker).await;
+        assert_eq!(header, 0x10, "first packet should be CONNECT");
+        broker.write_all(&[0x20, 0x02, 0x00, 0x00]).await.unwrap();
+
+        let (header, body) = read_packet(&mut broker).await;
+        assert_eq!(header, 0x31, "status should be published retained");
+        assert_eq!(
+            parse_publish(&body),
+            ("home/usenet/status".to_string(), "online".to_string())
+        );
+
+        event_tx.send(Event::QueuePaused).unwrap();
+        event_tx
+            .send(Event::Queued {
+                id: DownloadId(7),
+                name: "Movie.2024".to_string(),
+            })
+            .unwrap();
+
+        let (header, body) = read_packet(&mut broker).await;
+        assert_eq!(header, 0x31);
+        assert_eq!(
+            parse_publish(&body),
+            ("home/usenet/queue".to_string(), "paused".to_string())
+        );
+
+        let (header, body) = read_packet(&mut broker).await;
+        assert_eq!(header, 0x30, "events should not be retained");
+        let (topic, payload) = parse_publish(&body);
+        assert_eq!(topic, "home/usenet/added");
+        let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(payload["id"], 7);
+        assert_eq!(payload["name"], "Movie.2024");
+
+        // Closing the event bus disconnects cleanly
+        drop(event_tx);
+        let (header, _) = read_packet(&mut broker).await;
+        assert_eq!(header, 0xE0);
+        task.await.unwrap();
+    }
+}
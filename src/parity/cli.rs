@@ -1,9 +1,17 @@
 //! CLI-based PAR2 handler using external par2 binary
 
-use super::parser::{ExitStatus, parse_par2_repair_output, parse_par2_verify_output};
-use super::traits::{ParityCapabilities, ParityHandler, RepairResult, VerifyResult};
+use super::parser::{
+    ExitStatus, Par2Features, parse_par2_features, parse_par2_repair_output,
+    parse_par2_verify_output, parse_progress_line,
+};
+use super::traits::{ParityCapabilities, ParityHandler, ProgressFn, RepairResult, VerifyResult};
+use crate::config::ProcessPriorityConfig;
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 
 /// CLI-based PAR2 handler using external par2 binary
@@ -33,8 +41,15 @@ use tokio::process::Command;
 /// ```
 pub struct CliParityHandler {
     binary_path: PathBuf,
+    /// Features found by [`ParityHandler::probe`], `None` if the binary couldn't run
+    features: OnceLock<Option<Par2Features>>,
+    /// CPU and I/O priority par2 runs at
+    priority: ProcessPriorityConfig,
 }
 
+/// How long a `par2 -V` or `par2 -h` probe may take
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 impl CliParityHandler {
     /// Create a new CLI handler with an explicit binary path
     ///
@@ -42,7 +57,17 @@ impl CliParityHandler {
     ///
     /// * `binary_path` - Path to the par2 binary
     pub fn new(binary_path: PathBuf) -> Self {
-        Self { binary_path }
+        Self {
+            binary_path,
+            features: OnceLock::new(),
+            priority: ProcessPriorityConfig::default(),
+        }
+    }
+
+    /// Run par2 at the given CPU and I/O priority (Unix only)
+    pub fn with_process_priority(mut self, priority: ProcessPriorityConfig) -> Self {
+        self.priority = priority;
+        self
     }
 
     /// Attempt to find par2 in PATH
@@ -55,44 +80,159 @@ impl CliParityHandler {
     pub fn from_path() -> Option<Self> {
         which::which("par2").ok().map(Self::new)
     }
-}
 
-#[async_trait]
-impl ParityHandler for CliParityHandler {
-    async fn verify(&self, par2_file: &Path) -> crate::Result<VerifyResult> {
+    /// Run par2 with a single informational flag, returning stdout and stderr combined
+    async fn run_info(&self, flag: &str) -> std::io::Result<String> {
         let output = Command::new(&self.binary_path)
-            .arg("v") // Verify
-            .arg(par2_file)
-            .output()
+            .arg(flag)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(PROBE_TIMEOUT, output)
             .await
+            .map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::TimedOut, "par2 probe timed out")
+            })??;
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        Ok(text)
+    }
+
+    /// Run a par2 command, reporting progress parsed from stdout as it streams in
+    ///
+    /// Returns the full stdout, stderr and exit status once the process exits.
+    async fn run(
+        &self,
+        command: &str,
+        par2_file: &Path,
+        on_progress: ProgressFn<'_>,
+    ) -> crate::Result<(Vec<u8>, Vec<u8>, ExitStatus)> {
+        let mut par2 = Command::new(&self.binary_path);
+        crate::utils::apply_process_priority(par2.as_std_mut(), &self.priority);
+        let mut child = par2
+            .arg(command)
+            .arg(par2_file)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
             .map_err(|e| crate::Error::ExternalTool(format!("Failed to execute par2: {}", e)))?;
 
-        parse_par2_verify_output(
-            &output.stdout,
-            &output.stderr,
-            ExitStatus::from(output.status.success()),
-        )
+        // Drain stderr concurrently so a chatty process can't block on a full pipe
+        let stderr_task = child.stderr.take().map(|mut stderr| {
+            tokio::spawn(async move {
+                let mut buf = Vec::new();
+                let _ = stderr.read_to_end(&mut buf).await;
+                buf
+            })
+        });
+
+        let mut stdout_buf = Vec::new();
+        if let Some(mut stdout) = child.stdout.take() {
+            // par2cmdline redraws progress with '\r', so treat it as a line break too
+            let mut line_start = 0;
+            let mut chunk = [0u8; 4096];
+            loop {
+                let n = stdout.read(&mut chunk).await?;
+                if n == 0 {
+                    break;
+                }
+                for &byte in &chunk[..n] {
+                    if byte == b'\r' || byte == b'\n' {
+                        if let Some(percent) = std::str::from_utf8(&stdout_buf[line_start..])
+                            .ok()
+                            .and_then(parse_progress_line)
+                        {
+                            on_progress(percent);
+                        }
+                        stdout_buf.push(byte);
+                        line_start = stdout_buf.len();
+                    } else {
+                        stdout_buf.push(byte);
+                    }
+                }
+            }
+        }
+
+        let status = child
+            .wait()
+            .await
+            .map_err(|e| crate::Error::ExternalTool(format!("Failed to wait for par2: {}", e)))?;
+        let stderr_buf = match stderr_task {
+            Some(task) => task.await.unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        Ok((stdout_buf, stderr_buf, ExitStatus::from(status.success())))
+    }
+}
+
+#[async_trait]
+impl ParityHandler for CliParityHandler {
+    async fn verify(&self, par2_file: &Path) -> crate::Result<VerifyResult> {
+        self.verify_with_progress(par2_file, &|_| {}).await
     }
 
     async fn repair(&self, par2_file: &Path) -> crate::Result<RepairResult> {
-        let output = Command::new(&self.binary_path)
-            .arg("r") // Repair
-            .arg(par2_file)
-            .output()
-            .await
-            .map_err(|e| crate::Error::ExternalTool(format!("Failed to execute par2: {}", e)))?;
+        self.repair_with_progress(par2_file, &|_| {}).await
+    }
 
-        parse_par2_repair_output(
-            &output.stdout,
-            &output.stderr,
-            ExitStatus::from(output.status.success()),
-        )
+    async fn verify_with_progress(
+        &self,
+        par2_file: &Path,
+        on_progress: ProgressFn<'_>,
+    ) -> crate::Result<VerifyResult> {
+        // "v" = verify
+        let (stdout, stderr, status) = self.run("v", par2_file, on_progress).await?;
+        parse_par2_verify_output(&stdout, &stderr, status)
+    }
+
+    async fn repair_with_progress(
+        &self,
+        par2_file: &Path,
+        on_progress: ProgressFn<'_>,
+    ) -> crate::Result<RepairResult> {
+        // "r" = repair
+        let (stdout, stderr, status) = self.run("r", par2_file, on_progress).await?;
+        parse_par2_repair_output(&stdout, &stderr, status)
+    }
+
+    async fn probe(&self) {
+        // par2cmdline exits non-zero for -h, so only a failure to run counts
+        let mut output = String::new();
+        for flag in ["-V", "-h"] {
+            match self.run_info(flag).await {
+                Ok(text) => output.push_str(&text),
+                Err(e) => {
+                    tracing::warn!(
+                        binary = %self.binary_path.display(),
+                        error = %e,
+                        "par2 binary could not be run"
+                    );
+                    let _ = self.features.set(None);
+                    return;
+                }
+            }
+        }
+        let _ = self.features.set(Some(parse_par2_features(&output)));
     }
 
     fn capabilities(&self) -> ParityCapabilities {
-        ParityCapabilities {
-            can_verify: true,
-            can_repair: true,
+        match self.features.get() {
+            Some(None) => ParityCapabilities::default(),
+            Some(Some(features)) => ParityCapabilities {
+                can_verify: true,
+                can_repair: true,
+                can_create: features.can_create,
+                multithreaded: features.multithreaded,
+                version: features.version.clone(),
+            },
+            // Not probed yet: assume a working binary
+            None => ParityCapabilities {
+                can_verify: true,
+                can_repair: true,
+                ..Default::default()
+            },
         }
     }
 
@@ -614,4 +754,80 @@ mod tests {
             "Restored file should match original content"
         );
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_verify_with_progress_streams_percentages() {
+        use std::os::unix::fs::PermissionsExt;
+        use std::sync::Mutex;
+
+        // Fake par2 that redraws progress in place like par2cmdline does
+        let temp_dir = tempfile::tempdir().unwrap();
+        let script = temp_dir.path().join("par2");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\nprintf 'Loading: 25.0%%\\rLoading: 50.0%%\\rLoading: 100.0%%\\n'\n\
+             printf 'All files are correct, repair is not needed.\\n'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let handler = CliParityHandler::new(script);
+        let seen = Mutex::new(Vec::new());
+        let result = handler
+            .verify_with_progress(Path::new("test.par2"), &|p| seen.lock().unwrap().push(p))
+            .await
+            .unwrap();
+
+        assert!(result.is_complete, "final output should still be parsed");
+        assert_eq!(*seen.lock().unwrap(), vec![25.0, 50.0, 100.0]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_probe_reads_version_and_features() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Fake par2 answering -V and -h like par2cmdline 0.8 (which exits 1 for -h)
+        let temp_dir = tempfile::tempdir().unwrap();
+        let script = temp_dir.path().join("par2");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n\
+             if [ \"$1\" = \"-V\" ]; then echo 'par2cmdline version 0.8.1'; exit 0; fi\n\
+             echo 'Usage:'\n\
+             echo '  par2 c(reate) [options] <PAR2 file> [files] : Create PAR2 files'\n\
+             echo '  -t<n>    : Number of threads used for main processing (8)'\n\
+             exit 1\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let handler = CliParityHandler::new(script);
+        handler.probe().await;
+
+        assert_eq!(
+            handler.capabilities(),
+            ParityCapabilities {
+                can_verify: true,
+                can_repair: true,
+                can_create: true,
+                multithreaded: true,
+                version: Some("0.8.1".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_of_missing_binary_reports_nothing_available() {
+        let handler = CliParityHandler::new(PathBuf::from("/nonexistent/par2-binary-xyz"));
+        assert!(
+            handler.capabilities().can_verify,
+            "unprobed handler assumes a working binary"
+        );
+
+        handler.probe().await;
+
+        assert_eq!(handler.capabilities(), ParityCapabilities::default());
+    }
 }
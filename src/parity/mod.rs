@@ -50,4 +50,4 @@ mod traits;
 pub use cli::CliParityHandler;
 pub use noop::NoOpParityHandler;
 pub use par2_metadata::{Par2FileEntry, compute_16k_md5, parse_par2_file_entries};
-pub use traits::{ParityCapabilities, ParityHandler, RepairResult, VerifyResult};
+pub use traits::{ParityCapabilities, ParityHandler, ProgressFn, RepairResult, VerifyResult};
@@ -57,6 +57,7 @@ impl ParityHandler for NoOpParityHandler {
         ParityCapabilities {
             can_verify: false,
             can_repair: false,
+            ..Default::default()
         }
     }
 
@@ -40,6 +40,9 @@ const FILE_DESC_TYPE: &[u8; 16] = b"PAR 2.0\0FileDesc";
 /// Size of the fixed packet header (magic + length + hash + set_id + type)
 const HEADER_SIZE: usize = 8 + 8 + 16 + 16 + 16; // 64 bytes
 
+/// Offset of the recovery set ID within the header
+const SET_ID_OFFSET: usize = 8 + 8 + 16; // 32 bytes
+
 /// Offset of the packet type field within the header
 const TYPE_OFFSET: usize = 8 + 8 + 16 + 16; // 48 bytes
 
@@ -143,6 +146,30 @@ fn extract_filename(bytes: &[u8]) -> String {
     String::from_utf8_lossy(&bytes[..end]).into_owned()
 }
 
+/// Read the recovery set ID from the first packet header of a PAR2 file.
+///
+/// Every packet of a recovery set carries the same ID, so PAR2 files with equal IDs
+/// belong to the same set. Returns `None` if the file can't be read or doesn't start
+/// with a PAR2 packet.
+pub(crate) fn read_recovery_set_id(par2_path: &Path) -> Option<[u8; 16]> {
+    use std::io::Read;
+
+    let mut header = [0u8; HEADER_SIZE];
+    std::fs::File::open(par2_path)
+        .ok()?
+        .read_exact(&mut header)
+        .ok()?;
+    recovery_set_id_from_bytes(&header)
+}
+
+/// Recovery set ID of the packet at the start of `data`.
+pub(crate) fn recovery_set_id_from_bytes(data: &[u8]) -> Option<[u8; 16]> {
+    if data.len() < HEADER_SIZE || &data[..PAR2_MAGIC.len()] != PAR2_MAGIC {
+        return None;
+    }
+    data[SET_ID_OFFSET..SET_ID_OFFSET + 16].try_into().ok()
+}
+
 /// Compute the MD5 hash of the first 16KB of a file.
 ///
 /// Used by DirectRename to match completed files against PAR2 metadata.
@@ -289,4 +316,14 @@ mod tests {
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].filename, "real.rar");
     }
+
+    #[test]
+    fn recovery_set_id_is_read_from_first_header() {
+        let mut packet = build_file_desc_packet("movie.mkv", [1u8; 16]);
+        packet[SET_ID_OFFSET..SET_ID_OFFSET + 16].copy_from_slice(&[7u8; 16]);
+
+        assert_eq!(recovery_set_id_from_bytes(&packet), Some([7u8; 16]));
+        assert_eq!(recovery_set_id_from_bytes(b"not a par2 file"), None);
+        assert_eq!(recovery_set_id_from_bytes(&packet[..HEADER_SIZE - 1]), None);
+    }
 }
@@ -203,6 +203,56 @@ pub fn parse_par2_repair_output(
     })
 }
 
+/// Parse a progress percentage from a single line of par2 output
+///
+/// par2cmdline redraws its progress in place with carriage returns, producing segments
+/// like `Loading: 42.5%`, `Scanning: "file.rar": 12.0%` or `Repairing: 99.9%`.
+/// Returns `None` for lines that don't end in a percentage.
+pub(crate) fn parse_progress_line(line: &str) -> Option<f32> {
+    let number = line.trim().strip_suffix('%')?;
+    let start = number
+        .rfind(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map_or(0, |i| i + 1);
+    let percent: f32 = number[start..].parse().ok()?;
+    Some(percent.clamp(0.0, 100.0))
+}
+
+/// Optional features of a par2 binary
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Par2Features {
+    /// Version from the banner, e.g. `0.8.1`
+    pub version: Option<String>,
+    /// Accepts `-t<n>` to set the number of threads
+    pub multithreaded: bool,
+    /// Supports the `c(reate)` command
+    pub can_create: bool,
+}
+
+/// Parse the features of a par2 binary from its `-V` and `-h` output
+///
+/// par2cmdline and its forks print a banner like `par2cmdline version 0.8.1` and list
+/// their commands (`par2 c(reate) ...`) and options (`-t<n> : Number of threads ...`)
+/// in the help text.
+pub(crate) fn parse_par2_features(output: &str) -> Par2Features {
+    let version = output.lines().find_map(|line| {
+        let mut words = line.split_whitespace();
+        words.find(|word| word.eq_ignore_ascii_case("version"))?;
+        let version = words
+            .next()?
+            .trim_end_matches(|c: char| !c.is_ascii_alphanumeric());
+        version
+            .starts_with(|c: char| c.is_ascii_digit())
+            .then(|| version.to_string())
+    });
+    let lowercase = output.to_ascii_lowercase();
+
+    Par2Features {
+        version,
+        multithreaded: lowercase.contains("-t<") || lowercase.contains("number of threads"),
+        can_create: lowercase.contains("c(reate)"),
+    }
+}
+
 /// Extract a number that appears before the word "block" or "blocks" in a line.
 ///
 /// Handles various par2cmdline output formats:
@@ -533,4 +583,60 @@ mod tests {
         assert!(result.failed_files.is_empty());
         assert!(result.error.is_none());
     }
+
+    #[test]
+    fn progress_line_parses_par2cmdline_formats() {
+        assert_eq!(parse_progress_line("Loading: 42.5%"), Some(42.5));
+        assert_eq!(parse_progress_line("Repairing: 100.0%"), Some(100.0));
+        assert_eq!(
+            parse_progress_line("Scanning: \"movie.part01.rar\": 7.3%"),
+            Some(7.3)
+        );
+        assert_eq!(parse_progress_line("  Verifying: 3%  "), Some(3.0));
+    }
+
+    #[test]
+    fn progress_line_ignores_non_progress_output() {
+        assert_eq!(parse_progress_line("All files are correct"), None);
+        assert_eq!(parse_progress_line("Target: \"file.tar\" - damaged."), None);
+        assert_eq!(parse_progress_line("%"), None);
+        assert_eq!(parse_progress_line(""), None);
+    }
+
+    #[test]
+    fn test_parse_par2_features_of_par2cmdline() {
+        let output = "par2cmdline version 0.8.1\n\
+                      Usage:\n  par2 c(reate) [options] <PAR2 file> [files] : Create PAR2 files\n\
+                      \x20 -t<n>    : Number of threads used for main processing (8)\n";
+
+        assert_eq!(
+            parse_par2_features(output),
+            Par2Features {
+                version: Some("0.8.1".to_string()),
+                multithreaded: true,
+                can_create: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_par2_features_of_single_threaded_build() {
+        let output = "par2cmdline version 0.4, Copyright (C) 2003 Peter Brian Clements.\n\
+                      par2 v(erify) [options] <par2 file> [files]\n";
+
+        assert_eq!(
+            parse_par2_features(output),
+            Par2Features {
+                version: Some("0.4".to_string()),
+                multithreaded: false,
+                can_create: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_par2_features_without_banner() {
+        assert_eq!(parse_par2_features(""), Par2Features::default());
+        assert_eq!(parse_par2_features("unknown version string").version, None);
+    }
 }
@@ -35,13 +35,24 @@ pub struct RepairResult {
     pub error: Option<String>,
 }
 
+/// Callback receiving progress percentages (0.0 to 100.0) during verify/repair
+///
+/// May be called many times per second; callers are expected to throttle.
+pub type ProgressFn<'a> = &'a (dyn Fn(f32) + Send + Sync);
+
 /// Capabilities of a parity handler implementation
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ParityCapabilities {
     /// Can verify file integrity
     pub can_verify: bool,
     /// Can repair damaged files
     pub can_repair: bool,
+    /// Can create PAR2 recovery files
+    pub can_create: bool,
+    /// Spreads verification and repair over multiple threads
+    pub multithreaded: bool,
+    /// Version of the underlying tool or library, if known
+    pub version: Option<String>,
 }
 
 /// Trait for PAR2 parity handling
@@ -109,6 +120,38 @@ pub trait ParityHandler: Send + Sync {
     /// - The operation is not supported (for stub implementations)
     async fn repair(&self, par2_file: &Path) -> crate::Result<RepairResult>;
 
+    /// Verify integrity, reporting progress as it goes
+    ///
+    /// The default implementation calls [`Self::verify`] without reporting any progress.
+    /// Handlers that can observe progress (e.g. by parsing par2cmdline output or
+    /// computing it natively) should override this.
+    async fn verify_with_progress(
+        &self,
+        par2_file: &Path,
+        on_progress: ProgressFn<'_>,
+    ) -> crate::Result<VerifyResult> {
+        let _ = on_progress;
+        self.verify(par2_file).await
+    }
+
+    /// Repair damaged files, reporting progress as it goes
+    ///
+    /// The default implementation calls [`Self::repair`] without reporting any progress.
+    async fn repair_with_progress(
+        &self,
+        par2_file: &Path,
+        on_progress: ProgressFn<'_>,
+    ) -> crate::Result<RepairResult> {
+        let _ = on_progress;
+        self.repair(par2_file).await
+    }
+
+    /// Detect optional features of the underlying implementation
+    ///
+    /// Called once at startup, before [`Self::capabilities`] is first queried. The
+    /// default implementation does nothing.
+    async fn probe(&self) {}
+
     /// Query capabilities of this handler
     ///
     /// Returns information about what operations this handler supports.
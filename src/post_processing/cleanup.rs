@@ -2,16 +2,16 @@
 
 use crate::config::Config;
 use crate::error::Result;
+use crate::event_bus::EventSender;
 use crate::types::{DownloadId, Event};
 use std::path::{Path, PathBuf};
-use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
 
 /// Execute the cleanup stage
 pub(crate) async fn run_cleanup_stage(
     download_id: DownloadId,
     download_path: &Path,
-    event_tx: &broadcast::Sender<Event>,
+    event_tx: &EventSender,
     config: &Config,
 ) -> Result<()> {
     debug!(
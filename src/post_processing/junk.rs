@@ -0,0 +1,79 @@
+//! Junk filter run right before the move stage
+
+use crate::config::JunkFilterConfig;
+use crate::types::DownloadId;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+/// Delete the junk files under `source`, returning how many were deleted
+///
+/// Errors are logged as warnings; a file that can't be deleted is moved like any other.
+pub(crate) async fn remove_junk(
+    download_id: DownloadId,
+    source: &Path,
+    filter: &JunkFilterConfig,
+) -> usize {
+    let mut files = Vec::new();
+    collect_files(source, &mut files).await;
+
+    let video_release = files
+        .iter()
+        .any(|(path, _)| has_extension(path, &filter.video_extensions));
+    let mut removed = 0;
+    for (path, size) in &files {
+        let junk = has_extension(path, &filter.extensions)
+            || (video_release && has_extension(path, &filter.video_release_extensions))
+            || (filter.empty_files && *size == 0);
+        if !junk {
+            continue;
+        }
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => {
+                debug!(download_id = download_id.0, ?path, "deleted junk file");
+                removed += 1;
+            }
+            Err(e) => {
+                warn!(download_id = download_id.0, ?path, error = %e, "failed to delete junk file");
+            }
+        }
+    }
+
+    if removed > 0 {
+        info!(download_id = download_id.0, removed, "junk files removed");
+    }
+    removed
+}
+
+/// Whether `path` ends with one of `extensions` (case-insensitive, leading dot optional)
+pub(super) fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            extensions
+                .iter()
+                .any(|candidate| candidate.trim_start_matches('.').eq_ignore_ascii_case(ext))
+        })
+}
+
+/// Recursively collect the files under `path` with their sizes
+pub(super) fn collect_files<'a>(
+    path: &'a Path,
+    files: &'a mut Vec<(PathBuf, u64)>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + 'a>> {
+    Box::pin(async move {
+        let mut entries = match tokio::fs::read_dir(path).await {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            if metadata.is_file() {
+                files.push((entry.path(), metadata.len()));
+            } else if metadata.is_dir() {
+                collect_files(&entry.path(), files).await;
+            }
+        }
+    })
+}
@@ -4,60 +4,406 @@
 //! 1. Verify - PAR2 verification
 //! 2. Repair - PAR2 repair (if verification fails)
 //! 3. Extract - Archive extraction (RAR, 7z, ZIP)
-//! 4. Move - Move files to final destination
-//! 5. Cleanup - Remove intermediate files (.par2, .nzb, archives, samples)
-
-use crate::config::{Config, PostProcess};
-use crate::error::{PostProcessError, Result};
+//! 4. Scan - Virus scan of the extracted files (if enabled)
+//! 5. Move - Move files to final destination
+//! 6. Cleanup - Remove intermediate files (.par2, .nzb, archives, samples)
+//!
+//! Verify, repair, extract and scan record a checkpoint in the database once they
+//! succeed. A later run of the pipeline skips the checkpointed stages, so a download whose
+//! move failed on a full disk isn't verified and extracted again. Move and cleanup carry
+//! on from whatever is left in the temp folder, so they always run. Checkpoints are cleared
+//! when the download fetches articles again, when its pipeline completes, and for a stage
+//! and every later one when that stage runs again.
+
+use crate::config::{Config, JunkFilterConfig, MoveMode, PostProcess, VerifyPolicy};
+use crate::deobfuscation::{DeobfuscationContext, DeobfuscationPipeline};
+use crate::error::{Error, PostProcessError, Result};
+use crate::event_bus::EventSender;
+use crate::extraction::{CliRarExtractor, ExtractorRegistry};
 use crate::parity::ParityHandler;
-use crate::types::{DownloadId, Event};
-use crate::utils::get_unique_path;
+use crate::types::{DownloadId, Event, Stage};
+use crate::utils::{get_unique_path, long_path, sanitize_filename};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 mod cleanup;
+mod junk;
+mod permissions;
 mod repair;
+mod scan;
+mod subtitles;
+mod transfer;
 mod verify;
 
 // Re-export stages for internal use
 use cleanup::run_cleanup_stage;
 use repair::run_repair_stage;
+use scan::run_scan_stage;
+pub(crate) use transfer::remove_expired_linked_originals;
+use transfer::{TransferProgress, total_size, transfer_file};
 use verify::run_verify_stage;
 
+/// Stages of the full pipeline, in the order they run
+const PIPELINE_STAGES: [Stage; 6] = [
+    Stage::Verify,
+    Stage::Repair,
+    Stage::Extract,
+    Stage::Scan,
+    Stage::Move,
+    Stage::Cleanup,
+];
+
+/// What the pipeline did for a download, recorded for the completion sidecar
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct PostProcessSummary {
+    /// PAR2 verification ran
+    pub verified: bool,
+    /// Verification found damaged or missing files
+    pub damaged: bool,
+    /// Files rebuilt by PAR2 repair
+    pub repaired_files: Vec<String>,
+    /// Archives extracted, by file name
+    pub extracted_archives: Vec<String>,
+    /// Archives that failed to extract, by file name
+    pub failed_archives: Vec<String>,
+    /// Files written by extraction, including those from nested archives
+    pub extracted_files: usize,
+    /// Archives were extracted while downloading (DirectUnpack)
+    pub direct_unpack: bool,
+}
+
 /// Post-processing pipeline executor
 pub struct PostProcessor {
     /// Event channel for emitting pipeline events
-    event_tx: broadcast::Sender<Event>,
+    event_tx: EventSender,
     /// Configuration for file collision handling
     config: Arc<Config>,
     /// PAR2 parity handler for verification and repair
     parity_handler: Arc<dyn ParityHandler>,
+    /// External `unrar`/`7z` extractor for RAR archives, if one was found
+    rar_cli: Option<CliRarExtractor>,
+    /// Extractors registered through `UsenetDownloader::register_extractor`
+    extractors: ExtractorRegistry,
+    /// Renaming strategies run before extraction
+    deobfuscation: DeobfuscationPipeline,
     /// Database for password caching during extraction
     db: Arc<crate::db::Database>,
+    /// Final paths of the files moved by each download's last move stage
+    moved_files: std::sync::Mutex<HashMap<DownloadId, Vec<PathBuf>>>,
+    /// What each download's last pipeline run did
+    summaries: std::sync::Mutex<HashMap<DownloadId, PostProcessSummary>>,
+}
+
+/// Settings of a single pipeline run that a download's category can override
+#[derive(Clone, Debug)]
+pub struct RunOptions {
+    /// Move stage settings
+    pub(crate) move_options: MoveOptions,
+    /// When PAR2 verification runs
+    pub(crate) verify_policy: VerifyPolicy,
+    /// Cancelled by the stall watchdog to stop the run
+    pub(crate) cancel: CancellationToken,
+}
+
+impl RunOptions {
+    /// The settings from `config`, without any category override
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            move_options: MoveOptions::from_config(config),
+            verify_policy: config.processing.verify_policy,
+            cancel: CancellationToken::new(),
+        }
+    }
+}
+
+/// Move stage settings a download's category can override
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct MoveOptions {
+    /// Move the contents of a lone top-level folder rather than the folder itself
+    pub(crate) flatten_single_folder: bool,
+    /// Junk files removed before moving
+    pub(crate) junk_filter: JunkFilterConfig,
+}
+
+impl MoveOptions {
+    /// The settings from `config`, without any category override
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            flatten_single_folder: config.processing.extraction.flatten_single_folder,
+            junk_filter: config.processing.junk_filter.clone(),
+        }
+    }
 }
 
 impl PostProcessor {
     /// Create a new post-processing pipeline executor
     pub fn new(
-        event_tx: broadcast::Sender<Event>,
+        event_tx: EventSender,
         config: Arc<Config>,
         parity_handler: Arc<dyn ParityHandler>,
         db: Arc<crate::db::Database>,
     ) -> Self {
+        let rar_cli = CliRarExtractor::from_config(&config.tools);
+        match &rar_cli {
+            Some(cli) => info!(tool = ?cli.tool(), "external RAR extractor available"),
+            None => debug!("no external RAR extractor found, using built-in extractor only"),
+        }
+
         Self {
             event_tx,
             config,
             parity_handler,
+            rar_cli,
+            extractors: ExtractorRegistry::new(),
+            deobfuscation: DeobfuscationPipeline::default(),
             db,
+            moved_files: std::sync::Mutex::new(HashMap::new()),
+            summaries: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Extractors consulted before the built-in ones
+    pub(crate) fn extractors(&self) -> &ExtractorRegistry {
+        &self.extractors
+    }
+
+    /// Renaming strategies run before extraction
+    pub(crate) fn deobfuscation(&self) -> &DeobfuscationPipeline {
+        &self.deobfuscation
+    }
+
+    /// Take the final paths of the files moved by a download's last move stage
+    pub(crate) fn take_moved_files(&self, download_id: DownloadId) -> Vec<PathBuf> {
+        self.moved_files
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&download_id)
+            .unwrap_or_default()
+    }
+
+    /// Take the summary of what a download's last pipeline run did
+    pub(crate) fn take_summary(&self, download_id: DownloadId) -> PostProcessSummary {
+        self.summaries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&download_id)
+            .unwrap_or_default()
+    }
+
+    fn update_summary(
+        &self,
+        download_id: DownloadId,
+        update: impl FnOnce(&mut PostProcessSummary),
+    ) {
+        let mut summaries = self.summaries.lock().unwrap_or_else(|e| e.into_inner());
+        update(summaries.entry(download_id).or_default());
+    }
+
+    /// Journal the stage a download's pipeline is entering, so a crash can resume it there
+    ///
+    /// Running a stage again invalidates its checkpoint and those of every later stage.
+    async fn enter_stage(&self, download_id: DownloadId, stage: Stage) {
+        if let Err(e) = self
+            .db
+            .set_post_process_stage(download_id, Some(stage))
+            .await
+        {
+            warn!(download_id = download_id.0, error = %e, "failed to record post-processing stage");
+        }
+        let later = PIPELINE_STAGES
+            .iter()
+            .position(|&s| s == stage)
+            .map_or(&[][..], |index| &PIPELINE_STAGES[index..]);
+        if let Err(e) = self.db.clear_stage_checkpoints(download_id, later).await {
+            warn!(download_id = download_id.0, error = %e, "failed to clear stage checkpoints");
+        }
+    }
+
+    /// Record that a download completed `stage`, so a retry can skip it
+    async fn checkpoint(&self, download_id: DownloadId, stage: Stage) {
+        if let Err(e) = self.db.add_stage_checkpoint(download_id, stage).await {
+            warn!(download_id = download_id.0, error = %e, "failed to record stage checkpoint");
+        }
+    }
+
+    /// Stages a previous run of this download completed, none if they can't be read
+    async fn completed_stages(&self, download_id: DownloadId) -> Vec<Stage> {
+        self.db
+            .get_stage_checkpoints(download_id)
+            .await
+            .unwrap_or_else(|e| {
+                warn!(download_id = download_id.0, error = %e, "failed to read stage checkpoints");
+                Vec::new()
+            })
+    }
+
+    /// Fail a run that was cancelled, so it starts no further stage
+    fn check_cancelled(&self, download_id: DownloadId, options: &RunOptions) -> Result<()> {
+        if !options.cancel.is_cancelled() {
+            return Ok(());
+        }
+        Err(PostProcessError::Stalled {
+            id: download_id.into(),
+            reason: "stopped by the stall watchdog".to_string(),
+        }
+        .into())
+    }
+
+    /// Verify and repair, unless a previous run already left the files intact
+    async fn verify_unless_checkpointed(
+        &self,
+        download_id: DownloadId,
+        download_path: &Path,
+        repair: bool,
+        policy: VerifyPolicy,
+        done: &[Stage],
+    ) -> Result<()> {
+        if done.contains(&Stage::Verify) || done.contains(&Stage::Repair) {
+            info!(
+                download_id = download_id.0,
+                "files were verified by a previous run, skipping verify and repair"
+            );
+            self.update_summary(download_id, |summary| summary.verified = true);
+            return Ok(());
+        }
+        self.run_verify_and_repair(download_id, download_path, repair, policy)
+            .await
+    }
+
+    /// Extract, unless a previous run already extracted everything
+    async fn extract_unless_checkpointed(
+        &self,
+        download_id: DownloadId,
+        download_path: &Path,
+        options: &RunOptions,
+        done: &[Stage],
+    ) -> Result<PathBuf> {
+        if !done.contains(&Stage::Extract) {
+            return self
+                .run_extract_stage(download_id, download_path, options)
+                .await;
+        }
+        info!(
+            download_id = download_id.0,
+            "archives were extracted by a previous run, skipping extraction"
+        );
+        let extracted = download_path.join("extracted");
+        Ok(if extracted.is_dir() {
+            extracted
+        } else {
+            download_path.to_path_buf()
+        })
+    }
+
+    /// Scan, unless a previous run already found the files clean
+    async fn scan_unless_checkpointed(
+        &self,
+        download_id: DownloadId,
+        download_path: &Path,
+        scan_path: &Path,
+        done: &[Stage],
+    ) -> Result<()> {
+        if done.contains(&Stage::Scan) {
+            debug!(
+                download_id = download_id.0,
+                "files were scanned by a previous run, skipping scan"
+            );
+            return Ok(());
+        }
+        self.run_scan(download_id, download_path, scan_path).await
+    }
+
+    /// Run the verify stage, then the repair stage if damage was found and `repair` is set
+    async fn run_verify_and_repair(
+        &self,
+        download_id: DownloadId,
+        download_path: &Path,
+        repair: bool,
+        policy: VerifyPolicy,
+    ) -> Result<()> {
+        match policy {
+            VerifyPolicy::Never => {
+                info!(
+                    download_id = download_id.0,
+                    "verify policy is never, skipping verify and repair"
+                );
+                return Ok(());
+            }
+            VerifyPolicy::Always => self.ensure_verifiable(download_id, download_path).await?,
+            VerifyPolicy::WhenParsPresent => {}
         }
+        self.enter_stage(download_id, Stage::Verify).await;
+        let damaged = run_verify_stage(
+            download_id,
+            download_path,
+            &self.event_tx,
+            &*self.parity_handler,
+        )
+        .await?;
+        self.update_summary(download_id, |summary| {
+            summary.verified = true;
+            summary.damaged = damaged;
+        });
+        if !damaged {
+            self.checkpoint(download_id, Stage::Verify).await;
+        } else if repair {
+            self.run_repair(download_id, download_path).await?;
+        }
+        Ok(())
+    }
+
+    /// Fail a download that verification is required for but can't be verified
+    async fn ensure_verifiable(&self, download_id: DownloadId, download_path: &Path) -> Result<()> {
+        let reason = if !self.parity_handler.capabilities().can_verify {
+            format!(
+                "verification is required but {} can't verify PAR2 files",
+                self.parity_handler.name()
+            )
+        } else if verify::find_par2_files(download_path).await?.is_empty() {
+            "verification is required but the download has no PAR2 files".to_string()
+        } else {
+            return Ok(());
+        };
+        Err(PostProcessError::VerificationFailed {
+            id: download_id.into(),
+            reason,
+        }
+        .into())
+    }
+
+    /// Run the repair stage and record the files it rebuilt and the blocks it used
+    async fn run_repair(&self, download_id: DownloadId, download_path: &Path) -> Result<()> {
+        self.enter_stage(download_id, Stage::Repair).await;
+        let outcome = run_repair_stage(
+            download_id,
+            download_path,
+            &self.event_tx,
+            &*self.parity_handler,
+        )
+        .await?;
+        if let Err(e) = self
+            .db
+            .set_repair_blocks_used(download_id, outcome.blocks_used)
+            .await
+        {
+            warn!(download_id = download_id.0, error = %e, "failed to record repair blocks used");
+        }
+        self.update_summary(download_id, |summary| {
+            summary.repaired_files = outcome.repaired_files
+        });
+        self.checkpoint(download_id, Stage::Repair).await;
+        Ok(())
     }
 
     /// Execute post-processing pipeline for a completed download
     ///
     /// This is the main entry point for post-processing. It orchestrates
-    /// the pipeline stages based on the configured PostProcess mode.
+    /// the pipeline stages based on the configured PostProcess mode, skipping those a
+    /// previous run of this download completed.
     ///
     /// # Arguments
     ///
@@ -65,6 +411,7 @@ impl PostProcessor {
     /// * `download_path` - Path to the downloaded files
     /// * `post_process` - Post-processing mode to use
     /// * `destination` - Final destination for files
+    /// * `options` - Settings for this run, the download's category overrides applied
     ///
     /// # Returns
     ///
@@ -75,6 +422,7 @@ impl PostProcessor {
         download_path: PathBuf,
         post_process: PostProcess,
         destination: PathBuf,
+        options: &RunOptions,
     ) -> Result<PathBuf> {
         info!(
             download_id = download_id.0,
@@ -83,6 +431,8 @@ impl PostProcessor {
             ?destination,
             "starting post-processing pipeline"
         );
+        self.take_summary(download_id);
+        let done = self.completed_stages(download_id).await;
 
         // Execute pipeline stages based on post-processing mode
         match post_process {
@@ -97,11 +447,12 @@ impl PostProcessor {
 
             PostProcess::Verify => {
                 // Just verify
-                run_verify_stage(
+                self.verify_unless_checkpointed(
                     download_id,
                     &download_path,
-                    &self.event_tx,
-                    &*self.parity_handler,
+                    false,
+                    options.verify_policy,
+                    &done,
                 )
                 .await?;
                 Ok(download_path)
@@ -109,71 +460,66 @@ impl PostProcessor {
 
             PostProcess::Repair => {
                 // Verify and repair if needed
-                let damaged = run_verify_stage(
+                self.verify_unless_checkpointed(
                     download_id,
                     &download_path,
-                    &self.event_tx,
-                    &*self.parity_handler,
+                    true,
+                    options.verify_policy,
+                    &done,
                 )
                 .await?;
-                if damaged {
-                    run_repair_stage(
-                        download_id,
-                        &download_path,
-                        &self.event_tx,
-                        &*self.parity_handler,
-                    )
-                    .await?;
-                }
                 Ok(download_path)
             }
 
             PostProcess::Unpack => {
-                // Verify, repair, and extract
-                let damaged = run_verify_stage(
+                // Verify, repair, extract and scan
+                self.verify_unless_checkpointed(
                     download_id,
                     &download_path,
-                    &self.event_tx,
-                    &*self.parity_handler,
+                    true,
+                    options.verify_policy,
+                    &done,
                 )
                 .await?;
-                if damaged {
-                    run_repair_stage(
-                        download_id,
-                        &download_path,
-                        &self.event_tx,
-                        &*self.parity_handler,
-                    )
+                self.check_cancelled(download_id, options)?;
+                let extracted_path = self
+                    .extract_unless_checkpointed(download_id, &download_path, options, &done)
+                    .await?;
+                self.check_cancelled(download_id, options)?;
+                self.scan_unless_checkpointed(download_id, &download_path, &extracted_path, &done)
                     .await?;
-                }
-                let extracted_path = self.run_extract_stage(download_id, &download_path).await?;
                 Ok(extracted_path)
             }
 
             PostProcess::UnpackAndCleanup => {
-                // Full pipeline: verify, repair, extract, move, cleanup
-                let damaged = run_verify_stage(
+                // Full pipeline: verify, repair, extract, scan, move, cleanup
+                self.verify_unless_checkpointed(
                     download_id,
                     &download_path,
-                    &self.event_tx,
-                    &*self.parity_handler,
+                    true,
+                    options.verify_policy,
+                    &done,
                 )
                 .await?;
-                if damaged {
-                    run_repair_stage(
+                self.check_cancelled(download_id, options)?;
+                let extracted_path = self
+                    .extract_unless_checkpointed(download_id, &download_path, options, &done)
+                    .await?;
+                self.check_cancelled(download_id, options)?;
+                self.scan_unless_checkpointed(download_id, &download_path, &extracted_path, &done)
+                    .await?;
+                self.check_cancelled(download_id, options)?;
+                let final_path = self
+                    .run_move_stage(
                         download_id,
                         &download_path,
-                        &self.event_tx,
-                        &*self.parity_handler,
+                        &extracted_path,
+                        &destination,
+                        &options.move_options,
                     )
                     .await?;
-                }
-                let extracted_path = self.run_extract_stage(download_id, &download_path).await?;
-                let final_path = self
-                    .run_move_stage(download_id, &extracted_path, &destination)
-                    .await?;
-                run_cleanup_stage(download_id, &download_path, &self.event_tx, &self.config)
-                    .await?;
+                self.check_cancelled(download_id, options)?;
+                self.run_cleanup(download_id, &download_path).await?;
                 Ok(final_path)
             }
         }
@@ -188,6 +534,7 @@ impl PostProcessor {
         download_id: DownloadId,
         download_path: PathBuf,
         destination: PathBuf,
+        options: &RunOptions,
     ) -> Result<PathBuf> {
         info!(
             download_id = download_id.0,
@@ -195,6 +542,8 @@ impl PostProcessor {
             ?destination,
             "running move+cleanup only (DirectUnpack completed)"
         );
+        self.take_summary(download_id);
+        self.update_summary(download_id, |summary| summary.direct_unpack = true);
 
         // The extracted files should be in the same location the extract stage uses
         let extracted_path = download_path.join("extracted");
@@ -205,10 +554,113 @@ impl PostProcessor {
             download_path.clone()
         };
 
+        let done = self.completed_stages(download_id).await;
+        self.scan_unless_checkpointed(download_id, &download_path, &source, &done)
+            .await?;
+        self.check_cancelled(download_id, options)?;
+        let final_path = self
+            .run_move_stage(
+                download_id,
+                &download_path,
+                &source,
+                &destination,
+                &options.move_options,
+            )
+            .await?;
+        self.check_cancelled(download_id, options)?;
+        self.run_cleanup(download_id, &download_path).await?;
+        Ok(final_path)
+    }
+
+    /// Run the full pipeline starting at `from_stage`
+    ///
+    /// Every stage from `from_stage` through cleanup runs against the files already in
+    /// `download_path`, regardless of the download's post-processing mode. Starting at
+    /// `Repair` runs repair unconditionally; starting at `Verify` only repairs if damaged.
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(final_path) on success, Err on failure (including `Download` and
+    /// `DirectUnpack`, which can't be re-run from the temp folder)
+    pub async fn run_from_stage(
+        &self,
+        download_id: DownloadId,
+        download_path: PathBuf,
+        from_stage: Stage,
+        destination: PathBuf,
+        options: &RunOptions,
+    ) -> Result<PathBuf> {
+        info!(
+            download_id = download_id.0,
+            ?from_stage,
+            ?download_path,
+            ?destination,
+            "re-running post-processing from stage"
+        );
+        self.take_summary(download_id);
+
+        let extracted_path = match from_stage {
+            Stage::Verify | Stage::Repair | Stage::Extract => {
+                match from_stage {
+                    Stage::Verify => {
+                        self.run_verify_and_repair(
+                            download_id,
+                            &download_path,
+                            true,
+                            options.verify_policy,
+                        )
+                        .await?
+                    }
+                    Stage::Repair => self.run_repair(download_id, &download_path).await?,
+                    _ => {}
+                }
+                self.check_cancelled(download_id, options)?;
+                let extracted = self
+                    .run_extract_stage(download_id, &download_path, options)
+                    .await?;
+                self.check_cancelled(download_id, options)?;
+                self.run_scan(download_id, &download_path, &extracted)
+                    .await?;
+                extracted
+            }
+            Stage::Scan | Stage::Move => {
+                // Pick up where a previous extraction left its output
+                let extracted = download_path.join("extracted");
+                let extracted = if extracted.is_dir() {
+                    extracted
+                } else {
+                    download_path.clone()
+                };
+                if from_stage == Stage::Scan {
+                    self.run_scan(download_id, &download_path, &extracted)
+                        .await?;
+                }
+                extracted
+            }
+            Stage::Cleanup => {
+                self.run_cleanup(download_id, &download_path).await?;
+                return Ok(destination);
+            }
+            Stage::Download | Stage::DirectUnpack => {
+                return Err(crate::Error::NotSupported(format!(
+                    "cannot reprocess from the {:?} stage",
+                    from_stage
+                )));
+            }
+        };
+
+        self.check_cancelled(download_id, options)?;
         let final_path = self
-            .run_move_stage(download_id, &source, &destination)
+            .run_move_stage(
+                download_id,
+                &download_path,
+                &extracted_path,
+                &destination,
+                &options.move_options,
+            )
             .await?;
-        run_cleanup_stage(download_id, &download_path, &self.event_tx, &self.config).await?;
+        self.check_cancelled(download_id, options)?;
+        self.run_cleanup(download_id, &download_path).await?;
         Ok(final_path)
     }
 
@@ -223,6 +675,7 @@ impl PostProcessor {
     /// * `download_id` - The download to re-extract
     /// * `download_path` - Path to the downloaded files
     /// * `destination` - Final destination for extracted files
+    /// * `options` - Settings for this run, the download's category overrides applied
     ///
     /// # Returns
     ///
@@ -232,6 +685,7 @@ impl PostProcessor {
         download_id: DownloadId,
         download_path: PathBuf,
         destination: PathBuf,
+        options: &RunOptions,
     ) -> Result<PathBuf> {
         info!(
             download_id = download_id.0,
@@ -239,28 +693,45 @@ impl PostProcessor {
             ?destination,
             "starting re-extraction (skip verify/repair)"
         );
+        self.take_summary(download_id);
 
-        // Run only extract and move stages
-        let extracted_path = self.run_extract_stage(download_id, &download_path).await?;
+        // Run only extract, scan and move stages
+        let extracted_path = self
+            .run_extract_stage(download_id, &download_path, options)
+            .await?;
+        self.check_cancelled(download_id, options)?;
+        self.run_scan(download_id, &download_path, &extracted_path)
+            .await?;
 
+        self.check_cancelled(download_id, options)?;
         let final_path = self
-            .run_move_stage(download_id, &extracted_path, &destination)
+            .run_move_stage(
+                download_id,
+                &download_path,
+                &extracted_path,
+                &destination,
+                &options.move_options,
+            )
             .await?;
 
         Ok(final_path)
     }
 
     /// Execute the extract stage
+    ///
+    /// Cancelling the run kills the external extractor and skips the remaining archives.
     async fn run_extract_stage(
         &self,
         download_id: DownloadId,
         download_path: &Path,
+        options: &RunOptions,
     ) -> Result<PathBuf> {
         debug!(
             download_id = download_id.0,
             ?download_path,
             "running extract stage"
         );
+        self.enter_stage(download_id, Stage::Extract).await;
 
         // Emit Extracting event (initial progress)
         self.event_tx
@@ -271,6 +742,11 @@ impl PostProcessor {
             })
             .ok();
 
+        // Restore real names, including those RAR volumes need to be followed as a set
+        if self.config.automation.deobfuscation.enabled {
+            self.run_deobfuscation(download_id, download_path).await;
+        }
+
         // Detect all archives in the download directory
         let archives = self.detect_all_archives(download_path)?;
 
@@ -285,6 +761,7 @@ impl PostProcessor {
             self.event_tx
                 .send(Event::ExtractComplete { id: download_id })
                 .ok();
+            self.checkpoint(download_id, Stage::Extract).await;
 
             return Ok(download_path.to_path_buf());
         }
@@ -304,8 +781,24 @@ impl PostProcessor {
         let passwords = self.collect_extraction_passwords(download_id).await;
 
         // Extract all archives with progress tracking
-        self.extract_archives(download_id, &archives, &extract_dest, &passwords)
+        let started = std::time::Instant::now();
+        let password_failure = self
+            .extract_archives(download_id, &archives, &extract_dest, &passwords, options)
             .await;
+        if let Err(e) = self
+            .db
+            .set_extraction_time(download_id, started.elapsed())
+            .await
+        {
+            warn!(download_id = download_id.0, error = %e, "failed to record extraction time");
+        }
+        // Partly extracted: a later run has to extract again
+        self.check_cancelled(download_id, options)?;
+
+        // An archive no password opened leaves the download waiting for one
+        if let Some(e) = password_failure {
+            return Err(e);
+        }
 
         // Emit ExtractComplete event
         self.event_tx
@@ -318,6 +811,7 @@ impl PostProcessor {
             "extraction stage complete, extracted files in: {:?}",
             extract_dest
         );
+        self.checkpoint(download_id, Stage::Extract).await;
 
         Ok(extract_dest)
     }
@@ -325,29 +819,45 @@ impl PostProcessor {
     /// Collect passwords from all sources for extraction
     ///
     /// Gathers passwords from:
-    /// 1. Cached password from the database (includes NZB metadata and per-download passwords)
-    /// 2. Global password file from configuration
-    /// 3. Empty password if configured to try
+    /// 1. Cached password from the database (one that extracted this download before)
+    /// 2. Per-download password given when the download was added
+    /// 3. NZB metadata password
+    /// 4. Password provider lookup by release name (if configured)
+    /// 5. Global password file from configuration
+    /// 6. Empty password if configured to try
     async fn collect_extraction_passwords(
         &self,
         download_id: DownloadId,
     ) -> crate::extraction::PasswordList {
         // Get cached password for this download (if any)
-        // This includes any password from DownloadOptions or NZB metadata that was cached during download
         let cached_password = match self.db.get_cached_password(download_id).await {
             Ok(Some(pw)) => Some(pw),
             _ => None,
         };
+        let download_passwords = self
+            .db
+            .get_download_passwords(download_id)
+            .await
+            .unwrap_or_default();
+
+        // The password provider looks passwords up by release name
+        let provider = self.config.tools.password_provider.as_deref();
+        let release_name = match provider {
+            Some(_) => match self.db.get_download(download_id).await {
+                Ok(Some(download)) => download.release_name().to_string(),
+                _ => String::new(),
+            },
+            None => String::new(),
+        };
 
         // Collect passwords from all sources
-        // Note: Per-download and NZB metadata passwords are already cached in the database
-        // and retrieved above as cached_password (highest priority if extraction succeeded before)
         let passwords = crate::extraction::PasswordList::collect(
             cached_password.as_deref(),
-            None, // Per-download password already in cached_password
-            None, // NZB metadata password already in cached_password
+            download_passwords.password.as_deref(),
+            download_passwords.nzb_password.as_deref(),
             self.config.tools.password_file.as_deref(), // Global password file
-            self.config.tools.try_empty_password, // Try empty password as fallback
+            self.config.tools.try_empty_password,       // Try empty password as fallback
+            provider.map(|p| (p, release_name.as_str())),
         )
         .await;
 
@@ -361,19 +871,67 @@ impl PostProcessor {
         passwords
     }
 
+    /// Run the deobfuscation pipeline over the download folder
+    async fn run_deobfuscation(&self, download_id: DownloadId, download_path: &Path) {
+        let download = match self.db.get_download(download_id).await {
+            Ok(download) => download,
+            Err(e) => {
+                warn!(download_id = download_id.0, error = %e, "failed to load download for deobfuscation");
+                None
+            }
+        };
+        let name = download.as_ref().map_or("", |d| d.name.as_str());
+        let job_name = Path::new(name)
+            .extension()
+            .filter(|ext| ext.eq_ignore_ascii_case("nzb"))
+            .and_then(|_| Path::new(name).file_stem())
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(name);
+        let ctx = DeobfuscationContext {
+            download_id,
+            dir: download_path,
+            job_name,
+            nzb_meta_name: download.as_ref().and_then(|d| d.nzb_meta_name.as_deref()),
+            min_length: self.config.automation.deobfuscation.min_length,
+        };
+
+        let renamed = self
+            .deobfuscation
+            .run(&ctx, &self.config.automation.deobfuscation)
+            .await;
+        for (old, new) in &renamed {
+            debug!(
+                download_id = download_id.0,
+                ?old,
+                ?new,
+                "renamed obfuscated file"
+            );
+        }
+    }
+
     /// Extract all archives with progress tracking
     ///
     /// Iterates through all detected archives and extracts them with recursive
     /// nested archive support. Emits progress events and logs errors but continues
-    /// extraction even if individual archives fail.
+    /// extraction even if individual archives fail, but stops once the run is cancelled.
+    /// Returns the first failure to find a working password, if any archive had one.
     async fn extract_archives(
         &self,
         download_id: DownloadId,
         archives: &[PathBuf],
         extract_dest: &Path,
         passwords: &crate::extraction::PasswordList,
-    ) {
+        options: &RunOptions,
+    ) -> Option<Error> {
+        let rar_cli = self
+            .rar_cli
+            .clone()
+            .map(|cli| cli.with_cancellation(options.cancel.clone()));
+        let mut password_failure = None;
         for (i, archive_path) in archives.iter().enumerate() {
+            if options.cancel.is_cancelled() {
+                break;
+            }
             let archive_name = archive_path
                 .file_name()
                 .and_then(|n| n.to_str())
@@ -407,11 +965,17 @@ impl PostProcessor {
                 passwords,
                 &self.db,
                 &self.config.processing.extraction,
+                rar_cli.as_ref(),
+                &self.extractors,
                 0, // Start at depth 0
             )
             .await
             {
                 Ok(extracted_files) => {
+                    self.update_summary(download_id, |summary| {
+                        summary.extracted_archives.push(archive_name.to_string());
+                        summary.extracted_files += extracted_files.len();
+                    });
                     info!(
                         download_id = download_id.0,
                         ?archive_path,
@@ -422,6 +986,9 @@ impl PostProcessor {
                     );
                 }
                 Err(e) => {
+                    self.update_summary(download_id, |summary| {
+                        summary.failed_archives.push(archive_name.to_string());
+                    });
                     // Log error but continue with other archives
                     warn!(
                         download_id = download_id.0,
@@ -430,14 +997,28 @@ impl PostProcessor {
                         "failed to extract archive {}, continuing with others",
                         archive_name
                     );
+                    if password_failure.is_none()
+                        && matches!(
+                            e,
+                            Error::PostProcess(
+                                PostProcessError::AllPasswordsFailed { .. }
+                                    | PostProcessError::NoPasswordsAvailable { .. }
+                            )
+                        )
+                    {
+                        password_failure = Some(e);
+                    }
                 }
             }
         }
+        password_failure
     }
 
     /// Detect all archives in the download directory
     ///
-    /// Scans for RAR, 7z, and ZIP archives
+    /// Scans for files a registered extractor handles, for RAR, 7z, and ZIP archives and
+    /// Zstandard-compressed files by extension, then for archives whose names hide them
+    /// by signature
     fn detect_all_archives(&self, download_path: &Path) -> Result<Vec<PathBuf>> {
         let mut all_archives = Vec::new();
 
@@ -454,15 +1035,118 @@ impl PostProcessor {
         let zip_archives = crate::extraction::ZipExtractor::detect_zip_files(download_path)?;
         all_archives.extend(zip_archives);
 
-        Ok(all_archives)
+        // Detect Zstandard-compressed files
+        let zst_archives = crate::extraction::ZstdExtractor::detect_zst_files(download_path)?;
+        all_archives.extend(zst_archives);
+
+        // Detect archives obfuscated behind non-archive names
+        let disguised_archives = crate::extraction::detect_disguised_archives(download_path)?;
+        all_archives.extend(disguised_archives);
+
+        // Files a registered extractor handles are extracted by it alone
+        let mut custom_archives = self.extractors.detect_files(download_path)?;
+        all_archives.retain(|path| !custom_archives.contains(path));
+        custom_archives.extend(all_archives);
+
+        Ok(custom_archives)
+    }
+
+    /// Extract the archives in subtitle folders and pair the subtitles with their video
+    async fn organize_subtitles(&self, download_id: DownloadId, source: &Path) {
+        let config = &self.config.processing.subtitles;
+        let mut folders = Vec::new();
+        subtitles::find_subtitle_folders(source, &config.folder_names, &mut folders).await;
+        if folders.is_empty() {
+            return;
+        }
+
+        let mut passwords = None;
+        for folder in &folders {
+            let archives = self.detect_all_archives(folder).unwrap_or_default();
+            if archives.is_empty() {
+                continue;
+            }
+            if passwords.is_none() {
+                passwords = Some(self.collect_extraction_passwords(download_id).await);
+            }
+            let Some(passwords) = &passwords else {
+                continue;
+            };
+            let mut all_extracted = true;
+            for archive in &archives {
+                let result = match self.extractors.find(archive) {
+                    Some(extractor) => {
+                        extractor
+                            .extract(download_id, archive, folder, passwords)
+                            .await
+                    }
+                    None => {
+                        crate::extraction::extract_archive_with_cli(
+                            download_id,
+                            archive,
+                            folder,
+                            passwords,
+                            &self.db,
+                            self.rar_cli.as_ref(),
+                            self.config.processing.extraction.prefer_cli,
+                        )
+                        .await
+                    }
+                };
+                if let Err(e) = result {
+                    all_extracted = false;
+                    warn!(download_id = download_id.0, ?archive, error = %e, "failed to extract subtitle archive");
+                }
+            }
+            if all_extracted {
+                subtitles::remove_archives(
+                    folder,
+                    &self.config.processing.cleanup.archive_extensions,
+                )
+                .await;
+            }
+        }
+
+        subtitles::pair_subtitles(download_id, source, &folders, config).await;
+    }
+
+    /// Execute the scan stage over the files about to be moved
+    async fn run_scan(
+        &self,
+        download_id: DownloadId,
+        download_path: &Path,
+        scan_path: &Path,
+    ) -> Result<()> {
+        self.enter_stage(download_id, Stage::Scan).await;
+        run_scan_stage(
+            download_id,
+            download_path,
+            scan_path,
+            &self.event_tx,
+            &self.config,
+        )
+        .await?;
+        self.checkpoint(download_id, Stage::Scan).await;
+        Ok(())
+    }
+
+    /// Execute the cleanup stage
+    async fn run_cleanup(&self, download_id: DownloadId, download_path: &Path) -> Result<()> {
+        self.enter_stage(download_id, Stage::Cleanup).await;
+        run_cleanup_stage(download_id, download_path, &self.event_tx, &self.config).await
     }
 
     /// Execute the move stage
+    ///
+    /// In [`MoveMode::Link`](crate::config::MoveMode::Link) the originals stay in
+    /// `download_path` until `download.link_retention` has passed.
     async fn run_move_stage(
         &self,
         download_id: DownloadId,
+        download_path: &Path,
         source_path: &Path,
         destination: &Path,
+        options: &MoveOptions,
     ) -> Result<PathBuf> {
         debug!(
             download_id = download_id.0,
@@ -470,6 +1154,7 @@ impl PostProcessor {
             ?destination,
             "running move stage"
         );
+        self.enter_stage(download_id, Stage::Move).await;
 
         // Emit Moving event
         self.event_tx
@@ -479,8 +1164,33 @@ impl PostProcessor {
             })
             .ok();
 
+        if options.junk_filter.enabled {
+            junk::remove_junk(download_id, source_path, &options.junk_filter).await;
+        }
+        if self.config.processing.subtitles.enabled {
+            self.organize_subtitles(download_id, source_path).await;
+        }
+        let source_path = if options.flatten_single_folder {
+            &single_folder(source_path).await
+        } else {
+            source_path
+        };
+
         // Perform the actual file move with collision handling
-        self.move_files(download_id, source_path, destination).await
+        let final_path = self
+            .move_files(download_id, source_path, destination)
+            .await?;
+
+        if self.config.download.move_mode == MoveMode::Link {
+            transfer::retain_linked_originals(
+                download_id,
+                download_path,
+                self.config.download.link_retention,
+            )
+            .await;
+        }
+
+        Ok(final_path)
     }
 
     /// Move files from source to destination with collision handling
@@ -528,33 +1238,54 @@ impl PostProcessor {
             }
         };
 
+        let destination = &self.sanitize_last_component(destination);
+
         // Ensure destination parent directory exists
         if let Some(parent) = destination.parent() {
             // create_dir_all handles the case when directory already exists
-            fs::create_dir_all(parent).await?;
+            fs::create_dir_all(long_path(parent)).await?;
         }
 
-        // If source is a file, move it directly
-        if source_metadata.is_file() {
-            return self
-                .move_single_file(download_id, source_path, destination)
-                .await;
-        }
+        let progress = TransferProgress::new(
+            download_id,
+            self.event_tx.clone(),
+            total_size(source_path).await?,
+        );
 
-        // If source is a directory, move all its contents
-        if source_metadata.is_dir() {
-            return self
-                .move_directory_contents(download_id, source_path, destination)
-                .await;
+        let final_path = if source_metadata.is_file() {
+            // If source is a file, move it directly
+            self.move_single_file(download_id, source_path, destination, &progress)
+                .await?
+        } else if source_metadata.is_dir() {
+            // If source is a directory, move all its contents
+            self.move_directory_contents(download_id, source_path, destination, &progress)
+                .await?
+        } else {
+            // Source is neither file nor directory
+            return Err(crate::error::Error::PostProcess(
+                PostProcessError::InvalidPath {
+                    path: source_path.to_path_buf(),
+                    reason: "Source is neither a file nor a directory".to_string(),
+                },
+            ));
+        };
+
+        let verified_files = progress.verified_files();
+        if verified_files > 0 {
+            self.event_tx
+                .send(Event::MoveVerified {
+                    id: download_id,
+                    files: verified_files,
+                    checksum: self.config.download.move_checksum,
+                })
+                .ok();
         }
 
-        // If we get here, source is neither file nor directory
-        Err(crate::error::Error::PostProcess(
-            PostProcessError::InvalidPath {
-                path: source_path.to_path_buf(),
-                reason: "Source is neither a file nor a directory".to_string(),
-            },
-        ))
+        self.moved_files
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(download_id, progress.into_moved_files());
+        Ok(final_path)
     }
 
     /// Move a single file to destination with collision handling
@@ -563,9 +1294,8 @@ impl PostProcessor {
         download_id: DownloadId,
         source_file: &Path,
         destination: &Path,
+        progress: &TransferProgress,
     ) -> Result<PathBuf> {
-        use tokio::fs;
-
         // Apply collision handling to get the actual destination path
         let final_destination = get_unique_path(destination, self.config.download.file_collision)?;
 
@@ -576,8 +1306,24 @@ impl PostProcessor {
             "moving single file"
         );
 
-        // Perform the move
-        fs::rename(source_file, &final_destination).await?;
+        // Perform the move (rename, or verified copy across filesystems)
+        transfer_file(
+            download_id,
+            source_file,
+            &final_destination,
+            &self.config,
+            progress,
+        )
+        .await?;
+
+        permissions::apply(
+            download_id,
+            &final_destination,
+            false,
+            &self.config.processing.permissions,
+        )
+        .await;
+        progress.record_moved(final_destination.clone());
 
         info!(
             download_id = download_id.0,
@@ -595,6 +1341,7 @@ impl PostProcessor {
         download_id: DownloadId,
         source_dir: &'a Path,
         destination: &'a Path,
+        progress: &'a TransferProgress,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<PathBuf>> + Send + 'a>> {
         Box::pin(async move {
             use tokio::fs;
@@ -606,8 +1353,20 @@ impl PostProcessor {
                 "moving directory contents"
             );
 
-            // Create destination directory (create_dir_all handles existing)
-            fs::create_dir_all(destination).await?;
+            // Create destination directory (create_dir_all handles existing). Only
+            // directories created here get normalized permissions, so a shared
+            // destination like the download dir is left alone.
+            let created = !fs::try_exists(destination).await.unwrap_or(false);
+            fs::create_dir_all(long_path(destination)).await?;
+            if created {
+                permissions::apply(
+                    download_id,
+                    destination,
+                    true,
+                    &self.config.processing.permissions,
+                )
+                .await;
+            }
 
             // Read all entries in source directory
             let mut entries = fs::read_dir(source_dir).await?;
@@ -615,23 +1374,35 @@ impl PostProcessor {
             // Move each entry
             while let Some(entry) = entries.next_entry().await? {
                 let source_entry_path = entry.path();
-                let entry_name = entry.file_name();
-                let dest_entry_path = destination.join(&entry_name);
+                let dest_entry_path =
+                    self.sanitize_last_component(&destination.join(entry.file_name()));
 
                 // Get file type from the entry (avoids extra syscall)
                 let file_type = entry.file_type().await?;
 
                 if file_type.is_file() {
                     // Move file with collision handling
-                    self.move_single_file(download_id, &source_entry_path, &dest_entry_path)
-                        .await?;
+                    self.move_single_file(
+                        download_id,
+                        &source_entry_path,
+                        &dest_entry_path,
+                        progress,
+                    )
+                    .await?;
                 } else if file_type.is_dir() {
                     // Recursively move subdirectory
-                    self.move_directory_contents(download_id, &source_entry_path, &dest_entry_path)
-                        .await?;
+                    self.move_directory_contents(
+                        download_id,
+                        &source_entry_path,
+                        &dest_entry_path,
+                        progress,
+                    )
+                    .await?;
 
-                    // Remove the now-empty source subdirectory
-                    fs::remove_dir(&source_entry_path).await?;
+                    // Remove the now-empty source subdirectory (linked originals stay)
+                    if self.config.download.move_mode != MoveMode::Link {
+                        fs::remove_dir(&source_entry_path).await?;
+                    }
                 }
             }
 
@@ -645,6 +1416,51 @@ impl PostProcessor {
             Ok(destination.to_path_buf())
         })
     }
+
+    /// Apply the configured name sanitization to the last component of `path`
+    fn sanitize_last_component(&self, path: &Path) -> PathBuf {
+        match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => path.with_file_name(sanitize_filename(
+                name,
+                &self.config.processing.path_sanitization,
+            )),
+            None => path.to_path_buf(),
+        }
+    }
+}
+
+/// Build a parity progress callback that emits an event per whole-percent change
+///
+/// par2 reports progress in tenths of a percent, which would flood the event bus,
+/// so updates within the same whole percent are dropped.
+pub(crate) fn parity_progress_reporter(
+    download_id: DownloadId,
+    event_tx: &EventSender,
+    make_event: fn(DownloadId, f32) -> Event,
+) -> impl Fn(f32) + Send + Sync + use<> {
+    let event_tx = event_tx.clone();
+    let last = std::sync::atomic::AtomicU32::new(u32::MAX);
+    move |percent: f32| {
+        let whole = percent as u32;
+        if last.swap(whole, std::sync::atomic::Ordering::Relaxed) != whole {
+            event_tx.send(make_event(download_id, percent)).ok();
+        }
+    }
+}
+
+/// The only entry of `source`, if that's a folder, otherwise `source` itself
+pub(crate) async fn single_folder(source: &Path) -> PathBuf {
+    let Ok(mut entries) = tokio::fs::read_dir(source).await else {
+        return source.to_path_buf();
+    };
+    let (Ok(Some(only)), Ok(None)) = (entries.next_entry().await, entries.next_entry().await)
+    else {
+        return source.to_path_buf();
+    };
+    match only.file_type().await {
+        Ok(file_type) if file_type.is_dir() => only.path(),
+        _ => source.to_path_buf(),
+    }
 }
 
 // unwrap/expect are acceptable in tests for concise failure-on-error assertions
@@ -0,0 +1,67 @@
+//! Permissions and ownership normalization for files placed by the move stage
+
+use crate::config::PermissionsConfig;
+use crate::types::DownloadId;
+use std::path::Path;
+
+/// Apply the configured mode and ownership to a moved file or created directory
+///
+/// Failures are logged rather than returned: a file that was moved successfully but
+/// couldn't be chowned (e.g. when not running as root) shouldn't fail the download.
+#[cfg(unix)]
+pub(crate) async fn apply(
+    download_id: DownloadId,
+    path: &Path,
+    is_dir: bool,
+    permissions: &PermissionsConfig,
+) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let explicit_mode = if is_dir {
+        permissions.dir_mode
+    } else {
+        permissions.file_mode
+    };
+
+    let mode = match (explicit_mode, permissions.umask) {
+        (Some(mode), _) => Some(mode),
+        (None, Some(umask)) => match tokio::fs::metadata(path).await {
+            Ok(metadata) => Some(metadata.permissions().mode() & 0o7777 & !umask),
+            Err(e) => {
+                tracing::warn!(download_id = download_id.0, ?path, error = %e, "Failed to read permissions");
+                None
+            }
+        },
+        (None, None) => None,
+    };
+
+    if let Some(mode) = mode
+        && let Err(e) =
+            tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).await
+    {
+        tracing::warn!(download_id = download_id.0, ?path, mode = format!("{:04o}", mode), error = %e, "Failed to set permissions");
+    }
+
+    if (permissions.uid.is_some() || permissions.gid.is_some())
+        && let Err(e) = std::os::unix::fs::chown(path, permissions.uid, permissions.gid)
+    {
+        tracing::warn!(
+            download_id = download_id.0,
+            ?path,
+            uid = permissions.uid,
+            gid = permissions.gid,
+            error = %e,
+            "Failed to change ownership"
+        );
+    }
+}
+
+/// Permissions normalization is Unix-only; elsewhere this is a no-op
+#[cfg(not(unix))]
+pub(crate) async fn apply(
+    _download_id: DownloadId,
+    _path: &Path,
+    _is_dir: bool,
+    _permissions: &PermissionsConfig,
+) {
+}
@@ -1,10 +1,10 @@
 //! PAR2 repair stage
 
 use crate::error::Result;
+use crate::event_bus::EventSender;
 use crate::parity::ParityHandler;
 use crate::types::{DownloadId, Event};
 use std::path::Path;
-use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
 
 use super::PostProcessError;
@@ -54,13 +54,25 @@ async fn find_par2_files(download_path: &Path) -> Result<Vec<std::path::PathBuf>
     Ok(par2_files)
 }
 
+/// What a repair stage did
+#[derive(Debug, Default)]
+pub(crate) struct RepairOutcome {
+    /// Files PAR2 repaired
+    pub repaired_files: Vec<String>,
+    /// Recovery blocks used to rebuild them
+    pub blocks_used: u32,
+}
+
 /// Execute the repair stage
+///
+/// Each PAR2 recovery set in the download is repaired separately.
+/// Returns what PAR2 repaired, nothing when repair was skipped.
 pub(crate) async fn run_repair_stage(
     download_id: DownloadId,
     download_path: &Path,
-    event_tx: &broadcast::Sender<Event>,
+    event_tx: &EventSender,
     parity_handler: &dyn ParityHandler,
-) -> Result<()> {
+) -> Result<RepairOutcome> {
     debug!(
         download_id = download_id.0,
         ?download_path,
@@ -75,104 +87,128 @@ pub(crate) async fn run_repair_stage(
             download_id = download_id.0,
             "no PAR2 files found, skipping repair"
         );
-        return Ok(());
+        return Ok(RepairOutcome::default());
     }
 
-    // Use the first PAR2 file found (typically the .par2 file, not .vol files)
-    let par2_file = &par2_files[0];
-    debug!(
-        download_id = download_id.0,
-        ?par2_file,
-        "repairing with PAR2 file"
-    );
-
-    // First verify to get block counts for event emission
-    let verify_result = match parity_handler.verify(par2_file).await {
-        Ok(result) => result,
-        Err(crate::Error::NotSupported(ref msg)) => {
-            warn!(
-                download_id = download_id.0,
-                ?par2_file,
-                "PAR2 verification not supported (skipping repair): {}",
-                msg
-            );
-
-            // Emit RepairSkipped event
-            event_tx
-                .send(Event::RepairSkipped {
-                    id: download_id,
-                    reason: format!("PAR2 verification not supported: {}", msg),
-                })
-                .ok();
-
-            return Ok(());
-        }
-        Err(e) => return Err(e),
-    };
+    // Packs can hold several independent sets; each is repaired on its own
+    let sets = super::verify::par2_sets(&par2_files);
+    let mut outcome = RepairOutcome::default();
+    for (i, par2_file) in sets.iter().enumerate() {
+        debug!(
+            download_id = download_id.0,
+            ?par2_file,
+            set = i + 1,
+            sets = sets.len(),
+            "repairing with PAR2 file"
+        );
 
-    // Emit Repairing event
-    event_tx
-        .send(Event::Repairing {
-            id: download_id,
-            blocks_needed: verify_result.damaged_blocks,
-            blocks_available: verify_result.recovery_blocks_available,
-        })
-        .ok();
+        // First verify to get block counts for event emission
+        let verify_result = match parity_handler.verify(par2_file).await {
+            Ok(result) => result,
+            Err(crate::Error::NotSupported(ref msg)) => {
+                warn!(
+                    download_id = download_id.0,
+                    ?par2_file,
+                    "PAR2 verification not supported (skipping repair): {}",
+                    msg
+                );
+
+                // Emit RepairSkipped event
+                event_tx
+                    .send(Event::RepairSkipped {
+                        id: download_id,
+                        reason: format!("PAR2 verification not supported: {}", msg),
+                    })
+                    .ok();
+
+                return Ok(RepairOutcome::default());
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Emit Repairing event
+        event_tx
+            .send(Event::Repairing {
+                id: download_id,
+                blocks_needed: verify_result.damaged_blocks,
+                blocks_available: verify_result.recovery_blocks_available,
+            })
+            .ok();
+
+        // Call parity handler to repair, streaming progress where the handler supports it
+        let reporter = super::parity_progress_reporter(download_id, event_tx, |id, percent| {
+            Event::RepairProgress { id, percent }
+        });
+        let on_progress = |percent: f32| reporter((i as f32 * 100.0 + percent) / sets.len() as f32);
+        let repair_result = match parity_handler
+            .repair_with_progress(par2_file, &on_progress)
+            .await
+        {
+            Ok(result) => result,
+            Err(crate::Error::NotSupported(ref msg)) => {
+                warn!(
+                    download_id = download_id.0,
+                    ?par2_file,
+                    "PAR2 repair not supported: {}",
+                    msg
+                );
+
+                // Emit RepairSkipped event
+                event_tx
+                    .send(Event::RepairSkipped {
+                        id: download_id,
+                        reason: msg.clone(),
+                    })
+                    .ok();
+
+                return Ok(RepairOutcome::default());
+            }
+            Err(e) => return Err(e),
+        };
+
+        info!(
+            download_id = download_id.0,
+            ?par2_file,
+            success = repair_result.success,
+            repaired_files = repair_result.repaired_files.len(),
+            failed_files = repair_result.failed_files.len(),
+            "PAR2 repair complete"
+        );
 
-    // Call parity handler to repair
-    let repair_result = match parity_handler.repair(par2_file).await {
-        Ok(result) => result,
-        Err(crate::Error::NotSupported(ref msg)) => {
-            warn!(
-                download_id = download_id.0,
-                ?par2_file,
-                "PAR2 repair not supported: {}",
-                msg
-            );
-
-            // Emit RepairSkipped event
+        // If repair failed, return error
+        if !repair_result.success {
+            // Emit RepairComplete event
             event_tx
-                .send(Event::RepairSkipped {
+                .send(Event::RepairComplete {
                     id: download_id,
-                    reason: msg.clone(),
+                    success: false,
                 })
                 .ok();
 
-            return Ok(());
+            return Err(PostProcessError::RepairFailed {
+                id: download_id.into(),
+                reason: repair_result.error.unwrap_or_else(|| {
+                    format!(
+                        "repair failed for {} file(s): {}",
+                        repair_result.failed_files.len(),
+                        repair_result.failed_files.join(", ")
+                    )
+                }),
+            }
+            .into());
         }
-        Err(e) => return Err(e),
-    };
 
-    info!(
-        download_id = download_id.0,
-        success = repair_result.success,
-        repaired_files = repair_result.repaired_files.len(),
-        failed_files = repair_result.failed_files.len(),
-        "PAR2 repair complete"
-    );
+        outcome.repaired_files.extend(repair_result.repaired_files);
+        outcome.blocks_used += verify_result.damaged_blocks;
+    }
 
     // Emit RepairComplete event
     event_tx
         .send(Event::RepairComplete {
             id: download_id,
-            success: repair_result.success,
+            success: true,
         })
         .ok();
 
-    // If repair failed, return error
-    if !repair_result.success {
-        return Err(PostProcessError::RepairFailed {
-            id: download_id.into(),
-            reason: repair_result.error.unwrap_or_else(|| {
-                format!(
-                    "repair failed for {} file(s): {}",
-                    repair_result.failed_files.len(),
-                    repair_result.failed_files.join(", ")
-                )
-            }),
-        }
-        .into());
-    }
-
-    Ok(())
+    Ok(outcome)
 }
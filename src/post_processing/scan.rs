@@ -0,0 +1,184 @@
+//! Virus scan stage
+//!
+//! Runs the configured scanner (e.g. ClamAV's `clamscan`) over the files the move stage
+//! is about to place in the destination. A download with infected files fails, and with
+//! [`ScanAction::Quarantine`] its temp folder is moved aside first so nothing is left for
+//! a retry or a later reprocess to pick up by accident.
+
+use crate::config::{Config, ScanAction};
+use crate::error::{PostProcessError, Result};
+use crate::event_bus::EventSender;
+use crate::types::{DownloadId, Event};
+use crate::utils::get_unique_path;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+
+use super::transfer::total_size;
+
+/// Execute the scan stage over `scan_path`, which lies within `download_path`
+///
+/// Does nothing unless `processing.scan.enabled` is set, or when the files exceed
+/// `processing.scan.max_size`.
+pub(crate) async fn run_scan_stage(
+    download_id: DownloadId,
+    download_path: &Path,
+    scan_path: &Path,
+    event_tx: &EventSender,
+    config: &Config,
+) -> Result<()> {
+    let scan = &config.processing.scan;
+    if !scan.enabled {
+        return Ok(());
+    }
+
+    debug!(
+        download_id = download_id.0,
+        ?scan_path,
+        "running scan stage"
+    );
+
+    if let Some(max_size) = scan.max_size {
+        let size = total_size(scan_path).await?;
+        if size > max_size {
+            info!(
+                download_id = download_id.0,
+                size, max_size, "download exceeds scan.max_size, skipping virus scan"
+            );
+            return Ok(());
+        }
+    }
+
+    let scan_failed = |reason: String| {
+        crate::Error::PostProcess(PostProcessError::ScanFailed {
+            id: download_id.0,
+            reason,
+        })
+    };
+
+    let mut command = Command::new(&scan.command);
+    crate::utils::apply_process_priority(command.as_std_mut(), &config.tools.process_priority);
+    let output = command
+        .args(&scan.args)
+        .arg(scan_path)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = match tokio::time::timeout(scan.timeout, output).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            return Err(scan_failed(format!(
+                "failed to run {}: {}",
+                scan.command.display(),
+                e
+            )));
+        }
+        Err(_) => {
+            return Err(scan_failed(format!(
+                "scanner timed out after {:?}",
+                scan.timeout
+            )));
+        }
+    };
+
+    let infected = match output.status.code() {
+        Some(0) => false,
+        Some(code) if scan.infected_exit_codes.contains(&code) => true,
+        code => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(scan_failed(format!(
+                "scanner exited with code {:?}: {}",
+                code,
+                stderr.trim()
+            )));
+        }
+    };
+
+    let detections = if infected {
+        parse_detections(&String::from_utf8_lossy(&output.stdout), scan_path)
+    } else {
+        Vec::new()
+    };
+    event_tx
+        .send(Event::ScanComplete {
+            id: download_id,
+            infected,
+            detections: detections.clone(),
+        })
+        .ok();
+
+    if !infected {
+        debug!(download_id = download_id.0, "virus scan found nothing");
+        return Ok(());
+    }
+
+    warn!(
+        download_id = download_id.0,
+        ?detections,
+        "virus scan found infected files"
+    );
+
+    let mut reason = if detections.is_empty() {
+        format!(
+            "scanner exited with code {}",
+            output.status.code().unwrap_or_default()
+        )
+    } else {
+        detections.join("; ")
+    };
+    if scan.action == ScanAction::Quarantine {
+        match quarantine(download_path, config).await {
+            Ok(quarantined) => {
+                info!(
+                    download_id = download_id.0,
+                    ?quarantined,
+                    "download quarantined"
+                );
+                reason.push_str(&format!(" (quarantined in {})", quarantined.display()));
+            }
+            Err(e) => {
+                warn!(download_id = download_id.0, error = %e, "failed to quarantine download");
+                reason.push_str(&format!(" (quarantine failed: {})", e));
+            }
+        }
+    }
+
+    Err(crate::Error::PostProcess(PostProcessError::Infected {
+        id: download_id.0,
+        reason,
+    }))
+}
+
+/// Each non-empty line of scanner output, with the scanned folder's prefix removed
+pub(super) fn parse_detections(stdout: &str, scan_path: &Path) -> Vec<String> {
+    let prefix = format!("{}{}", scan_path.display(), std::path::MAIN_SEPARATOR);
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.strip_prefix(&prefix).unwrap_or(line).to_string())
+        .collect()
+}
+
+/// Move a download's temp folder into the quarantine folder, returning where it went
+async fn quarantine(download_path: &Path, config: &Config) -> Result<PathBuf> {
+    let quarantine_dir = config
+        .processing
+        .scan
+        .quarantine_dir
+        .clone()
+        .unwrap_or_else(|| config.download.temp_dir.join("quarantine"));
+    tokio::fs::create_dir_all(&quarantine_dir).await?;
+
+    let name = download_path
+        .file_name()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("download"));
+    let target = get_unique_path(
+        &quarantine_dir.join(name),
+        crate::config::FileCollisionAction::Rename,
+    )?;
+    tokio::fs::rename(download_path, &target).await?;
+    Ok(target)
+}
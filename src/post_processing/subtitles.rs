@@ -0,0 +1,198 @@
+//! Subtitle organizer run right before the move stage
+
+use crate::config::SubtitleConfig;
+use crate::types::DownloadId;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use super::junk::{collect_files, has_extension};
+
+/// Recursively collect the subtitle folders under `path`, without looking inside them
+pub(crate) fn find_subtitle_folders<'a>(
+    path: &'a Path,
+    names: &'a [String],
+    folders: &'a mut Vec<PathBuf>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + 'a>> {
+    Box::pin(async move {
+        let Ok(mut entries) = tokio::fs::read_dir(path).await else {
+            return;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if !entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            let entry_path = entry.path();
+            let is_subtitle_folder = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| names.iter().any(|n| n.eq_ignore_ascii_case(name)));
+            if is_subtitle_folder {
+                folders.push(entry_path);
+            } else {
+                find_subtitle_folders(&entry_path, names, folders).await;
+            }
+        }
+    })
+}
+
+/// Move the subtitles in `folders` beside the video they belong to, named after it
+///
+/// A subtitle belongs to the video whose name appears in its path below the subtitle
+/// folder (e.g. `Subs/Show.S01E02/2_eng.srt`), otherwise to the largest video. Emptied
+/// subtitle folders are removed. Returns how many subtitles were moved.
+pub(crate) async fn pair_subtitles(
+    download_id: DownloadId,
+    source: &Path,
+    folders: &[PathBuf],
+    config: &SubtitleConfig,
+) -> usize {
+    let mut files = Vec::new();
+    collect_files(source, &mut files).await;
+    let mut videos: Vec<(PathBuf, u64)> = files
+        .into_iter()
+        .filter(|(path, _)| {
+            has_extension(path, &config.video_extensions)
+                && !folders.iter().any(|folder| path.starts_with(folder))
+        })
+        .collect();
+    // Largest first: that's the main video
+    videos.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    let Some((main_video, _)) = videos.first() else {
+        debug!(
+            download_id = download_id.0,
+            "no video to pair subtitles with"
+        );
+        return 0;
+    };
+
+    let mut moved = 0;
+    for folder in folders {
+        let mut subtitles = Vec::new();
+        collect_files(folder, &mut subtitles).await;
+        subtitles.sort();
+        for (subtitle, _) in subtitles {
+            if !has_extension(&subtitle, &config.extensions) {
+                continue;
+            }
+            let relative = subtitle
+                .strip_prefix(folder)
+                .unwrap_or(&subtitle)
+                .to_string_lossy()
+                .to_lowercase();
+            let video = videos
+                .iter()
+                .map(|(video, _)| video)
+                .find(|video| stem(video).is_some_and(|s| relative.contains(&s.to_lowercase())))
+                .unwrap_or(main_video);
+
+            let Some(target) = subtitle_path(video, &subtitle) else {
+                continue;
+            };
+            match tokio::fs::rename(&subtitle, &target).await {
+                Ok(()) => {
+                    debug!(
+                        download_id = download_id.0,
+                        ?subtitle,
+                        ?target,
+                        "moved subtitle"
+                    );
+                    moved += 1;
+                }
+                Err(e) => {
+                    warn!(download_id = download_id.0, ?subtitle, error = %e, "failed to move subtitle");
+                }
+            }
+        }
+        remove_empty_dirs(folder).await;
+    }
+
+    if moved > 0 {
+        info!(
+            download_id = download_id.0,
+            moved, "subtitles paired with video"
+        );
+    }
+    moved
+}
+
+/// Where `subtitle` goes beside `video`: `{video}.{language}.{ext}`, numbered if taken
+fn subtitle_path(video: &Path, subtitle: &Path) -> Option<PathBuf> {
+    let video_stem = stem(video)?;
+    let extension = subtitle.extension()?.to_str()?;
+    let base = match subtitle_language(video_stem, stem(subtitle)?) {
+        Some(language) => format!("{}.{}", video_stem, language),
+        None => video_stem.to_string(),
+    };
+    let parent = video.parent()?;
+    let mut target = parent.join(format!("{}.{}", base, extension));
+    let mut n = 2;
+    while target.exists() {
+        target = parent.join(format!("{}.{}.{}", base, n, extension));
+        n += 1;
+    }
+    Some(target)
+}
+
+/// The language tags of a subtitle named `subtitle_stem`
+///
+/// What follows the video's name (`Movie.eng.forced` gives `eng.forced`), otherwise the
+/// last word of the name if it isn't a number (`2_eng` gives `eng`).
+pub(crate) fn subtitle_language(video_stem: &str, subtitle_stem: &str) -> Option<String> {
+    if let Some(rest) = subtitle_stem
+        .get(..video_stem.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(video_stem))
+        .map(|_| &subtitle_stem[video_stem.len()..])
+    {
+        let rest = rest.trim_matches(|c: char| matches!(c, '.' | '_' | '-' | ' '));
+        return (!rest.is_empty()).then(|| rest.to_lowercase());
+    }
+    subtitle_stem
+        .rsplit(['.', '_', '-', ' '])
+        .next()
+        .filter(|word| !word.is_empty() && word.chars().all(char::is_alphabetic))
+        .map(str::to_lowercase)
+}
+
+/// Remove the archives, including old-style RAR volumes (`.r00`), directly in `folder`
+pub(crate) async fn remove_archives(folder: &Path, archive_extensions: &[String]) {
+    let Ok(mut entries) = tokio::fs::read_dir(folder).await else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let old_volume = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ext.len() == 3
+                    && ext.starts_with(['r', 'R'])
+                    && ext[1..].chars().all(|c| c.is_ascii_digit())
+            });
+        if (old_volume || has_extension(&path, archive_extensions))
+            && let Err(e) = tokio::fs::remove_file(&path).await
+        {
+            warn!(?path, error = %e, "failed to delete extracted subtitle archive");
+        }
+    }
+}
+
+fn stem(path: &Path) -> Option<&str> {
+    path.file_stem().and_then(|s| s.to_str())
+}
+
+/// Remove `path` and the folders below it if they hold no files
+fn remove_empty_dirs<'a>(
+    path: &'a Path,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + 'a>> {
+    Box::pin(async move {
+        if let Ok(mut entries) = tokio::fs::read_dir(path).await {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+                    remove_empty_dirs(&entry.path()).await;
+                }
+            }
+        }
+        // Fails while anything is left in it
+        let _ = tokio::fs::remove_dir(path).await;
+    })
+}
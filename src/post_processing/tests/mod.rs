@@ -1,6 +1,5 @@
 use super::*;
 use crate::parity::NoOpParityHandler;
-use tokio::sync::broadcast;
 
 /// Helper to create a no-op parity handler for tests
 fn test_parity_handler() -> Arc<dyn ParityHandler> {
@@ -15,7 +14,7 @@ async fn test_database() -> Arc<crate::db::Database> {
 
 #[tokio::test]
 async fn test_post_processing_none() {
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(tx, config, test_parity_handler(), test_database().await);
 
@@ -28,6 +27,7 @@ async fn test_post_processing_none() {
             download_path.clone(),
             PostProcess::None,
             destination,
+            &RunOptions::from_config(&processor.config),
         )
         .await;
 
@@ -39,7 +39,7 @@ async fn test_post_processing_none() {
 async fn test_post_processing_verify() {
     use tempfile::TempDir;
 
-    let (tx, mut rx) = broadcast::channel(100);
+    let (tx, mut rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(tx, config, test_parity_handler(), test_database().await);
 
@@ -55,6 +55,7 @@ async fn test_post_processing_verify() {
             download_path.clone(),
             PostProcess::Verify,
             destination,
+            &RunOptions::from_config(&processor.config),
         )
         .await;
 
@@ -79,7 +80,7 @@ async fn test_post_processing_unpack_and_cleanup() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, mut rx) = broadcast::channel(100);
+    let (tx, mut rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(tx, config, test_parity_handler(), test_database().await);
 
@@ -100,6 +101,7 @@ async fn test_post_processing_unpack_and_cleanup() {
             download_path.clone(),
             PostProcess::UnpackAndCleanup,
             destination.clone(),
+            &RunOptions::from_config(&processor.config),
         )
         .await;
 
@@ -136,7 +138,7 @@ async fn test_stage_executor_ordering() {
     use tokio::fs;
 
     // Verify that stages execute in the correct order
-    let (tx, mut rx) = broadcast::channel(100);
+    let (tx, mut rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(tx, config, test_parity_handler(), test_database().await);
 
@@ -156,6 +158,7 @@ async fn test_stage_executor_ordering() {
             download_path,
             PostProcess::UnpackAndCleanup,
             destination,
+            &RunOptions::from_config(&processor.config),
         )
         .await
         .unwrap();
@@ -191,7 +194,7 @@ async fn test_move_files_single_file_no_collision() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(tx, config, test_parity_handler(), test_database().await);
 
@@ -213,7 +216,7 @@ async fn test_move_files_collision_rename() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let mut config = Config::default();
     config.download.file_collision = crate::config::FileCollisionAction::Rename;
     let processor = PostProcessor::new(
@@ -247,7 +250,7 @@ async fn test_move_files_collision_overwrite() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let mut config = Config::default();
     config.download.file_collision = crate::config::FileCollisionAction::Overwrite;
     let processor = PostProcessor::new(
@@ -281,7 +284,7 @@ async fn test_move_files_collision_skip() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let mut config = Config::default();
     config.download.file_collision = crate::config::FileCollisionAction::Skip;
     let processor = PostProcessor::new(
@@ -314,7 +317,7 @@ async fn test_move_directory_contents() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(tx, config, test_parity_handler(), test_database().await);
 
@@ -359,7 +362,7 @@ async fn test_move_directory_with_collision_rename() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let mut config = Config::default();
     config.download.file_collision = crate::config::FileCollisionAction::Rename;
     let processor = PostProcessor::new(
@@ -409,7 +412,7 @@ async fn test_cleanup_removes_target_extensions() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(
         tx,
@@ -474,7 +477,7 @@ async fn test_cleanup_removes_archive_files() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(
         tx,
@@ -526,7 +529,7 @@ async fn test_cleanup_removes_sample_folders() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(
         tx,
@@ -582,7 +585,7 @@ async fn test_cleanup_case_insensitive() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(
         tx,
@@ -626,7 +629,7 @@ async fn test_cleanup_recursive() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(
         tx,
@@ -678,7 +681,7 @@ async fn test_cleanup_disabled() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let mut config = Config::default();
     config.processing.cleanup.enabled = false;
     let config = Arc::new(config);
@@ -720,7 +723,7 @@ async fn test_cleanup_delete_samples_disabled() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let mut config = Config::default();
     config.processing.cleanup.delete_samples = false;
     let config = Arc::new(config);
@@ -766,7 +769,7 @@ async fn test_cleanup_delete_samples_disabled() {
 
 #[tokio::test]
 async fn test_cleanup_nonexistent_path() {
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     let processor = PostProcessor::new(
         tx,
@@ -793,7 +796,7 @@ async fn test_verify_stage_handles_not_supported() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, mut rx) = broadcast::channel(100);
+    let (tx, mut rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     // NoOpParityHandler returns Error::NotSupported for verify
     let processor = PostProcessor::new(
@@ -843,7 +846,7 @@ async fn test_move_nested_subdirectory_with_collision_rename() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, _rx) = broadcast::channel(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
     let mut config = Config::default();
     config.download.file_collision = crate::config::FileCollisionAction::Rename;
     let processor = PostProcessor::new(
@@ -907,7 +910,7 @@ async fn test_repair_stage_handles_not_supported() {
     use tempfile::TempDir;
     use tokio::fs;
 
-    let (tx, mut rx) = broadcast::channel(100);
+    let (tx, mut rx) = crate::event_bus::channel(100);
     let config = Arc::new(Config::default());
     // NoOpParityHandler returns Error::NotSupported for both verify and repair
     let processor = PostProcessor::new(
@@ -933,10 +936,1382 @@ async fn test_repair_stage_handles_not_supported() {
         &*processor.parity_handler,
     )
     .await;
-    assert!(result.is_ok());
+    assert!(
+        result.unwrap().repaired_files.is_empty(),
+        "a skipped repair repairs nothing"
+    );
 
     // Verify now returns NotSupported, so the repair stage hits the verify catch
     // and emits only RepairSkipped (never reaches Repairing)
     let event1 = rx.recv().await.unwrap();
     assert!(matches!(event1, Event::RepairSkipped { id, .. } if id == DownloadId(1)));
 }
+
+/// Parity handler that reports a fixed sequence of progress values before succeeding
+struct ProgressParityHandler;
+
+#[async_trait::async_trait]
+impl ParityHandler for ProgressParityHandler {
+    async fn verify(&self, par2_file: &Path) -> Result<crate::parity::VerifyResult> {
+        self.verify_with_progress(par2_file, &|_| {}).await
+    }
+
+    async fn repair(&self, _par2_file: &Path) -> Result<crate::parity::RepairResult> {
+        Ok(crate::parity::RepairResult {
+            success: true,
+            repaired_files: vec![],
+            failed_files: vec![],
+            error: None,
+        })
+    }
+
+    async fn verify_with_progress(
+        &self,
+        _par2_file: &Path,
+        on_progress: crate::parity::ProgressFn<'_>,
+    ) -> Result<crate::parity::VerifyResult> {
+        for percent in [10.0, 10.4, 10.9, 55.0, 100.0] {
+            on_progress(percent);
+        }
+        Ok(crate::parity::VerifyResult {
+            is_complete: true,
+            damaged_blocks: 0,
+            recovery_blocks_available: 0,
+            repairable: false,
+            damaged_files: vec![],
+            missing_files: vec![],
+        })
+    }
+
+    fn capabilities(&self) -> crate::parity::ParityCapabilities {
+        crate::parity::ParityCapabilities {
+            can_verify: true,
+            can_repair: true,
+            ..Default::default()
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "progress-test"
+    }
+}
+
+#[tokio::test]
+async fn test_verify_stage_emits_throttled_progress() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, mut rx) = crate::event_bus::channel(100);
+    let temp_dir = TempDir::new().unwrap();
+    let download_path = temp_dir.path().join("download");
+    fs::create_dir_all(&download_path).await.unwrap();
+    fs::write(download_path.join("test.par2"), b"fake par2 data")
+        .await
+        .unwrap();
+
+    let damaged = crate::post_processing::verify::run_verify_stage(
+        DownloadId(1),
+        &download_path,
+        &tx,
+        &ProgressParityHandler,
+    )
+    .await
+    .unwrap();
+    assert!(!damaged);
+
+    let mut progress = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let Event::VerifyProgress { id, percent } = event {
+            assert_eq!(id, DownloadId(1));
+            progress.push(percent);
+        }
+    }
+    // 10.4 and 10.9 fall in the same whole percent as 10.0 and are dropped
+    assert_eq!(progress, vec![10.0, 55.0, 100.0]);
+}
+
+#[tokio::test]
+async fn test_move_files_copy_mode_verifies_and_removes_source() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, mut rx) = crate::event_bus::channel(1000);
+    let mut config = Config::default();
+    config.download.move_mode = crate::config::MoveMode::Copy;
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    let dest_dir = temp_dir.path().join("dest");
+    fs::create_dir_all(source_dir.join("sub")).await.unwrap();
+    let big = vec![7u8; 3 * 1024 * 1024];
+    fs::write(source_dir.join("movie.mkv"), &big).await.unwrap();
+    fs::write(source_dir.join("sub/movie.srt"), b"subtitle")
+        .await
+        .unwrap();
+
+    processor
+        .move_files(DownloadId(1), &source_dir, &dest_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(fs::read(dest_dir.join("movie.mkv")).await.unwrap(), big);
+    assert_eq!(
+        fs::read(dest_dir.join("sub/movie.srt")).await.unwrap(),
+        b"subtitle"
+    );
+    assert!(!source_dir.join("movie.mkv").exists());
+    assert!(!source_dir.join("sub").exists());
+    assert!(
+        !dest_dir.join(".movie.mkv.partial").exists(),
+        "partial files should be renamed into place"
+    );
+
+    let mut percents = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let Event::MoveProgress { id, percent } = event {
+            assert_eq!(id, DownloadId(1));
+            percents.push(percent);
+        }
+    }
+    assert!(!percents.is_empty(), "copying should emit MoveProgress");
+    assert!(percents.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(percents.last().copied(), Some(100.0));
+}
+
+#[tokio::test]
+async fn test_move_files_copy_mode_emits_move_verified() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, mut rx) = crate::event_bus::channel(1000);
+    let mut config = Config::default();
+    config.download.move_mode = crate::config::MoveMode::Copy;
+    config.download.move_checksum = crate::config::MoveChecksum::Blake3;
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    let dest_dir = temp_dir.path().join("dest");
+    fs::create_dir_all(&source_dir).await.unwrap();
+    fs::write(source_dir.join("a.mkv"), b"first").await.unwrap();
+    fs::write(source_dir.join("b.mkv"), b"second")
+        .await
+        .unwrap();
+
+    processor
+        .move_files(DownloadId(1), &source_dir, &dest_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(fs::read(dest_dir.join("b.mkv")).await.unwrap(), b"second");
+    let verified: Vec<_> = std::iter::from_fn(|| rx.try_recv().ok())
+        .filter_map(|event| match event {
+            Event::MoveVerified {
+                id,
+                files,
+                checksum,
+            } => Some((id, files, checksum)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        verified,
+        vec![(DownloadId(1), 2, crate::config::MoveChecksum::Blake3)]
+    );
+}
+
+#[tokio::test]
+async fn test_move_files_rename_mode_emits_no_progress() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, mut rx) = crate::event_bus::channel(100);
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(Config::default()),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source.txt");
+    let dest = temp_dir.path().join("dest.txt");
+    fs::write(&source, b"content").await.unwrap();
+
+    processor
+        .move_files(DownloadId(1), &source, &dest)
+        .await
+        .unwrap();
+
+    assert!(dest.exists() && !source.exists());
+    assert!(
+        rx.try_recv().is_err(),
+        "same-filesystem renames should not emit progress or MoveVerified"
+    );
+}
+
+#[tokio::test]
+async fn test_move_files_link_mode_keeps_originals() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let mut config = Config::default();
+    config.download.move_mode = crate::config::MoveMode::Link;
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    let dest_dir = temp_dir.path().join("dest");
+    fs::create_dir_all(source_dir.join("sub")).await.unwrap();
+    fs::write(source_dir.join("movie.mkv"), b"video")
+        .await
+        .unwrap();
+    fs::write(source_dir.join("sub/movie.srt"), b"subtitle")
+        .await
+        .unwrap();
+
+    processor
+        .move_files(DownloadId(1), &source_dir, &dest_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fs::read(dest_dir.join("movie.mkv")).await.unwrap(),
+        b"video"
+    );
+    assert_eq!(
+        fs::read(dest_dir.join("sub/movie.srt")).await.unwrap(),
+        b"subtitle"
+    );
+    assert_eq!(
+        fs::read(source_dir.join("movie.mkv")).await.unwrap(),
+        b"video"
+    );
+    assert!(source_dir.join("sub/movie.srt").exists());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let linked = fs::metadata(dest_dir.join("movie.mkv")).await.unwrap();
+        assert_eq!(linked.nlink(), 2, "same-volume files should be hard-linked");
+    }
+}
+
+#[tokio::test]
+async fn test_remove_expired_linked_originals() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let temp_dir = TempDir::new().unwrap();
+    let linked = temp_dir.path().join("linked");
+    let unlinked = temp_dir.path().join("unlinked");
+    fs::create_dir_all(&linked).await.unwrap();
+    fs::create_dir_all(&unlinked).await.unwrap();
+    fs::write(linked.join("movie.mkv"), b"video").await.unwrap();
+    fs::write(unlinked.join("movie.mkv"), b"video")
+        .await
+        .unwrap();
+
+    let retention = std::time::Duration::from_secs(3600);
+    transfer::retain_linked_originals(DownloadId(1), &linked, retention).await;
+    remove_expired_linked_originals(temp_dir.path(), retention).await;
+    assert!(linked.exists(), "retention hasn't passed yet");
+
+    remove_expired_linked_originals(temp_dir.path(), std::time::Duration::ZERO).await;
+    assert!(!linked.exists());
+    assert!(
+        unlinked.exists(),
+        "folders that weren't linked are left alone"
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_move_files_applies_configured_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let mut config = Config::default();
+    config.processing.permissions.file_mode = Some(0o644);
+    config.processing.permissions.dir_mode = Some(0o750);
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    let dest_dir = temp_dir.path().join("dest");
+    fs::create_dir_all(source_dir.join("extras")).await.unwrap();
+    fs::write(source_dir.join("movie.mkv"), b"video")
+        .await
+        .unwrap();
+    fs::write(source_dir.join("extras/bonus.mkv"), b"bonus")
+        .await
+        .unwrap();
+    // Extracted files are often owner-only
+    for file in ["movie.mkv", "extras/bonus.mkv"] {
+        fs::set_permissions(
+            source_dir.join(file),
+            std::fs::Permissions::from_mode(0o600),
+        )
+        .await
+        .unwrap();
+    }
+
+    processor
+        .move_files(DownloadId(1), &source_dir, &dest_dir)
+        .await
+        .unwrap();
+
+    let mode = |p: PathBuf| std::fs::metadata(p).unwrap().permissions().mode() & 0o7777;
+    assert_eq!(mode(dest_dir.join("movie.mkv")), 0o644);
+    assert_eq!(mode(dest_dir.join("extras/bonus.mkv")), 0o644);
+    assert_eq!(mode(dest_dir.join("extras")), 0o750);
+    assert_eq!(mode(dest_dir.clone()), 0o750);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_move_files_applies_umask_without_explicit_mode() {
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let mut config = Config::default();
+    config.processing.permissions.umask = Some(0o027);
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source.txt");
+    let dest = temp_dir.path().join("dest.txt");
+    fs::write(&source, b"content").await.unwrap();
+    fs::set_permissions(&source, std::fs::Permissions::from_mode(0o666))
+        .await
+        .unwrap();
+
+    processor
+        .move_files(DownloadId(1), &source, &dest)
+        .await
+        .unwrap();
+
+    let mode = std::fs::metadata(&dest).unwrap().permissions().mode() & 0o7777;
+    assert_eq!(mode, 0o640);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_move_files_sanitizes_names_for_windows() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let mut config = Config::default();
+    config.processing.path_sanitization.windows_compatible = true;
+    config
+        .processing
+        .path_sanitization
+        .transliterate_illegal_chars = true;
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    let dest_dir = temp_dir.path().join("Show: Season 1.");
+    fs::create_dir_all(source_dir.join("extras. "))
+        .await
+        .unwrap();
+    fs::write(source_dir.join("con.nfo"), b"info")
+        .await
+        .unwrap();
+    fs::write(source_dir.join("extras. /what?.mkv"), b"video")
+        .await
+        .unwrap();
+
+    let final_path = processor
+        .move_files(DownloadId(1), &source_dir, &dest_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(final_path, temp_dir.path().join("Show： Season 1"));
+    assert!(final_path.join("con_.nfo").exists());
+    assert!(final_path.join("extras/what？.mkv").exists());
+}
+
+/// Config with the scan stage running `sh -c <script>`, which gets the scanned path as `$1`
+#[cfg(unix)]
+fn scan_config(script: &str) -> Config {
+    let mut config = Config::default();
+    config.processing.scan.enabled = true;
+    config.processing.scan.command = PathBuf::from("sh");
+    config.processing.scan.args = vec!["-c".to_string(), script.to_string(), "scanner".to_string()];
+    config
+}
+
+#[cfg(unix)]
+const INFECTED_SCANNER: &str = r#"echo "$1/eicar.com: Eicar-Signature FOUND"; exit 1"#;
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_scan_stage_passes_clean_download() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, mut rx) = crate::event_bus::channel(100);
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(scan_config("exit 0")),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let download_path = temp_dir.path().join("download");
+    let destination = temp_dir.path().join("destination");
+    fs::create_dir_all(&download_path).await.unwrap();
+    fs::write(download_path.join("movie.mkv"), b"video")
+        .await
+        .unwrap();
+
+    processor
+        .start_post_processing(
+            DownloadId(1),
+            download_path,
+            PostProcess::UnpackAndCleanup,
+            destination.clone(),
+            &RunOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap();
+
+    assert!(destination.join("movie.mkv").exists());
+    let mut scanned = None;
+    while let Ok(event) = rx.try_recv() {
+        if let Event::ScanComplete {
+            infected,
+            detections,
+            ..
+        } = event
+        {
+            scanned = Some((infected, detections));
+        }
+    }
+    assert_eq!(scanned, Some((false, vec![])));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_scan_stage_fails_infected_download() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, mut rx) = crate::event_bus::channel(100);
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(scan_config(INFECTED_SCANNER)),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let download_path = temp_dir.path().join("download");
+    let destination = temp_dir.path().join("destination");
+    fs::create_dir_all(&download_path).await.unwrap();
+    fs::write(download_path.join("eicar.com"), b"virus")
+        .await
+        .unwrap();
+
+    let err = processor
+        .start_post_processing(
+            DownloadId(1),
+            download_path.clone(),
+            PostProcess::UnpackAndCleanup,
+            destination.clone(),
+            &RunOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        crate::Error::PostProcess(PostProcessError::Infected { id: 1, ref reason })
+            if reason == "eicar.com: Eicar-Signature FOUND"
+    ));
+    assert!(!destination.exists(), "infected files are never moved");
+    assert!(download_path.join("eicar.com").exists());
+
+    let mut scanned = None;
+    while let Ok(event) = rx.try_recv() {
+        if let Event::ScanComplete {
+            infected,
+            detections,
+            ..
+        } = event
+        {
+            scanned = Some((infected, detections));
+        }
+    }
+    assert_eq!(
+        scanned,
+        Some((true, vec!["eicar.com: Eicar-Signature FOUND".to_string()]))
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_scan_stage_quarantines_infected_download() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let temp_dir = TempDir::new().unwrap();
+    let quarantine_dir = temp_dir.path().join("quarantine");
+    let mut config = scan_config(INFECTED_SCANNER);
+    config.processing.scan.action = crate::config::ScanAction::Quarantine;
+    config.processing.scan.quarantine_dir = Some(quarantine_dir.clone());
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let download_path = temp_dir.path().join("download");
+    fs::create_dir_all(&download_path).await.unwrap();
+    fs::write(download_path.join("eicar.com"), b"virus")
+        .await
+        .unwrap();
+
+    let err = processor
+        .run_move_and_cleanup(
+            DownloadId(1),
+            download_path.clone(),
+            temp_dir.path().join("destination"),
+            &RunOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        crate::Error::PostProcess(PostProcessError::Infected { ref reason, .. })
+            if reason.contains("quarantined in")
+    ));
+    assert!(!download_path.exists());
+    assert!(quarantine_dir.join("download/eicar.com").exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_scan_stage_fails_when_scanner_times_out() {
+    use tempfile::TempDir;
+
+    let mut config = scan_config("sleep 5");
+    config.processing.scan.timeout = std::time::Duration::from_millis(100);
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let download_path = temp_dir.path().join("download");
+    tokio::fs::create_dir_all(&download_path).await.unwrap();
+
+    let err = processor
+        .run_from_stage(
+            DownloadId(1),
+            download_path,
+            Stage::Scan,
+            temp_dir.path().join("destination"),
+            &RunOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        crate::Error::PostProcess(PostProcessError::ScanFailed { ref reason, .. })
+            if reason.contains("timed out")
+    ));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_scan_stage_skips_downloads_over_max_size() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let mut config = scan_config(INFECTED_SCANNER);
+    config.processing.scan.max_size = Some(4);
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let download_path = temp_dir.path().join("download");
+    let destination = temp_dir.path().join("destination");
+    fs::create_dir_all(&download_path).await.unwrap();
+    fs::write(download_path.join("movie.mkv"), b"larger than four bytes")
+        .await
+        .unwrap();
+
+    processor
+        .run_move_and_cleanup(
+            DownloadId(1),
+            download_path,
+            destination.clone(),
+            &RunOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap();
+
+    assert!(destination.join("movie.mkv").exists());
+}
+
+#[test]
+fn test_scan_detections_are_relative_to_the_scanned_folder() {
+    let scan_path = Path::new("/tmp/job/extracted");
+    let stdout = format!(
+        "{0}{1}a.exe: Eicar-Signature FOUND\n\n  {0}{1}b.exe: Win.Trojan FOUND\nother: FOUND\n",
+        scan_path.display(),
+        std::path::MAIN_SEPARATOR
+    );
+
+    assert_eq!(
+        scan::parse_detections(&stdout, scan_path),
+        vec![
+            "a.exe: Eicar-Signature FOUND",
+            "b.exe: Win.Trojan FOUND",
+            "other: FOUND"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_move_stage_flattens_single_folder() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let mut config = Config::default();
+    config.processing.extraction.flatten_single_folder = true;
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    let dest_dir = temp_dir.path().join("dest");
+    fs::create_dir_all(source_dir.join("Show.S01E01/Subs"))
+        .await
+        .unwrap();
+    fs::write(source_dir.join("Show.S01E01/episode.mkv"), b"video")
+        .await
+        .unwrap();
+    fs::write(source_dir.join("Show.S01E01/Subs/episode.srt"), b"subtitle")
+        .await
+        .unwrap();
+
+    processor
+        .run_move_stage(
+            DownloadId(1),
+            temp_dir.path(),
+            &source_dir,
+            &dest_dir,
+            &MoveOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap();
+
+    assert!(dest_dir.join("episode.mkv").exists());
+    // Only the top-level folder is flattened
+    assert!(dest_dir.join("Subs/episode.srt").exists());
+    assert!(!dest_dir.join("Show.S01E01").exists());
+}
+
+#[tokio::test]
+async fn test_move_stage_flatten_override_keeps_structure() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let mut config = Config::default();
+    config.processing.extraction.flatten_single_folder = true;
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    let dest_dir = temp_dir.path().join("dest");
+    fs::create_dir_all(source_dir.join("Movie.2024"))
+        .await
+        .unwrap();
+    fs::write(source_dir.join("Movie.2024/movie.mkv"), b"video")
+        .await
+        .unwrap();
+
+    let options = MoveOptions {
+        flatten_single_folder: false,
+        junk_filter: JunkFilterConfig::default(),
+    };
+    processor
+        .run_move_stage(
+            DownloadId(1),
+            temp_dir.path(),
+            &source_dir,
+            &dest_dir,
+            &options,
+        )
+        .await
+        .unwrap();
+
+    assert!(dest_dir.join("Movie.2024/movie.mkv").exists());
+}
+
+#[tokio::test]
+async fn test_single_folder_needs_a_lone_directory() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path();
+    fs::create_dir(source.join("Release")).await.unwrap();
+    assert_eq!(single_folder(source).await, source.join("Release"));
+
+    fs::write(source.join("release.nfo"), b"info")
+        .await
+        .unwrap();
+    assert_eq!(single_folder(source).await, source);
+
+    let file_only = source.join("Release");
+    fs::write(file_only.join("movie.mkv"), b"video")
+        .await
+        .unwrap();
+    assert_eq!(single_folder(&file_only).await, file_only);
+}
+
+#[tokio::test]
+async fn test_move_stage_removes_junk() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let mut config = Config::default();
+    config.processing.junk_filter.enabled = true;
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    let dest_dir = temp_dir.path().join("dest");
+    fs::create_dir_all(source_dir.join("extras")).await.unwrap();
+    fs::write(source_dir.join("movie.mkv"), b"video")
+        .await
+        .unwrap();
+    fs::write(source_dir.join("movie.nfo"), b"info")
+        .await
+        .unwrap();
+    fs::write(source_dir.join("Visit Us.URL"), b"[InternetShortcut]")
+        .await
+        .unwrap();
+    fs::write(source_dir.join("extras/codec.exe"), b"MZ")
+        .await
+        .unwrap();
+    fs::write(source_dir.join("empty.srt"), b"").await.unwrap();
+
+    processor
+        .run_move_stage(
+            DownloadId(1),
+            temp_dir.path(),
+            &source_dir,
+            &dest_dir,
+            &MoveOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap();
+
+    assert!(dest_dir.join("movie.mkv").exists());
+    assert!(dest_dir.join("movie.nfo").exists());
+    assert!(!dest_dir.join("Visit Us.URL").exists());
+    assert!(!dest_dir.join("extras/codec.exe").exists());
+    assert!(!dest_dir.join("empty.srt").exists());
+}
+
+#[tokio::test]
+async fn test_junk_filter_keeps_executables_outside_video_releases() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("setup.exe"), b"MZ")
+        .await
+        .unwrap();
+    fs::write(temp_dir.path().join("readme.txt"), b"ad")
+        .await
+        .unwrap();
+    let filter = JunkFilterConfig {
+        enabled: true,
+        ..Default::default()
+    };
+
+    let removed = junk::remove_junk(DownloadId(1), temp_dir.path(), &filter).await;
+
+    assert_eq!(removed, 1);
+    assert!(temp_dir.path().join("setup.exe").exists());
+    assert!(!temp_dir.path().join("readme.txt").exists());
+}
+
+#[tokio::test]
+async fn test_move_stage_pairs_subtitles_with_video() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let mut config = Config::default();
+    config.processing.subtitles.enabled = true;
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(config),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    let dest_dir = temp_dir.path().join("dest");
+    fs::create_dir_all(source_dir.join("Subs")).await.unwrap();
+    fs::write(source_dir.join("Movie.2024.mkv"), vec![0u8; 1024])
+        .await
+        .unwrap();
+    fs::write(source_dir.join("Movie.2024.sample.mkv"), b"sample")
+        .await
+        .unwrap();
+    fs::write(source_dir.join("Subs/2_eng.srt"), b"english")
+        .await
+        .unwrap();
+    fs::write(source_dir.join("Subs/3_eng.srt"), b"english sdh")
+        .await
+        .unwrap();
+    // A subtitle-only archive inside the subtitle folder
+    {
+        let file = std::fs::File::create(source_dir.join("Subs/subs.zip")).unwrap();
+        let mut writer = ::zip::ZipWriter::new(file);
+        writer
+            .start_file("4_ger.srt", ::zip::write::FileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut writer, b"german").unwrap();
+        writer.finish().unwrap();
+    }
+
+    processor
+        .run_move_stage(
+            DownloadId(1),
+            temp_dir.path(),
+            &source_dir,
+            &dest_dir,
+            &MoveOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fs::read(dest_dir.join("Movie.2024.eng.srt")).await.unwrap(),
+        b"english"
+    );
+    assert_eq!(
+        fs::read(dest_dir.join("Movie.2024.eng.2.srt"))
+            .await
+            .unwrap(),
+        b"english sdh"
+    );
+    assert_eq!(
+        fs::read(dest_dir.join("Movie.2024.ger.srt")).await.unwrap(),
+        b"german"
+    );
+    assert!(!dest_dir.join("Subs").exists());
+}
+
+#[test]
+fn test_subtitle_language() {
+    use subtitles::subtitle_language;
+
+    assert_eq!(subtitle_language("Movie", "2_eng").as_deref(), Some("eng"));
+    assert_eq!(
+        subtitle_language("Movie", "movie.eng.forced").as_deref(),
+        Some("eng.forced")
+    );
+    assert_eq!(subtitle_language("Movie", "Movie"), None);
+    assert_eq!(subtitle_language("Movie", "2"), None);
+}
+
+/// Parity handler that records which PAR2 files it was given, damaged for `damaged`
+#[derive(Default)]
+struct RecordingParityHandler {
+    damaged: Option<&'static str>,
+    verified: std::sync::Mutex<Vec<String>>,
+    repaired: std::sync::Mutex<Vec<String>>,
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap().to_string_lossy().into_owned()
+}
+
+#[async_trait::async_trait]
+impl ParityHandler for RecordingParityHandler {
+    async fn verify(&self, par2_file: &Path) -> Result<crate::parity::VerifyResult> {
+        let name = file_name(par2_file);
+        let damaged = self.damaged == Some(name.as_str());
+        self.verified.lock().unwrap().push(name);
+        Ok(crate::parity::VerifyResult {
+            is_complete: !damaged,
+            damaged_blocks: if damaged { 3 } else { 0 },
+            recovery_blocks_available: 10,
+            repairable: true,
+            damaged_files: vec![],
+            missing_files: vec![],
+        })
+    }
+
+    async fn repair(&self, par2_file: &Path) -> Result<crate::parity::RepairResult> {
+        let name = file_name(par2_file);
+        self.repaired.lock().unwrap().push(name.clone());
+        Ok(crate::parity::RepairResult {
+            success: true,
+            repaired_files: if self.damaged == Some(name.as_str()) {
+                vec![format!("{}.mkv", name.trim_end_matches(".par2"))]
+            } else {
+                vec![]
+            },
+            failed_files: vec![],
+            error: None,
+        })
+    }
+
+    fn capabilities(&self) -> crate::parity::ParityCapabilities {
+        crate::parity::ParityCapabilities {
+            can_verify: true,
+            can_repair: true,
+            ..Default::default()
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "recording-test"
+    }
+}
+
+/// A PAR2 file whose packet header puts it in recovery set `set_id`
+fn par2_packet(set_id: u8) -> Vec<u8> {
+    let mut packet = Vec::new();
+    packet.extend_from_slice(b"PAR2\0PKT");
+    packet.extend_from_slice(&64u64.to_le_bytes());
+    packet.extend_from_slice(&[0u8; 16]);
+    packet.extend_from_slice(&[set_id; 16]);
+    packet.extend_from_slice(b"PAR 2.0\0Main\0\0\0\0");
+    packet
+}
+
+#[tokio::test]
+async fn test_verify_and_repair_run_per_par2_set() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, mut rx) = crate::event_bus::channel(100);
+    let temp_dir = TempDir::new().unwrap();
+    let download_path = temp_dir.path();
+    // Two episodes with their own sets; the obfuscated volume goes with its set by ID
+    fs::write(download_path.join("Show.S01E01.par2"), par2_packet(1))
+        .await
+        .unwrap();
+    fs::write(download_path.join("a8f3c2.vol00+01.par2"), par2_packet(1))
+        .await
+        .unwrap();
+    fs::write(download_path.join("Show.S01E02.par2"), par2_packet(2))
+        .await
+        .unwrap();
+    fs::write(
+        download_path.join("Show.S01E02.vol01+02.par2"),
+        par2_packet(2),
+    )
+    .await
+    .unwrap();
+    let handler = RecordingParityHandler {
+        damaged: Some("Show.S01E02.par2"),
+        ..Default::default()
+    };
+
+    let damaged = crate::post_processing::verify::run_verify_stage(
+        DownloadId(1),
+        download_path,
+        &tx,
+        &handler,
+    )
+    .await
+    .unwrap();
+    assert!(damaged);
+    assert_eq!(
+        *handler.verified.lock().unwrap(),
+        vec!["Show.S01E01.par2", "Show.S01E02.par2"]
+    );
+    let mut complete = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let Event::VerifyComplete { damaged, .. } = event {
+            complete.push(damaged);
+        }
+    }
+    assert_eq!(
+        complete,
+        vec![true],
+        "one VerifyComplete for the whole download"
+    );
+
+    let outcome = crate::post_processing::repair::run_repair_stage(
+        DownloadId(1),
+        download_path,
+        &tx,
+        &handler,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        *handler.repaired.lock().unwrap(),
+        vec!["Show.S01E01.par2", "Show.S01E02.par2"]
+    );
+    assert_eq!(outcome.repaired_files, vec!["Show.S01E02.mkv"]);
+    assert_eq!(outcome.blocks_used, 3);
+}
+
+#[test]
+fn test_par2_sets_group_by_name_without_set_id() {
+    let files: Vec<PathBuf> = [
+        "/dl/Movie.par2",
+        "/dl/Other.par2",
+        "/dl/Movie.vol00+01.par2",
+        "/dl/Other.vol03+04.PAR2",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect();
+
+    assert_eq!(
+        crate::post_processing::verify::par2_sets(&files),
+        vec![
+            PathBuf::from("/dl/Movie.par2"),
+            PathBuf::from("/dl/Other.par2")
+        ]
+    );
+}
+
+/// Claims `.arj` files, and ZIP files named `legacy*`
+struct ArjExtractor;
+
+#[async_trait::async_trait]
+impl crate::extraction::Extractor for ArjExtractor {
+    fn name(&self) -> &str {
+        "arj"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        name.ends_with(".arj") || name.starts_with("legacy")
+    }
+
+    async fn extract(
+        &self,
+        _download_id: DownloadId,
+        _archive_path: &Path,
+        _dest_path: &Path,
+        _passwords: &crate::extraction::PasswordList,
+    ) -> Result<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+}
+
+#[tokio::test]
+async fn test_detect_all_archives_includes_registered_extractor_files_once() {
+    use tempfile::TempDir;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(Config::default()),
+        test_parity_handler(),
+        test_database().await,
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    std::fs::write(dir.join("release.arj"), b"arj data").unwrap();
+    std::fs::write(dir.join("legacy.zip"), b"zip data").unwrap();
+    std::fs::write(dir.join("release.zip"), b"zip data").unwrap();
+    std::fs::write(dir.join("video.mkv"), b"video data").unwrap();
+
+    let mut before = processor.detect_all_archives(dir).unwrap();
+    before.sort();
+    assert_eq!(
+        before,
+        vec![dir.join("legacy.zip"), dir.join("release.zip")]
+    );
+
+    processor.extractors().register(Arc::new(ArjExtractor));
+
+    let archives = processor.detect_all_archives(dir).unwrap();
+    let mut custom = archives[..2].to_vec();
+    custom.sort();
+    assert_eq!(
+        custom,
+        vec![dir.join("legacy.zip"), dir.join("release.arj")],
+        "registered extractors' files come first"
+    );
+    assert_eq!(archives[2..], [dir.join("release.zip")]);
+}
+
+#[tokio::test]
+async fn test_rerun_skips_stages_checkpointed_by_a_failed_run() {
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let db = test_database().await;
+    let download_id = db
+        .insert_download(&crate::db::NewDownload {
+            name: "Show".to_string(),
+            nzb_path: "/tmp/show.nzb".to_string(),
+            nzb_meta_name: None,
+            nzb_hash: None,
+            job_name: None,
+            category: None,
+            destination: "/tmp/show".to_string(),
+            post_process: PostProcess::UnpackAndCleanup.to_i32(),
+            priority: 0,
+            status: 3,
+            size_bytes: 1024,
+        })
+        .await
+        .unwrap();
+    let handler = Arc::new(RecordingParityHandler {
+        damaged: Some("Show.par2"),
+        ..Default::default()
+    });
+    let processor =
+        PostProcessor::new(tx, Arc::new(Config::default()), handler.clone(), db.clone());
+
+    let temp_dir = TempDir::new().unwrap();
+    let download_path = temp_dir.path().join("download");
+    fs::create_dir_all(&download_path).await.unwrap();
+    fs::write(download_path.join("Show.par2"), par2_packet(1))
+        .await
+        .unwrap();
+    fs::write(download_path.join("Show.mkv"), b"video")
+        .await
+        .unwrap();
+
+    // The move fails: the destination's parent is a file
+    fs::write(temp_dir.path().join("blocked"), b"")
+        .await
+        .unwrap();
+    let result = processor
+        .start_post_processing(
+            download_id,
+            download_path.clone(),
+            PostProcess::UnpackAndCleanup,
+            temp_dir.path().join("blocked").join("Show"),
+            &RunOptions::from_config(&processor.config),
+        )
+        .await;
+    assert!(result.is_err());
+    let mut done = db.get_stage_checkpoints(download_id).await.unwrap();
+    done.sort_by_key(|stage| format!("{:?}", stage));
+    assert_eq!(done, vec![Stage::Extract, Stage::Repair, Stage::Scan]);
+    let verified = handler.verified.lock().unwrap().len();
+    let repaired = handler.repaired.lock().unwrap().len();
+
+    let destination = temp_dir.path().join("Show");
+    processor
+        .start_post_processing(
+            download_id,
+            download_path,
+            PostProcess::UnpackAndCleanup,
+            destination.clone(),
+            &RunOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        handler.verified.lock().unwrap().len(),
+        verified,
+        "not verified again"
+    );
+    assert_eq!(
+        handler.repaired.lock().unwrap().len(),
+        repaired,
+        "not repaired again"
+    );
+    assert!(destination.join("Show.mkv").exists());
+}
+
+#[tokio::test]
+async fn test_running_a_stage_again_clears_its_checkpoint_and_later_ones() {
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let db = test_database().await;
+    let download_id = db
+        .insert_download(&crate::db::NewDownload {
+            name: "Show".to_string(),
+            nzb_path: "/tmp/show.nzb".to_string(),
+            nzb_meta_name: None,
+            nzb_hash: None,
+            job_name: None,
+            category: None,
+            destination: "/tmp/show".to_string(),
+            post_process: PostProcess::UnpackAndCleanup.to_i32(),
+            priority: 0,
+            status: 3,
+            size_bytes: 1024,
+        })
+        .await
+        .unwrap();
+    for stage in [Stage::Verify, Stage::Extract, Stage::Scan] {
+        db.add_stage_checkpoint(download_id, stage).await.unwrap();
+    }
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(Config::default()),
+        test_parity_handler(),
+        db.clone(),
+    );
+
+    processor.enter_stage(download_id, Stage::Extract).await;
+    assert_eq!(
+        db.get_stage_checkpoints(download_id).await.unwrap(),
+        vec![Stage::Verify]
+    );
+}
+
+#[tokio::test]
+async fn test_verify_policy_never_skips_verification() {
+    use tempfile::TempDir;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let mut config = Config::default();
+    config.processing.verify_policy = VerifyPolicy::Never;
+    let handler = Arc::new(RecordingParityHandler::default());
+    let processor =
+        PostProcessor::new(tx, Arc::new(config), handler.clone(), test_database().await);
+
+    let temp_dir = TempDir::new().unwrap();
+    tokio::fs::write(temp_dir.path().join("Show.par2"), par2_packet(1))
+        .await
+        .unwrap();
+
+    processor
+        .start_post_processing(
+            DownloadId(1),
+            temp_dir.path().to_path_buf(),
+            PostProcess::Repair,
+            temp_dir.path().join("destination"),
+            &RunOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap();
+    assert!(handler.verified.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_verify_policy_always_fails_downloads_without_par2_files() {
+    use tempfile::TempDir;
+
+    let (tx, _rx) = crate::event_bus::channel(100);
+    let processor = PostProcessor::new(
+        tx,
+        Arc::new(Config::default()),
+        Arc::new(RecordingParityHandler::default()),
+        test_database().await,
+    );
+    let temp_dir = TempDir::new().unwrap();
+    tokio::fs::write(temp_dir.path().join("Show.mkv"), b"video")
+        .await
+        .unwrap();
+
+    // The default policy lets a download without PAR2 files through
+    processor
+        .start_post_processing(
+            DownloadId(1),
+            temp_dir.path().to_path_buf(),
+            PostProcess::Verify,
+            temp_dir.path().join("destination"),
+            &RunOptions::from_config(&processor.config),
+        )
+        .await
+        .unwrap();
+
+    // A category requiring verification doesn't
+    let options = RunOptions {
+        verify_policy: VerifyPolicy::Always,
+        ..RunOptions::from_config(&processor.config)
+    };
+    let result = processor
+        .start_post_processing(
+            DownloadId(1),
+            temp_dir.path().to_path_buf(),
+            PostProcess::Verify,
+            temp_dir.path().join("destination"),
+            &options,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::PostProcess(
+            PostProcessError::VerificationFailed { .. }
+        ))
+    ));
+}
@@ -0,0 +1,450 @@
+//! File transfer for the move stage
+//!
+//! Files are renamed into place when source and destination share a filesystem. When
+//! they don't (EXDEV), or when [`MoveMode::Copy`] is configured, the file is copied to a
+//! hidden partial file next to the destination, verified against a checksum of the source
+//! taken while copying ([`MoveChecksum`]), renamed into place and only then deleted from
+//! the temp directory. The destination therefore never contains a half-written file under
+//! its final name. A copy that doesn't match its source is retried with the
+//! `processing.retry` backoff, since network storage can corrupt a write transiently.
+//!
+//! [`MoveMode::Link`] hard-links files into place instead, or reflinks them where hard
+//! links aren't supported, and falls back to a verified copy. The originals stay in the
+//! temp directory: a marker file records when the download was linked, and the folder is
+//! deleted once `download.link_retention` has passed (see [`retain_linked_originals`]).
+
+use crate::config::{Config, MoveChecksum, MoveMode};
+use crate::error::{PostProcessError, Result};
+use crate::event_bus::EventSender;
+use crate::types::{DownloadId, Event};
+use crate::utils::long_path;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::{debug, info, warn};
+
+/// Buffer size for copying and hashing
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Marker left in a download's temp folder once its files were linked into place; its
+/// modification time is when the retention period started
+const LINKED_MARKER: &str = ".linked";
+
+/// Byte-level progress across one move stage
+///
+/// Renames complete instantly and only advance the counter; copies emit
+/// [`Event::MoveProgress`] once per whole-percent change.
+pub(crate) struct TransferProgress {
+    download_id: DownloadId,
+    event_tx: EventSender,
+    total_bytes: u64,
+    transferred_bytes: AtomicU64,
+    last_percent: AtomicU32,
+    verified_files: AtomicUsize,
+    moved_files: std::sync::Mutex<Vec<PathBuf>>,
+}
+
+impl TransferProgress {
+    /// Create a tracker for moving `total_bytes`
+    pub(crate) fn new(download_id: DownloadId, event_tx: EventSender, total_bytes: u64) -> Self {
+        Self {
+            download_id,
+            event_tx,
+            total_bytes,
+            transferred_bytes: AtomicU64::new(0),
+            last_percent: AtomicU32::new(u32::MAX),
+            verified_files: AtomicUsize::new(0),
+            moved_files: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record a file that reached its final location
+    pub(crate) fn record_moved(&self, path: PathBuf) {
+        self.moved_files
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(path);
+    }
+
+    /// Number of files copied and checksum-verified so far
+    pub(crate) fn verified_files(&self) -> usize {
+        self.verified_files.load(Ordering::Relaxed)
+    }
+
+    /// Final paths of all files moved, in move order
+    pub(crate) fn into_moved_files(self) -> Vec<PathBuf> {
+        self.moved_files
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn advance(&self, bytes: u64, emit: bool) {
+        let transferred = self.transferred_bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if !emit || self.total_bytes == 0 {
+            return;
+        }
+
+        let percent = (transferred as f64 / self.total_bytes as f64 * 100.0).min(100.0) as f32;
+        let whole = percent as u32;
+        if self.last_percent.swap(whole, Ordering::Relaxed) != whole {
+            self.event_tx
+                .send(Event::MoveProgress {
+                    id: self.download_id,
+                    percent,
+                })
+                .ok();
+        }
+    }
+}
+
+/// Total size in bytes of a file or directory tree
+pub(crate) fn total_size<'a>(
+    path: &'a Path,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<u64>> + Send + 'a>> {
+    Box::pin(async move {
+        let metadata = tokio::fs::metadata(path).await?;
+        if !metadata.is_dir() {
+            return Ok(metadata.len());
+        }
+
+        let mut total = 0;
+        let mut entries = tokio::fs::read_dir(path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            total += total_size(&entry.path()).await?;
+        }
+        Ok(total)
+    })
+}
+
+/// Move one file to `destination` according to `download.move_mode`
+pub(crate) async fn transfer_file(
+    download_id: DownloadId,
+    source: &Path,
+    destination: &Path,
+    config: &Config,
+    progress: &TransferProgress,
+) -> Result<()> {
+    let source = &long_path(source);
+    let destination = &long_path(destination);
+
+    match config.download.move_mode {
+        MoveMode::Rename => match tokio::fs::rename(source, destination).await {
+            Ok(()) => {
+                let size = tokio::fs::metadata(destination)
+                    .await
+                    .map(|m| m.len())
+                    .unwrap_or(0);
+                progress.advance(size, false);
+                return Ok(());
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+                debug!(
+                    download_id = download_id.0,
+                    ?source,
+                    ?destination,
+                    "cross-device move, falling back to copy"
+                );
+            }
+            Err(e) => return Err(e.into()),
+        },
+        MoveMode::Link => {
+            if link_file(download_id, source, destination).await {
+                let size = tokio::fs::metadata(destination)
+                    .await
+                    .map(|m| m.len())
+                    .unwrap_or(0);
+                progress.advance(size, false);
+                return Ok(());
+            }
+        }
+        MoveMode::Copy => {}
+    }
+
+    let checksum = config.download.move_checksum;
+    let keep_source = config.download.move_mode == MoveMode::Link;
+    crate::retry::download_with_retry(&config.processing.retry, || {
+        copy_verified(
+            download_id,
+            source,
+            destination,
+            checksum,
+            keep_source,
+            progress,
+        )
+    })
+    .await?;
+    progress.verified_files.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Copy, verify and atomically publish a file, then delete the source unless
+/// `keep_source` is set
+async fn copy_verified(
+    download_id: DownloadId,
+    source: &Path,
+    destination: &Path,
+    checksum: MoveChecksum,
+    keep_source: bool,
+    progress: &TransferProgress,
+) -> Result<()> {
+    let partial = partial_path(destination);
+
+    let source_hash = match copy_and_hash(source, &partial, checksum, progress).await {
+        Ok(hash) => hash,
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e);
+        }
+    };
+
+    let copied_hash = hash_file(&partial, checksum).await?;
+    if copied_hash != source_hash {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(PostProcessError::ChecksumMismatch {
+            source_path: source.to_path_buf(),
+            dest_path: destination.to_path_buf(),
+        }
+        .into());
+    }
+
+    // Carry over permissions before the file becomes visible under its final name
+    if let Ok(metadata) = tokio::fs::metadata(source).await {
+        let _ = tokio::fs::set_permissions(&partial, metadata.permissions()).await;
+    }
+
+    tokio::fs::rename(&partial, destination).await?;
+    if !keep_source {
+        tokio::fs::remove_file(source).await?;
+    }
+
+    info!(
+        download_id = download_id.0,
+        ?source,
+        ?destination,
+        "copied and verified file"
+    );
+
+    Ok(())
+}
+
+/// Hard-link `source` to `destination`, or reflink it where hard links aren't supported
+///
+/// Returns `false` if neither worked (e.g. across filesystems), leaving `destination`
+/// absent.
+async fn link_file(download_id: DownloadId, source: &Path, destination: &Path) -> bool {
+    let error = match tokio::fs::hard_link(source, destination).await {
+        Ok(()) => {
+            debug!(
+                download_id = download_id.0,
+                ?source,
+                ?destination,
+                "hard-linked file"
+            );
+            return true;
+        }
+        Err(e) => e,
+    };
+
+    if error.kind() != std::io::ErrorKind::CrossesDevices && reflink(source, destination).await {
+        debug!(
+            download_id = download_id.0,
+            ?source,
+            ?destination,
+            "reflinked file"
+        );
+        return true;
+    }
+
+    debug!(
+        download_id = download_id.0,
+        ?source,
+        ?destination,
+        error = %error,
+        "can't link file, falling back to copy"
+    );
+    false
+}
+
+/// Clone `source` to `destination` sharing its data blocks (btrfs, XFS, ...)
+#[cfg(target_os = "linux")]
+async fn reflink(source: &Path, destination: &Path) -> bool {
+    let partial = partial_path(destination);
+    let cloned = tokio::process::Command::new("cp")
+        .arg("--reflink=always")
+        .arg("--")
+        .arg(source)
+        .arg(&partial)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .await
+        .is_ok_and(|status| status.success());
+    if cloned && tokio::fs::rename(&partial, destination).await.is_ok() {
+        return true;
+    }
+    let _ = tokio::fs::remove_file(&partial).await;
+    false
+}
+
+/// Reflinks are only attempted on Linux
+#[cfg(not(target_os = "linux"))]
+async fn reflink(_source: &Path, _destination: &Path) -> bool {
+    false
+}
+
+/// Keep the originals of a linked download for `retention`, then delete its temp folder
+///
+/// Restarts the retention period if the download is linked again (e.g. reprocessed).
+pub(crate) async fn retain_linked_originals(
+    download_id: DownloadId,
+    download_path: &Path,
+    retention: std::time::Duration,
+) {
+    if let Err(e) = tokio::fs::write(download_path.join(LINKED_MARKER), b"").await {
+        warn!(
+            download_id = download_id.0,
+            ?download_path,
+            error = %e,
+            "failed to mark linked originals, they won't be deleted automatically"
+        );
+        return;
+    }
+
+    let download_path = download_path.to_path_buf();
+    tokio::spawn(async move {
+        tokio::time::sleep(retention).await;
+        remove_if_expired(&download_path, retention).await;
+    });
+}
+
+/// Delete the temp folders in `temp_dir` whose linked originals outlived `retention`
+///
+/// Catches up on folders whose retention ran out while the downloader wasn't running.
+/// Looks one level deeper too, where the date and category layouts put download folders.
+pub(crate) async fn remove_expired_linked_originals(
+    temp_dir: &Path,
+    retention: std::time::Duration,
+) {
+    let Ok(mut entries) = tokio::fs::read_dir(temp_dir).await else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if remove_if_expired(&entry.path(), retention).await
+            || !entry.file_type().await.is_ok_and(|t| t.is_dir())
+        {
+            continue;
+        }
+        let Ok(mut children) = tokio::fs::read_dir(entry.path()).await else {
+            continue;
+        };
+        while let Ok(Some(child)) = children.next_entry().await {
+            remove_if_expired(&child.path(), retention).await;
+        }
+    }
+}
+
+/// Delete `download_path` if it holds linked originals older than `retention`
+///
+/// Returns whether `download_path` holds linked originals at all.
+async fn remove_if_expired(download_path: &Path, retention: std::time::Duration) -> bool {
+    let linked_at = tokio::fs::metadata(download_path.join(LINKED_MARKER))
+        .await
+        .and_then(|metadata| metadata.modified());
+    let Ok(linked_at) = linked_at else {
+        return false;
+    };
+    if linked_at.elapsed().unwrap_or_default() < retention {
+        return true;
+    }
+
+    match tokio::fs::remove_dir_all(download_path).await {
+        Ok(()) => info!(?download_path, "deleted linked originals after retention"),
+        Err(e) => warn!(?download_path, error = %e, "failed to delete linked originals"),
+    }
+    true
+}
+
+/// Hidden sibling of `destination` used while a copy is in flight
+fn partial_path(destination: &Path) -> PathBuf {
+    let name = destination
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    destination.with_file_name(format!(".{}.partial", name))
+}
+
+/// Incremental hash for either [`MoveChecksum`] algorithm
+enum Hasher {
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(checksum: MoveChecksum) -> Self {
+        match checksum {
+            MoveChecksum::Sha256 => Self::Sha256(Sha256::new()),
+            MoveChecksum::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        match self {
+            Self::Sha256(hasher) => hasher.finalize().into(),
+            Self::Blake3(hasher) => hasher.finalize().into(),
+        }
+    }
+}
+
+/// Copy `source` to `target`, returning the checksum of the bytes read
+async fn copy_and_hash(
+    source: &Path,
+    target: &Path,
+    checksum: MoveChecksum,
+    progress: &TransferProgress,
+) -> Result<[u8; 32]> {
+    let mut reader = tokio::fs::File::open(source).await?;
+    let mut writer = tokio::fs::File::create(target).await?;
+    let mut hasher = Hasher::new(checksum);
+    let mut buf = vec![0u8; COPY_BUFFER_SIZE];
+
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        writer.write_all(&buf[..n]).await?;
+        progress.advance(n as u64, true);
+    }
+
+    writer.flush().await?;
+    writer.sync_all().await?;
+    Ok(hasher.finalize())
+}
+
+/// Checksum of a file's contents
+async fn hash_file(path: &Path, checksum: MoveChecksum) -> Result<[u8; 32]> {
+    let mut reader = tokio::fs::File::open(path).await?;
+    let mut hasher = Hasher::new(checksum);
+    let mut buf = vec![0u8; COPY_BUFFER_SIZE];
+
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+
+    Ok(hasher.finalize())
+}
@@ -1,22 +1,23 @@
 //! PAR2 verification stage
 
 use crate::error::Result;
+use crate::event_bus::EventSender;
 use crate::parity::ParityHandler;
 use crate::types::{DownloadId, Event};
 use std::path::{Path, PathBuf};
-use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
 
 use super::PostProcessError;
 
 /// Execute the verify stage
 ///
+/// Each PAR2 recovery set in the download (see [`par2_sets`]) is verified separately.
 /// Returns `Ok(true)` if files are damaged but repairable, `Ok(false)` if
 /// files are intact (or verification was skipped/not supported).
 pub(crate) async fn run_verify_stage(
     download_id: DownloadId,
     download_path: &Path,
-    event_tx: &broadcast::Sender<Event>,
+    event_tx: &EventSender,
     parity_handler: &dyn ParityHandler,
 ) -> Result<bool> {
     debug!(
@@ -48,74 +49,147 @@ pub(crate) async fn run_verify_stage(
         return Ok(false);
     }
 
-    // Use the first PAR2 file found (typically the .par2 file, not .vol files)
-    let par2_file = &par2_files[0];
-    debug!(
-        download_id = download_id.0,
-        ?par2_file,
-        "verifying with PAR2 file"
-    );
+    // Packs can hold several independent sets; each is verified on its own
+    let sets = par2_sets(&par2_files);
+    let mut damaged = false;
+    for (i, par2_file) in sets.iter().enumerate() {
+        debug!(
+            download_id = download_id.0,
+            ?par2_file,
+            set = i + 1,
+            sets = sets.len(),
+            "verifying with PAR2 file"
+        );
 
-    // Call parity handler to verify
-    let verify_result = match parity_handler.verify(par2_file).await {
-        Ok(result) => result,
-        Err(crate::Error::NotSupported(ref msg)) => {
-            warn!(
-                download_id = download_id.0,
-                ?par2_file,
-                "PAR2 verification not supported: {}",
-                msg
-            );
-
-            // Emit VerifyComplete event (skipped, assume no damage)
+        // Call parity handler to verify, streaming progress where the handler supports it
+        let reporter = super::parity_progress_reporter(download_id, event_tx, |id, percent| {
+            Event::VerifyProgress { id, percent }
+        });
+        let on_progress = |percent: f32| reporter((i as f32 * 100.0 + percent) / sets.len() as f32);
+        let verify_result = match parity_handler
+            .verify_with_progress(par2_file, &on_progress)
+            .await
+        {
+            Ok(result) => result,
+            Err(crate::Error::NotSupported(ref msg)) => {
+                warn!(
+                    download_id = download_id.0,
+                    ?par2_file,
+                    "PAR2 verification not supported: {}",
+                    msg
+                );
+
+                // Emit VerifyComplete event (skipped, assume no damage)
+                event_tx
+                    .send(Event::VerifyComplete {
+                        id: download_id,
+                        damaged: false,
+                    })
+                    .ok();
+
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
+
+        info!(
+            download_id = download_id.0,
+            ?par2_file,
+            is_complete = verify_result.is_complete,
+            damaged_blocks = verify_result.damaged_blocks,
+            recovery_blocks = verify_result.recovery_blocks_available,
+            repairable = verify_result.repairable,
+            "PAR2 verification complete"
+        );
+
+        // If files are damaged and not repairable, fail immediately
+        if !verify_result.is_complete && !verify_result.repairable {
             event_tx
                 .send(Event::VerifyComplete {
                     id: download_id,
-                    damaged: false,
+                    damaged: true,
                 })
                 .ok();
 
-            return Ok(false);
+            let set = if sets.len() > 1 {
+                format!(" in PAR2 set {}", par2_file.display())
+            } else {
+                String::new()
+            };
+            return Err(PostProcessError::VerificationFailed {
+                id: download_id.into(),
+                reason: format!(
+                    "files{} are damaged ({} blocks) but cannot be repaired (need {} more recovery blocks)",
+                    set,
+                    verify_result.damaged_blocks,
+                    verify_result.damaged_blocks.saturating_sub(verify_result.recovery_blocks_available)
+                ),
+            }
+            .into());
         }
-        Err(e) => return Err(e),
-    };
-
-    info!(
-        download_id = download_id.0,
-        is_complete = verify_result.is_complete,
-        damaged_blocks = verify_result.damaged_blocks,
-        recovery_blocks = verify_result.recovery_blocks_available,
-        repairable = verify_result.repairable,
-        "PAR2 verification complete"
-    );
+        damaged |= !verify_result.is_complete;
+    }
 
     // Emit VerifyComplete event
     event_tx
         .send(Event::VerifyComplete {
             id: download_id,
-            damaged: !verify_result.is_complete,
+            damaged,
         })
         .ok();
 
-    // If files are damaged and not repairable, fail immediately
-    if !verify_result.is_complete && !verify_result.repairable {
-        return Err(PostProcessError::VerificationFailed {
-            id: download_id.into(),
-            reason: format!(
-                "files are damaged ({} blocks) but cannot be repaired (need {} more recovery blocks)",
-                verify_result.damaged_blocks,
-                verify_result.damaged_blocks.saturating_sub(verify_result.recovery_blocks_available)
-            ),
+    // Return whether files are damaged (but repairable)
+    Ok(damaged)
+}
+
+/// One PAR2 file per recovery set, in the order of `par2_files`
+///
+/// Files are grouped by the recovery set ID in their first packet, or by name
+/// (`Show.vol03+04.par2` goes with `Show.par2`) when that can't be read. With
+/// `par2_files` sorted by [`find_par2_files`], each set is represented by its base file.
+pub(super) fn par2_sets(par2_files: &[PathBuf]) -> Vec<PathBuf> {
+    #[derive(PartialEq)]
+    enum SetKey {
+        Id([u8; 16]),
+        Name(String),
+    }
+
+    let mut keys = Vec::new();
+    let mut sets = Vec::new();
+    for par2_file in par2_files {
+        let key = match crate::parity::par2_metadata::read_recovery_set_id(par2_file) {
+            Some(id) => SetKey::Id(id),
+            None => SetKey::Name(par2_set_name(par2_file)),
+        };
+        if !keys.contains(&key) {
+            keys.push(key);
+            sets.push(par2_file.clone());
         }
-        .into());
     }
+    sets
+}
 
-    // Return whether files are damaged (but repairable)
-    Ok(!verify_result.is_complete)
+/// Name of the set a PAR2 file belongs to: its name without `.volNN+NN.par2`
+fn par2_set_name(par2_file: &Path) -> String {
+    let name = par2_file
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let name = name.strip_suffix(".par2").unwrap_or(&name);
+    match name.rfind(".vol") {
+        Some(i)
+            if name[i + 4..]
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '+' || c == '-') =>
+        {
+            name[..i].to_string()
+        }
+        _ => name.to_string(),
+    }
 }
 
 /// Find all PAR2 files in the download directory
-async fn find_par2_files(download_path: &Path) -> Result<Vec<PathBuf>> {
+pub(super) async fn find_par2_files(download_path: &Path) -> Result<Vec<PathBuf>> {
     let mut par2_files = Vec::new();
 
     let mut entries = tokio::fs::read_dir(download_path)
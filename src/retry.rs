@@ -32,7 +32,7 @@
 //! ```
 
 use crate::config::RetryConfig;
-use crate::error::Error;
+use crate::error::{Error, PostProcessError};
 use rand::Rng;
 use std::future::Future;
 use std::time::Duration;
@@ -79,7 +79,9 @@ impl IsRetryable for Error {
             }
             // Download errors are not retryable (state/not-found/space errors)
             Error::Download(_) => false,
-            // Post-processing errors are generally permanent
+            // A corrupted copy may succeed on the next attempt
+            Error::PostProcess(PostProcessError::ChecksumMismatch { .. }) => true,
+            // Other post-processing errors are generally permanent
             Error::PostProcess(_) => false,
             // Database errors should not be retried (likely permanent)
             Error::Database(_) | Error::Sqlx(_) => false,
@@ -103,6 +105,12 @@ impl IsRetryable for Error {
             Error::InsufficientSpace { .. } => false,
             // Disk space check errors are permanent (file system issues)
             Error::DiskSpaceCheckFailed(_) => false,
+            // A bad backup archive won't fix itself
+            Error::InvalidBackup(_) => false,
+            // Busy until the user pauses or drains the queue
+            Error::Busy(_) => false,
+            // Read-only mode lasts until restarted without it
+            Error::ReadOnly => false,
             // External tool errors might be retryable (temporary failures)
             Error::ExternalTool(msg) => {
                 // Retry on timeouts, busy states, but not on "not found" errors
@@ -576,6 +584,22 @@ mod tests {
         assert!(!Error::Download(DownloadError::NotFound { id: 123 }).is_retryable());
     }
 
+    #[test]
+    fn test_error_is_retryable_checksum_mismatch() {
+        let mismatch = Error::PostProcess(PostProcessError::ChecksumMismatch {
+            source_path: "/tmp/a.mkv".into(),
+            dest_path: "/mnt/nas/a.mkv".into(),
+        });
+        assert!(mismatch.is_retryable());
+
+        let move_failed = Error::PostProcess(PostProcessError::MoveFailed {
+            source_path: "/tmp/a.mkv".into(),
+            dest_path: "/mnt/nas/a.mkv".into(),
+            reason: "permission denied".to_string(),
+        });
+        assert!(!move_failed.is_retryable());
+    }
+
     // -----------------------------------------------------------------------
     // add_jitter bounds verification
     // -----------------------------------------------------------------------
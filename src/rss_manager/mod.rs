@@ -3,12 +3,13 @@
 //! This module provides functionality for monitoring RSS/Atom feeds, filtering items based on
 //! configurable rules, and automatically downloading matching NZB files. It supports both RSS 2.0
 //! and Atom feed formats, with regex-based filtering, size constraints, age limits, and duplicate
-//! detection.
+//! detection, both per feed (items already seen) and across feeds (releases already grabbed).
 
 use crate::UsenetDownloader;
-use crate::config::{RssFeedConfig, RssFilter};
+use crate::config::{RssFeedConfig, RssFilter, RssFilterAction};
 use crate::db::Database;
 use crate::error::{Error, Result};
+use crate::types::Event;
 use chrono::{DateTime, Utc};
 use regex::Regex;
 use std::sync::Arc;
@@ -131,8 +132,7 @@ impl RssManager {
 
         // Fetch feed content
         let response = self
-            .http_client
-            .get(&feed_config.url)
+            .feed_request(feed_config)
             .send()
             .await
             .map_err(|e| Error::Other(format!("Failed to fetch RSS feed: {}", e)))?;
@@ -174,6 +174,37 @@ impl RssManager {
         }
     }
 
+    /// Build the request for a feed, adding its API key, headers, basic auth and cookies
+    fn feed_request(&self, feed_config: &RssFeedConfig) -> reqwest::RequestBuilder {
+        let auth = &feed_config.auth;
+        let mut request = self.http_client.get(&feed_config.url);
+
+        // Appended to any query the URL already has
+        if let Some(api_key) = &auth.api_key {
+            request = request.query(&[(auth.api_key_param.as_str(), api_key.as_str())]);
+        }
+
+        for (name, value) in &auth.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        if let Some(username) = &auth.username {
+            request = request.basic_auth(username, auth.password.as_deref());
+        }
+
+        if !auth.cookies.is_empty() {
+            let mut cookies: Vec<String> = auth
+                .cookies
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            cookies.sort();
+            request = request.header(reqwest::header::COOKIE, cookies.join("; "));
+        }
+
+        request
+    }
+
     /// Parse feed content as RSS
     fn parse_as_rss(&self, content: &str) -> Result<Vec<RssItem>> {
         let channel = content
@@ -404,13 +435,50 @@ impl RssManager {
         true
     }
 
+    /// Key identifying a release across feeds
+    ///
+    /// The title is lowercased and every run of non-alphanumeric characters becomes a
+    /// single space, so `Show.Name.S01E01.720p-GRP` and `Show Name S01E01 720p GRP` match.
+    fn release_key(title: &str) -> String {
+        title
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Whether two release sizes are within `tolerance_percent` of the larger one
+    fn sizes_match(a: u64, b: u64, tolerance_percent: f64) -> bool {
+        a.abs_diff(b) as f64 <= a.max(b) as f64 * tolerance_percent / 100.0
+    }
+
+    /// Find the feed a release was already grabbed from within the dedup window
+    async fn find_previous_grab(&self, item: &RssItem, release_key: &str) -> Result<Option<i64>> {
+        let dedup = &self.downloader.config.automation.rss_dedup;
+        let since = Utc::now().timestamp() - dedup.window.as_secs() as i64;
+        let grabs = self.db.find_rss_grabs(release_key, since).await?;
+
+        Ok(grabs
+            .into_iter()
+            .find(|grab| match (item.size, grab.size) {
+                (Some(size), Some(grabbed)) => {
+                    Self::sizes_match(size, grabbed as u64, dedup.size_tolerance_percent)
+                }
+                // Without both sizes the title alone decides
+                _ => true,
+            })
+            .map(|grab| grab.feed_id))
+    }
+
     /// Process feed items: check if seen, apply filters, mark as seen, and optionally auto-download
     ///
     /// This method implements the core RSS feed processing logic:
     /// 1. Skips items that have already been seen (checks rss_seen table)
     /// 2. Applies filters to determine if items should be processed
     /// 3. Marks matching items as seen to prevent re-processing
-    /// 4. Auto-downloads items if auto_download=true and item has NZB URL
+    /// 4. Auto-downloads items if auto_download=true and item has NZB URL, unless the
+    ///    same release was already grabbed from any feed (see [`crate::config::RssDedupConfig`])
     ///
     /// # Arguments
     /// * `feed_id` - Database ID of the feed (for seen tracking)
@@ -430,6 +498,12 @@ impl RssManager {
     ) -> Result<usize> {
         let mut downloaded_count = 0;
 
+        let dedup = &self.downloader.config.automation.rss_dedup;
+        if dedup.enabled {
+            let before = Utc::now().timestamp() - dedup.window.as_secs() as i64;
+            self.db.delete_rss_grabs_before(before).await?;
+        }
+
         for item in items {
             // Skip if already seen
             if self.db.is_rss_item_seen(feed_id, &item.guid).await? {
@@ -437,55 +511,104 @@ impl RssManager {
                 continue;
             }
 
-            // Check if item matches any of the configured filters
-            let matches = if feed_config.filters.is_empty() {
-                // No filters = accept everything
-                true
-            } else {
-                // At least one filter must match
-                feed_config
-                    .filters
-                    .iter()
-                    .any(|filter| self.matches_filters(&item, filter))
-            };
+            // A matching reject filter drops the item. If there are other filters, one of
+            // them must match, and the first that does decides what happens to the item.
+            let (matched, rejected): (Vec<&RssFilter>, Vec<&RssFilter>) = feed_config
+                .filters
+                .iter()
+                .filter(|filter| self.matches_filters(&item, filter))
+                .partition(|filter| filter.action != RssFilterAction::Reject);
+
+            if let Some(filter) = rejected.first() {
+                debug!("Item '{}' rejected by filter '{}'", item.title, filter.name);
+                continue;
+            }
 
-            if !matches {
+            let selects = feed_config
+                .filters
+                .iter()
+                .any(|filter| filter.action != RssFilterAction::Reject);
+            if selects && matched.is_empty() {
                 debug!("Item '{}' did not match any filters, skipping", item.title);
                 continue;
             }
+            let filter = matched.first().copied();
 
             // Mark as seen to prevent re-processing
             self.db.mark_rss_item_seen(feed_id, &item.guid).await?;
             info!("New RSS item matched filters: {}", item.title);
 
-            // Auto-download if enabled and NZB URL is available
-            if feed_config.auto_download {
-                if let Some(nzb_url) = &item.nzb_url {
-                    let options = crate::types::DownloadOptions {
-                        category: feed_config.category.clone(),
-                        destination: None,
-                        post_process: None,
-                        priority: feed_config.priority,
-                        password: None,
-                    };
-
-                    match self.downloader.add_nzb_url(nzb_url, options).await {
-                        Ok(download_id) => {
-                            info!(
-                                "Auto-downloaded '{}' from RSS feed (download_id: {})",
-                                item.title, download_id
-                            );
-                            downloaded_count += 1;
-                        }
-                        Err(e) => {
-                            warn!(
-                                "Failed to auto-download '{}' from RSS feed: {}",
-                                item.title, e
-                            );
-                        }
+            let action = filter.map_or(RssFilterAction::Download, |filter| filter.action);
+            if action == RssFilterAction::Notify || !feed_config.auto_download {
+                self.downloader.emit_event(Event::RssItemMatched {
+                    feed_id,
+                    title: item.title.clone(),
+                    nzb_url: item.nzb_url.clone(),
+                    filter: filter.map(|filter| filter.name.clone()),
+                });
+                continue;
+            }
+
+            // Auto-download if the NZB URL is available
+            let Some(nzb_url) = &item.nzb_url else {
+                debug!("Item '{}' has no NZB URL, cannot auto-download", item.title);
+                continue;
+            };
+
+            let release_key = Self::release_key(&item.title);
+            if dedup.enabled
+                && let Some(grabbed_from) = self.find_previous_grab(&item, &release_key).await?
+            {
+                info!(
+                    "Skipping '{}': already grabbed from RSS feed {}",
+                    item.title, grabbed_from
+                );
+                continue;
+            }
+
+            let options = crate::types::DownloadOptions {
+                category: filter
+                    .and_then(|filter| filter.category.clone())
+                    .or_else(|| feed_config.category.clone()),
+                destination: None,
+                post_process: None,
+                priority: filter
+                    .and_then(|filter| filter.priority)
+                    .unwrap_or(feed_config.priority),
+                password: None,
+                batch_id: None,
+                temp_dir: None,
+            };
+
+            match self.downloader.add_nzb_url(nzb_url, options).await {
+                Ok(download_id) => {
+                    info!(
+                        "Auto-downloaded '{}' from RSS feed (download_id: {})",
+                        item.title, download_id
+                    );
+                    downloaded_count += 1;
+
+                    if action == RssFilterAction::DownloadPaused
+                        && let Err(e) = self.downloader.pause(download_id).await
+                    {
+                        warn!("Failed to pause RSS download {}: {}", download_id, e);
                     }
-                } else {
-                    debug!("Item '{}' has no NZB URL, cannot auto-download", item.title);
+
+                    if dedup.enabled {
+                        self.db
+                            .record_rss_grab(
+                                feed_id,
+                                &release_key,
+                                item.size.map(|size| size as i64),
+                            )
+                            .await?;
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to auto-download '{}' from RSS feed: {}",
+                        item.title, e
+                    );
                 }
             }
         }
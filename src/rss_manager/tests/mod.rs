@@ -47,10 +47,15 @@ async fn test_rss_manager_with_filters() {
             min_size: Some(1024 * 1024 * 1024),            // 1 GB
             max_size: Some(10 * 1024 * 1024 * 1024),       // 10 GB
             max_age: Some(Duration::from_secs(86400 * 7)), // 7 days
+            action: Default::default(),
+            category: None,
+            priority: None,
         }],
         auto_download: true,
         priority: Priority::High,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     }];
 
     let manager = RssManager::new(db, downloader, feeds).expect("Failed to create manager");
@@ -249,6 +254,9 @@ async fn test_matches_filters_include_patterns() {
         min_size: None,
         max_size: None,
         max_age: None,
+        action: Default::default(),
+        category: None,
+        priority: None,
     };
 
     // Should match - has 1080p
@@ -309,6 +317,9 @@ async fn test_matches_filters_exclude_patterns() {
         min_size: None,
         max_size: None,
         max_age: None,
+        action: Default::default(),
+        category: None,
+        priority: None,
     };
 
     // Should match - has 1080p, no CAM
@@ -369,6 +380,9 @@ async fn test_matches_filters_regex_patterns() {
         min_size: None,
         max_size: None,
         max_age: None,
+        action: Default::default(),
+        category: None,
+        priority: None,
     };
 
     // Should match - has S01E01
@@ -429,6 +443,9 @@ async fn test_matches_filters_size_constraints() {
         min_size: Some(1024 * 1024 * 500),      // 500 MB
         max_size: Some(1024 * 1024 * 1024 * 5), // 5 GB
         max_age: None,
+        action: Default::default(),
+        category: None,
+        priority: None,
     };
 
     // Should match - size within range
@@ -504,6 +521,9 @@ async fn test_matches_filters_age_constraint() {
         min_size: None,
         max_size: None,
         max_age: Some(Duration::from_secs(86400 * 7)), // 7 days
+        action: Default::default(),
+        category: None,
+        priority: None,
     };
 
     // Should match - recent item (1 day old)
@@ -564,6 +584,9 @@ async fn test_matches_filters_description_matching() {
         min_size: None,
         max_size: None,
         max_age: None,
+        action: Default::default(),
+        category: None,
+        priority: None,
     };
 
     // Should match - BluRay in description
@@ -610,6 +633,9 @@ async fn test_matches_filters_no_filters() {
         min_size: None,
         max_size: None,
         max_age: None,
+        action: Default::default(),
+        category: None,
+        priority: None,
     };
 
     let item = RssItem {
@@ -661,6 +687,8 @@ async fn test_process_feed_items_auto_download_enabled() {
         auto_download: true,
         priority: crate::types::Priority::Normal,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let items = vec![
@@ -737,6 +765,8 @@ async fn test_process_feed_items_auto_download_disabled() {
         auto_download: false, // Disabled
         priority: crate::types::Priority::Normal,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let items = vec![RssItem {
@@ -802,6 +832,8 @@ async fn test_process_feed_items_skips_seen() {
         auto_download: true,
         priority: crate::types::Priority::Normal,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let items = vec![
@@ -872,10 +904,15 @@ async fn test_process_feed_items_with_filters() {
             min_size: Some(500 * 1024 * 1024), // 500 MB minimum
             max_size: None,
             max_age: None,
+            action: Default::default(),
+            category: None,
+            priority: None,
         }],
         auto_download: true,
         priority: crate::types::Priority::Normal,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let items = vec![
@@ -967,6 +1004,8 @@ async fn test_process_feed_items_no_nzb_url() {
         auto_download: true,
         priority: crate::types::Priority::Normal,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let items = vec![RssItem {
@@ -1030,6 +1069,9 @@ async fn test_process_feed_items_multiple_filters_or_logic() {
                 min_size: None,
                 max_size: None,
                 max_age: None,
+                action: Default::default(),
+                category: None,
+                priority: None,
             },
             RssFilter {
                 name: "TV Shows".to_string(),
@@ -1038,11 +1080,16 @@ async fn test_process_feed_items_multiple_filters_or_logic() {
                 min_size: None,
                 max_size: None,
                 max_age: None,
+                action: Default::default(),
+                category: None,
+                priority: None,
             },
         ],
         auto_download: true,
         priority: crate::types::Priority::Normal,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let items = vec![
@@ -1196,10 +1243,15 @@ async fn test_rss_end_to_end_with_mock_server() {
             min_size: Some(1_000_000_000), // 1 GB minimum
             max_size: None,
             max_age: None,
+            action: Default::default(),
+            category: None,
+            priority: None,
         }],
         auto_download: true,
         priority: crate::types::Priority::High,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     // Create RSS manager and fetch feed
@@ -1337,6 +1389,8 @@ async fn check_feed_returns_error_on_http_500() {
         auto_download: false,
         priority: Priority::Normal,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let manager = RssManager::new(db, downloader, vec![]).unwrap();
@@ -1374,6 +1428,8 @@ async fn check_feed_returns_error_on_http_404() {
         auto_download: false,
         priority: Priority::Normal,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let manager = RssManager::new(db, downloader, vec![]).unwrap();
@@ -1415,6 +1471,8 @@ async fn check_feed_returns_error_on_connection_refused() {
         auto_download: false,
         priority: Priority::Normal,
         enabled: true,
+        auth: Default::default(),
+        preference: 0,
     };
 
     let manager = RssManager::new(db, downloader, vec![]).unwrap();
@@ -1434,3 +1492,333 @@ async fn check_feed_returns_error_on_connection_refused() {
         Err(other) => panic!("expected Error::Other for connection refused, got: {other:?}"),
     }
 }
+
+#[tokio::test]
+async fn check_feed_sends_feed_auth() {
+    let (db, downloader) = create_test_setup().await;
+
+    let std_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = std_listener.local_addr().unwrap();
+    drop(std_listener);
+    let listener = TokioTcpListener::bind(addr).await.unwrap();
+
+    // Capture the raw request and answer with an empty feed
+    let (request_tx, request_rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        if let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = vec![0; 4096];
+            let n = socket.read(&mut buf).await.unwrap_or(0);
+            let _ = request_tx.send(String::from_utf8_lossy(&buf[..n]).to_lowercase());
+
+            let body = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>t</title><link>http://x</link><description>d</description></channel></rss>"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/rss+xml\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+
+    let feed_config = RssFeedConfig {
+        url: format!("http://{}/rss?cat=tv", addr),
+        check_interval: Duration::from_secs(900),
+        category: None,
+        filters: vec![],
+        auto_download: false,
+        priority: Priority::Normal,
+        enabled: true,
+        auth: crate::config::RssFeedAuth {
+            headers: [("X-Indexer-Token".to_string(), "token".to_string())].into(),
+            username: Some("user".to_string()),
+            password: Some("pass".to_string()),
+            cookies: [
+                ("uid".to_string(), "42".to_string()),
+                ("session".to_string(), "abc".to_string()),
+            ]
+            .into(),
+            api_key: Some("secret".to_string()),
+            ..Default::default()
+        },
+        preference: 0,
+    };
+
+    let manager = RssManager::new(db, downloader, vec![]).unwrap();
+    let items = manager.check_feed(&feed_config).await.unwrap();
+    assert!(items.is_empty());
+
+    let request = request_rx.await.unwrap();
+    assert!(
+        request.starts_with("get /rss?cat=tv&apikey=secret "),
+        "API key should be appended to the query, got: {request}"
+    );
+    assert!(request.contains("x-indexer-token: token\r\n"));
+    // base64("user:pass")
+    assert!(request.contains("authorization: basic dxnlcjpwyxnz\r\n"));
+    assert!(request.contains("cookie: session=abc; uid=42\r\n"));
+}
+
+// =========================================================================
+// Cross-feed deduplication
+// =========================================================================
+
+#[test]
+fn release_key_ignores_case_and_punctuation() {
+    assert_eq!(
+        RssManager::release_key("Show.Name.S01E01.720p.WEB-DL-GRP"),
+        "show name s01e01 720p web dl grp"
+    );
+    assert_eq!(
+        RssManager::release_key(" show_name  s01e01 720p web.dl [grp] "),
+        "show name s01e01 720p web dl grp"
+    );
+    assert_ne!(
+        RssManager::release_key("Show.Name.S01E01.720p"),
+        RssManager::release_key("Show.Name.S01E01.1080p")
+    );
+}
+
+#[test]
+fn sizes_match_within_tolerance() {
+    assert!(RssManager::sizes_match(1_000, 1_000, 0.0));
+    assert!(RssManager::sizes_match(1_000, 1_050, 5.0));
+    assert!(RssManager::sizes_match(1_050, 1_000, 5.0));
+    assert!(!RssManager::sizes_match(1_000, 1_100, 5.0));
+    assert!(!RssManager::sizes_match(1_000, 1_001, 0.0));
+}
+
+#[tokio::test]
+async fn process_feed_items_skips_release_grabbed_from_another_feed() {
+    use crate::downloader::test_helpers::SAMPLE_NZB;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let (db, downloader) = create_test_setup().await;
+    let server = MockServer::start().await;
+    for (nzb_path, expected_requests) in [("/a.nzb", 1), ("/b1.nzb", 0), ("/b2.nzb", 1)] {
+        Mock::given(method("GET"))
+            .and(path(nzb_path))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(SAMPLE_NZB))
+            .expect(expected_requests)
+            .mount(&server)
+            .await;
+    }
+
+    let mut feed_ids = Vec::new();
+    for name in ["Indexer A", "Indexer B"] {
+        let feed_id = db
+            .insert_rss_feed(crate::db::InsertRssFeedParams {
+                name,
+                url: "http://example.com/feed.rss",
+                check_interval_secs: 900,
+                category: None,
+                auto_download: true,
+                priority: 0,
+                enabled: true,
+                auth: None,
+                preference: 0,
+            })
+            .await
+            .unwrap();
+        feed_ids.push(feed_id);
+    }
+    let feed_config = RssFeedConfig {
+        url: "http://example.com/feed.rss".to_string(),
+        check_interval: Duration::from_secs(900),
+        category: None,
+        filters: vec![],
+        auto_download: true,
+        priority: Priority::Normal,
+        enabled: true,
+        auth: Default::default(),
+        preference: 0,
+    };
+    let item = |guid: &str, title: &str, size: u64, nzb_path: &str| RssItem {
+        title: title.to_string(),
+        link: None,
+        guid: guid.to_string(),
+        pub_date: Some(Utc::now()),
+        description: None,
+        size: Some(size),
+        nzb_url: Some(format!("{}{}", server.uri(), nzb_path)),
+    };
+
+    let manager = RssManager::new(db.clone(), downloader, vec![]).unwrap();
+    let downloaded = manager
+        .process_feed_items(
+            feed_ids[0],
+            &feed_config,
+            vec![item("a", "Show.Name.S01E01.720p-GRP", 1_000_000, "/a.nzb")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(downloaded, 1);
+
+    // The same release within 5% is skipped without fetching its NZB; a release
+    // with the same title but a different size is still tried
+    let downloaded = manager
+        .process_feed_items(
+            feed_ids[1],
+            &feed_config,
+            vec![
+                item("b1", "show name s01e01 720p grp", 1_020_000, "/b1.nzb"),
+                item("b2", "Show.Name.S01E01.720p-GRP", 3_000_000, "/b2.nzb"),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(downloaded, 1, "only b2 should be added");
+    assert!(db.is_rss_item_seen(feed_ids[1], "b1").await.unwrap());
+
+    let grabs = db
+        .find_rss_grabs("show name s01e01 720p grp", 0)
+        .await
+        .unwrap();
+    assert_eq!(grabs.len(), 2);
+    assert!(
+        grabs
+            .iter()
+            .any(|g| g.feed_id == feed_ids[0] && g.size == Some(1_000_000))
+    );
+    assert!(
+        grabs
+            .iter()
+            .any(|g| g.feed_id == feed_ids[1] && g.size == Some(3_000_000))
+    );
+
+    server.verify().await;
+}
+
+// =========================================================================
+// Filter actions
+// =========================================================================
+
+#[tokio::test]
+async fn process_feed_items_applies_filter_actions() {
+    use crate::config::RssFilterAction;
+    use crate::downloader::test_helpers::SAMPLE_NZB;
+    use crate::types::{Event, Status};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let (db, downloader) = create_test_setup().await;
+    let server = MockServer::start().await;
+    for (nzb_path, expected_requests) in [
+        ("/cam.nzb", 0),
+        ("/notify.nzb", 0),
+        ("/grab.nzb", 1),
+        ("/hold.nzb", 1),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(nzb_path))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(SAMPLE_NZB))
+            .expect(expected_requests)
+            .mount(&server)
+            .await;
+    }
+
+    let feed_id = db
+        .insert_rss_feed(crate::db::InsertRssFeedParams {
+            name: "Actions",
+            url: "http://example.com/feed.rss",
+            check_interval_secs: 900,
+            category: Some("tv"),
+            auto_download: true,
+            priority: 0,
+            enabled: true,
+            auth: None,
+            preference: 0,
+        })
+        .await
+        .unwrap();
+
+    let filter = |name: &str, include: &str, action: RssFilterAction| RssFilter {
+        name: name.to_string(),
+        include: vec![include.to_string()],
+        exclude: vec![],
+        min_size: None,
+        max_size: None,
+        max_age: None,
+        action,
+        category: None,
+        priority: None,
+    };
+    let feed_config = RssFeedConfig {
+        url: "http://example.com/feed.rss".to_string(),
+        check_interval: Duration::from_secs(900),
+        category: Some("tv".to_string()),
+        filters: vec![
+            filter("Grab", "Grab", RssFilterAction::Download),
+            filter("No CAM", "CAM", RssFilterAction::Reject),
+            filter("Alerts", "Notify", RssFilterAction::Notify),
+            RssFilter {
+                category: Some("tv-rss".to_string()),
+                priority: Some(Priority::High),
+                ..filter("Hold", "Hold", RssFilterAction::DownloadPaused)
+            },
+        ],
+        auto_download: true,
+        priority: Priority::Normal,
+        enabled: true,
+        auth: Default::default(),
+        preference: 0,
+    };
+    let item = |guid: &str, title: &str| RssItem {
+        title: title.to_string(),
+        link: None,
+        guid: guid.to_string(),
+        pub_date: Some(Utc::now()),
+        description: None,
+        size: None,
+        nzb_url: Some(format!("{}/{}.nzb", server.uri(), guid)),
+    };
+
+    let mut events = downloader.subscribe();
+    let manager = RssManager::new(db.clone(), downloader.clone(), vec![]).unwrap();
+    let downloaded = manager
+        .process_feed_items(
+            feed_id,
+            &feed_config,
+            vec![
+                item("cam", "Movie.CAM.Grab"),
+                item("notify", "Show.S01.Notify"),
+                item("grab", "Show.S01E01.Grab"),
+                item("hold", "Show.S01E02.Hold"),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(downloaded, 2);
+
+    // A reject filter wins over a matching download filter
+    assert!(!db.is_rss_item_seen(feed_id, "cam").await.unwrap());
+    assert!(db.is_rss_item_seen(feed_id, "notify").await.unwrap());
+
+    let mut matched = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let Event::RssItemMatched { title, filter, .. } = event {
+            matched.push((title, filter));
+        }
+    }
+    assert_eq!(
+        matched,
+        vec![("Show.S01.Notify".to_string(), Some("Alerts".to_string()))]
+    );
+
+    let downloads = downloader.db.list_downloads().await.unwrap();
+    assert_eq!(downloads.len(), 2);
+    let grab = downloads
+        .iter()
+        .find(|d| d.category.as_deref() == Some("tv"))
+        .expect("grab should use the feed's category");
+    assert_eq!(grab.priority, Priority::Normal as i32);
+    assert_eq!(grab.status, Status::Queued.to_i32());
+    let hold = downloads
+        .iter()
+        .find(|d| d.category.as_deref() == Some("tv-rss"))
+        .expect("hold should use the filter's category");
+    assert_eq!(hold.priority, Priority::High as i32);
+    assert_eq!(hold.status, Status::Paused.to_i32());
+
+    server.verify().await;
+}
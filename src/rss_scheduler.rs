@@ -104,7 +104,7 @@ impl RssScheduler {
             }
 
             // Read feeds from the database so API-added/modified feeds are picked up
-            let db_feeds = match self.downloader.db.get_all_rss_feeds().await {
+            let mut db_feeds = match self.downloader.db.get_all_rss_feeds().await {
                 Ok(f) => f,
                 Err(e) => {
                     error!(error = %e, "Failed to load RSS feeds from database, falling back to config");
@@ -124,6 +124,10 @@ impl RssScheduler {
 
             let now = SystemTime::now();
 
+            // Preferred feeds first, so a release listed on several feeds due together is
+            // grabbed from the most preferred one (stable: ties keep ID order)
+            db_feeds.sort_by_key(|feed| feed.preference);
+
             // Process database feeds (these have proper IDs)
             for feed_row in &db_feeds {
                 if feed_row.enabled == 0 {
@@ -139,6 +143,12 @@ impl RssScheduler {
                     auto_download: feed_row.auto_download != 0,
                     priority: crate::types::Priority::from_i32(feed_row.priority),
                     enabled: true,
+                    auth: feed_row
+                        .auth
+                        .as_deref()
+                        .and_then(|s| serde_json::from_str(s).ok())
+                        .unwrap_or_default(),
+                    preference: feed_row.preference,
                 };
 
                 // Check if it's time to check this feed
@@ -187,6 +197,9 @@ impl RssScheduler {
                         min_size: row.min_size.map(|s| s as u64),
                         max_size: row.max_size.map(|s| s as u64),
                         max_age: row.max_age_secs.map(|s| Duration::from_secs(s as u64)),
+                        action: crate::config::RssFilterAction::from_i32(row.action),
+                        category: row.category,
+                        priority: row.priority.map(crate::types::Priority::from_i32),
                     })
                     .collect();
 
@@ -1,16 +1,17 @@
 //! Time-based scheduler for applying speed limits and pause/resume based on schedules.
 //!
 //! The scheduler allows users to define rules that automatically adjust download behavior
-//! based on the time of day and day of week. Common use cases include:
+//! based on the time of day and day of week, or during one-off date ranges. Common use cases
+//! include:
 //! - Limiting speed during work hours to preserve bandwidth
 //! - Running unlimited during off-peak hours (nights/weekends)
-//! - Pausing downloads during specific time windows
+//! - Pausing downloads during specific time windows, such as a holiday
 //!
 //! # Example
 //!
 //! ```rust
-//! use usenet_dl::scheduler::{ScheduleRule, ScheduleAction, RuleId, Weekday};
-//! use chrono::NaiveTime;
+//! use usenet_dl::scheduler::{DateRange, ScheduleRule, ScheduleAction, RuleId, Weekday};
+//! use chrono::{NaiveDate, NaiveTime};
 //!
 //! // Unlimited at night (midnight to 6 AM)
 //! let night_rule = ScheduleRule {
@@ -21,6 +22,7 @@
 //!     end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
 //!     action: ScheduleAction::Unlimited,
 //!     enabled: true,
+//!     dates: None,
 //! };
 //!
 //! // Limited during work hours (weekdays only)
@@ -33,10 +35,26 @@
 //!     end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
 //!     action: ScheduleAction::SpeedLimit(1_000_000),  // 1 MB/s
 //!     enabled: true,
+//!     dates: None,
+//! };
+//!
+//! // Paused over the holidays, from 18:00 on Dec 24 until 08:00 on Dec 26
+//! let holiday_rule = ScheduleRule {
+//!     id: RuleId::new(3),
+//!     name: "Holidays".into(),
+//!     days: vec![],  // Ignored for one-off rules
+//!     start_time: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+//!     end_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+//!     action: ScheduleAction::Pause,
+//!     enabled: true,
+//!     dates: Some(DateRange {
+//!         start: NaiveDate::from_ymd_opt(2024, 12, 24).unwrap(),
+//!         end: NaiveDate::from_ymd_opt(2024, 12, 26).unwrap(),
+//!     }),
 //! };
 //! ```
 
-use chrono::{Datelike, NaiveTime};
+use chrono::{Datelike, NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
@@ -102,7 +120,7 @@ pub struct ScheduleRule {
     /// Human-readable name for this rule
     pub name: String,
 
-    /// Days this rule applies (empty = all days)
+    /// Days this rule applies (empty = all days; ignored for one-off rules)
     pub days: Vec<Weekday>,
 
     /// Start time (HH:MM:SS, 24-hour format)
@@ -118,6 +136,21 @@ pub struct ScheduleRule {
 
     /// Whether this rule is currently active
     pub enabled: bool,
+
+    /// Date range of a one-off rule (`None` for a rule that repeats weekly)
+    #[serde(default)]
+    pub dates: Option<DateRange>,
+}
+
+/// Dates of a one-off rule
+///
+/// The rule runs once, from its `start_time` on `start` until its `end_time` on `end`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DateRange {
+    /// Day the rule starts
+    pub start: NaiveDate,
+    /// Day the rule ends
+    pub end: NaiveDate,
 }
 
 /// Action to take when a schedule rule is active
@@ -213,6 +246,7 @@ impl Scheduler {
     ///         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
     ///         action: ScheduleAction::SpeedLimit(1_000_000),
     ///         enabled: true,
+    ///         dates: None,
     ///     },
     /// ];
     ///
@@ -262,16 +296,29 @@ impl Scheduler {
         }
     }
 
-    /// Get the current effective action based on the current time
+    /// Get the effective action right now, with rules read in `timezone`
+    ///
+    /// `None` evaluates rules in the host's local time zone.
+    pub fn current_action_in(&self, timezone: Option<chrono_tz::Tz>) -> Option<ScheduleAction> {
+        match timezone {
+            Some(tz) => self.get_current_action(chrono::Utc::now().with_timezone(&tz)),
+            None => self.get_current_action(chrono::Local::now()),
+        }
+    }
+
+    /// Get the effective action at `now`
     ///
-    /// Evaluates all rules and returns the action of the first matching rule.
+    /// Rule times are wall-clock times in `now`'s time zone. Evaluates all rules and returns the action of the first matching rule.
     /// Returns None if no rules match the current time.
     ///
     /// Rules are evaluated in order:
     /// 1. Rule must be enabled
-    /// 2. Rule must match the current day (empty days = all days)
-    /// 3. Current time must be >= start_time and < end_time
-    /// 4. First matching rule wins
+    /// 2. A one-off rule matches from `start_time` on its first day until `end_time` on
+    ///    its last day
+    /// 3. A weekly rule must match the current day (empty days = all days), and the
+    ///    current time must be >= start_time and < end_time
+    /// 4. A matching one-off rule wins over weekly rules; otherwise the first matching
+    ///    rule wins
     ///
     /// # Example
     ///
@@ -289,6 +336,7 @@ impl Scheduler {
     ///         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
     ///         action: ScheduleAction::SpeedLimit(1_000_000),
     ///         enabled: true,
+    ///         dates: None,
     ///     },
     /// ];
     ///
@@ -297,30 +345,36 @@ impl Scheduler {
     ///     // Apply the action
     /// }
     /// ```
-    pub fn get_current_action(
+    pub fn get_current_action<Tz: chrono::TimeZone>(
         &self,
-        now: chrono::DateTime<chrono::Local>,
+        now: chrono::DateTime<Tz>,
     ) -> Option<ScheduleAction> {
         let weekday = Weekday::from_chrono(now.weekday());
+        let local = now.naive_local();
         let time = now.time();
 
-        self.rules
-            .iter()
+        let enabled = || self.rules.iter().filter(|r| r.enabled);
+        enabled()
             .find(|r| {
-                if !r.enabled {
-                    return false;
-                }
-                if !r.days.is_empty() && !r.days.contains(&weekday) {
-                    return false;
-                }
-                // Handle midnight-crossing rules (e.g., 22:00 to 06:00)
-                if r.start_time <= r.end_time {
-                    // Normal case: start < end (same day)
-                    time >= r.start_time && time < r.end_time
-                } else {
-                    // Midnight crossing: start > end (e.g., 22:00 to 06:00)
-                    time >= r.start_time || time < r.end_time
-                }
+                r.dates.is_some_and(|dates| {
+                    local >= dates.start.and_time(r.start_time)
+                        && local < dates.end.and_time(r.end_time)
+                })
+            })
+            .or_else(|| {
+                enabled().filter(|r| r.dates.is_none()).find(|r| {
+                    if !r.days.is_empty() && !r.days.contains(&weekday) {
+                        return false;
+                    }
+                    // Handle midnight-crossing rules (e.g., 22:00 to 06:00)
+                    if r.start_time <= r.end_time {
+                        // Normal case: start < end (same day)
+                        time >= r.start_time && time < r.end_time
+                    } else {
+                        // Midnight crossing: start > end (e.g., 22:00 to 06:00)
+                        time >= r.start_time || time < r.end_time
+                    }
+                })
             })
             .map(|r| r.action.clone())
     }
@@ -11,6 +11,7 @@ fn test_schedule_rule_creation() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(1_000_000),
         enabled: true,
+        dates: None,
     };
 
     assert_eq!(rule.id, RuleId(1));
@@ -63,6 +64,7 @@ fn test_schedule_rule_serialization() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(2_000_000),
         enabled: true,
+        dates: None,
     };
 
     let json = serde_json::to_string(&rule).unwrap();
@@ -120,6 +122,7 @@ fn test_schedule_rule_with_all_weekdays() {
         end_time: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(5_000_000),
         enabled: true,
+        dates: None,
     };
 
     assert_eq!(rule.days.len(), 5);
@@ -138,6 +141,7 @@ fn test_scheduler_creation() {
         end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }];
 
     let scheduler = Scheduler::new(rules.clone());
@@ -164,6 +168,7 @@ fn test_scheduler_add_rule() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(1_000_000),
         enabled: true,
+        dates: None,
     };
 
     scheduler.add_rule(rule.clone());
@@ -183,6 +188,7 @@ fn test_scheduler_remove_rule() {
             end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
             action: ScheduleAction::Unlimited,
             enabled: true,
+            dates: None,
         },
         ScheduleRule {
             id: RuleId(2),
@@ -192,6 +198,7 @@ fn test_scheduler_remove_rule() {
             end_time: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
             action: ScheduleAction::Pause,
             enabled: true,
+            dates: None,
         },
     ];
 
@@ -220,6 +227,7 @@ fn test_scheduler_update_rule() {
         end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }];
 
     let mut scheduler = Scheduler::new(rules);
@@ -234,6 +242,7 @@ fn test_scheduler_update_rule() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(5_000_000),
         enabled: false,
+        dates: None,
     };
 
     let success = scheduler.update_rule(updated_rule);
@@ -251,6 +260,7 @@ fn test_scheduler_update_rule() {
         end_time: NaiveTime::from_hms_opt(1, 0, 0).unwrap(),
         action: ScheduleAction::Pause,
         enabled: true,
+        dates: None,
     };
 
     let failed = scheduler.update_rule(non_existent);
@@ -268,6 +278,7 @@ fn test_scheduler_set_rules() {
         end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }];
 
     let mut scheduler = Scheduler::new(initial_rules);
@@ -283,6 +294,7 @@ fn test_scheduler_set_rules() {
             end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
             action: ScheduleAction::SpeedLimit(1_000_000),
             enabled: true,
+            dates: None,
         },
         ScheduleRule {
             id: RuleId(3),
@@ -292,6 +304,7 @@ fn test_scheduler_set_rules() {
             end_time: NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
             action: ScheduleAction::Unlimited,
             enabled: true,
+            dates: None,
         },
     ];
 
@@ -314,6 +327,7 @@ fn test_scheduler_multiple_operations() {
         end_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     });
 
     scheduler.add_rule(ScheduleRule {
@@ -324,6 +338,7 @@ fn test_scheduler_multiple_operations() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(2_000_000),
         enabled: true,
+        dates: None,
     });
 
     scheduler.add_rule(ScheduleRule {
@@ -334,6 +349,7 @@ fn test_scheduler_multiple_operations() {
         end_time: NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
         action: ScheduleAction::Pause,
         enabled: true,
+        dates: None,
     });
 
     assert_eq!(scheduler.rules().len(), 3);
@@ -353,6 +369,7 @@ fn test_scheduler_multiple_operations() {
         end_time: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: false,
+        dates: None,
     });
 
     assert_eq!(scheduler.rules()[1].name, "Updated Rule 3");
@@ -381,6 +398,7 @@ fn test_get_current_action_disabled_rule() {
         end_time: NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
         action: ScheduleAction::Pause,
         enabled: false, // Disabled
+        dates: None,
     }];
 
     let scheduler = Scheduler::new(rules);
@@ -411,6 +429,7 @@ fn test_get_current_action_time_match() {
         end_time: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(1_000_000),
         enabled: true,
+        dates: None,
     }];
 
     let scheduler = Scheduler::new(rules);
@@ -441,6 +460,7 @@ fn test_get_current_action_time_no_match() {
         end_time: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(1_000_000),
         enabled: true,
+        dates: None,
     }];
 
     let scheduler = Scheduler::new(rules);
@@ -472,6 +492,7 @@ fn test_get_current_action_day_match() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }];
 
     let scheduler = Scheduler::new(rules);
@@ -509,6 +530,7 @@ fn test_get_current_action_day_no_match() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::Pause,
         enabled: true,
+        dates: None,
     }];
 
     let scheduler = Scheduler::new(rules);
@@ -538,6 +560,7 @@ fn test_get_current_action_empty_days_matches_all() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(5_000_000),
         enabled: true,
+        dates: None,
     }];
 
     let scheduler = Scheduler::new(rules);
@@ -569,6 +592,7 @@ fn test_get_current_action_first_match_wins() {
             end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
             action: ScheduleAction::SpeedLimit(1_000_000),
             enabled: true,
+            dates: None,
         },
         ScheduleRule {
             id: RuleId(2),
@@ -578,6 +602,7 @@ fn test_get_current_action_first_match_wins() {
             end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
             action: ScheduleAction::Unlimited,
             enabled: true,
+            dates: None,
         },
     ];
 
@@ -610,6 +635,7 @@ fn test_get_current_action_boundary_start_inclusive() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::Pause,
         enabled: true,
+        dates: None,
     }];
 
     let scheduler = Scheduler::new(rules);
@@ -641,6 +667,7 @@ fn test_get_current_action_boundary_end_exclusive() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(1_000_000),
         enabled: true,
+        dates: None,
     }];
 
     let scheduler = Scheduler::new(rules);
@@ -671,6 +698,7 @@ fn test_get_current_action_all_action_types() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(2_000_000),
         enabled: true,
+        dates: None,
     }]);
     assert_eq!(
         scheduler.get_current_action(now),
@@ -686,6 +714,7 @@ fn test_get_current_action_all_action_types() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }]);
     assert_eq!(
         scheduler.get_current_action(now),
@@ -701,6 +730,7 @@ fn test_get_current_action_all_action_types() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::Pause,
         enabled: true,
+        dates: None,
     }]);
     assert_eq!(
         scheduler.get_current_action(now),
@@ -732,6 +762,7 @@ fn test_get_current_action_complex_scenario() {
             end_time: NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
             action: ScheduleAction::Pause,
             enabled: false,
+            dates: None,
         },
         // Rule 2: Wrong time window, should be ignored
         ScheduleRule {
@@ -742,6 +773,7 @@ fn test_get_current_action_complex_scenario() {
             end_time: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
             action: ScheduleAction::Unlimited,
             enabled: true,
+            dates: None,
         },
         // Rule 3: Wrong day, should be ignored
         ScheduleRule {
@@ -752,6 +784,7 @@ fn test_get_current_action_complex_scenario() {
             end_time: NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
             action: ScheduleAction::SpeedLimit(100),
             enabled: true,
+            dates: None,
         },
         // Rule 4: Should match (all days, correct time)
         ScheduleRule {
@@ -762,6 +795,7 @@ fn test_get_current_action_complex_scenario() {
             end_time: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
             action: ScheduleAction::SpeedLimit(3_000_000),
             enabled: true,
+            dates: None,
         },
         // Rule 5: Also matches but should not be returned (first match wins)
         ScheduleRule {
@@ -772,6 +806,7 @@ fn test_get_current_action_complex_scenario() {
             end_time: NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
             action: ScheduleAction::Pause,
             enabled: true,
+            dates: None,
         },
     ];
 
@@ -800,6 +835,7 @@ fn test_time_transition_entering_rule_window() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(1_000_000),
         enabled: true,
+        dates: None,
     }];
     let scheduler = Scheduler::new(rules);
 
@@ -852,6 +888,7 @@ fn test_time_transition_exiting_rule_window() {
         end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
         action: ScheduleAction::SpeedLimit(1_000_000),
         enabled: true,
+        dates: None,
     }];
     let scheduler = Scheduler::new(rules);
 
@@ -903,6 +940,7 @@ fn test_time_transition_between_sequential_rules() {
             end_time: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
             action: ScheduleAction::SpeedLimit(500_000),
             enabled: true,
+            dates: None,
         },
         ScheduleRule {
             id: RuleId(2),
@@ -912,6 +950,7 @@ fn test_time_transition_between_sequential_rules() {
             end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
             action: ScheduleAction::SpeedLimit(2_000_000),
             enabled: true,
+            dates: None,
         },
     ];
     let scheduler = Scheduler::new(rules);
@@ -969,6 +1008,7 @@ fn test_time_transition_one_minute_window() {
         end_time: NaiveTime::from_hms_opt(14, 31, 0).unwrap(),
         action: ScheduleAction::Pause,
         enabled: true,
+        dates: None,
     }];
     let scheduler = Scheduler::new(rules);
 
@@ -1032,6 +1072,7 @@ fn test_time_transition_midnight_boundary_simple() {
         end_time: NaiveTime::from_hms_opt(23, 59, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }];
     let scheduler = Scheduler::new(rules);
 
@@ -1074,6 +1115,7 @@ fn test_overlapping_rules_priority_order() {
             end_time: NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
             action: ScheduleAction::SpeedLimit(1_000_000),
             enabled: true,
+            dates: None,
         },
         // Rule 2: Work hours override (medium priority)
         ScheduleRule {
@@ -1084,6 +1126,7 @@ fn test_overlapping_rules_priority_order() {
             end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
             action: ScheduleAction::SpeedLimit(500_000),
             enabled: true,
+            dates: None,
         },
         // Rule 3: Lunch break (highest priority, most specific)
         ScheduleRule {
@@ -1094,6 +1137,7 @@ fn test_overlapping_rules_priority_order() {
             end_time: NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
             action: ScheduleAction::Unlimited,
             enabled: true,
+            dates: None,
         },
     ];
     let scheduler = Scheduler::new(rules);
@@ -1152,6 +1196,7 @@ fn test_action_type_transitions() {
             end_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
             action: ScheduleAction::SpeedLimit(500_000),
             enabled: true,
+            dates: None,
         },
         ScheduleRule {
             id: RuleId(2),
@@ -1161,6 +1206,7 @@ fn test_action_type_transitions() {
             end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
             action: ScheduleAction::Pause,
             enabled: true,
+            dates: None,
         },
         ScheduleRule {
             id: RuleId(3),
@@ -1170,6 +1216,7 @@ fn test_action_type_transitions() {
             end_time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
             action: ScheduleAction::Unlimited,
             enabled: true,
+            dates: None,
         },
     ];
     let scheduler = Scheduler::new(rules);
@@ -1245,6 +1292,7 @@ fn test_specific_day_vs_all_days_priority() {
             end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
             action: ScheduleAction::SpeedLimit(1_000_000),
             enabled: true,
+            dates: None,
         },
         // Specific Monday rule
         ScheduleRule {
@@ -1255,6 +1303,7 @@ fn test_specific_day_vs_all_days_priority() {
             end_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
             action: ScheduleAction::Unlimited,
             enabled: true,
+            dates: None,
         },
     ];
     let scheduler = Scheduler::new(rules);
@@ -1286,6 +1335,7 @@ fn test_minute_boundary_precision() {
         end_time: NaiveTime::from_hms_opt(11, 30, 0).unwrap(),
         action: ScheduleAction::Pause,
         enabled: true,
+        dates: None,
     }];
     let scheduler = Scheduler::new(rules);
 
@@ -1379,6 +1429,7 @@ fn test_midnight_crossing_rule_matches_before_midnight() {
         end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }];
     let scheduler = Scheduler::new(rules);
 
@@ -1409,6 +1460,7 @@ fn test_midnight_crossing_rule_matches_after_midnight() {
         end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }];
     let scheduler = Scheduler::new(rules);
 
@@ -1439,6 +1491,7 @@ fn test_midnight_crossing_rule_does_not_match_daytime() {
         end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }];
     let scheduler = Scheduler::new(rules);
 
@@ -1468,6 +1521,7 @@ fn test_midnight_crossing_rule_boundary_start_inclusive() {
         end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }];
     let scheduler = Scheduler::new(rules);
 
@@ -1498,6 +1552,7 @@ fn test_midnight_crossing_rule_boundary_end_exclusive() {
         end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
         action: ScheduleAction::Unlimited,
         enabled: true,
+        dates: None,
     }];
     let scheduler = Scheduler::new(rules);
 
@@ -1514,3 +1569,126 @@ fn test_midnight_crossing_rule_boundary_end_exclusive() {
         "exactly 06:00 should NOT match a 22:00→06:00 rule (end is exclusive)"
     );
 }
+
+fn holiday_rule(enabled: bool) -> ScheduleRule {
+    ScheduleRule {
+        id: RuleId(2),
+        name: "Holidays".into(),
+        days: vec![Weekday::Monday],
+        start_time: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+        end_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+        action: ScheduleAction::Pause,
+        enabled,
+        dates: Some(DateRange {
+            start: NaiveDate::from_ymd_opt(2024, 12, 24).unwrap(),
+            end: NaiveDate::from_ymd_opt(2024, 12, 26).unwrap(),
+        }),
+    }
+}
+
+fn local_at(day: u32, hour: u32, minute: u32) -> chrono::DateTime<chrono::Local> {
+    use chrono::TimeZone;
+    chrono::Local
+        .with_ymd_and_hms(2024, 12, day, hour, minute, 0)
+        .single()
+        .unwrap()
+}
+
+#[test]
+fn test_one_off_rule_spans_its_date_range() {
+    let scheduler = Scheduler::new(vec![holiday_rule(true)]);
+
+    // Days are ignored: Dec 24-26 2024 is Tuesday to Thursday
+    for (day, hour, minute, active) in [
+        (24, 17, 59, false),
+        (24, 18, 0, true),
+        (25, 3, 0, true),
+        (25, 12, 0, true),
+        (26, 7, 59, true),
+        (26, 8, 0, false),
+        (27, 19, 0, false),
+    ] {
+        assert_eq!(
+            scheduler.get_current_action(local_at(day, hour, minute)),
+            active.then_some(ScheduleAction::Pause),
+            "Dec {} {:02}:{:02}",
+            day,
+            hour,
+            minute
+        );
+    }
+}
+
+#[test]
+fn test_one_off_rule_wins_over_earlier_weekly_rule() {
+    let weekly = ScheduleRule {
+        id: RuleId(1),
+        name: "Evenings".into(),
+        days: vec![],
+        start_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        end_time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+        action: ScheduleAction::SpeedLimit(1_000_000),
+        enabled: true,
+        dates: None,
+    };
+
+    let scheduler = Scheduler::new(vec![weekly.clone(), holiday_rule(true)]);
+    assert_eq!(
+        scheduler.get_current_action(local_at(25, 20, 0)),
+        Some(ScheduleAction::Pause)
+    );
+    assert_eq!(
+        scheduler.get_current_action(local_at(27, 20, 0)),
+        Some(ScheduleAction::SpeedLimit(1_000_000))
+    );
+
+    let scheduler = Scheduler::new(vec![weekly, holiday_rule(false)]);
+    assert_eq!(
+        scheduler.get_current_action(local_at(25, 20, 0)),
+        Some(ScheduleAction::SpeedLimit(1_000_000)),
+        "a disabled one-off rule should not match"
+    );
+}
+
+#[test]
+fn test_one_off_rule_serialization() {
+    let json = serde_json::to_value(holiday_rule(true)).unwrap();
+    assert_eq!(
+        json["dates"],
+        serde_json::json!({"start": "2024-12-24", "end": "2024-12-26"})
+    );
+
+    let parsed: ScheduleRule = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, holiday_rule(true));
+}
+
+#[test]
+fn test_rules_evaluate_in_the_given_time_zone() {
+    use chrono::TimeZone;
+
+    let scheduler = Scheduler::new(vec![ScheduleRule {
+        id: RuleId(1),
+        name: "Evening".into(),
+        days: vec![Weekday::Saturday],
+        start_time: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+        end_time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+        action: ScheduleAction::Pause,
+        enabled: true,
+        dates: None,
+    }]);
+
+    // Saturday 2024-06-01 20:30 UTC is 22:30 in Amsterdam (CEST) and 16:30 in New York
+    let instant = chrono::Utc
+        .with_ymd_and_hms(2024, 6, 1, 20, 30, 0)
+        .single()
+        .unwrap();
+    assert_eq!(scheduler.get_current_action(instant), None);
+    assert_eq!(
+        scheduler.get_current_action(instant.with_timezone(&chrono_tz::Europe::Amsterdam)),
+        Some(ScheduleAction::Pause)
+    );
+    assert_eq!(
+        scheduler.get_current_action(instant.with_timezone(&chrono_tz::America::New_York)),
+        None
+    );
+}
@@ -1,7 +1,8 @@
 //! Scheduler task execution for time-based automation
 //!
 //! This module provides the background task that evaluates schedule rules and applies
-//! actions (speed limits, pauses) based on the current time and day of week.
+//! actions (speed limits, pauses) based on the current time and day of week, in the time
+//! zone set by `scheduler.timezone` (the host's local time zone by default).
 //!
 //! # Features
 //!
@@ -37,7 +38,6 @@ use crate::{
     UsenetDownloader,
     scheduler::{ScheduleAction, Scheduler},
 };
-use chrono::Local;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use tokio::time::{Duration, sleep};
@@ -98,11 +98,10 @@ impl SchedulerTask {
                 break;
             }
 
-            // Get current time
-            let now = Local::now();
-
-            // Evaluate schedule rules
-            let current_action = self.scheduler.get_current_action(now);
+            // Evaluate schedule rules at the current time in the configured time zone
+            let current_action = self
+                .scheduler
+                .current_action_in(self.downloader.config.scheduler.tz());
 
             // Apply action if it changed
             if current_action != last_action {
@@ -145,8 +144,12 @@ impl SchedulerTask {
             }
             ScheduleAction::Pause => {
                 info!("Applying scheduled pause");
-                // Pause all downloads via the queue
-                if let Err(e) = self.downloader.pause_all().await {
+                // Stop downloading; already downloaded jobs keep post-processing
+                if let Err(e) = self
+                    .downloader
+                    .pause_queue(crate::types::PauseScope::Download)
+                    .await
+                {
                     tracing::warn!(error = %e, "Failed to pause downloads for scheduled action");
                 }
             }
@@ -172,7 +175,7 @@ impl SchedulerTask {
 mod tests {
     use super::*;
     use crate::scheduler::{ScheduleRule, Weekday};
-    use chrono::{Datelike, NaiveTime, Timelike};
+    use chrono::{Datelike, Local, NaiveTime, Timelike};
 
     async fn create_test_downloader() -> (UsenetDownloader, tempfile::TempDir) {
         crate::downloader::test_helpers::create_test_downloader().await
@@ -227,6 +230,7 @@ mod tests {
             end_time,
             action: ScheduleAction::SpeedLimit(1_000_000), // 1 MB/s
             enabled: true,
+            dates: None,
         };
 
         let scheduler = Scheduler::new(vec![rule]);
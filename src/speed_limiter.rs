@@ -1,44 +1,78 @@
-//! Speed limiting using token bucket algorithm
+//! Speed limiting using a hierarchical leaky bucket
 //!
 //! The SpeedLimiter provides global bandwidth limiting across all concurrent downloads
-//! using an efficient lock-free token bucket implementation.
+//! using a lock-free leaky bucket: each bucket is a single timestamp, so there is no
+//! periodic refill to drift or lose fractional bytes at high rates.
+//!
+//! Limiters can be chained with [`SpeedLimiter::child`] to share a hierarchical budget:
+//! a per-download limiter is a child of the global limiter, so bytes must fit both
+//! budgets. Per-server limiters are separate buckets applied once the server is known.
 
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
+/// Longest transfer time covered by a single sub-allocation
+///
+/// Large acquisitions are admitted in slices of at most this much time at the current
+/// rate, so concurrent connections interleave instead of taking turns draining the
+/// whole bucket, and rate changes reach waiting callers within one slice.
+const SLICE: Duration = Duration::from_millis(5);
+
+/// Longest single sleep while waiting for a slice, so rate changes are noticed promptly
+const MAX_WAIT: Duration = Duration::from_millis(100);
+
+/// Nanoseconds per second, for converting between bytes and transfer time
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
 /// Global speed limiter shared across all downloads
 ///
-/// Uses a token bucket algorithm for efficient, lock-free bandwidth limiting.
-/// All concurrent downloads share the same bucket, naturally distributing
-/// bandwidth based on demand.
+/// Uses a leaky bucket (GCRA) for lock-free bandwidth limiting. All concurrent
+/// downloads share the same bucket, naturally distributing bandwidth based on demand.
 ///
 /// # Algorithm
 ///
-/// - Tokens represent bytes that can be transferred
-/// - Tokens refill at a constant rate (limit_bps)
-/// - Downloads acquire tokens before transferring data
-/// - If insufficient tokens, download waits until refill
+/// - The bucket tracks the time at which everything admitted so far has drained
+///   at `limit_bps` (the theoretical arrival time)
+/// - Admitting bytes pushes that time forward by their transfer time
+/// - A caller waits until the drain time is no more than one bucket (the burst size)
+///   ahead of now, sleeping until that exact deadline rather than polling
+/// - Acquisitions are split into sub-allocations of at most [`SLICE`] so concurrent
+///   connections get interleaved slots
 ///
 /// # Implementation
 ///
-/// Uses AtomicU64 for lock-free token tracking:
+/// Uses AtomicU64 for lock-free state:
 /// - `limit_bps`: Speed limit in bytes per second (0 = unlimited)
-/// - `tokens`: Available tokens (bytes that can be transferred now)
-/// - `last_refill`: Timestamp of last token refill (nanoseconds since epoch)
+/// - `burst_bytes`: Bucket capacity in bytes (0 = one second's worth of `limit_bps`)
+/// - `drained_at`: When the bucket will be empty (nanoseconds since an arbitrary epoch)
+/// - `generation`: Bumped on every rate or burst change
+/// - `held`: Set while transfers are held back entirely (see [`SpeedLimiter::set_held`])
+///
+/// Rate and burst can be changed at any time. The bucket's fill level carries over to
+/// the new rate, and waiting callers notice the change within 100ms, so changes apply
+/// without interrupting transfers.
 #[derive(Clone)]
 pub struct SpeedLimiter {
     /// Speed limit in bytes per second (0 = unlimited)
     limit_bps: Arc<AtomicU64>,
-    /// Available tokens (current bucket capacity in bytes)
-    tokens: Arc<AtomicU64>,
-    /// Last refill timestamp (nanoseconds since arbitrary epoch)
-    last_refill: Arc<AtomicU64>,
+    /// Bucket capacity in bytes (0 = same as limit_bps)
+    burst_bytes: Arc<AtomicU64>,
+    /// Time at which everything admitted so far has drained (nanoseconds since arbitrary epoch)
+    drained_at: Arc<AtomicU64>,
+    /// Incremented whenever the rate or burst changes
+    generation: Arc<AtomicU64>,
+    /// Whether acquisitions wait until the limiter is released
+    held: Arc<AtomicBool>,
+    /// Enclosing budget that must also admit every acquired byte
+    parent: Option<Box<SpeedLimiter>>,
 }
 
 impl SpeedLimiter {
     /// Create a new SpeedLimiter with the specified limit
     ///
+    /// The bucket starts full.
+    ///
     /// # Arguments
     ///
     /// * `limit_bps` - Speed limit in bytes per second (None = unlimited)
@@ -56,21 +90,44 @@ impl SpeedLimiter {
     /// ```
     #[must_use]
     pub fn new(limit_bps: Option<u64>) -> Self {
-        let limit = limit_bps.unwrap_or(0);
-        let now = Self::now_nanos();
+        Self {
+            limit_bps: Arc::new(AtomicU64::new(limit_bps.unwrap_or(0))),
+            burst_bytes: Arc::new(AtomicU64::new(0)),
+            drained_at: Arc::new(AtomicU64::new(Self::now_nanos())),
+            generation: Arc::new(AtomicU64::new(0)),
+            held: Arc::new(AtomicBool::new(false)),
+            parent: None,
+        }
+    }
 
+    /// Create a limiter nested inside this one
+    ///
+    /// Bytes acquired through the child are charged to both the child's own bucket
+    /// and this limiter, so the child can never exceed either limit. Changes made to
+    /// this limiter later are seen by the child.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usenet_dl::speed_limiter::SpeedLimiter;
+    ///
+    /// let global = SpeedLimiter::new(Some(10_000_000));
+    /// let per_download = global.child(Some(2_000_000));
+    /// assert_eq!(per_download.get_limit(), Some(2_000_000));
+    /// ```
+    #[must_use]
+    pub fn child(&self, limit_bps: Option<u64>) -> Self {
         Self {
-            limit_bps: Arc::new(AtomicU64::new(limit)),
-            tokens: Arc::new(AtomicU64::new(limit)),
-            last_refill: Arc::new(AtomicU64::new(now)),
+            parent: Some(Box::new(self.clone())),
+            ..Self::new(limit_bps)
         }
     }
 
     /// Set a new speed limit
     ///
-    /// This takes effect immediately. If increasing the limit, tokens are
-    /// refilled to the new capacity. If decreasing, excess tokens remain
-    /// until consumed.
+    /// This takes effect immediately. If increasing the limit, the difference is
+    /// added to the bucket right away. If decreasing, the bucket is capped at the
+    /// new capacity and bytes already admitted drain at the new rate.
     ///
     /// # Arguments
     ///
@@ -91,15 +148,106 @@ impl SpeedLimiter {
     /// ```
     pub fn set_limit(&self, limit_bps: Option<u64>) {
         let new_limit = limit_bps.unwrap_or(0);
+        let old_capacity = self.capacity();
         let old_limit = self.limit_bps.swap(new_limit, Ordering::SeqCst);
 
-        // If increasing limit, add extra tokens to bucket
-        if new_limit > old_limit {
-            let extra_tokens = new_limit - old_limit;
-            self.tokens.fetch_add(extra_tokens, Ordering::SeqCst);
+        self.rebase(old_limit, old_capacity, new_limit.saturating_sub(old_limit));
+    }
+
+    /// Set the bucket capacity (burst size) in bytes
+    ///
+    /// `None` uses one second's worth of the rate limit. A smaller burst smooths
+    /// traffic; a larger one lets short transfers run at full speed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usenet_dl::speed_limiter::SpeedLimiter;
+    ///
+    /// let limiter = SpeedLimiter::new(Some(10_000_000));
+    /// limiter.set_burst(Some(1_000_000));
+    /// assert_eq!(limiter.get_burst(), Some(1_000_000));
+    /// ```
+    pub fn set_burst(&self, burst_bytes: Option<u64>) {
+        let old_capacity = self.capacity();
+        self.burst_bytes
+            .store(burst_bytes.unwrap_or(0), Ordering::SeqCst);
+
+        self.rebase(self.limit_bps.load(Ordering::SeqCst), old_capacity, 0);
+    }
+
+    /// Get the configured burst size (None = one second of the rate limit)
+    pub fn get_burst(&self) -> Option<u64> {
+        let burst = self.burst_bytes.load(Ordering::Relaxed);
+        if burst == 0 { None } else { Some(burst) }
+    }
+
+    /// Current bucket capacity in bytes
+    fn capacity(&self) -> u64 {
+        match self.burst_bytes.load(Ordering::Relaxed) {
+            0 => self.limit_bps.load(Ordering::Relaxed),
+            burst => burst,
         }
     }
 
+    /// Carry the bucket's fill level over to a new rate or capacity
+    ///
+    /// The level is measured at the old rate and capacity, raised by `extra` bytes,
+    /// capped at the new capacity and converted back into a drain time at the new
+    /// rate. Bytes admitted but not yet drained stay owed, so a lower limit can't be
+    /// dodged by reservations made just before the change.
+    fn rebase(&self, old_rate: u64, old_capacity: u64, extra: u64) {
+        let rate = self.limit_bps.load(Ordering::SeqCst);
+        let capacity = self.capacity();
+
+        // The closure always returns Some, so the update can't fail
+        let _ = self
+            .drained_at
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |drained_at| {
+                let now = Self::now_nanos();
+                if rate == 0 {
+                    return Some(now);
+                }
+
+                // Coming from unlimited, the bucket starts full
+                let level = if old_rate == 0 {
+                    i128::from(capacity)
+                } else {
+                    Self::level(drained_at, now, old_rate, old_capacity)
+                };
+                let level = (level + i128::from(extra)).min(i128::from(capacity));
+
+                let backlog = Self::nanos_for_signed(i128::from(capacity) - level, rate);
+                Some(now.saturating_add(backlog))
+            });
+
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Hold back or release transfers through this limiter
+    ///
+    /// While held, [`acquire`](Self::acquire) admits nothing until the limiter is
+    /// released, whatever the rate; bytes admitted before are unaffected. Waiting
+    /// callers notice a release within 100ms.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usenet_dl::speed_limiter::SpeedLimiter;
+    ///
+    /// let limiter = SpeedLimiter::new(None);
+    /// limiter.set_held(true);
+    /// assert!(limiter.is_held());
+    /// ```
+    pub fn set_held(&self, held: bool) {
+        self.held.store(held, Ordering::SeqCst);
+    }
+
+    /// Whether transfers through this limiter are held back
+    pub fn is_held(&self) -> bool {
+        self.held.load(Ordering::SeqCst)
+    }
+
     /// Get the current speed limit
     ///
     /// Returns None if unlimited, otherwise the limit in bytes per second.
@@ -110,8 +258,8 @@ impl SpeedLimiter {
 
     /// Acquire permission to transfer the specified number of bytes
     ///
-    /// This method blocks until sufficient tokens are available. For unlimited
-    /// speed (limit = 0), this returns immediately.
+    /// This method blocks until the bytes fit this limiter and every parent. For
+    /// unlimited speed (limit = 0 everywhere), this returns immediately.
     ///
     /// # Arguments
     ///
@@ -132,96 +280,130 @@ impl SpeedLimiter {
     /// # }
     /// ```
     pub async fn acquire(&self, bytes: u64) {
-        // Fast path: nothing to acquire
-        if bytes == 0 {
-            return;
-        }
+        let mut remaining = bytes;
 
-        // Fast path: unlimited speed
-        if self.limit_bps.load(Ordering::Relaxed) == 0 {
-            return;
-        }
+        while remaining > 0 {
+            self.wait_while_held().await;
 
-        let mut remaining = bytes;
+            // Re-read the limits for each slice so dynamic changes take effect
+            let Some(slice) = self.slice_bytes() else {
+                // Unlimited at every level — no throttle needed
+                return;
+            };
+            let chunk = remaining.min(slice);
+
+            let mut level = Some(self);
+            while let Some(limiter) = level {
+                limiter.admit(chunk).await;
+                level = limiter.parent.as_deref();
+            }
 
+            remaining -= chunk;
+        }
+    }
+
+    /// Wait until neither this limiter nor any parent is held
+    async fn wait_while_held(&self) {
         loop {
-            // Re-read the limit each iteration so dynamic changes take effect
-            let limit = self.limit_bps.load(Ordering::Relaxed);
-            if limit == 0 {
-                // Limit was removed while we were waiting — no throttle needed
+            let mut level = Some(self);
+            let mut held = false;
+            while let Some(limiter) = level {
+                held |= limiter.is_held();
+                level = limiter.parent.as_deref();
+            }
+            if !held {
                 return;
             }
+            tokio::time::sleep(MAX_WAIT).await;
+        }
+    }
 
-            // Refill tokens based on elapsed time
-            self.refill_tokens();
-
-            // Try to consume available tokens (partial consumption allowed)
-            let current_tokens = self.tokens.load(Ordering::SeqCst);
-            let to_consume = remaining.min(current_tokens);
-
-            if to_consume > 0 {
-                if self
-                    .tokens
-                    .compare_exchange(
-                        current_tokens,
-                        current_tokens - to_consume,
-                        Ordering::SeqCst,
-                        Ordering::SeqCst,
-                    )
-                    .is_ok()
-                {
-                    remaining -= to_consume;
-                    if remaining == 0 {
-                        return;
-                    }
-                }
-                // CAS failed or still have remaining — retry immediately
-                continue;
+    /// Size of one sub-allocation: [`SLICE`] at the slowest limited level
+    ///
+    /// Returns None if this limiter and all its parents are unlimited.
+    fn slice_bytes(&self) -> Option<u64> {
+        let mut slice = None;
+        let mut level = Some(self);
+        while let Some(limiter) = level {
+            let rate = limiter.limit_bps.load(Ordering::Relaxed);
+            if rate > 0 {
+                let bytes = (u128::from(rate) * SLICE.as_nanos() / NANOS_PER_SEC).max(1) as u64;
+                slice = Some(slice.map_or(bytes, |s: u64| s.min(bytes)));
             }
-
-            // No tokens available — wait for refill.
-            // Cap sleep at 100ms so we re-check the limit frequently,
-            // allowing dynamic limit changes to take effect promptly.
-            let wait_ms = (remaining as f64 / limit as f64 * 1000.0) as u64;
-            tokio::time::sleep(Duration::from_millis(wait_ms.clamp(10, 100))).await;
+            level = limiter.parent.as_deref();
         }
+        slice
     }
 
-    /// Refill tokens based on elapsed time since last refill
+    /// Admit `bytes` through this limiter's own bucket, waiting until they fit
     ///
-    /// This is called automatically by acquire(), but can be called manually
-    /// for testing or monitoring purposes.
-    fn refill_tokens(&self) {
-        let limit = self.limit_bps.load(Ordering::Relaxed);
-        if limit == 0 {
-            return; // Unlimited
+    /// The bytes are reserved up front, then the caller sleeps until the bucket has
+    /// drained enough to hold them. If the rate or burst changes while waiting, the
+    /// reservation was carried over to the new rate by the change and counts as
+    /// admitted.
+    async fn admit(&self, bytes: u64) {
+        let rate = self.limit_bps.load(Ordering::SeqCst);
+        if rate == 0 {
+            return;
         }
 
-        let now = Self::now_nanos();
-        let last = self.last_refill.load(Ordering::SeqCst);
-
-        // Calculate elapsed time in seconds
-        let elapsed_nanos = now.saturating_sub(last);
-        let elapsed_secs = elapsed_nanos as f64 / 1_000_000_000.0;
-
-        // Calculate tokens to add (bytes per second * seconds elapsed)
-        let tokens_to_add = (limit as f64 * elapsed_secs) as u64;
-
-        if tokens_to_add > 0 {
-            // Try to update last_refill timestamp atomically
-            if self
-                .last_refill
-                .compare_exchange(last, now, Ordering::SeqCst, Ordering::SeqCst)
-                .is_ok()
-            {
-                // Add tokens, but cap at limit (bucket capacity)
-                let current_tokens = self.tokens.load(Ordering::SeqCst);
-                let new_tokens = (current_tokens + tokens_to_add).min(limit);
-                self.tokens.store(new_tokens, Ordering::SeqCst);
+        let generation = self.generation.load(Ordering::SeqCst);
+        let tolerance = Self::nanos_for(self.capacity(), rate);
+        let cost = Self::nanos_for(bytes, rate);
+
+        let mut due = 0;
+        // The closure always returns Some, so the update can't fail
+        let _ = self
+            .drained_at
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |drained_at| {
+                let drained_at = drained_at.max(Self::now_nanos()).saturating_add(cost);
+                due = drained_at.saturating_sub(tolerance);
+                Some(drained_at)
+            });
+
+        loop {
+            let now = Self::now_nanos();
+            if due <= now || self.generation.load(Ordering::SeqCst) != generation {
+                return;
             }
+            tokio::time::sleep(Duration::from_nanos(due - now).min(MAX_WAIT)).await;
         }
     }
 
+    /// Bytes the bucket can still admit without waiting (negative while callers wait)
+    fn level(drained_at: u64, now: u64, rate: u64, capacity: u64) -> i128 {
+        let backlog = u128::from(drained_at.saturating_sub(now));
+        let owed = backlog * u128::from(rate) / NANOS_PER_SEC;
+        i128::from(capacity) - owed.min(i128::MAX as u128) as i128
+    }
+
+    /// Time to transfer `bytes` at `rate` bytes per second, in nanoseconds (rounded up)
+    fn nanos_for(bytes: u64, rate: u64) -> u64 {
+        let nanos = (u128::from(bytes) * NANOS_PER_SEC).div_ceil(u128::from(rate));
+        nanos.min(u128::from(u64::MAX)) as u64
+    }
+
+    /// [`Self::nanos_for`] for a byte count that may be negative (treated as zero)
+    fn nanos_for_signed(bytes: i128, rate: u64) -> u64 {
+        Self::nanos_for(bytes.clamp(0, i128::from(u64::MAX)) as u64, rate)
+    }
+
+    /// Bytes available right now (0 for unlimited limiters, which keep no bucket)
+    #[cfg(test)]
+    fn available(&self) -> u64 {
+        let rate = self.limit_bps.load(Ordering::SeqCst);
+        if rate == 0 {
+            return 0;
+        }
+        let level = Self::level(
+            self.drained_at.load(Ordering::SeqCst),
+            Self::now_nanos(),
+            rate,
+            self.capacity(),
+        );
+        level.max(0) as u64
+    }
+
     /// Get current monotonic time in nanoseconds
     ///
     /// Uses a monotonic clock that is not affected by system time changes.
@@ -240,30 +422,40 @@ impl SpeedLimiter {
 mod tests {
     use super::*;
 
+    /// Empty the bucket so the next acquire has to wait for the full rate
+    fn drain(limiter: &SpeedLimiter) {
+        let rate = limiter.limit_bps.load(Ordering::SeqCst);
+        let tolerance = SpeedLimiter::nanos_for(limiter.capacity(), rate);
+        limiter
+            .drained_at
+            .store(SpeedLimiter::now_nanos() + tolerance, Ordering::SeqCst);
+    }
+
     #[test]
     fn test_set_limit_increase() {
         let limiter = SpeedLimiter::new(Some(5_000_000)); // 5 MB/s
-        let old_tokens = limiter.tokens.load(Ordering::Relaxed);
+        let old_tokens = limiter.available();
 
         limiter.set_limit(Some(10_000_000)); // 10 MB/s
 
         assert_eq!(limiter.get_limit(), Some(10_000_000));
         // Tokens should increase by 5 MB
-        let new_tokens = limiter.tokens.load(Ordering::Relaxed);
+        let new_tokens = limiter.available();
         assert_eq!(new_tokens, old_tokens + 5_000_000);
     }
 
     #[test]
     fn test_set_limit_decrease() {
         let limiter = SpeedLimiter::new(Some(10_000_000)); // 10 MB/s
-        let old_tokens = limiter.tokens.load(Ordering::Relaxed);
+        let old_tokens = limiter.available();
 
         limiter.set_limit(Some(5_000_000)); // 5 MB/s
 
         assert_eq!(limiter.get_limit(), Some(5_000_000));
-        // Tokens should remain the same (not decreased)
-        let new_tokens = limiter.tokens.load(Ordering::Relaxed);
-        assert_eq!(new_tokens, old_tokens);
+        // Tokens above the new capacity are discarded so the lower rate applies immediately
+        let new_tokens = limiter.available();
+        assert_eq!(old_tokens, 10_000_000);
+        assert_eq!(new_tokens, 5_000_000);
     }
 
     #[tokio::test]
@@ -289,7 +481,7 @@ mod tests {
         }
 
         // Total: 1 MB consumed
-        let remaining = limiter.tokens.load(Ordering::Relaxed);
+        let remaining = limiter.available();
         assert!(
             (8_999_000..=9_001_000).contains(&remaining),
             "expected ~9_000_000 tokens remaining, got {remaining}"
@@ -332,7 +524,7 @@ mod tests {
         );
         // Tokens should also be 0 (no bucket needed for unlimited)
         assert_eq!(
-            limiter.tokens.load(Ordering::Relaxed),
+            limiter.available(),
             0,
             "tokens should be 0 for unlimited limiter (no bucket needed)"
         );
@@ -349,7 +541,7 @@ mod tests {
         );
         // Tokens should be initialized to the limit (full bucket)
         assert_eq!(
-            limiter.tokens.load(Ordering::Relaxed),
+            limiter.available(),
             42_000,
             "initial tokens should equal the limit (full bucket)"
         );
@@ -384,16 +576,15 @@ mod tests {
     async fn test_acquire_zero_bytes_returns_immediately() {
         let limiter = SpeedLimiter::new(Some(100)); // Very low limit: 100 bytes/s
 
-        // Drain all tokens first to ensure the limiter would block on any real acquire
-        limiter.tokens.store(0, Ordering::SeqCst);
+        // Push the theoretical arrival time a full bucket ahead so any real acquire would wait
+        drain(&limiter);
 
         let start = Instant::now();
         limiter.acquire(0).await;
         let elapsed = start.elapsed();
 
-        // 0 bytes should return immediately even with an empty bucket
-        // The loop condition: remaining starts at 0, so the loop body
-        // checks `remaining == 0` and returns immediately.
+        // Zero bytes admit nothing, so the theoretical arrival time never advances and
+        // there is no deadline to sleep until, however far ahead of now it already is
         assert!(
             elapsed < Duration::from_millis(50),
             "acquire(0) should return immediately, took {:?}",
@@ -408,11 +599,7 @@ mod tests {
         let limiter = SpeedLimiter::new(Some(rate_bps));
 
         // Drain the bucket completely
-        limiter.tokens.store(0, Ordering::SeqCst);
-        // Reset the refill timestamp to now so refill calculation is clean
-        limiter
-            .last_refill
-            .store(SpeedLimiter::now_nanos(), Ordering::SeqCst);
+        drain(&limiter);
 
         let bytes_to_acquire = 500_u64; // 500 bytes at 1000 B/s = ~500ms
 
@@ -446,10 +633,7 @@ mod tests {
         let limiter = SpeedLimiter::new(Some(rate_bps));
 
         // Drain bucket so all tasks must wait for refills
-        limiter.tokens.store(0, Ordering::SeqCst);
-        limiter
-            .last_refill
-            .store(SpeedLimiter::now_nanos(), Ordering::SeqCst);
+        drain(&limiter);
 
         let num_tasks = 4;
         let bytes_per_task = 500_u64;
@@ -494,10 +678,7 @@ mod tests {
     async fn test_set_limit_while_acquire_waiting_picks_up_new_limit() {
         // Start with a very slow limit so acquire will block for a long time
         let limiter = SpeedLimiter::new(Some(100)); // 100 B/s
-        limiter.tokens.store(0, Ordering::SeqCst);
-        limiter
-            .last_refill
-            .store(SpeedLimiter::now_nanos(), Ordering::SeqCst);
+        drain(&limiter);
 
         let limiter_for_task = limiter.clone();
 
@@ -534,10 +715,7 @@ mod tests {
     async fn test_set_limit_to_unlimited_unblocks_waiting_acquire() {
         // Start with 1 byte/s — acquiring 1 MB would take ~1 million seconds
         let limiter = SpeedLimiter::new(Some(1));
-        limiter.tokens.store(0, Ordering::SeqCst);
-        limiter
-            .last_refill
-            .store(SpeedLimiter::now_nanos(), Ordering::SeqCst);
+        drain(&limiter);
 
         let limiter_for_task = limiter.clone();
 
@@ -589,4 +767,148 @@ mod tests {
             "clone should reflect limit change made via original"
         );
     }
+
+    #[test]
+    fn test_set_burst_clamps_tokens() {
+        let limiter = SpeedLimiter::new(Some(1_000_000));
+        assert_eq!(limiter.get_burst(), None);
+
+        limiter.set_burst(Some(100_000));
+        assert_eq!(limiter.get_burst(), Some(100_000));
+        assert_eq!(limiter.available(), 100_000);
+
+        // Idle time never fills the bucket beyond the burst size
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(limiter.available(), 100_000);
+
+        limiter.set_burst(None);
+        assert_eq!(limiter.get_burst(), None);
+    }
+
+    #[tokio::test]
+    async fn test_child_charges_parent_budget() {
+        let parent = SpeedLimiter::new(Some(1_000_000));
+        let child = parent.child(None);
+
+        // Unlimited child still draws down the parent bucket
+        child.acquire(400_000).await;
+        assert!(parent.available() <= 600_000 + 50_000);
+
+        // Resizing the parent is visible through the child's parent handle
+        parent.set_limit(Some(2_000_000));
+        assert_eq!(
+            child.parent.as_ref().and_then(|p| p.get_limit()),
+            Some(2_000_000)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_child_limit_applies_below_unlimited_parent() {
+        let parent = SpeedLimiter::new(None);
+        let child = parent.child(Some(1_000_000));
+
+        child.acquire(1_000_000).await;
+
+        let start = Instant::now();
+        child.acquire(500_000).await;
+        let elapsed = start.elapsed();
+
+        assert!(
+            elapsed >= Duration::from_millis(300),
+            "child limit should throttle even when the parent is unlimited, took {:?}",
+            elapsed
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_high_rate_throughput_tracks_limit() {
+        // 100 MB/s with a small burst: the rate where a polling token bucket oscillated
+        let rate_bps = 100_000_000;
+        let limiter = SpeedLimiter::new(Some(rate_bps));
+        limiter.set_burst(Some(1_000_000));
+        drain(&limiter);
+
+        let tasks = 8;
+        let chunks_per_task = 20;
+        let chunk = 500_000_u64;
+        let total_bytes = tasks * chunks_per_task * chunk; // 80 MB
+
+        let start = Instant::now();
+        let handles: Vec<_> = (0..tasks)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move {
+                    for _ in 0..chunks_per_task {
+                        limiter.acquire(chunk).await;
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        let elapsed = start.elapsed();
+
+        // Expected: 80 MB / 100 MB/s = 800ms; admission never runs ahead of the rate
+        let expected = Duration::from_nanos(SpeedLimiter::nanos_for(total_bytes, rate_bps));
+        assert!(
+            elapsed >= expected.mul_f64(0.95),
+            "throughput exceeded the limit: {:?} for {total_bytes} bytes (expected ~{:?})",
+            elapsed,
+            expected
+        );
+        assert!(
+            elapsed <= expected * 3,
+            "throughput fell well short of the limit: {:?} (expected ~{:?})",
+            elapsed,
+            expected
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_large_acquire_does_not_starve_small_one() {
+        let limiter = SpeedLimiter::new(Some(1_000_000)); // 1 MB/s
+        drain(&limiter);
+
+        // One second's worth for the large caller
+        let large = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(1_000_000).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // Sub-allocations interleave, so 10 KB doesn't queue behind the whole megabyte
+        let start = Instant::now();
+        limiter.acquire(10_000).await;
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed < Duration::from_millis(300),
+            "small acquire waited {:?} behind a large one",
+            elapsed
+        );
+        assert!(!large.is_finished());
+
+        large.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_held_limiter_waits_until_released() {
+        let parent = SpeedLimiter::new(None);
+        let child = parent.child(None);
+        parent.set_held(true);
+
+        let waiting = tokio::spawn({
+            let child = child.clone();
+            async move { child.acquire(1_000).await }
+        });
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(!waiting.is_finished(), "a held parent holds its children");
+
+        parent.set_held(false);
+        tokio::time::timeout(Duration::from_millis(500), waiting)
+            .await
+            .expect("release should let the acquire through")
+            .unwrap();
+        assert!(!child.is_held());
+    }
 }
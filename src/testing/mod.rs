@@ -0,0 +1,403 @@
+//! In-process NNTP server for integration tests.
+//!
+//! [`MockNntpServer`] listens on a local port and answers the commands the downloader
+//! sends, serving articles from memory. Articles can be taken away to get
+//! `430 No such article`, login can be made to require credentials, and responses can
+//! be throttled, so download, failover and error flows can be exercised without a real
+//! Usenet account.
+//!
+//! Connections use TLS with a built-in self-signed certificate. Certificate checks are
+//! skipped for the address of a running mock server, and only in builds with this
+//! module; every other server's certificate is verified.
+//!
+//! Available to the crate's own tests, and to other crates with the `testing` feature.
+//!
+//! ```no_run
+//! use usenet_dl::testing::MockNntpServer;
+//! use usenet_dl::{Config, UsenetDownloader};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let server = MockNntpServer::start().await?;
+//! let nzb = server.add_file("movie.mkv", &[0u8; 100_000], 32_000);
+//!
+//! let config = Config {
+//!     servers: vec![server.server_config()],
+//!     ..Default::default()
+//! };
+//! let downloader = UsenetDownloader::new(config).await?;
+//! downloader
+//!     .add_nzb_content(nzb.as_bytes(), "movie", Default::default())
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::ServerConfig;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+/// Self-signed certificate for `localhost` and `127.0.0.1`
+const CERTIFICATE: &str = include_str!("cert.pem");
+const PRIVATE_KEY: &str = include_str!("key.pem");
+
+/// Addresses of running mock servers, whose certificate isn't verified
+static MOCK_ADDRS: Mutex<Vec<SocketAddr>> = Mutex::new(Vec::new());
+
+/// Bytes written between throttling pauses
+const THROTTLE_CHUNK: usize = 16 * 1024;
+
+/// yEnc line length used by [`MockNntpServer::add_file`]
+const YENC_LINE_LENGTH: usize = 128;
+
+/// Articles and behaviour shared by the server's connections
+#[derive(Default)]
+struct MockState {
+    /// Article bodies by message-ID (without angle brackets)
+    articles: HashMap<String, Vec<u8>>,
+    /// Files added with `add_file`, as (filename, message-IDs and segment sizes)
+    files: Vec<(String, Vec<(String, usize)>)>,
+    /// Username and password login requires, if any
+    credentials: Option<(String, String)>,
+    /// Rate article responses are sent at, in bytes per second
+    bytes_per_second: Option<u64>,
+    /// Message-IDs requested with ARTICLE, in order
+    requests: Vec<String>,
+}
+
+/// NNTP server serving articles from memory, for tests
+///
+/// Listens on `127.0.0.1` on a free port until dropped.
+pub struct MockNntpServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockNntpServer {
+    /// Start a server with no articles that accepts any login
+    pub async fn start() -> std::io::Result<Self> {
+        let acceptor = tls_acceptor()?;
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        mock_addrs().push(addr);
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let task = tokio::spawn({
+            let state = Arc::clone(&state);
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let acceptor = acceptor.clone();
+                    let state = Arc::clone(&state);
+                    tokio::spawn(async move {
+                        if let Err(e) = serve(acceptor, stream, state).await {
+                            tracing::debug!(error = %e, "Mock NNTP connection ended");
+                        }
+                    });
+                }
+            }
+        });
+
+        Ok(Self { addr, state, task })
+    }
+
+    /// Address the server listens on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Configuration for connecting to this server, with the credentials it requires
+    pub fn server_config(&self) -> ServerConfig {
+        let credentials = self.state().credentials.clone();
+        ServerConfig {
+            host: self.addr.ip().to_string(),
+            port: self.addr.port(),
+            tls: true,
+            username: credentials.as_ref().map(|(username, _)| username.clone()),
+            password: credentials.map(|(_, password)| password),
+            connections: 2,
+            priority: 0,
+            pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
+        }
+    }
+
+    /// Serve `body` for `message_id` (with or without angle brackets)
+    ///
+    /// The body is sent as given after a `Message-ID` header, with lines dot-stuffed.
+    pub fn add_article(&self, message_id: &str, body: impl Into<Vec<u8>>) {
+        self.state()
+            .articles
+            .insert(bare_message_id(message_id).to_string(), body.into());
+    }
+
+    /// Stop serving `message_id`, so requests for it get `430 No such article`
+    pub fn remove_article(&self, message_id: &str) {
+        self.state().articles.remove(bare_message_id(message_id));
+    }
+
+    /// Serve `data` as a yEnc-encoded file split into `segment_size`-byte articles
+    ///
+    /// Returns an NZB listing every file added so far, for adding to a downloader.
+    /// Message-IDs are `<filename>.<part>@mock`.
+    ///
+    /// # Panics
+    ///
+    /// If `segment_size` is 0.
+    pub fn add_file(&self, filename: &str, data: &[u8], segment_size: usize) -> String {
+        assert!(segment_size > 0, "segment_size must be at least 1");
+        let total_parts = data.len().div_ceil(segment_size).max(1) as u32;
+        let mut segments = Vec::new();
+        let mut state = self.state();
+        for (i, chunk) in data
+            .chunks(segment_size)
+            .chain(data.is_empty().then_some(&[][..]))
+            .enumerate()
+        {
+            let part = i as u32 + 1;
+            let begin = (i * segment_size) as u64 + 1;
+            let end = begin + chunk.len() as u64 - 1;
+            let body = nntp_rs::yenc_encode(
+                chunk,
+                filename,
+                YENC_LINE_LENGTH,
+                Some((part, total_parts, begin, end, data.len() as u64)),
+            )
+            .unwrap_or_default();
+            let message_id = format!("{}.{}@mock", filename, part);
+            segments.push((message_id.clone(), body.len()));
+            state.articles.insert(message_id, body);
+        }
+        state.files.push((filename.to_string(), segments));
+        nzb(&state.files)
+    }
+
+    /// Require this username and password to log in; other logins get `481`
+    pub fn require_auth(&self, username: &str, password: &str) {
+        self.state().credentials = Some((username.to_string(), password.to_string()));
+    }
+
+    /// Send article responses at about `bytes_per_second` (None = as fast as possible)
+    pub fn throttle(&self, bytes_per_second: Option<u64>) {
+        self.state().bytes_per_second = bytes_per_second.filter(|&rate| rate > 0);
+    }
+
+    /// Message-IDs requested with ARTICLE so far, in order and without angle brackets
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for MockNntpServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        mock_addrs().retain(|addr| *addr != self.addr);
+    }
+}
+
+fn mock_addrs() -> std::sync::MutexGuard<'static, Vec<SocketAddr>> {
+    MOCK_ADDRS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Whether `host:port` is a running mock server, whose self-signed certificate is accepted
+pub(crate) fn is_mock_server(host: &str, port: u16) -> bool {
+    mock_addrs()
+        .iter()
+        .any(|addr| addr.port() == port && addr.ip().to_string() == host)
+}
+
+/// TLS acceptor using the built-in certificate
+fn tls_acceptor() -> std::io::Result<TlsAcceptor> {
+    let invalid = |e: &dyn std::fmt::Display| std::io::Error::other(e.to_string());
+    let certificate =
+        CertificateDer::from_pem_slice(CERTIFICATE.as_bytes()).map_err(|e| invalid(&e))?;
+    let key = PrivateKeyDer::from_pem_slice(PRIVATE_KEY.as_bytes()).map_err(|e| invalid(&e))?;
+    let config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| invalid(&e))?
+    .with_no_client_auth()
+    .with_single_cert(vec![certificate], key)
+    .map_err(|e| invalid(&e))?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Answer commands on one connection until the client quits or disconnects
+async fn serve(
+    acceptor: TlsAcceptor,
+    stream: TcpStream,
+    state: Arc<Mutex<MockState>>,
+) -> std::io::Result<()> {
+    let stream = acceptor.accept(stream).await?;
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+    let lock = || state.lock().unwrap_or_else(|e| e.into_inner());
+
+    writer
+        .write_all(b"200 usenet-dl mock server ready\r\n")
+        .await?;
+    let mut username = None;
+    let mut authenticated = lock().credentials.is_none();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(());
+        }
+        let command = line.trim_end();
+        let (verb, argument) = command.split_once(' ').unwrap_or((command, ""));
+        let response: Vec<u8> = match verb.to_ascii_uppercase().as_str() {
+            "AUTHINFO" => {
+                let (kind, value) = argument.split_once(' ').unwrap_or((argument, ""));
+                match kind.to_ascii_uppercase().as_str() {
+                    "USER" if authenticated => b"281 Authentication accepted\r\n".to_vec(),
+                    "USER" => {
+                        username = Some(value.to_string());
+                        b"381 Password required\r\n".to_vec()
+                    }
+                    "PASS" => {
+                        let accepted = lock().credentials.as_ref().is_none_or(|(user, pass)| {
+                            username.as_deref() == Some(user.as_str()) && value == pass
+                        });
+                        if accepted {
+                            authenticated = true;
+                            b"281 Authentication accepted\r\n".to_vec()
+                        } else {
+                            b"481 Authentication failed\r\n".to_vec()
+                        }
+                    }
+                    _ => b"501 Syntax error\r\n".to_vec(),
+                }
+            }
+            "CAPABILITIES" => {
+                b"101 Capability list follows\r\nVERSION 2\r\nREADER\r\nAUTHINFO USER\r\n.\r\n"
+                    .to_vec()
+            }
+            "DATE" => format!("111 {}\r\n", chrono::Utc::now().format("%Y%m%d%H%M%S")).into_bytes(),
+            "ARTICLE" if !authenticated => b"480 Authentication required\r\n".to_vec(),
+            "ARTICLE" => {
+                let message_id = bare_message_id(argument).to_string();
+                let (body, rate) = {
+                    let mut state = lock();
+                    state.requests.push(message_id.clone());
+                    (
+                        state.articles.get(&message_id).cloned(),
+                        state.bytes_per_second,
+                    )
+                };
+                match body {
+                    Some(body) => {
+                        let response = article_response(&message_id, &body);
+                        write_throttled(&mut writer, &response, rate).await?;
+                        continue;
+                    }
+                    None => b"430 No such article\r\n".to_vec(),
+                }
+            }
+            "QUIT" => {
+                writer.write_all(b"205 Goodbye\r\n").await?;
+                return Ok(());
+            }
+            _ => b"500 Unknown command\r\n".to_vec(),
+        };
+        writer.write_all(&response).await?;
+    }
+}
+
+/// `220` response carrying `body`, dot-stuffed and terminated
+fn article_response(message_id: &str, body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "220 0 <{id}> article follows\r\nMessage-ID: <{id}>\r\n\r\n",
+        id = message_id
+    )
+    .into_bytes();
+    let body = body.strip_suffix(b"\r\n").unwrap_or(body);
+    for line in body.split(|&b| b == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.starts_with(b".") {
+            response.push(b'.');
+        }
+        response.extend_from_slice(line);
+        response.extend_from_slice(b"\r\n");
+    }
+    response.extend_from_slice(b".\r\n");
+    response
+}
+
+/// Write `data`, pausing between chunks to keep to `rate` bytes per second
+async fn write_throttled(
+    writer: &mut (impl AsyncWrite + Unpin),
+    data: &[u8],
+    rate: Option<u64>,
+) -> std::io::Result<()> {
+    let Some(rate) = rate else {
+        return writer.write_all(data).await;
+    };
+    for chunk in data.chunks(THROTTLE_CHUNK) {
+        writer.write_all(chunk).await?;
+        writer.flush().await?;
+        tokio::time::sleep(Duration::from_secs_f64(chunk.len() as f64 / rate as f64)).await;
+    }
+    Ok(())
+}
+
+/// NZB listing `files`, one segment per article
+fn nzb(files: &[(String, Vec<(String, usize)>)]) -> String {
+    let mut nzb = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <nzb xmlns=\"http://www.newzbin.com/DTD/2003/nzb\">\n",
+    );
+    for (filename, segments) in files {
+        nzb.push_str(&format!(
+            "  <file poster=\"mock@example.com\" date=\"0\" subject=\"&quot;{}&quot; yEnc (1/{})\">\n    <groups><group>alt.binaries.test</group></groups>\n    <segments>\n",
+            xml_escape(filename),
+            segments.len()
+        ));
+        for (i, (message_id, bytes)) in segments.iter().enumerate() {
+            nzb.push_str(&format!(
+                "      <segment bytes=\"{}\" number=\"{}\">{}</segment>\n",
+                bytes,
+                i + 1,
+                xml_escape(message_id)
+            ));
+        }
+        nzb.push_str("    </segments>\n  </file>\n");
+    }
+    nzb.push_str("</nzb>\n");
+    nzb
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn bare_message_id(message_id: &str) -> &str {
+    message_id
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+}
+
+// unwrap/expect are acceptable in tests for concise failure-on-error assertions
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,110 @@
+use super::*;
+use std::time::Instant;
+
+async fn connect(server: &MockNntpServer) -> nntp_rs::NntpClient {
+    let config: nntp_rs::ServerConfig = server.server_config().into();
+    nntp_rs::NntpClient::connect(Arc::new(config))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn serves_added_articles_and_430_for_the_rest() {
+    let server = MockNntpServer::start().await.unwrap();
+    server.add_article("<one@test>", "first line\r\n.dotted line\r\n");
+    let mut client = connect(&server).await;
+    client.authenticate().await.unwrap();
+
+    let responses = client
+        .fetch_articles_pipelined(&["<one@test>"], 1)
+        .await
+        .unwrap();
+    let data = String::from_utf8(responses[0].data.clone()).unwrap();
+    assert!(data.contains("first line.dotted line"), "got {:?}", data);
+
+    server.remove_article("one@test");
+    match client.fetch_articles_pipelined(&["<one@test>"], 1).await {
+        Err(nntp_rs::NntpError::NoSuchArticle(id)) => assert_eq!(id, "<one@test>"),
+        other => panic!("expected 430, got {:?}", other.map(|r| r.len())),
+    }
+    assert_eq!(server.requests(), ["one@test", "one@test"]);
+}
+
+#[tokio::test]
+async fn add_file_splits_data_into_yenc_segments() {
+    let server = MockNntpServer::start().await.unwrap();
+    let data: Vec<u8> = (0..=255).cycle().take(2500).collect();
+
+    let nzb = server.add_file("data.bin", &data, 1000);
+
+    let parsed = nntp_rs::parse_nzb(&nzb).unwrap();
+    assert_eq!(parsed.files.len(), 1);
+    let segments = &parsed.files[0].segments;
+    assert_eq!(segments.len(), 3);
+    assert_eq!(segments[2].message_id, "data.bin.3@mock");
+
+    let state = server.state();
+    let mut decoded = Vec::new();
+    for segment in segments {
+        let body = &state.articles[&segment.message_id];
+        decoded.extend(nntp_rs::yenc_decode(body).unwrap().data);
+    }
+    assert_eq!(decoded, data);
+}
+
+#[tokio::test]
+async fn rejects_wrong_credentials() {
+    let server = MockNntpServer::start().await.unwrap();
+    server.require_auth("user", "secret");
+    server.add_article("one@test", "body");
+
+    let mut config = server.server_config();
+    assert_eq!(config.username.as_deref(), Some("user"));
+    let mut client = connect(&server).await;
+    client.authenticate().await.unwrap();
+    assert!(
+        client
+            .fetch_articles_pipelined(&["<one@test>"], 1)
+            .await
+            .is_ok()
+    );
+
+    config.password = Some("wrong".to_string());
+    let mut client = nntp_rs::NntpClient::connect(Arc::new(config.into()))
+        .await
+        .unwrap();
+    assert!(matches!(
+        client.authenticate().await,
+        Err(nntp_rs::NntpError::AuthFailed(_))
+    ));
+    match client.fetch_articles_pipelined(&["<one@test>"], 1).await {
+        Err(nntp_rs::NntpError::Protocol { code, .. }) => assert_eq!(code, 480),
+        other => panic!("expected 480, got {:?}", other.map(|r| r.len())),
+    }
+}
+
+#[tokio::test]
+async fn throttle_slows_article_responses() {
+    let server = MockNntpServer::start().await.unwrap();
+    server.add_article("big@test", vec![b'x'; 64 * 1024]);
+    server.throttle(Some(256 * 1024));
+    let mut client = connect(&server).await;
+    client.authenticate().await.unwrap();
+
+    let start = Instant::now();
+    client
+        .fetch_articles_pipelined(&["<big@test>"], 1)
+        .await
+        .unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn only_running_mock_servers_skip_certificate_checks() {
+    let server = MockNntpServer::start().await.unwrap();
+    let config = server.server_config();
+    assert!(nntp_rs::ServerConfig::from(config.clone()).allow_insecure_tls);
+
+    drop(server);
+    assert!(!nntp_rs::ServerConfig::from(config).allow_insecure_tls);
+}
@@ -108,6 +108,15 @@ pub enum Status {
     Complete,
     /// Failed with error
     Failed,
+    /// Aborted because more data was missing than PAR2 recovery data could repair
+    #[serde(rename = "failed_unrepairable")]
+    FailedUnrepairable,
+    /// Held as a possible duplicate until it's accepted or discarded
+    #[serde(rename = "held_duplicate")]
+    HeldDuplicate,
+    /// Parked because no password opened its archives, until one is submitted
+    #[serde(rename = "waiting_for_password")]
+    WaitingForPassword,
 }
 
 impl Status {
@@ -120,6 +129,9 @@ impl Status {
             3 => Status::Processing,
             4 => Status::Complete,
             5 => Status::Failed,
+            6 => Status::FailedUnrepairable,
+            7 => Status::HeldDuplicate,
+            8 => Status::WaitingForPassword,
             _ => Status::Failed, // Default to Failed for unknown status
         }
     }
@@ -133,10 +145,38 @@ impl Status {
             Status::Processing => 3,
             Status::Complete => 4,
             Status::Failed => 5,
+            Status::FailedUnrepairable => 6,
+            Status::HeldDuplicate => 7,
+            Status::WaitingForPassword => 8,
         }
     }
 }
 
+/// What a pause or resume applies to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseScope {
+    /// Downloading only; jobs that finished downloading still post-process
+    Download,
+    /// Post-processing only; downloads go on and wait before post-processing
+    PostProcessing,
+    /// Both (default)
+    #[default]
+    All,
+}
+
+impl PauseScope {
+    /// Whether the scope covers downloading
+    pub fn includes_download(self) -> bool {
+        matches!(self, PauseScope::Download | PauseScope::All)
+    }
+
+    /// Whether the scope covers post-processing
+    pub fn includes_post_processing(self) -> bool {
+        matches!(self, PauseScope::PostProcessing | PauseScope::All)
+    }
+}
+
 /// Download priority
 #[derive(
     Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema,
@@ -179,6 +219,8 @@ pub enum Stage {
     Repair,
     /// Archive extraction
     Extract,
+    /// Virus scan of the extracted files
+    Scan,
     /// Move to final destination
     Move,
     /// Cleanup intermediate files
@@ -187,16 +229,33 @@ pub enum Stage {
     DirectUnpack,
 }
 
-/// Archive type detected by file extension
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+/// Archive type detected by file extension or signature
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ArchiveType {
-    /// RAR archive (.rar, .r00, .r01, etc.)
+    /// RAR archive (.rar, .r00, .r01, .cbr, etc.)
     Rar,
     /// 7-Zip archive (.7z)
     SevenZip,
-    /// ZIP archive (.zip)
+    /// ZIP archive (.zip, .cbz)
     Zip,
+    /// Zstandard-compressed file (.zst)
+    Zstd,
+    /// Format handled by a registered [`Extractor`](crate::extraction::Extractor), by its name
+    Custom(String),
+}
+
+/// How much bandwidth downloads may use, as set by the host application
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BandwidthMode {
+    /// The configured speed limit applies (default)
+    #[default]
+    Normal,
+    /// `download.metered` applies: its speed limit, and large downloads stay queued
+    Metered,
+    /// No speed limit
+    Unlimited,
 }
 
 /// Event emitted during download lifecycle
@@ -217,6 +276,16 @@ pub enum Event {
         id: DownloadId,
     },
 
+    /// Download renamed (e.g. by a deobfuscation consumer pushing back the real name)
+    Renamed {
+        /// Download ID
+        id: DownloadId,
+        /// Previous name
+        old: String,
+        /// New name
+        new: String,
+    },
+
     /// Download progress update
     Downloading {
         /// Download ID
@@ -265,12 +334,28 @@ pub enum Event {
         articles_total: Option<u64>,
     },
 
+    /// Download finished downloading and waits for a free post-processing slot
+    PostProcessQueued {
+        /// Download ID
+        id: DownloadId,
+        /// Place in the post-processing queue (1 is next)
+        position: usize,
+    },
+
     /// PAR2 verification started
     Verifying {
         /// Download ID
         id: DownloadId,
     },
 
+    /// PAR2 verification progress
+    VerifyProgress {
+        /// Download ID
+        id: DownloadId,
+        /// Progress percentage (0.0 to 100.0)
+        percent: f32,
+    },
+
     /// PAR2 verification completed
     VerifyComplete {
         /// Download ID
@@ -289,6 +374,14 @@ pub enum Event {
         blocks_available: u32,
     },
 
+    /// PAR2 repair progress
+    RepairProgress {
+        /// Download ID
+        id: DownloadId,
+        /// Progress percentage (0.0 to 100.0)
+        percent: f32,
+    },
+
     /// PAR2 repair completed
     RepairComplete {
         /// Download ID
@@ -297,6 +390,18 @@ pub enum Event {
         success: bool,
     },
 
+    /// Repair was short of recovery blocks; the held PAR2 volumes are being fetched
+    ///
+    /// Repair runs again once they have been downloaded.
+    FetchingExtraPars {
+        /// Download ID
+        id: DownloadId,
+        /// Number of PAR2 volumes queued
+        files: usize,
+        /// Recovery blocks they carry, from their names (`.vol03+04.par2` carries 4)
+        blocks: u32,
+    },
+
     /// PAR2 repair skipped (not supported or not needed)
     RepairSkipped {
         /// Download ID
@@ -315,12 +420,30 @@ pub enum Event {
         percent: f32,
     },
 
+    /// No password opened the download's archives; it waits until one is submitted
+    WaitingForPassword {
+        /// Download ID
+        id: DownloadId,
+        /// Archive that couldn't be opened
+        archive: PathBuf,
+    },
+
     /// Archive extraction completed
     ExtractComplete {
         /// Download ID
         id: DownloadId,
     },
 
+    /// Virus scan completed
+    ScanComplete {
+        /// Download ID
+        id: DownloadId,
+        /// The scanner found infected files
+        infected: bool,
+        /// What the scanner reported, one line per detection
+        detections: Vec<String>,
+    },
+
     /// Moving files to destination
     Moving {
         /// Download ID
@@ -329,6 +452,27 @@ pub enum Event {
         destination: PathBuf,
     },
 
+    /// Copy progress while moving files across filesystems (or in copy mode)
+    MoveProgress {
+        /// Download ID
+        id: DownloadId,
+        /// Progress percentage (0.0 to 100.0)
+        percent: f32,
+    },
+
+    /// Files copied by the move stage matched their checksums
+    ///
+    /// Emitted once per move stage that copied files (across filesystems or with
+    /// `move_mode = "copy"`); renames aren't checksummed.
+    MoveVerified {
+        /// Download ID
+        id: DownloadId,
+        /// Number of files copied and verified
+        files: usize,
+        /// Checksum algorithm used
+        checksum: crate::config::MoveChecksum,
+    },
+
     /// Cleaning up intermediate files
     Cleaning {
         /// Download ID
@@ -343,6 +487,22 @@ pub enum Event {
         path: PathBuf,
     },
 
+    /// Download finished post-processing and every completion script succeeded
+    ///
+    /// The single event importers (e.g. Sonarr-style media managers) should act on:
+    /// emitted after [`Event::Complete`] once the `on_post_process_complete` and
+    /// `on_complete` scripts exited successfully, and not at all if any of them failed.
+    ReadyForImport {
+        /// Download ID
+        id: DownloadId,
+        /// Category (if assigned)
+        category: Option<String>,
+        /// Final path
+        final_path: PathBuf,
+        /// Final paths of all files moved to the destination
+        files: Vec<PathBuf>,
+    },
+
     /// Download failed at some stage
     Failed {
         /// Download ID
@@ -361,12 +521,40 @@ pub enum Event {
         limit_bps: Option<u64>,
     },
 
+    /// Bandwidth mode changed
+    BandwidthModeChanged {
+        /// New mode
+        mode: BandwidthMode,
+    },
+
     /// Queue paused
     QueuePaused,
 
     /// Queue resumed
     QueueResumed,
 
+    /// Post-processing paused queue-wide; downloaded jobs wait until it's resumed
+    PostProcessingPaused,
+
+    /// Post-processing resumed queue-wide
+    PostProcessingResumed,
+
+    /// A `force` priority download started and is holding back the other active downloads
+    DownloadsPreempted {
+        /// Forced download
+        id: DownloadId,
+        /// Downloads held back until the forced downloads finish
+        preempted: Vec<DownloadId>,
+    },
+
+    /// The last forced download finished and the held back downloads resumed
+    PreemptionEnded {
+        /// Forced download that finished last
+        id: DownloadId,
+        /// Downloads that resumed
+        resumed: Vec<DownloadId>,
+    },
+
     /// Webhook delivery failed
     WebhookFailed {
         /// Webhook URL
@@ -395,6 +583,16 @@ pub enum Event {
         existing_name: String,
     },
 
+    /// Download held as a duplicate until it's accepted or discarded
+    DuplicateHeld {
+        /// Held download ID
+        id: DownloadId,
+        /// Name of the held download
+        name: String,
+        /// The download it duplicates
+        duplicate: DuplicateInfo,
+    },
+
     /// DirectUnpack coordinator started for a download
     DirectUnpackStarted {
         /// Download ID
@@ -453,10 +651,204 @@ pub enum Event {
         new_name: String,
     },
 
+    /// Files with a rejected extension were left out of a download
+    FilesRejected {
+        /// Download ID
+        id: DownloadId,
+        /// Names of the skipped files
+        files: Vec<String>,
+    },
+
+    /// A deferred download's NZB was fetched and it's about to start
+    NzbFetched {
+        /// Download ID
+        id: DownloadId,
+        /// Download name, which may have changed to the fetched NZB's file name
+        name: String,
+        /// Total size in bytes
+        size_bytes: u64,
+    },
+
+    /// A deferred download's NZB couldn't be fetched, so the download fails
+    NzbFetchFailed {
+        /// Download ID
+        id: DownloadId,
+        /// URL the download was added with
+        url: String,
+        /// Why each attempt failed, in the order they were made
+        reasons: Vec<String>,
+    },
+
+    /// The stall watchdog found a download making no progress and acted on it
+    Stalled {
+        /// Download ID
+        id: DownloadId,
+        /// Why the download counts as stalled
+        reason: String,
+        /// What was done about it
+        action: crate::config::StallAction,
+        /// How many times the download has stalled, including this time
+        attempts: u32,
+    },
+
+    /// A watch folder file couldn't be imported
+    ///
+    /// Files that are malformed, hold no NZBs or are rejected as duplicates are moved to
+    /// the watch folder's error folder.
+    NzbImportFailed {
+        /// File in the watch folder (for an archive entry, the archive)
+        path: PathBuf,
+        /// Why the import failed
+        reason: String,
+    },
+
+    /// An RSS item matched a `notify` filter, or a feed that doesn't auto-download
+    RssItemMatched {
+        /// RSS feed ID
+        feed_id: i64,
+        /// Item title
+        title: String,
+        /// NZB URL, if the feed provided one
+        nzb_url: Option<String>,
+        /// Name of the filter that matched (`None` for a feed without filters)
+        filter: Option<String>,
+    },
+
+    /// Every download in a batch reached a final state (emitted once per batch)
+    BatchComplete {
+        /// Batch ID
+        id: i64,
+        /// Batch name
+        name: String,
+        /// Number of downloads that completed successfully
+        succeeded: usize,
+        /// Number of downloads that failed
+        failed: usize,
+    },
+
+    /// Buffered usage counters were written to the statistics tables
+    StatsRollup {
+        /// UTC date the bytes were attributed to (YYYY-MM-DD)
+        date: String,
+        /// Bytes added by this rollup across all servers and categories
+        bytes: u64,
+    },
+
+    /// Drain finished: no active downloads or post-processing remain
+    Drained,
+
     /// Graceful shutdown initiated
     Shutdown,
 }
 
+impl Event {
+    /// Name of the event, as sent in the `type` field of its JSON and [`EventEnvelope`]
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Event::Queued { .. } => "queued",
+            Event::Removed { .. } => "removed",
+            Event::Renamed { .. } => "renamed",
+            Event::Downloading { .. } => "downloading",
+            Event::DownloadComplete { .. } => "download_complete",
+            Event::DownloadFailed { .. } => "download_failed",
+            Event::PostProcessQueued { .. } => "post_process_queued",
+            Event::Verifying { .. } => "verifying",
+            Event::VerifyProgress { .. } => "verify_progress",
+            Event::VerifyComplete { .. } => "verify_complete",
+            Event::Repairing { .. } => "repairing",
+            Event::RepairProgress { .. } => "repair_progress",
+            Event::RepairComplete { .. } => "repair_complete",
+            Event::FetchingExtraPars { .. } => "fetching_extra_pars",
+            Event::RepairSkipped { .. } => "repair_skipped",
+            Event::Extracting { .. } => "extracting",
+            Event::WaitingForPassword { .. } => "waiting_for_password",
+            Event::ExtractComplete { .. } => "extract_complete",
+            Event::ScanComplete { .. } => "scan_complete",
+            Event::Moving { .. } => "moving",
+            Event::MoveProgress { .. } => "move_progress",
+            Event::MoveVerified { .. } => "move_verified",
+            Event::Cleaning { .. } => "cleaning",
+            Event::Complete { .. } => "complete",
+            Event::ReadyForImport { .. } => "ready_for_import",
+            Event::Failed { .. } => "failed",
+            Event::SpeedLimitChanged { .. } => "speed_limit_changed",
+            Event::BandwidthModeChanged { .. } => "bandwidth_mode_changed",
+            Event::QueuePaused => "queue_paused",
+            Event::QueueResumed => "queue_resumed",
+            Event::PostProcessingPaused => "post_processing_paused",
+            Event::PostProcessingResumed => "post_processing_resumed",
+            Event::DownloadsPreempted { .. } => "downloads_preempted",
+            Event::PreemptionEnded { .. } => "preemption_ended",
+            Event::WebhookFailed { .. } => "webhook_failed",
+            Event::ScriptFailed { .. } => "script_failed",
+            Event::DuplicateDetected { .. } => "duplicate_detected",
+            Event::DuplicateHeld { .. } => "duplicate_held",
+            Event::DirectUnpackStarted { .. } => "direct_unpack_started",
+            Event::FileCompleted { .. } => "file_completed",
+            Event::DirectUnpackExtracting { .. } => "direct_unpack_extracting",
+            Event::DirectUnpackExtracted { .. } => "direct_unpack_extracted",
+            Event::DirectUnpackCancelled { .. } => "direct_unpack_cancelled",
+            Event::DirectUnpackComplete { .. } => "direct_unpack_complete",
+            Event::DirectRenamed { .. } => "direct_renamed",
+            Event::FilesRejected { .. } => "files_rejected",
+            Event::NzbFetched { .. } => "nzb_fetched",
+            Event::NzbFetchFailed { .. } => "nzb_fetch_failed",
+            Event::Stalled { .. } => "stalled",
+            Event::NzbImportFailed { .. } => "nzb_import_failed",
+            Event::RssItemMatched { .. } => "rss_item_matched",
+            Event::BatchComplete { .. } => "batch_complete",
+            Event::StatsRollup { .. } => "stats_rollup",
+            Event::Drained => "drained",
+            Event::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// Version of the [`EventEnvelope`] format
+pub const EVENT_ENVELOPE_VERSION: u32 = 1;
+
+/// An [`Event`] in a stable, versioned JSON wrapper
+///
+/// Sent by `GET /events` and [`UsenetDownloader::subscribe_json`](crate::UsenetDownloader::subscribe_json).
+/// The envelope's fields only change with a new [`EVENT_ENVELOPE_VERSION`], so consumers
+/// can rely on them even when the `Event` enum is reorganized.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventEnvelope {
+    /// Envelope format version ([`EVENT_ENVELOPE_VERSION`])
+    pub version: u32,
+    /// Sequence number, increasing by one per event and continuing across restarts
+    ///
+    /// A gap means events were coalesced or dropped before reaching this subscriber.
+    pub seq: u64,
+    /// When the event was emitted
+    pub timestamp: DateTime<Utc>,
+    /// Event name, e.g. `queued` or `download_complete`
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// Event fields, without the `type` tag (an empty object for events without fields)
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+}
+
+impl EventEnvelope {
+    /// Wrap an event with its sequence number, timestamped now
+    pub fn new(seq: u64, event: &Event) -> Self {
+        let mut payload = match serde_json::to_value(event) {
+            Ok(serde_json::Value::Object(fields)) => fields,
+            _ => serde_json::Map::new(),
+        };
+        payload.remove("type");
+
+        Self {
+            version: EVENT_ENVELOPE_VERSION,
+            seq,
+            timestamp: Utc::now(),
+            event_type: event.event_type().to_string(),
+            payload: serde_json::Value::Object(payload),
+        }
+    }
+}
+
 /// Information about a download in the queue
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct DownloadInfo {
@@ -495,6 +887,91 @@ pub struct DownloadInfo {
 
     /// When the download started (None if not started yet)
     pub started_at: Option<DateTime<Utc>>,
+
+    /// Directory holding the in-progress data for this download
+    pub incomplete_path: PathBuf,
+
+    /// The download this one duplicates, while it's held as a duplicate
+    pub duplicate: Option<DuplicateInfo>,
+
+    /// Whether this download's post-processing is paused (it waits once downloaded)
+    pub post_processing_paused: bool,
+
+    /// URL the NZB will be fetched from when the download starts, while it's deferred
+    pub deferred_url: Option<String>,
+
+    /// Why the stall watchdog last acted on this download, if it has
+    pub stall_reason: Option<String>,
+
+    /// Why the download failed: failure code, error message and remediation hint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<crate::error::ErrorDetail>,
+}
+
+/// Files left in a download's temp folder
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct DownloadArtifacts {
+    /// Download ID
+    pub id: DownloadId,
+
+    /// Temp folder the files are in
+    pub path: PathBuf,
+
+    /// Every file in the folder, sorted by path
+    pub files: Vec<DownloadArtifact>,
+
+    /// Combined size of the files in bytes
+    pub total_bytes: u64,
+}
+
+/// A file left in a download's temp folder
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct DownloadArtifact {
+    /// Path relative to the temp folder
+    pub path: PathBuf,
+
+    /// File size in bytes
+    pub size_bytes: u64,
+}
+
+/// A group of downloads added together (e.g., a season pack split into episode NZBs)
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchInfo {
+    /// Unique batch identifier
+    pub id: i64,
+
+    /// Batch name
+    pub name: String,
+
+    /// Number of downloads in the batch
+    pub total: usize,
+
+    /// Number of downloads that completed successfully
+    pub succeeded: usize,
+
+    /// Number of downloads that failed
+    pub failed: usize,
+
+    /// Aggregated progress across all members, weighted by size (0.0 to 100.0)
+    pub progress: f32,
+
+    /// Combined download speed in bytes per second
+    pub speed_bps: u64,
+
+    /// Combined size in bytes
+    pub size_bytes: u64,
+
+    /// Combined downloaded bytes
+    pub downloaded_bytes: u64,
+
+    /// When the batch was created
+    pub created_at: DateTime<Utc>,
+
+    /// When every member finished (None while any member is still pending)
+    pub completed_at: Option<DateTime<Utc>>,
+
+    /// Member downloads
+    pub downloads: Vec<DownloadInfo>,
 }
 
 /// Options for adding a download to the queue
@@ -519,6 +996,17 @@ pub struct DownloadOptions {
     /// Password for this specific download (high priority)
     #[serde(default)]
     pub password: Option<String>,
+
+    /// Batch to add this download to (must already exist)
+    #[serde(default)]
+    pub batch_id: Option<i64>,
+
+    /// Override the temp directory holding in-progress data (None = use `download.temp_dir`)
+    ///
+    /// Lets in-progress data live on fast scratch storage while `destination` is elsewhere.
+    /// The download's files are kept in a `download_{id}` subdirectory.
+    #[serde(default)]
+    pub temp_dir: Option<PathBuf>,
 }
 
 /// Historical download record
@@ -545,8 +1033,52 @@ pub struct HistoryEntry {
     /// Time spent downloading (not including queue wait time)
     pub download_time: Duration,
 
+    /// Bytes fetched from servers, including articles fetched more than once
+    #[serde(default)]
+    pub fetched_bytes: u64,
+
+    /// Average download speed in bytes per second (`fetched_bytes` over `download_time`)
+    #[serde(default)]
+    pub average_speed_bps: u64,
+
+    /// Bytes fetched from each server, largest first
+    #[serde(default)]
+    pub server_bytes: Vec<ServerUsage>,
+
+    /// PAR2 recovery blocks used to repair damaged files
+    #[serde(default)]
+    pub repair_blocks_used: u32,
+
+    /// Time spent extracting archives
+    #[serde(default)]
+    pub extraction_time: Duration,
+
     /// When the download completed (successfully or failed)
     pub completed_at: DateTime<Utc>,
+
+    /// Download this entry records, whose files a retry re-queues
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_id: Option<DownloadId>,
+
+    /// Download created by retrying this entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retried_as: Option<DownloadId>,
+}
+
+/// One page of download history
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct HistoryPage {
+    /// Entries on this page, most recently completed first
+    pub items: Vec<HistoryEntry>,
+
+    /// Number of entries matching the filter across all pages
+    pub total: i64,
+
+    /// Maximum number of entries per page
+    pub limit: usize,
+
+    /// Number of matching entries skipped before this page
+    pub offset: usize,
 }
 
 /// Queue statistics
@@ -584,10 +1116,22 @@ pub struct QueueStats {
 
     /// Whether queue is accepting new downloads
     pub accepting_new: bool,
+
+    /// Whether the downloader is draining for maintenance
+    pub draining: bool,
+
+    /// Whether post-processing is paused queue-wide
+    pub post_processing_paused: bool,
+
+    /// Number of post-processing runs holding a slot (at most `max_parallel_jobs`)
+    pub post_processing_running: usize,
+
+    /// Number of downloads waiting for a free post-processing slot
+    pub post_processing_queued: usize,
 }
 
 /// Information about a detected duplicate download
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct DuplicateInfo {
     /// Detection method that found the duplicate
     pub method: crate::config::DuplicateMethod,
@@ -599,6 +1143,66 @@ pub struct DuplicateInfo {
     pub existing_name: String,
 }
 
+/// What adding an NZB would do, from a dry run that queues nothing
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct NzbValidation {
+    /// Name the download would be added under
+    pub name: String,
+
+    /// Job name used for deobfuscation and duplicate detection (NZB title if present)
+    pub job_name: String,
+
+    /// Whether the NZB carries a password in its metadata
+    pub has_password: bool,
+
+    /// Total size of all articles in bytes
+    pub size_bytes: u64,
+
+    /// Number of articles (segments) across all files
+    pub segment_count: usize,
+
+    /// Files in the NZB, in NZB order
+    pub files: Vec<NzbFilePreview>,
+
+    /// Existing download this one duplicates, if any
+    pub duplicate: Option<DuplicateInfo>,
+
+    /// What `processing.duplicate.action` would do with the duplicate
+    pub duplicate_action: Option<crate::config::DuplicateAction>,
+
+    /// Category the download would be added to
+    pub category: Option<String>,
+
+    /// Whether `category` is configured (unknown categories fall back to the defaults)
+    pub category_configured: bool,
+
+    /// Destination directory after applying the category
+    pub destination: PathBuf,
+
+    /// Post-processing mode after applying the category
+    pub post_process: crate::config::PostProcess,
+
+    /// Problems adding would run into, e.g. insufficient disk space (the add is
+    /// rejected) or an unknown category (the defaults are used)
+    pub warnings: Vec<String>,
+}
+
+/// A file listed in an NZB
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct NzbFilePreview {
+    /// Filename parsed from the subject line
+    pub filename: String,
+
+    /// Subject line as posted
+    pub subject: String,
+
+    /// Number of articles (segments)
+    pub segments: usize,
+
+    /// Combined article size in bytes
+    pub size_bytes: u64,
+}
+
 /// Payload sent to webhooks
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct WebhookPayload {
@@ -630,6 +1234,28 @@ pub struct WebhookPayload {
     pub timestamp: i64,
 }
 
+/// Payload sent to a webhook in digest mode, once per `digest_interval`
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDigest {
+    /// Event type (always "digest")
+    pub event: String,
+
+    /// Start of the period covered (Unix timestamp in seconds)
+    pub period_start: i64,
+
+    /// End of the period covered (Unix timestamp in seconds)
+    pub period_end: i64,
+
+    /// Number of downloads that completed in the period
+    pub completed: usize,
+
+    /// Number of downloads that failed in the period
+    pub failed: usize,
+
+    /// The payloads the webhook would otherwise have received, oldest first
+    pub events: Vec<WebhookPayload>,
+}
+
 // unwrap/expect are acceptable in tests for concise failure-on-error assertions
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 #[cfg(test)]
@@ -648,6 +1274,7 @@ mod tests {
             (Status::Processing, 3),
             (Status::Complete, 4),
             (Status::Failed, 5),
+            (Status::FailedUnrepairable, 6),
         ];
 
         for (variant, expected_int) in cases {
@@ -888,6 +1515,169 @@ pub struct ServerTestResult {
     pub capabilities: Option<ServerCapabilities>,
 }
 
+/// Options for a server speed test
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SpeedTestOptions {
+    /// Newsgroup to sample recent articles from (default: "alt.binaries.test")
+    #[serde(default)]
+    pub group: Option<String>,
+
+    /// Specific message IDs to download instead of sampling a group
+    #[serde(default)]
+    pub message_ids: Vec<String>,
+
+    /// Approximate bytes to download per run when sampling a group (default: 100 MiB)
+    #[serde(default)]
+    pub bytes: Option<u64>,
+
+    /// Connection counts to measure (default: doubling from 1 up to the server's `connections`)
+    #[serde(default)]
+    pub connection_counts: Vec<usize>,
+
+    /// Pipeline depth per connection (default: the server's `pipeline_depth`)
+    #[serde(default)]
+    pub pipeline_depth: Option<usize>,
+}
+
+/// Throughput measured with one connection count during a speed test
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SpeedTestRun {
+    /// Number of parallel connections used
+    pub connections: usize,
+
+    /// Bytes downloaded
+    pub bytes: u64,
+
+    /// Wall-clock time spent downloading (excludes connecting and authenticating)
+    pub duration_ms: u64,
+
+    /// Sustained throughput in bytes per second
+    pub bytes_per_sec: u64,
+
+    /// Articles that could not be fetched
+    pub failed_articles: usize,
+}
+
+/// Result of a server speed test
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SpeedTestResult {
+    /// Server hostname
+    pub server: String,
+
+    /// Pipeline depth used for every run
+    pub pipeline_depth: usize,
+
+    /// Number of articles downloaded per run
+    pub sample_articles: usize,
+
+    /// One entry per connection count, in the order measured
+    pub runs: Vec<SpeedTestRun>,
+
+    /// Fewest connections that reached at least 95% of the best throughput
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommended_connections: Option<usize>,
+
+    /// Error that stopped the test early (runs completed before it are kept)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Estimated availability of a download on the configured servers
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CompletionCheck {
+    /// Download that was checked
+    pub download_id: DownloadId,
+
+    /// Number of articles in the download
+    pub total_articles: usize,
+
+    /// Number of randomly chosen articles checked
+    pub sampled_articles: usize,
+
+    /// Sampled articles at least one server has
+    pub available_articles: usize,
+
+    /// Sampled articles every answering server reported missing
+    pub missing_articles: usize,
+
+    /// Fraction of the answered sample that is available (0.0-1.0); `None` if no server answered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_completion: Option<f64>,
+
+    /// Per-server results, in the order of `config.servers`
+    pub servers: Vec<ServerCompletion>,
+}
+
+/// Completion check results for one server
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServerCompletion {
+    /// Server hostname
+    pub server: String,
+
+    /// Sampled articles the server has
+    pub available: usize,
+
+    /// Sampled articles the server reported missing
+    pub missing: usize,
+
+    /// Sampled articles the server could not be asked about
+    pub errors: usize,
+}
+
+/// Outcome of a single health probe check
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthCheck {
+    /// Check name (e.g., "database", "servers", "temp_dir")
+    pub name: String,
+
+    /// Whether the check passed
+    pub ok: bool,
+
+    /// Failure reason or extra detail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Result of a liveness or readiness probe
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthReport {
+    /// Whether every check passed
+    pub ok: bool,
+
+    /// Individual check results (empty when no checks are configured)
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthCheck {
+    /// Create a passing check
+    pub fn passed(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: true,
+            message: None,
+        }
+    }
+
+    /// Create a failing check with a reason
+    pub fn failed(name: &str, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: false,
+            message: Some(message.into()),
+        }
+    }
+}
+
+impl HealthReport {
+    /// Build a report that is ok only if every check passed
+    pub fn from_checks(checks: Vec<HealthCheck>) -> Self {
+        Self {
+            ok: checks.iter().all(|c| c.ok),
+            checks,
+        }
+    }
+}
+
 /// NNTP server capabilities discovered during testing
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ServerCapabilities {
@@ -902,6 +1692,130 @@ pub struct ServerCapabilities {
     pub compression: bool,
 }
 
+/// Whether a configured server is in rotation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServerStatus {
+    /// Server index, as used by the other server endpoints
+    pub id: usize,
+
+    /// Server hostname
+    pub host: String,
+
+    /// Whether article fetches use this server
+    pub enabled: bool,
+
+    /// When a temporarily disabled server is enabled again (None if enabled or disabled
+    /// until re-enabled by hand)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_until: Option<DateTime<Utc>>,
+}
+
+/// One NNTP exchange recorded when `logging.nntp_trace` is enabled
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NntpTraceEntry {
+    /// When the exchange completed
+    pub timestamp: DateTime<Utc>,
+
+    /// Command sent (e.g. "ARTICLE <id@example>"), or "CONNECT" for connection checkout
+    pub command: String,
+
+    /// Response status line, or the error that ended the exchange (credentials redacted)
+    pub response: String,
+
+    /// Time taken in milliseconds; pipelined commands share their batch's duration
+    pub duration_ms: u64,
+}
+
+/// One tracing event captured for the live log stream
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LogEntry {
+    /// When the event was recorded
+    pub timestamp: DateTime<Utc>,
+
+    /// Event level
+    pub level: crate::config::LogLevel,
+
+    /// Module path or target the event was emitted from (e.g. "usenet_dl::downloader")
+    pub target: String,
+
+    /// Formatted message
+    pub message: String,
+
+    /// Structured fields other than the message
+    #[schema(value_type = Object)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Reporting window for usage statistics (rolling, ending today in UTC)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsPeriod {
+    /// Today only
+    #[default]
+    Day,
+    /// The last 7 days
+    Week,
+    /// The last 30 days
+    Month,
+}
+
+impl StatsPeriod {
+    /// Number of days covered by this period
+    pub fn days(self) -> i64 {
+        match self {
+            StatsPeriod::Day => 1,
+            StatsPeriod::Week => 7,
+            StatsPeriod::Month => 30,
+        }
+    }
+}
+
+/// Bytes downloaded from one server within a reporting period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServerUsage {
+    /// Server hostname
+    pub server: String,
+    /// Bytes downloaded
+    pub bytes: u64,
+}
+
+/// Bytes downloaded for one category within a reporting period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CategoryUsage {
+    /// Category name (None for uncategorized downloads)
+    pub category: Option<String>,
+    /// Bytes downloaded
+    pub bytes: u64,
+}
+
+/// Bytes downloaded on a single day
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DailyUsage {
+    /// UTC date (YYYY-MM-DD)
+    pub date: String,
+    /// Bytes downloaded
+    pub bytes: u64,
+}
+
+/// Aggregated usage statistics for a reporting period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UsageStats {
+    /// Reporting window
+    pub period: StatsPeriod,
+    /// First day included (YYYY-MM-DD, UTC)
+    pub start: String,
+    /// Last day included (YYYY-MM-DD, UTC)
+    pub end: String,
+    /// Total bytes downloaded in the period
+    pub total_bytes: u64,
+    /// Bytes per server, largest first
+    pub servers: Vec<ServerUsage>,
+    /// Bytes per category, largest first
+    pub categories: Vec<CategoryUsage>,
+    /// Bytes per day, oldest first (days without traffic are included as 0)
+    pub daily: Vec<DailyUsage>,
+}
+
 /// Overall system capabilities for post-processing features
 ///
 /// This struct provides information about what features are available
@@ -924,6 +1838,15 @@ pub struct ParityCapabilitiesInfo {
     /// Whether PAR2 repair is available
     pub can_repair: bool,
 
+    /// Whether PAR2 recovery files can be created
+    pub can_create: bool,
+
+    /// Whether verification and repair use multiple threads
+    pub multithreaded: bool,
+
+    /// Version of the par2 binary or library, if known
+    pub version: Option<String>,
+
     /// Name of the parity handler implementation in use
     pub handler: String,
 }
@@ -1,12 +1,24 @@
 //! Utility functions for file operations and path manipulation
 
-use crate::config::FileCollisionAction;
+use crate::config::{FileCollisionAction, PathSanitizationConfig, ProcessPriorityConfig};
 use crate::error::{Error, PostProcessError, Result};
 use std::path::{Path, PathBuf};
 
 /// Maximum number of rename attempts when resolving file collisions
 const MAX_RENAME_ATTEMPTS: u32 = 9999;
 
+/// Device names Windows reserves regardless of extension (`CON.txt` is still `CON`)
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Path length (in UTF-16 units) at which Win32 APIs need the `\\?\` prefix
+const WINDOWS_MAX_PATH: usize = 260;
+
+/// Longest job name kept in files named after a job, in bytes
+const MAX_JOB_FILE_STEM_LEN: usize = 200;
+
 /// Get a unique path for a file, handling collisions according to the specified action
 ///
 /// # Arguments
@@ -207,6 +219,13 @@ pub fn extract_filename_from_response(response: &reqwest::Response, url: &str) -
     }
 
     // Fall back to extracting from URL path
+    extract_filename_from_url(url)
+}
+
+/// Extract a filename (without extension) from the last segment of a URL's path
+///
+/// Returns "download" if the URL doesn't parse or its path ends in `/`.
+pub fn extract_filename_from_url(url: &str) -> String {
     if let Ok(parsed_url) = url::Url::parse(url)
         && let Some(mut segments) = parsed_url.path_segments()
         && let Some(last_segment) = segments.next_back()
@@ -321,6 +340,192 @@ pub fn get_available_space(path: &Path) -> std::io::Result<u64> {
     }
 }
 
+/// Run an external tool at the configured CPU and I/O priority
+///
+/// The settings are applied in the child between fork and exec, so they never affect
+/// the downloader itself. Any the system refuses are skipped and the tool runs at the
+/// inherited priority instead. Does nothing on non-Unix platforms.
+pub(crate) fn apply_process_priority(
+    command: &mut std::process::Command,
+    priority: &ProcessPriorityConfig,
+) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        if priority.is_inherited() {
+            return;
+        }
+        let priority = priority.clone();
+
+        // SAFETY: the hook runs in the forked child before exec, so it only makes
+        // syscalls, which are async-signal-safe, and never allocates. Their results are
+        // ignored on purpose: a refused setting leaves the inherited value.
+        unsafe {
+            command.pre_exec(move || {
+                if let Some(nice) = priority.nice {
+                    libc::setpriority(libc::PRIO_PROCESS, 0, nice);
+                }
+
+                #[cfg(target_os = "linux")]
+                {
+                    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+                    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+                    let ioprio = match priority.io_class {
+                        Some(crate::config::IoClass::BestEffort) => Some(
+                            (2 << IOPRIO_CLASS_SHIFT)
+                                | libc::c_int::from(priority.io_priority.unwrap_or(4).min(7)),
+                        ),
+                        Some(crate::config::IoClass::Idle) => Some(3 << IOPRIO_CLASS_SHIFT),
+                        None => None,
+                    };
+                    if let Some(ioprio) = ioprio {
+                        libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio);
+                    }
+
+                    if !priority.cpu_affinity.is_empty() {
+                        let mut cpus: libc::cpu_set_t = std::mem::zeroed();
+                        for &cpu in &priority.cpu_affinity {
+                            if cpu < libc::CPU_SETSIZE as usize {
+                                libc::CPU_SET(cpu, &mut cpus);
+                            }
+                        }
+                        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &cpus);
+                    }
+                }
+
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (command, priority);
+    }
+}
+
+/// Make a single file or folder name valid on Windows
+///
+/// Does nothing unless `config.windows_compatible` is set. Otherwise the characters
+/// `<>:"/\|?*` are replaced with `_` (or their full-width look-alikes when
+/// `config.transliterate_illegal_chars` is set), control characters become `_`,
+/// trailing dots and spaces are trimmed, and reserved device names get a `_` suffix
+/// on their stem (`CON.txt` becomes `CON_.txt`).
+///
+/// # Examples
+///
+/// ```
+/// use usenet_dl::config::PathSanitizationConfig;
+/// use usenet_dl::utils::sanitize_filename;
+///
+/// let config = PathSanitizationConfig {
+///     windows_compatible: true,
+///     transliterate_illegal_chars: false,
+/// };
+/// assert_eq!(sanitize_filename("nul.txt", &config), "nul_.txt");
+/// assert_eq!(sanitize_filename("Title: Part 1. ", &config), "Title_ Part 1");
+/// ```
+pub fn sanitize_filename(name: &str, config: &PathSanitizationConfig) -> String {
+    if !config.windows_compatible {
+        return name.to_string();
+    }
+
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => {
+                if config.transliterate_illegal_chars {
+                    // ASCII 0x21..=0x7E map onto the full-width block at a fixed offset
+                    char::from_u32(c as u32 + 0xFEE0).unwrap_or('_')
+                } else {
+                    '_'
+                }
+            }
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    // Win32 silently strips trailing dots and spaces, so "name." would become "name"
+    let trimmed_len = sanitized.trim_end_matches(['.', ' ']).len();
+    sanitized.truncate(trimmed_len);
+    if sanitized.is_empty() {
+        return "_".to_string();
+    }
+
+    let (stem, extension) = match sanitized.split_once('.') {
+        Some((stem, extension)) => (stem, Some(extension)),
+        None => (sanitized.as_str(), None),
+    };
+    let is_reserved = WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.trim_end().eq_ignore_ascii_case(reserved));
+
+    match (is_reserved, extension) {
+        (true, Some(extension)) => format!("{}_.{}", stem, extension),
+        (true, None) => format!("{}_", stem),
+        (false, _) => sanitized,
+    }
+}
+
+/// Turn a job name into a file name stem that is valid on every platform
+///
+/// Applies the Windows naming rules of [`sanitize_filename`] regardless of platform,
+/// which also removes path separators, and truncates the result to
+/// [`MAX_JOB_FILE_STEM_LEN`] bytes.
+pub(crate) fn job_file_stem(job_name: &str) -> String {
+    let sanitization = PathSanitizationConfig {
+        windows_compatible: true,
+        transliterate_illegal_chars: false,
+    };
+    let mut stem = sanitize_filename(job_name, &sanitization);
+    if stem.len() > MAX_JOB_FILE_STEM_LEN {
+        let mut end = MAX_JOB_FILE_STEM_LEN;
+        while !stem.is_char_boundary(end) {
+            end -= 1;
+        }
+        stem.truncate(end);
+    }
+    stem
+}
+
+/// Prefix long absolute paths with `\\?\` so Windows APIs accept them
+///
+/// Paths of [`WINDOWS_MAX_PATH`] or more UTF-16 units fail with most Win32 calls unless
+/// they use the extended-length form. Returns the path unchanged on other platforms.
+pub fn long_path(path: &Path) -> PathBuf {
+    if cfg!(windows) {
+        extended_length_path(path)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Platform-independent core of [`long_path`]
+fn extended_length_path(path: &Path) -> PathBuf {
+    let Some(raw) = path.to_str() else {
+        return path.to_path_buf();
+    };
+
+    if raw.encode_utf16().count() < WINDOWS_MAX_PATH || raw.starts_with(r"\\?\") {
+        return path.to_path_buf();
+    }
+
+    // Extended-length paths are passed to the filesystem verbatim, so they must
+    // use backslashes only
+    let normalized = raw.replace('/', "\\");
+    let bytes = normalized.as_bytes();
+    if let Some(share) = normalized.strip_prefix(r"\\") {
+        PathBuf::from(format!(r"\\?\UNC\{}", share))
+    } else if bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && &bytes[1..3] == b":\\" {
+        PathBuf::from(format!(r"\\?\{}", normalized))
+    } else {
+        // Relative paths can't use the prefix
+        path.to_path_buf()
+    }
+}
+
 // unwrap/expect are acceptable in tests for concise failure-on-error assertions
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 #[cfg(test)]
@@ -768,4 +973,135 @@ mod tests {
              is invisible to get_unique_path"
         );
     }
+
+    fn windows_rules() -> PathSanitizationConfig {
+        PathSanitizationConfig {
+            windows_compatible: true,
+            transliterate_illegal_chars: false,
+        }
+    }
+
+    #[test]
+    fn sanitize_filename_is_noop_without_windows_rules() {
+        let config = PathSanitizationConfig {
+            windows_compatible: false,
+            transliterate_illegal_chars: true,
+        };
+        assert_eq!(sanitize_filename("CON.txt", &config), "CON.txt");
+        assert_eq!(sanitize_filename("a:b. ", &config), "a:b. ");
+    }
+
+    #[test]
+    fn sanitize_filename_suffixes_reserved_device_names() {
+        let config = windows_rules();
+        assert_eq!(sanitize_filename("CON", &config), "CON_");
+        assert_eq!(sanitize_filename("nul.txt", &config), "nul_.txt");
+        assert_eq!(sanitize_filename("Com1.tar.gz", &config), "Com1_.tar.gz");
+        assert_eq!(sanitize_filename("LPT9", &config), "LPT9_");
+        // Only exact stems are reserved
+        assert_eq!(sanitize_filename("CONSOLE.txt", &config), "CONSOLE.txt");
+        assert_eq!(sanitize_filename("COM10", &config), "COM10");
+        assert_eq!(sanitize_filename("my.con", &config), "my.con");
+    }
+
+    #[test]
+    fn sanitize_filename_trims_trailing_dots_and_spaces() {
+        let config = windows_rules();
+        assert_eq!(sanitize_filename("Movie. ", &config), "Movie");
+        assert_eq!(sanitize_filename("Folder...", &config), "Folder");
+        assert_eq!(sanitize_filename(" leading", &config), " leading");
+        assert_eq!(sanitize_filename("...", &config), "_");
+        assert_eq!(sanitize_filename("aux. ", &config), "aux_");
+    }
+
+    #[test]
+    fn sanitize_filename_replaces_illegal_characters() {
+        let config = windows_rules();
+        assert_eq!(
+            sanitize_filename("What? <Cut>: \"A|B\" *\\", &config),
+            "What_ _Cut__ _A_B_ __"
+        );
+        assert_eq!(sanitize_filename("tab\there", &config), "tab_here");
+    }
+
+    #[test]
+    fn sanitize_filename_transliterates_illegal_characters() {
+        let config = PathSanitizationConfig {
+            windows_compatible: true,
+            transliterate_illegal_chars: true,
+        };
+        assert_eq!(
+            sanitize_filename("Who? <Me>: \"A|B\" *\\", &config),
+            "Who？ ＜Me＞： ＂A｜B＂ ＊＼"
+        );
+        // Control characters have no look-alike
+        assert_eq!(sanitize_filename("a\u{1}b", &config), "a_b");
+    }
+
+    #[test]
+    fn extended_length_path_leaves_short_and_relative_paths() {
+        let short = Path::new(r"C:\Downloads\movie.mkv");
+        assert_eq!(extended_length_path(short), short);
+
+        let relative = format!(r"downloads\{}", "a".repeat(300));
+        assert_eq!(
+            extended_length_path(Path::new(&relative)),
+            Path::new(&relative)
+        );
+    }
+
+    #[test]
+    fn extended_length_path_prefixes_long_paths() {
+        let long_name = "a".repeat(300);
+
+        let drive = format!(r"C:\Downloads/{}", long_name);
+        assert_eq!(
+            extended_length_path(Path::new(&drive)),
+            PathBuf::from(format!(r"\\?\C:\Downloads\{}", long_name))
+        );
+
+        let unc = format!(r"\\nas\media\{}", long_name);
+        assert_eq!(
+            extended_length_path(Path::new(&unc)),
+            PathBuf::from(format!(r"\\?\UNC\nas\media\{}", long_name))
+        );
+
+        // Already-prefixed paths are left alone
+        let prefixed = format!(r"\\?\C:\{}", long_name);
+        assert_eq!(
+            extended_length_path(Path::new(&prefixed)),
+            Path::new(&prefixed)
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn long_path_is_noop_off_windows() {
+        let path = PathBuf::from(format!("/downloads/{}", "a".repeat(300)));
+        assert_eq!(long_path(&path), path);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn apply_process_priority_sets_niceness_and_affinity_of_the_tool() {
+        let priority = ProcessPriorityConfig {
+            nice: Some(15),
+            io_class: Some(crate::config::IoClass::Idle),
+            io_priority: None,
+            cpu_affinity: vec![0],
+        };
+        let mut command = std::process::Command::new("sh");
+        command
+            .arg("-c")
+            .arg("cut -d' ' -f19 /proc/self/stat; grep Cpus_allowed_list /proc/self/status");
+        apply_process_priority(&mut command, &priority);
+        let output = command.output().unwrap();
+        let text = String::from_utf8_lossy(&output.stdout);
+        let mut lines = text.lines();
+
+        // A process that already runs nicer than 15 can't lower its niceness
+        let nice: i32 = lines.next().unwrap().trim().parse().unwrap();
+        assert!(nice >= 15, "tool should run at niceness 15, got {}", nice);
+        assert_eq!(lines.next().unwrap().split_whitespace().last(), Some("0"));
+    }
 }
@@ -58,6 +58,10 @@ pub fn load_server_config() -> Result<ServerConfig, ConfigError> {
         connections,
         priority: 0,
         pipeline_depth: 10,
+        speed_limit_bps: None,
+        idle_timeout: None,
+        cost: 0.0,
+        quota_bytes: None,
     })
 }
 
@@ -89,6 +93,7 @@ pub async fn create_live_downloader() -> Result<(Arc<UsenetDownloader>, TempDir)
             database_path: temp_dir.path().join("test.db"),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         download: DownloadConfig {
             download_dir: temp_dir.path().join("downloads"),
@@ -118,6 +123,7 @@ pub async fn create_downloader_bad_auth() -> Result<(Arc<UsenetDownloader>, Temp
             database_path: temp_dir.path().join("test.db"),
             schedule_rules: vec![],
             categories: std::collections::HashMap::new(),
+            ..Default::default()
         },
         download: DownloadConfig {
             download_dir: temp_dir.path().join("downloads"),
@@ -154,8 +160,15 @@ pub async fn create_docker_downloader(
             connections: 2,
             priority: 0,
             pipeline_depth: 10,
+            speed_limit_bps: None,
+            idle_timeout: None,
+            cost: 0.0,
+            quota_bytes: None,
         }],
-        database_path: temp_dir.path().join("test.db"),
+        persistence: PersistenceConfig {
+            database_path: temp_dir.path().join("test.db"),
+            ..Default::default()
+        },
         download: DownloadConfig {
             download_dir: temp_dir.path().join("downloads"),
             temp_dir: temp_dir.path().join("temp"),